@@ -0,0 +1,136 @@
+//! Thread scaling of the ranking stage: BM25 scoring of candidate blocks and the
+//! post-rank merge of adjacent blocks, each run in rayon pools of growing size.
+//!
+//! Run with `cargo bench --bench ranking_benchmarks`; on a many-core machine the
+//! per-thread-count timings show how far the stage scales.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use probe_code::models::SearchResult;
+use probe_code::ranking::{rank_documents, rank_documents_simd, RankingParams};
+use probe_code::search::block_merging::merge_ranked_blocks;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+/// Thread counts to measure: powers of two up to the number of cores
+fn thread_counts() -> Vec<usize> {
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    std::iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|&n| n <= cores)
+        .collect()
+}
+
+fn pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap()
+}
+
+/// Code-like blocks of 20 to 200 tokens, the same for every run
+fn generate_blocks(count: usize) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(42);
+    let words = [
+        "fn", "let", "return", "self", "config", "parse", "token", "result", "error", "buffer",
+        "index", "search", "query", "match", "file", "path", "load", "save", "value", "node",
+    ];
+    (0..count)
+        .map(|i| {
+            let length = rng.gen_range(20..200);
+            let mut block = format!("fn handler_{i}() {{");
+            for _ in 0..length {
+                block.push(' ');
+                block.push_str(words[rng.gen_range(0..words.len())]);
+            }
+            block.push('}');
+            block
+        })
+        .collect()
+}
+
+fn bench_bm25_scaling(c: &mut Criterion) {
+    let blocks = generate_blocks(5_000);
+    let documents: Vec<&str> = blocks.iter().map(String::as_str).collect();
+    let params = RankingParams {
+        documents: &documents,
+        query: "config parse error",
+        pre_tokenized: None,
+    };
+
+    let mut group = c.benchmark_group("bm25_scaling");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(documents.len() as u64));
+    for threads in thread_counts() {
+        let pool = pool(threads);
+        group.bench_with_input(BenchmarkId::new("simd", threads), &params, |b, params| {
+            b.iter(|| pool.install(|| black_box(rank_documents_simd(params))))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("boolean", threads),
+            &params,
+            |b, params| b.iter(|| pool.install(|| black_box(rank_documents(params)))),
+        );
+    }
+    group.finish();
+}
+
+fn search_result(file: usize, start: usize, code: &str) -> SearchResult {
+    let lines = code.lines().count().max(1);
+    SearchResult {
+        file: format!("src/module_{file}.rs"),
+        lines: (start, start + lines - 1),
+        node_type: "function_item".to_string(),
+        code: code.into(),
+        symbol_signature: None,
+        matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
+        rank: Some(start),
+        score: Some(1.0 / start as f64),
+        tfidf_score: None,
+        bm25_score: Some(1.0 / start as f64),
+        tfidf_rank: None,
+        bm25_rank: None,
+        new_score: None,
+        hybrid2_rank: None,
+        combined_score_rank: None,
+        file_unique_terms: Some(2),
+        file_total_matches: Some(4),
+        file_match_rank: None,
+        block_unique_terms: Some(2),
+        block_total_matches: Some(2),
+        parent_file_id: Some(format!("src/module_{file}.rs")),
+        block_id: None,
+        matched_keywords: Some(vec!["config".to_string()]),
+        matched_lines: Some(vec![1]),
+        tokenized_content: None,
+        parent_context: None,
+    }
+}
+
+fn bench_merge_scaling(c: &mut Criterion) {
+    let code = "fn handler() {\n    let config = load();\n    parse(config)\n}";
+    // 2,000 files of 10 blocks each, some close enough to merge
+    let results: Vec<SearchResult> = (0..2_000)
+        .flat_map(|file| (0..10).map(move |block| search_result(file, 1 + block * 6, code)))
+        .collect();
+
+    let mut group = c.benchmark_group("merge_scaling");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(results.len() as u64));
+    for threads in thread_counts() {
+        let pool = pool(threads);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &results,
+            |b, results| {
+                b.iter(|| pool.install(|| black_box(merge_ranked_blocks(results.clone(), Some(5)))))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_bm25_scaling, bench_merge_scaling);
+criterion_main!(benches);
@@ -343,26 +343,12 @@ fn benchmark_search_patterns(c: &mut Criterion) {
             b.iter(|| {
                 let query = vec![pattern.to_string()];
                 let options = SearchOptions {
-                    path: &search_path,
+                    paths: std::slice::from_ref(&search_path),
                     queries: &query,
-                    files_only: false,
-                    custom_ignores: &[],
-                    exclude_filenames: false,
                     reranker: "hybrid",
-                    frequency_search: true,
-                    exact: false,
-                    language: None,
                     max_results: Some(100),
-                    max_bytes: None,
-                    max_tokens: None,
                     allow_tests: true,
-                    no_merge: false,
-                    merge_threshold: None,
-                    dry_run: false,
-                    session: None,
-                    timeout: 30,
-                    question: None,
-                    no_gitignore: false,
+                    ..Default::default()
                 };
 
                 black_box(perform_probe(&options).unwrap())
@@ -386,26 +372,12 @@ fn benchmark_result_limits(c: &mut Criterion) {
             b.iter(|| {
                 let query = vec!["use".to_string()]; // Common pattern
                 let options = SearchOptions {
-                    path: &search_path,
+                    paths: std::slice::from_ref(&search_path),
                     queries: &query,
-                    files_only: false,
-                    custom_ignores: &[],
-                    exclude_filenames: false,
                     reranker: "hybrid",
-                    frequency_search: true,
-                    exact: false,
-                    language: None,
                     max_results: Some(limit),
-                    max_bytes: None,
-                    max_tokens: None,
                     allow_tests: true,
-                    no_merge: false,
-                    merge_threshold: None,
-                    dry_run: false,
-                    session: None,
-                    timeout: 30,
-                    question: None,
-                    no_gitignore: false,
+                    ..Default::default()
                 };
 
                 black_box(perform_probe(&options).unwrap())
@@ -433,26 +405,12 @@ fn benchmark_search_options(c: &mut Criterion) {
                 b.iter(|| {
                     let query = vec!["HashMap".to_string()];
                     let options = SearchOptions {
-                        path: &search_path,
+                        paths: std::slice::from_ref(&search_path),
                         queries: &query,
-                        files_only: false,
-                        custom_ignores: &[],
-                        exclude_filenames: false,
                         reranker,
-                        frequency_search: true,
-                        exact: false,
-                        language: None,
                         max_results: Some(50),
-                        max_bytes: None,
-                        max_tokens: None,
                         allow_tests: true,
-                        no_merge: false,
-                        merge_threshold: None,
-                        dry_run: false,
-                        session: None,
-                        timeout: 30,
-                        question: None,
-                        no_gitignore: false,
+                        ..Default::default()
                     };
 
                     black_box(perform_probe(&options).unwrap())
@@ -469,26 +427,13 @@ fn benchmark_search_options(c: &mut Criterion) {
             b.iter(|| {
                 let query = vec!["HashMap".to_string()];
                 let options = SearchOptions {
-                    path: &search_path,
+                    paths: std::slice::from_ref(&search_path),
                     queries: &query,
-                    files_only: false,
-                    custom_ignores: &[],
-                    exclude_filenames: false,
                     reranker: "hybrid",
                     frequency_search: freq,
-                    exact: false,
-                    language: None,
                     max_results: Some(50),
-                    max_bytes: None,
-                    max_tokens: None,
                     allow_tests: true,
-                    no_merge: false,
-                    merge_threshold: None,
-                    dry_run: false,
-                    session: None,
-                    timeout: 30,
-                    question: None,
-                    no_gitignore: false,
+                    ..Default::default()
                 };
 
                 black_box(perform_probe(&options).unwrap())
@@ -503,26 +448,13 @@ fn benchmark_search_options(c: &mut Criterion) {
             b.iter(|| {
                 let query = vec!["HashMap".to_string()];
                 let options = SearchOptions {
-                    path: &search_path,
+                    paths: std::slice::from_ref(&search_path),
                     queries: &query,
-                    files_only: false,
-                    custom_ignores: &[],
-                    exclude_filenames: false,
                     reranker: "hybrid",
                     frequency_search: freq,
-                    exact: false,
-                    language: None,
                     max_results: Some(50),
-                    max_bytes: None,
-                    max_tokens: None,
                     allow_tests: true,
-                    no_merge: false,
-                    merge_threshold: None,
-                    dry_run: false,
-                    session: None,
-                    timeout: 30,
-                    question: None,
-                    no_gitignore: false,
+                    ..Default::default()
                 };
 
                 black_box(perform_probe(&options).unwrap())
@@ -558,26 +490,12 @@ fn benchmark_query_complexity(c: &mut Criterion) {
                 b.iter(|| {
                     let query: Vec<String> = query_terms.iter().map(|s| s.to_string()).collect();
                     let options = SearchOptions {
-                        path: &search_path,
+                        paths: std::slice::from_ref(&search_path),
                         queries: &query,
-                        files_only: false,
-                        custom_ignores: &[],
-                        exclude_filenames: false,
                         reranker: "hybrid",
-                        frequency_search: true,
-                        exact: false,
-                        language: None,
                         max_results: Some(100),
-                        max_bytes: None,
-                        max_tokens: None,
                         allow_tests: true,
-                        no_merge: false,
-                        merge_threshold: None,
-                        dry_run: false,
-                        session: None,
-                        timeout: 30,
-                        question: None,
-                        no_gitignore: false,
+                        ..Default::default()
                     };
 
                     black_box(perform_probe(&options).unwrap())
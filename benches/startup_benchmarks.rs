@@ -0,0 +1,77 @@
+//! Wall time of whole `probe` processes for small, common calls, where process start and
+//! the initialization of global state dominate. A lazily built singleton that is built
+//! eagerly again (a tokenizer, a reranker model, a parser pool) shows up here as a jump
+//! from milliseconds to hundreds of milliseconds.
+//!
+//! Run with `cargo bench --bench startup_benchmarks` or `probe benchmark --bench startup`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// A small project, so the calls spend their time starting up rather than searching
+fn small_project() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for i in 0..20 {
+        fs::write(
+            dir.path().join(format!("module_{i}.rs")),
+            format!(
+                "/// Loads the configuration of module {i}\npub fn load_config_{i}(path: &str) -> Config {{\n    Config::parse(path)\n}}\n"
+            ),
+        )
+        .unwrap();
+    }
+    dir
+}
+
+fn run_probe(args: &[&str], dir: &Path) {
+    let status = Command::new(env!("CARGO_BIN_EXE_probe"))
+        .args(args)
+        .arg(dir)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "probe {args:?} failed");
+}
+
+fn bench_startup(c: &mut Criterion) {
+    let project = small_project();
+    let dir = project.path();
+
+    let mut group = c.benchmark_group("startup");
+    group.sample_size(20);
+    group.measurement_time(Duration::from_secs(5));
+
+    group.bench_function("version", |b| {
+        b.iter(|| {
+            let status = Command::new(env!("CARGO_BIN_EXE_probe"))
+                .arg("--version")
+                .stdout(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        })
+    });
+    group.bench_function("grep", |b| b.iter(|| run_probe(&["grep", "config"], dir)));
+    group.bench_function("search_no_match", |b| {
+        b.iter(|| run_probe(&["search", "xyzzy"], dir))
+    });
+    group.bench_function("search", |b| {
+        b.iter(|| run_probe(&["search", "load config"], dir))
+    });
+    group.bench_function("search_files_only", |b| {
+        b.iter(|| run_probe(&["search", "load config", "--files-only"], dir))
+    });
+    group.bench_function("search_json", |b| {
+        b.iter(|| run_probe(&["search", "load config", "--format", "json"], dir))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_startup);
+criterion_main!(benches);
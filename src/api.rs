@@ -0,0 +1,340 @@
+//! Stable API for embedding probe in other Rust tools.
+//!
+//! [`SearchRequest`] and [`ExtractRequest`] are builders over the same machinery the
+//! `search` and `extract` commands use, returning [`SearchMatch`] and [`ExtractedBlock`]
+//! values that carry only what a caller needs. Unlike [`SearchOptions`] and
+//! [`SearchResult`], which change whenever the ranking pipeline does, this module follows
+//! semver: builder methods and result fields are only added (result structs are
+//! `#[non_exhaustive]`), never changed or removed, within a major version.
+//!
+//! ```no_run
+//! use probe_code::api::{ExtractRequest, SearchRequest};
+//!
+//! let matches = SearchRequest::new("parse config")
+//!     .path("src")
+//!     .max_results(5)
+//!     .run()?;
+//! for m in &matches {
+//!     println!("{}:{}-{} {}", m.file.display(), m.start_line, m.end_line, m.node_type);
+//! }
+//!
+//! let blocks = ExtractRequest::new().target("src/main.rs:42").run()?;
+//! println!("{}", blocks[0].code);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use anyhow::Result;
+use std::path::PathBuf;
+
+use probe_code::extract::{parse_file_with_line, process_file_for_extraction};
+use probe_code::models::SearchResult;
+use probe_code::search::{perform_probe, SearchOptions};
+
+/// A code block found by a search, most relevant first
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SearchMatch {
+    pub file: PathBuf,
+    /// First line of the block (1-based)
+    pub start_line: usize,
+    /// Last line of the block (1-based, inclusive)
+    pub end_line: usize,
+    /// Kind of syntax node the block is, e.g. `function_item` or `class_definition`
+    pub node_type: String,
+    pub code: String,
+    /// Relevance score from the ranker; only comparable within one search
+    pub score: Option<f64>,
+    /// Query terms found in the block
+    pub matched_terms: Vec<String>,
+}
+
+impl From<SearchResult> for SearchMatch {
+    fn from(result: SearchResult) -> Self {
+        SearchMatch {
+            file: PathBuf::from(result.file),
+            start_line: result.lines.0,
+            end_line: result.lines.1,
+            node_type: result.node_type,
+            code: result.code.into(),
+            score: result.score,
+            matched_terms: result.matched_keywords.unwrap_or_default(),
+        }
+    }
+}
+
+/// A code block extracted from a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExtractedBlock {
+    pub file: PathBuf,
+    /// First line of the block (1-based)
+    pub start_line: usize,
+    /// Last line of the block (1-based, inclusive)
+    pub end_line: usize,
+    /// Kind of syntax node the block was extracted from, or `file` for a whole file
+    pub node_type: String,
+    pub code: String,
+}
+
+impl From<SearchResult> for ExtractedBlock {
+    fn from(result: SearchResult) -> Self {
+        ExtractedBlock {
+            file: PathBuf::from(result.file),
+            start_line: result.lines.0,
+            end_line: result.lines.1,
+            node_type: result.node_type,
+            code: result.code.into(),
+        }
+    }
+}
+
+/// A search over one or more directories, configured builder-style
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    query: String,
+    paths: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    ignore: Vec<String>,
+    language: Option<String>,
+    reranker: String,
+    max_results: Option<usize>,
+    max_bytes: Option<usize>,
+    max_tokens: Option<usize>,
+    max_files: Option<usize>,
+    exact: bool,
+    allow_tests: bool,
+    no_gitignore: bool,
+    include_generated: bool,
+    timeout: u64,
+}
+
+impl SearchRequest {
+    /// A search for `query` (same syntax as `probe search`) in the current directory
+    pub fn new(query: impl Into<String>) -> Self {
+        SearchRequest {
+            query: query.into(),
+            paths: Vec::new(),
+            files: Vec::new(),
+            ignore: Vec::new(),
+            language: None,
+            reranker: "bm25".to_string(),
+            max_results: None,
+            max_bytes: None,
+            max_tokens: None,
+            max_files: None,
+            exact: false,
+            allow_tests: false,
+            no_gitignore: false,
+            include_generated: false,
+            timeout: 30,
+        }
+    }
+
+    /// Add a directory or file to search; the current directory is used if none is added
+    pub fn path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.paths.push(path.into());
+        self
+    }
+
+    /// Add a file to search. Once any file is added, only the added files are searched:
+    /// paths are not walked and ignore rules don't apply.
+    pub fn file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.files.push(file.into());
+        self
+    }
+
+    /// Add a pattern of files to ignore, in addition to .gitignore rules
+    pub fn ignore(mut self, pattern: impl Into<String>) -> Self {
+        self.ignore.push(pattern.into());
+        self
+    }
+
+    /// Only search files of this language, e.g. `rust` or `python`
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Ranking algorithm, as for `--reranker` (default: `bm25`)
+    pub fn reranker(mut self, reranker: impl Into<String>) -> Self {
+        self.reranker = reranker.into();
+        self
+    }
+
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Cap the total size of returned code
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Cap the total tokens of returned code
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Cap the number of distinct files the results come from
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    /// Match terms exactly, without stemming or splitting identifiers
+    pub fn exact(mut self, exact: bool) -> Self {
+        self.exact = exact;
+        self
+    }
+
+    /// Include test files and test code blocks
+    pub fn allow_tests(mut self, allow_tests: bool) -> Self {
+        self.allow_tests = allow_tests;
+        self
+    }
+
+    /// Search files that .gitignore excludes
+    pub fn no_gitignore(mut self, no_gitignore: bool) -> Self {
+        self.no_gitignore = no_gitignore;
+        self
+    }
+
+    /// Include generated and license-only files
+    pub fn include_generated(mut self, include_generated: bool) -> Self {
+        self.include_generated = include_generated;
+        self
+    }
+
+    /// Give up after this many seconds (default: 30)
+    pub fn timeout_secs(mut self, timeout: u64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run the search
+    pub fn run(&self) -> Result<Vec<SearchMatch>> {
+        let paths = if self.paths.is_empty() {
+            vec![PathBuf::from(".")]
+        } else {
+            self.paths.clone()
+        };
+        let queries = vec![self.query.clone()];
+        let options = SearchOptions {
+            paths: &paths,
+            queries: &queries,
+            custom_ignores: &self.ignore,
+            reranker: &self.reranker,
+            exact: self.exact,
+            language: self.language.as_deref(),
+            max_results: self.max_results,
+            max_bytes: self.max_bytes,
+            max_tokens: self.max_tokens,
+            max_files: self.max_files,
+            allow_tests: self.allow_tests,
+            timeout: self.timeout,
+            no_gitignore: self.no_gitignore,
+            include_generated: self.include_generated,
+            files_from: (!self.files.is_empty()).then_some(self.files.as_slice()),
+            ..Default::default()
+        };
+        let results = perform_probe(&options)?;
+        Ok(results
+            .results
+            .into_iter()
+            .filter(|r| !r.file.is_empty())
+            .map(SearchMatch::from)
+            .collect())
+    }
+}
+
+/// Extraction of code blocks by file target, configured builder-style
+#[derive(Debug, Clone, Default)]
+pub struct ExtractRequest {
+    targets: Vec<String>,
+    context_lines: usize,
+    allow_tests: bool,
+}
+
+impl ExtractRequest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a target: `path` (whole file), `path:line`, `path:start-end` or `path#symbol`
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.targets.push(target.into());
+        self
+    }
+
+    /// Lines of context to include around each block
+    pub fn context_lines(mut self, context_lines: usize) -> Self {
+        self.context_lines = context_lines;
+        self
+    }
+
+    /// Allow extracting from test files
+    pub fn allow_tests(mut self, allow_tests: bool) -> Self {
+        self.allow_tests = allow_tests;
+        self
+    }
+
+    /// Extract every target, in order. Fails on the first target that can't be read.
+    pub fn run(&self) -> Result<Vec<ExtractedBlock>> {
+        let mut blocks = Vec::new();
+        for target in &self.targets {
+            for (path, start, end, symbol, lines) in parse_file_with_line(target, self.allow_tests)
+            {
+                let result = process_file_for_extraction(
+                    &path,
+                    start,
+                    end,
+                    symbol.as_deref(),
+                    self.allow_tests,
+                    self.context_lines,
+                    lines.as_ref(),
+                    false,
+                )?;
+                blocks.push(ExtractedBlock::from(result));
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_search_and_extract() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("ledger.rs");
+        fs::write(
+            &file,
+            "fn ledger_balance() -> i64 {\n    42\n}\n\nfn unrelated() {}\n",
+        )
+        .unwrap();
+
+        let matches = SearchRequest::new("ledger balance")
+            .path(temp.path())
+            .max_results(1)
+            .run()
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].start_line, matches[0].end_line), (1, 3));
+        assert!(matches[0].code.contains("ledger_balance"));
+
+        let blocks = ExtractRequest::new()
+            .target(format!("{}:2", file.display()))
+            .run()
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert!(blocks[0].code.contains("ledger_balance"));
+        assert_eq!((blocks[0].start_line, blocks[0].end_line), (1, 3));
+    }
+}
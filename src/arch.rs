@@ -0,0 +1,350 @@
+//! `probe arch`: check the import graph of a codebase against dependency rules between
+//! its directories, failing CI when an import breaks one.
+//!
+//! A rules file holds one rule per line, with `#` starting a comment:
+//!
+//! ```text
+//! src/ui must not import src/db
+//! src/domain may only import src/util, src/models
+//! ```
+//!
+//! Paths are relative to the checked directory and cover everything below them. A
+//! `may only import` rule always allows imports within its own directory. Imports of
+//! code outside the checked files, such as third-party packages, are never checked.
+
+use anyhow::{Context, Result};
+use colored::*;
+use probe_code::extract::imports::{file_imports, resolve_import};
+use probe_code::search::file_list_cache::get_file_list;
+use probe_code::warnings;
+use rayon::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+const FORBID: &str = " must not import ";
+const ALLOW_ONLY: &str = " may only import ";
+
+pub struct ArchParams<'a> {
+    pub path: &'a Path,
+    pub rules: &'a Path,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RuleKind {
+    Forbid,
+    AllowOnly,
+}
+
+#[derive(Debug, PartialEq)]
+struct Rule {
+    /// The rule as written, for messages
+    text: String,
+    from: String,
+    kind: RuleKind,
+    targets: Vec<String>,
+}
+
+impl Rule {
+    /// Whether `file` importing `target` breaks the rule
+    fn is_broken_by(&self, file: &str, target: &str) -> bool {
+        if !within(file, &self.from) {
+            return false;
+        }
+        let listed = self.targets.iter().any(|dir| within(target, dir));
+        match self.kind {
+            RuleKind::Forbid => listed,
+            RuleKind::AllowOnly => !listed && !within(target, &self.from),
+        }
+    }
+}
+
+/// An import breaking a rule
+#[derive(Debug, Serialize)]
+struct Violation<'a> {
+    rule: &'a str,
+    #[serde(skip)]
+    rule_index: usize,
+    file: String,
+    line: usize,
+    /// The module as written in the import
+    import: String,
+    /// The file it resolves to
+    target: String,
+}
+
+/// Check the rules and print the violations; `true` when there are any, so the caller
+/// can exit with a failure status
+pub fn handle_arch(params: ArchParams) -> Result<bool> {
+    warnings::set_json(params.format != "text");
+    let content = fs::read_to_string(params.rules)
+        .with_context(|| format!("Failed to read rules file {}", params.rules.display()))?;
+    let rules = parse_rules(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid rules file {}:{e}", params.rules.display()))?;
+    if rules.is_empty() {
+        anyhow::bail!("No rules in {}", params.rules.display());
+    }
+
+    let root = params
+        .path
+        .canonicalize()
+        .with_context(|| format!("Failed to access {}", params.path.display()))?;
+    let file_list = get_file_list(
+        &root,
+        params.allow_tests,
+        params.ignore,
+        params.no_gitignore,
+    )?;
+    let relative = |path: &Path| {
+        path.strip_prefix(&root)
+            .ok()
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+    };
+
+    // Every import of a checked file that resolves to another checked file
+    let mut edges: Vec<(String, usize, String, String)> = file_list
+        .files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let content = fs::read_to_string(file).unwrap_or_default();
+            let imports = file_imports(&file.to_string_lossy(), &content);
+            let source = relative(file);
+            let mut edges = Vec::new();
+            for import in imports {
+                let targets: BTreeSet<String> = resolve_import(&root, file, &import.spec)
+                    .iter()
+                    .filter(|target| *target != file)
+                    .filter_map(|target| relative(target))
+                    .collect();
+                for target in targets {
+                    if let Some(source) = &source {
+                        edges.push((source.clone(), import.line, import.spec.clone(), target));
+                    }
+                }
+            }
+            edges
+        })
+        .collect();
+    edges.sort();
+
+    let mut violations = Vec::new();
+    for (file, line, import, target) in edges {
+        for (rule_index, rule) in rules.iter().enumerate() {
+            if rule.is_broken_by(&file, &target) {
+                violations.push(Violation {
+                    rule: &rule.text,
+                    rule_index,
+                    file: file.clone(),
+                    line,
+                    import: import.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+    }
+
+    match params.format {
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&json!({
+                "rules": rules.iter().map(|rule| &rule.text).collect::<Vec<_>>(),
+                "files_checked": file_list.files.len(),
+                "violations": violations,
+            }))?
+        ),
+        "sarif" => println!(
+            "{}",
+            serde_json::to_string_pretty(&sarif(&rules, &violations))?
+        ),
+        _ => print_text(&violations, rules.len(), file_list.files.len()),
+    }
+    Ok(!violations.is_empty())
+}
+
+/// Parse a rules file; errors start with the line number
+fn parse_rules(content: &str) -> Result<Vec<Rule>, String> {
+    let mut rules = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let text = line.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+        let (from, targets, kind) = if let Some((from, targets)) = text.split_once(FORBID) {
+            (from, targets, RuleKind::Forbid)
+        } else if let Some((from, targets)) = text.split_once(ALLOW_ONLY) {
+            (from, targets, RuleKind::AllowOnly)
+        } else {
+            return Err(format!(
+                "{}: expected `<dir> must not import <dir>, ...` or `<dir> may only import <dir>, ...`",
+                index + 1
+            ));
+        };
+        let targets: Vec<String> = targets
+            .split(',')
+            .map(rule_path)
+            .filter(|target| !target.is_empty())
+            .collect();
+        if targets.is_empty() {
+            return Err(format!("{}: the rule names no directories", index + 1));
+        }
+        rules.push(Rule {
+            text: text.to_string(),
+            from: rule_path(from),
+            kind,
+            targets,
+        });
+    }
+    Ok(rules)
+}
+
+/// A directory of a rule as a path relative to the checked directory
+fn rule_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    if path == "." {
+        String::new()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Whether `path` is `dir` or below it; the empty directory holds everything
+fn within(path: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// The violations as a SARIF 2.1.0 log, for code scanning tools
+fn sarif(rules: &[Rule], violations: &[Violation]) -> serde_json::Value {
+    let rules: Vec<_> = rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            json!({
+                "id": format!("arch/{}", index + 1),
+                "shortDescription": { "text": rule.text },
+            })
+        })
+        .collect();
+    let results: Vec<_> = violations
+        .iter()
+        .map(|violation| {
+            json!({
+                "ruleId": format!("arch/{}", violation.rule_index + 1),
+                "ruleIndex": violation.rule_index,
+                "level": "error",
+                "message": { "text": message(violation) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": violation.file },
+                        "region": { "startLine": violation.line },
+                    }
+                }],
+            })
+        })
+        .collect();
+    json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "probe",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+fn message(violation: &Violation) -> String {
+    format!(
+        "Imports {} ({}), breaking the rule `{}`",
+        violation.target, violation.import, violation.rule
+    )
+}
+
+fn print_text(violations: &[Violation], rules: usize, files: usize) {
+    for violation in violations {
+        println!(
+            "{}:{}: {}",
+            violation.file.bold(),
+            violation.line,
+            message(violation)
+        );
+    }
+    if violations.is_empty() {
+        println!(
+            "{}",
+            format!("No violations of {rules} rules in {files} files").green()
+        );
+    } else {
+        let files: BTreeSet<&str> = violations.iter().map(|v| v.file.as_str()).collect();
+        println!();
+        println!(
+            "{}",
+            format!(
+                "{} violations of {rules} rules in {} files",
+                violations.len(),
+                files.len()
+            )
+            .bold()
+            .red()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = parse_rules(
+            "# Layers\nsrc/ui/ must not import src/db\n\n./src/domain may only import src/util, src/models # shared\n",
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            [
+                Rule {
+                    text: "src/ui/ must not import src/db".to_string(),
+                    from: "src/ui".to_string(),
+                    kind: RuleKind::Forbid,
+                    targets: vec!["src/db".to_string()],
+                },
+                Rule {
+                    text: "./src/domain may only import src/util, src/models".to_string(),
+                    from: "src/domain".to_string(),
+                    kind: RuleKind::AllowOnly,
+                    targets: vec!["src/util".to_string(), "src/models".to_string()],
+                },
+            ]
+        );
+        assert!(parse_rules("ui imports db").unwrap_err().starts_with("1:"));
+        assert!(parse_rules("ui must not import ,").is_err());
+    }
+
+    #[test]
+    fn test_rule_is_broken_by() {
+        let rules = parse_rules("ui must not import db\ndomain may only import util").unwrap();
+        assert!(rules[0].is_broken_by("ui/view.ts", "db/conn.ts"));
+        assert!(!rules[0].is_broken_by("ui/view.ts", "dbtools/conn.ts"));
+        assert!(!rules[0].is_broken_by("uikit/view.ts", "db/conn.ts"));
+
+        assert!(rules[1].is_broken_by("domain/user.ts", "ui/view.ts"));
+        assert!(!rules[1].is_broken_by("domain/user.ts", "util/time.ts"));
+        assert!(!rules[1].is_broken_by("domain/user.ts", "domain/order.ts"));
+    }
+}
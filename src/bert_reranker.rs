@@ -19,6 +19,8 @@ use serde_json;
 use std::path::Path;
 #[cfg(feature = "bert-reranker")]
 use tokenizers::Tokenizer;
+#[cfg(feature = "bert-reranker")]
+use tracing::debug;
 
 use crate::models::SearchResult;
 
@@ -34,10 +36,10 @@ pub struct BertReranker {
 #[cfg(feature = "bert-reranker")]
 impl BertReranker {
     pub async fn new(model_name: &str) -> Result<Self> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         if debug_mode {
-            println!("DEBUG: Loading BERT model: {model_name}");
+            debug!("Loading BERT model: {model_name}");
         }
 
         // Check if we have local model files first
@@ -52,7 +54,7 @@ impl BertReranker {
         let local_model_dir = Path::new("examples/reranker/models").join(model_dir_name);
         let (config_path, tokenizer_path, weights_path) = if local_model_dir.exists() {
             if debug_mode {
-                println!("DEBUG: Using local model files from: {local_model_dir:?}");
+                debug!("Using local model files from: {local_model_dir:?}");
             }
             (
                 local_model_dir.join("config.json"),
@@ -61,7 +63,7 @@ impl BertReranker {
             )
         } else {
             if debug_mode {
-                println!("DEBUG: Downloading model files from HuggingFace Hub...");
+                debug!("Downloading model files from HuggingFace Hub...");
             }
 
             let api = Api::new()?;
@@ -85,20 +87,20 @@ impl BertReranker {
             let weights_path = match repo.get("model.safetensors").await {
                 Ok(path) => {
                     if debug_mode {
-                        println!("DEBUG: Using model.safetensors");
+                        debug!("Using model.safetensors");
                     }
                     path
                 }
                 Err(_) => match repo.get("pytorch_model.bin").await {
                     Ok(path) => {
                         if debug_mode {
-                            println!("DEBUG: Using pytorch_model.bin");
+                            debug!("Using pytorch_model.bin");
                         }
                         path
                     }
                     Err(e) => {
                         if debug_mode {
-                            println!("DEBUG: Trying model.bin as fallback...");
+                            debug!("Trying model.bin as fallback...");
                         }
                         repo.get("model.bin")
                             .await
@@ -111,7 +113,7 @@ impl BertReranker {
         };
 
         if debug_mode {
-            println!("DEBUG: Loading configuration...");
+            debug!("Loading configuration...");
         }
         // Load configuration
         let config_content =
@@ -121,21 +123,21 @@ impl BertReranker {
 
         let max_length = config.max_position_embeddings.min(512); // Limit for performance
         if debug_mode {
-            println!(
-                "DEBUG: Model config loaded - max_length: {}, hidden_size: {}",
+            debug!(
+                "Model config loaded - max_length: {}, hidden_size: {}",
                 max_length, config.hidden_size
             );
         }
 
         if debug_mode {
-            println!("DEBUG: Loading tokenizer...");
+            debug!("Loading tokenizer...");
         }
         // Load tokenizer
         let tokenizer = Tokenizer::from_file(tokenizer_path)
             .map_err(|e| anyhow::anyhow!("Failed to load tokenizer: {}", e))?;
 
         if debug_mode {
-            println!("DEBUG: Setting up device and loading model weights...");
+            debug!("Setting up device and loading model weights...");
         }
         // Setup device (CPU for compatibility, could be GPU if available)
         let device = Device::Cpu;
@@ -149,20 +151,20 @@ impl BertReranker {
         };
 
         if debug_mode {
-            println!("DEBUG: Creating BERT model...");
+            debug!("Creating BERT model...");
         }
         // Create BERT model
         // MS MARCO models typically have the BERT weights at root level
         let bert = match BertModel::load(vb.clone(), &config) {
             Ok(model) => {
                 if debug_mode {
-                    println!("DEBUG: Loaded BERT model from root level");
+                    debug!("Loaded BERT model from root level");
                 }
                 model
             }
             Err(_) => {
                 if debug_mode {
-                    println!("DEBUG: Failed to load BERT from root, trying 'bert' prefix");
+                    debug!("Failed to load BERT from root, trying 'bert' prefix");
                 }
                 BertModel::load(vb.pp("bert"), &config)
                     .context("Failed to load BERT model from any path")?
@@ -170,34 +172,34 @@ impl BertReranker {
         };
 
         if debug_mode {
-            println!("DEBUG: Creating classification head...");
+            debug!("Creating classification head...");
         }
         // Create classification head (linear layer for sequence classification)
         // Try different paths for the classifier weights
         let classifier = match linear(config.hidden_size, 1, vb.pp("classifier")) {
             Ok(classifier) => {
                 if debug_mode {
-                    println!("DEBUG: Successfully loaded classifier from 'classifier' path");
+                    debug!("Successfully loaded classifier from 'classifier' path");
                 }
                 classifier
             }
             Err(_) => {
                 // Try alternative paths
                 if debug_mode {
-                    println!(
-                        "DEBUG: Failed to load classifier from 'classifier' path, trying 'pooler'"
-                    );
+                    debug!("Failed to load classifier from 'classifier' path, trying 'pooler'");
                 }
                 match linear(config.hidden_size, 1, vb.pp("pooler")) {
                     Ok(classifier) => {
                         if debug_mode {
-                            println!("DEBUG: Successfully loaded classifier from 'pooler' path");
+                            debug!("Successfully loaded classifier from 'pooler' path");
                         }
                         classifier
                     }
                     Err(_) => {
                         if debug_mode {
-                            println!("DEBUG: Failed to load classifier from 'pooler' path, trying root level");
+                            debug!(
+                                "Failed to load classifier from 'pooler' path, trying root level"
+                            );
                         }
                         // Try root level
                         linear(config.hidden_size, 1, vb.clone())
@@ -208,7 +210,7 @@ impl BertReranker {
         };
 
         if debug_mode {
-            println!("DEBUG: BERT reranker loaded successfully!");
+            debug!("BERT reranker loaded successfully!");
         }
 
         Ok(Self {
@@ -281,9 +283,9 @@ impl BertReranker {
         } else {
             // If tokenizer doesn't provide type IDs, we might have an issue
             // Log a warning in debug mode
-            let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+            let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
             if debug_mode {
-                println!("WARNING: Tokenizer did not generate token type IDs. This may affect model performance.");
+                debug!("WARNING: Tokenizer did not generate token type IDs. This may affect model performance.");
             }
             None
         };
@@ -308,10 +310,10 @@ impl BertReranker {
         let score = raw_score;
 
         // Debug: Log raw score
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         if debug_mode {
-            println!(
-                "DEBUG: Raw BERT score for query '{}' (first 50 chars): {:.6}",
+            debug!(
+                "Raw BERT score for query '{}' (first 50 chars): {:.6}",
                 &query[..query.len().min(50)],
                 score
             );
@@ -358,13 +360,7 @@ pub async fn rerank_with_bert(
     }
 
     // Use the existing ParallelBertReranker for better performance
-    // Create a parallel reranker with auto-detected thread count
-    let num_threads = std::thread::available_parallelism()
-        .map(|n| n.get())
-        .unwrap_or(4)
-        .min(8); // Cap at 8 threads to avoid overwhelming the system
-
-    let parallel_reranker = ParallelBertReranker::new(model_name, Some(num_threads)).await?;
+    let parallel_reranker = loaded_reranker(model_name).await?;
 
     // Use the question if provided, otherwise join the queries
     let combined_query = if let Some(q) = question {
@@ -401,11 +397,11 @@ pub async fn rerank_with_bert(
     }
 
     // Debug: Show scores before sorting
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     if debug_mode {
-        println!("\nDEBUG: BERT scores before sorting:");
+        debug!("BERT scores before sorting:");
         for (i, result) in results.iter().enumerate() {
-            println!(
+            debug!(
                 "  [{}] {} - Score: {:.6}",
                 i,
                 &result.file[result.file.len().saturating_sub(50)..], // Last 50 chars of filename
@@ -424,9 +420,9 @@ pub async fn rerank_with_bert(
     });
 
     if debug_mode {
-        println!("\nDEBUG: BERT scores after sorting:");
+        debug!("BERT scores after sorting:");
         for (i, result) in results.iter().enumerate() {
-            println!(
+            debug!(
                 "  [{}] {} - Score: {:.6}",
                 i,
                 &result.file[result.file.len().saturating_sub(50)..], // Last 50 chars of filename
@@ -444,6 +440,46 @@ pub async fn rerank_with_bert(
     Ok(())
 }
 
+/// Rerankers loaded by this process, by model name
+#[cfg(feature = "bert-reranker")]
+static LOADED_RERANKERS: std::sync::Mutex<
+    Option<std::collections::HashMap<String, std::sync::Arc<ParallelBertReranker>>>,
+> = std::sync::Mutex::new(None);
+
+/// The parallel reranker for a model, loading it the first time the model is used.
+/// Loading takes seconds, so nothing is loaded unless a BERT reranker is selected, and a
+/// long-running `probe serve` or MCP server loads each model once.
+#[cfg(feature = "bert-reranker")]
+async fn loaded_reranker(model_name: &str) -> Result<std::sync::Arc<ParallelBertReranker>> {
+    let loaded = |model_name: &str| {
+        LOADED_RERANKERS
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|rerankers| rerankers.get(model_name).cloned())
+    };
+    if let Some(reranker) = loaded(model_name) {
+        return Ok(reranker);
+    }
+
+    // Create a parallel reranker with auto-detected thread count
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(8); // Cap at 8 threads to avoid overwhelming the system
+    let reranker =
+        std::sync::Arc::new(ParallelBertReranker::new(model_name, Some(num_threads)).await?);
+
+    // Another search may have loaded the model meanwhile; keep the first one
+    Ok(LOADED_RERANKERS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(Default::default)
+        .entry(model_name.to_string())
+        .or_insert(reranker)
+        .clone())
+}
+
 /// Non-feature version of rerank_with_bert
 #[cfg(not(feature = "bert-reranker"))]
 #[allow(dead_code)]
@@ -491,20 +527,16 @@ impl ParallelBertReranker {
                 .min(8) // Cap at 8 threads
         });
 
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         if debug_mode {
-            println!("DEBUG: Creating parallel BERT reranker with {num_threads} engines");
+            debug!("Creating parallel BERT reranker with {num_threads} engines");
         }
 
         let mut engines = Vec::new();
         for i in 0..num_threads {
             if debug_mode {
-                println!(
-                    "DEBUG: Loading BERT model for engine {} of {}",
-                    i + 1,
-                    num_threads
-                );
+                debug!("Loading BERT model for engine {} of {}", i + 1, num_threads);
             }
             let engine = BertInferenceEngine::new(model_name).await?;
             engines.push(std::sync::Arc::new(parking_lot::Mutex::new(engine)));
@@ -522,12 +554,12 @@ impl ParallelBertReranker {
         use std::sync::Arc;
         use std::time::{Duration, Instant};
 
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         let total_docs = documents.len();
 
         if debug_mode {
-            println!(
-                "DEBUG: Starting BERT reranking for {} documents with {} engines (num_threads={})",
+            debug!(
+                "Starting BERT reranking for {} documents with {} engines (num_threads={})",
                 total_docs,
                 self.engines.len(),
                 self.num_threads
@@ -573,7 +605,7 @@ impl ParallelBertReranker {
                         if current > last_reported && current > 0 {
                             let elapsed = start_time.elapsed();
                             let rate = current as f64 / elapsed.as_secs_f64();
-                            println!("DEBUG: BERT reranking progress: {current}/{total_docs} documents processed ({rate:.1} docs/sec)");
+                            debug!("BERT reranking progress: {current}/{total_docs} documents processed ({rate:.1} docs/sec)");
                         }
                         break;
                     }
@@ -582,7 +614,7 @@ impl ParallelBertReranker {
                     if current > last_reported {
                         let elapsed = start_time.elapsed();
                         let rate = current as f64 / elapsed.as_secs_f64();
-                        println!("DEBUG: BERT reranking progress: {current}/{total_docs} documents processed ({rate:.1} docs/sec)");
+                        debug!("BERT reranking progress: {current}/{total_docs} documents processed ({rate:.1} docs/sec)");
                         last_reported = current;
                     }
                 }
@@ -593,7 +625,7 @@ impl ParallelBertReranker {
         };
 
         if debug_mode {
-            eprintln!("DEBUG: About to start parallel processing of {total_docs} documents");
+            debug!("About to start parallel processing of {total_docs} documents");
         }
 
         // Create chunks for parallel processing - each chunk is processed by a dedicated engine
@@ -607,11 +639,7 @@ impl ParallelBertReranker {
             .collect();
 
         if debug_mode {
-            println!(
-                "DEBUG: Created {} chunks of size ~{}",
-                chunks.len(),
-                chunk_size
-            );
+            debug!("Created {} chunks of size ~{}", chunks.len(), chunk_size);
         }
 
         // Process chunks in parallel - each chunk is assigned to a specific engine
@@ -626,8 +654,8 @@ impl ParallelBertReranker {
                 let engine = &engines[engine_idx];
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Thread {} processing chunk {} with {} documents using engine {}",
+                    debug!(
+                        "Thread {} processing chunk {} with {} documents using engine {}",
                         chunk_idx,
                         chunk_idx,
                         chunk.len(),
@@ -650,7 +678,7 @@ impl ParallelBertReranker {
                     if let Some(ref count) = processed_count {
                         let new_count = count.fetch_add(1, Ordering::Relaxed) + 1;
                         if debug_mode && new_count % 10 == 0 {
-                            eprintln!("DEBUG: Processed {new_count} documents so far");
+                            debug!("Processed {new_count} documents so far");
                         }
                     }
                 }
@@ -658,8 +686,8 @@ impl ParallelBertReranker {
                 drop(engine_guard); // Explicitly release lock
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Chunk {} completed processing {} documents",
+                    debug!(
+                        "Chunk {} completed processing {} documents",
                         chunk_idx,
                         chunk_results.len()
                     );
@@ -670,7 +698,7 @@ impl ParallelBertReranker {
             .collect();
 
         if debug_mode {
-            eprintln!("DEBUG: All chunks processed, flattening results...");
+            debug!("All chunks processed, flattening results...");
         }
 
         // Signal that processing is complete
@@ -689,8 +717,8 @@ impl ParallelBertReranker {
         if debug_mode {
             let elapsed = start_time.elapsed();
             let rate = total_docs as f64 / elapsed.as_secs_f64();
-            println!(
-                "DEBUG: BERT reranking completed: {} documents in {:.2}s ({:.1} docs/sec)",
+            debug!(
+                "BERT reranking completed: {} documents in {:.2}s ({:.1} docs/sec)",
                 total_docs,
                 elapsed.as_secs_f64(),
                 rate
@@ -701,8 +729,8 @@ impl ParallelBertReranker {
         all_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
         if debug_mode {
-            println!(
-                "DEBUG: Parallel processing complete, {} results sorted",
+            debug!(
+                "Parallel processing complete, {} results sorted",
                 all_scores.len()
             );
         }
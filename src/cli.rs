@@ -19,6 +19,15 @@ pub struct Args {
     #[arg(value_name = "PATH")]
     pub paths: Vec<PathBuf>,
 
+    /// Scope the search to a workspace member package (Cargo, npm/pnpm or Go workspace)
+    #[arg(long = "package", value_name = "NAME")]
+    pub package: Option<String>,
+
+    /// Search a remote git repository, given as URL[@REF], from a shallow clone cached in
+    /// ~/.cache/probe/repos (paths are taken inside the repository)
+    #[arg(long = "repo", value_name = "URL[@REF]")]
+    pub repo: Option<String>,
+
     /// Skip AST parsing and just output unique files
     #[arg(short, long = "files-only")]
     pub files_only: bool,
@@ -27,22 +36,149 @@ pub struct Args {
     #[arg(short, long)]
     pub ignore: Vec<String>,
 
+    /// Only search files matching this glob (.gitignore syntax, relative to the search
+    /// root, e.g. 'src/**/*.rs'); can be repeated
+    #[arg(long = "include", value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Descend at most N directories below each search path (1 searches only the
+    /// files directly in it)
+    #[arg(long = "max-depth", value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Also search hidden files and directories (names starting with a dot)
+    #[arg(long = "hidden")]
+    pub hidden: bool,
+
+    /// Skip files larger than SIZE, e.g. 2M, 500K or 1G (1024-based; plain numbers are
+    /// bytes). Skipped files are listed with the results
+    #[arg(long = "max-filesize", value_name = "SIZE", value_parser = parse_file_size)]
+    pub max_filesize: Option<u64>,
+
+    /// Also search binary files (a NUL byte in their first 8 KB), which are skipped by
+    /// default
+    #[arg(long = "binary")]
+    pub binary: bool,
+
+    /// Also search inside .zip, .jar, .tar.gz and .tgz archives; results in them are
+    /// shown as archive.zip!path/inner.rs
+    #[arg(long = "search-archives")]
+    pub search_archives: bool,
+
+    /// Only search files of this type, e.g. rust or web (see --type-list); can be repeated
+    #[arg(short = 't', long = "type", value_name = "TYPE")]
+    pub file_type: Vec<String>,
+
+    /// Define a file type for --type, e.g. 'web:*.html,*.css'; can be repeated. Types
+    /// kept in the [types] section of probe.toml are always defined
+    #[arg(long = "type-add", value_name = "TYPE:GLOBS")]
+    pub type_add: Vec<String>,
+
+    /// Print the known file types and their globs, then exit
+    #[arg(long = "type-list")]
+    pub type_list: bool,
+
     /// Exclude files whose names match query words (filename matching is enabled by default)
     #[arg(short = 'n', long = "exclude-filenames")]
     pub exclude_filenames: bool,
 
+    /// Only match and rank declaration signatures (function, struct, class names...), so
+    /// definitions aren't drowned out by call sites
+    #[arg(long = "symbols-only")]
+    pub symbols_only: bool,
+
+    /// Ignore matches in comments and rank blocks by their code alone
+    #[arg(long = "no-comments")]
+    pub no_comments: bool,
+
+    /// Only match and rank comments, to find documentation rather than implementation
+    #[arg(long = "comments-only", conflicts_with = "no_comments")]
+    pub comments_only: bool,
+
+    /// Only match and rank string literals, to find user-facing messages, log strings and
+    /// error text without hits in identifiers
+    #[arg(long = "strings-only", conflicts_with_all = ["no_comments", "comments_only"])]
+    pub strings_only: bool,
+
     /// Ranking algorithm for search results. BERT models (ms-marco-*) require --features bert-reranker
-    #[arg(short = 'r', long = "reranker", default_value = "bm25", value_parser = ["bm25", "hybrid", "hybrid2", "tfidf", "ms-marco-tinybert", "ms-marco-minilm-l6", "ms-marco-minilm-l12"])]
+    #[arg(short = 'r', long = "reranker", default_value = "bm25", value_parser = ["bm25", "hybrid", "hybrid2", "hybrid-trigram", "tfidf", "ms-marco-tinybert", "ms-marco-minilm-l6", "ms-marco-minilm-l12"])]
     pub reranker: String,
 
+    /// With --reranker hybrid-trigram, the share of the trigram-vector similarity rank in
+    /// the fused ranking, from 0 (BM25 order) to 1 (similarity order)
+    #[arg(long = "vector-weight", default_value = "0.5")]
+    pub vector_weight: f64,
+
+    /// Rank recently changed files higher: by modification time (mtime, the default)
+    /// or by the date of the last commit touching the file (git)
+    #[arg(long = "boost-recent", value_name = "SOURCE", num_args = 0..=1, default_missing_value = "mtime", value_parser = ["mtime", "git"])]
+    pub boost_recent: Option<String>,
+
+    /// With --boost-recent, the share of recency in the score, from 0 (no effect) to 1
+    #[arg(long = "recency-weight", default_value = "0.3")]
+    pub recency_weight: f64,
+
     /// Use frequency-based search with stemming and stopword removal (enabled by default)
     #[arg(short = 's', long = "frequency", default_value = "true")]
     pub frequency_search: bool,
 
-    /// Perform exact search without tokenization (case-insensitive)
+    /// Perform exact search without tokenization (case-insensitive unless --case-sensitive)
     #[arg(short = 'e', long = "exact")]
     pub exact: bool,
 
+    /// Treat the query as one regular expression matched against file content, like grep.
+    /// Matches are still expanded to code blocks and ranked like a normal search
+    #[arg(long = "regex", conflicts_with = "exact")]
+    pub regex: bool,
+
+    /// With --exact, whether to escape regex metacharacters in the query (default: on).
+    /// `off` treats the query as a raw regular expression
+    #[arg(long = "regex-escape", default_value = "on", value_parser = ["on", "off"])]
+    pub regex_escape: String,
+
+    /// With --exact, match terms that contain an uppercase letter case-sensitively
+    /// (all-lowercase terms stay case-insensitive). Enable by default with PROBE_SMART_CASE=1
+    #[arg(short = 'S', long = "smart-case")]
+    pub smart_case: bool,
+
+    /// Also match words one edit away from each term (a typo such as `recieve` finds
+    /// `receive`). A `~` suffix does this for a single term: `recieve~`
+    #[arg(long = "fuzzy")]
+    pub fuzzy: bool,
+
+    /// Match terms only with the case they are written in, so `Parser` no longer finds
+    /// `parser`. A `case:sensitive` term in the query does the same
+    #[arg(long = "case-sensitive")]
+    pub case_sensitive: bool,
+
+    /// Only report how many files, blocks (estimated) and lines match, without extracting
+    /// or ranking blocks; a cheap check of whether a query needs narrowing
+    #[arg(long = "count-only")]
+    pub count_only: bool,
+
+    /// Instead of the results, report how they differ from a result set saved with
+    /// `--format json`: new, removed and moved blocks
+    #[arg(long = "compare-to", value_name = "FILE")]
+    pub compare_to: Option<String>,
+
+    /// Also save the result set with its full code to FILE, to render it again later
+    /// with `probe show FILE` in any output format
+    #[arg(long = "save-results", value_name = "FILE")]
+    pub save_results: Option<String>,
+
+    /// Feed the locations of the top TOP_K results (default: 5) into `probe extract` and
+    /// print the extracted blocks as one document instead of the search results
+    #[arg(long = "then-extract", value_name = "TOP_K", num_args = 0..=1, default_missing_value = "5")]
+    pub then_extract: Option<usize>,
+
+    /// With --then-extract, number of context lines around each extracted block
+    #[arg(long = "extract-context", value_name = "N", default_value = "0")]
+    pub extract_context: usize,
+
+    /// With --then-extract, include the doc comment preceding each extracted block
+    #[arg(long = "extract-doc-comments")]
+    pub extract_doc_comments: bool,
+
     /// Maximum number of results to return
     #[arg(long = "max-results")]
     pub max_results: Option<usize>,
@@ -55,6 +191,16 @@ pub struct Args {
     #[arg(long = "max-tokens")]
     pub max_tokens: Option<usize>,
 
+    /// Maximum number of distinct files in the results; further blocks of the files
+    /// already included are still returned
+    #[arg(long = "max-files")]
+    pub max_files: Option<usize>,
+
+    /// Shorten blocks longer than this many lines to their signature and the matched lines
+    /// with a little context, replacing the rest with `... N lines omitted ...` markers
+    #[arg(long = "max-block-lines")]
+    pub max_block_lines: Option<usize>,
+
     /// Allow test files and test code blocks in search results
     #[arg(long = "allow-tests")]
     pub allow_tests: bool,
@@ -63,27 +209,148 @@ pub struct Args {
     #[arg(long = "no-gitignore")]
     pub no_gitignore: bool,
 
+    /// Run this query as a search of its own; repeat to run several in one invocation.
+    /// PATTERN is then taken as the first path
+    #[arg(long = "query", value_name = "QUERY")]
+    pub query: Vec<String>,
+
+    /// Run each line of FILE as a search of its own, like --query (`-` reads stdin)
+    #[arg(long = "queries-file", value_name = "FILE")]
+    pub queries_file: Option<String>,
+
+    /// Search only the files listed in FILE, one path per line or NUL-separated (`-`
+    /// reads stdin). Directory walking, .gitignore and --ignore rules are skipped
+    #[arg(long = "files-from", value_name = "FILE")]
+    pub files_from: Option<String>,
+
+    /// Only search files with uncommitted changes: staged, unstaged and untracked
+    #[arg(long = "changed")]
+    pub changed: bool,
+
+    /// Only search files changed since REF (a branch, tag or commit) on this branch,
+    /// or not committed yet, e.g. --since origin/main in CI
+    #[arg(long = "since", value_name = "REF")]
+    pub since: Option<String>,
+
+    /// Show the last author, commit and date of each result, from git blame
+    #[arg(long = "blame")]
+    pub blame: bool,
+
+    /// Show the owners of each result's file, from the repository's CODEOWNERS
+    #[arg(long = "owners")]
+    pub owners: bool,
+
+    /// Also search dependency sources: vendor/, node_modules packages, the cargo
+    /// registry cache and the Go module cache, at the versions the project uses
+    #[arg(long = "include-deps")]
+    pub include_deps: bool,
+
+    /// Include generated files ("do not edit" / "generated by" headers, *.pb.go, ...) and
+    /// license-only files, which are left out of results by default
+    #[arg(long = "include-generated")]
+    pub include_generated: bool,
+
+    /// Score multiplier for results matched only by filename, relative to content
+    /// matches (default: 1.0; 0 pushes filename-only hits to the end)
+    #[arg(long = "filename-weight", default_value = "1.0")]
+    pub filename_weight: f64,
+
+    /// Collapse identical blocks found in several files (vendored or generated copies)
+    /// into one result listing the other files
+    #[arg(long = "dedupe-cross-file", visible_alias = "dedup-content")]
+    pub dedupe_cross_file: bool,
+
+    /// Allow at most this many of the top results from any single directory before
+    /// results from other directories (default when given without a value: 2)
+    #[arg(long = "diversify", value_name = "PER_DIR", num_args = 0..=1, default_missing_value = "2")]
+    pub diversify: Option<usize>,
+
+    /// Skip this many ranked results before applying the limits, to page through
+    /// results without a session (combine with --stable-order)
+    #[arg(long = "offset", value_name = "N", default_value = "0")]
+    pub offset: usize,
+
+    /// Break ranking ties by file and line so repeated searches, and --offset pages,
+    /// return results in the same order
+    #[arg(long = "stable-order")]
+    pub stable_order: bool,
+
+    /// Mask credentials (AWS keys, private key blocks, API tokens, quoted secret
+    /// assignments) in returned code before it is printed
+    #[arg(long = "redact-secrets")]
+    pub redact_secrets: bool,
+
+    /// Never return an entire file as one result: results spanning a whole file (small
+    /// or unparseable files, filename matches) are split into chunks of consecutive lines
+    #[arg(long = "no-whole-files")]
+    pub no_whole_files: bool,
+
+    /// Stemmer for query and content tokens: porter, snowball-en (default), none, or
+    /// snowball-<lang> for non-English code (e.g. snowball-de). Also settable with
+    /// PROBE_STEMMER or `stemmer = "..."` in the project's probe.toml
+    #[arg(long = "stemmer", value_name = "STEMMER")]
+    pub stemmer: Option<String>,
+
     /// Disable merging of adjacent code blocks after ranking (merging enabled by default)
     #[arg(long = "no-merge", default_value = "false")]
     pub no_merge: bool,
 
+    /// Do not extend code blocks to include attached attributes, decorators and annotations
+    #[arg(long = "no-attributes")]
+    pub no_attributes: bool,
+
     /// Maximum number of lines between code blocks to consider them adjacent for merging (default: 5)
     #[arg(long = "merge-threshold")]
     pub merge_threshold: Option<usize>,
 
+    /// Order of the returned results: score (relevance, default), path (file and line),
+    /// lines (shortest blocks first) or mtime (recently modified files first).
+    /// Applied after limits, so limits still keep the most relevant results
+    #[arg(long = "sort", default_value = "score", value_parser = ["score", "path", "lines", "mtime"])]
+    pub sort: String,
+
+    /// Reverse the --sort order
+    #[arg(long = "reverse")]
+    pub reverse: bool,
+
+    /// Organize terminal, markdown and plain output into sections per directory, file
+    /// or language, each with its result count
+    #[arg(long = "group-by", value_parser = ["dir", "file", "language"])]
+    pub group_by: Option<String>,
+
     /// Output only file names and line numbers without full content
     #[arg(long = "dry-run")]
     pub dry_run: bool,
 
     /// Output format (default: outline)
-    /// Use 'json' or 'xml' for machine-readable output
-    #[arg(short = 'o', long = "format", default_value = "outline", value_parser = ["terminal", "markdown", "plain", "json", "xml", "color", "outline", "outline-xml"])]
+    /// Use 'json' or 'xml' for machine-readable output, 'ndjson' for one JSON object per result line
+    #[arg(short = 'o', long = "format", default_value = "outline", value_parser = ["terminal", "markdown", "plain", "json", "ndjson", "xml", "color", "outline", "outline-xml"])]
     pub format: String,
 
     /// Session ID for caching search results
     #[arg(long = "session")]
     pub session: Option<String>,
 
+    /// What --session deduplicates across: `query` (default) only skips blocks already
+    /// returned for the same query, `session` skips blocks returned by any query in the session
+    #[arg(long = "session-scope", default_value = "query", value_parser = ["query", "session"])]
+    pub session_scope: String,
+
+    /// Delete session caches not used for this long, e.g. `30m`, `2h`, `7d` (default: 24h).
+    /// Expired sessions are cleaned up whenever probe searches
+    #[arg(long = "session-ttl", value_name = "DURATION")]
+    pub session_ttl: Option<String>,
+
+    /// Maximum number of blocks remembered per session cache; the oldest are evicted
+    /// beyond this (default: 10000)
+    #[arg(long = "session-max-entries", value_name = "N")]
+    pub session_max_entries: Option<usize>,
+
+    /// Only search the files and blocks returned by earlier queries of this session, to
+    /// narrow down a previous search step by step
+    #[arg(long = "within-session", value_name = "SESSION")]
+    pub within_session: Option<String>,
+
     /// Timeout in seconds for search operation (default: 30)
     #[arg(long = "timeout", default_value = "30")]
     pub timeout: u64,
@@ -96,11 +363,67 @@ pub struct Args {
     #[arg(short = 'v', long = "verbose")]
     pub verbose: bool,
 
+    /// Log level of diagnostic logging, written to stderr or --log-file (default: warn,
+    /// or debug when DEBUG=1 is set)
+    #[arg(long = "log-level", global = true, value_parser = ["off", "error", "warn", "info", "debug", "trace"])]
+    pub log_level: Option<String>,
+
+    /// Append logs to this file instead of writing them to stderr
+    #[arg(long = "log-file", global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Log record format: text (human-readable) or json (one object per line)
+    #[arg(long = "log-format", global = true, default_value = "text", value_parser = ["text", "json"])]
+    pub log_format: String,
+
+    /// Show a progress bar (files done / total and the current stage) on stderr while
+    /// searching, extracting or indexing. Ignored when stderr is not a terminal
+    #[arg(long = "progress", global = true)]
+    pub progress: bool,
+
+    /// Parsers kept in the pool per language (default: PROBE_PARSER_POOL_SIZE, else the
+    /// number of threads but at least 4)
+    #[arg(long = "parser-pool-size", global = true, value_name = "N")]
+    pub parser_pool_size: Option<usize>,
+
+    /// Drop pooled parsers unused for this many seconds (default:
+    /// PROBE_PARSER_IDLE_SECS, else keep them)
+    #[arg(long = "parser-idle-timeout", global = true, value_name = "SECS")]
+    pub parser_idle_timeout: Option<u64>,
+
+    /// Print parser pool hits, misses, evictions and parse times per language to
+    /// stderr when done
+    #[arg(long = "parser-stats", global = true)]
+    pub parser_stats: bool,
+
+    /// Keep token counts in ~/.cache/probe/token-counts between runs, so blocks
+    /// counted by an earlier search are not tokenized again
+    #[arg(long = "token-cache", global = true)]
+    pub token_cache: bool,
+
+    /// Read settings from this file instead of the project's probe.toml. Settings are
+    /// layered: command line > PROBE_* environment variables > this file or the
+    /// project config > the user config (~/.config/probe/config.toml)
+    #[arg(long = "config", global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Language of summaries, hints and tips (default: PROBE_LANG, else the locale's
+    /// language if there is a catalog for it, else English)
+    #[arg(long = "lang", global = true, value_name = "LANG", value_parser = ["en", "de", "es"])]
+    pub lang: Option<String>,
+
+    /// Don't print tips and hints, e.g. for scripts and minimal output. Also set with
+    /// PROBE_NO_TIPS=1
+    #[arg(long = "no-tips", global = true)]
+    pub no_tips: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
 #[derive(Subcommand, Debug)]
+// `Search` carries every search flag; it is parsed once, so boxing it buys nothing
+#[allow(clippy::large_enum_variant)]
 pub enum Commands {
     /// Search code using patterns with intelligent ranking
     ///
@@ -119,13 +442,25 @@ pub enum Commands {
     /// Example: probe search "function AND ext:rs" ./
     Search {
         /// Search pattern (regex supported)
-        #[arg(value_name = "PATTERN")]
-        pattern: String,
+        #[arg(
+            value_name = "PATTERN",
+            required_unless_present_any = ["type_list", "query", "queries_file"]
+        )]
+        pattern: Option<String>,
 
         /// Files or directories to search (defaults to current directory)
         #[arg(value_name = "PATH", default_value = ".")]
         paths: Vec<PathBuf>,
 
+        /// Scope the search to a workspace member package (Cargo, npm/pnpm or Go workspace)
+        #[arg(long = "package", value_name = "NAME")]
+        package: Option<String>,
+
+        /// Search a remote git repository, given as URL[@REF], from a shallow clone cached in
+        /// ~/.cache/probe/repos (paths are taken inside the repository)
+        #[arg(long = "repo", value_name = "URL[@REF]")]
+        repo: Option<String>,
+
         /// Skip AST parsing and just output unique files
         #[arg(short, long = "files-only")]
         files_only: bool,
@@ -134,22 +469,149 @@ pub enum Commands {
         #[arg(short, long)]
         ignore: Vec<String>,
 
+        /// Only search files matching this glob (.gitignore syntax, relative to the search
+        /// root, e.g. 'src/**/*.rs'); can be repeated
+        #[arg(long = "include", value_name = "GLOB")]
+        include: Vec<String>,
+
+        /// Descend at most N directories below each search path (1 searches only the
+        /// files directly in it)
+        #[arg(long = "max-depth", value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Also search hidden files and directories (names starting with a dot)
+        #[arg(long = "hidden")]
+        hidden: bool,
+
+        /// Skip files larger than SIZE, e.g. 2M, 500K or 1G (1024-based; plain numbers are
+        /// bytes). Skipped files are listed with the results
+        #[arg(long = "max-filesize", value_name = "SIZE", value_parser = parse_file_size)]
+        max_filesize: Option<u64>,
+
+        /// Also search binary files (a NUL byte in their first 8 KB), which are skipped
+        /// by default
+        #[arg(long = "binary")]
+        binary: bool,
+
+        /// Also search inside .zip, .jar, .tar.gz and .tgz archives; results in them are
+        /// shown as archive.zip!path/inner.rs
+        #[arg(long = "search-archives")]
+        search_archives: bool,
+
+        /// Only search files of this type, e.g. rust or web (see --type-list); can be repeated
+        #[arg(short = 't', long = "type", value_name = "TYPE")]
+        file_type: Vec<String>,
+
+        /// Define a file type for --type, e.g. 'web:*.html,*.css'; can be repeated. Types
+        /// kept in the [types] section of probe.toml are always defined
+        #[arg(long = "type-add", value_name = "TYPE:GLOBS")]
+        type_add: Vec<String>,
+
+        /// Print the known file types and their globs, then exit
+        #[arg(long = "type-list")]
+        type_list: bool,
+
         /// Exclude files whose names match query words (filename matching is enabled by default)
         #[arg(short = 'n', long = "exclude-filenames")]
         exclude_filenames: bool,
 
+        /// Only match and rank declaration signatures (function, struct, class names...), so
+        /// definitions aren't drowned out by call sites
+        #[arg(long = "symbols-only")]
+        symbols_only: bool,
+
+        /// Ignore matches in comments and rank blocks by their code alone
+        #[arg(long = "no-comments")]
+        no_comments: bool,
+
+        /// Only match and rank comments, to find documentation rather than implementation
+        #[arg(long = "comments-only", conflicts_with = "no_comments")]
+        comments_only: bool,
+
+        /// Only match and rank string literals, to find user-facing messages, log strings
+        /// and error text without hits in identifiers
+        #[arg(long = "strings-only", conflicts_with_all = ["no_comments", "comments_only"])]
+        strings_only: bool,
+
         /// Ranking algorithm for search results. BERT models (ms-marco-*) require --features bert-reranker
-        #[arg(short = 'r', long = "reranker", default_value = "bm25", value_parser = ["bm25", "hybrid", "hybrid2", "tfidf", "ms-marco-tinybert", "ms-marco-minilm-l6", "ms-marco-minilm-l12"])]
+        #[arg(short = 'r', long = "reranker", default_value = "bm25", value_parser = ["bm25", "hybrid", "hybrid2", "hybrid-trigram", "tfidf", "ms-marco-tinybert", "ms-marco-minilm-l6", "ms-marco-minilm-l12"])]
         reranker: String,
 
+        /// With --reranker hybrid-trigram, the share of the trigram-vector similarity rank in
+        /// the fused ranking, from 0 (BM25 order) to 1 (similarity order)
+        #[arg(long = "vector-weight", default_value = "0.5")]
+        vector_weight: f64,
+
+        /// Rank recently changed files higher: by modification time (mtime, the default)
+        /// or by the date of the last commit touching the file (git)
+        #[arg(long = "boost-recent", value_name = "SOURCE", num_args = 0..=1, default_missing_value = "mtime", value_parser = ["mtime", "git"])]
+        boost_recent: Option<String>,
+
+        /// With --boost-recent, the share of recency in the score, from 0 (no effect) to 1
+        #[arg(long = "recency-weight", default_value = "0.3")]
+        recency_weight: f64,
+
         /// Use frequency-based search with stemming and stopword removal (enabled by default)
         #[arg(short = 's', long = "frequency", default_value = "true")]
         frequency_search: bool,
 
-        /// Perform exact search without tokenization (case-insensitive)
+        /// Perform exact search without tokenization (case-insensitive unless --case-sensitive)
         #[arg(short = 'e', long = "exact")]
         exact: bool,
 
+        /// Treat the query as one regular expression matched against file content, like grep.
+        /// Matches are still expanded to code blocks and ranked like a normal search
+        #[arg(long = "regex", conflicts_with = "exact")]
+        regex: bool,
+
+        /// With --exact, whether to escape regex metacharacters in the query (default: on).
+        /// `off` treats the query as a raw regular expression
+        #[arg(long = "regex-escape", default_value = "on", value_parser = ["on", "off"])]
+        regex_escape: String,
+
+        /// With --exact, match terms that contain an uppercase letter case-sensitively
+        /// (all-lowercase terms stay case-insensitive). Enable by default with PROBE_SMART_CASE=1
+        #[arg(short = 'S', long = "smart-case")]
+        smart_case: bool,
+
+        /// Also match words one edit away from each term (a typo such as `recieve` finds
+        /// `receive`). A `~` suffix does this for a single term: `recieve~`
+        #[arg(long = "fuzzy")]
+        fuzzy: bool,
+
+        /// Match terms only with the case they are written in, so `Parser` no longer finds
+        /// `parser`. A `case:sensitive` term in the query does the same
+        #[arg(long = "case-sensitive")]
+        case_sensitive: bool,
+
+        /// Only report how many files, blocks (estimated) and lines match, without extracting
+        /// or ranking blocks; a cheap check of whether a query needs narrowing
+        #[arg(long = "count-only")]
+        count_only: bool,
+
+        /// Instead of the results, report how they differ from a result set saved with
+        /// `--format json`: new, removed and moved blocks
+        #[arg(long = "compare-to", value_name = "FILE")]
+        compare_to: Option<String>,
+
+        /// Also save the result set with its full code to FILE, to render it again later
+        /// with `probe show FILE` in any output format
+        #[arg(long = "save-results", value_name = "FILE")]
+        save_results: Option<String>,
+
+        /// Feed the locations of the top TOP_K results (default: 5) into `probe extract` and
+        /// print the extracted blocks as one document instead of the search results
+        #[arg(long = "then-extract", value_name = "TOP_K", num_args = 0..=1, default_missing_value = "5")]
+        then_extract: Option<usize>,
+
+        /// With --then-extract, number of context lines around each extracted block
+        #[arg(long = "extract-context", value_name = "N", default_value = "0")]
+        extract_context: usize,
+
+        /// With --then-extract, include the doc comment preceding each extracted block
+        #[arg(long = "extract-doc-comments")]
+        extract_doc_comments: bool,
+
         /// Enforce strict ElasticSearch query syntax (require explicit AND/OR operators and quotes for exact matches)
         #[arg(long = "strict-elastic-syntax")]
         strict_elastic_syntax: bool,
@@ -184,6 +646,16 @@ pub enum Commands {
         #[arg(long = "max-tokens")]
         max_tokens: Option<usize>,
 
+        /// Maximum number of distinct files in the results; further blocks of the files
+        /// already included are still returned
+        #[arg(long = "max-files")]
+        max_files: Option<usize>,
+
+        /// Shorten blocks longer than this many lines to their signature and the matched lines
+        /// with a little context, replacing the rest with `... N lines omitted ...` markers
+        #[arg(long = "max-block-lines")]
+        max_block_lines: Option<usize>,
+
         /// Allow test files and test code blocks in search results
         #[arg(long = "allow-tests")]
         allow_tests: bool,
@@ -192,27 +664,149 @@ pub enum Commands {
         #[arg(long = "no-gitignore")]
         no_gitignore: bool,
 
+        /// Run this query as a search of its own; repeat to run several in one invocation.
+        /// PATTERN is then taken as the first path
+        #[arg(long = "query", value_name = "QUERY")]
+        query: Vec<String>,
+
+        /// Run each line of FILE as a search of its own, like --query (`-` reads stdin)
+        #[arg(long = "queries-file", value_name = "FILE")]
+        queries_file: Option<String>,
+
+        /// Search only the files listed in FILE, one path per line or NUL-separated (`-`
+        /// reads stdin). Directory walking, .gitignore and --ignore rules are skipped
+        #[arg(long = "files-from", value_name = "FILE")]
+        files_from: Option<String>,
+
+        /// Only search files with uncommitted changes: staged, unstaged and untracked
+        #[arg(long = "changed")]
+        changed: bool,
+
+        /// Only search files changed since REF (a branch, tag or commit) on this branch,
+        /// or not committed yet, e.g. --since origin/main in CI
+        #[arg(long = "since", value_name = "REF")]
+        since: Option<String>,
+
+        /// Show the last author, commit and date of each result, from git blame
+        #[arg(long = "blame")]
+        blame: bool,
+
+        /// Show the owners of each result's file, from the repository's CODEOWNERS
+        #[arg(long = "owners")]
+        owners: bool,
+
+        /// Also search dependency sources: vendor/, node_modules packages, the cargo
+        /// registry cache and the Go module cache, at the versions the project uses
+        #[arg(long = "include-deps")]
+        include_deps: bool,
+
+        /// Include generated files ("do not edit" / "generated by" headers, *.pb.go, ...) and
+        /// license-only files, which are left out of results by default
+        #[arg(long = "include-generated")]
+        include_generated: bool,
+
+        /// Score multiplier for results matched only by filename, relative to content
+        /// matches (default: 1.0; 0 pushes filename-only hits to the end)
+        #[arg(long = "filename-weight", default_value = "1.0")]
+        filename_weight: f64,
+
+        /// Collapse identical blocks found in several files (vendored or generated copies)
+        /// into one result listing the other files
+        #[arg(long = "dedupe-cross-file", visible_alias = "dedup-content")]
+        dedupe_cross_file: bool,
+
+        /// Allow at most this many of the top results from any single directory before
+        /// results from other directories (default when given without a value: 2)
+        #[arg(long = "diversify", value_name = "PER_DIR", num_args = 0..=1, default_missing_value = "2")]
+        diversify: Option<usize>,
+
+        /// Skip this many ranked results before applying the limits, to page through
+        /// results without a session (combine with --stable-order)
+        #[arg(long = "offset", value_name = "N", default_value = "0")]
+        offset: usize,
+
+        /// Break ranking ties by file and line so repeated searches, and --offset pages,
+        /// return results in the same order
+        #[arg(long = "stable-order")]
+        stable_order: bool,
+
+        /// Mask credentials (AWS keys, private key blocks, API tokens, quoted secret
+        /// assignments) in returned code before it is printed
+        #[arg(long = "redact-secrets")]
+        redact_secrets: bool,
+
+        /// Never return an entire file as one result: results spanning a whole file (small
+        /// or unparseable files, filename matches) are split into chunks of consecutive lines
+        #[arg(long = "no-whole-files")]
+        no_whole_files: bool,
+
+        /// Stemmer for query and content tokens: porter, snowball-en (default), none, or
+        /// snowball-<lang> for non-English code (e.g. snowball-de). Also settable with
+        /// PROBE_STEMMER or `stemmer = "..."` in the project's probe.toml
+        #[arg(long = "stemmer", value_name = "STEMMER")]
+        stemmer: Option<String>,
+
         /// Disable merging of adjacent code blocks after ranking (merging enabled by default)
         #[arg(long = "no-merge", default_value = "false")]
         no_merge: bool,
 
+        /// Do not extend code blocks to include attached attributes, decorators and annotations
+        #[arg(long = "no-attributes")]
+        no_attributes: bool,
+
         /// Maximum number of lines between code blocks to consider them adjacent for merging (default: 5)
         #[arg(long = "merge-threshold")]
         merge_threshold: Option<usize>,
 
+        /// Order of the returned results: score (relevance, default), path (file and line),
+        /// lines (shortest blocks first) or mtime (recently modified files first).
+        /// Applied after limits, so limits still keep the most relevant results
+        #[arg(long = "sort", default_value = "score", value_parser = ["score", "path", "lines", "mtime"])]
+        sort: String,
+
+        /// Reverse the --sort order
+        #[arg(long = "reverse")]
+        reverse: bool,
+
+        /// Organize terminal, markdown and plain output into sections per directory, file
+        /// or language, each with its result count
+        #[arg(long = "group-by", value_parser = ["dir", "file", "language"])]
+        group_by: Option<String>,
+
         /// Output only file names and line numbers without full content
         #[arg(long = "dry-run")]
         dry_run: bool,
 
         /// Output format (default: outline)
-        /// Use 'json' or 'xml' for machine-readable output with structured data
-        #[arg(short = 'o', long = "format", default_value = "outline", value_parser = ["terminal", "markdown", "plain", "json", "xml", "color", "outline", "outline-xml"])]
+        /// Use 'json' or 'xml' for machine-readable output with structured data, 'ndjson'
+        /// to stream one JSON object per result line
+        #[arg(short = 'o', long = "format", default_value = "outline", value_parser = ["terminal", "markdown", "plain", "json", "ndjson", "xml", "color", "outline", "outline-xml"])]
         format: String,
 
         /// Session ID for caching search results
         #[arg(long = "session")]
         session: Option<String>,
 
+        /// What --session deduplicates across: `query` (default) only skips blocks already
+        /// returned for the same query, `session` skips blocks returned by any query in the session
+        #[arg(long = "session-scope", default_value = "query", value_parser = ["query", "session"])]
+        session_scope: String,
+
+        /// Delete session caches not used for this long, e.g. `30m`, `2h`, `7d` (default: 24h).
+        /// Expired sessions are cleaned up whenever probe searches
+        #[arg(long = "session-ttl", value_name = "DURATION")]
+        session_ttl: Option<String>,
+
+        /// Maximum number of blocks remembered per session cache; the oldest are evicted
+        /// beyond this (default: 10000)
+        #[arg(long = "session-max-entries", value_name = "N")]
+        session_max_entries: Option<usize>,
+
+        /// Only search the files and blocks returned by earlier queries of this session, to
+        /// narrow down a previous search step by step
+        #[arg(long = "within-session", value_name = "SESSION")]
+        within_session: Option<String>,
+
         /// Timeout in seconds for search operation (default: 30)
         #[arg(long = "timeout", default_value = "30")]
         timeout: u64,
@@ -233,15 +827,28 @@ pub enum Commands {
     /// the closest suitable parent node (function, struct, class, etc.) for that line.
     /// You can also specify a symbol name using the hash syntax (e.g., file.rs#function_name) to
     /// extract the code block for that specific symbol.
+    /// For Rust trait implementations, use file.rs#Trait for Type to extract an impl block,
+    /// or file.rs#Type::method / file.rs#Trait for Type::method to pick a method from it.
+    /// Logical module paths are accepted too: crate::module::submodule for Rust,
+    /// github.com/org/repo/pkg for Go packages and com.example.pkg.Class for Java.
     Extract {
         /// Files to extract from (can include line numbers with colon, e.g., file.rs:10, or symbol names with hash, e.g., file.rs#function_name)
         #[arg(value_name = "FILES")]
         files: Vec<String>,
 
+        /// Resolve relative file paths inside a workspace member package
+        #[arg(long = "package", value_name = "NAME")]
+        package: Option<String>,
+
         /// Custom patterns to ignore (in addition to .gitignore and common patterns)
         #[arg(short, long)]
         ignore: Vec<String>,
 
+        /// Only extract from files matching this glob (.gitignore syntax, relative to the
+        /// current directory, e.g. 'src/**/*.rs'); can be repeated
+        #[arg(long = "include", value_name = "GLOB")]
+        include: Vec<String>,
+
         /// Do not respect .gitignore files and patterns (gitignore is respected by default)
         #[arg(long = "no-gitignore")]
         no_gitignore: bool,
@@ -250,6 +857,47 @@ pub enum Commands {
         #[arg(short = 'c', long = "context", default_value = "0")]
         context_lines: usize,
 
+        /// Include the doc comment (rustdoc, JSDoc, Javadoc, etc.) preceding each extracted block
+        #[arg(long = "with-doc-comments")]
+        with_doc_comments: bool,
+
+        /// Do not extend extracted blocks to include attached attributes, decorators and annotations
+        #[arg(long = "no-attributes")]
+        no_attributes: bool,
+
+        /// Extract a symbol from a dependency crate, e.g. `tokio#spawn` or
+        /// `tokio#Runtime::block_on` (uses the cargo registry cache; includes doc comments)
+        #[arg(long = "dep", value_name = "CRATE#SYMBOL")]
+        deps: Vec<String>,
+
+        /// Mask credentials (AWS keys, private key blocks, API tokens, quoted secret
+        /// assignments) in extracted code before it is printed or copied
+        #[arg(long = "redact-secrets")]
+        redact_secrets: bool,
+
+        /// Never return an entire file as one result: a file requested without lines, or
+        /// extracted whole because it couldn't be parsed, is split into chunks of lines
+        #[arg(long = "no-whole-files")]
+        no_whole_files: bool,
+
+        /// Also save the extracted blocks with their full code to FILE, to render them
+        /// again later with `probe show FILE` in any output format
+        #[arg(long = "save-results", value_name = "FILE")]
+        save_results: Option<String>,
+
+        /// Show the last author, commit and date of each block, from git blame
+        #[arg(long = "blame")]
+        blame: bool,
+
+        /// Show the owners of each block's file, from the repository's CODEOWNERS
+        #[arg(long = "owners")]
+        owners: bool,
+
+        /// Keep only public items (pub in Rust, exported in Go, JavaScript and
+        /// TypeScript, public in Java and C#); a whole file becomes its public items
+        #[arg(long = "public-only")]
+        public_only: bool,
+
         /// Output format (default: color)
         /// Use 'json' or 'xml' for machine-readable output with structured data
         /// Use 'outline-diff' for semantically enhanced git diff output
@@ -338,10 +986,30 @@ pub enum Commands {
         #[arg(long = "no-gitignore")]
         no_gitignore: bool,
 
+        /// Descend at most N directories below the search path (1 searches only the
+        /// files directly in it)
+        #[arg(long = "max-depth", value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Also search hidden files and directories (names starting with a dot)
+        #[arg(long = "hidden")]
+        hidden: bool,
+
+        /// Skip files larger than SIZE, e.g. 2M, 500K or 1G (1024-based; plain numbers are
+        /// bytes)
+        #[arg(long = "max-filesize", value_name = "SIZE", value_parser = parse_file_size)]
+        max_filesize: Option<u64>,
+
         /// Maximum number of results to return
         #[arg(long = "max-results")]
         max_results: Option<usize>,
 
+        /// Return the whole function, class or other block enclosing each match, as
+        /// extract would, instead of just the matched node. Matches in the same block
+        /// are merged into one result
+        #[arg(long = "full-block")]
+        full_block: bool,
+
         /// Output format (default: color)
         /// Use 'json' or 'xml' for machine-readable output with structured data
         #[arg(short = 'o', long = "format", default_value = "color", value_parser = ["markdown", "plain", "json", "xml", "color", "outline-xml"])]
@@ -352,11 +1020,12 @@ pub enum Commands {
     ///
     /// This command runs comprehensive performance benchmarks using the Criterion framework.
     /// It tests various aspects of the search engine including search patterns, result limits,
-    /// different options, timing infrastructure, and language parsing performance.
+    /// different options, timing infrastructure, language parsing performance, the thread
+    /// scaling of result ranking, and the startup time of short probe calls.
     /// Results are saved to the target/criterion directory.
     Benchmark {
         /// Specific benchmark to run (default: all)
-        #[arg(long = "bench", value_parser = ["all", "search", "timing", "parsing"])]
+        #[arg(long = "bench", value_parser = ["all", "search", "timing", "parsing", "ranking", "startup"])]
         bench: Option<String>,
 
         /// Number of iterations for each benchmark (default: auto)
@@ -394,8 +1063,8 @@ pub enum Commands {
     /// this command performs simple line-based pattern matching with fast output.
     Grep {
         /// Pattern to search for (regex supported)
-        #[arg(value_name = "PATTERN")]
-        pattern: String,
+        #[arg(value_name = "PATTERN", required_unless_present = "type_list")]
+        pattern: Option<String>,
 
         /// Files or directories to search (defaults to current directory)
         #[arg(value_name = "PATH", default_value = ".")]
@@ -405,6 +1074,11 @@ pub enum Commands {
         #[arg(short = 'i', long = "ignore-case")]
         ignore_case: bool,
 
+        /// Case insensitive unless the pattern contains an uppercase letter.
+        /// Enable by default with PROBE_SMART_CASE=1
+        #[arg(short = 'S', long = "smart-case")]
+        smart_case: bool,
+
         /// Show line numbers (enabled by default)
         #[arg(short = 'n', long = "line-number", default_value = "true")]
         line_number: bool,
@@ -425,6 +1099,11 @@ pub enum Commands {
         #[arg(short = 'v', long = "invert-match")]
         invert_match: bool,
 
+        /// Search binary files byte-wise: each match is shown as its byte offset and a
+        /// hex/ASCII dump of the bytes around it instead of as a text line
+        #[arg(long = "binary", conflicts_with = "invert_match")]
+        binary: bool,
+
         /// Show NUM lines before each match
         #[arg(short = 'B', long = "before-context", value_name = "NUM")]
         before_context: Option<usize>,
@@ -441,17 +1120,512 @@ pub enum Commands {
         #[arg(long = "ignore")]
         ignore: Vec<String>,
 
+        /// Descend at most N directories below each path (1 searches only the files
+        /// directly in it). Hidden files are always searched
+        #[arg(long = "max-depth", value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// Only search files of this type, e.g. rust or web (see --type-list); can be repeated
+        #[arg(short = 't', long = "type", value_name = "TYPE")]
+        file_type: Vec<String>,
+
+        /// Define a file type for --type, e.g. 'web:*.html,*.css'; can be repeated. Types
+        /// kept in the [types] section of probe.toml are always defined
+        #[arg(long = "type-add", value_name = "TYPE:GLOBS")]
+        type_add: Vec<String>,
+
+        /// Print the known file types and their globs, then exit
+        #[arg(long = "type-list")]
+        type_list: bool,
+
         /// Do not respect .gitignore files
         #[arg(long = "no-gitignore")]
         no_gitignore: bool,
 
+        /// Search only the files listed in FILE, one path per line or NUL-separated (`-`
+        /// reads stdin), instead of walking PATH
+        #[arg(long = "files-from", value_name = "FILE")]
+        files_from: Option<String>,
+
         /// Enable colored output
         #[arg(long = "color", value_parser = ["auto", "always", "never"], default_value = "auto")]
         color: String,
 
-        /// Maximum number of matches to show
+        /// Stop reading a file after this many matching lines (and their after-context)
         #[arg(short = 'm', long = "max-count")]
         max_count: Option<usize>,
+
+        /// Stop after reporting this many files
+        #[arg(long = "max-files")]
+        max_files: Option<usize>,
+
+        /// Print the file name once above its matches instead of on every line
+        #[arg(long = "heading", overrides_with = "no_heading")]
+        heading: bool,
+
+        /// Print the file name on every line (default)
+        #[arg(long = "no-heading", overrides_with = "heading")]
+        no_heading: bool,
+
+        /// Line printed between non-adjacent groups of context lines
+        #[arg(long = "group-separator", default_value = "--")]
+        group_separator: String,
+
+        /// Do not print a separator between groups of context lines
+        #[arg(long = "no-group-separator")]
+        no_group_separator: bool,
+    },
+
+    /// Count tokens in files, directories or stdin
+    ///
+    /// Uses the same tokenizer as --max-tokens by default, so the numbers are
+    /// consistent with probe's own limits. Directories are walked respecting
+    /// .gitignore and show a per-file breakdown.
+    ///
+    /// Example: probe tokens src/ or: cat prompt.md | probe tokens -
+    Tokens {
+        /// Files or directories to count, or `-` to read stdin
+        #[arg(value_name = "PATH", required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Tokenizer: p50k (default, the one used by --max-tokens), cl100k, o200k or r50k
+        #[arg(long = "tokenizer", default_value = "p50k", value_parser = ["p50k", "cl100k", "o200k", "r50k"])]
+        tokenizer: String,
+
+        /// Output format (default: plain)
+        /// Use 'json' for machine-readable JSON output
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+
+        /// Do not respect .gitignore files
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+    },
+
+    /// Build an on-disk file index for a directory
+    ///
+    /// Stores the list of files a search would walk (respecting .gitignore and the
+    /// same ignore options as search). With --watch the command keeps running and
+    /// re-indexes only the files that change, and searches with matching options take
+    /// their file list from the index instead of walking the directory.
+    ///
+    /// Example: probe index --watch .
+    Index {
+        /// Directory to index (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Keep running and update the index as files change
+        #[arg(short = 'w', long = "watch")]
+        watch: bool,
+
+        /// Index test files too (searches with --allow-tests use this index)
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+    },
+
+    /// Render a result set saved with --save-results
+    ///
+    /// Prints the blocks of an earlier search or extraction in any output format
+    /// without running it again.
+    ///
+    /// Example: probe search "auth" --save-results auth.json, then: probe show auth.json -o markdown
+    Show {
+        /// File written by --save-results
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Output format (default: outline for searches, color for extractions)
+        #[arg(short = 'o', long = "format", value_parser = ["terminal", "markdown", "plain", "json", "ndjson", "xml", "color", "outline", "outline-xml"])]
+        format: Option<String>,
+    },
+
+    /// Check the environment probe runs in and suggest fixes
+    ///
+    /// Checks git, clipboard access, terminal colors, the project's probe.toml,
+    /// session caches, the tree-sitter grammars and the file index, printing a fix
+    /// for each problem. Exits with an error if any check fails.
+    ///
+    /// Example: probe doctor
+    Doctor {
+        /// Project directory to check the config and index of
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+    },
+
+    /// Inspect the configuration probe runs with
+    ///
+    /// Settings come from the user config (~/.config/probe/config.toml), the project's
+    /// probe.toml / .probe.toml / .probe/config.toml (or --config), PROBE_* environment
+    /// variables and the command line, each overriding the ones before.
+    ///
+    /// Example: probe config show --resolved
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Search the lines added and removed in git history
+    ///
+    /// Finds the commits whose changes match PATTERN, like `git log -S` (or `git log -G`
+    /// with --regex), ranks them by how many changed lines match and shows the code
+    /// block around each change as it was at that revision.
+    ///
+    /// Example: probe history "load_config" src/
+    History {
+        /// String to look for in the changed lines (a regex with --regex)
+        #[arg(value_name = "PATTERN")]
+        pattern: String,
+
+        /// File or directory whose history to search (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Match PATTERN as a regex against changed lines (`git log -G`) instead of
+        /// finding commits that change its number of occurrences (`git log -S`)
+        #[arg(short = 'G', long = "regex")]
+        regex: bool,
+
+        /// Case-insensitive search
+        #[arg(short = 'i', long = "ignore-case")]
+        ignore_case: bool,
+
+        /// Look at most at this many matching commits, newest first
+        #[arg(short = 'n', long = "max-commits", default_value = "100")]
+        max_commits: usize,
+
+        /// Allow test files and test code blocks in the context
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+    },
+
+    /// Report TODO, FIXME and HACK notes left in comments
+    ///
+    /// Looks for the tags in comments only, found with the language parsers, so
+    /// strings and code mentioning a tag don't count. Notes are grouped by tag, file
+    /// or, with --blame, by the author who last changed them.
+    ///
+    /// Example: probe todos src/ --tags TODO,FIXME,XXX --group-by file
+    Todos {
+        /// File or directory to scan (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Comma-separated tags to look for, matched case-sensitively as whole words
+        #[arg(
+            short = 't',
+            long = "tags",
+            value_delimiter = ',',
+            default_value = "TODO,FIXME,HACK"
+        )]
+        tags: Vec<String>,
+
+        /// How to group the notes (owner needs --blame)
+        #[arg(short = 'g', long = "group-by", default_value = "tag", value_parser = ["tag", "file", "owner"])]
+        group_by: String,
+
+        /// Show who last changed each note and when, from git blame
+        #[arg(long = "blame")]
+        blame: bool,
+
+        /// Include test files
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json", "markdown"])]
+        format: String,
+    },
+
+    /// List the user-facing string literals in code, with the symbol around each
+    ///
+    /// For localization audits and reviews of the prompts in LLM application code.
+    /// Imports, attribute arguments, docstrings, object keys and strings without a
+    /// letter are left out; arguments of translation functions like t() are marked
+    /// as i18n keys.
+    ///
+    /// Example: probe strings src/ --min-length 4 --no-logs
+    Strings {
+        /// File or directory to scan (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Shortest string to list, in characters
+        #[arg(long = "min-length", default_value = "2")]
+        min_length: usize,
+
+        /// Leave out the arguments of logging calls (console.log, logger.info, log::warn!, ...)
+        #[arg(long = "no-logs")]
+        no_logs: bool,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+    },
+
+    /// Classify the changes between two revisions as breaking, additive or internal
+    ///
+    /// Compares the items of every changed source file at both revisions, with what is
+    /// public decided as for --public-only: removing a public item or changing its
+    /// signature is breaking, adding one is additive, everything else is internal.
+    /// Reports the version part to bump, in JSON for release tooling.
+    ///
+    /// Example: probe semver v1.4.0..HEAD src/ --format json
+    Semver {
+        /// Revisions to compare, as <base>..<head>; <base> alone compares with HEAD
+        #[arg(value_name = "RANGE")]
+        range: String,
+
+        /// File or directory to compare (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+    },
+
+    /// Write one JSON record per symbol, for embedding databases and fine-tuning datasets
+    ///
+    /// Every named function, method and type gets a record with its path, language,
+    /// qualified name, signature, doc comment, body and token count. Nested symbols
+    /// get records of their own.
+    ///
+    /// Example: probe export src/ --language rust --max-tokens 2000 > snippets.jsonl
+    Export {
+        /// File or directory to export (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Only export files in this language (rust, python, typescript, ...)
+        #[arg(short = 'l', long = "language")]
+        language: Option<String>,
+
+        /// Leave out symbols with fewer tokens
+        #[arg(long = "min-tokens", default_value = "0")]
+        min_tokens: usize,
+
+        /// Leave out symbols with more tokens
+        #[arg(long = "max-tokens")]
+        max_tokens: Option<usize>,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format: one record per line, or one JSON array
+        #[arg(short = 'o', long = "format", default_value = "jsonl", value_parser = ["jsonl", "json"])]
+        format: String,
+    },
+
+    /// Find the symbols lexically closest to a question, from a local vector store
+    ///
+    /// `probe similar index` hashes the words and character trigrams of every function,
+    /// method and type of a codebase into a store under ~/.cache/probe/similar;
+    /// `probe similar search` ranks them by similarity to a question, re-indexing
+    /// changed files first. Similarity is lexical, with no model, and runs offline.
+    ///
+    /// Example: probe similar search "where are sessions expired" src/
+    Similar {
+        #[command(subcommand)]
+        action: SimilarAction,
+    },
+
+    /// Report structural metrics of every file and symbol
+    ///
+    /// Lines of code, comment ratio, cyclomatic complexity, and fan-in and fan-out
+    /// from the call graph, per file and per symbol. The text format lists the most
+    /// complex; JSON and CSV export everything for tracking hotspots over time.
+    ///
+    /// Example: probe metrics src/ --format csv > metrics.csv
+    Metrics {
+        /// File or directory to measure (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Only measure files in this language (rust, python, typescript, ...)
+        #[arg(short = 'l', long = "language")]
+        language: Option<String>,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Number of files and symbols listed by the text format
+        #[arg(long = "top", default_value = "20")]
+        top: usize,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "text", value_parser = ["text", "json", "csv"])]
+        format: String,
+    },
+
+    /// Check the import graph against dependency rules between directories
+    ///
+    /// Each line of the rules file is a rule such as `src/ui must not import src/db` or
+    /// `src/domain may only import src/util, src/models`. Violations are printed as text,
+    /// JSON or SARIF, and any violation makes probe exit with status 1, for CI.
+    ///
+    /// Example: probe arch --rules arch.rules --format sarif > arch.sarif
+    Arch {
+        /// Directory to check; rule paths are relative to it (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// File with the dependency rules, one per line
+        #[arg(long = "rules", value_name = "FILE")]
+        rules: PathBuf,
+
+        /// Include test files
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "text", value_parser = ["text", "json", "sarif"])]
+        format: String,
+    },
+
+    /// Gather the code relevant to a task into one token-budgeted bundle
+    ///
+    /// Combines the ranked keyword search with the vector store of `probe similar`, favours the
+    /// files the other candidates depend on, and packs the best blocks into the budget
+    /// after a map of the files and symbols included, ready for an LLM prompt.
+    ///
+    /// Example: probe context "add retries to the upload client" --max-tokens 8000
+    Context {
+        /// What the context is for, in plain language
+        #[arg(value_name = "TASK")]
+        task: String,
+
+        /// Directory to gather context from (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Token budget of the whole bundle
+        #[arg(long = "max-tokens", default_value = "8000")]
+        max_tokens: usize,
+
+        /// Rank with the keyword search alone, without building the vector store
+        #[arg(long = "no-similar")]
+        no_similar: bool,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format: the bundle as Markdown, or its files and blocks as JSON
+        #[arg(short = 'o', long = "format", default_value = "markdown", value_parser = ["markdown", "json"])]
+        format: String,
+    },
+
+    /// Run a Model Context Protocol server over stdio
+    ///
+    /// Exposes search, extract, query and outline as MCP tools that return JSON
+    /// results, so AI assistants can call probe directly. Configure your assistant
+    /// to launch `probe mcp` as a stdio server.
+    ///
+    /// Example: probe mcp
+    Mcp,
+
+    /// Run an HTTP server exposing search, extract, outline and query
+    ///
+    /// Endpoints /search, /extract, /outline and /query take their parameters from
+    /// the query string or a JSON body and answer with the same JSON as --format json.
+    /// Parsers and caches stay warm between requests.
+    ///
+    /// Example: probe serve --port 8080
+    Serve {
+        /// Port to listen on
+        #[arg(short = 'p', long = "port", default_value = "8080")]
+        port: u16,
+
+        /// Address to bind to
+        #[arg(long = "host", default_value = "127.0.0.1")]
+        host: String,
+
+        /// Allow test files and test code blocks in all responses
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short = 'i', long = "ignore")]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
     },
 
     /// List all symbols (functions, classes, structs, etc.) in a file
@@ -466,6 +1640,10 @@ pub enum Commands {
         #[arg(value_name = "FILE")]
         file: PathBuf,
 
+        /// Resolve the file path inside a workspace member package
+        #[arg(long = "package", value_name = "NAME")]
+        package: Option<String>,
+
         /// Output format (default: plain)
         /// Use 'json' for machine-readable JSON output
         #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
@@ -475,8 +1653,102 @@ pub enum Commands {
         #[arg(long = "allow-tests")]
         allow_tests: bool,
 
+        /// List only public symbols (pub in Rust, exported in Go, JavaScript and
+        /// TypeScript, public in Java and C#), the API surface of the file
+        #[arg(long = "public-only")]
+        public_only: bool,
+
         /// Do not respect .gitignore files
         #[arg(long = "no-gitignore")]
         no_gitignore: bool,
     },
 }
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// List the config files that apply, lowest precedence first; with --resolved,
+    /// print the effective value of every setting and where it came from
+    Show {
+        /// Project directory to resolve the configuration for
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Print the effective settings with their origins
+        #[arg(long = "resolved")]
+        resolved: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "plain", value_parser = ["plain", "json"])]
+        format: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SimilarAction {
+    /// Build or update the vector store of a directory
+    Index {
+        /// Directory to index (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+    },
+
+    /// Find the symbols closest to a question
+    Search {
+        /// The question, in plain language
+        #[arg(value_name = "QUESTION")]
+        question: String,
+
+        /// Directory to search (defaults to current directory)
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Maximum number of results to return
+        #[arg(long = "max-results", default_value = "10")]
+        max_results: usize,
+
+        /// Include test files and test code
+        #[arg(long = "allow-tests")]
+        allow_tests: bool,
+
+        /// Custom patterns to ignore (in addition to .gitignore and common patterns)
+        #[arg(short, long)]
+        ignore: Vec<String>,
+
+        /// Do not respect .gitignore files and patterns (gitignore is respected by default)
+        #[arg(long = "no-gitignore")]
+        no_gitignore: bool,
+
+        /// Output format
+        #[arg(short = 'o', long = "format", default_value = "color", value_parser = ["terminal", "markdown", "plain", "json", "xml", "color"])]
+        format: String,
+    },
+}
+
+/// Parse a file size such as `2M`: a number of bytes with an optional K, M or G suffix
+/// (1024-based)
+pub fn parse_file_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&value[..i], 1 << 10),
+        Some((i, 'm' | 'M')) => (&value[..i], 1 << 20),
+        Some((i, 'g' | 'G')) => (&value[..i], 1 << 30),
+        _ => (value, 1),
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid size '{value}', expected e.g. 2M, 500K or 1048576"))
+}
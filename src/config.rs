@@ -0,0 +1,133 @@
+//! `probe config show`: the config files that apply to a directory and, with
+//! `--resolved`, the effective value of every setting and where it came from.
+
+use anyhow::Result;
+use clap::CommandFactory;
+use colored::*;
+use probe_code::search::project_config::{config_layers, resolve_config, ResolvedConfig};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::cli::Args;
+
+/// One row of `probe config show --resolved`
+#[derive(Debug, Serialize)]
+struct Setting {
+    setting: &'static str,
+    value: String,
+    origin: String,
+}
+
+pub fn handle_config_show(path: &Path, resolved: bool, format: &str) -> Result<()> {
+    let layers = config_layers(path)?;
+    if !resolved {
+        let origins: Vec<String> = layers
+            .iter()
+            .map(|layer| layer.source.origin("*"))
+            .collect();
+        if format == "json" {
+            println!("{}", serde_json::to_string_pretty(&origins)?);
+        } else {
+            for origin in origins {
+                println!("{origin}");
+            }
+        }
+        return Ok(());
+    }
+
+    let settings = settings(&resolve_config(layers));
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&settings)?);
+    } else {
+        let width = settings
+            .iter()
+            .map(|setting| setting.setting.len() + setting.value.len())
+            .max()
+            .unwrap_or(0);
+        for setting in &settings {
+            let line = format!("{} = {}", setting.setting, setting.value);
+            println!(
+                "{line:<width$}  {}",
+                format!("# {}", setting.origin).dimmed(),
+                width = width + 3
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Every setting with its value, falling back to the defaults of `probe search`
+fn settings(resolved: &ResolvedConfig) -> Vec<Setting> {
+    let config = &resolved.config;
+    let quote = |value: &str| format!("{value:?}");
+    let list = |values: &[String]| {
+        let quoted: Vec<String> = values.iter().map(|value| quote(value)).collect();
+        format!("[{}]", quoted.join(", "))
+    };
+    let mut languages: Vec<String> = config
+        .languages
+        .iter()
+        .map(|(from, to)| format!("{from} = {}", quote(to)))
+        .collect();
+    languages.sort();
+
+    let values = [
+        (
+            "stemmer",
+            quote(&config.stemmer.unwrap_or_default().to_string()),
+        ),
+        (
+            "reranker",
+            quote(
+                &config
+                    .reranker
+                    .clone()
+                    .unwrap_or_else(|| default_of("reranker")),
+            ),
+        ),
+        (
+            "format",
+            quote(
+                &config
+                    .format
+                    .clone()
+                    .unwrap_or_else(|| default_of("format")),
+            ),
+        ),
+        (
+            "max_tokens",
+            config
+                .max_tokens
+                .map_or("none".to_string(), |tokens| tokens.to_string()),
+        ),
+        (
+            "allow_tests",
+            config.allow_tests.unwrap_or_default().to_string(),
+        ),
+        ("ignore", list(&config.ignore)),
+        ("languages", format!("{{ {} }}", languages.join(", "))),
+        ("types", list(&config.types)),
+    ];
+    values
+        .into_iter()
+        .map(|(setting, value)| Setting {
+            setting,
+            value,
+            origin: resolved
+                .origins
+                .get(setting)
+                .map_or("default".to_string(), |origins| origins.join(", ")),
+        })
+        .collect()
+}
+
+/// The default value of a `probe search` option
+fn default_of(id: &str) -> String {
+    let command = Args::command();
+    command
+        .find_subcommand("search")
+        .and_then(|search| search.get_arguments().find(|arg| arg.get_id() == id))
+        .and_then(|arg| arg.get_default_values().first())
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
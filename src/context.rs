@@ -0,0 +1,438 @@
+//! `probe context`: gather the code relevant to a task into one token-budgeted bundle,
+//! ready to drop into an LLM prompt.
+//!
+//! The task description is planned into a keyword query (stop words dropped) for the
+//! ranked search, and asked as is of the vector store of `probe similar`. The two rankings are
+//! fused by reciprocal rank, then weighted by each file's centrality among the
+//! candidates: how many of the other candidate files use the symbols it defines. Blocks
+//! are packed best first while the bundle fits the token budget, overlapping blocks of
+//! a file merged, and printed by file after a map of everything included.
+
+use anyhow::Result;
+use probe_code::extract::symbols::{file_symbols, language_name, Symbol};
+use probe_code::search::search_tokens::count_tokens;
+use probe_code::search::tokenization::is_stop_word;
+use probe_code::search::vector_store::VectorStore;
+use probe_code::search::{perform_probe, SearchOptions};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Blocks taken from each ranking before fusing them
+const CANDIDATES: usize = 40;
+
+/// Damping of reciprocal rank fusion: a block ranked `r` scores `1 / (RRF_K + r)`
+const RRF_K: f64 = 60.0;
+
+/// How much centrality can raise a block's score: a file used by every other
+/// candidate scores `1 + CENTRALITY_WEIGHT` times as much, worth a few ranks near the
+/// top, so relevance still comes first
+const CENTRALITY_WEIGHT: f64 = 0.1;
+
+/// Defined names shorter than this are too common to tell which file is used
+const MIN_SYMBOL_LEN: usize = 4;
+
+pub struct ContextParams<'a> {
+    pub task: &'a str,
+    pub path: &'a Path,
+    pub max_tokens: usize,
+    /// Rank with the keyword search alone, leaving the vector store alone
+    pub no_similar: bool,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+/// A candidate block, or the union of the overlapping ones of a file
+#[derive(Debug, Clone, PartialEq)]
+struct Block {
+    file: PathBuf,
+    lines: (usize, usize),
+    score: f64,
+}
+
+#[derive(Serialize)]
+struct Bundle<'a> {
+    task: &'a str,
+    query: &'a str,
+    max_tokens: usize,
+    tokens: usize,
+    files: Vec<BundleFile>,
+}
+
+#[derive(Serialize)]
+struct BundleFile {
+    path: String,
+    language: &'static str,
+    blocks: Vec<BundleBlock>,
+}
+
+#[derive(Serialize)]
+struct BundleBlock {
+    lines: (usize, usize),
+    symbols: Vec<String>,
+    score: f64,
+    code: String,
+}
+
+pub fn handle_context(params: ContextParams) -> Result<()> {
+    let root = fs::canonicalize(params.path)?;
+    let query = plan_query(params.task);
+
+    let mut rankings = vec![keyword_ranking(&params, &query)?];
+    if !params.no_similar {
+        rankings.push(similarity_ranking(&params, &root)?);
+    }
+    let mut candidates = fuse_rankings(&rankings);
+
+    let mut contents: HashMap<PathBuf, String> = HashMap::new();
+    for block in &candidates {
+        if !contents.contains_key(&block.file) {
+            if let Ok(content) = fs::read_to_string(&block.file) {
+                contents.insert(block.file.clone(), content);
+            }
+        }
+    }
+    candidates.retain(|block| contents.contains_key(&block.file));
+    let symbols: HashMap<&PathBuf, Vec<Symbol>> = contents
+        .iter()
+        .map(|(file, content)| {
+            let symbols = file_symbols(&file.to_string_lossy(), content, true);
+            (file, symbols)
+        })
+        .collect();
+    let centrality = centrality(&contents, &symbols);
+    for block in &mut candidates {
+        block.score *= 1.0 + CENTRALITY_WEIGHT * centrality.get(&block.file).unwrap_or(&0.0);
+    }
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| (&a.file, a.lines).cmp(&(&b.file, b.lines)))
+    });
+
+    let mut bundle = Bundle {
+        task: params.task,
+        query: &query,
+        max_tokens: params.max_tokens,
+        tokens: 0,
+        files: Vec::new(),
+    };
+    let mut selected: Vec<Block> = Vec::new();
+    for candidate in candidates {
+        let mut attempt = selected.clone();
+        add_block(&mut attempt, candidate);
+        let files = bundle_files(&attempt, &root, &contents, &symbols);
+        let tokens = count_tokens(&render_bundle(params.task, &files));
+        if tokens <= params.max_tokens {
+            selected = attempt;
+            bundle.files = files;
+            bundle.tokens = tokens;
+        }
+    }
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&bundle)?);
+    } else {
+        print!("{}", render_bundle(params.task, &bundle.files));
+    }
+    Ok(())
+}
+
+/// The keyword query of a task: its words, without stop words and repeats, any of
+/// which may match. Falls back to the task as written when every word is a stop word.
+fn plan_query(task: &str) -> String {
+    let mut seen = HashSet::new();
+    let words: Vec<&str> = task
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty() && !is_stop_word(&word.to_lowercase()))
+        .filter(|word| seen.insert(word.to_lowercase()))
+        .collect();
+    if words.is_empty() {
+        task.to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+/// The blocks of the ranked keyword search, best first
+fn keyword_ranking(params: &ContextParams, query: &str) -> Result<Vec<Block>> {
+    let paths = vec![params.path.to_path_buf()];
+    let queries = vec![query.to_string()];
+    let options = SearchOptions {
+        paths: &paths,
+        queries: &queries,
+        custom_ignores: params.ignore,
+        reranker: "hybrid",
+        max_results: Some(CANDIDATES),
+        allow_tests: params.allow_tests,
+        no_gitignore: params.no_gitignore,
+        ..Default::default()
+    };
+    Ok(perform_probe(&options)?
+        .results
+        .into_iter()
+        .filter(|result| !result.file.is_empty())
+        .filter_map(|result| {
+            Some(Block {
+                file: fs::canonicalize(&result.file).ok()?,
+                lines: result.lines,
+                score: 0.0,
+            })
+        })
+        .collect())
+}
+
+/// The symbols of the vector store closest to the task, best first. The store is
+/// brought up to date first, and saved when that changed it.
+fn similarity_ranking(params: &ContextParams, root: &Path) -> Result<Vec<Block>> {
+    let (store, update) = VectorStore::update(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.no_gitignore,
+    )?;
+    if !update.is_empty() {
+        store.save()?;
+    }
+    Ok(store
+        .search(params.task, CANDIDATES)
+        .into_iter()
+        .map(|m| Block {
+            file: root.join(m.file),
+            lines: m.chunk.lines,
+            score: 0.0,
+        })
+        .collect())
+}
+
+/// Score the blocks of all rankings by reciprocal rank fusion. Overlapping blocks of a
+/// file, such as a keyword match and the symbol around it, become one block that adds
+/// up their scores.
+fn fuse_rankings(rankings: &[Vec<Block>]) -> Vec<Block> {
+    let mut fused: Vec<Block> = Vec::new();
+    for ranking in rankings {
+        for (rank, block) in ranking.iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match fused.iter_mut().find(|b| overlaps(b, block)) {
+                Some(existing) => {
+                    existing.lines = (
+                        existing.lines.0.min(block.lines.0),
+                        existing.lines.1.max(block.lines.1),
+                    );
+                    existing.score += score;
+                }
+                None => fused.push(Block {
+                    score,
+                    ..block.clone()
+                }),
+            }
+        }
+    }
+    fused
+}
+
+fn overlaps(a: &Block, b: &Block) -> bool {
+    a.file == b.file && a.lines.0 <= b.lines.1 && b.lines.0 <= a.lines.1
+}
+
+/// For each file, the share of the other files that use a name it defines, from 0 to 1
+fn centrality<'a>(
+    contents: &'a HashMap<PathBuf, String>,
+    symbols: &HashMap<&PathBuf, Vec<Symbol>>,
+) -> HashMap<&'a PathBuf, f64> {
+    let identifiers: HashMap<&PathBuf, HashSet<&str>> = contents
+        .iter()
+        .map(|(file, content)| {
+            let words = content
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .filter(|word| word.len() >= MIN_SYMBOL_LEN)
+                .collect();
+            (file, words)
+        })
+        .collect();
+    let others = contents.len().saturating_sub(1).max(1) as f64;
+    contents
+        .keys()
+        .map(|file| {
+            let defined: HashSet<&str> = symbols
+                .get(file)
+                .into_iter()
+                .flatten()
+                .filter_map(|symbol| symbol.name.rsplit('.').next())
+                .filter(|name| name.len() >= MIN_SYMBOL_LEN)
+                .collect();
+            let users = identifiers
+                .iter()
+                .filter(|(other, words)| {
+                    **other != file && defined.iter().any(|name| words.contains(name))
+                })
+                .count();
+            (file, users as f64 / others)
+        })
+        .collect()
+}
+
+/// Add a block to a selection, merging it with the blocks of its file it overlaps or
+/// touches; the merged block keeps the best score
+fn add_block(selected: &mut Vec<Block>, mut block: Block) {
+    selected.retain(|other| {
+        let joins = other.file == block.file
+            && other.lines.0 <= block.lines.1 + 1
+            && block.lines.0 <= other.lines.1 + 1;
+        if joins {
+            block.lines = (
+                block.lines.0.min(other.lines.0),
+                block.lines.1.max(other.lines.1),
+            );
+            block.score = block.score.max(other.score);
+        }
+        !joins
+    });
+    selected.push(block);
+}
+
+/// The selected blocks by file, the file of the best block first, with their code
+fn bundle_files(
+    selected: &[Block],
+    root: &Path,
+    contents: &HashMap<PathBuf, String>,
+    symbols: &HashMap<&PathBuf, Vec<Symbol>>,
+) -> Vec<BundleFile> {
+    let mut by_file: Vec<(&PathBuf, Vec<&Block>)> = Vec::new();
+    for block in selected {
+        match by_file.iter_mut().find(|(file, _)| *file == &block.file) {
+            Some((_, blocks)) => blocks.push(block),
+            None => by_file.push((&block.file, vec![block])),
+        }
+    }
+    let best = |blocks: &[&Block]| blocks.iter().map(|b| b.score).fold(0.0, f64::max);
+    by_file.sort_by(|a, b| best(&b.1).total_cmp(&best(&a.1)).then(a.0.cmp(b.0)));
+
+    by_file
+        .into_iter()
+        .map(|(file, mut blocks)| {
+            blocks.sort_by_key(|block| block.lines);
+            let extension = file.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+            let lines: Vec<&str> = contents[file].lines().collect();
+            let file_symbols = symbols.get(file).map_or(&[][..], Vec::as_slice);
+            BundleFile {
+                path: file
+                    .strip_prefix(root)
+                    .unwrap_or(file)
+                    .display()
+                    .to_string(),
+                language: language_name(extension),
+                blocks: blocks
+                    .into_iter()
+                    .map(|block| {
+                        let (start, end) = block.lines;
+                        BundleBlock {
+                            lines: block.lines,
+                            symbols: block_symbols(file_symbols, block.lines),
+                            score: block.score,
+                            code: lines[(start - 1).min(lines.len())..end.min(lines.len())]
+                                .join("\n"),
+                        }
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// The symbols starting in a block, or else the innermost one around it
+fn block_symbols(symbols: &[Symbol], lines: (usize, usize)) -> Vec<String> {
+    let starting: Vec<String> = symbols
+        .iter()
+        .filter(|symbol| (lines.0..=lines.1).contains(&symbol.lines.0))
+        .map(|symbol| symbol.name.clone())
+        .collect();
+    if !starting.is_empty() {
+        return starting;
+    }
+    symbols
+        .iter()
+        .filter(|symbol| symbol.lines.0 <= lines.0 && lines.1 <= symbol.lines.1)
+        .min_by_key(|symbol| symbol.lines.1 - symbol.lines.0)
+        .map(|symbol| vec![symbol.name.clone()])
+        .unwrap_or_default()
+}
+
+/// The bundle as Markdown: the task, a map of the files and symbols included, then the
+/// code of each block
+fn render_bundle(task: &str, files: &[BundleFile]) -> String {
+    let mut out = format!("# Context: {task}\n\n## Files\n\n");
+    for file in files {
+        let blocks: Vec<String> = file
+            .blocks
+            .iter()
+            .map(|block| {
+                let range = format!("{}-{}", block.lines.0, block.lines.1);
+                match block.symbols.as_slice() {
+                    [] => range,
+                    symbols => format!("{range} ({})", symbols.join(", ")),
+                }
+            })
+            .collect();
+        out.push_str(&format!("- {}: {}\n", file.path, blocks.join("; ")));
+    }
+    for file in files {
+        for block in &file.blocks {
+            out.push_str(&format!(
+                "\n## {}:{}-{}\n\n```{}\n{}\n```\n",
+                file.path, block.lines.0, block.lines.1, file.language, block.code
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(file: &str, lines: (usize, usize), score: f64) -> Block {
+        Block {
+            file: PathBuf::from(file),
+            lines,
+            score,
+        }
+    }
+
+    #[test]
+    fn test_plan_query_drops_stop_words() {
+        assert_eq!(
+            plan_query("Fix how the session cache expires the entries, session TTL"),
+            "Fix session cache expires entries TTL"
+        );
+        assert_eq!(plan_query("the and"), "the and");
+    }
+
+    #[test]
+    fn test_fuse_rankings_adds_up_shared_blocks() {
+        let keyword = vec![block("a.rs", (1, 5), 0.0), block("b.rs", (1, 6), 0.0)];
+        let similar = vec![block("b.rs", (2, 8), 0.0)];
+        let fused = fuse_rankings(&[keyword, similar]);
+        assert_eq!(fused.len(), 2);
+        assert_eq!(fused[1].lines, (1, 8));
+        assert!(fused[1].score > fused[0].score);
+    }
+
+    #[test]
+    fn test_add_block_merges_overlapping_blocks() {
+        let mut selected = vec![block("a.rs", (10, 20), 0.5), block("b.rs", (1, 4), 0.1)];
+        add_block(&mut selected, block("a.rs", (21, 30), 0.7));
+        add_block(&mut selected, block("a.rs", (40, 50), 0.2));
+        assert_eq!(
+            selected,
+            vec![
+                block("b.rs", (1, 4), 0.1),
+                block("a.rs", (10, 30), 0.7),
+                block("a.rs", (40, 50), 0.2)
+            ]
+        );
+    }
+}
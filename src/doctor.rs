@@ -0,0 +1,444 @@
+//! `probe doctor`: check the environment probe runs in and print a fix for each
+//! problem found, so support issues start from the same facts.
+
+use anyhow::Result;
+use colored::*;
+use probe_code::language::factory::{get_language_impl, language_extension};
+use probe_code::search::cache::sessions_dir;
+use probe_code::search::file_index::{index_state, IndexState};
+use probe_code::search::project_config::{check_setting, CONFIG_KEYS, CONFIG_SECTIONS};
+use probe_code::search::stemming::{project_config_path, StemmerKind};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Extensions of one file type per supported language, for the grammar check
+const GRAMMAR_EXTENSIONS: &[(&str, &str)] = &[
+    ("rust", "rs"),
+    ("javascript", "js"),
+    ("typescript", "ts"),
+    ("tsx", "tsx"),
+    ("python", "py"),
+    ("go", "go"),
+    ("c", "c"),
+    ("cpp", "cpp"),
+    ("java", "java"),
+    ("ruby", "rb"),
+    ("php", "php"),
+    ("swift", "swift"),
+    ("csharp", "cs"),
+    ("html", "html"),
+    ("markdown", "md"),
+    ("yaml", "yaml"),
+];
+
+pub struct DoctorParams {
+    pub path: PathBuf,
+    pub format: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Warning,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check {
+            name,
+            status: Status::Error,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+pub fn handle_doctor(params: DoctorParams) -> Result<()> {
+    let checks = vec![
+        check_git(),
+        check_clipboard(),
+        check_color(),
+        check_config(&params.path),
+        check_sessions(&sessions_dir()),
+        check_grammars(),
+        check_index(&params.path),
+    ];
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        println!("Probe version: {}", probe_code::version::get_version());
+        println!();
+        for check in &checks {
+            let marker = match check.status {
+                Status::Ok => "✓".green(),
+                Status::Warning => "!".yellow(),
+                Status::Error => "✗".red(),
+            };
+            println!("{marker} {}: {}", check.name.bold(), check.detail);
+            if let Some(fix) = &check.fix {
+                println!("    {} {fix}", "fix:".cyan());
+            }
+        }
+    }
+
+    let errors = checks
+        .iter()
+        .filter(|check| check.status == Status::Error)
+        .count();
+    if errors > 0 {
+        anyhow::bail!("{errors} check(s) failed");
+    }
+    Ok(())
+}
+
+fn check_git() -> Check {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::ok(
+            "git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => Check::warning(
+            "git",
+            "git was not found on PATH",
+            "Install git; .gitignore rules still apply without it, but git-based features won't work",
+        ),
+    }
+}
+
+fn check_clipboard() -> Check {
+    match arboard::Clipboard::new() {
+        Ok(_) => Check::ok("clipboard", "available"),
+        Err(e) => Check::warning(
+            "clipboard",
+            format!("unavailable ({e})"),
+            "extract --from-clipboard/--to-clipboard need a desktop session (X11 or Wayland on Linux); pipe through stdin/stdout instead",
+        ),
+    }
+}
+
+fn check_color() -> Check {
+    if std::env::var_os("NO_COLOR").is_some() {
+        return Check::ok("color", "disabled by NO_COLOR");
+    }
+    if !atty::is(atty::Stream::Stdout) {
+        return Check::ok("color", "disabled (stdout is not a terminal)");
+    }
+    match std::env::var("TERM") {
+        Ok(term) if term == "dumb" => Check::warning(
+            "color",
+            "TERM=dumb, so output is not colored",
+            "Set TERM to your terminal type, e.g. TERM=xterm-256color",
+        ),
+        _ => Check::ok("color", "enabled"),
+    }
+}
+
+fn check_config(path: &Path) -> Check {
+    if let Ok(value) = std::env::var("PROBE_STEMMER") {
+        if let Err(e) = value.parse::<StemmerKind>() {
+            return Check::error(
+                "config",
+                format!("PROBE_STEMMER: {e}"),
+                "Fix or unset PROBE_STEMMER",
+            );
+        }
+    }
+    let Some(config) = project_config_path(path) else {
+        return Check::ok(
+            "config",
+            "no probe.toml or .probe/config.toml (defaults apply)",
+        );
+    };
+    let content = match fs::read_to_string(&config) {
+        Ok(content) => content,
+        Err(e) => {
+            return Check::error(
+                "config",
+                format!("{}: {e}", config.display()),
+                "Make the file readable or remove it",
+            )
+        }
+    };
+
+    let mut unknown = Vec::new();
+    let mut section = None;
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if !CONFIG_SECTIONS.contains(&name) {
+                unknown.push(line.to_string());
+            }
+            section = Some(name.to_string());
+            continue;
+        }
+        let location = format!("{}:{}", config.display(), index + 1);
+        let Some((key, value)) = line.split_once('=') else {
+            return Check::error(
+                "config",
+                format!("{location}: expected `key = value`"),
+                "Fix or remove the line",
+            );
+        };
+        let key = key.trim();
+        if section.as_deref() == Some("queries") {
+            let query = value.trim().trim_matches('"').replace("\\\"", "\"");
+            if let Some(problem) = saved_query_problem(&query) {
+                return Check::error(
+                    "config",
+                    format!("{location}: saved query `{key}` {problem}"),
+                    "Fix the query syntax",
+                );
+            }
+            continue;
+        }
+        if section.as_deref() == Some("languages") {
+            let language = value.trim().trim_matches('"');
+            if language_extension(language).is_none() {
+                return Check::error(
+                    "config",
+                    format!("{location}: unknown language '{language}' for {key}"),
+                    "Map the extension to a supported language, e.g. rust or html",
+                );
+            }
+            continue;
+        }
+        if section.is_some() {
+            continue;
+        }
+        if !CONFIG_KEYS.contains(&key) {
+            unknown.push(key.to_string());
+            continue;
+        }
+        if let Err(e) = check_setting(key, value.trim()) {
+            return Check::error(
+                "config",
+                format!("{location}: {e}"),
+                format!("Fix the {key} value"),
+            );
+        }
+    }
+
+    if unknown.is_empty() {
+        Check::ok("config", format!("{} is valid", config.display()))
+    } else {
+        Check::warning(
+            "config",
+            format!(
+                "{} has unknown settings: {}",
+                config.display(),
+                unknown.join(", ")
+            ),
+            format!(
+                "Remove them or check their spelling; known settings: {}",
+                CONFIG_KEYS.join(", ")
+            ),
+        )
+    }
+}
+
+/// What makes a saved query unusable, if anything
+fn saved_query_problem(query: &str) -> Option<&'static str> {
+    if query.trim().is_empty() {
+        return Some("is empty");
+    }
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in query.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Some("has an unmatched `)`");
+        }
+    }
+    if in_quotes {
+        Some("has an unclosed quote")
+    } else if depth > 0 {
+        Some("has an unclosed `(`")
+    } else {
+        None
+    }
+}
+
+fn check_sessions(dir: &Path) -> Check {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Check::ok("sessions", "no session caches yet");
+    };
+    let mut files = 0;
+    let mut bytes = 0;
+    let mut corrupt = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        files += 1;
+        bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        let parses = fs::read(&path)
+            .ok()
+            .is_some_and(|data| serde_json::from_slice::<serde_json::Value>(&data).is_ok());
+        if !parses {
+            corrupt.push(path);
+        }
+    }
+
+    let writable = fs::metadata(dir).is_ok_and(|m| !m.permissions().readonly());
+    let summary = format!(
+        "{files} cache files ({:.1} MB) in {}",
+        bytes as f64 / 1_000_000.0,
+        dir.display()
+    );
+    if !writable {
+        Check::error(
+            "sessions",
+            format!("{summary}; the directory is read-only"),
+            format!(
+                "Make {} writable so --session can record results",
+                dir.display()
+            ),
+        )
+    } else if !corrupt.is_empty() {
+        Check::warning(
+            "sessions",
+            format!("{summary}; {} are unreadable", corrupt.len()),
+            format!("Delete them, e.g. rm {}", corrupt[0].display()),
+        )
+    } else {
+        Check::ok("sessions", summary)
+    }
+}
+
+/// Why the grammar for `extension` can't be loaded, if it can't
+fn grammar_error(extension: &str) -> Option<String> {
+    let Some(language) = get_language_impl(extension) else {
+        return Some("no language implementation".to_string());
+    };
+    tree_sitter::Parser::new()
+        .set_language(&language.get_tree_sitter_language())
+        .err()
+        .map(|e| e.to_string())
+}
+
+fn check_grammars() -> Check {
+    let failed: Vec<String> = GRAMMAR_EXTENSIONS
+        .iter()
+        .filter_map(|(name, extension)| grammar_error(extension).map(|e| format!("{name} ({e})")))
+        .collect();
+    if failed.is_empty() {
+        Check::ok(
+            "grammars",
+            format!("{} languages load", GRAMMAR_EXTENSIONS.len()),
+        )
+    } else {
+        Check::error(
+            "grammars",
+            format!("failed to load: {}", failed.join(", ")),
+            "Rebuild or reinstall probe with grammar versions its tree-sitter runtime supports",
+        )
+    }
+}
+
+fn check_index(path: &Path) -> Check {
+    match index_state(path, false, &[], false) {
+        IndexState::Live => Check::ok("index", "kept current by a running `probe index --watch`"),
+        IndexState::Missing => Check::ok(
+            "index",
+            "none (searches walk the tree; `probe index --watch` speeds up large repositories)",
+        ),
+        IndexState::Stale => Check::warning(
+            "index",
+            "an index exists but its watcher is not running, so searches walk the tree",
+            format!("Run `probe index --watch {}`", path.display()),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config() {
+        let temp = TempDir::new().unwrap();
+        let config = temp.path().join("probe.toml");
+
+        fs::write(&config, "# project settings\nstemmer = \"porter\"\n").unwrap();
+        assert_eq!(check_config(temp.path()).status, Status::Ok);
+
+        fs::write(&config, "stemmer = \"klingon\"\n").unwrap();
+        let check = check_config(temp.path());
+        assert_eq!(check.status, Status::Error);
+        assert!(check.detail.contains("probe.toml:1"), "{}", check.detail);
+
+        fs::write(&config, "stemer = \"porter\"\n").unwrap();
+        assert_eq!(check_config(temp.path()).status, Status::Warning);
+
+        fs::write(&config, "[queries]\nauth = \"login OR oauth\"\n").unwrap();
+        assert_eq!(check_config(temp.path()).status, Status::Ok);
+
+        fs::write(&config, "[queries]\nauth = \"(login OR\"\n").unwrap();
+        let check = check_config(temp.path());
+        assert_eq!(check.status, Status::Error);
+        assert!(check.detail.contains("`auth`"), "{}", check.detail);
+    }
+
+    #[test]
+    fn test_check_sessions_reports_corrupt_files() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a_1.json"), "{\"cache\": {}}").unwrap();
+        assert_eq!(check_sessions(temp.path()).status, Status::Ok);
+
+        fs::write(temp.path().join("b_2.json"), "{\"cache\": ").unwrap();
+        let check = check_sessions(temp.path());
+        assert_eq!(check.status, Status::Warning);
+        assert!(check.fix.unwrap().contains("b_2.json"));
+    }
+
+    #[test]
+    fn test_grammar_error() {
+        assert_eq!(grammar_error("rs"), None);
+        assert!(grammar_error("unknown").is_some());
+    }
+}
@@ -0,0 +1,118 @@
+//! `probe export`: write one record per symbol of a codebase, for building embedding
+//! databases or fine-tuning datasets.
+//!
+//! Symbols are the named functions, methods and types of `extract::symbols`, nested
+//! ones included: a method gets a record of its own and is part of its class's body
+//! too. Each record has the symbol's path, language, qualified name, signature, doc
+//! comment, body and token count.
+
+use anyhow::Result;
+use probe_code::extract::symbols::{file_symbols, language_name};
+use probe_code::search::file_list_cache::get_file_list_by_language;
+use probe_code::search::search_tokens::count_tokens;
+use probe_code::warnings;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+pub struct ExportParams<'a> {
+    pub path: &'a Path,
+    pub language: Option<&'a str>,
+    /// Leave out symbols with fewer tokens
+    pub min_tokens: usize,
+    /// Leave out symbols with more tokens
+    pub max_tokens: Option<usize>,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+/// A symbol and its code
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Snippet {
+    path: String,
+    language: &'static str,
+    /// Qualified with the symbols around it, as `Type.method`
+    symbol: String,
+    kind: String,
+    lines: (usize, usize),
+    /// The symbol as written up to its body, on one line
+    signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    doc_comment: Option<String>,
+    body: String,
+    tokens: usize,
+}
+
+pub fn handle_export(params: ExportParams) -> Result<()> {
+    warnings::set_json(true);
+    let file_list = get_file_list_by_language(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.language,
+        params.no_gitignore,
+    )?;
+    let mut snippets: Vec<Snippet> = file_list
+        .files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let Ok(content) = fs::read_to_string(file) else {
+                return Vec::new();
+            };
+            export_snippets(&file.to_string_lossy(), &content, params.allow_tests)
+                .into_iter()
+                .filter(|snippet| {
+                    snippet.tokens >= params.min_tokens
+                        && params.max_tokens.is_none_or(|max| snippet.tokens <= max)
+                })
+                .collect()
+        })
+        .collect();
+    snippets.sort_by(|a, b| a.path.cmp(&b.path).then(a.lines.0.cmp(&b.lines.0)));
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&snippets)?);
+        return Ok(());
+    }
+    let mut stdout = io::stdout().lock();
+    for snippet in &snippets {
+        let written = serde_json::to_writer(&mut stdout, snippet)
+            .map_err(io::Error::from)
+            .and_then(|()| stdout.write_all(b"\n"));
+        match written {
+            // The reader has seen enough, as with `| head`
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            other => other?,
+        }
+    }
+    match stdout.flush() {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => Ok(other?),
+    }
+}
+
+/// The symbols of a file as records; none without a parser for it
+fn export_snippets(path: &str, content: &str, allow_tests: bool) -> Vec<Snippet> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    file_symbols(path, content, allow_tests)
+        .into_iter()
+        .map(|symbol| Snippet {
+            path: path.to_string(),
+            language: language_name(extension),
+            symbol: symbol.name,
+            kind: symbol.kind,
+            lines: symbol.lines,
+            signature: symbol.signature,
+            doc_comment: symbol.doc_comment,
+            tokens: count_tokens(&symbol.body),
+            body: symbol.body,
+        })
+        .collect()
+}
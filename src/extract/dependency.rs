@@ -0,0 +1,132 @@
+//! Functions for extracting symbols from third-party crates.
+//!
+//! `probe extract --dep tokio#spawn` locates the dependency's source in the cargo
+//! registry cache (at the version pinned in the project's Cargo.lock when there is
+//! one) and finds the file defining the symbol, so it can be extracted together with
+//! its doc comment as an offline docs lookup.
+
+use anyhow::{anyhow, Result};
+use probe_code::extract::file_paths::FilePathInfo;
+use probe_code::path_resolver::find_crate_source;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolve a `crate#symbol` dependency spec to the file and symbol to extract.
+///
+/// The symbol may be nested (`Runtime.spawn` or `Runtime::spawn`); the file is chosen
+/// by the definition of its first segment, preferring `pub` items and files closest
+/// to the crate root.
+pub fn resolve_dependency_symbol(spec: &str, project: &Path) -> Result<FilePathInfo> {
+    let (crate_name, symbol) = spec
+        .split_once('#')
+        .filter(|(name, symbol)| !name.is_empty() && !symbol.is_empty())
+        .ok_or_else(|| anyhow!("Invalid dependency spec '{spec}', expected CRATE#SYMBOL"))?;
+    let symbol = symbol.replace("::", ".");
+
+    let crate_dir = find_crate_source(project, crate_name).ok_or_else(|| {
+        anyhow!("Could not find the source of crate '{crate_name}' in the cargo registry cache")
+    })?;
+
+    let parts: Vec<&str> = symbol.split('.').collect();
+    let item = parts[0];
+    // Methods usually live in an `impl` block away from the type definition
+    let member_file = match parts.last() {
+        Some(member) if parts.len() > 1 => find_defining_file(&crate_dir, member, Some(item)),
+        _ => None,
+    };
+    let file = member_file
+        .or_else(|| find_defining_file(&crate_dir, item, None))
+        .ok_or_else(|| {
+            anyhow!(
+                "Could not find symbol '{item}' in crate '{crate_name}' ({})",
+                crate_dir.display()
+            )
+        })?;
+
+    Ok((file, None, None, Some(symbol), None))
+}
+
+/// Find the Rust file under `crate_dir/src` that defines `item`, optionally only
+/// among files with an `impl` block for `impl_for`
+fn find_defining_file(crate_dir: &Path, item: &str, impl_for: Option<&str>) -> Option<PathBuf> {
+    let definition = Regex::new(&format!(
+        r#"(?m)^\s*(pub(\([^)]*\))?\s+)?((async|const|unsafe|extern\s+"[^"]*")\s+)*(fn|struct|enum|union|trait|type|const|static|mod|macro_rules!)\s+{}\b"#,
+        regex::escape(item)
+    ))
+    .ok()?;
+    let impl_block = match impl_for {
+        Some(ty) => Some(Regex::new(&format!(r"\bimpl\b[^{{;]*\b{}\b", regex::escape(ty))).ok()?),
+        None => None,
+    };
+
+    let mut files = Vec::new();
+    collect_rust_files(&crate_dir.join("src"), &mut files);
+
+    // Rank: public definitions first, then shallower paths, then by name for determinism
+    files
+        .into_iter()
+        .filter_map(|file| {
+            let content = fs::read_to_string(&file).ok()?;
+            if impl_block.as_ref().is_some_and(|re| !re.is_match(&content)) {
+                return None;
+            }
+            let is_pub = definition
+                .captures_iter(&content)
+                .map(|caps| caps.get(1).is_some())
+                .reduce(|a, b| a || b)?;
+            Some((!is_pub, file.components().count(), file))
+        })
+        .min()
+        .map(|(_, _, file)| file)
+}
+
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_rust_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_defining_file_prefers_public_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("runtime/task")).unwrap();
+        fs::write(src.join("lib.rs"), "pub mod runtime;\n").unwrap();
+        fs::write(src.join("runtime/mod.rs"), "fn spawn_inner() {}\n").unwrap();
+        fs::write(
+            src.join("runtime/task/spawn.rs"),
+            "/// Spawns a task.\npub fn spawn() {}\n",
+        )
+        .unwrap();
+        fs::write(src.join("runtime/helper.rs"), "fn spawn() {}\n").unwrap();
+
+        assert_eq!(
+            find_defining_file(dir.path(), "spawn", None),
+            Some(src.join("runtime/task/spawn.rs"))
+        );
+        assert_eq!(find_defining_file(dir.path(), "missing", None), None);
+
+        fs::write(
+            src.join("runtime/builder.rs"),
+            "impl Runtime {\n    pub fn spawn(&self) {}\n}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_defining_file(dir.path(), "spawn", Some("Runtime")),
+            Some(src.join("runtime/builder.rs"))
+        );
+    }
+}
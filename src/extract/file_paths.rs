@@ -5,11 +5,15 @@
 
 use glob::glob;
 use ignore::WalkBuilder;
+use probe_code::extract::module_path;
 use probe_code::language::is_test_file;
 use probe_code::path_resolver::resolve_path;
+use probe_code::search::notebooks;
+use probe_code::warnings::{warn, WarningKind};
 use regex::Regex;
 use std::collections::HashSet;
 use std::path::PathBuf;
+use tracing::debug;
 
 /// Helper function to validate if a string is likely to be a file path
 /// and not a code construct like "locals.nodes" or "each.value"
@@ -98,7 +102,7 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
     let mut current_file_lines = HashSet::new();
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Split the text into lines
     let lines: Vec<&str> = text.lines().collect();
@@ -127,8 +131,8 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
             let is_test = is_test_file(file_path);
             if !is_ignored_by_gitignore(file_path) && (allow_tests || !is_test) {
                 if debug_mode {
-                    println!(
-                        "[DEBUG] Adding file with {} changed lines: {file_path:?}",
+                    debug!(
+                        "Adding file with {} changed lines: {file_path:?}",
                         changed_lines.len()
                     );
                 }
@@ -147,9 +151,9 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
                 processed_files.insert(file_path.to_string_lossy().to_string());
             } else if debug_mode {
                 if is_ignored_by_gitignore(file_path) {
-                    println!("[DEBUG] Skipping ignored file: {file_path:?}");
+                    debug!("Skipping ignored file: {file_path:?}");
                 } else if !allow_tests && is_test {
-                    println!("[DEBUG] Skipping test file: {file_path:?}");
+                    debug!("Skipping test file: {file_path:?}");
                 }
             }
         }
@@ -180,7 +184,7 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
             current_file_lines = HashSet::new(); // Reset lines for the new file
 
             if debug_mode {
-                println!("[DEBUG] Found file in git diff: {file_path:?}");
+                debug!("Found file in git diff: {file_path:?}");
             }
 
             i += 1;
@@ -197,9 +201,7 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
                     .unwrap_or(1);
 
                 if debug_mode {
-                    println!(
-                        "[DEBUG] Found hunk for file {file_path:?}: parsing for actual changed lines"
-                    );
+                    debug!("Found hunk for file {file_path:?}: parsing for actual changed lines");
                 }
 
                 // Move to the next line after the hunk header
@@ -220,7 +222,7 @@ pub fn extract_file_paths_from_git_diff(text: &str, allow_tests: bool) -> Vec<Fi
                     if hunk_line.starts_with('+') && !hunk_line.starts_with("+++") {
                         // This is an added/modified line in the new version
                         if debug_mode {
-                            println!("[DEBUG] Found changed line at {current_line}: {hunk_line}");
+                            debug!("Found changed line at {current_line}: {hunk_line}");
                         }
                         current_file_lines.insert(current_line);
                     }
@@ -277,7 +279,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
     let mut processed_paths = HashSet::new();
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Preprocess the text to handle paths wrapped in backticks, quotes, and markdown formatting
     // This replaces backticks, single quotes, double quotes, and markdown bold/italic with spaces
@@ -352,8 +354,8 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
     let text = &preprocessed_text;
 
     if debug_mode {
-        println!("[DEBUG] Original text length: {}", text.len());
-        println!("[DEBUG] Preprocessed text: {text:?}");
+        debug!("Original text length: {}", text.len());
+        debug!("Preprocessed text: {text:?}");
     }
 
     // First, try to match file paths with symbol references (e.g., file.rs#function_name)
@@ -384,9 +386,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                         results.push((entry, None, None, Some(symbol.to_string()), None));
                     } else if debug_mode {
                         if is_ignored_by_gitignore(&entry) {
-                            println!("DEBUG: Skipping ignored file: {entry:?}");
+                            debug!("Skipping ignored file: {entry:?}");
                         } else if !allow_tests && is_test {
-                            println!("DEBUG: Skipping test file: {entry:?}");
+                            debug!("Skipping test file: {entry:?}");
                         }
                     }
                 }
@@ -402,15 +404,15 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                         results.push((resolved_path, None, None, Some(symbol.to_string()), None));
                     } else if debug_mode {
                         if is_ignored_by_gitignore(&resolved_path) {
-                            println!("DEBUG: Skipping ignored file: {file_path:?}");
+                            debug!("Skipping ignored file: {file_path:?}");
                         } else if !allow_tests && is_test {
-                            println!("DEBUG: Skipping test file: {file_path:?}");
+                            debug!("Skipping test file: {file_path:?}");
                         }
                     }
                 }
                 Err(err) => {
                     if debug_mode {
-                        println!("DEBUG: Failed to resolve path '{file_path}': {err}");
+                        debug!("Failed to resolve path '{file_path}': {err}");
                     }
 
                     // Fall back to the original path
@@ -422,9 +424,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                         results.push((path, None, None, Some(symbol.to_string()), None));
                     } else if debug_mode {
                         if is_ignored_by_gitignore(&path) {
-                            println!("DEBUG: Skipping ignored file: {file_path:?}");
+                            debug!("Skipping ignored file: {file_path:?}");
                         } else if !allow_tests && is_test {
-                            println!("DEBUG: Skipping test file: {file_path:?}");
+                            debug!("Skipping test file: {file_path:?}");
                         }
                     }
                 }
@@ -464,9 +466,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             results.push((entry, Some(start), Some(end), None, None));
                         } else if debug_mode {
                             if is_ignored_by_gitignore(&entry) {
-                                println!("DEBUG: Skipping ignored file: {entry:?}");
+                                debug!("Skipping ignored file: {entry:?}");
                             } else if !allow_tests && is_test {
-                                println!("DEBUG: Skipping test file: {entry:?}");
+                                debug!("Skipping test file: {entry:?}");
                             }
                         }
                     }
@@ -481,15 +483,15 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             results.push((resolved_path, Some(start), Some(end), None, None));
                         } else if debug_mode {
                             if is_ignored_by_gitignore(&resolved_path) {
-                                println!("DEBUG: Skipping ignored file: {file_path:?}");
+                                debug!("Skipping ignored file: {file_path:?}");
                             } else if !allow_tests && is_test {
-                                println!("DEBUG: Skipping test file: {file_path:?}");
+                                debug!("Skipping test file: {file_path:?}");
                             }
                         }
                     }
                     Err(err) => {
                         if debug_mode {
-                            println!("DEBUG: Failed to resolve path '{file_path}': {err}");
+                            debug!("Failed to resolve path '{file_path}': {err}");
                         }
 
                         // Fall back to the original path
@@ -500,9 +502,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             results.push((path, Some(start), Some(end), None, None));
                         } else if debug_mode {
                             if is_ignored_by_gitignore(&path) {
-                                println!("DEBUG: Skipping ignored file: {file_path:?}");
+                                debug!("Skipping ignored file: {file_path:?}");
                             } else if !allow_tests && is_test {
-                                println!("DEBUG: Skipping test file: {file_path:?}");
+                                debug!("Skipping test file: {file_path:?}");
                             }
                         }
                     }
@@ -542,9 +544,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             results.push((entry, line_num, None, None, None));
                         } else if debug_mode {
                             if is_ignored_by_gitignore(&entry) {
-                                println!("DEBUG: Skipping ignored file: {entry:?}");
+                                debug!("Skipping ignored file: {entry:?}");
                             } else if !allow_tests && is_test {
-                                println!("DEBUG: Skipping test file: {entry:?}");
+                                debug!("Skipping test file: {entry:?}");
                             }
                         }
                     }
@@ -560,15 +562,15 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                         results.push((path, line_num, None, None, None));
                     } else if debug_mode {
                         if is_ignored_by_gitignore(&path) {
-                            println!("DEBUG: Skipping ignored file: {file_path:?}");
+                            debug!("Skipping ignored file: {file_path:?}");
                         } else if !allow_tests && is_test {
-                            println!("DEBUG: Skipping test file: {file_path:?}");
+                            debug!("Skipping test file: {file_path:?}");
                         }
                     }
                 }
                 Err(err) => {
                     if debug_mode {
-                        println!("DEBUG: Failed to resolve path '{file_path}': {err}");
+                        debug!("Failed to resolve path '{file_path}': {err}");
                     }
 
                     // Fall back to the original path
@@ -579,9 +581,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                         results.push((path, line_num, None, None, None));
                     } else if debug_mode {
                         if is_ignored_by_gitignore(&path) {
-                            println!("DEBUG: Skipping ignored file: {file_path:?}");
+                            debug!("Skipping ignored file: {file_path:?}");
                         } else if !allow_tests && is_test {
-                            println!("DEBUG: Skipping test file: {file_path:?}");
+                            debug!("Skipping test file: {file_path:?}");
                         }
                     }
                 }
@@ -606,7 +608,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
         let file_path = file_path.as_str();
 
         if debug_mode {
-            println!("DEBUG: simple_file_regex matched: '{file_path}'");
+            debug!("simple_file_regex matched: '{file_path}'");
         }
 
         // Skip if we've already processed this path with a symbol, line number, or range
@@ -626,9 +628,9 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                                 results.push((entry, None, None, None, None));
                             } else if debug_mode {
                                 if is_ignored_by_gitignore(&entry) {
-                                    println!("DEBUG: Skipping ignored file: {entry:?}");
+                                    debug!("Skipping ignored file: {entry:?}");
                                 } else if !allow_tests && is_test {
-                                    println!("DEBUG: Skipping test file: {entry:?}");
+                                    debug!("Skipping test file: {entry:?}");
                                 }
                             }
                         }
@@ -637,7 +639,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
             } else {
                 // Check if the path needs special resolution
                 if debug_mode {
-                    println!("DEBUG: Attempting to resolve path: '{file_path}'");
+                    debug!("Attempting to resolve path: '{file_path}'");
                 }
                 match resolve_path(file_path) {
                     Ok(path) => {
@@ -659,7 +661,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             // Apply our file path validation
                             if !is_likely_file_path(file_path) {
                                 if debug_mode {
-                                    println!("DEBUG: Skipping '{file_path}' - appears to be a code construct, not a file path");
+                                    debug!("Skipping '{file_path}' - appears to be a code construct, not a file path");
                                 }
                                 continue;
                             }
@@ -670,7 +672,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                                 && !file_path.contains('\\')
                             {
                                 if debug_mode {
-                                    println!("DEBUG: Skipping non-existent path that doesn't look like a file: {file_path:?}");
+                                    debug!("Skipping non-existent path that doesn't look like a file: {file_path:?}");
                                 }
                                 continue;
                             }
@@ -682,15 +684,15 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                             processed_paths.insert(file_path.to_string());
                         } else if debug_mode {
                             if is_ignored_by_gitignore(&path) {
-                                println!("DEBUG: Skipping ignored file: {file_path:?}");
+                                debug!("Skipping ignored file: {file_path:?}");
                             } else if !allow_tests && is_test {
-                                println!("DEBUG: Skipping test file: {file_path:?}");
+                                debug!("Skipping test file: {file_path:?}");
                             }
                         }
                     }
                     Err(err) => {
                         if debug_mode {
-                            println!("DEBUG: Failed to resolve path '{file_path}': {err}");
+                            debug!("Failed to resolve path '{file_path}': {err}");
                         }
 
                         // Fall back to the original path, but validate it first
@@ -706,16 +708,16 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
                                     processed_paths.insert(file_path.to_string());
                                 } else if debug_mode {
                                     if is_ignored_by_gitignore(&path) {
-                                        println!("DEBUG: Skipping ignored file: {file_path:?}");
+                                        debug!("Skipping ignored file: {file_path:?}");
                                     } else if !allow_tests && is_test {
-                                        println!("DEBUG: Skipping test file: {file_path:?}");
+                                        debug!("Skipping test file: {file_path:?}");
                                     }
                                 }
                             } else if debug_mode {
-                                println!("DEBUG: Skipping non-existent path that doesn't look like a file: {file_path:?}");
+                                debug!("Skipping non-existent path that doesn't look like a file: {file_path:?}");
                             }
                         } else if debug_mode {
-                            println!("DEBUG: Skipping '{file_path}' - appears to be a code construct, not a file path");
+                            debug!("Skipping '{file_path}' - appears to be a code construct, not a file path");
                         }
                     }
                 }
@@ -731,7 +733,7 @@ pub fn extract_file_paths_from_text(text: &str, allow_tests: bool) -> Vec<FilePa
 /// If allow_tests is false, test files will be filtered out.
 pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo> {
     let mut results = Vec::new();
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Remove any surrounding backticks or quotes, but not apostrophes within words
     // First check if the input starts and ends with the same quote character
@@ -748,6 +750,18 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
         input.trim_matches(|c| c == '`' || c == '"')
     };
 
+    // Notebook cells as search reports them, e.g. "train.ipynb:cell 4" or
+    // "train.ipynb:cell 4:10-20" for lines of the cell
+    if let Some(cell_results) = parse_notebook_cell(cleaned_input) {
+        return cell_results;
+    }
+
+    // Logical module paths (e.g. "crate::search::query", "com.example.service") resolve
+    // to the files defining that module
+    if let Some(module_results) = module_path::resolve_module_path(cleaned_input, allow_tests) {
+        return module_results;
+    }
+
     // Replace language-specific path separators with . for universal symbol access
     // Note: We only replace these in the symbol part (after #), not in file paths
     // Rust & C++ & Ruby: :: -> . (e.g., "std::vector::new" -> "std.vector.new")
@@ -787,8 +801,8 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                 }
             }
             Err(err) => {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    println!("DEBUG: Failed to resolve path '{file_part}': {err}");
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!("Failed to resolve path '{file_part}': {err}");
                 }
 
                 // Fall back to the original path, but validate it first
@@ -802,10 +816,12 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                             results.push((path, None, None, Some(symbol.to_string()), None));
                         }
                     } else if debug_mode {
-                        println!("DEBUG: Skipping non-existent path that doesn't look like a file: {file_part:?}");
+                        debug!("Skipping non-existent path that doesn't look like a file: {file_part:?}");
                     }
                 } else if debug_mode {
-                    println!("DEBUG: Skipping '{file_part}' - appears to be a code construct, not a file path");
+                    debug!(
+                        "Skipping '{file_part}' - appears to be a code construct, not a file path"
+                    );
                 }
             }
         }
@@ -845,7 +861,7 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                 }
                 Err(err) => {
                     if debug_mode {
-                        println!("DEBUG: Failed to resolve path '{file_part}': {err}");
+                        debug!("Failed to resolve path '{file_part}': {err}");
                     }
 
                     // Fall back to the original path
@@ -898,8 +914,8 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                             }
                         }
                         Err(err) => {
-                            if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                                println!("DEBUG: Failed to resolve path '{file_part}': {err}");
+                            if tracing::enabled!(tracing::Level::DEBUG) {
+                                debug!("Failed to resolve path '{file_part}': {err}");
                             }
 
                             // Fall back to the original path
@@ -947,8 +963,8 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                             }
                         }
                         Err(err) => {
-                            if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                                println!("DEBUG: Failed to resolve path '{file_part}': {err}");
+                            if tracing::enabled!(tracing::Level::DEBUG) {
+                                debug!("Failed to resolve path '{file_part}': {err}");
                             }
 
                             // Fall back to the original path
@@ -992,7 +1008,7 @@ pub fn parse_file_with_line(input: &str, allow_tests: bool) -> Vec<FilePathInfo>
                 Err(err) => {
                     // If resolution fails, log the error and try with the original path
                     if debug_mode {
-                        println!("DEBUG: Failed to resolve path '{cleaned_input}': {err}");
+                        debug!("Failed to resolve path '{cleaned_input}': {err}");
                     }
 
                     // Fall back to the original path
@@ -1015,6 +1031,98 @@ thread_local! {
 }
 
 /// Set custom ignore patterns for the current thread
+/// Resolve a `notebook.ipynb:cell N[:start[-end]]` target to the file of that cell;
+/// `None` when the input is not such a target
+fn parse_notebook_cell(input: &str) -> Option<Vec<FilePathInfo>> {
+    let cell_regex = Regex::new(r"^(.+\.ipynb):cell ?(\d+)(?::(\d+)(?:-(\d+))?)?$").unwrap();
+    let captures = cell_regex.captures(input)?;
+    let notebook = PathBuf::from(&captures[1]);
+    let number: usize = captures[2].parse().ok()?;
+    let start = captures.get(3).and_then(|m| m.as_str().parse().ok());
+    let end = captures
+        .get(4)
+        .and_then(|m| m.as_str().parse().ok())
+        .or(start);
+
+    let cells = match notebooks::notebook_cells(&notebook) {
+        Ok(cells) => cells,
+        Err(err) => {
+            warn(
+                WarningKind::Other,
+                Some(&notebook),
+                format!("Error reading notebook: {err:#}"),
+            );
+            return Some(Vec::new());
+        }
+    };
+    let cell = cells.into_iter().find(|cell| {
+        cell.file_stem()
+            .is_some_and(|stem| stem.to_string_lossy() == format!("cell-{number}"))
+    });
+    match cell {
+        Some(cell) => Some(vec![(cell, start, end, None, None)]),
+        None => {
+            warn(
+                WarningKind::Other,
+                Some(&notebook),
+                format!("{} has no code cell {number}", notebook.display()),
+            );
+            Some(Vec::new())
+        }
+    }
+}
+
+/// Extract from the code cells of notebooks rather than their JSON: a whole notebook
+/// becomes all of its code cells, and a symbol is looked up in the cells that mention
+/// it. Targets with line numbers are left alone, since they refer to the JSON.
+pub fn split_notebooks(file_paths: Vec<FilePathInfo>) -> Vec<FilePathInfo> {
+    let mut split = Vec::with_capacity(file_paths.len());
+    for target in file_paths {
+        let (path, start, _, symbol, lines) = &target;
+        if !notebooks::is_notebook(path) || start.is_some() || lines.is_some() {
+            split.push(target);
+            continue;
+        }
+        let cells = match notebooks::notebook_cells(path) {
+            Ok(cells) if !cells.is_empty() => cells,
+            Ok(_) => continue,
+            Err(err) => {
+                warn(
+                    WarningKind::Other,
+                    Some(path),
+                    format!("Error reading notebook: {err:#}"),
+                );
+                continue;
+            }
+        };
+        let cells = match symbol {
+            Some(symbol) => {
+                let name = symbol.rsplit('.').next().unwrap_or(symbol);
+                let mentioning: Vec<PathBuf> = cells
+                    .iter()
+                    .filter(|cell| {
+                        std::fs::read_to_string(cell).is_ok_and(|source| source.contains(name))
+                    })
+                    .cloned()
+                    .collect();
+                // With no cell mentioning it, the first cell reports the symbol as missing
+                if mentioning.is_empty() {
+                    cells.into_iter().take(1).collect()
+                } else {
+                    mentioning
+                }
+            }
+            None => cells,
+        };
+        split.extend(
+            cells
+                .into_iter()
+                .map(|cell| (cell, None, None, symbol.clone(), None)),
+        );
+    }
+    split
+}
+
 pub fn set_custom_ignores(patterns: &[String]) {
     CUSTOM_IGNORES.with(|cell| {
         let mut ignores = cell.borrow_mut();
@@ -1026,7 +1134,7 @@ pub fn set_custom_ignores(patterns: &[String]) {
 /// Check if a file should be ignored according to .gitignore rules
 fn is_ignored_by_gitignore(path: &PathBuf) -> bool {
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Simple check for common ignore patterns in the path
     let path_str = path.to_string_lossy();
@@ -1095,9 +1203,7 @@ fn is_ignored_by_gitignore(path: &PathBuf) -> bool {
     for pattern in &common_directory_patterns {
         if path_str.contains(pattern) {
             if debug_mode {
-                println!(
-                    "DEBUG: File {path:?} is ignored (contains directory pattern '{pattern}')"
-                );
+                debug!("File {path:?} is ignored (contains directory pattern '{pattern}')");
             }
             return true;
         }
@@ -1107,9 +1213,7 @@ fn is_ignored_by_gitignore(path: &PathBuf) -> bool {
     for pattern in &common_root_directory_patterns {
         if path_str.starts_with(pattern) {
             if debug_mode {
-                println!(
-                    "DEBUG: File {path:?} is ignored (starts with directory pattern '{pattern}')"
-                );
+                debug!("File {path:?} is ignored (starts with directory pattern '{pattern}')");
             }
             return true;
         }
@@ -1133,7 +1237,7 @@ fn is_ignored_by_gitignore(path: &PathBuf) -> bool {
         || path_lowercase.ends_with("\\__pycache__")
     {
         if debug_mode {
-            println!("DEBUG: File {path:?} is ignored (ends with ignore directory)");
+            debug!("File {path:?} is ignored (ends with ignore directory)");
         }
         return true;
     }
@@ -1142,7 +1246,7 @@ fn is_ignored_by_gitignore(path: &PathBuf) -> bool {
     for pattern in &custom_patterns {
         if path_str.contains(pattern) {
             if debug_mode {
-                println!("DEBUG: File {path:?} is ignored (contains custom pattern '{pattern}')");
+                debug!("File {path:?} is ignored (contains custom pattern '{pattern}')");
             }
             return true;
         }
@@ -1614,10 +1718,7 @@ Also: version 1.2.3, but not file.extension.that.is.too.long.to.be.real.
             .map(|(path, _, _, _, _)| path.to_string_lossy().to_string())
             .collect();
 
-        println!(
-            "DEBUG: Extracted {} files: {file_names:?}",
-            file_names.len()
-        );
+        debug!("Extracted {} files: {file_names:?}", file_names.len());
 
         // Should find these files
         assert!(
@@ -4,7 +4,9 @@
 //! in various formats (terminal, markdown, plain, json, xml, color).
 
 use anyhow::Result;
-use probe_code::models::SearchResult;
+use probe_code::messages::{text, text_with, Msg};
+use probe_code::models::{BlameInfo, SearchResult};
+use probe_code::search::search_output::{blame_note, owners_note};
 use probe_code::search::search_tokens::sum_tokens_with_deduplication;
 use serde::Serialize;
 use std::fmt::Write as FmtWrite;
@@ -53,6 +55,10 @@ fn format_extraction_internal(
                     #[serde(serialize_with = "serialize_lines_as_array")]
                     lines: (usize, usize),
                     node_type: &'a str,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    error: Option<String>,
+                    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+                    suggestions: &'a [String],
                 }
 
                 // Helper function to serialize lines as an array
@@ -76,6 +82,11 @@ fn format_extraction_internal(
                         file: &r.file,
                         lines: r.lines,
                         node_type: &r.node_type,
+                        error: symbol_not_found_message(r),
+                        suggestions: r
+                            .provenance
+                            .as_ref()
+                            .map_or(&[][..], |p| p.suggestions.as_slice()),
                     })
                     .collect();
 
@@ -87,6 +98,9 @@ fn format_extraction_internal(
                     },
                     "version": probe_code::version::get_version()
                 });
+                if probe_code::interrupt::is_interrupted() {
+                    wrapper["summary"]["interrupted"] = serde_json::json!(true);
+                }
 
                 // Add system prompt, user instructions, and original_input if provided
                 if let Some(prompt) = system_prompt {
@@ -113,9 +127,30 @@ fn format_extraction_internal(
                     node_type: &'a str,
                     code: &'a str,
                     #[serde(skip_serializing_if = "Option::is_none")]
+                    error: Option<String>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
                     symbol_signature: Option<&'a String>,
                     #[serde(skip_serializing_if = "Option::is_none")]
                     original_input: Option<&'a str>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    provenance: Option<JsonProvenance<'a>>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    blame: Option<&'a BlameInfo>,
+                    #[serde(skip_serializing_if = "Option::is_none")]
+                    owners: Option<&'a Vec<String>>,
+                }
+
+                // How the block was resolved, so callers can tell a fallback from a hit
+                #[derive(Serialize)]
+                struct JsonProvenance<'a> {
+                    requested: &'a str,
+                    strategy: &'a str,
+                    confidence: f64,
+                    clamped: bool,
+                    requested_lines: Option<[usize; 2]>,
+                    parse_error: Option<&'a str>,
+                    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+                    suggestions: &'a [String],
                 }
 
                 // Helper function to serialize lines as an array
@@ -140,11 +175,23 @@ fn format_extraction_internal(
                         lines: r.lines,
                         node_type: &r.node_type,
                         code: &r.code,
+                        error: symbol_not_found_message(r),
                         symbol_signature: r.symbol_signature.as_ref(),
                         // We no longer put original_input per result. If you truly need it,
                         // you can uncomment the line below, but it's typically at the root.
                         // original_input: r.original_input.as_deref(),
                         original_input: None,
+                        provenance: r.provenance.as_ref().map(|p| JsonProvenance {
+                            requested: &p.requested,
+                            strategy: &p.strategy,
+                            confidence: p.confidence,
+                            clamped: p.clamped_from.is_some(),
+                            requested_lines: p.clamped_from.map(|(start, end)| [start, end]),
+                            parse_error: p.parse_error.as_deref(),
+                            suggestions: &p.suggestions,
+                        }),
+                        blame: r.blame.as_ref(),
+                        owners: r.owners.as_ref(),
                     })
                     .collect();
 
@@ -178,6 +225,9 @@ fn format_extraction_internal(
                     },
                     "version": probe_code::version::get_version()
                 });
+                if probe_code::interrupt::is_interrupted() {
+                    wrapper["summary"]["interrupted"] = serde_json::json!(true);
+                }
 
                 // Add system prompt, user instructions, and original_input if provided
                 if let Some(input) = original_input {
@@ -212,6 +262,10 @@ fn format_extraction_internal(
                     writeln!(output, "  <result>")?;
                     writeln!(output, "    <file>{}</file>", escape_xml(&result.file))?;
 
+                    if write_xml_symbol_not_found(&mut output, result)? {
+                        continue;
+                    }
+
                     if result.node_type != "file" {
                         writeln!(output, "    <lines>")?;
                         writeln!(output, "      <start>{}</start>", result.lines.0)?;
@@ -232,6 +286,9 @@ fn format_extraction_internal(
                 // Summary
                 writeln!(output, "  <summary>")?;
                 writeln!(output, "    <count>{}</count>", results.len())?;
+                if probe_code::interrupt::is_interrupted() {
+                    writeln!(output, "    <interrupted>true</interrupted>")?;
+                }
                 writeln!(output, "  </summary>")?;
                 writeln!(
                     output,
@@ -244,6 +301,10 @@ fn format_extraction_internal(
                     writeln!(output, "  <result>")?;
                     writeln!(output, "    <file>{}</file>", escape_xml(&result.file))?;
 
+                    if write_xml_symbol_not_found(&mut output, result)? {
+                        continue;
+                    }
+
                     if result.node_type != "file" {
                         writeln!(output, "    <lines>")?;
                         writeln!(output, "      <start>{}</start>", result.lines.0)?;
@@ -264,6 +325,25 @@ fn format_extraction_internal(
                         )?;
                     }
 
+                    if let Some(blame) = &result.blame {
+                        writeln!(output, "    <blame>")?;
+                        writeln!(
+                            output,
+                            "      <author>{}</author>",
+                            escape_xml(&blame.author)
+                        )?;
+                        writeln!(output, "      <commit>{}</commit>", blame.commit)?;
+                        writeln!(output, "      <date>{}</date>", blame.date)?;
+                        writeln!(output, "    </blame>")?;
+                    }
+                    if let Some(owners) = &result.owners {
+                        writeln!(output, "    <owners>")?;
+                        for owner in owners {
+                            writeln!(output, "      <owner>{}</owner>", escape_xml(owner))?;
+                        }
+                        writeln!(output, "    </owners>")?;
+                    }
+
                     // Use CDATA to preserve formatting and special characters
                     writeln!(output, "    <code><![CDATA[{}]]></code>", &result.code)?;
 
@@ -300,6 +380,9 @@ fn format_extraction_internal(
                 };
 
                 writeln!(output, "    <total_tokens>{total_tokens}</total_tokens>")?;
+                if probe_code::interrupt::is_interrupted() {
+                    writeln!(output, "    <interrupted>true</interrupted>")?;
+                }
                 writeln!(output, "  </summary>")?;
                 writeln!(
                     output,
@@ -342,7 +425,7 @@ fn format_extraction_internal(
 
             // If there are no results
             if results.is_empty() {
-                writeln!(output, "{}", "No results found.".yellow().bold())?;
+                writeln!(output, "{}", text(Msg::NoResults).yellow().bold())?;
             } else {
                 // For each result, we either skip the code if is_dry_run, or include it otherwise.
                 for result in results {
@@ -353,6 +436,12 @@ fn format_extraction_internal(
                         writeln!(output, "File: {}", result.file.yellow())?;
                     }
 
+                    if let Some(message) = symbol_not_found_message(result) {
+                        writeln!(output, "{}", message.red())?;
+                        writeln!(output)?;
+                        continue;
+                    }
+
                     // Show lines if not a full file
                     if result.node_type != "file" {
                         if format == "markdown" {
@@ -371,6 +460,17 @@ fn format_extraction_internal(
                         }
                     }
 
+                    for note in [blame_note(result), owners_note(result)]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if format == "markdown" {
+                            writeln!(output, "### {note}")?;
+                        } else {
+                            writeln!(output, "{}", note.dimmed())?;
+                        }
+                    }
+
                     // In dry-run, we do NOT print the code or symbols
                     if !is_dry_run {
                         // Check if we should display symbols instead of code
@@ -505,8 +605,16 @@ fn format_extraction_internal(
                     } else {
                         sum_tokens_with_deduplication(&code_blocks)
                     };
-                    writeln!(output, "Total bytes returned: {total_bytes}")?;
-                    writeln!(output, "Total tokens returned: {total_tokens}")?;
+                    writeln!(
+                        output,
+                        "{}",
+                        text_with(Msg::TotalBytes, &[("bytes", &total_bytes)])
+                    )?;
+                    writeln!(
+                        output,
+                        "{}",
+                        text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+                    )?;
                 }
             }
         }
@@ -602,6 +710,51 @@ pub fn format_and_print_extraction_results(
 }
 
 /// Helper function to escape XML special characters
+/// "Symbol 'x' not found in FILE. Did you mean: ...?" for `symbol_not_found` results
+fn symbol_not_found_message(result: &SearchResult) -> Option<String> {
+    if result.node_type != "symbol_not_found" {
+        return None;
+    }
+    let provenance = result.provenance.as_ref()?;
+    let symbol = provenance
+        .requested
+        .rsplit_once('#')
+        .map_or(provenance.requested.as_str(), |(_, symbol)| symbol);
+    let mut message = format!("Symbol '{symbol}' not found in {}", result.file);
+    if !provenance.suggestions.is_empty() {
+        write!(
+            message,
+            ". Did you mean: {}?",
+            provenance.suggestions.join(", ")
+        )
+        .ok()?;
+    }
+    Some(message)
+}
+
+/// Write the error and suggestions of a `symbol_not_found` result and close its
+/// `<result>` element. Returns false, writing nothing, for any other result.
+fn write_xml_symbol_not_found(output: &mut String, result: &SearchResult) -> Result<bool> {
+    let Some(message) = symbol_not_found_message(result) else {
+        return Ok(false);
+    };
+    writeln!(output, "    <node_type>{}</node_type>", result.node_type)?;
+    writeln!(output, "    <error>{}</error>", escape_xml(&message))?;
+    if let Some(provenance) = &result.provenance {
+        writeln!(output, "    <suggestions>")?;
+        for suggestion in &provenance.suggestions {
+            writeln!(
+                output,
+                "      <suggestion>{}</suggestion>",
+                escape_xml(suggestion)
+            )?;
+        }
+        writeln!(output, "    </suggestions>")?;
+    }
+    writeln!(output, "  </result>")?;
+    Ok(true)
+}
+
 fn escape_xml(s: &str) -> String {
     s.replace("&", "&amp;")
         .replace("<", "&lt;")
@@ -646,6 +799,47 @@ pub fn get_language_from_extension(extension: &str) -> &'static str {
     }
 }
 
+/// Format a file's grouped symbols as the JSON document printed by `--format json`
+pub fn format_outline_json(
+    file: &Path,
+    grouped_symbols: &std::collections::HashMap<String, Vec<SearchResult>>,
+) -> Result<String> {
+    #[derive(Serialize)]
+    struct JsonOutline {
+        file: String,
+        symbols: std::collections::HashMap<String, Vec<JsonSymbol>>,
+    }
+
+    #[derive(Serialize)]
+    struct JsonSymbol {
+        name: Option<String>,
+        signature: Option<String>,
+        line: usize,
+    }
+
+    let mut json_symbols: std::collections::HashMap<String, Vec<JsonSymbol>> =
+        std::collections::HashMap::new();
+
+    for (node_type, symbols) in grouped_symbols {
+        let json_symbols_for_type: Vec<JsonSymbol> = symbols
+            .iter()
+            .map(|s| JsonSymbol {
+                name: extract_symbol_name(&s.node_type, &s.code),
+                signature: s.symbol_signature.clone(),
+                line: s.lines.0,
+            })
+            .collect();
+        json_symbols.insert(node_type.clone(), json_symbols_for_type);
+    }
+
+    let outline = JsonOutline {
+        file: file.to_string_lossy().to_string(),
+        symbols: json_symbols,
+    };
+
+    Ok(serde_json::to_string_pretty(&outline)?)
+}
+
 /// Format and print outline results
 ///
 /// This function formats symbol outline information for a file and prints it.
@@ -667,44 +861,9 @@ pub fn format_outline(
 ) -> Result<()> {
     match format {
         "json" => {
-            // JSON output
-            #[derive(Serialize)]
-            struct JsonOutline {
-                file: String,
-                symbols: std::collections::HashMap<String, Vec<JsonSymbol>>,
-            }
-
-            #[derive(Serialize)]
-            struct JsonSymbol {
-                name: Option<String>,
-                signature: Option<String>,
-                line: usize,
-            }
-
-            let mut json_symbols: std::collections::HashMap<String, Vec<JsonSymbol>> =
-                std::collections::HashMap::new();
-
-            for (node_type, symbols) in grouped_symbols {
-                let json_symbols_for_type: Vec<JsonSymbol> = symbols
-                    .iter()
-                    .map(|s| JsonSymbol {
-                        name: extract_symbol_name(&s.node_type, &s.code),
-                        signature: s.symbol_signature.clone(),
-                        line: s.lines.0,
-                    })
-                    .collect();
-                json_symbols.insert(node_type.clone(), json_symbols_for_type);
-            }
-
-            let outline = JsonOutline {
-                file: file.to_string_lossy().to_string(),
-                symbols: json_symbols,
-            };
-
-            let json_output = serde_json::to_string_pretty(&outline)?;
-            println!("{}", json_output);
+            println!("{}", format_outline_json(file, grouped_symbols)?);
         }
-        "plain" | _ => {
+        _ => {
             // Plain text output with grouping
             println!("{}", file.display());
             println!("{}", "=".repeat(file.to_string_lossy().len()));
@@ -721,7 +880,7 @@ pub fn format_outline(
 
                     // Sort symbols by line number
                     let mut sorted_symbols = symbols.clone();
-                    sorted_symbols.sort_by(|a, b| a.lines.0.cmp(&b.lines.0));
+                    sorted_symbols.sort_by_key(|a| a.lines.0);
 
                     for symbol in sorted_symbols {
                         let signature = symbol
@@ -773,7 +932,9 @@ fn extract_symbol_name(node_type: &str, code: &str) -> Option<String> {
         if let Some(start) = code.find("impl ") {
             let after_impl = &code[start + 5..];
             // Handle generic impls like `impl<T> Foo`
-            let mut end = after_impl.find(|c: char| c == '{' || c == '<' || c == '\n').unwrap_or(after_impl.len());
+            let mut end = after_impl
+                .find(['{', '<', '\n'])
+                .unwrap_or(after_impl.len());
             // Skip generic parameters
             if after_impl.starts_with('<') {
                 if let Some(gt) = after_impl.find('>') {
@@ -787,7 +948,9 @@ fn extract_symbol_name(node_type: &str, code: &str) -> Option<String> {
                         end = gt + 1 + new_end;
                     }
                 }
-            } else if let Some(new_end) = after_impl.find(|c: char| !c.is_alphanumeric() && c != '_') {
+            } else if let Some(new_end) =
+                after_impl.find(|c: char| !c.is_alphanumeric() && c != '_')
+            {
                 end = new_end;
             }
             return Some(after_impl[..end].trim().to_string());
@@ -803,7 +966,7 @@ fn extract_symbol_name(node_type: &str, code: &str) -> Option<String> {
             }
         }
         // For arrow functions and methods, try to find the identifier
-        if code.find(|c: char| c == '(' || c == '=').is_some() {
+        if code.find(['(', '=']).is_some() {
             // Not a named function, return None
         }
     }
@@ -831,7 +994,8 @@ fn get_category_header(node_type: &str) -> String {
         "impl_item" => "Impls".to_string(),
         "trait_item" => "Traits".to_string(),
         "enum_item" => "Enums".to_string(),
-        "macro_rules" => "Macros".to_string(),
+        "macro_definition" => "Macros".to_string(),
+        "macro_invocation" => "Macro Items".to_string(),
         "const_item" => "Constants".to_string(),
         "static_item" => "Statics".to_string(),
         "type_alias" => "Type Aliases".to_string(),
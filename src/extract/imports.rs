@@ -0,0 +1,317 @@
+//! The imports of a source file and the files they resolve to, for the import graph of
+//! `probe arch`.
+//!
+//! Relative imports resolve against the importing file: JavaScript and TypeScript `./x`
+//! and `../x` (adding an extension or an `index` file) and Python `.x`. Absolute Python
+//! imports resolve against the project root, and Rust `crate::` paths, Go import paths and
+//! Java packages through the module paths of `probe extract`. Imports of the standard
+//! library and third-party packages resolve to no file.
+
+use super::module_path::resolve_module_path_in;
+use probe_code::language::get_or_parse_tree_pooled;
+use std::path::{Component, Path, PathBuf};
+use tree_sitter::Node;
+
+/// Extensions tried for a JavaScript or TypeScript import without one
+const SCRIPT_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    /// The module as written: `./db/conn`, `crate::db::conn`, `app.db`, ...
+    pub spec: String,
+    pub line: usize,
+}
+
+/// The imports of a file in source order; empty for languages without import support.
+/// `path` picks the language by its extension and keys the parse tree cache.
+pub fn file_imports(path: &str, content: &str) -> Vec<Import> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    if !matches!(extension, "rs" | "py" | "go" | "java") && !is_script(extension) {
+        return Vec::new();
+    }
+    let Ok(tree) = get_or_parse_tree_pooled(path, content, extension) else {
+        return Vec::new();
+    };
+    let mut imports = Vec::new();
+    collect_imports(
+        tree.root_node(),
+        content.as_bytes(),
+        extension,
+        &mut imports,
+    );
+    imports
+}
+
+fn is_script(extension: &str) -> bool {
+    SCRIPT_EXTENSIONS.contains(&extension)
+}
+
+fn collect_imports(node: Node, source: &[u8], extension: &str, imports: &mut Vec<Import>) {
+    let text = |node: Node| node.utf8_text(source).unwrap_or("").to_string();
+    let line = node.start_position().row + 1;
+    let mut add = |spec: String| {
+        if !spec.is_empty() {
+            imports.push(Import { spec, line });
+        }
+    };
+
+    match (extension, node.kind()) {
+        ("rs", "use_declaration") => {
+            if let Some(argument) = node.child_by_field_name("argument") {
+                use_paths(&text(argument)).into_iter().for_each(add);
+            }
+            return;
+        }
+        ("py", "import_statement") => {
+            let mut cursor = node.walk();
+            for name in node.children_by_field_name("name", &mut cursor) {
+                let module = name.child_by_field_name("name").unwrap_or(name);
+                add(text(module));
+            }
+            return;
+        }
+        ("py", "import_from_statement") => {
+            if let Some(module) = node.child_by_field_name("module_name") {
+                add(text(module));
+            }
+            return;
+        }
+        ("go", "import_spec") => {
+            if let Some(path) = node.child_by_field_name("path") {
+                add(unquote(&text(path)));
+            }
+            return;
+        }
+        ("java", "import_declaration") => {
+            let mut cursor = node.walk();
+            let name = node
+                .named_children(&mut cursor)
+                .find(|child| child.kind().ends_with("identifier"));
+            if let Some(name) = name {
+                add(text(name));
+            }
+            return;
+        }
+        (ext, "import_statement" | "export_statement") if is_script(ext) => {
+            if let Some(source_node) = node.child_by_field_name("source") {
+                add(unquote(&text(source_node)));
+            }
+        }
+        (ext, "call_expression") if is_script(ext) => {
+            // `require("./x")` and `import("./x")`
+            let function = node.child_by_field_name("function").map(text);
+            if matches!(function.as_deref(), Some("require" | "import")) {
+                let argument = node
+                    .child_by_field_name("arguments")
+                    .and_then(|arguments| arguments.named_child(0))
+                    .filter(|argument| argument.kind() == "string");
+                if let Some(argument) = argument {
+                    add(unquote(&text(argument)));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_imports(child, source, extension, imports);
+    }
+}
+
+fn unquote(text: &str) -> String {
+    text.trim_matches(|c| c == '"' || c == '\'' || c == '`')
+        .to_string()
+}
+
+/// The paths a Rust `use` brings in, one per item of a `{...}` group:
+/// `crate::a::{b, c::D}` gives `crate::a::b` and `crate::a::c::D`
+fn use_paths(argument: &str) -> Vec<String> {
+    let argument: String = argument.split_whitespace().collect::<Vec<_>>().join(" ");
+    let argument = argument.split(" as ").next().unwrap_or("").trim();
+    let Some((prefix, group)) = argument.split_once("::{") else {
+        return vec![argument.trim_end_matches("::*").to_string()];
+    };
+    let group = group.strip_suffix('}').unwrap_or(group);
+
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in group.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&group[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&group[start..]);
+
+    items
+        .into_iter()
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .flat_map(|item| match item {
+            "self" | "*" => vec![prefix.to_string()],
+            _ => use_paths(&format!("{prefix}::{item}")),
+        })
+        .collect()
+}
+
+/// The files of the project an import of `file` refers to; empty for imports outside it
+pub fn resolve_import(root: &Path, file: &Path, spec: &str) -> Vec<PathBuf> {
+    let extension = file.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let dir = file.parent().unwrap_or(root);
+    if is_script(extension) {
+        if !spec.starts_with('.') {
+            return Vec::new();
+        }
+        let base = normalize(&dir.join(spec));
+        let mut candidates = vec![base.clone()];
+        for ext in SCRIPT_EXTENSIONS {
+            let mut with_extension = base.clone().into_os_string();
+            with_extension.push(format!(".{ext}"));
+            candidates.push(with_extension.into());
+        }
+        for ext in SCRIPT_EXTENSIONS {
+            candidates.push(base.join(format!("index.{ext}")));
+        }
+        return candidates
+            .into_iter()
+            .find(|candidate| candidate.is_file())
+            .into_iter()
+            .collect();
+    }
+    if extension == "py" {
+        let module = spec.trim_start_matches('.');
+        let dots = spec.len() - module.len();
+        let mut base = if dots == 0 {
+            root.to_path_buf()
+        } else {
+            dir.to_path_buf()
+        };
+        for _ in 1..dots {
+            base.pop();
+        }
+        for segment in module.split('.').filter(|s| !s.is_empty()) {
+            base.push(segment);
+        }
+        let mut module_file = base.clone().into_os_string();
+        module_file.push(".py");
+        // `from . import x` names the package itself
+        let module_file = (!module.is_empty()).then(|| PathBuf::from(module_file));
+        return module_file
+            .into_iter()
+            .chain([base.join("__init__.py")])
+            .find(|candidate| candidate.is_file())
+            .into_iter()
+            .collect();
+    }
+
+    let spec = spec.trim_end_matches(".*");
+    resolve_module_path_in(root, spec, true)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(path, ..)| normalize(&path))
+        .collect()
+}
+
+/// Remove `.` and `..` components without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn specs(path: &str, content: &str) -> Vec<(String, usize)> {
+        file_imports(path, content)
+            .into_iter()
+            .map(|import| (import.spec, import.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_file_imports() {
+        let rust = "use crate::db::{conn, models::User as U};\nuse std::fs;\n";
+        assert_eq!(
+            specs("imports_test.rs", rust),
+            [
+                ("crate::db::conn".to_string(), 1),
+                ("crate::db::models::User".to_string(), 1),
+                ("std::fs".to_string(), 2)
+            ]
+        );
+
+        let script = "import { a } from './db/conn';\nconst b = require(\"../util\");\n";
+        assert_eq!(
+            specs("imports_test.ts", script),
+            [("./db/conn".to_string(), 1), ("../util".to_string(), 2)]
+        );
+
+        let python = "import os.path, app.db as db\nfrom .models import User\n";
+        assert_eq!(
+            specs("imports_test.py", python),
+            [
+                ("os.path".to_string(), 1),
+                ("app.db".to_string(), 1),
+                (".models".to_string(), 2)
+            ]
+        );
+        assert!(specs("notes.txt", "import x").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "ui/view.ts",
+            "db/index.ts",
+            "app/db.py",
+            "app/models/__init__.py",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let view = root.join("ui/view.ts");
+        assert_eq!(
+            resolve_import(root, &view, "../db"),
+            [root.join("db/index.ts")]
+        );
+        assert!(resolve_import(root, &view, "react").is_empty());
+
+        let db = root.join("app/db.py");
+        assert_eq!(
+            resolve_import(root, &db, ".models"),
+            [root.join("app/models/__init__.py")]
+        );
+        assert_eq!(
+            resolve_import(root, &db, "app.db"),
+            [root.join("app/db.py")]
+        );
+        assert!(resolve_import(root, &db, "os.path").is_empty());
+    }
+}
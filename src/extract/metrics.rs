@@ -0,0 +1,323 @@
+//! Structural metrics of a source file and its symbols, for `probe metrics`: line
+//! counts, cyclomatic complexity and the calls each symbol makes.
+//!
+//! Lines are classified from the parse tree: a line with any token of code is code,
+//! else a line inside a comment is a comment line, else it's blank. Complexity is one
+//! plus the decision points (branches, loops, match arms, catch clauses, `&&` and `||`)
+//! of a symbol, not counting those of the symbols nested in it, so a method's branches
+//! count for the method and not for its class.
+
+use probe_code::extract::symbols::collect_symbols;
+use probe_code::language::factory::get_language_impl;
+use probe_code::language::get_or_parse_tree_pooled;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Node kinds that add a path through the code
+const DECISION_KINDS: &[&str] = &[
+    "if_expression",
+    "if_statement",
+    "elif_clause",
+    "else_if_clause",
+    "for_statement",
+    "for_expression",
+    "for_in_statement",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "while_statement",
+    "while_expression",
+    "loop_expression",
+    "do_statement",
+    "match_arm",
+    "case_clause",
+    "switch_case",
+    "switch_section",
+    "expression_case",
+    "type_case",
+    "catch_clause",
+    "except_clause",
+    "rescue",
+    "conditional_expression",
+    "ternary_expression",
+    "boolean_operator",
+];
+
+/// Node kinds of function calls
+const CALL_KINDS: &[&str] = &[
+    "call_expression",
+    "call",
+    "method_invocation",
+    "invocation_expression",
+    "function_call_expression",
+    "member_call_expression",
+];
+
+/// The lines of a file or symbol by what they hold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LineCounts {
+    pub lines: usize,
+    pub code: usize,
+    pub comment: usize,
+    pub blank: usize,
+}
+
+impl LineCounts {
+    /// Comment lines per line of code or comment, from 0 to 1
+    pub fn comment_ratio(&self) -> f64 {
+        match self.code + self.comment {
+            0 => 0.0,
+            total => self.comment as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolMetrics {
+    /// Qualified with the symbols around it, as `Type.method`
+    pub name: String,
+    pub kind: String,
+    pub lines: (usize, usize),
+    pub counts: LineCounts,
+    pub complexity: usize,
+    /// The functions it calls, each once in order of first call, as written without
+    /// arguments: `name`, or `Type.name` for a call through a type or module path
+    pub calls: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMetrics {
+    pub counts: LineCounts,
+    /// One plus all decision points of the file
+    pub complexity: usize,
+    pub symbols: Vec<SymbolMetrics>,
+}
+
+/// The metrics of a file; `None` without a parser for it. `path` picks the language by
+/// its extension and keys the parse tree cache.
+pub fn file_metrics(path: &str, content: &str, allow_tests: bool) -> Option<FileMetrics> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    let language_impl = get_language_impl(extension)?;
+    let tree = get_or_parse_tree_pooled(path, content, extension).ok()?;
+    let source = content.as_bytes();
+    let root = tree.root_node();
+
+    let lines = content.lines().count();
+    // The tree ends on the row after a final newline
+    let rows = lines.max(root.end_position().row + 1);
+    let mut code_rows = vec![false; rows];
+    let mut comment_rows = vec![false; rows];
+    mark_rows(root, &mut code_rows, &mut comment_rows);
+    let count_lines = |(start, end): (usize, usize)| {
+        let mut counts = LineCounts {
+            lines: end + 1 - start,
+            ..LineCounts::default()
+        };
+        for row in start - 1..end.min(rows) {
+            if code_rows[row] {
+                counts.code += 1;
+            } else if comment_rows[row] {
+                counts.comment += 1;
+            } else {
+                counts.blank += 1;
+            }
+        }
+        counts
+    };
+
+    let mut nodes = Vec::new();
+    collect_symbols(
+        root,
+        source,
+        language_impl.as_ref(),
+        allow_tests,
+        None,
+        &mut |node, name| nodes.push((node, name)),
+    );
+    let symbol_ids: HashSet<usize> = nodes.iter().map(|(node, _)| node.id()).collect();
+    let symbols = nodes
+        .into_iter()
+        .map(|(node, name)| {
+            let lines = (node.start_position().row + 1, node.end_position().row + 1);
+            let mut complexity = 1;
+            let mut calls = Vec::new();
+            visit_own_nodes(node, &symbol_ids, &mut |child| {
+                complexity += usize::from(is_decision(child, source));
+                if let Some(call) = callee(child, source) {
+                    if !calls.contains(&call) {
+                        calls.push(call);
+                    }
+                }
+            });
+            SymbolMetrics {
+                name,
+                kind: node.kind().to_string(),
+                lines,
+                counts: count_lines(lines),
+                complexity,
+                calls,
+            }
+        })
+        .collect();
+
+    let mut complexity = 1;
+    visit_own_nodes(root, &HashSet::new(), &mut |node| {
+        complexity += usize::from(is_decision(node, source));
+    });
+    Some(FileMetrics {
+        counts: count_lines((1, lines)),
+        complexity,
+        symbols,
+    })
+}
+
+/// Mark the rows holding tokens of code and the rows inside comments
+fn mark_rows(node: Node, code_rows: &mut [bool], comment_rows: &mut [bool]) {
+    let rows = node.start_position().row..=node.end_position().row;
+    if node.kind().contains("comment") {
+        rows.for_each(|row| comment_rows[row] = true);
+        return;
+    }
+    if node.child_count() == 0 {
+        if node.start_byte() < node.end_byte() {
+            rows.for_each(|row| code_rows[row] = true);
+        }
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        mark_rows(child, code_rows, comment_rows);
+    }
+}
+
+/// Call `visit` with every node under `node`, leaving out the symbols in `skip` and the
+/// nodes under them
+fn visit_own_nodes<'a>(node: Node<'a>, skip: &HashSet<usize>, visit: &mut dyn FnMut(Node<'a>)) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if skip.contains(&child.id()) {
+            continue;
+        }
+        visit(child);
+        visit_own_nodes(child, skip, visit);
+    }
+}
+
+fn is_decision(node: Node, source: &[u8]) -> bool {
+    if DECISION_KINDS.contains(&node.kind()) {
+        return true;
+    }
+    node.kind() == "binary_expression"
+        && node
+            .child_by_field_name("operator")
+            .and_then(|operator| operator.utf8_text(source).ok())
+            .is_some_and(|operator| matches!(operator, "&&" | "||" | "and" | "or"))
+}
+
+/// The function a call node calls: its name, qualified by the type or module of a
+/// path like `Type::name`; `None` for other nodes and calls of computed functions
+fn callee(node: Node, source: &[u8]) -> Option<String> {
+    if !CALL_KINDS.contains(&node.kind()) {
+        return None;
+    }
+    let mut target = ["function", "name", "method"]
+        .iter()
+        .find_map(|field| node.child_by_field_name(field))?;
+    loop {
+        let kind = target.kind();
+        if kind == "scoped_identifier" || kind == "qualified_identifier" {
+            // The last two segments of `module::Type::name`
+            let text = target.utf8_text(source).ok()?;
+            let segments: Vec<&str> = text.split("::").map(str::trim).collect();
+            return Some(segments[segments.len().saturating_sub(2)..].join("."));
+        }
+        if kind.ends_with("identifier") || kind == "name" {
+            return target.utf8_text(source).ok().map(str::to_string);
+        }
+        // A method, field or generic function: follow the part naming the function
+        target = ["field", "property", "name", "attribute", "function"]
+            .iter()
+            .find_map(|field| target.child_by_field_name(field))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_metrics() {
+        let content = "\
+// Parse the input
+fn parse(input: &str) -> usize {
+    // Empty input has no items
+    if input.is_empty() || input == \"-\" {
+        return 0;
+    }
+
+    for line in input.lines() {
+        helpers::check(line);
+    }
+    count(input)
+}
+
+impl Parser {
+    fn run(&self) {
+        self.parse_all();
+        Parser::new();
+    }
+}
+";
+        let metrics = file_metrics("lib.rs", content, false).unwrap();
+        assert_eq!(
+            metrics.counts,
+            LineCounts {
+                lines: 19,
+                code: 15,
+                comment: 2,
+                blank: 2
+            }
+        );
+        // The function's if, ||, for, and the file's base path
+        assert_eq!(metrics.complexity, 4);
+
+        let parse = &metrics.symbols[0];
+        assert_eq!(parse.name, "parse");
+        assert_eq!(parse.lines, (2, 12));
+        assert_eq!(parse.complexity, 4);
+        assert_eq!(parse.counts.comment, 1);
+        assert_eq!(parse.counts.blank, 1);
+        assert_eq!(parse.calls, ["is_empty", "lines", "helpers.check", "count"]);
+
+        let run = metrics
+            .symbols
+            .iter()
+            .find(|s| s.name == "Parser.run")
+            .unwrap();
+        assert_eq!(run.complexity, 1);
+        assert_eq!(run.calls, ["parse_all", "Parser.new"]);
+        // The impl doesn't count its method's calls
+        let parser = metrics.symbols.iter().find(|s| s.name == "Parser").unwrap();
+        assert!(parser.calls.is_empty());
+    }
+
+    #[test]
+    fn test_python_metrics() {
+        let content = "\
+def greet(name):
+    \"\"\"Say hello.\"\"\"
+    if name and name.strip():
+        print(name)
+";
+        let metrics = file_metrics("util.py", content, false).unwrap();
+        let greet = &metrics.symbols[0];
+        assert_eq!(greet.complexity, 3);
+        assert_eq!(greet.calls, ["strip", "print"]);
+        assert_eq!(greet.counts.code, 4);
+        assert!(file_metrics("notes.txt", "text", false).is_none());
+    }
+}
@@ -4,12 +4,17 @@
 //! and optional line numbers. When a line number is specified, it uses tree-sitter to find
 //! the closest suitable parent node (function, struct, class, etc.) for that line.
 
+mod dependency;
 mod file_paths;
 mod formatter;
+pub mod imports;
+pub mod metrics;
+mod module_path;
 mod outline_diff_formatter;
 mod processor;
 mod prompts;
 pub mod symbol_finder;
+pub mod symbols;
 
 // Re-export public functions
 #[allow(unused_imports)]
@@ -22,21 +27,29 @@ pub use formatter::{
     format_and_print_extraction_results, format_extraction_dry_run, format_extraction_results,
 };
 #[allow(unused_imports)]
-pub use processor::process_file_for_extraction;
+pub use formatter::{format_outline, format_outline_json};
 #[allow(unused_imports)]
-pub use processor::{extract_all_symbols_from_file, group_symbols_by_type};
+pub use processor::{
+    exclude_leading_attributes, include_preceding_doc_comments, process_file_for_extraction,
+};
 #[allow(unused_imports)]
-pub use formatter::format_outline;
+pub use processor::{extract_all_symbols_from_file, group_symbols_by_type, keep_public_results};
 #[allow(unused_imports)]
 pub use prompts::PromptTemplate;
 
 use anyhow::Result;
 use probe_code::extract::file_paths::{set_custom_ignores, FilePathInfo};
+use probe_code::interrupt;
 use probe_code::models::SearchResult;
+use probe_code::progress::Progress;
+use probe_code::search::filters::IncludeGlobs;
+use probe_code::search::notebooks;
+use probe_code::snapshot::{ResultSnapshot, SnapshotKind};
 use std::collections::HashSet;
 use std::io::Read;
 #[allow(unused_imports)]
 use std::path::PathBuf;
+use tracing::debug;
 
 /// Options for the extract command
 pub struct ExtractOptions {
@@ -68,6 +81,27 @@ pub struct ExtractOptions {
     pub instructions: Option<String>,
     /// Whether to ignore .gitignore files
     pub no_gitignore: bool,
+    /// Whether to extend extracted blocks upward to include their doc comments
+    pub with_doc_comments: bool,
+    /// Whether to drop attributes/decorators/annotations attached to extracted blocks
+    pub no_attributes: bool,
+    /// Dependency symbols to extract (`crate#symbol`), looked up in the cargo registry cache
+    pub deps: Vec<String>,
+    /// Whether to mask credentials in extracted code and echoed input
+    pub redact_secrets: bool,
+    /// Whether to split results spanning an entire file into chunks of consecutive lines
+    pub no_whole_files: bool,
+    /// File to save the extracted blocks to, for `probe show`
+    pub save_results: Option<String>,
+    /// Only extract from files matching one of these globs (.gitignore syntax, relative
+    /// to the current directory)
+    pub include: Vec<String>,
+    /// Whether to annotate each block with the last commit to change it
+    pub blame: bool,
+    /// Whether to annotate each block with the owners of its file from CODEOWNERS
+    pub owners: bool,
+    /// Whether to keep only public items, turning whole files into their public items
+    pub public_only: bool,
 }
 
 /// Handle the extract command
@@ -75,46 +109,45 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     use arboard::Clipboard;
     use colored::*;
 
+    probe_code::warnings::set_json(options.format == "json");
+
     // Print version at the start for text-based formats
     if options.format != "json" && options.format != "xml" {
         println!("Probe version: {}", crate::version::get_version());
     }
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        eprintln!("\n[DEBUG] ===== Extract Command Started =====");
-        eprintln!("[DEBUG] Files to process: {files:?}", files = options.files);
-        eprintln!(
-            "[DEBUG] Custom ignores: {custom_ignores:?}",
+        debug!("===== Extract Command Started =====");
+        debug!("Files to process: {files:?}", files = options.files);
+        debug!(
+            "Custom ignores: {custom_ignores:?}",
             custom_ignores = options.custom_ignores
         );
-        eprintln!(
-            "[DEBUG] Context lines: {context_lines}",
+        debug!(
+            "Context lines: {context_lines}",
             context_lines = options.context_lines
         );
-        eprintln!("[DEBUG] Output format: {format}", format = options.format);
-        eprintln!(
-            "[DEBUG] Read from clipboard: {from_clipboard}",
+        debug!("Output format: {format}", format = options.format);
+        debug!(
+            "Read from clipboard: {from_clipboard}",
             from_clipboard = options.from_clipboard
         );
-        eprintln!(
-            "[DEBUG] Write to clipboard: {to_clipboard}",
+        debug!(
+            "Write to clipboard: {to_clipboard}",
             to_clipboard = options.to_clipboard
         );
-        eprintln!("[DEBUG] Dry run: {dry_run}", dry_run = options.dry_run);
-        eprintln!("[DEBUG] Parse as git diff: {diff}", diff = options.diff);
-        eprintln!(
-            "[DEBUG] Allow tests: {allow_tests}",
+        debug!("Dry run: {dry_run}", dry_run = options.dry_run);
+        debug!("Parse as git diff: {diff}", diff = options.diff);
+        debug!(
+            "Allow tests: {allow_tests}",
             allow_tests = options.allow_tests
         );
-        eprintln!(
-            "[DEBUG] Prompt template: {prompt:?}",
-            prompt = options.prompt
-        );
-        eprintln!(
-            "[DEBUG] Instructions: {instructions:?}",
+        debug!("Prompt template: {prompt:?}", prompt = options.prompt);
+        debug!(
+            "Instructions: {instructions:?}",
             instructions = options.instructions
         );
     }
@@ -139,16 +172,16 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         if options.keep_input {
             original_input = Some(buffer.clone());
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Stored original clipboard input: {} bytes",
+                debug!(
+                    "Stored original clipboard input: {} bytes",
                     original_input.as_ref().map_or(0, |s| s.len())
                 );
             }
         }
 
         if debug_mode {
-            eprintln!(
-                "[DEBUG] Reading from clipboard, content length: {} bytes",
+            debug!(
+                "Reading from clipboard, content length: {} bytes",
                 buffer.len()
             );
         }
@@ -159,7 +192,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         if is_diff_format {
             // Parse as git diff format
             if debug_mode {
-                eprintln!("[DEBUG] Parsing clipboard content as git diff format");
+                debug!("Parsing clipboard content as git diff format");
             }
 
             // Store the diff buffer for outline-diff format (needs raw diff text)
@@ -174,13 +207,10 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         }
 
         if debug_mode {
-            eprintln!(
-                "[DEBUG] Extracted {} file paths from clipboard",
-                file_paths.len()
-            );
+            debug!("Extracted {} file paths from clipboard", file_paths.len());
             for (path, start, end, symbol, lines) in &file_paths {
-                eprintln!(
-                    "[DEBUG]   - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
+                debug!(
+                    "  - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
                     path,
                     start,
                     end,
@@ -223,18 +253,15 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         if options.keep_input {
             original_input = Some(buffer.clone());
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Stored original file input: {} bytes",
+                debug!(
+                    "Stored original file input: {} bytes",
                     original_input.as_ref().map_or(0, |s| s.len())
                 );
             }
         }
 
         if debug_mode {
-            eprintln!(
-                "[DEBUG] Reading from file, content length: {} bytes",
-                buffer.len()
-            );
+            debug!("Reading from file, content length: {} bytes", buffer.len());
         }
 
         // Auto-detect git diff format or use explicit flag
@@ -243,7 +270,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         if is_diff_format {
             // Parse as git diff format
             if debug_mode {
-                eprintln!("[DEBUG] Parsing file content as git diff format");
+                debug!("Parsing file content as git diff format");
             }
 
             // Store the diff buffer for outline-diff format (needs raw diff text)
@@ -258,13 +285,10 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         }
 
         if debug_mode {
-            eprintln!(
-                "[DEBUG] Extracted {} file paths from input file",
-                file_paths.len()
-            );
+            debug!("Extracted {} file paths from input file", file_paths.len());
             for (path, start, end, symbol, lines) in &file_paths {
-                eprintln!(
-                    "[DEBUG]   - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
+                debug!(
+                    "  - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
                     path,
                     start,
                     end,
@@ -285,7 +309,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             }
             return Ok(());
         }
-    } else if options.files.is_empty() {
+    } else if options.files.is_empty() && options.deps.is_empty() {
         // Check if stdin is available (not a terminal)
         let is_stdin_available = !atty::is(atty::Stream::Stdin);
 
@@ -301,18 +325,15 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             if options.keep_input {
                 original_input = Some(buffer.clone());
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Stored original stdin input: {} bytes",
+                    debug!(
+                        "Stored original stdin input: {} bytes",
                         original_input.as_ref().map_or(0, |s| s.len())
                     );
                 }
             }
 
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Reading from stdin, content length: {} bytes",
-                    buffer.len()
-                );
+                debug!("Reading from stdin, content length: {} bytes", buffer.len());
             }
 
             // Auto-detect git diff format or use explicit flag
@@ -321,7 +342,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             if is_diff_format {
                 // Parse as git diff format
                 if debug_mode {
-                    eprintln!("[DEBUG] Parsing stdin content as git diff format");
+                    debug!("Parsing stdin content as git diff format");
                 }
 
                 // Store the diff buffer for outline-diff format (needs raw diff text)
@@ -349,13 +370,10 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         }
 
         if debug_mode {
-            eprintln!(
-                "[DEBUG] Extracted {} file paths from stdin",
-                file_paths.len()
-            );
+            debug!("Extracted {} file paths from stdin", file_paths.len());
             for (path, start, end, symbol, lines) in &file_paths {
-                eprintln!(
-                    "[DEBUG]   - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
+                debug!(
+                    "  - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
                     path,
                     start,
                     end,
@@ -374,15 +392,15 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     } else {
         // Parse command-line arguments
         if debug_mode {
-            eprintln!("[DEBUG] Parsing command-line arguments");
+            debug!("Parsing command-line arguments");
         }
 
         // Store the original input if keep_input is true
         if options.keep_input {
             original_input = Some(options.files.join(" "));
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Stored original command-line input: {}",
+                debug!(
+                    "Stored original command-line input: {}",
                     original_input.as_ref().unwrap_or(&String::new())
                 );
             }
@@ -390,20 +408,16 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
 
         for file in &options.files {
             if debug_mode {
-                eprintln!("[DEBUG] Parsing file argument: {file}");
+                debug!("Parsing file argument: {file}");
             }
 
             let paths = file_paths::parse_file_with_line(file, options.allow_tests);
 
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Parsed {} paths from argument '{}'",
-                    paths.len(),
-                    file
-                );
+                debug!("Parsed {} paths from argument '{}'", paths.len(), file);
                 for (path, start, end, symbol, lines) in &paths {
-                    eprintln!(
-                        "[DEBUG]   - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
+                    debug!(
+                        "  - {:?} (lines: {:?}-{:?}, symbol: {:?}, specific lines: {:?})",
                         path,
                         start,
                         end,
@@ -417,6 +431,29 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         }
     }
 
+    // Notebooks are extracted from their code cells
+    file_paths = file_paths::split_notebooks(file_paths);
+
+    // Paths may be given relative to the current directory or absolute
+    let include_roots = [PathBuf::from("."), std::env::current_dir()?];
+    if let Some(globs) = IncludeGlobs::new(&include_roots, &options.include)? {
+        file_paths.retain(|(path, ..)| globs.matches(path));
+    }
+
+    // Dependency symbols are always extracted with their doc comments
+    let mut dependency_files = HashSet::new();
+    if !options.deps.is_empty() {
+        let project = std::env::current_dir()?;
+        for spec in &options.deps {
+            let target = dependency::resolve_dependency_symbol(spec, &project)?;
+            if debug_mode {
+                debug!("Resolved dependency '{spec}' to {:?}", target.0);
+            }
+            dependency_files.insert(target.0.clone());
+            file_paths.push(target);
+        }
+    }
+
     // Only print file information for non-JSON/XML formats
     if options.format != "json" && options.format != "xml" {
         println!("{text}", text = "Files to extract:".bold().green());
@@ -425,26 +462,29 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             if let (Some(start), Some(end)) = (start_line, end_line) {
                 println!(
                     "  {path} (lines {start}-{end})",
-                    path = path.display(),
+                    path = notebooks::display_path(path),
                     start = start,
                     end = end
                 );
             } else if let Some(line_num) = start_line {
                 println!(
                     "  {path} (line {line_num})",
-                    path = path.display(),
+                    path = notebooks::display_path(path),
                     line_num = line_num
                 );
             } else if let Some(sym) = symbol {
-                println!("  {path} (symbol: {sym})", path = path.display());
+                println!(
+                    "  {path} (symbol: {sym})",
+                    path = notebooks::display_path(path)
+                );
             } else if let Some(lines_set) = lines {
                 println!(
                     "  {path} (specific lines: {count} lines)",
-                    path = path.display(),
+                    path = notebooks::display_path(path),
                     count = lines_set.len()
                 );
             } else {
-                println!("  {path}", path = path.display());
+                println!("  {path}", path = notebooks::display_path(path));
             }
         }
 
@@ -469,15 +509,12 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     // Process prompt template and instructions if provided
     let system_prompt = if let Some(prompt_template) = &options.prompt {
         if debug_mode {
-            eprintln!("[DEBUG] Processing prompt template: {prompt_template:?}");
+            debug!("Processing prompt template: {prompt_template:?}");
         }
         match prompt_template.get_content() {
             Ok(content) => {
                 if debug_mode {
-                    println!(
-                        "[DEBUG] Loaded prompt template content ({} bytes)",
-                        content.len()
-                    );
+                    debug!("Loaded prompt template content ({} bytes)", content.len());
                 }
                 Some(content)
             }
@@ -487,7 +524,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                     text = format!("Error loading prompt template: {e}").red()
                 );
                 if debug_mode {
-                    eprintln!("[DEBUG] Error loading prompt template: {e}");
+                    debug!("Error loading prompt template: {e}");
                 }
                 None
             }
@@ -513,6 +550,8 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         specific_lines: Option<HashSet<usize>>,
         allow_tests: bool,
         context_lines: usize,
+        with_doc_comments: bool,
+        no_attributes: bool,
         debug_mode: bool,
         format: String,
 
@@ -529,6 +568,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         .into_iter()
         .map(
             |(path, start_line, end_line, symbol, specific_lines)| FileProcessingParams {
+                with_doc_comments: options.with_doc_comments || dependency_files.contains(&path),
                 path,
                 start_line,
                 end_line,
@@ -536,6 +576,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                 specific_lines,
                 allow_tests: options.allow_tests,
                 context_lines: options.context_lines,
+                no_attributes: options.no_attributes,
                 debug_mode,
                 format: options.format.clone(),
                 original_input: original_input.clone(),
@@ -546,27 +587,32 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         .collect();
 
     // Process files in parallel
+    let progress = Progress::new("Extracting", file_params.len());
     file_params.par_iter().for_each(|params| {
+        if interrupt::is_interrupted() {
+            return;
+        }
+        progress.inc();
         if params.debug_mode {
-            eprintln!("\n[DEBUG] Processing file: {:?}", params.path);
-            eprintln!("[DEBUG] Start line: {:?}", params.start_line);
-            eprintln!("[DEBUG] End line: {:?}", params.end_line);
-            eprintln!("[DEBUG] Symbol: {:?}", params.symbol);
-            eprintln!(
-                "[DEBUG] Specific lines: {:?}",
+            debug!("Processing file: {:?}", params.path);
+            debug!("Start line: {:?}", params.start_line);
+            debug!("End line: {:?}", params.end_line);
+            debug!("Symbol: {:?}", params.symbol);
+            debug!(
+                "Specific lines: {:?}",
                 params.specific_lines.as_ref().map(|l| l.len())
             );
 
             // Check if file exists
             if params.path.exists() {
-                eprintln!("[DEBUG] File exists: Yes");
+                debug!("File exists: Yes");
 
                 // Get file extension and language
                 if let Some(ext) = params.path.extension().and_then(|e| e.to_str()) {
                     let language = formatter::get_language_from_extension(ext);
-                    eprintln!("[DEBUG] File extension: {ext}");
-                    eprintln!(
-                        "[DEBUG] Detected language: {}",
+                    debug!("File extension: {ext}");
+                    debug!(
+                        "Detected language: {}",
                         if language.is_empty() {
                             "unknown"
                         } else {
@@ -574,17 +620,17 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                         }
                     );
                 } else {
-                    eprintln!("[DEBUG] File has no extension");
+                    debug!("File has no extension");
                 }
             } else {
-                eprintln!("[DEBUG] File exists: No");
+                debug!("File exists: No");
             }
         }
 
         // The allow_tests check is now handled in the file path extraction functions
         // We only need to check if this is a test file for debugging purposes
         if params.debug_mode && crate::language::is_test_file(&params.path) && !params.allow_tests {
-            eprintln!("[DEBUG] Test file detected: {:?}", params.path);
+            debug!("Test file detected: {:?}", params.path);
         }
 
         match processor::process_file_for_extraction(
@@ -597,14 +643,33 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             params.specific_lines.as_ref(),
             false, // symbols functionality removed
         ) {
-            Ok(result) => {
+            Ok(mut result) => {
+                if params.no_attributes {
+                    if let Err(e) = processor::exclude_leading_attributes(&params.path, &mut result)
+                    {
+                        if params.debug_mode {
+                            debug!("Failed to exclude attributes: {e}");
+                        }
+                    }
+                }
+
+                if params.with_doc_comments {
+                    if let Err(e) =
+                        processor::include_preceding_doc_comments(&params.path, &mut result)
+                    {
+                        if params.debug_mode {
+                            debug!("Failed to include doc comments: {e}");
+                        }
+                    }
+                }
+
                 if params.debug_mode {
-                    eprintln!("[DEBUG] Successfully extracted code from {:?}", params.path);
-                    eprintln!("[DEBUG] Extracted lines: {:?}", result.lines);
-                    eprintln!("[DEBUG] Node type: {}", result.node_type);
-                    eprintln!("[DEBUG] Code length: {} bytes", result.code.len());
-                    eprintln!(
-                        "[DEBUG] Estimated tokens: {}",
+                    debug!("Successfully extracted code from {:?}", params.path);
+                    debug!("Extracted lines: {:?}", result.lines);
+                    debug!("Node type: {}", result.node_type);
+                    debug!("Code length: {} bytes", result.code.len());
+                    debug!(
+                        "Estimated tokens: {}",
                         crate::search::search_tokens::count_tokens(&result.code)
                     );
                 }
@@ -620,7 +685,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                     e = e
                 );
                 if params.debug_mode {
-                    eprintln!("[DEBUG] Error: {error_msg}");
+                    debug!("Error: {error_msg}");
                 }
                 // Only print error messages for non-JSON/XML formats
                 if params.format != "json" && params.format != "xml" {
@@ -643,12 +708,19 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         .into_inner()
         .expect("Failed to get inner errors");
 
+    // Results in notebook cells are reported as `notebook.ipynb:cell N`
+    for result in &mut results {
+        if let Some(location) = notebooks::notebook_location(&result.file) {
+            if let Some(provenance) = result.provenance.as_mut() {
+                provenance.requested = provenance.requested.replacen(&result.file, &location, 1);
+            }
+            result.file = location;
+        }
+    }
+
     // Deduplicate results based on file path and line range
     if debug_mode {
-        eprintln!(
-            "[DEBUG] Before deduplication: {len} results",
-            len = results.len()
-        );
+        debug!("Before deduplication: {len} results", len = results.len());
     }
 
     // First, sort results by file path and then by line range size (largest first)
@@ -669,10 +741,10 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     });
 
     if debug_mode {
-        eprintln!("[DEBUG] Sorted results by file path and range size");
+        debug!("Sorted results by file path and range size");
         for (i, result) in results.iter().enumerate() {
-            eprintln!(
-                "[DEBUG] Result {}: {} (lines {}-{}, size: {})",
+            debug!(
+                "Result {}: {} (lines {}-{}, size: {})",
                 i,
                 result.file,
                 result.lines.0,
@@ -694,6 +766,10 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         }
 
         let result_i = &results[i];
+        // Missing-symbol entries have no lines; each one is reported on its own
+        if result_i.node_type == "symbol_not_found" {
+            continue;
+        }
         let file_i = &result_i.file;
         let start_i = result_i.lines.0;
         let end_i = result_i.lines.1;
@@ -703,7 +779,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
         if !seen_exact.insert(key) {
             to_retain[i] = false;
             if debug_mode {
-                eprintln!("[DEBUG] Removing exact duplicate: {file_i} (lines {start_i}-{end_i})");
+                debug!("Removing exact duplicate: {file_i} (lines {start_i}-{end_i})");
             }
             continue;
         }
@@ -715,6 +791,9 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             }
 
             let result_j = &results[j];
+            if result_j.node_type == "symbol_not_found" {
+                continue;
+            }
             let file_j = &result_j.file;
             let start_j = result_j.lines.0;
             let end_j = result_j.lines.1;
@@ -728,7 +807,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
             if start_j >= start_i && end_j <= end_i {
                 to_retain[j] = false;
                 if debug_mode {
-                    eprintln!("[DEBUG] Removing nested duplicate: {file_j} (lines {start_j}-{end_j}) contained within (lines {start_i}-{end_i})");
+                    debug!("Removing nested duplicate: {file_j} (lines {start_j}-{end_j}) contained within (lines {start_i}-{end_i})");
                 }
             }
         }
@@ -747,18 +826,53 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     results = new_results;
 
     if debug_mode {
-        eprintln!(
-            "[DEBUG] After deduplication: {len} results",
-            len = results.len()
-        );
+        debug!("After deduplication: {len} results", len = results.len());
     }
 
     if debug_mode {
-        eprintln!("\n[DEBUG] ===== Extraction Summary =====");
-        eprintln!("[DEBUG] Total results: {}", results.len());
-        eprintln!("[DEBUG] Total errors: {}", errors.len());
-        eprintln!("[DEBUG] Output format: {}", options.format);
-        eprintln!("[DEBUG] Dry run: {}", options.dry_run);
+        debug!("===== Extraction Summary =====");
+        debug!("Total results: {}", results.len());
+        debug!("Total errors: {}", errors.len());
+        debug!("Output format: {}", options.format);
+        debug!("Dry run: {}", options.dry_run);
+    }
+
+    // Before chunking, so whole files become their public items rather than chunks
+    if options.public_only {
+        results =
+            processor::keep_public_results(results, options.allow_tests, options.with_doc_comments);
+    }
+
+    // Chunked after deduplication, which would drop the chunks as nested in each other
+    if options.no_whole_files {
+        results = crate::search::file_chunking::chunk_whole_files(results, false);
+    }
+
+    if options.redact_secrets {
+        let mut redacted = crate::redact::redact_results(&mut results);
+        if let Some(input) = original_input.as_mut() {
+            let (masked, count) = crate::redact::redact_secrets(input);
+            if count > 0 {
+                *input = masked.into_owned();
+                redacted += count;
+            }
+        }
+        if redacted > 0 && options.format != "json" && options.format != "xml" {
+            eprintln!("Redacted {redacted} possible secrets from the output");
+        }
+    }
+
+    if options.blame {
+        crate::search::blame::annotate_blame(&mut results);
+    }
+    if options.owners {
+        crate::search::codeowners::annotate_owners(&mut results);
+    }
+
+    if let Some(path) = &options.save_results {
+        let targets = options.files.join(" ");
+        ResultSnapshot::new(SnapshotKind::Extract, &targets, &results)
+            .save(std::path::Path::new(path))?;
     }
 
     // Format the results
@@ -809,10 +923,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                 println!("{}", "Results copied to clipboard.".green().bold());
 
                 if debug_mode {
-                    println!(
-                        "[DEBUG] Wrote {} bytes to clipboard",
-                        formatted_output.len()
-                    );
+                    debug!("Wrote {} bytes to clipboard", formatted_output.len());
                 }
             } else {
                 // Print to stdout
@@ -825,7 +936,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
                 eprintln!("{}", format!("Error formatting results: {e}").red());
             }
             if debug_mode {
-                eprintln!("[DEBUG] Error formatting results: {e}");
+                debug!("Error formatting results: {e}");
             }
         }
     }
@@ -842,7 +953,7 @@ pub fn handle_extract(options: ExtractOptions) -> Result<()> {
     }
 
     if debug_mode {
-        eprintln!("[DEBUG] ===== Extract Command Completed =====");
+        debug!("===== Extract Command Completed =====");
     }
 
     Ok(())
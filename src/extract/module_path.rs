@@ -0,0 +1,425 @@
+//! Functions for resolving logical module paths to files.
+//!
+//! This module lets extraction targets be given by their logical name instead of a
+//! filesystem path:
+//! - Rust: `crate::search::query` (or `<crate_name>::...`), following `mod` declarations,
+//!   `#[path]` attributes and the `foo.rs` / `foo/mod.rs` directory conventions. Trailing
+//!   segments that are not modules are treated as an item (e.g. `crate::models::SearchResult`).
+//! - Go: `github.com/acme/app/internal/store`, resolved against the module path in `go.mod`.
+//! - Java: `com.example.service` (a package) or `com.example.service.UserService` (a class),
+//!   resolved against the usual source roots.
+
+use probe_code::extract::file_paths::FilePathInfo;
+use probe_code::language::is_test_file;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Source roots searched for Java packages, relative to the project root
+const JAVA_SOURCE_ROOTS: &[&str] = &["src/main/java", "src/test/java", "src", "."];
+
+/// Resolve a logical module path relative to the current directory.
+///
+/// Returns `None` when the input is not a module path (or is an existing filesystem path),
+/// so callers can fall back to regular file path parsing.
+pub fn resolve_module_path(input: &str, allow_tests: bool) -> Option<Vec<FilePathInfo>> {
+    let base = std::env::current_dir().ok()?;
+    let results = resolve_module_path_in(&base, input, allow_tests)?;
+
+    // Report paths relative to the current directory, like paths given on the command line
+    Some(
+        results
+            .into_iter()
+            .map(|(path, start, end, symbol, lines)| {
+                let path = path
+                    .strip_prefix(&base)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path);
+                (path, start, end, symbol, lines)
+            })
+            .collect(),
+    )
+}
+
+/// Resolve a logical module path relative to `base`. An optional `#symbol` suffix selects
+/// an item inside the resolved module.
+pub fn resolve_module_path_in(
+    base: &Path,
+    input: &str,
+    allow_tests: bool,
+) -> Option<Vec<FilePathInfo>> {
+    let (module_part, symbol) = match input.split_once('#') {
+        Some((module_part, symbol)) => (module_part, Some(symbol.replace("::", "."))),
+        None => (input, None),
+    };
+
+    if module_part.is_empty() || base.join(module_part).exists() {
+        return None;
+    }
+
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
+
+    let resolved = if module_part.contains("::") {
+        resolve_rust_module(base, module_part)
+            .map(|(file, item)| vec![(file, join_symbols(item, symbol.clone()))])
+    } else if module_part.contains('/') {
+        resolve_go_package(base, module_part, allow_tests)
+            .map(|files| attach_symbol(files, symbol.clone()))
+    } else if module_part.contains('.') {
+        resolve_java_package(base, module_part, allow_tests)
+            .map(|(files, item)| attach_symbol(files, join_symbols(item, symbol.clone())))
+    } else {
+        None
+    }?;
+
+    if debug_mode {
+        debug!("Resolved module path '{input}' to {resolved:?}");
+    }
+
+    let results: Vec<FilePathInfo> = resolved
+        .into_iter()
+        .filter(|(path, _)| allow_tests || !is_test_file(path))
+        .map(|(path, symbol)| (path, None, None, symbol, None))
+        .collect();
+
+    Some(results)
+}
+
+/// Combine a symbol resolved from the module path with an explicit `#symbol` suffix
+fn join_symbols(resolved: Option<String>, explicit: Option<String>) -> Option<String> {
+    match (resolved, explicit) {
+        (Some(resolved), Some(explicit)) => Some(format!("{resolved}.{explicit}")),
+        (resolved, explicit) => resolved.or(explicit),
+    }
+}
+
+/// Attach a symbol to resolved files; a symbol only makes sense for a single file
+fn attach_symbol(files: Vec<PathBuf>, symbol: Option<String>) -> Vec<(PathBuf, Option<String>)> {
+    let symbol = if files.len() == 1 { symbol } else { None };
+    files
+        .into_iter()
+        .map(|file| (file, symbol.clone()))
+        .collect()
+}
+
+fn is_identifier(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Find the nearest directory at or above `start` that contains `marker`
+fn find_upwards(start: &Path, marker: &str) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(marker).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Read the `[package] name` from a Cargo.toml
+fn cargo_package_name(manifest: &Path) -> Option<String> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let mut in_package = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                let value = value.trim_start().strip_prefix('=')?.trim();
+                return Some(value.trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+/// How a `mod` declaration was found in a parent module file
+enum ModDeclaration {
+    /// `mod name;`, with the `#[path]` attribute value if present
+    External(Option<String>),
+    /// `mod name { ... }`
+    Inline,
+}
+
+fn find_mod_declaration(source: &str, name: &str) -> Option<ModDeclaration> {
+    let pattern = format!(
+        r#"(?m)^[ \t]*(?:#\[path\s*=\s*"(?P<path>[^"]+)"\][ \t]*\r?\n?[ \t]*)?(?:#\[[^\]]*\][ \t]*\r?\n?[ \t]*)*(?:pub(?:\([^)]*\))?[ \t]+)?mod[ \t]+{}[ \t]*(?P<kind>[;{{])"#,
+        regex::escape(name)
+    );
+    let re = Regex::new(&pattern).ok()?;
+    let captures = re.captures(source)?;
+    match &captures["kind"] {
+        "{" => Some(ModDeclaration::Inline),
+        _ => Some(ModDeclaration::External(
+            captures.name("path").map(|m| m.as_str().to_string()),
+        )),
+    }
+}
+
+/// Resolve `crate::a::b` to the file defining module `b`, plus an item path for segments
+/// that live inside that file (inline modules or items)
+fn resolve_rust_module(base: &Path, module_path: &str) -> Option<(PathBuf, Option<String>)> {
+    let segments: Vec<&str> = module_path.split("::").collect();
+    if segments.len() < 2 || !segments.iter().all(|s| is_identifier(s)) {
+        return None;
+    }
+
+    let crate_dir = find_upwards(base, "Cargo.toml")?;
+    if segments[0] != "crate" {
+        let package = cargo_package_name(&crate_dir.join("Cargo.toml"))?;
+        if package.replace('-', "_") != segments[0] {
+            return None;
+        }
+    }
+
+    let src_dir = crate_dir.join("src");
+    let mut current_file = ["lib.rs", "main.rs"]
+        .iter()
+        .map(|root| src_dir.join(root))
+        .find(|root| root.is_file())?;
+    let mut module_dir = src_dir;
+
+    for (index, segment) in segments.iter().enumerate().skip(1) {
+        let source = fs::read_to_string(&current_file).ok()?;
+        let remaining = || Some(segments[index..].join("."));
+
+        match find_mod_declaration(&source, segment) {
+            // Inline module or an item: the rest of the path lives in this file
+            Some(ModDeclaration::Inline) | None => return Some((current_file, remaining())),
+            Some(ModDeclaration::External(path_attr)) => {
+                let next_file = match path_attr {
+                    Some(path_attr) => current_file.parent()?.join(path_attr),
+                    None => {
+                        let flat = module_dir.join(format!("{segment}.rs"));
+                        if flat.is_file() {
+                            flat
+                        } else {
+                            module_dir.join(segment).join("mod.rs")
+                        }
+                    }
+                };
+                if !next_file.is_file() {
+                    return None;
+                }
+
+                module_dir = if next_file.file_name().is_some_and(|f| f == "mod.rs") {
+                    next_file.parent()?.to_path_buf()
+                } else {
+                    module_dir.join(segment)
+                };
+                current_file = next_file;
+            }
+        }
+    }
+
+    Some((current_file, None))
+}
+
+/// List source files with the given extension directly inside a package directory
+fn package_files(dir: &Path, extension: &str, allow_tests: bool) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == extension))
+        .filter(|path| allow_tests || !is_test_file(path))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Resolve a Go import path inside the current module to the files of that package
+fn resolve_go_package(base: &Path, import_path: &str, allow_tests: bool) -> Option<Vec<PathBuf>> {
+    let module_root = find_upwards(base, "go.mod")?;
+    let go_mod = fs::read_to_string(module_root.join("go.mod")).ok()?;
+    let module_name = go_mod
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))?
+        .trim()
+        .trim_matches('"');
+
+    let relative = if import_path == module_name {
+        ""
+    } else {
+        import_path.strip_prefix(module_name)?.strip_prefix('/')?
+    };
+
+    let files = package_files(&module_root.join(relative), "go", allow_tests);
+    (!files.is_empty()).then_some(files)
+}
+
+/// Resolve a Java package or fully qualified class name, returning the files and an item
+/// path for trailing segments below the class
+fn resolve_java_package(
+    base: &Path,
+    qualified_name: &str,
+    allow_tests: bool,
+) -> Option<(Vec<PathBuf>, Option<String>)> {
+    let segments: Vec<&str> = qualified_name.split('.').collect();
+    if segments.len() < 2
+        || !segments.iter().all(|s| is_identifier(s))
+        || !segments[0].starts_with(|c: char| c.is_lowercase())
+    {
+        return None;
+    }
+
+    for root in JAVA_SOURCE_ROOTS.iter().map(|root| base.join(root)) {
+        // Whole package
+        let package_dir = root.join(segments.join("/"));
+        if package_dir.is_dir() {
+            let files = package_files(&package_dir, "java", allow_tests);
+            if !files.is_empty() {
+                return Some((files, None));
+            }
+        }
+
+        // Class, optionally followed by a member path
+        for split in (1..=segments.len()).rev() {
+            let class_file = root.join(format!("{}.java", segments[..split].join("/")));
+            if class_file.is_file() {
+                let member = (split < segments.len())
+                    .then(|| format!("{}.{}", segments[split - 1], segments[split..].join(".")));
+                return Some((vec![class_file], member));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_rust_module_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("Cargo.toml"),
+            "[package]\nname = \"my-crate\"\nversion = \"0.1.0\"\n",
+        );
+        write(
+            &root.join("src/lib.rs"),
+            "pub mod search;\n#[path = \"custom/io_impl.rs\"]\nmod io;\nmod inline { pub fn f() {} }\n",
+        );
+        write(&root.join("src/search/mod.rs"), "pub mod query;\n");
+        write(
+            &root.join("src/search/query.rs"),
+            "pub struct Query;\nimpl Query { pub fn parse() {} }\n",
+        );
+        write(&root.join("src/custom/io_impl.rs"), "pub fn read() {}\n");
+
+        let resolve = |input: &str| resolve_module_path_in(root, input, false).unwrap();
+
+        assert_eq!(
+            resolve("crate::search::query"),
+            vec![(root.join("src/search/query.rs"), None, None, None, None)]
+        );
+        assert_eq!(
+            resolve("my_crate::search"),
+            vec![(root.join("src/search/mod.rs"), None, None, None, None)]
+        );
+        assert_eq!(
+            resolve("crate::io"),
+            vec![(root.join("src/custom/io_impl.rs"), None, None, None, None)]
+        );
+        assert_eq!(
+            resolve("crate::search::query::Query"),
+            vec![(
+                root.join("src/search/query.rs"),
+                None,
+                None,
+                Some("Query".to_string()),
+                None
+            )]
+        );
+        assert_eq!(
+            resolve("crate::search::query#Query::parse"),
+            vec![(
+                root.join("src/search/query.rs"),
+                None,
+                None,
+                Some("Query.parse".to_string()),
+                None
+            )]
+        );
+        assert_eq!(
+            resolve("crate::inline::f"),
+            vec![(
+                root.join("src/lib.rs"),
+                None,
+                None,
+                Some("inline.f".to_string()),
+                None
+            )]
+        );
+
+        // Unknown crates and regular paths are left to file path parsing
+        assert!(resolve_module_path_in(root, "other_crate::search", false).is_none());
+        assert!(resolve_module_path_in(root, "src/lib.rs", false).is_none());
+    }
+
+    #[test]
+    fn test_resolve_go_and_java_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            &root.join("go.mod"),
+            "module github.com/acme/app\n\ngo 1.21\n",
+        );
+        write(&root.join("internal/store/store.go"), "package store\n");
+        write(&root.join("internal/store/cache.go"), "package store\n");
+        write(
+            &root.join("internal/store/store_test.go"),
+            "package store\n",
+        );
+
+        let results =
+            resolve_module_path_in(root, "github.com/acme/app/internal/store", false).unwrap();
+        let files: Vec<PathBuf> = results.into_iter().map(|(path, ..)| path).collect();
+        assert_eq!(
+            files,
+            vec![
+                root.join("internal/store/cache.go"),
+                root.join("internal/store/store.go")
+            ]
+        );
+        assert!(resolve_module_path_in(root, "github.com/other/app", false).is_none());
+
+        let java_root = root.join("src/main/java/com/example/service");
+        write(
+            &java_root.join("UserService.java"),
+            "class UserService {}\n",
+        );
+        write(
+            &java_root.join("OrderService.java"),
+            "class OrderService {}\n",
+        );
+
+        let results = resolve_module_path_in(root, "com.example.service", false).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let results =
+            resolve_module_path_in(root, "com.example.service.UserService.find", false).unwrap();
+        assert_eq!(
+            results,
+            vec![(
+                java_root.join("UserService.java"),
+                None,
+                None,
+                Some("UserService.find".to_string()),
+                None
+            )]
+        );
+        assert!(resolve_module_path_in(root, "main.rs", false).is_none());
+    }
+}
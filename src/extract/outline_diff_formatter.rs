@@ -16,6 +16,7 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Write as FmtWrite;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tracing::debug;
 
 /// Type of line in a diff
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -207,17 +208,17 @@ fn format_file_outline_diff(
             .unwrap_or_default();
 
         // Debug output
-        if std::env::var("DEBUG").unwrap_or_default() == "1" {
-            eprintln!(
-                "[DEBUG outline-diff] matched_lines (relative): {:?}",
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            debug!(
+                "outline-diff: matched_lines (relative): {:?}",
                 result.matched_lines
             );
-            eprintln!(
-                "[DEBUG outline-diff] matched_lines (absolute): {:?}",
+            debug!(
+                "outline-diff: matched_lines (absolute): {:?}",
                 matched_lines_absolute
             );
-            eprintln!(
-                "[DEBUG outline-diff] outline collected {} lines",
+            debug!(
+                "outline-diff: outline collected {} lines",
                 lines_with_types.len()
             );
         }
@@ -3,13 +3,16 @@
 //! This module provides functions for processing files and extracting code blocks
 //! based on file paths and optional line numbers.
 use anyhow::{Context, Result};
-use probe_code::extract::symbol_finder::find_symbol_in_file;
+use probe_code::extract::symbol_finder::{find_symbol_in_file, SymbolNotFound};
+use probe_code::language::common::find_attributed_start;
 use probe_code::language::factory::get_language_impl;
 use probe_code::language::parser::parse_file_for_code_blocks;
-use probe_code::models::SearchResult;
+use probe_code::models::{BlockCode, ExtractionProvenance, SearchResult};
+use probe_code::warnings::{warn, WarningKind};
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Process a single file and extract code blocks
 ///
@@ -22,6 +25,12 @@ use std::path::Path;
 ///
 /// This function returns a single SearchResult that includes either the merged AST code
 /// or the literal lines as a fallback.
+///
+/// The result's `provenance` records the requested target, which strategy produced
+/// the block, whether the requested lines were clamped and any parse error that
+/// forced a fallback. A symbol that does not exist in the file is not an error: the
+/// result is an empty `symbol_not_found` block whose provenance suggests the closest
+/// symbol names.
 #[allow(clippy::too_many_arguments)]
 pub fn process_file_for_extraction(
     path: &Path,
@@ -32,25 +41,204 @@ pub fn process_file_for_extraction(
     context_lines: usize,
     specific_lines: Option<&HashSet<usize>>,
     symbols: bool,
+) -> Result<SearchResult> {
+    let mut resolution = Resolution::default();
+    let mut result = match extract_block(
+        path,
+        start_line,
+        end_line,
+        symbol,
+        allow_tests,
+        context_lines,
+        specific_lines,
+        symbols,
+        &mut resolution,
+    ) {
+        Ok(result) => result,
+        Err(e) => match e.downcast::<SymbolNotFound>() {
+            Ok(missing) => {
+                let requested = describe_target(path, start_line, end_line, symbol, specific_lines);
+                return Ok(symbol_not_found_result(missing, requested));
+            }
+            Err(e) => return Err(e),
+        },
+    };
+
+    let strategy = match result.node_type.as_str() {
+        "text_search" => "text-search",
+        "file" => "file",
+        _ if symbol.is_some() => "symbol",
+        "range" | "specific_lines" => "literal-fallback",
+        "context" => "context",
+        _ => "ast-range",
+    };
+    let whole_file_requested = symbol.is_none()
+        && start_line.is_none()
+        && specific_lines.is_none_or(|lines| lines.is_empty());
+    result.provenance = Some(ExtractionProvenance {
+        requested: describe_target(path, start_line, end_line, symbol, specific_lines),
+        strategy: strategy.to_string(),
+        confidence: extraction_confidence(
+            strategy,
+            &result.node_type,
+            whole_file_requested,
+            &resolution,
+        ),
+        clamped_from: resolution.clamped_from,
+        parse_error: resolution.parse_error,
+        suggestions: Vec::new(),
+    });
+
+    Ok(result)
+}
+
+/// An empty `symbol_not_found` result whose provenance lists the closest symbols,
+/// so a missing symbol shows up in every output format instead of only on stderr
+fn symbol_not_found_result(missing: SymbolNotFound, requested: String) -> SearchResult {
+    SearchResult {
+        file: missing.file.to_string_lossy().to_string(),
+        lines: (0, 0),
+        node_type: "symbol_not_found".to_string(),
+        code: BlockCode::default(),
+        symbol_signature: None,
+        matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: Some(ExtractionProvenance {
+            requested,
+            strategy: "not-found".to_string(),
+            confidence: 0.0,
+            clamped_from: None,
+            parse_error: None,
+            suggestions: missing.suggestions,
+        }),
+        rank: None,
+        score: None,
+        tfidf_score: None,
+        bm25_score: None,
+        tfidf_rank: None,
+        bm25_rank: None,
+        new_score: None,
+        hybrid2_rank: None,
+        combined_score_rank: None,
+        file_unique_terms: None,
+        file_total_matches: None,
+        file_match_rank: None,
+        block_unique_terms: None,
+        block_total_matches: None,
+        parent_file_id: None,
+        block_id: None,
+        matched_keywords: None,
+        matched_lines: None,
+        tokenized_content: None,
+        parent_context: None,
+    }
+}
+
+/// Details of how `extract_block` got to its result, for `ExtractionProvenance`
+#[derive(Default)]
+struct Resolution {
+    clamped_from: Option<(usize, usize)>,
+    parse_error: Option<String>,
+}
+
+/// Node types of a whole parsed file, returned for a symbol only when nothing smaller
+/// was found
+const FILE_NODE_TYPES: &[&str] = &[
+    "source_file",
+    "program",
+    "module",
+    "translation_unit",
+    "compilation_unit",
+];
+
+/// How likely a result is the code that was asked for, from 0.0 to 1.0. An AST node
+/// found by name or for the requested lines is trusted most; text search, context and
+/// literal line fallbacks less, and less again when the file failed to parse or the
+/// requested lines had to be clamped to the file.
+fn extraction_confidence(
+    strategy: &str,
+    node_type: &str,
+    whole_file_requested: bool,
+    resolution: &Resolution,
+) -> f64 {
+    let mut confidence: f64 = match strategy {
+        // A symbol or lines were asked for, but the whole file came back
+        "symbol" if FILE_NODE_TYPES.contains(&node_type) => 0.3,
+        "symbol" => 1.0,
+        "file" if whole_file_requested => 1.0,
+        "file" => 0.3,
+        "ast-range" => 0.9,
+        "text-search" => 0.6,
+        "context" => 0.5,
+        "literal-fallback" => 0.4,
+        _ => 0.0,
+    };
+    if resolution.parse_error.is_some() {
+        confidence -= 0.2;
+    }
+    if resolution.clamped_from.is_some() {
+        confidence -= 0.3;
+    }
+    (confidence.max(0.0) * 100.0).round() / 100.0
+}
+
+/// The extraction target as the user would have written it
+fn describe_target(
+    path: &Path,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    symbol: Option<&str>,
+    specific_lines: Option<&HashSet<usize>>,
+) -> String {
+    let path = path.to_string_lossy();
+    match (symbol, start_line, end_line) {
+        (Some(symbol), _, _) => format!("{path}#{symbol}"),
+        (None, Some(start), Some(end)) => format!("{path}:{start}-{end}"),
+        (None, Some(line), None) => format!("{path}:{line}"),
+        _ => match specific_lines.filter(|lines| !lines.is_empty()) {
+            Some(lines) => {
+                let mut lines: Vec<usize> = lines.iter().copied().collect();
+                lines.sort_unstable();
+                let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                format!("{path}:{}", lines.join(","))
+            }
+            None => path.to_string(),
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extract_block(
+    path: &Path,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+    symbol: Option<&str>,
+    allow_tests: bool,
+    context_lines: usize,
+    specific_lines: Option<&HashSet<usize>>,
+    symbols: bool,
+    resolution: &mut Resolution,
 ) -> Result<SearchResult> {
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        eprintln!("\n[DEBUG] ===== Processing File for Extraction =====");
-        eprintln!("[DEBUG] File path: {path:?}");
-        eprintln!("[DEBUG] Start line: {start_line:?}");
-        eprintln!("[DEBUG] End line: {end_line:?}");
-        eprintln!("[DEBUG] Symbol: {symbol:?}");
-        eprintln!("[DEBUG] Allow tests: {allow_tests}");
-        eprintln!("[DEBUG] Context lines: {context_lines}");
-        eprintln!("[DEBUG] Specific lines: {specific_lines:?}");
+        debug!("===== Processing File for Extraction =====");
+        debug!("File path: {path:?}");
+        debug!("Start line: {start_line:?}");
+        debug!("End line: {end_line:?}");
+        debug!("Symbol: {symbol:?}");
+        debug!("Allow tests: {allow_tests}");
+        debug!("Context lines: {context_lines}");
+        debug!("Specific lines: {specific_lines:?}");
     }
 
     // Check if the file exists
     if !path.exists() {
         if debug_mode {
-            eprintln!("[DEBUG] Error: File does not exist");
+            debug!("Error: File does not exist");
         }
         return Err(anyhow::anyhow!("File does not exist: {:?}", path));
     }
@@ -60,15 +248,15 @@ pub fn process_file_for_extraction(
     let lines: Vec<&str> = content.lines().collect();
 
     if debug_mode {
-        eprintln!("[DEBUG] File read successfully");
-        eprintln!("[DEBUG] File size: {} bytes", content.len());
-        eprintln!("[DEBUG] Line count: {}", lines.len());
+        debug!("File read successfully");
+        debug!("File size: {} bytes", content.len());
+        debug!("Line count: {}", lines.len());
     }
 
     // If we have a symbol, find it in the file
     if let Some(symbol_name) = symbol {
         if debug_mode {
-            eprintln!("[DEBUG] Looking for symbol: {symbol_name}");
+            debug!("Looking for symbol: {symbol_name}");
         }
         // Find the symbol in the file
         return find_symbol_in_file(path, symbol_name, &content, allow_tests, context_lines);
@@ -77,7 +265,7 @@ pub fn process_file_for_extraction(
     // If we have a line range (start_line, end_line), gather AST blocks overlapping that range.
     if let (Some(start), Some(end)) = (start_line, end_line) {
         if debug_mode {
-            eprintln!("[DEBUG] Extracting line range: {start}-{end} (with AST merging)");
+            debug!("Extracting line range: {start}-{end} (with AST merging)");
         }
 
         // Clamp line numbers to valid ranges instead of failing
@@ -97,9 +285,15 @@ pub fn process_file_for_extraction(
             clamped_end = clamped_start;
         }
 
-        if debug_mode && (clamped_start != start || clamped_end != end) {
-            eprintln!(
-                "[DEBUG] Requested lines {start}-{end} out of range; clamping to {clamped_start}-{clamped_end}"
+        if clamped_start != start || clamped_end != end {
+            resolution.clamped_from = Some((start, end));
+            warn(
+                WarningKind::ClampedLines,
+                Some(path),
+                format!(
+                    "Requested lines {start}-{end} of {} out of range; clamping to {clamped_start}-{clamped_end}",
+                    path.display()
+                ),
             );
         }
 
@@ -146,8 +340,8 @@ pub fn process_file_for_extraction(
                 let merged_end = max_end + 1;
 
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Found {} overlapping AST blocks, merging into lines {}-{}",
+                    debug!(
+                        "Found {} overlapping AST blocks, merging into lines {}-{}",
                         blocks.len(),
                         merged_start,
                         merged_end
@@ -185,7 +379,7 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (merged_start, merged_end),
                     node_type: "merged_ast_range".to_string(),
-                    code: merged_content,
+                    code: merged_content.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path,
                         &content,
@@ -194,6 +388,10 @@ pub fn process_file_for_extraction(
                         symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -216,11 +414,19 @@ pub fn process_file_for_extraction(
                     parent_context: None,
                 })
             }
-            _ => {
+            other => {
+                if let Err(e) = other {
+                    warn(
+                        WarningKind::ParseFailed,
+                        Some(path),
+                        format!("Failed to parse {}: {e}", path.display()),
+                    );
+                    resolution.parse_error = Some(e.to_string());
+                }
                 // Fallback to literal extraction of lines [start..end]
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] No AST blocks found for the range {start}-{end}, falling back to literal lines"
+                    debug!(
+                        "No AST blocks found for the range {start}-{end}, falling back to literal lines"
                     );
                 }
                 let start_idx = start - 1;
@@ -238,11 +444,15 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (start, end),
                     node_type: "range".to_string(),
-                    code: range_content,
+                    code: range_content.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path, &content, start, end, symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -270,15 +480,16 @@ pub fn process_file_for_extraction(
     // Single line extraction
     else if let Some(line_num) = start_line {
         if debug_mode {
-            eprintln!("[DEBUG] Single line extraction requested: line {line_num}");
+            debug!("Single line extraction requested: line {line_num}");
         }
         // Clamp line number to valid range instead of failing
         let clamped_line_num = line_num.clamp(1, lines.len());
 
+        if clamped_line_num != line_num {
+            resolution.clamped_from = Some((line_num, line_num));
+        }
         if debug_mode && clamped_line_num != line_num {
-            eprintln!(
-                "[DEBUG] Requested line {line_num} out of bounds; clamping to {clamped_line_num}"
-            );
+            debug!("Requested line {line_num} out of bounds; clamping to {clamped_line_num}");
         }
 
         // Use the clamped value for the rest of the function
@@ -318,8 +529,8 @@ pub fn process_file_for_extraction(
                 let merged_end = max_end + 1;
 
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Found {} AST blocks for line {}, merging into lines {}-{}",
+                    debug!(
+                        "Found {} AST blocks for line {}, merging into lines {}-{}",
                         blocks.len(),
                         line_num,
                         merged_start,
@@ -340,7 +551,7 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (merged_start, merged_end),
                     node_type: "merged_ast_line".to_string(),
-                    code: merged_content,
+                    code: merged_content.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path,
                         &content,
@@ -349,6 +560,10 @@ pub fn process_file_for_extraction(
                         symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -371,12 +586,18 @@ pub fn process_file_for_extraction(
                     parent_context: None,
                 })
             }
-            _ => {
+            other => {
+                if let Err(e) = other {
+                    warn(
+                        WarningKind::ParseFailed,
+                        Some(path),
+                        format!("Failed to parse {}: {e}", path.display()),
+                    );
+                    resolution.parse_error = Some(e.to_string());
+                }
                 // If no AST block found, fallback to the line + context
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] No AST blocks found for line {line_num}, using context-based fallback"
-                    );
+                    debug!("No AST blocks found for line {line_num}, using context-based fallback");
                 }
 
                 // Extract context
@@ -405,11 +626,15 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (start_ctx, end_ctx),
                     node_type: "context".to_string(),
-                    code: context_code,
+                    code: context_code.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path, &content, start_ctx, end_ctx, symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -436,12 +661,12 @@ pub fn process_file_for_extraction(
     } else if let Some(lines_set) = specific_lines {
         // We have specific lines to extract
         if debug_mode {
-            eprintln!("[DEBUG] Extracting specific lines: {lines_set:?}");
+            debug!("Extracting specific lines: {lines_set:?}");
         }
 
         if lines_set.is_empty() {
             if debug_mode {
-                eprintln!("[DEBUG] No specific lines provided, returning entire file content");
+                debug!("No specific lines provided, returning entire file content");
             }
 
             // Tokenize the content
@@ -456,7 +681,7 @@ pub fn process_file_for_extraction(
                 file: path.to_string_lossy().to_string(),
                 lines: (1, lines.len()),
                 node_type: "file".to_string(),
-                code: content.clone(),
+                code: content.clone().into(),
                 symbol_signature: extract_symbol_signature_for_extract(
                     path,
                     &content,
@@ -465,6 +690,10 @@ pub fn process_file_for_extraction(
                     symbols,
                 ),
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -504,9 +733,14 @@ pub fn process_file_for_extraction(
             }
         }
 
+        if any_clamped {
+            let min = lines_set.iter().min().copied().unwrap_or(0);
+            let max = lines_set.iter().max().copied().unwrap_or(0);
+            resolution.clamped_from = Some((min, max));
+        }
         if debug_mode && any_clamped {
-            eprintln!(
-                "[DEBUG] Some requested lines were out of bounds; clamping to valid range 1-{}",
+            debug!(
+                "Some requested lines were out of bounds; clamping to valid range 1-{}",
                 lines.len()
             );
         }
@@ -540,8 +774,8 @@ pub fn process_file_for_extraction(
                 let merged_end = max_end + 1;
 
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Found {} AST blocks for specific lines, merging into lines {}-{}",
+                    debug!(
+                        "Found {} AST blocks for specific lines, merging into lines {}-{}",
                         blocks.len(),
                         merged_start,
                         merged_end
@@ -562,7 +796,7 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (merged_start, merged_end),
                     node_type: "merged_ast_specific_lines".to_string(),
-                    code: merged_content,
+                    code: merged_content.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path,
                         &content,
@@ -571,6 +805,10 @@ pub fn process_file_for_extraction(
                         symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -593,12 +831,18 @@ pub fn process_file_for_extraction(
                     parent_context: None,
                 })
             }
-            _ => {
+            other => {
+                if let Err(e) = other {
+                    warn(
+                        WarningKind::ParseFailed,
+                        Some(path),
+                        format!("Failed to parse {}: {e}", path.display()),
+                    );
+                    resolution.parse_error = Some(e.to_string());
+                }
                 // Fallback to literal extraction of the specific lines
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] No AST blocks found for specific lines, falling back to literal lines"
-                    );
+                    debug!("No AST blocks found for specific lines, falling back to literal lines");
                 }
 
                 // Get the min and max line numbers
@@ -642,11 +886,15 @@ pub fn process_file_for_extraction(
                     file: path.to_string_lossy().to_string(),
                     lines: (start, end),
                     node_type: "specific_lines".to_string(),
-                    code: range_content,
+                    code: range_content.into(),
                     symbol_signature: extract_symbol_signature_for_extract(
                         path, &content, start, end, symbols,
                     ),
                     matched_by_filename: None,
+                    duplicate_files: None,
+                    blame: None,
+                    owners: None,
+                    provenance: None,
                     rank: None,
                     score: None,
                     tfidf_score: None,
@@ -673,7 +921,7 @@ pub fn process_file_for_extraction(
     } else {
         // No line specified, return the entire file
         if debug_mode {
-            eprintln!("[DEBUG] No line or range specified, returning entire file content");
+            debug!("No line or range specified, returning entire file content");
         }
 
         // Tokenize the content
@@ -687,7 +935,7 @@ pub fn process_file_for_extraction(
             file: path.to_string_lossy().to_string(),
             lines: (1, lines.len()),
             node_type: "file".to_string(),
-            code: content.clone(),
+            code: content.clone().into(),
             symbol_signature: extract_symbol_signature_for_extract(
                 path,
                 &content,
@@ -696,6 +944,10 @@ pub fn process_file_for_extraction(
                 symbols,
             ),
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -733,7 +985,7 @@ fn extract_symbol_signature_for_extract(
         return None;
     }
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Get file extension
     let extension = file_extension(path);
@@ -742,8 +994,8 @@ fn extract_symbol_signature_for_extract(
     let language_impl = get_language_impl(extension)?;
 
     if debug_mode {
-        eprintln!(
-            "[DEBUG] Extracting symbol signature for lines {}-{} in {}",
+        debug!(
+            "Extracting symbol signature for lines {}-{} in {}",
             start_line,
             end_line,
             path.display()
@@ -752,7 +1004,7 @@ fn extract_symbol_signature_for_extract(
 
     // Try to parse the content
     if let Ok(mut parser) = probe_code::language::get_pooled_parser(extension) {
-        if let Some(tree) = parser.parse(content, None) {
+        if let Some(tree) = probe_code::language::timed_parse(&mut parser, extension, content) {
             // Convert line numbers to byte ranges
             let lines: Vec<&str> = content.lines().collect();
 
@@ -781,8 +1033,8 @@ fn extract_symbol_signature_for_extract(
             };
 
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Line range {}-{} maps to byte range {}-{}",
+                debug!(
+                    "Line range {}-{} maps to byte range {}-{}",
                     start_line, end_line, start_byte, end_byte
                 );
             }
@@ -804,14 +1056,14 @@ fn extract_symbol_signature_for_extract(
             signature
         } else {
             if debug_mode {
-                eprintln!("[DEBUG] Failed to parse content for symbol signature");
+                debug!("Failed to parse content for symbol signature");
             }
             probe_code::language::return_pooled_parser(extension, parser);
             None
         }
     } else {
         if debug_mode {
-            eprintln!("[DEBUG] Failed to get parser for symbol signature extraction");
+            debug!("Failed to get parser for symbol signature extraction");
         }
         None
     }
@@ -849,8 +1101,8 @@ fn find_node_and_extract_signature(
             || (node.start_byte() == start_byte && node.end_byte() == end_byte)
         {
             if debug_mode {
-                eprintln!(
-                    "[DEBUG] Checking node of type '{}' for symbol signature (range {}-{})",
+                debug!(
+                    "Checking node of type '{}' for symbol signature (range {}-{})",
                     node.kind(),
                     node.start_byte(),
                     node.end_byte()
@@ -860,16 +1112,16 @@ fn find_node_and_extract_signature(
             let signature = language_impl.get_symbol_signature(node, source);
             if let Some(ref sig) = signature {
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Found symbol signature for node type '{}': {}",
+                    debug!(
+                        "Found symbol signature for node type '{}': {}",
                         node.kind(),
                         sig
                     );
                 }
                 return signature;
             } else if debug_mode {
-                eprintln!(
-                    "[DEBUG] No symbol signature available for node type '{}'",
+                debug!(
+                    "No symbol signature available for node type '{}'",
                     node.kind()
                 );
             }
@@ -878,14 +1130,19 @@ fn find_node_and_extract_signature(
     None
 }
 
-/// Extract all root-level symbols from a file
+/// Extract all root-level symbols from a file, or with `public_only` only those that are
+/// part of its public API
 /// Returns a vector of SearchResults, one for each root-level symbol
 #[allow(dead_code)]
-pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<Vec<SearchResult>> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+pub fn extract_all_symbols_from_file(
+    path: &Path,
+    allow_tests: bool,
+    public_only: bool,
+) -> Result<Vec<SearchResult>> {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        eprintln!("[DEBUG] Extracting all symbols from file: {:?}", path);
+        debug!("Extracting all symbols from file: {:?}", path);
     }
 
     // Check if the file exists
@@ -902,26 +1159,26 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
         .ok_or_else(|| anyhow::anyhow!("Unsupported file extension: {}", extension))?;
 
     if debug_mode {
-        eprintln!("[DEBUG] File extension: {}, Language detected", extension);
+        debug!("File extension: {}, Language detected", extension);
     }
 
     // Parse the file with tree-sitter
     let mut results = Vec::new();
 
     if let Ok(mut parser) = probe_code::language::get_pooled_parser(extension) {
-        if let Some(tree) = parser.parse(&content, None) {
+        if let Some(tree) = probe_code::language::timed_parse(&mut parser, extension, &content) {
             let root_node = tree.root_node();
 
             if debug_mode {
-                eprintln!("[DEBUG] Successfully parsed file, traversing root-level nodes");
+                debug!("Successfully parsed file, traversing root-level nodes");
             }
 
             // Find all root-level acceptable parent nodes
             let mut cursor = root_node.walk();
             for child in root_node.children(&mut cursor) {
                 if debug_mode {
-                    eprintln!(
-                        "[DEBUG] Checking root-level node: {} at lines {}-{}",
+                    debug!(
+                        "Checking root-level node: {} at lines {}-{}",
                         child.kind(),
                         child.start_position().row + 1,
                         child.end_position().row + 1
@@ -931,7 +1188,14 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
                 // Skip test nodes if not allowed
                 if !allow_tests && language_impl.is_test_node(&child, content.as_bytes()) {
                     if debug_mode {
-                        eprintln!("[DEBUG] Skipping test node: {}", child.kind());
+                        debug!("Skipping test node: {}", child.kind());
+                    }
+                    continue;
+                }
+
+                if public_only && !language_impl.is_public(&child, content.as_bytes()) {
+                    if debug_mode {
+                        debug!("Skipping non-public node: {}", child.kind());
                     }
                     continue;
                 }
@@ -939,8 +1203,8 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
                 // Check if this is an acceptable parent (symbol we want to extract)
                 if language_impl.is_acceptable_parent(&child) {
                     if debug_mode {
-                        eprintln!(
-                            "[DEBUG] Found acceptable symbol: {} at lines {}-{}",
+                        debug!(
+                            "Found acceptable symbol: {} at lines {}-{}",
                             child.kind(),
                             child.start_position().row + 1,
                             child.end_position().row + 1
@@ -959,9 +1223,13 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
                             file: path.to_string_lossy().to_string(),
                             lines: (start_line, end_line),
                             node_type: child.kind().to_string(),
-                            code: String::new(), // Empty code since we only want the signature
+                            code: BlockCode::default(), // Empty code since we only want the signature
                             symbol_signature: Some(signature),
                             matched_by_filename: None,
+                            duplicate_files: None,
+                            blame: None,
+                            owners: None,
+                            provenance: None,
                             rank: None,
                             score: None,
                             tfidf_score: None,
@@ -987,23 +1255,23 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
                         results.push(result);
 
                         if debug_mode {
-                            eprintln!(
-                                "[DEBUG] Added symbol result: {} (lines {}-{})",
+                            debug!(
+                                "Added symbol result: {} (lines {}-{})",
                                 child.kind(),
                                 start_line,
                                 end_line
                             );
                         }
                     } else if debug_mode {
-                        eprintln!("[DEBUG] No signature available for node: {}", child.kind());
+                        debug!("No signature available for node: {}", child.kind());
                     }
                 } else if debug_mode {
-                    eprintln!("[DEBUG] Node not acceptable as symbol: {}", child.kind());
+                    debug!("Node not acceptable as symbol: {}", child.kind());
                 }
             }
         } else {
             if debug_mode {
-                eprintln!("[DEBUG] Failed to parse file with tree-sitter");
+                debug!("Failed to parse file with tree-sitter");
             }
             probe_code::language::return_pooled_parser(extension, parser);
             return Err(anyhow::anyhow!("Failed to parse file: {:?}", path));
@@ -1016,16 +1284,16 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
     }
 
     // Sort results by line number for consistent ordering
-    results.sort_by(|a, b| a.lines.0.cmp(&b.lines.0));
+    results.sort_by_key(|a| a.lines.0);
 
     if debug_mode {
-        eprintln!(
-            "[DEBUG] Found {} symbols in file (sorted by line number)",
+        debug!(
+            "Found {} symbols in file (sorted by line number)",
             results.len()
         );
         for result in &results {
-            eprintln!(
-                "[DEBUG]   {} at lines {}-{}",
+            debug!(
+                "  {} at lines {}-{}",
                 result.node_type, result.lines.0, result.lines.1
             );
         }
@@ -1034,6 +1302,270 @@ pub fn extract_all_symbols_from_file(path: &Path, allow_tests: bool) -> Result<V
     Ok(results)
 }
 
+/// Last row a node actually occupies. Line comments in some grammars include the trailing
+/// newline, which makes them end at column 0 of the following row.
+fn last_content_row(node: &tree_sitter::Node<'_>) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Find the child of `node` covering the given (0-based) row
+fn covering_child(node: tree_sitter::Node<'_>, row: usize) -> Option<tree_sitter::Node<'_>> {
+    let mut cursor = node.walk();
+    let child = node
+        .children(&mut cursor)
+        .find(|child| child.start_position().row <= row && last_content_row(child) >= row);
+    child
+}
+
+/// Find the outermost node that starts on the given (0-based) row
+fn outermost_node_starting_at(
+    root: tree_sitter::Node<'_>,
+    row: usize,
+) -> Option<tree_sitter::Node<'_>> {
+    let mut candidate = covering_child(root, row)?;
+
+    // Descend until we reach a node that starts exactly on the row
+    while candidate.start_position().row != row {
+        candidate = covering_child(candidate, row)?;
+    }
+
+    Some(candidate)
+}
+
+/// Read and parse a file so an extraction result's start line can be adjusted.
+/// Returns `None` when the language is unsupported or the result has no valid range.
+fn parse_for_block_adjustment(
+    path: &Path,
+    result: &SearchResult,
+) -> Result<Option<(String, tree_sitter::Tree)>> {
+    let extension = file_extension(path);
+    if get_language_impl(extension).is_none()
+        || result.lines.0 == 0
+        || result.lines.0 > result.lines.1
+    {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path).context(format!("Failed to read file: {path:?}"))?;
+
+    let mut parser = probe_code::language::get_pooled_parser(extension)
+        .map_err(|e| anyhow::anyhow!("Failed to get pooled parser: {}", e))?;
+    let tree = probe_code::language::timed_parse(&mut parser, extension, &content);
+    probe_code::language::return_pooled_parser(extension, parser);
+
+    Ok(tree.map(|tree| (content, tree)))
+}
+
+/// Move the start of an extraction result to a new (0-based) row, re-slicing its code
+fn rebase_result_start(
+    path: &Path,
+    content: &str,
+    result: &mut SearchResult,
+    new_start_row: usize,
+) {
+    let new_start = new_start_row + 1;
+    if new_start == result.lines.0 || new_start > result.lines.1 {
+        return;
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let end_idx = std::cmp::min(result.lines.1, lines.len());
+    let old_start = result.lines.0;
+
+    result.code = lines[new_start_row..end_idx].join("\n").into();
+    result.lines.0 = new_start;
+    if let Some(matched_lines) = result.matched_lines.as_mut() {
+        for line in matched_lines.iter_mut() {
+            *line = (*line + old_start).saturating_sub(new_start);
+        }
+    }
+
+    let filename = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    result.tokenized_content = Some(crate::ranking::preprocess_text_with_filename(
+        &result.code,
+        &filename,
+    ));
+}
+
+/// Extend an extraction result upward to include the doc comment block that precedes it.
+///
+/// Doc comments often sit outside the symbol's AST node (rustdoc, JSDoc, Javadoc, Go and
+/// Python `#` comments), so a plain symbol or line extraction cuts them off. Which comments
+/// count as documentation is decided by the language's `is_doc_comment`. Attributes and
+/// decorators between the comment and the item are kept as well. Results are left
+/// untouched when the language is unsupported or no adjacent doc comment exists.
+pub fn include_preceding_doc_comments(path: &Path, result: &mut SearchResult) -> Result<()> {
+    let Some((content, tree)) = parse_for_block_adjustment(path, result)? else {
+        return Ok(());
+    };
+    let Some(language_impl) = get_language_impl(file_extension(path)) else {
+        return Ok(());
+    };
+    let source = content.as_bytes();
+
+    let Some(item) = outermost_node_starting_at(tree.root_node(), result.lines.0 - 1) else {
+        return Ok(());
+    };
+
+    // Walk back over adjacent siblings: doc comments extend the block, attributes and
+    // decorators are only kept when a doc comment sits above them
+    let mut new_start_row = item.start_position().row;
+    let mut frontier_row = new_start_row;
+    let mut sibling = item.prev_sibling();
+    while let Some(node) = sibling {
+        // Stop at blank-line gaps
+        if last_content_row(&node) + 1 < frontier_row {
+            break;
+        }
+
+        if language_impl.is_doc_comment(&node, source) {
+            frontier_row = node.start_position().row;
+            new_start_row = frontier_row;
+        } else if language_impl.is_attribute_node(&node) {
+            frontier_row = node.start_position().row;
+        } else {
+            break;
+        }
+        sibling = node.prev_sibling();
+    }
+
+    if new_start_row + 1 < result.lines.0 {
+        rebase_result_start(path, &content, result, new_start_row);
+    }
+
+    Ok(())
+}
+
+/// Drop the attributes, decorators and annotations an extraction result starts with.
+///
+/// Block resolution includes attributes attached to an item by default; this undoes it
+/// for `--no-attributes` so the result starts at the item itself.
+pub fn exclude_leading_attributes(path: &Path, result: &mut SearchResult) -> Result<()> {
+    let Some((content, tree)) = parse_for_block_adjustment(path, result)? else {
+        return Ok(());
+    };
+    let Some(language_impl) = get_language_impl(file_extension(path)) else {
+        return Ok(());
+    };
+
+    let Some(mut node) = outermost_node_starting_at(tree.root_node(), result.lines.0 - 1) else {
+        return Ok(());
+    };
+
+    let mut new_start_row = None;
+    while language_impl.is_attribute_node(&node) {
+        let Some(next) = node.next_sibling() else {
+            break;
+        };
+        // Never move past the end of the extracted block
+        if next.start_position().row + 1 > result.lines.1 {
+            break;
+        }
+        new_start_row = Some(next.start_position().row);
+        node = next;
+    }
+
+    if let Some(row) = new_start_row {
+        rebase_result_start(path, &content, result, row);
+    }
+
+    Ok(())
+}
+
+/// Keep the public API among extraction results, for `--public-only`.
+///
+/// A whole file becomes its public root-level items with their attributes (and doc
+/// comments with `with_doc_comments`), and any other block is kept when the item it
+/// starts with is public. Files in languages without a parser are kept as they are.
+pub fn keep_public_results(
+    results: Vec<SearchResult>,
+    allow_tests: bool,
+    with_doc_comments: bool,
+) -> Vec<SearchResult> {
+    results
+        .into_iter()
+        .flat_map(|result| {
+            let path = PathBuf::from(&result.file);
+            if result.node_type == "file" {
+                match public_items(&path, allow_tests, with_doc_comments) {
+                    Ok(items) => items,
+                    Err(_) => vec![result],
+                }
+            } else if is_public_block(&path, &result) {
+                vec![result]
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+/// The public root-level items of a file, with their code
+fn public_items(
+    path: &Path,
+    allow_tests: bool,
+    with_doc_comments: bool,
+) -> Result<Vec<SearchResult>> {
+    let mut items = extract_all_symbols_from_file(path, allow_tests, true)?;
+    let Some(first) = items.first() else {
+        return Ok(items);
+    };
+    let Some((content, tree)) = parse_for_block_adjustment(path, first)? else {
+        return Ok(items);
+    };
+    let Some(language_impl) = get_language_impl(file_extension(path)) else {
+        return Ok(items);
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    for item in &mut items {
+        let (mut start, end) = item.lines;
+        // Attributes belong to the item, as in any other extraction
+        if let Some(node) = outermost_node_starting_at(tree.root_node(), start - 1) {
+            start = find_attributed_start(node, language_impl.as_ref())
+                .start_position()
+                .row
+                + 1;
+        }
+        let end = end.min(lines.len());
+        if start >= 1 && start <= end {
+            item.lines.0 = start;
+            item.code = lines[start - 1..end].join("\n").into();
+        }
+        if with_doc_comments {
+            include_preceding_doc_comments(path, item)?;
+        }
+    }
+    Ok(items)
+}
+
+fn is_public_block(path: &Path, result: &SearchResult) -> bool {
+    let Ok(Some((content, tree))) = parse_for_block_adjustment(path, result) else {
+        return true;
+    };
+    let Some(language_impl) = get_language_impl(file_extension(path)) else {
+        return true;
+    };
+    let source = content.as_bytes();
+    let Some(mut node) = outermost_node_starting_at(tree.root_node(), result.lines.0 - 1) else {
+        return true;
+    };
+    while language_impl.is_doc_comment(&node, source) || language_impl.is_attribute_node(&node) {
+        match node.next_named_sibling() {
+            Some(next) => node = next,
+            None => break,
+        }
+    }
+    language_impl.is_public(&node, source)
+}
+
 /// Helper to get file extension as a &str
 fn file_extension(path: &Path) -> &str {
     path.extension().and_then(|ext| ext.to_str()).unwrap_or("")
@@ -1051,15 +1583,42 @@ fn file_extension(path: &Path) -> &str {
 /// # Returns
 ///
 /// A HashMap mapping node_type names to vectors of SearchResults
-pub fn group_symbols_by_type(symbols: Vec<SearchResult>) -> std::collections::HashMap<String, Vec<SearchResult>> {
-    let mut grouped: std::collections::HashMap<String, Vec<SearchResult>> = std::collections::HashMap::new();
+pub fn group_symbols_by_type(
+    symbols: Vec<SearchResult>,
+) -> std::collections::HashMap<String, Vec<SearchResult>> {
+    let mut grouped: std::collections::HashMap<String, Vec<SearchResult>> =
+        std::collections::HashMap::new();
 
     for symbol in symbols {
         grouped
             .entry(symbol.node_type.clone())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(symbol);
     }
 
     grouped
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_file_in_place_of_symbol_confidence() {
+        let resolution = Resolution::default();
+        for node_type in FILE_NODE_TYPES {
+            assert_eq!(
+                extraction_confidence("symbol", node_type, false, &resolution),
+                0.3
+            );
+        }
+        assert_eq!(
+            extraction_confidence("file", "file", false, &resolution),
+            0.3
+        );
+        assert_eq!(
+            extraction_confidence("symbol", "function_item", false, &resolution),
+            1.0
+        );
+    }
+}
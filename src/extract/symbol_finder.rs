@@ -4,8 +4,11 @@
 //! in files using tree-sitter.
 
 use anyhow::Result;
+use probe_code::language::common::find_attributed_start;
 use probe_code::models::SearchResult;
-use std::path::Path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Find a symbol (function, struct, class, etc.) in a file by name
 ///
@@ -31,7 +34,7 @@ pub fn find_symbol_in_file(
     _allow_tests: bool,
     context_lines: usize,
 ) -> Result<SearchResult> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if the symbol contains a dot, indicating a nested symbol path
     let symbol_parts: Vec<&str> = symbol.split('.').collect();
@@ -41,23 +44,23 @@ pub fn find_symbol_in_file(
     // The find_symbol_node function already handles nested symbols
 
     if debug_mode {
-        println!("\n[DEBUG] ===== Symbol Search =====");
+        debug!("===== Symbol Search =====");
         if is_nested_symbol {
-            println!("[DEBUG] Searching for nested symbol '{symbol}' in file {path:?}");
-            println!(
-                "[DEBUG] Symbol parts: {symbol_parts:?} (parent: '{}', child: '{}')",
+            debug!("Searching for nested symbol '{symbol}' in file {path:?}");
+            debug!(
+                "Symbol parts: {symbol_parts:?} (parent: '{}', child: '{}')",
                 symbol_parts[0],
                 symbol_parts.last().unwrap_or(&"")
             );
         } else {
-            println!("[DEBUG] Searching for symbol '{symbol}' in file {path:?}");
+            debug!("Searching for symbol '{symbol}' in file {path:?}");
         }
-        println!(
-            "[DEBUG] Content size: {content_len} bytes",
+        debug!(
+            "Content size: {content_len} bytes",
             content_len = content.len()
         );
-        println!(
-            "[DEBUG] Line count: {line_count}",
+        debug!(
+            "Line count: {line_count}",
             line_count = content.lines().count()
         );
     }
@@ -66,7 +69,7 @@ pub fn find_symbol_in_file(
     let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
 
     if debug_mode {
-        println!("[DEBUG] File extension: {extension}");
+        debug!("File extension: {extension}");
     }
 
     // Get the language implementation for this extension
@@ -75,7 +78,7 @@ pub fn find_symbol_in_file(
         Some(impl_) => impl_,
         None => {
             if debug_mode {
-                println!("[DEBUG] Language extension '{extension}' not supported for AST parsing, returning full file");
+                debug!("Language extension '{extension}' not supported for AST parsing, returning full file");
             }
             // Return the entire file as a SearchResult when language is unsupported
             let lines: Vec<&str> = content.lines().collect();
@@ -90,9 +93,13 @@ pub fn find_symbol_in_file(
                 file: path.to_string_lossy().to_string(),
                 lines: (1, lines.len()),
                 node_type: "file".to_string(),
-                code: content.to_string(),
+                code: content.into(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -118,16 +125,15 @@ pub fn find_symbol_in_file(
     };
 
     if debug_mode {
-        println!("[DEBUG] Language detected: {extension}");
-        println!("[DEBUG] Using tree-sitter to parse file");
+        debug!("Language detected: {extension}");
+        debug!("Using tree-sitter to parse file");
     }
 
     // Parse the file with tree-sitter using pooled parser for better performance
     let mut parser = crate::language::get_pooled_parser(extension)
         .map_err(|e| anyhow::anyhow!("Failed to get pooled parser: {}", e))?;
 
-    let tree = parser
-        .parse(content.as_bytes(), None)
+    let tree = crate::language::timed_parse(&mut parser, extension, content)
         .ok_or_else(|| anyhow::anyhow!("Failed to parse file"))?;
 
     // Return parser to pool for reuse
@@ -136,19 +142,19 @@ pub fn find_symbol_in_file(
     let root_node = tree.root_node();
 
     if debug_mode {
-        println!("[DEBUG] File parsed successfully");
-        println!(
-            "[DEBUG] Root node type: {root_node_kind}",
+        debug!("File parsed successfully");
+        debug!(
+            "Root node type: {root_node_kind}",
             root_node_kind = root_node.kind()
         );
-        println!(
-            "[DEBUG] Root node range: {}:{} - {}:{}",
+        debug!(
+            "Root node range: {}:{} - {}:{}",
             root_node.start_position().row + 1,
             root_node.start_position().column + 1,
             root_node.end_position().row + 1,
             root_node.end_position().column + 1
         );
-        println!("[DEBUG] Searching for symbol '{symbol}' in AST");
+        debug!("Searching for symbol '{symbol}' in AST");
     }
 
     // Function to recursively search for a node with the given symbol name
@@ -168,8 +174,8 @@ pub fn find_symbol_in_file(
         // Check if this node is an acceptable parent (function, struct, class, etc.)
         if language_impl.is_acceptable_parent(&node) {
             if debug_mode {
-                println!(
-                    "[DEBUG] Checking node type '{}' at {}:{} for symbol '{}'",
+                debug!(
+                    "Checking node type '{}' at {}:{} for symbol '{}'",
                     node.kind(),
                     node.start_position().row + 1,
                     node.start_position().column + 1,
@@ -189,9 +195,7 @@ pub fn find_symbol_in_file(
                     // Get the text of this identifier
                     if let Ok(name) = child.utf8_text(content) {
                         if debug_mode {
-                            println!(
-                                "[DEBUG] Found identifier: '{name}' (looking for '{current_symbol}')"
-                            );
+                            debug!("Found identifier: '{name}' (looking for '{current_symbol}')");
                         }
 
                         if name == current_symbol {
@@ -199,8 +203,8 @@ pub fn find_symbol_in_file(
                                 // If this is a nested symbol, we found the parent
                                 // Now we need to search for the child within this node
                                 if debug_mode {
-                                    println!(
-                                        "[DEBUG] Found parent symbol '{}' in node type '{}', now searching for child '{}'",
+                                    debug!(
+                                        "Found parent symbol '{}' in node type '{}', now searching for child '{}'",
                                         current_symbol,
                                         node.kind(),
                                         symbol_parts[1]
@@ -220,24 +224,29 @@ pub fn find_symbol_in_file(
                                                     method_child.utf8_text(content)
                                                 {
                                                     if debug_mode {
-                                                        println!(
-                                                            "[DEBUG] Found direct method: '{}' (looking for '{}')",
+                                                        debug!(
+                                                            "Found direct method: '{}' (looking for '{}')",
                                                             method_name, symbol_parts[1]
                                                         );
                                                     }
 
                                                     if method_name == symbol_parts[1] {
                                                         if debug_mode {
-                                                            println!(
-                                                                "[DEBUG] Found child symbol '{}' as direct method_definition",
+                                                            debug!(
+                                                                "Found child symbol '{}' as direct method_definition",
                                                                 symbol_parts[1]
                                                             );
-                                                            println!(
-                                                                "[DEBUG] Symbol location: {}:{} - {}:{}",
-                                                                direct_child.start_position().row + 1,
-                                                                direct_child.start_position().column + 1,
+                                                            debug!(
+                                                                "Symbol location: {}:{} - {}:{}",
+                                                                direct_child.start_position().row
+                                                                    + 1,
+                                                                direct_child
+                                                                    .start_position()
+                                                                    .column
+                                                                    + 1,
                                                                 direct_child.end_position().row + 1,
-                                                                direct_child.end_position().column + 1
+                                                                direct_child.end_position().column
+                                                                    + 1
                                                             );
                                                         }
                                                         return Some(direct_child);
@@ -252,8 +261,8 @@ pub fn find_symbol_in_file(
                                 let mut child_cursor = node.walk();
                                 for child_node in node.children(&mut child_cursor) {
                                     if debug_mode {
-                                        println!(
-                                            "[DEBUG] Checking child node type '{}' for symbol '{}'",
+                                        debug!(
+                                            "Checking child node type '{}' for symbol '{}'",
                                             child_node.kind(),
                                             symbol_parts[1]
                                         );
@@ -283,20 +292,20 @@ pub fn find_symbol_in_file(
                                                                 method_child.utf8_text(content)
                                                             {
                                                                 if debug_mode {
-                                                                    println!(
-                                                                        "[DEBUG] Found method: '{}' (looking for '{}')",
+                                                                    debug!(
+                                                                        "Found method: '{}' (looking for '{}')",
                                                                         method_name, symbol_parts[1]
                                                                     );
                                                                 }
 
                                                                 if method_name == symbol_parts[1] {
                                                                     if debug_mode {
-                                                                        println!(
-                                                                            "[DEBUG] Found child symbol '{}' in method_definition",
+                                                                        debug!(
+                                                                            "Found child symbol '{}' in method_definition",
                                                                             symbol_parts[1]
                                                                         );
-                                                                        println!(
-                                                                            "[DEBUG] Symbol location: {}:{} - {}:{}",
+                                                                        debug!(
+                                                                            "Symbol location: {}:{} - {}:{}",
                                                                             subchild.start_position().row + 1,
                                                                             subchild.start_position().column + 1,
                                                                             subchild.end_position().row + 1,
@@ -312,25 +321,27 @@ pub fn find_symbol_in_file(
                                                 }
                                                 if let Ok(name) = subchild.utf8_text(content) {
                                                     if debug_mode {
-                                                        println!(
-                                                            "[DEBUG] Found identifier: '{}' (looking for '{}')",
+                                                        debug!(
+                                                            "Found identifier: '{}' (looking for '{}')",
                                                             name, symbol_parts[1]
                                                         );
                                                     }
 
                                                     if name == symbol_parts[1] {
                                                         if debug_mode {
-                                                            println!(
-                                                                "[DEBUG] Found child symbol '{}' in node type '{}'",
+                                                            debug!(
+                                                                "Found child symbol '{}' in node type '{}'",
                                                                 symbol_parts[1],
                                                                 child_node.kind()
                                                             );
-                                                            println!(
-                                                                "[DEBUG] Symbol location: {}:{} - {}:{}",
+                                                            debug!(
+                                                                "Symbol location: {}:{} - {}:{}",
                                                                 child_node.start_position().row + 1,
-                                                                child_node.start_position().column + 1,
+                                                                child_node.start_position().column
+                                                                    + 1,
                                                                 child_node.end_position().row + 1,
-                                                                child_node.end_position().column + 1
+                                                                child_node.end_position().column
+                                                                    + 1
                                                             );
                                                         }
                                                         return Some(child_node);
@@ -354,13 +365,13 @@ pub fn find_symbol_in_file(
                             } else {
                                 // If this is a simple symbol, we found it
                                 if debug_mode {
-                                    println!(
-                                        "[DEBUG] Found symbol '{}' in node type '{}'",
+                                    debug!(
+                                        "Found symbol '{}' in node type '{}'",
                                         current_symbol,
                                         node.kind()
                                     );
-                                    println!(
-                                        "[DEBUG] Symbol location: {}:{} - {}:{}",
+                                    debug!(
+                                        "Symbol location: {}:{} - {}:{}",
                                         node.start_position().row + 1,
                                         node.start_position().column + 1,
                                         node.end_position().row + 1,
@@ -375,7 +386,7 @@ pub fn find_symbol_in_file(
                     // For function_declarator, we need to look deeper
                     if child.kind() == "function_declarator" {
                         if debug_mode {
-                            println!("[DEBUG] Checking function_declarator for symbol");
+                            debug!("Checking function_declarator for symbol");
                         }
 
                         let mut subcursor = child.walk();
@@ -383,7 +394,7 @@ pub fn find_symbol_in_file(
                             if subchild.kind() == "identifier" {
                                 if let Ok(name) = subchild.utf8_text(content) {
                                     if debug_mode {
-                                        println!("[DEBUG] Found function identifier: '{name}' (looking for '{current_symbol}')");
+                                        debug!("Found function identifier: '{name}' (looking for '{current_symbol}')");
                                     }
 
                                     if name == current_symbol {
@@ -391,8 +402,8 @@ pub fn find_symbol_in_file(
                                             // If this is a nested symbol, we found the parent
                                             // Now we need to search for the child within this node
                                             if debug_mode {
-                                                println!(
-                                                    "[DEBUG] Found parent symbol '{}' in function_declarator, now searching for child '{}'",
+                                                debug!(
+                                                    "Found parent symbol '{}' in function_declarator, now searching for child '{}'",
                                                     current_symbol,
                                                     symbol_parts[1]
                                                 );
@@ -414,11 +425,11 @@ pub fn find_symbol_in_file(
                                         } else {
                                             // If this is a simple symbol, we found it
                                             if debug_mode {
-                                                println!(
-                                                    "[DEBUG] Found symbol '{current_symbol}' in function_declarator"
+                                                debug!(
+                                                    "Found symbol '{current_symbol}' in function_declarator"
                                                 );
-                                                println!(
-                                                    "[DEBUG] Symbol location: {}:{} - {}:{}",
+                                                debug!(
+                                                    "Symbol location: {}:{} - {}:{}",
                                                     node.start_position().row + 1,
                                                     node.start_position().column + 1,
                                                     node.end_position().row + 1,
@@ -449,26 +460,35 @@ pub fn find_symbol_in_file(
         None
     }
 
+    // Rust trait implementations get dedicated resolution so that
+    // `Trait for Type` and `Type::method` pick the right impl block
+    let rust_impl_node = if extension == "rs" {
+        find_rust_impl_node(root_node, &symbol_parts, content.as_bytes(), debug_mode)
+    } else {
+        None
+    };
+
     // Search for the symbol in the AST
-    if let Some(found_node) = find_symbol_node(
-        root_node,
-        &symbol_parts,
-        language_impl.as_ref(),
-        content.as_bytes(),
-        debug_mode,
-    ) {
-        let node_start_line = found_node.start_position().row + 1;
+    if let Some(found_node) = rust_impl_node.or_else(|| {
+        find_symbol_node(
+            root_node,
+            &symbol_parts,
+            language_impl.as_ref(),
+            content.as_bytes(),
+            debug_mode,
+        )
+    }) {
+        // Start at any attributes/decorators/annotations attached to the symbol
+        let start_node = find_attributed_start(found_node, language_impl.as_ref());
+        let node_start_line = start_node.start_position().row + 1;
         let node_end_line = found_node.end_position().row + 1;
 
         if debug_mode {
-            println!("\n[DEBUG] ===== Symbol Found =====");
-            println!("[DEBUG] Found symbol '{symbol}' at lines {node_start_line}-{node_end_line}");
-            println!(
-                "[DEBUG] Node type: {node_kind}",
-                node_kind = found_node.kind()
-            );
-            println!(
-                "[DEBUG] Node range: {}:{} - {}:{}",
+            debug!("===== Symbol Found =====");
+            debug!("Found symbol '{symbol}' at lines {node_start_line}-{node_end_line}");
+            debug!("Node type: {node_kind}", node_kind = found_node.kind());
+            debug!(
+                "Node range: {}:{} - {}:{}",
                 found_node.start_position().row + 1,
                 found_node.start_position().column + 1,
                 found_node.end_position().row + 1,
@@ -477,15 +497,15 @@ pub fn find_symbol_in_file(
         }
 
         // Extract the code block
-        let node_text = &content[found_node.start_byte()..found_node.end_byte()];
+        let node_text = &content[start_node.start_byte()..found_node.end_byte()];
 
         if debug_mode {
-            println!(
-                "[DEBUG] Extracted code size: {code_size} bytes",
+            debug!(
+                "Extracted code size: {code_size} bytes",
                 code_size = node_text.len()
             );
-            println!(
-                "[DEBUG] Extracted code lines: {line_count}",
+            debug!(
+                "Extracted code lines: {line_count}",
                 line_count = node_text.lines().count()
             );
         }
@@ -503,9 +523,13 @@ pub fn find_symbol_in_file(
             file: path.to_string_lossy().to_string(),
             lines: (node_start_line, node_end_line),
             node_type: found_node.kind().to_string(),
-            code: node_text_str,
+            code: node_text_str.into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -531,9 +555,9 @@ pub fn find_symbol_in_file(
 
     // If we couldn't find the symbol using tree-sitter, try a simple text search as fallback
     if debug_mode {
-        println!("\n[DEBUG] ===== Symbol Not Found in AST =====");
-        println!("[DEBUG] Symbol '{symbol}' not found in AST");
-        println!("[DEBUG] Trying text search fallback");
+        debug!("===== Symbol Not Found in AST =====");
+        debug!("Symbol '{symbol}' not found in AST");
+        debug!("Trying text search fallback");
     }
 
     // Simple text search for the symbol
@@ -545,8 +569,8 @@ pub fn find_symbol_in_file(
     let search_terms = if is_nested_symbol {
         // For nested symbols, we'll look for lines containing all parts
         if debug_mode {
-            println!(
-                "[DEBUG] Using fallback search for nested symbol: looking for lines containing all parts"
+            debug!(
+                "Using fallback search for nested symbol: looking for lines containing all parts"
             );
         }
         symbol_parts.to_vec()
@@ -555,8 +579,8 @@ pub fn find_symbol_in_file(
     };
 
     if debug_mode {
-        println!(
-            "[DEBUG] Performing text search for '{:?}' across {} lines",
+        debug!(
+            "Performing text search for '{:?}' across {} lines",
             search_terms,
             lines.len()
         );
@@ -569,8 +593,8 @@ pub fn find_symbol_in_file(
         if found {
             found_line = Some(i + 1); // 1-indexed line number
             if debug_mode {
-                println!(
-                    "[DEBUG] Found symbol '{}' in line {}: '{}'",
+                debug!(
+                    "Found symbol '{}' in line {}: '{}'",
                     symbol,
                     i + 1,
                     line.trim()
@@ -582,8 +606,8 @@ pub fn find_symbol_in_file(
 
     if let Some(line_num) = found_line {
         if debug_mode {
-            println!("\n[DEBUG] ===== Symbol Found via Text Search =====");
-            println!("[DEBUG] Found symbol '{symbol}' using text search at line {line_num}");
+            debug!("===== Symbol Found via Text Search =====");
+            debug!("Found symbol '{symbol}' using text search at line {line_num}");
         }
 
         // Extract context around the line
@@ -591,9 +615,9 @@ pub fn find_symbol_in_file(
         let end_line = std::cmp::min(line_num + context_lines, lines.len());
 
         if debug_mode {
-            println!("[DEBUG] Extracting context around line {line_num}");
-            println!("[DEBUG] Context lines: {context_lines}");
-            println!("[DEBUG] Extracting lines {start_line}-{end_line}");
+            debug!("Extracting context around line {line_num}");
+            debug!("Context lines: {context_lines}");
+            debug!("Extracting lines {start_line}-{end_line}");
         }
 
         // Adjust start_line to be at least 1 (1-indexed)
@@ -602,12 +626,12 @@ pub fn find_symbol_in_file(
         let context = lines[start_idx..end_line].join("\n");
 
         if debug_mode {
-            println!(
-                "[DEBUG] Extracted {line_count} lines of code",
+            debug!(
+                "Extracted {line_count} lines of code",
                 line_count = end_line - start_line
             );
-            println!(
-                "[DEBUG] Content size: {content_size} bytes",
+            debug!(
+                "Content size: {content_size} bytes",
                 content_size = context.len()
             );
         }
@@ -623,9 +647,13 @@ pub fn find_symbol_in_file(
             file: path.to_string_lossy().to_string(),
             lines: (start_line, end_line),
             node_type: "text_search".to_string(),
-            code: context,
+            code: context.into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -651,16 +679,251 @@ pub fn find_symbol_in_file(
 
     // If we get here, we couldn't find the symbol
     if debug_mode {
-        println!("\n[DEBUG] ===== Symbol Not Found =====");
-        println!("[DEBUG] Symbol '{symbol}' not found in file {path:?}");
-        println!("[DEBUG] Neither AST parsing nor text search found the symbol");
+        debug!("===== Symbol Not Found =====");
+        debug!("Symbol '{symbol}' not found in file {path:?}");
+        debug!("Neither AST parsing nor text search found the symbol");
+    }
+
+    let mut names = Vec::new();
+    collect_symbol_names(
+        root_node,
+        language_impl.as_ref(),
+        content.as_bytes(),
+        &mut names,
+    );
+    Err(SymbolNotFound {
+        symbol: symbol.to_string(),
+        file: path.to_path_buf(),
+        suggestions: rank_suggestions(symbol, names),
+    }
+    .into())
+}
+
+/// Error returned when a requested symbol does not exist in a file, carrying the
+/// closest symbol names defined there so callers can offer "did you mean" hints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolNotFound {
+    pub symbol: String,
+    pub file: PathBuf,
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for SymbolNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Symbol '{}' not found in file {:?}",
+            self.symbol, self.file
+        )?;
+        if !self.suggestions.is_empty() {
+            write!(f, ". Did you mean: {}?", self.suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SymbolNotFound {}
+
+/// Maximum number of suggestions offered for a missing symbol
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Collect the names of all symbol definitions (functions, types, methods, ...)
+fn collect_symbol_names(
+    node: tree_sitter::Node,
+    language_impl: &dyn crate::language::language_trait::LanguageImpl,
+    content: &[u8],
+    names: &mut Vec<String>,
+) {
+    if language_impl.is_acceptable_parent(&node) {
+        let name = node.child_by_field_name("name").or_else(|| {
+            let mut cursor = node.walk();
+            let found = node.children(&mut cursor).find(|child| {
+                matches!(
+                    child.kind(),
+                    "identifier" | "field_identifier" | "type_identifier" | "property_identifier"
+                )
+            });
+            found
+        });
+        if let Some(Ok(name)) = name.map(|n| n.utf8_text(content)) {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_symbol_names(child, language_impl, content, names);
+    }
+}
+
+/// Rank candidate names by case-insensitive edit distance to `symbol`, keeping only
+/// plausible matches. Names containing the symbol (or contained in it) rank first.
+fn rank_suggestions(symbol: &str, names: Vec<String>) -> Vec<String> {
+    // For nested paths like `Class.method` compare against the last segment
+    let wanted = symbol.rsplit('.').next().unwrap_or(symbol).to_lowercase();
+    let max_distance = (wanted.chars().count() / 2).max(2);
+
+    let mut scored: Vec<(bool, usize, String)> = names
+        .into_iter()
+        .filter_map(|name| {
+            let lower = name.to_lowercase();
+            let related = lower.contains(&wanted) || wanted.contains(&lower);
+            let distance = levenshtein(&wanted, &lower);
+            (related || distance <= max_distance).then_some((!related, distance, name))
+        })
+        .collect();
+    scored.sort();
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, name)| name)
+        .collect()
+}
+
+/// Levenshtein edit distance between two strings, counted in characters
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Strip generic arguments and path qualifiers from a Rust type or trait
+/// reference, e.g. `fmt::Display` -> `Display`, `Wrapper<T>` -> `Wrapper`.
+fn rust_base_type_name(text: &str) -> &str {
+    let without_generics = text.split('<').next().unwrap_or(text).trim();
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+        .trim()
+}
+
+/// Resolve Rust impl-oriented symbol paths.
+///
+/// Supports:
+/// - `Trait for Type` to select the impl block of a trait implementation
+/// - `Trait for Type.method` to select a method inside that impl block
+/// - `Type.method` (from `Type::method`) to select a method from any impl of `Type`,
+///   preferring inherent impls over trait impls
+///
+/// Returns `None` when the symbol is not impl-shaped or nothing matches, in which
+/// case the caller falls back to the generic symbol search.
+fn find_rust_impl_node<'a>(
+    root: tree_sitter::Node<'a>,
+    symbol_parts: &[&str],
+    content: &'a [u8],
+    debug_mode: bool,
+) -> Option<tree_sitter::Node<'a>> {
+    // Locate the `Trait for Type` part, if any. Trait paths such as `fmt::Display`
+    // arrive split on `.`, so re-join everything up to that part.
+    let (trait_name, type_name, members) =
+        match symbol_parts.iter().position(|part| part.contains(" for ")) {
+            Some(idx) => {
+                let impl_spec = symbol_parts[..=idx].join("::");
+                let (trait_part, type_part) = impl_spec.split_once(" for ")?;
+                (
+                    Some(rust_base_type_name(trait_part).to_string()),
+                    rust_base_type_name(type_part).to_string(),
+                    &symbol_parts[idx + 1..],
+                )
+            }
+            None if symbol_parts.len() >= 2 => (
+                None,
+                rust_base_type_name(symbol_parts[0]).to_string(),
+                &symbol_parts[1..],
+            ),
+            None => return None,
+        };
+
+    if debug_mode {
+        debug!(
+            "Resolving Rust impl: trait={trait_name:?}, type='{type_name}', members={members:?}"
+        );
+    }
+
+    // Collect matching impl blocks in document order
+    fn collect_impls<'a>(
+        node: tree_sitter::Node<'a>,
+        trait_name: Option<&str>,
+        type_name: &str,
+        content: &'a [u8],
+        out: &mut Vec<tree_sitter::Node<'a>>,
+    ) {
+        if node.kind() == "impl_item" {
+            let impl_type = node
+                .child_by_field_name("type")
+                .and_then(|n| n.utf8_text(content).ok())
+                .map(rust_base_type_name);
+            let impl_trait = node
+                .child_by_field_name("trait")
+                .and_then(|n| n.utf8_text(content).ok())
+                .map(rust_base_type_name);
+
+            let type_matches = impl_type == Some(type_name);
+            let trait_matches = match trait_name {
+                Some(wanted) => impl_trait == Some(wanted),
+                None => true,
+            };
+            if type_matches && trait_matches {
+                out.push(node);
+            }
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect_impls(child, trait_name, type_name, content, out);
+        }
+    }
+
+    let mut impls = Vec::new();
+    collect_impls(root, trait_name.as_deref(), &type_name, content, &mut impls);
+
+    // Without a trait, inherent impls take precedence over trait impls
+    if trait_name.is_none() {
+        impls.sort_by_key(|node| node.child_by_field_name("trait").is_some());
+    }
+
+    if members.is_empty() {
+        return impls.into_iter().next();
+    }
+
+    let member = members[0];
+    for impl_node in impls {
+        let Some(body) = impl_node.child_by_field_name("body") else {
+            continue;
+        };
+        let mut cursor = body.walk();
+        for item in body.children(&mut cursor) {
+            let name = item
+                .child_by_field_name("name")
+                .and_then(|n| n.utf8_text(content).ok());
+            if name == Some(member) {
+                if debug_mode {
+                    debug!(
+                        "Found '{member}' in impl at lines {}-{}",
+                        impl_node.start_position().row + 1,
+                        impl_node.end_position().row + 1
+                    );
+                }
+                return Some(item);
+            }
+        }
     }
 
-    Err(anyhow::anyhow!(
-        "Symbol '{}' not found in file {:?}",
-        symbol,
-        path
-    ))
+    None
 }
 
 #[cfg(test)]
@@ -845,4 +1108,60 @@ fn test_function() {
         // Clean up
         let _ = fs::remove_file(&test_file);
     }
+
+    #[test]
+    fn test_rust_trait_impl_resolution() {
+        let temp_dir = std::env::temp_dir();
+        let test_file = temp_dir.join("test_symbol_rust_trait_impls.rs");
+
+        let content = r#"struct Point {
+    x: i32,
+}
+
+impl Point {
+    fn new() -> Self {
+        Point { x: 0 }
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "display")
+    }
+}
+
+impl fmt::Debug for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "debug")
+    }
+}"#;
+
+        let mut file = fs::File::create(&test_file).unwrap();
+        write!(file, "{content}").unwrap();
+
+        // Whole impl block selected by trait and type
+        let result = find_symbol_in_file(&test_file, "Debug for Point", content, true, 0).unwrap();
+        assert_eq!(result.node_type, "impl_item");
+        assert!(result.code.starts_with("impl fmt::Debug for Point"));
+
+        // Trait paths are accepted (`::` arrives converted to `.`)
+        let result =
+            find_symbol_in_file(&test_file, "fmt.Display for Point", content, true, 0).unwrap();
+        assert!(result.code.starts_with("impl fmt::Display for Point"));
+
+        // Method inside a specific trait impl
+        let result =
+            find_symbol_in_file(&test_file, "Debug for Point.fmt", content, true, 0).unwrap();
+        assert_eq!(result.node_type, "function_item");
+        assert!(result.code.contains("\"debug\""));
+        assert_eq!(result.lines, (18, 20));
+
+        // Type::method resolves through any impl of the type
+        let result = find_symbol_in_file(&test_file, "Point.fmt", content, true, 0).unwrap();
+        assert!(result.code.contains("\"display\""));
+        let result = find_symbol_in_file(&test_file, "Point.new", content, true, 0).unwrap();
+        assert!(result.code.contains("fn new()"));
+
+        let _ = fs::remove_file(&test_file);
+    }
 }
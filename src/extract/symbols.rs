@@ -0,0 +1,283 @@
+//! The named functions, methods and types of a source file, for `probe export` and
+//! the symbol-level chunks of `probe similar`.
+//!
+//! Symbols are found with tree-sitter, nested ones included: a method is a symbol of
+//! its own and is part of its class's body too. A symbol's doc comment is the comment
+//! block right above it (attributes and decorators may come in between) as written, or
+//! a Python docstring.
+
+use probe_code::language::factory::get_language_impl;
+use probe_code::language::get_or_parse_tree_pooled;
+use probe_code::language::language_trait::LanguageImpl;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Parts of the node kinds of functions and types, whose names make up a symbol
+pub const SYMBOL_KINDS: &[&str] = &[
+    "class",
+    "enum",
+    "function",
+    "impl",
+    "interface",
+    "method",
+    "module",
+    "namespace",
+    "struct",
+    "trait",
+];
+
+/// Nodes that may stand between a symbol and its doc comment
+const ATTRIBUTE_KINDS: &[&str] = &["attribute_item", "decorator", "annotation"];
+
+/// Nodes wrapping a definition, whose doc comment precedes the wrapper
+const WRAPPER_KINDS: &[&str] = &["decorated_definition", "export_statement"];
+
+/// A symbol of a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// Qualified with the symbols around it, as `Type.method`
+    pub name: String,
+    pub kind: String,
+    pub lines: (usize, usize),
+    /// The symbol as written up to its body, on one line
+    pub signature: String,
+    pub doc_comment: Option<String>,
+    pub body: String,
+}
+
+/// The symbols of a file in source order; none without a parser for it. `path` picks
+/// the language by its extension and keys the parse tree cache.
+pub fn file_symbols(path: &str, content: &str, allow_tests: bool) -> Vec<Symbol> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    let (Some(language_impl), Ok(tree)) = (
+        get_language_impl(extension),
+        get_or_parse_tree_pooled(path, content, extension),
+    ) else {
+        return Vec::new();
+    };
+    let source = content.as_bytes();
+    let mut symbols = Vec::new();
+    collect_symbols(
+        tree.root_node(),
+        source,
+        language_impl.as_ref(),
+        allow_tests,
+        None,
+        &mut |node, name| {
+            symbols.push(Symbol {
+                name,
+                kind: node.kind().to_string(),
+                lines: (node.start_position().row + 1, node.end_position().row + 1),
+                signature: signature(&node, source),
+                doc_comment: doc_comment(node, source),
+                body: node_text(node, source),
+            });
+        },
+    );
+    symbols
+}
+
+/// Call `add` with each symbol node under `node` and its qualified name, outer symbols
+/// before the ones nested in them
+pub(crate) fn collect_symbols<'tree>(
+    node: Node<'tree>,
+    source: &[u8],
+    language_impl: &dyn LanguageImpl,
+    allow_tests: bool,
+    owner: Option<&str>,
+    add: &mut dyn FnMut(Node<'tree>, String),
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if !allow_tests && language_impl.is_test_node(&child, source) {
+            continue;
+        }
+        let name = language_impl
+            .is_acceptable_parent(&child)
+            .then(|| symbol_name(child, source))
+            .flatten();
+        match name {
+            Some(name) => {
+                let symbol = match owner {
+                    Some(owner) => format!("{owner}.{name}"),
+                    None => name,
+                };
+                add(child, symbol.clone());
+                collect_symbols(
+                    child,
+                    source,
+                    language_impl,
+                    allow_tests,
+                    Some(&symbol),
+                    add,
+                );
+            }
+            None => collect_symbols(child, source, language_impl, allow_tests, owner, add),
+        }
+    }
+}
+
+/// The name of a function or type node; `None` for other nodes, like assignments
+pub fn symbol_name(node: Node, source: &[u8]) -> Option<String> {
+    let kind = node.kind();
+    if !SYMBOL_KINDS.iter().any(|part| kind.contains(part)) {
+        return None;
+    }
+    name_node(node)?.utf8_text(source).ok().map(str::to_string)
+}
+
+/// The node holding the name of a definition, for any node kind
+pub fn name_node(node: Node) -> Option<Node> {
+    // C and C++ name functions in nested declarators, Rust names `impl Type` by its type
+    let mut name = node
+        .child_by_field_name("name")
+        .or_else(|| node.child_by_field_name("declarator"))
+        .or_else(|| node.child_by_field_name("type"))?;
+    while let Some(declarator) = name.child_by_field_name("declarator") {
+        name = declarator;
+    }
+    Some(name)
+}
+
+/// A symbol as written up to its body, whitespace collapsed
+fn signature(node: &Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = String::from_utf8_lossy(&source[node.start_byte()..end]);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.trim_end_matches(['{', ':']).trim_end().to_string()
+}
+
+/// The comments right above a symbol, or the docstring at the start of its body
+fn doc_comment(node: Node, source: &[u8]) -> Option<String> {
+    let mut anchor = node;
+    while let Some(parent) = anchor
+        .parent()
+        .filter(|parent| WRAPPER_KINDS.contains(&parent.kind()))
+    {
+        anchor = parent;
+    }
+    let mut comments = Vec::new();
+    let mut next_row = anchor.start_position().row;
+    let mut sibling = anchor.prev_named_sibling();
+    while let Some(node) = sibling {
+        // A blank line ends the block
+        if node.end_position().row + 1 < next_row {
+            break;
+        }
+        if node.kind().contains("comment") {
+            comments.push(node_text(node, source).trim_end().to_string());
+        } else if !ATTRIBUTE_KINDS.contains(&node.kind()) {
+            break;
+        }
+        next_row = node.start_position().row;
+        sibling = node.prev_named_sibling();
+    }
+    if !comments.is_empty() {
+        comments.reverse();
+        return Some(comments.join("\n"));
+    }
+
+    let first = node.child_by_field_name("body")?.named_child(0)?;
+    let string = (first.kind() == "expression_statement")
+        .then(|| first.named_child(0))
+        .flatten()?;
+    (string.kind() == "string").then(|| node_text(string, source))
+}
+
+fn node_text(node: Node, source: &[u8]) -> String {
+    String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()]).into_owned()
+}
+
+/// The language of the files with `extension` that have a parser, by its `--language`
+/// name
+pub fn language_name(extension: &str) -> &'static str {
+    match extension {
+        "rs" => "rust",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "go" => "go",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "hxx" => "cpp",
+        "java" => "java",
+        "rb" => "ruby",
+        "php" => "php",
+        "swift" => "swift",
+        "cs" => "csharp",
+        "html" | "htm" => "html",
+        "md" | "markdown" => "markdown",
+        "yaml" | "yml" => "yaml",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_symbols() {
+        let source = r#"use std::fmt;
+
+/// A point on the plane
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    /// The distance from the origin
+    pub fn norm(&self) -> i32 {
+        self.x.abs()
+    }
+}
+
+// Unrelated note
+
+fn helper() {}
+"#;
+        let symbols = file_symbols("src/point.rs", source, false);
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Point", "Point", "Point.norm", "helper"]);
+
+        let point = &symbols[0];
+        assert_eq!(language_name("rs"), "rust");
+        assert_eq!(point.kind, "struct_item");
+        assert_eq!(point.lines, (5, 7));
+        assert_eq!(point.signature, "pub struct Point");
+        assert_eq!(
+            point.doc_comment.as_deref(),
+            Some("/// A point on the plane")
+        );
+
+        let norm = &symbols[2];
+        assert_eq!(norm.signature, "pub fn norm(&self) -> i32");
+        assert_eq!(
+            norm.doc_comment.as_deref(),
+            Some("/// The distance from the origin")
+        );
+        assert!(norm.body.starts_with("pub fn norm") && norm.body.ends_with('}'));
+
+        // A comment separated by a blank line documents nothing
+        assert_eq!(symbols[3].doc_comment, None);
+    }
+
+    #[test]
+    fn test_python_docstring() {
+        let source = "class Cache:\n    \"\"\"Keeps results.\"\"\"\n\n    def get(self, key):\n        return None\n";
+        let symbols = file_symbols("cache.py", source, false);
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Cache", "Cache.get"]);
+        assert_eq!(
+            symbols[0].doc_comment.as_deref(),
+            Some("\"\"\"Keeps results.\"\"\"")
+        );
+        assert_eq!(symbols[1].signature, "def get(self, key)");
+        assert_eq!(symbols[1].doc_comment, None);
+    }
+}
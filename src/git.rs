@@ -0,0 +1,42 @@
+//! Running git, for the commands and filters that read repository history.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Run git in `dir` and return its stdout; a failing command is an error with git's
+/// stderr
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        // Fail instead of prompting for credentials
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_git_reports_failures() {
+        let temp = TempDir::new().unwrap();
+        assert!(git(temp.path(), &["--version"])
+            .unwrap()
+            .starts_with("git version"));
+        let err = git(temp.path(), &["rev-parse", "HEAD"]).unwrap_err();
+        assert!(err.to_string().starts_with("git rev-parse HEAD: "), "{err}");
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::{Context, Result};
 use colored::*;
 use ignore::WalkBuilder;
+use probe_code::search::file_list_cache::{is_binary_file, PROBEIGNORE_FILENAME};
+use probe_code::search::filters::FileTypes;
+use probe_code::search::query::has_uppercase_literal;
 use regex::{Regex, RegexBuilder};
 use std::collections::VecDeque;
 use std::fs;
@@ -12,6 +15,7 @@ pub struct GrepParams {
     pub pattern: String,
     pub paths: Vec<PathBuf>,
     pub ignore_case: bool,
+    pub smart_case: bool,
     pub line_number: bool,
     pub count: bool,
     pub files_with_matches: bool,
@@ -21,11 +25,29 @@ pub struct GrepParams {
     pub after_context: Option<usize>,
     pub context: Option<usize>,
     pub ignore: Vec<String>,
+    /// Only search files of these types (--type)
+    pub file_types: Vec<String>,
+    /// User-defined file types, as `name:glob,glob`
+    pub type_definitions: Vec<String>,
     pub no_gitignore: bool,
     pub color: String,
     pub max_count: Option<usize>,
+    pub heading: bool,
+    pub group_separator: Option<String>,
+    pub max_files: Option<usize>,
+    /// Descend at most this many directories below each path
+    pub max_depth: Option<usize>,
+    /// Search exactly these files, in order, instead of walking `paths`
+    pub files_from: Option<Vec<PathBuf>>,
+    /// Search binary files byte-wise, reporting match offsets with a hex dump
+    pub binary: bool,
 }
 
+/// Bytes of context shown on each side of a binary match
+const BINARY_CONTEXT_BYTES: usize = 8;
+/// Matched bytes shown in a binary match; longer matches are cut off
+const BINARY_MAX_MATCH_BYTES: usize = 32;
+
 /// Configuration for grep operations
 struct GrepConfig {
     regex: Regex,
@@ -35,14 +57,32 @@ struct GrepConfig {
     show_line_numbers: bool,
     invert_match: bool,
     max_count: Option<usize>,
+    /// Print the file name once above its matches instead of on every line
+    heading: bool,
+    /// Printed between non-adjacent context groups; `None` prints nothing
+    group_separator: Option<String>,
+    /// With --binary, the pattern compiled for matching raw bytes
+    binary_regex: Option<regex::bytes::Regex>,
 }
 
 impl GrepConfig {
     fn from_params(params: &GrepParams) -> Result<Self> {
+        let case_insensitive =
+            params.ignore_case || (params.smart_case && !has_uppercase_literal(&params.pattern));
         let regex = RegexBuilder::new(&params.pattern)
-            .case_insensitive(params.ignore_case)
+            .case_insensitive(case_insensitive)
             .build()
             .context("Failed to compile regex pattern")?;
+        let binary_regex = if params.binary {
+            Some(
+                regex::bytes::RegexBuilder::new(&params.pattern)
+                    .case_insensitive(case_insensitive)
+                    .build()
+                    .context("Failed to compile regex pattern")?,
+            )
+        } else {
+            None
+        };
 
         let before_context = params.context.or(params.before_context).unwrap_or(0);
         let after_context = params.context.or(params.after_context).unwrap_or(0);
@@ -61,6 +101,9 @@ impl GrepConfig {
             show_line_numbers: params.line_number,
             invert_match: params.invert_match,
             max_count: params.max_count,
+            heading: params.heading,
+            group_separator: params.group_separator.clone(),
+            binary_regex,
         })
     }
 }
@@ -72,6 +115,18 @@ struct MatchedLine {
     content: String,
 }
 
+/// A match in a binary file with the bytes around it
+struct BinaryMatch {
+    /// Byte offset of the match in the file
+    offset: usize,
+    before: Vec<u8>,
+    /// The matched bytes, cut off at `BINARY_MAX_MATCH_BYTES`
+    matched: Vec<u8>,
+    /// Matched bytes left out of `matched`
+    truncated: usize,
+    after: Vec<u8>,
+}
+
 /// Result of processing a single file (for simple modes)
 struct FileMatchResult {
     has_match: bool,
@@ -88,8 +143,13 @@ impl<'a> FileProcessor<'a> {
         Self { config }
     }
 
-    /// Process file and return basic match info (for count/files-only modes)
-    fn count_matches(&self, file_path: &Path) -> Result<FileMatchResult> {
+    /// Process file and return basic match info (for count/files-only modes).
+    /// Reading stops once `limit` matches were found.
+    fn count_matches(&self, file_path: &Path, limit: Option<usize>) -> Result<FileMatchResult> {
+        if let Some(regex) = self.binary_regex(file_path) {
+            return self.process_binary(file_path, regex, limit, |_| {});
+        }
+
         let file = fs::File::open(file_path)
             .with_context(|| format!("Failed to open file: {}", file_path.display()))?;
 
@@ -109,11 +169,8 @@ impl<'a> FileProcessor<'a> {
                 has_match = true;
                 match_count += 1;
 
-                // Check max count
-                if let Some(max) = self.config.max_count {
-                    if match_count >= max {
-                        break;
-                    }
+                if limit.is_some_and(|max| match_count >= max) {
+                    break;
                 }
             }
         }
@@ -124,6 +181,61 @@ impl<'a> FileProcessor<'a> {
         })
     }
 
+    /// With --binary, the byte regex to search `file_path` with if it is a binary file
+    fn binary_regex(&self, file_path: &Path) -> Option<&regex::bytes::Regex> {
+        self.config
+            .binary_regex
+            .as_ref()
+            .filter(|_| is_binary_file(file_path))
+    }
+
+    /// Search a binary file byte-wise, stopping after `limit` matches
+    fn process_binary<F>(
+        &self,
+        file_path: &Path,
+        regex: &regex::bytes::Regex,
+        limit: Option<usize>,
+        mut output_fn: F,
+    ) -> Result<FileMatchResult>
+    where
+        F: FnMut(&BinaryMatch),
+    {
+        let data = fs::read(file_path)
+            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        let mut match_count = 0;
+
+        for m in regex.find_iter(&data) {
+            if limit.is_some_and(|max| match_count >= max) {
+                break;
+            }
+            match_count += 1;
+
+            let shown_end = m.end().min(m.start() + BINARY_MAX_MATCH_BYTES);
+            let after = if shown_end < m.end() {
+                Vec::new()
+            } else {
+                data[m.end()..(m.end() + BINARY_CONTEXT_BYTES).min(data.len())].to_vec()
+            };
+            output_fn(&BinaryMatch {
+                offset: m.start(),
+                before: data[m.start().saturating_sub(BINARY_CONTEXT_BYTES)..m.start()].to_vec(),
+                matched: data[m.start()..shown_end].to_vec(),
+                truncated: m.end() - shown_end,
+                after,
+            });
+        }
+
+        Ok(FileMatchResult {
+            has_match: match_count > 0,
+            match_count,
+        })
+    }
+
+    /// Whether context lines are printed, which is when groups get separators
+    fn has_context(&self) -> bool {
+        self.config.before_context > 0 || self.config.after_context > 0
+    }
+
     /// Process file with streaming output (for full context mode)
     fn process_with_output<F>(&self, file_path: &Path, mut output_fn: F) -> Result<FileMatchResult>
     where
@@ -144,6 +256,12 @@ impl<'a> FileProcessor<'a> {
         let mut after_remaining = 0;
 
         for (line_index, line_result) in reader.lines().enumerate() {
+            // Once --max-count matches were printed, only their after-context is left to read
+            let satisfied = self.config.max_count.is_some_and(|max| match_count >= max);
+            if satisfied && after_remaining == 0 {
+                break;
+            }
+
             let line_number = line_index + 1;
             let content = match line_result {
                 Ok(l) => l,
@@ -157,17 +275,10 @@ impl<'a> FileProcessor<'a> {
                 content,
             };
 
-            if is_match {
+            if is_match && !satisfied {
                 has_match = true;
                 match_count += 1;
 
-                // Check max count
-                if let Some(max) = self.config.max_count {
-                    if match_count > max {
-                        break;
-                    }
-                }
-
                 // Print before-context lines from buffer
                 for ctx_line in &before_buffer {
                     output_fn(ctx_line, false);
@@ -229,20 +340,42 @@ impl OutputMode {
 pub fn handle_grep(params: GrepParams) -> Result<()> {
     let config = GrepConfig::from_params(&params)?;
     let output_mode = OutputMode::from_params(&params);
+    let file_types = FileTypes::new(&params.file_types, &params.type_definitions)?;
 
     // Use Arc to share config across threads
     let config = std::sync::Arc::new(config);
     let params = std::sync::Arc::new(params);
 
-    // Mutex for synchronized output to prevent interleaved results
-    let stdout = Mutex::new(io::stdout());
+    // Mutex for synchronized output to prevent interleaved results, with the number of
+    // files reported so far (for separators between files and --max-files)
+    let stdout = Mutex::new((io::stdout(), 0usize));
+    let files_limit_reached = || {
+        params
+            .max_files
+            .is_some_and(|max| stdout.lock().is_ok_and(|guard| guard.1 >= max))
+    };
+
+    if let Some(files) = &params.files_from {
+        for file in files {
+            let state = grep_file(file, &config, &params, output_mode, &stdout);
+            if matches!(state, ignore::WalkState::Quit) {
+                break;
+            }
+        }
+        return Ok(());
+    }
 
     for path in params.paths.iter() {
-        let walker = build_walker_parallel(path, &params.ignore, params.no_gitignore);
+        if files_limit_reached() {
+            break;
+        }
+        let walker =
+            build_walker_parallel(path, &params.ignore, params.no_gitignore, params.max_depth);
 
         let config = config.clone();
         let params = params.clone();
         let stdout_ref = &stdout;
+        let file_types = &file_types;
 
         walker.run(|| {
             let config = config.clone();
@@ -258,83 +391,135 @@ pub fn handle_grep(params: GrepParams) -> Result<()> {
                 if entry.file_type().is_none_or(|ft| ft.is_dir()) {
                     return ignore::WalkState::Continue;
                 }
+                if file_types
+                    .as_ref()
+                    .is_some_and(|types| !types.matches(entry.path()))
+                {
+                    return ignore::WalkState::Continue;
+                }
+
+                grep_file(entry.path(), &config, &params, output_mode, stdout_ref)
+            })
+        });
+    }
 
-                let file_path = entry.path();
-                let file_processor = FileProcessor::new(&config);
+    Ok(())
+}
 
-                match output_mode {
-                    OutputMode::FullWithContext => {
-                        // For streaming mode, collect output in a buffer first
-                        let mut buffer = Vec::new();
-
-                        let result =
-                            file_processor.process_with_output(file_path, |line, is_match| {
-                                // Format line into buffer
-                                let formatted = format_line(&config, file_path, line, is_match);
-                                buffer.push(formatted);
-                            });
-
-                        let result = match result {
-                            Ok(r) => r,
-                            Err(_) => return ignore::WalkState::Continue,
-                        };
-
-                        // Skip files based on match status
-                        if should_skip_file(&result, &params) {
-                            return ignore::WalkState::Continue;
+/// Grep one file and print its results; `Quit` once --max-files is reached
+fn grep_file(
+    file_path: &Path,
+    config: &GrepConfig,
+    params: &GrepParams,
+    output_mode: OutputMode,
+    stdout: &Mutex<(io::Stdout, usize)>,
+) -> ignore::WalkState {
+    let file_processor = FileProcessor::new(config);
+
+    match output_mode {
+        OutputMode::FullWithContext => {
+            // For streaming mode, collect output in a buffer first
+            let mut buffer = Vec::new();
+            let mut last_line: Option<usize> = None;
+
+            let result = if let Some(regex) = file_processor.binary_regex(file_path) {
+                file_processor.process_binary(file_path, regex, config.max_count, |m| {
+                    buffer.push(format_binary_match(config, file_path, m));
+                })
+            } else {
+                file_processor.process_with_output(file_path, |line, is_match| {
+                    // Separate context groups that aren't adjacent, like grep's `--`
+                    if let (Some(last), Some(separator)) = (last_line, &config.group_separator) {
+                        if file_processor.has_context() && line.line_number > last + 1 {
+                            buffer.push(format_separator(config, separator));
                         }
+                    }
+                    last_line = Some(line.line_number);
 
-                        // Write entire buffer atomically
-                        if !buffer.is_empty() {
-                            if let Ok(mut out) = stdout_ref.lock() {
-                                for line in buffer {
-                                    let _ = writeln!(out, "{}", line);
-                                }
+                    // Format line into buffer
+                    let formatted = format_line(config, file_path, line, is_match);
+                    buffer.push(formatted);
+                })
+            };
+
+            let result = match result {
+                Ok(r) => r,
+                Err(_) => return ignore::WalkState::Continue,
+            };
+
+            // Skip files based on match status
+            if should_skip_file(&result, params) {
+                return ignore::WalkState::Continue;
+            }
+
+            // Write entire buffer atomically
+            if !buffer.is_empty() {
+                if let Ok(mut guard) = stdout.lock() {
+                    let (out, files_reported) = &mut *guard;
+                    if params.max_files.is_some_and(|max| *files_reported >= max) {
+                        return ignore::WalkState::Quit;
+                    }
+                    if *files_reported > 0 {
+                        if config.heading {
+                            let _ = writeln!(out);
+                        } else if let Some(separator) = &config.group_separator {
+                            if file_processor.has_context() {
+                                let _ = writeln!(out, "{}", format_separator(config, separator));
                             }
                         }
                     }
-                    _ => {
-                        // Simple modes: count matches only
-                        let result = match file_processor.count_matches(file_path) {
-                            Ok(r) => r,
-                            Err(_) => return ignore::WalkState::Continue,
-                        };
-
-                        // Apply filtering based on output mode
-                        if should_skip_file(&result, &params) {
-                            return ignore::WalkState::Continue;
-                        }
+                    if config.heading {
+                        let _ = writeln!(out, "{}", format_heading(config, file_path));
+                    }
+                    for line in buffer {
+                        let _ = writeln!(out, "{}", line);
+                    }
+                    *files_reported += 1;
+                }
+            }
+        }
+        _ => {
+            // Simple modes: count matches only. Listing files only needs
+            // to know whether there is a match at all.
+            let limit = match output_mode {
+                OutputMode::Count => config.max_count,
+                _ => Some(1),
+            };
+            let result = match file_processor.count_matches(file_path, limit) {
+                Ok(r) => r,
+                Err(_) => return ignore::WalkState::Continue,
+            };
 
-                        // Format and write output atomically
-                        if let Ok(mut out) = stdout_ref.lock() {
-                            match output_mode {
-                                OutputMode::FilesWithMatches | OutputMode::FilesWithoutMatch => {
-                                    let _ = writeln!(out, "{}", file_path.display());
-                                }
-                                OutputMode::Count => {
-                                    if config.show_line_numbers {
-                                        let _ = writeln!(
-                                            out,
-                                            "{}:{}",
-                                            file_path.display(),
-                                            result.match_count
-                                        );
-                                    } else {
-                                        let _ = writeln!(out, "{}", result.match_count);
-                                    }
-                                }
-                                OutputMode::FullWithContext => unreachable!(),
-                            }
+            // Apply filtering based on output mode
+            if should_skip_file(&result, params) {
+                return ignore::WalkState::Continue;
+            }
+
+            // Format and write output atomically
+            if let Ok(mut guard) = stdout.lock() {
+                let (out, files_reported) = &mut *guard;
+                if params.max_files.is_some_and(|max| *files_reported >= max) {
+                    return ignore::WalkState::Quit;
+                }
+                *files_reported += 1;
+                match output_mode {
+                    OutputMode::FilesWithMatches | OutputMode::FilesWithoutMatch => {
+                        let _ = writeln!(out, "{}", file_path.display());
+                    }
+                    OutputMode::Count => {
+                        if config.show_line_numbers {
+                            let _ = writeln!(out, "{}:{}", file_path.display(), result.match_count);
+                        } else {
+                            let _ = writeln!(out, "{}", result.match_count);
                         }
                     }
+                    OutputMode::FullWithContext => unreachable!(),
                 }
-
-                ignore::WalkState::Continue
-            })
-        });
+            }
+        }
     }
 
-    Ok(())
+    ignore::WalkState::Continue
 }
 
 /// Format a single line for output
@@ -344,7 +529,12 @@ fn format_line(
     line: &MatchedLine,
     is_match: bool,
 ) -> String {
-    let file_str = file_path.display().to_string();
+    // With --heading the file name is printed once above its lines
+    let file_str = if config.heading {
+        String::new()
+    } else {
+        file_path.display().to_string()
+    };
 
     if config.use_color {
         format_colored_line(config, &file_str, line, is_match)
@@ -360,30 +550,34 @@ fn format_colored_line(
     line: &MatchedLine,
     is_match: bool,
 ) -> String {
+    let separator = if is_match { ":" } else { "-" };
+    let prefix = if file_str.is_empty() {
+        String::new()
+    } else {
+        format!("{}{separator}", file_str.green())
+    };
     if is_match {
         let highlighted = highlight_matches(config, &line.content);
 
         if config.show_line_numbers {
             format!(
-                "{}:{}:{}",
-                file_str.green(),
+                "{prefix}{}:{}",
                 line.line_number.to_string().green(),
                 highlighted
             )
         } else {
-            format!("{}:{}", file_str.green(), highlighted)
+            format!("{prefix}{highlighted}")
         }
     } else {
         // Context line
         if config.show_line_numbers {
             format!(
-                "{}-{}-{}",
-                file_str.green(),
+                "{prefix}{}-{}",
                 line.line_number.to_string().cyan(),
                 line.content
             )
         } else {
-            format!("{}-{}", file_str.green(), line.content)
+            format!("{prefix}{}", line.content)
         }
     }
 }
@@ -395,15 +589,91 @@ fn format_plain_line(
     line: &MatchedLine,
     is_match: bool,
 ) -> String {
+    let separator = if is_match { ":" } else { "-" };
+    let prefix = if file_str.is_empty() {
+        String::new()
+    } else {
+        format!("{file_str}{separator}")
+    };
     if config.show_line_numbers {
-        let separator = if is_match { ":" } else { "-" };
-        format!(
-            "{}{}{}{}{}",
-            file_str, separator, line.line_number, separator, line.content
-        )
+        format!("{prefix}{}{separator}{}", line.line_number, line.content)
+    } else {
+        format!("{prefix}{}", line.content)
+    }
+}
+
+/// Format a binary match as its offset and a hex dump with the printable bytes
+/// alongside, e.g. `0x0000102a: 00 01 [48 69] 0a  |..Hi.|`
+fn format_binary_match(config: &GrepConfig, file_path: &Path, m: &BinaryMatch) -> String {
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let ascii = |bytes: &[u8]| -> String {
+        bytes
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    };
+
+    let mut matched_hex = format!("[{}]", hex(&m.matched));
+    let mut matched_ascii = ascii(&m.matched);
+    let mut offset = format!("{:#010x}", m.offset);
+    if config.use_color {
+        matched_hex = matched_hex.red().bold().to_string();
+        matched_ascii = matched_ascii.red().bold().to_string();
+        offset = offset.green().to_string();
+    }
+    let truncated = if m.truncated > 0 {
+        format!(" (+{} bytes)", m.truncated)
+    } else {
+        String::new()
+    };
+    let dump = [hex(&m.before), matched_hex, hex(&m.after)]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let line = format!(
+        "{offset}: {dump}{truncated}  |{}{matched_ascii}{}|",
+        ascii(&m.before),
+        ascii(&m.after)
+    );
+
+    if config.heading {
+        line
+    } else if config.use_color {
+        format!("{}:{line}", file_path.display().to_string().green())
     } else {
-        let separator = if is_match { ":" } else { "-" };
-        format!("{}{}{}", file_str, separator, line.content)
+        format!("{}:{line}", file_path.display())
+    }
+}
+
+/// Format the file name line printed above a file's matches with --heading
+fn format_heading(config: &GrepConfig, file_path: &Path) -> String {
+    let file_str = file_path.display().to_string();
+    if config.use_color {
+        file_str.green().bold().to_string()
+    } else {
+        file_str
+    }
+}
+
+/// Format the separator printed between context groups
+fn format_separator(config: &GrepConfig, separator: &str) -> String {
+    if config.use_color {
+        separator.cyan().to_string()
+    } else {
+        separator.to_string()
     }
 }
 
@@ -426,6 +696,7 @@ fn build_walker_parallel(
     path: &Path,
     ignore_patterns: &[String],
     no_gitignore: bool,
+    max_depth: Option<usize>,
 ) -> ignore::WalkParallel {
     let mut walker_builder = WalkBuilder::new(path);
     walker_builder
@@ -436,6 +707,8 @@ fn build_walker_parallel(
         .git_ignore(!no_gitignore)
         .git_global(!no_gitignore)
         .git_exclude(!no_gitignore)
+        .add_custom_ignore_filename(PROBEIGNORE_FILENAME)
+        .max_depth(max_depth)
         .threads(num_cpus::get()); // Use all available CPU cores
 
     for pattern in ignore_patterns {
@@ -488,9 +761,18 @@ mod tests {
             after_context: Some(3),
             context: None,
             ignore: vec![],
+            file_types: vec![],
+            type_definitions: vec![],
             no_gitignore: false,
             color: "never".to_string(),
             max_count: Some(10),
+            heading: false,
+            group_separator: Some("--".to_string()),
+            max_files: None,
+            max_depth: None,
+            files_from: None,
+            binary: false,
+            smart_case: false,
         };
 
         let config = GrepConfig::from_params(&params).unwrap();
@@ -516,9 +798,18 @@ mod tests {
             after_context: None,
             context: None,
             ignore: vec![],
+            file_types: vec![],
+            type_definitions: vec![],
             no_gitignore: false,
             color: "auto".to_string(),
             max_count: None,
+            heading: false,
+            group_separator: Some("--".to_string()),
+            max_files: None,
+            max_depth: None,
+            files_from: None,
+            binary: false,
+            smart_case: false,
         };
 
         matches!(
@@ -546,9 +837,18 @@ mod tests {
             after_context: None,
             context: None,
             ignore: vec![],
+            file_types: vec![],
+            type_definitions: vec![],
             no_gitignore: false,
             color: "auto".to_string(),
             max_count: None,
+            heading: false,
+            group_separator: Some("--".to_string()),
+            max_files: None,
+            max_depth: None,
+            files_from: None,
+            binary: false,
+            smart_case: false,
         };
 
         let result = FileMatchResult {
@@ -578,6 +878,9 @@ mod tests {
             show_line_numbers: true,
             invert_match: false,
             max_count: None,
+            heading: false,
+            group_separator: Some("--".to_string()),
+            binary_regex: None,
         };
 
         let processor = FileProcessor::new(&config);
@@ -608,4 +911,137 @@ mod tests {
         // Should output: line 2 (before), line 3 (before), line 4 (match), line 5 (after)
         assert_eq!(output_lines, vec![2, 3, 4, 5]);
     }
+
+    #[test]
+    fn test_line_markers_with_and_without_heading() {
+        let mut config = GrepConfig {
+            regex: regex::Regex::new("match").unwrap(),
+            before_context: 1,
+            after_context: 0,
+            use_color: false,
+            show_line_numbers: true,
+            invert_match: false,
+            max_count: None,
+            heading: false,
+            group_separator: Some("--".to_string()),
+            binary_regex: None,
+        };
+        let path = Path::new("src/a.rs");
+        let matched = MatchedLine {
+            line_number: 4,
+            content: "match here".to_string(),
+        };
+        let context = MatchedLine {
+            line_number: 3,
+            content: "before".to_string(),
+        };
+
+        assert_eq!(
+            format_line(&config, path, &matched, true),
+            "src/a.rs:4:match here"
+        );
+        assert_eq!(
+            format_line(&config, path, &context, false),
+            "src/a.rs-3-before"
+        );
+
+        config.heading = true;
+        assert_eq!(format_line(&config, path, &matched, true), "4:match here");
+        assert_eq!(format_line(&config, path, &context, false), "3-before");
+
+        config.show_line_numbers = false;
+        assert_eq!(format_line(&config, path, &context, false), "before");
+    }
+
+    #[test]
+    fn test_max_count_stops_after_trailing_context() {
+        let config = GrepConfig {
+            regex: regex::Regex::new("match").unwrap(),
+            before_context: 0,
+            after_context: 1,
+            use_color: false,
+            show_line_numbers: true,
+            invert_match: false,
+            max_count: Some(2),
+            heading: false,
+            group_separator: None,
+            binary_regex: None,
+        };
+        let processor = FileProcessor::new(&config);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("test.txt");
+        std::fs::write(
+            &file_path,
+            "match 1\nafter\nmatch 2\nmatch 3\nafter\nmatch 4\n",
+        )
+        .unwrap();
+
+        let mut output_lines = Vec::new();
+        let result = processor
+            .process_with_output(&file_path, |line, is_match| {
+                output_lines.push((line.line_number, is_match));
+            })
+            .unwrap();
+
+        // The third match is only printed as the second match's after-context
+        assert_eq!(result.match_count, 2);
+        assert_eq!(
+            output_lines,
+            vec![(1, true), (2, false), (3, true), (4, false)]
+        );
+
+        assert_eq!(
+            processor
+                .count_matches(&file_path, Some(1))
+                .unwrap()
+                .match_count,
+            1
+        );
+        assert_eq!(
+            processor
+                .count_matches(&file_path, None)
+                .unwrap()
+                .match_count,
+            4
+        );
+    }
+    #[test]
+    fn test_binary_matches_show_offset_and_hex() {
+        let config = GrepConfig {
+            regex: regex::Regex::new("hello").unwrap(),
+            before_context: 0,
+            after_context: 0,
+            use_color: false,
+            show_line_numbers: true,
+            invert_match: false,
+            max_count: None,
+            heading: false,
+            group_separator: None,
+            binary_regex: Some(regex::bytes::Regex::new("hello").unwrap()),
+        };
+        let processor = FileProcessor::new(&config);
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary = temp_dir.path().join("blob.bin");
+        std::fs::write(&binary, b"\x00\x01hello\xff").unwrap();
+        let text = temp_dir.path().join("notes.txt");
+        std::fs::write(&text, "hello\n").unwrap();
+
+        // Text files keep going through the line-based path
+        assert!(processor.binary_regex(&text).is_none());
+        let regex = processor.binary_regex(&binary).unwrap();
+
+        let mut output = Vec::new();
+        let result = processor
+            .process_binary(&binary, regex, None, |m| {
+                output.push(format_binary_match(&config, Path::new("blob.bin"), m));
+            })
+            .unwrap();
+        assert_eq!(result.match_count, 1);
+        assert_eq!(
+            output,
+            ["blob.bin:0x00000002: 00 01 [68 65 6c 6c 6f] ff  |..hello.|"]
+        );
+    }
 }
@@ -0,0 +1,480 @@
+//! `probe history`: search the lines added and removed by each commit, like
+//! `git log -S` / `git log -G`, and show the code block around every matching change
+//! as it was at that revision.
+//!
+//! Commits are ranked by how many of their changed lines match the pattern, newest
+//! first on ties. Added lines are shown in the block they belong to after the commit,
+//! removed lines in the block they belonged to before it.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use probe_code::git::git;
+use probe_code::language::parser::parse_file_for_code_blocks;
+use probe_code::warnings;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+pub struct HistoryParams<'a> {
+    pub pattern: &'a str,
+    pub path: &'a Path,
+    /// Treat the pattern as a regex matched against changed lines (`git log -G`)
+    /// instead of a string whose number of occurrences changed (`git log -S`)
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub max_commits: usize,
+    pub allow_tests: bool,
+    pub format: &'a str,
+}
+
+/// A line added or removed by a commit that matches the pattern
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ChangedLine {
+    line: usize,
+    /// `+` for an added line, `-` for a removed one
+    kind: char,
+    text: String,
+}
+
+/// The matching changes a commit made to one file
+#[derive(Debug, Default, PartialEq)]
+struct FileChange {
+    /// Path before the commit, `None` for a new file
+    old_path: Option<String>,
+    /// Path after the commit, `None` for a deleted file
+    new_path: Option<String>,
+    added: Vec<ChangedLine>,
+    removed: Vec<ChangedLine>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Commit {
+    hash: String,
+    timestamp: i64,
+    date: String,
+    author: String,
+    subject: String,
+    files: Vec<FileChange>,
+}
+
+impl Commit {
+    fn score(&self) -> usize {
+        self.files
+            .iter()
+            .map(|file| file.added.len() + file.removed.len())
+            .sum()
+    }
+}
+
+/// The code block around some of a commit's matching changes
+#[derive(Debug, Serialize)]
+struct ContextBlock {
+    file: String,
+    /// Revision the block is taken from: the commit for added lines, its parent for
+    /// removed ones
+    revision: String,
+    lines: (usize, usize),
+    node_type: String,
+    code: String,
+    changes: Vec<ChangedLine>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryResult {
+    commit: String,
+    date: String,
+    author: String,
+    subject: String,
+    matching_lines: usize,
+    blocks: Vec<ContextBlock>,
+}
+
+pub fn handle_history(params: HistoryParams) -> Result<()> {
+    if params.pattern.is_empty() {
+        bail!("The pattern must not be empty");
+    }
+    warnings::set_json(params.format == "json");
+    let matcher = RegexBuilder::new(&if params.regex {
+        params.pattern.to_string()
+    } else {
+        regex::escape(params.pattern)
+    })
+    .case_insensitive(params.ignore_case)
+    .build()
+    .with_context(|| format!("Invalid pattern '{}'", params.pattern))?;
+
+    let (dir, pathspec) = if params.path.is_file() {
+        let name = params
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        (params.path.parent().unwrap_or(Path::new("")), name)
+    } else {
+        (params.path, ".".to_string())
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+
+    let pickaxe = format!(
+        "{}{}",
+        if params.regex { "-G" } else { "-S" },
+        params.pattern
+    );
+    let max_count = format!("--max-count={}", params.max_commits);
+    let mut args = vec![
+        "-c",
+        "core.quotePath=false",
+        "log",
+        &pickaxe,
+        &max_count,
+        "--format=%x1e%H%x1f%at%x1f%as%x1f%an%x1f%s",
+        "--patch",
+        "--unified=0",
+        "--no-renames",
+        "--no-color",
+        "--no-ext-diff",
+    ];
+    if params.ignore_case {
+        args.push("--regexp-ignore-case");
+    }
+    args.extend(["--", &pathspec]);
+    let log = git(dir, &args)
+        .with_context(|| format!("Can't read the git history of {}", params.path.display()))?;
+
+    let mut commits = parse_log(&log, &matcher);
+    commits.retain(|commit| commit.score() > 0);
+    commits.sort_by(|a, b| {
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+    });
+
+    let results: Vec<HistoryResult> = commits
+        .iter()
+        .map(|commit| HistoryResult {
+            commit: commit.hash.clone(),
+            date: commit.date.clone(),
+            author: commit.author.clone(),
+            subject: commit.subject.clone(),
+            matching_lines: commit.score(),
+            blocks: context_blocks(dir, commit, params.allow_tests),
+        })
+        .collect();
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    if results.is_empty() {
+        println!("No commits found");
+        return Ok(());
+    }
+    for result in &results {
+        print_result(result);
+    }
+    Ok(())
+}
+
+fn print_result(result: &HistoryResult) {
+    println!(
+        "{} {} {} {} ({} matching {})",
+        result.commit[..result.commit.len().min(12)].yellow(),
+        result.date,
+        result.author.cyan(),
+        result.subject.bold(),
+        result.matching_lines,
+        if result.matching_lines == 1 {
+            "line"
+        } else {
+            "lines"
+        }
+    );
+    for block in &result.blocks {
+        println!(
+            "  {}:{}-{} {} @ {}",
+            block.file.bold(),
+            block.lines.0,
+            block.lines.1,
+            block.node_type.dimmed(),
+            block.revision
+        );
+        let width = block.lines.1.to_string().len();
+        for (offset, code) in block.code.lines().enumerate() {
+            let number = block.lines.0 + offset;
+            let line = format!("{number:>width$} | {code}");
+            match block.changes.iter().find(|change| change.line == number) {
+                Some(change) if change.kind == '+' => println!("  + {}", line.green()),
+                Some(_) => println!("  - {}", line.red()),
+                None => println!("    {line}"),
+            }
+        }
+    }
+    println!();
+}
+
+/// Parse `git log --patch --unified=0` output, keeping only the changed lines that
+/// match `matcher`
+fn parse_log(log: &str, matcher: &Regex) -> Vec<Commit> {
+    let mut commits = Vec::new();
+    for record in log.split('\x1e').filter(|record| !record.trim().is_empty()) {
+        let mut lines = record.lines();
+        let header: Vec<&str> = lines.next().unwrap_or_default().split('\x1f').collect();
+        if header.len() < 5 {
+            continue;
+        }
+        let mut commit = Commit {
+            hash: header[0].to_string(),
+            timestamp: header[1].parse().unwrap_or_default(),
+            date: header[2].to_string(),
+            author: header[3].to_string(),
+            subject: header[4].to_string(),
+            files: Vec::new(),
+        };
+
+        let mut file: Option<FileChange> = None;
+        let (mut old_line, mut new_line) = (0, 0);
+        // `---`/`+++` are only file names before the first hunk of a file
+        let mut in_hunk = false;
+        for line in lines {
+            if line.starts_with("diff --git ") {
+                commit.files.extend(file.take());
+                file = Some(FileChange::default());
+                in_hunk = false;
+            } else if let Some(current) = file.as_mut() {
+                if let Some(hunk) = line.strip_prefix("@@ ") {
+                    (old_line, new_line) = hunk_starts(hunk);
+                    in_hunk = true;
+                } else if !in_hunk {
+                    if let Some(path) = line.strip_prefix("--- ") {
+                        current.old_path = diff_path(path, "a/");
+                    } else if let Some(path) = line.strip_prefix("+++ ") {
+                        current.new_path = diff_path(path, "b/");
+                    }
+                } else if let Some(text) = line.strip_prefix('-') {
+                    if matcher.is_match(text) {
+                        current.removed.push(ChangedLine {
+                            line: old_line,
+                            kind: '-',
+                            text: text.to_string(),
+                        });
+                    }
+                    old_line += 1;
+                } else if let Some(text) = line.strip_prefix('+') {
+                    if matcher.is_match(text) {
+                        current.added.push(ChangedLine {
+                            line: new_line,
+                            kind: '+',
+                            text: text.to_string(),
+                        });
+                    }
+                    new_line += 1;
+                }
+            }
+        }
+        commit.files.extend(file);
+        commit
+            .files
+            .retain(|file| !file.added.is_empty() || !file.removed.is_empty());
+        commits.push(commit);
+    }
+    commits
+}
+
+/// The path in a `---`/`+++` line, `None` for `/dev/null`
+fn diff_path(path: &str, prefix: &str) -> Option<String> {
+    let path = path.trim_end_matches('\t');
+    path.strip_prefix(prefix).map(str::to_string)
+}
+
+/// First old and new line numbers of a hunk from `-a,b +c,d @@ ...`
+fn hunk_starts(hunk: &str) -> (usize, usize) {
+    let mut ranges = hunk.split_whitespace();
+    let mut start = |sign: char| {
+        ranges
+            .next()
+            .and_then(|range| range.strip_prefix(sign))
+            .and_then(|range| range.split(',').next())
+            .and_then(|line| line.parse().ok())
+            .unwrap_or(0)
+    };
+    let old = start('-');
+    let new = start('+');
+    (old, new)
+}
+
+/// The blocks around a commit's matching changes: added lines at the commit, removed
+/// lines at its parent
+fn context_blocks(dir: &Path, commit: &Commit, allow_tests: bool) -> Vec<ContextBlock> {
+    let mut blocks = Vec::new();
+    for file in &commit.files {
+        let sides = [
+            (&file.new_path, commit.hash.clone(), &file.added),
+            (&file.old_path, format!("{}^", commit.hash), &file.removed),
+        ];
+        for (path, revision, changes) in sides {
+            let Some(path) = path else { continue };
+            if changes.is_empty() {
+                continue;
+            }
+            let content = git(dir, &["show", &format!("{revision}:{path}")]).unwrap_or_default();
+            let short = format!(
+                "{}{}",
+                &commit.hash[..commit.hash.len().min(12)],
+                if revision.ends_with('^') { "^" } else { "" }
+            );
+            blocks.extend(blocks_around(&content, path, &short, changes, allow_tests));
+        }
+    }
+    blocks
+}
+
+/// Group `changes` by the AST block of `content` they fall in. Changes outside any
+/// block (or in files probe can't parse) are shown on their own
+fn blocks_around(
+    content: &str,
+    path: &str,
+    revision: &str,
+    changes: &[ChangedLine],
+    allow_tests: bool,
+) -> Vec<ContextBlock> {
+    let source: Vec<&str> = content.lines().collect();
+    let lines: HashSet<usize> = changes.iter().map(|change| change.line).collect();
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let parsed = parse_file_for_code_blocks(content, &extension, &lines, allow_tests, None)
+        .unwrap_or_default();
+
+    // Keyed by the block's range so overlapping results collapse into one block
+    let mut grouped: BTreeMap<(usize, usize), (String, Vec<ChangedLine>)> = BTreeMap::new();
+    for change in changes {
+        let block = parsed
+            .iter()
+            .filter(|block| (block.start_row + 1..=block.end_row + 1).contains(&change.line))
+            .min_by_key(|block| block.end_row - block.start_row);
+        let (range, node_type) = match block {
+            Some(block) => (
+                (block.start_row + 1, block.end_row + 1),
+                block.node_type.clone(),
+            ),
+            None => ((change.line, change.line), "line".to_string()),
+        };
+        grouped
+            .entry(range)
+            .or_insert_with(|| (node_type, Vec::new()))
+            .1
+            .push(change.clone());
+    }
+
+    grouped
+        .into_iter()
+        .map(|((start, end), (node_type, changes))| {
+            let code = source
+                .get(start.saturating_sub(1)..end.min(source.len()))
+                .map(|lines| lines.join("\n"))
+                .unwrap_or_else(|| {
+                    changes
+                        .iter()
+                        .map(|change| change.text.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                });
+            ContextBlock {
+                file: path.to_string(),
+                revision: revision.to_string(),
+                lines: (start, end),
+                node_type,
+                code,
+                changes,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\x1eabc123\x1f1700000000\x1f2023-11-14\x1fAda\x1fUse the new loader
+
+diff --git a/src/config.rs b/src/config.rs
+index 1111111..2222222 100644
+--- a/src/config.rs
++++ b/src/config.rs
+@@ -3 +3,2 @@ fn load() {
+-    let config = old_loader(path);
++    let config = new_loader(path);
++    validate(&config);
+diff --git a/README.md b/README.md
+index 3333333..4444444 100644
+--- a/README.md
++++ b/README.md
+@@ -10,0 +11 @@
++Unrelated line
+\x1edef456\x1f1600000000\x1f2020-09-13\x1fGrace\x1fAdd config loading
+
+diff --git a/src/config.rs b/src/config.rs
+new file mode 100644
+index 0000000..1111111
+--- /dev/null
++++ b/src/config.rs
+@@ -0,0 +1,5 @@
++fn load() {
++    let path = \"probe.toml\";
++    let config = old_loader(path);
++    config
++}
+";
+
+    #[test]
+    fn test_parse_log_keeps_matching_lines() {
+        let commits = parse_log(LOG, &Regex::new("loader").unwrap());
+        assert_eq!(commits.len(), 2);
+
+        let latest = &commits[0];
+        assert_eq!(latest.hash, "abc123");
+        assert_eq!(latest.author, "Ada");
+        assert_eq!(latest.score(), 2);
+        assert_eq!(latest.files.len(), 1);
+        let file = &latest.files[0];
+        assert_eq!(file.old_path.as_deref(), Some("src/config.rs"));
+        assert_eq!(
+            file.removed,
+            vec![ChangedLine {
+                line: 3,
+                kind: '-',
+                text: "    let config = old_loader(path);".to_string()
+            }]
+        );
+        assert_eq!(file.added[0].line, 3);
+
+        let first = &commits[1];
+        assert_eq!(first.files[0].old_path, None);
+        assert_eq!(first.files[0].added[0].line, 3);
+    }
+
+    #[test]
+    fn test_blocks_around_uses_the_enclosing_function() {
+        let content =
+            "use std::fs;\n\nfn load() {\n    let config = new_loader();\n    config\n}\n";
+        let changes = vec![ChangedLine {
+            line: 4,
+            kind: '+',
+            text: "    let config = new_loader();".to_string(),
+        }];
+        let blocks = blocks_around(content, "src/config.rs", "abc123", &changes, false);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].lines, (3, 6));
+        assert!(blocks[0].code.starts_with("fn load() {"));
+
+        let blocks = blocks_around("a\nb\n", "notes.txt", "abc123", &changes, false);
+        assert_eq!(blocks[0].node_type, "line");
+        assert_eq!(blocks[0].code, "    let config = new_loader();");
+    }
+}
@@ -0,0 +1,125 @@
+//! `probe index`: build the on-disk file index for a directory, and with `--watch`
+//! keep it current so searches skip walking the tree.
+//!
+//! Filesystem events only re-walk the paths they name, so a watcher misses files that an
+//! edited ignore file now ignores or brings back. SIGHUP rebuilds the index from a fresh
+//! walk, dropping stale entries without restarting the watcher.
+
+use crate::reload::{self, Reloads};
+use anyhow::{Context, Result};
+use colored::*;
+use notify::{RecursiveMode, Watcher};
+use probe_code::search::file_index::{FileIndex, HEARTBEAT_INTERVAL};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// How long to wait for more events before applying a batch, so a save that
+/// touches several files (or an editor's write-and-rename) is applied once
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub struct IndexParams {
+    pub path: PathBuf,
+    pub watch: bool,
+    pub allow_tests: bool,
+    pub ignore: Vec<String>,
+    pub no_gitignore: bool,
+}
+
+pub fn handle_index(params: IndexParams) -> Result<()> {
+    let start = Instant::now();
+    let mut index = FileIndex::build(
+        &params.path,
+        params.allow_tests,
+        &params.ignore,
+        params.no_gitignore,
+    )?;
+    let storage = index.save()?;
+    println!(
+        "Indexed {} files in {} ({:.2?})",
+        index.files.len(),
+        index.root.display(),
+        start.elapsed()
+    );
+    println!("{} {}", "Index:".bold().green(), storage.display());
+
+    if !params.watch {
+        return Ok(());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).context("Failed to start the filesystem watcher")?;
+    watcher
+        .watch(&index.root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", index.root.display()))?;
+    index.touch_heartbeat()?;
+    reload::install_sighup_handler();
+    let reloads = Reloads::default();
+    println!(
+        "Watching {} for changes (SIGHUP to re-index, Ctrl-C to stop)",
+        index.root.display()
+    );
+
+    let mut last_heartbeat = Instant::now();
+    loop {
+        let mut changed = BTreeSet::new();
+        match rx.recv_timeout(HEARTBEAT_INTERVAL) {
+            Ok(event) => collect_paths(event, &mut changed),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect_paths(event, &mut changed);
+        }
+
+        if reloads.pending() {
+            // A fresh walk covers the changed paths too
+            let start = Instant::now();
+            index = FileIndex::build(
+                &params.path,
+                params.allow_tests,
+                &params.ignore,
+                params.no_gitignore,
+            )?;
+            index.save()?;
+            index.touch_heartbeat()?;
+            last_heartbeat = Instant::now();
+            println!(
+                "Reloaded: re-indexed {} files ({:.2?})",
+                index.files.len(),
+                start.elapsed()
+            );
+        } else if !changed.is_empty() {
+            let changed: Vec<PathBuf> = changed.into_iter().collect();
+            let update = index.update_paths(&changed);
+            if !update.is_empty() {
+                index.save()?;
+                println!(
+                    "Updated index: {} added, {} modified, {} removed ({} files)",
+                    update.added,
+                    update.modified,
+                    update.removed,
+                    index.files.len()
+                );
+            }
+        }
+        if last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+            index.touch_heartbeat()?;
+            last_heartbeat = Instant::now();
+        }
+    }
+
+    index.clear_heartbeat();
+    Ok(())
+}
+
+fn collect_paths(event: notify::Result<notify::Event>, changed: &mut BTreeSet<PathBuf>) {
+    match event {
+        // Access events don't change the file list or metadata
+        Ok(event) if !event.kind.is_access() => changed.extend(event.paths),
+        Ok(_) => {}
+        Err(e) => eprintln!("Watch error: {e}"),
+    }
+}
@@ -0,0 +1,25 @@
+//! Cooperative cancellation of searches and extractions.
+//!
+//! The CLI calls [`interrupt`] on the first Ctrl-C. File searching, file processing and
+//! extraction check [`is_interrupted`] before each file and stop picking up new work, so
+//! the results collected so far are still ranked and printed, marked as interrupted.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Ask running searches and extractions to stop and return what they have so far.
+/// Stays set, so later searches return nothing, until [`reset`] is called.
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Whether [`interrupt`] was called
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Clear the interrupt, for callers that keep searching after cancelling one search
+pub fn reset() {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+}
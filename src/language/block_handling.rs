@@ -4,7 +4,7 @@ pub fn merge_code_blocks(
     code_blocks: Vec<crate::models::CodeBlock>,
 ) -> Vec<crate::models::CodeBlock> {
     let mut merged_blocks: Vec<crate::models::CodeBlock> = Vec::new();
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     for block in code_blocks {
         if let Some(last) = merged_blocks.last_mut() {
@@ -13,8 +13,8 @@ pub fn merge_code_blocks(
 
             if block.start_row <= last.end_row + threshold {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Merging blocks: {} ({}-{}) with {} ({}-{})",
+                    tracing::trace!(
+                        "Merging blocks: {} ({}-{}) with {} ({}-{})",
                         last.node_type,
                         last.start_row + 1,
                         last.end_row + 1,
@@ -34,13 +34,10 @@ pub fn merge_code_blocks(
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: After merging: {len} blocks",
-            len = merged_blocks.len()
-        );
+        tracing::trace!("After merging: {len} blocks", len = merged_blocks.len());
         for (i, block) in merged_blocks.iter().enumerate() {
-            println!(
-                "DEBUG:   Block {}: type={}, lines={}-{}",
+            tracing::trace!(
+                "  Block {}: type={}, lines={}-{}",
                 i + 1,
                 block.node_type,
                 block.start_row + 1,
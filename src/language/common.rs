@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 use tree_sitter::Node;
 
+use probe_code::language::language_trait::LanguageImpl;
+
 /// Helper function to collect all node types in the AST
 pub fn collect_node_types(node: Node, node_types: &mut HashSet<String>) {
     node_types.insert(node.kind().to_string());
@@ -10,3 +12,73 @@ pub fn collect_node_types(node: Node, node_types: &mut HashSet<String>) {
         collect_node_types(child, node_types);
     }
 }
+
+/// Find the first attribute/decorator/annotation attached to a node, walking back over
+/// consecutive attribute siblings. Returns the node itself when it has no attributes.
+pub fn find_attributed_start<'a>(node: Node<'a>, language_impl: &dyn LanguageImpl) -> Node<'a> {
+    let mut start = node;
+    while let Some(prev) = start.prev_sibling() {
+        if !language_impl.is_attribute_node(&prev) {
+            break;
+        }
+        start = prev;
+    }
+    start
+}
+
+/// Find the item an attribute/decorator/annotation node is attached to, walking forward
+/// over any further attributes. Returns `None` when the node is not an attribute.
+pub fn find_attributed_item<'a>(
+    node: Node<'a>,
+    language_impl: &dyn LanguageImpl,
+) -> Option<Node<'a>> {
+    if !language_impl.is_attribute_node(&node) {
+        return None;
+    }
+    let mut next = node.next_sibling();
+    while let Some(candidate) = next {
+        if !language_impl.is_attribute_node(&candidate) {
+            return Some(candidate);
+        }
+        next = candidate.next_sibling();
+    }
+    None
+}
+
+/// Check if a JavaScript or TypeScript node is exported: an `export` statement, the
+/// declaration in one, or a member of an exported class or interface that isn't
+/// `private`, `protected` or `#private`
+pub fn is_exported(node: &Node, source: &[u8]) -> bool {
+    if node.kind() == "export_statement"
+        || node
+            .parent()
+            .is_some_and(|parent| parent.kind() == "export_statement")
+    {
+        return true;
+    }
+    if !matches!(
+        node.kind(),
+        "method_definition"
+            | "public_field_definition"
+            | "field_definition"
+            | "method_signature"
+            | "property_signature"
+            | "abstract_method_signature"
+    ) {
+        return false;
+    }
+    let mut cursor = node.walk();
+    let hidden = node.children(&mut cursor).any(|child| {
+        child.kind() == "private_property_identifier"
+            || (child.kind() == "accessibility_modifier"
+                && child
+                    .utf8_text(source)
+                    .is_ok_and(|modifier| modifier != "public"))
+    });
+    // Members sit in the class or interface body
+    !hidden
+        && node
+            .parent()
+            .and_then(|body| body.parent())
+            .is_some_and(|owner| is_exported(&owner, source))
+}
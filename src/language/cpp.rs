@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for C++
@@ -38,7 +39,7 @@ impl LanguageImpl for CppLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // C++: Check function_definition nodes with test in the name
@@ -52,7 +53,7 @@ impl LanguageImpl for CppLanguage {
                             let name = subchild.utf8_text(source).unwrap_or("");
                             if name.contains("test") || name.contains("Test") {
                                 if debug_mode {
-                                    println!("DEBUG: Test node detected (C++): test function");
+                                    trace!("Test node detected (C++): test function");
                                 }
                                 return true;
                             }
@@ -64,4 +65,13 @@ impl LanguageImpl for CppLanguage {
 
         false
     }
+
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        // Functions and globals are visible to other files unless declared `static`
+        let mut cursor = node.walk();
+        let is_static = node.children(&mut cursor).any(|child| {
+            child.kind() == "storage_class_specifier" && child.utf8_text(source) == Ok("static")
+        });
+        !is_static
+    }
 }
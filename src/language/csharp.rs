@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for C#
@@ -25,6 +26,12 @@ impl LanguageImpl for CSharpLanguage {
         "cs"
     }
 
+    fn is_doc_comment(&self, node: &Node, source: &[u8]) -> bool {
+        // XML doc comments (`///`) and block doc comments
+        let text = node.utf8_text(source).unwrap_or("");
+        node.kind() == "comment" && (text.starts_with("///") || text.starts_with("/**"))
+    }
+
     fn is_acceptable_parent(&self, node: &Node) -> bool {
         matches!(
             node.kind(),
@@ -42,7 +49,7 @@ impl LanguageImpl for CSharpLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // C#: Check for test methods with attributes
@@ -68,7 +75,7 @@ impl LanguageImpl for CSharpLanguage {
 
             if has_test_attribute {
                 if debug_mode {
-                    println!("DEBUG: Test node detected (C#): test attribute");
+                    trace!("Test node detected (C#): test attribute");
                 }
                 return true;
             }
@@ -79,7 +86,7 @@ impl LanguageImpl for CSharpLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("Test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (C#): Test method");
+                            trace!("Test node detected (C#): Test method");
                         }
                         return true;
                     }
@@ -97,7 +104,7 @@ impl LanguageImpl for CSharpLanguage {
                     let attr_text = child.utf8_text(source).unwrap_or("");
                     if attr_text.contains("[TestClass") || attr_text.contains("[TestFixture") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (C#): test class attribute");
+                            trace!("Test node detected (C#): test class attribute");
                         }
                         return true;
                     }
@@ -110,9 +117,7 @@ impl LanguageImpl for CSharpLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.ends_with("Tests") || name.ends_with("Test") {
                         if debug_mode {
-                            println!(
-                                "DEBUG: Test node detected (C#): Test class naming convention"
-                            );
+                            trace!("Test node detected (C#): Test class naming convention");
                         }
                         return true;
                     }
@@ -123,6 +128,21 @@ impl LanguageImpl for CSharpLanguage {
         false
     }
 
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        let mut cursor = node.walk();
+        let public = node
+            .children(&mut cursor)
+            .any(|child| child.kind() == "modifier" && child.utf8_text(source) == Ok("public"));
+        // Interface members are public without saying so
+        public
+            || node
+                .parent()
+                .filter(|body| body.kind() == "declaration_list")
+                .and_then(|body| body.parent())
+                .filter(|owner| owner.kind() == "interface_declaration")
+                .is_some_and(|interface| self.is_public(&interface, source))
+    }
+
     fn find_parent_function<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
         let mut current = node;
 
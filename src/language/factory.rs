@@ -14,9 +14,50 @@ use probe_code::language::rust::RustLanguage;
 use probe_code::language::swift::SwiftLanguage;
 use probe_code::language::typescript::TypeScriptLanguage;
 use probe_code::language::yaml::YamlLanguage;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+static EXTENSION_MAPPINGS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Parse files with the extensions in `mappings` as the language of the mapped
+/// extension (`tpl` -> `html`), e.g. from the `[languages]` section of probe.toml.
+///
+/// Returns `false` if mappings were already set for this process.
+pub fn set_extension_mappings(mappings: HashMap<String, String>) -> bool {
+    EXTENSION_MAPPINGS.set(mappings).is_ok()
+}
+
+/// The extension a language is known by to [`get_language_impl`]; accepts language
+/// names (`rust`, `csharp`) as well as extensions
+pub fn language_extension(language: &str) -> Option<&'static str> {
+    let extension = match language.to_lowercase().as_str() {
+        "rust" | "rs" => "rs",
+        "javascript" | "js" | "jsx" => "js",
+        "typescript" | "ts" => "ts",
+        "tsx" => "tsx",
+        "python" | "py" => "py",
+        "go" => "go",
+        "c" | "h" => "c",
+        "cpp" | "c++" | "cc" | "cxx" | "hpp" | "hxx" => "cpp",
+        "java" => "java",
+        "ruby" | "rb" => "rb",
+        "php" => "php",
+        "swift" => "swift",
+        "csharp" | "c#" | "cs" => "cs",
+        "html" | "htm" => "html",
+        "markdown" | "md" => "md",
+        "yaml" | "yml" => "yaml",
+        _ => return None,
+    };
+    Some(extension)
+}
 
 /// Factory function to get the appropriate language implementation based on file extension
 pub fn get_language_impl(extension: &str) -> Option<Box<dyn LanguageImpl>> {
+    let extension = EXTENSION_MAPPINGS
+        .get()
+        .and_then(|mappings| mappings.get(extension))
+        .map_or(extension, String::as_str);
     match extension {
         "rs" => Some(Box::new(RustLanguage::new())),
         "js" | "jsx" => Some(Box::new(JavaScriptLanguage::new())),
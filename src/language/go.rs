@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Go
@@ -28,22 +29,22 @@ impl LanguageImpl for GoLanguage {
     fn is_acceptable_parent(&self, node: &Node) -> bool {
         matches!(
             node.kind(),
-            "function_declaration" |
-            "method_declaration" |
-            "type_declaration" |
-            "struct_type" |
-            "interface_type" |
-            "const_declaration" |
-            "var_declaration" |
-            "const_spec" |
-            "var_spec" |
-            "short_var_declaration" |
-            "type_spec" // Added for type definitions
+            "function_declaration"
+                | "method_declaration"
+                | "type_declaration"
+                | "struct_type"
+                | "interface_type"
+                | "const_declaration"
+                | "var_declaration"
+                | "const_spec"
+                | "var_spec"
+                | "short_var_declaration"
+                | "type_spec" // Added for type definitions
         )
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Go: Check function_declaration nodes with names starting with Test
@@ -54,7 +55,7 @@ impl LanguageImpl for GoLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("Test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Go): Test function");
+                            trace!("Test node detected (Go): Test function");
                         }
                         return true;
                     }
@@ -66,11 +67,11 @@ impl LanguageImpl for GoLanguage {
     }
 
     fn find_parent_function<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         if debug_mode {
-            println!(
-                "DEBUG: Finding parent function for {node_kind}",
+            trace!(
+                "Finding parent function for {node_kind}",
                 node_kind = node.kind()
             );
         }
@@ -80,8 +81,8 @@ impl LanguageImpl for GoLanguage {
         while let Some(parent) = current.parent() {
             if parent.kind() == "function_declaration" || parent.kind() == "method_declaration" {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Found parent function: {parent_kind}",
+                    trace!(
+                        "Found parent function: {parent_kind}",
                         parent_kind = parent.kind()
                     );
                 }
@@ -91,8 +92,8 @@ impl LanguageImpl for GoLanguage {
         }
 
         if debug_mode {
-            println!(
-                "DEBUG: No parent function found for {node_kind}",
+            trace!(
+                "No parent function found for {node_kind}",
                 node_kind = node.kind()
             );
         }
@@ -100,6 +101,28 @@ impl LanguageImpl for GoLanguage {
         None
     }
 
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        // Exported names start with an upper case letter
+        let exported = |name: Option<Node>| {
+            name.and_then(|name| name.utf8_text(source).ok())
+                .and_then(|name| name.chars().next())
+                .is_some_and(char::is_uppercase)
+        };
+        match node.kind() {
+            // `type`, `const` and `var` blocks export whatever any of their specs export
+            "type_declaration" | "const_declaration" | "var_declaration" => {
+                let mut cursor = node.walk();
+                let public = node
+                    .named_children(&mut cursor)
+                    .any(|spec| exported(spec.child_by_field_name("name")));
+                public
+            }
+            _ => node
+                .child_by_field_name("name")
+                .is_none_or(|name| exported(Some(name))),
+        }
+    }
+
     fn get_symbol_signature(&self, node: &Node, source: &[u8]) -> Option<String> {
         match node.kind() {
             "function_declaration" => {
@@ -171,7 +194,7 @@ impl LanguageImpl for GoLanguage {
 
                         // Add type if present (in const_spec)
                         if let Some(type_node) = const_spec.child_by_field_name("type") {
-                            sig.push_str(" ");
+                            sig.push(' ');
                             let type_text = &source[type_node.start_byte()..type_node.end_byte()];
                             sig.push_str(&String::from_utf8_lossy(type_text));
                         }
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for HTML
@@ -238,7 +239,7 @@ impl LanguageImpl for HtmlLanguage {
     }
 
     fn is_acceptable_parent(&self, node: &Node) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Accept HTML structural elements and semantic containers
         let acceptable = matches!(
@@ -257,8 +258,8 @@ impl LanguageImpl for HtmlLanguage {
             let tag_name = self
                 .get_tag_name(node)
                 .unwrap_or_else(|| node.kind().to_string());
-            println!(
-                "DEBUG: HTML acceptable parent: {} ({}) at lines {}-{}",
+            trace!(
+                "HTML acceptable parent: {} ({}) at lines {}-{}",
                 tag_name,
                 node.kind(),
                 node.start_position().row + 1,
@@ -270,12 +271,12 @@ impl LanguageImpl for HtmlLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Check for test-related attributes
         if self.has_test_attributes(node, source) {
             if debug_mode {
-                println!("DEBUG: Test node detected (HTML): test-related attributes");
+                trace!("Test node detected (HTML): test-related attributes");
             }
             return true;
         }
@@ -285,10 +286,7 @@ impl LanguageImpl for HtmlLanguage {
             && self.has_test_content(node, source)
         {
             if debug_mode {
-                println!(
-                    "DEBUG: Test node detected (HTML): test-related {}",
-                    node.kind()
-                );
+                trace!("Test node detected (HTML): test-related {}", node.kind());
             }
             return true;
         }
@@ -296,7 +294,7 @@ impl LanguageImpl for HtmlLanguage {
         // Check for test-related script or style content (for regular elements)
         if self.is_test_script_or_style(node, source) {
             if debug_mode {
-                println!("DEBUG: Test node detected (HTML): test-related script/style");
+                trace!("Test node detected (HTML): test-related script/style");
             }
             return true;
         }
@@ -313,8 +311,8 @@ impl LanguageImpl for HtmlLanguage {
                 ) && self.has_test_content(node, source)
                 {
                     if debug_mode {
-                        println!(
-                            "DEBUG: Test node detected (HTML): test-related content in {}",
+                        trace!(
+                            "Test node detected (HTML): test-related content in {}",
                             tag_name
                         );
                     }
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Java
@@ -25,6 +26,12 @@ impl LanguageImpl for JavaLanguage {
         "java"
     }
 
+    fn is_doc_comment(&self, node: &Node, source: &[u8]) -> bool {
+        // Javadoc comments
+        matches!(node.kind(), "block_comment" | "comment")
+            && node.utf8_text(source).unwrap_or("").starts_with("/**")
+    }
+
     fn is_acceptable_parent(&self, node: &Node) -> bool {
         matches!(
             node.kind(),
@@ -41,7 +48,7 @@ impl LanguageImpl for JavaLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Java: Check method_declaration nodes with @Test annotation
@@ -55,7 +62,7 @@ impl LanguageImpl for JavaLanguage {
                             let annotation_text = annotation.utf8_text(source).unwrap_or("");
                             if annotation_text.contains("@Test") {
                                 if debug_mode {
-                                    println!("DEBUG: Test node detected (Java): @Test method");
+                                    trace!("Test node detected (Java): @Test method");
                                 }
                                 return true;
                             }
@@ -67,4 +74,21 @@ impl LanguageImpl for JavaLanguage {
 
         false
     }
+
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        let mut cursor = node.walk();
+        let public = node.children(&mut cursor).any(|child| {
+            child.kind() == "modifiers"
+                && child
+                    .utf8_text(source)
+                    .is_ok_and(|modifiers| modifiers.split_whitespace().any(|m| m == "public"))
+        });
+        // Interface members are public without saying so
+        public
+            || node
+                .parent()
+                .filter(|body| body.kind() == "interface_body")
+                .and_then(|body| body.parent())
+                .is_some_and(|interface| self.is_public(&interface, source))
+    }
 }
@@ -22,6 +22,33 @@ pub trait LanguageImpl {
         None
     }
 
+    /// Check if a comment node documents the item that follows it
+    /// By default every comment kind counts; languages with a dedicated doc comment
+    /// syntax (e.g. `///` or `/** */`) override this to skip ordinary comments.
+    fn is_doc_comment(&self, node: &Node, _source: &[u8]) -> bool {
+        matches!(
+            node.kind(),
+            "comment" | "line_comment" | "block_comment" | "doc_comment"
+        )
+    }
+
+    /// Check if a node is an attribute, decorator or annotation attached to the following item
+    /// Such nodes are siblings of the item in the AST (e.g. Rust `#[derive(...)]`, Python
+    /// `@decorator`), so block resolution extends upward over them.
+    fn is_attribute_node(&self, node: &Node) -> bool {
+        matches!(
+            node.kind(),
+            "attribute_item" | "decorator" | "annotation" | "marker_annotation" | "attribute_list"
+        )
+    }
+
+    /// Check if a node is part of the public API of its module, for `--public-only`
+    /// By default everything is; languages with visibility rules (`pub`, `export`,
+    /// exported capitalized names, `public`) override this.
+    fn is_public(&self, _node: &Node, _source: &[u8]) -> bool {
+        true
+    }
+
     /// Extract the symbol signature without implementation body
     /// Returns a clean signature for functions, structs, classes, methods, constants, etc.
     fn get_symbol_signature(&self, _node: &Node, _source: &[u8]) -> Option<String> {
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Markdown
@@ -100,7 +101,7 @@ impl LanguageImpl for MarkdownLanguage {
     }
 
     fn is_acceptable_parent(&self, node: &Node) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Accept markdown structural elements
         let acceptable = matches!(
@@ -122,8 +123,8 @@ impl LanguageImpl for MarkdownLanguage {
         );
 
         if debug_mode && acceptable {
-            println!(
-                "DEBUG: Markdown acceptable parent: {} at lines {}-{}",
+            trace!(
+                "Markdown acceptable parent: {} at lines {}-{}",
                 node.kind(),
                 node.start_position().row + 1,
                 node.end_position().row + 1
@@ -134,7 +135,7 @@ impl LanguageImpl for MarkdownLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // In markdown, we can consider test-related sections
         // Check for headers that contain test-related keywords
@@ -149,7 +150,7 @@ impl LanguageImpl for MarkdownLanguage {
                     || lower_text.contains("demo");
 
                 if debug_mode && is_test {
-                    println!("DEBUG: Test node detected (Markdown): test-related header");
+                    trace!("Test node detected (Markdown): test-related header");
                 }
                 return is_test;
             }
@@ -166,7 +167,7 @@ impl LanguageImpl for MarkdownLanguage {
                     || lower_lang == "pytest";
 
                 if debug_mode && is_test_lang {
-                    println!("DEBUG: Test node detected (Markdown): test-related code block");
+                    trace!("Test node detected (Markdown): test-related code block");
                 }
                 return is_test_lang;
             }
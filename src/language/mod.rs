@@ -30,7 +30,10 @@ pub mod yaml;
 
 // Re-export items for backward compatibility
 pub use parser::{parse_file_for_code_blocks, parse_file_for_code_blocks_with_tree};
-pub use parser_pool::{clear_parser_pool, get_pool_stats, get_pooled_parser, return_pooled_parser};
+pub use parser_pool::{
+    clear_parser_pool, format_parser_stats, get_pool_stats, get_pooled_parser, parser_stats,
+    return_pooled_parser, set_pool_config, timed_parse, PoolConfig,
+};
 pub use test_detection::is_test_file;
 #[allow(unused_imports)]
 pub use tree_cache::{
@@ -2,12 +2,15 @@ use anyhow::{Context, Result};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
-use tree_sitter::{Node, Parser as TSParser};
+use tracing::trace;
+use tree_sitter::Node;
 
+use probe_code::language::common::{find_attributed_item, find_attributed_start};
 use probe_code::language::factory::get_language_impl;
 use probe_code::language::language_trait::LanguageImpl;
 use probe_code::language::tree_cache;
 use probe_code::models::CodeBlock;
+use probe_code::warnings::{warn_once, WarningKind};
 
 /// Node type priority for deterministic selection when multiple important types match same content
 /// Higher index = higher priority (more specific types should win)
@@ -163,6 +166,7 @@ impl CachedNodeInfo {
         language_impl: &dyn LanguageImpl,
         content: &[u8],
         allow_tests: bool,
+        include_attributes: bool,
     ) -> Self {
         // Determine the representative node based on the same logic used in the live processing path
         let mut rep_node = info.node; // Default to self
@@ -206,21 +210,37 @@ impl CachedNodeInfo {
             .context_node
             .map(|ctx| language_impl.is_test_node(&ctx, content));
 
+        // Acceptable nodes start at their first attached attribute/decorator, if any
+        let block_start = |node: Node<'_>| {
+            let start = if include_attributes && language_impl.is_acceptable_parent(&node) {
+                find_attributed_start(node, language_impl)
+            } else {
+                node
+            };
+            (start.start_byte(), start.start_position().row)
+        };
+        let (start_byte, start_row) = block_start(info.node);
+        let context_start = info.context_node.map(block_start);
+
         CachedNodeInfo {
             // Original node details
-            start_byte: info.node.start_byte(),
+            start_byte,
             end_byte: info.node.end_byte(),
-            start_row: info.node.start_position().row,
+            start_row,
             end_row: info.node.end_position().row,
             node_kind: info.node.kind().to_string(),
             is_comment: info.is_comment,
             is_test: info.is_test, // Original node test status
             original_node_is_acceptable: original_acceptable,
             // Context node details
-            context_node_bytes: info.context_node.map(|n| (n.start_byte(), n.end_byte())),
+            context_node_bytes: info
+                .context_node
+                .zip(context_start)
+                .map(|(n, (start_byte, _))| (start_byte, n.end_byte())),
             context_node_rows: info
                 .context_node
-                .map(|n| (n.start_position().row, n.end_position().row)),
+                .zip(context_start)
+                .map(|(n, (_, start_row))| (start_row, n.end_position().row)),
             context_node_kind: info.context_node.map(|n| n.kind().to_string()),
             context_node_is_test: context_test, // Context node test status
             // specificity: info.specificity, // Original node specificity - REMOVED (unused)
@@ -325,13 +345,13 @@ fn find_acceptable_child<'a>(node: Node<'a>, language_impl: &dyn LanguageImpl) -
 
 /// Finds the immediate next node that follows a given node in the AST
 fn find_immediate_next_node(node: Node<'_>) -> Option<Node<'_>> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // First try direct next sibling
     if let Some(next) = node.next_sibling() {
         if debug_mode {
-            eprintln!(
-                "DEBUG: Found immediate next sibling: type='{}', lines={}-{}",
+            trace!(
+                "Found immediate next sibling: type='{}', lines={}-{}",
                 next.kind(),
                 next.start_position().row + 1,
                 next.end_position().row + 1
@@ -344,8 +364,8 @@ fn find_immediate_next_node(node: Node<'_>) -> Option<Node<'_>> {
     if let Some(parent) = node.parent() {
         if let Some(next_parent) = parent.next_sibling() {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found parent's next sibling: type='{}', lines={}-{}",
+                trace!(
+                    "Found parent's next sibling: type='{}', lines={}-{}",
                     next_parent.kind(),
                     next_parent.start_position().row + 1,
                     next_parent.end_position().row + 1
@@ -356,7 +376,7 @@ fn find_immediate_next_node(node: Node<'_>) -> Option<Node<'_>> {
     }
 
     if debug_mode {
-        eprintln!("DEBUG: No immediate next node found");
+        trace!("No immediate next node found");
     }
     None
 }
@@ -371,8 +391,8 @@ fn find_comment_context_node<'a>(
     let start_row = comment_node.start_position().row;
 
     if debug_mode {
-        eprintln!(
-            "DEBUG: Finding context for comment at lines {}-{}: {}",
+        trace!(
+            "Finding context for comment at lines {}-{}: {}",
             comment_node.start_position().row + 1,
             comment_node.end_position().row + 1,
             comment_node.kind()
@@ -398,8 +418,8 @@ fn find_comment_context_node<'a>(
         // Found a non-comment sibling
         if language_impl.is_acceptable_parent(&sibling) {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found next non-comment sibling for comment at line {}: type='{}', lines={}-{}",
+                trace!(
+                    "Found next non-comment sibling for comment at line {}: type='{}', lines={}-{}",
                     start_row + 1,
                     sibling.kind(),
                     sibling.start_position().row + 1,
@@ -411,8 +431,8 @@ fn find_comment_context_node<'a>(
             // If next sibling isn't acceptable, check its children
             if let Some(child) = find_acceptable_child(sibling, language_impl) {
                 if debug_mode {
-                    eprintln!(
-                        "DEBUG: Found acceptable child in next non-comment sibling for comment at line {}: type='{}', lines={}-{}",
+                    trace!(
+                        "Found acceptable child in next non-comment sibling for comment at line {}: type='{}', lines={}-{}",
                         start_row + 1,
                         child.kind(),
                         child.start_position().row + 1,
@@ -436,8 +456,8 @@ fn find_comment_context_node<'a>(
         if let Some(prev_sibling) = find_prev_sibling(comment_node) {
             if language_impl.is_acceptable_parent(&prev_sibling) {
                 if debug_mode {
-                    eprintln!(
-                        "DEBUG: Found previous sibling for comment at line {}: type='{}', lines={}-{}",
+                    trace!(
+                        "Found previous sibling for comment at line {}: type='{}', lines={}-{}",
                         start_row + 1,
                         prev_sibling.kind(),
                         prev_sibling.start_position().row + 1,
@@ -449,8 +469,8 @@ fn find_comment_context_node<'a>(
                 // If previous sibling isn't acceptable, check its children
                 if let Some(child) = find_acceptable_child(prev_sibling, language_impl) {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Found acceptable child in previous sibling for comment at line {}: type='{}', lines={}-{}",
+                        trace!(
+                            "Found acceptable child in previous sibling for comment at line {}: type='{}', lines={}-{}",
                             start_row + 1,
                             child.kind(),
                             child.start_position().row + 1,
@@ -468,8 +488,8 @@ fn find_comment_context_node<'a>(
     while let Some(parent) = current.parent() {
         if language_impl.is_acceptable_parent(&parent) {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found parent for comment at line {}: type='{}', lines={}-{}",
+                trace!(
+                    "Found parent for comment at line {}: type='{}', lines={}-{}",
                     start_row + 1,
                     parent.kind(),
                     parent.start_position().row + 1,
@@ -485,8 +505,8 @@ fn find_comment_context_node<'a>(
     if let Some(next_node) = find_immediate_next_node(comment_node) {
         if language_impl.is_acceptable_parent(&next_node) {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Using immediate next acceptable node: type='{}', lines={}-{}",
+                trace!(
+                    "Using immediate next acceptable node: type='{}', lines={}-{}",
                     next_node.kind(),
                     next_node.start_position().row + 1,
                     next_node.end_position().row + 1
@@ -498,8 +518,8 @@ fn find_comment_context_node<'a>(
         // Look for acceptable child in the next node
         if let Some(child) = find_acceptable_child(next_node, language_impl) {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found acceptable child in next node: type='{}', lines={}-{}",
+                trace!(
+                    "Found acceptable child in next node: type='{}', lines={}-{}",
                     child.kind(),
                     child.start_position().row + 1,
                     child.end_position().row + 1
@@ -510,7 +530,7 @@ fn find_comment_context_node<'a>(
     }
 
     if debug_mode {
-        eprintln!("DEBUG: No related node found for the comment");
+        trace!("No related node found for the comment");
     }
     None
 }
@@ -524,6 +544,7 @@ fn build_sparse_line_map<'a>(
     language_impl: &dyn LanguageImpl,
     content: &[u8],
     allow_tests: bool,
+    include_attributes: bool,
     debug_mode: bool,
 ) -> SparseLineMap {
     // Create buffer zones around requested lines for context
@@ -535,8 +556,8 @@ fn build_sparse_line_map<'a>(
     sorted_lines.sort();
 
     if debug_mode {
-        eprintln!(
-            "DEBUG: SPARSE OPTIMIZATION - Building sparse line map for {} lines",
+        trace!(
+            "SPARSE OPTIMIZATION - Building sparse line map for {} lines",
             sorted_lines.len()
         );
     }
@@ -565,8 +586,8 @@ fn build_sparse_line_map<'a>(
     let mut sparse_map = SparseLineMap::new(base_offset);
 
     if debug_mode {
-        eprintln!(
-            "DEBUG: SPARSE OPTIMIZATION - Target ranges: {target_ranges:?}, base_offset: {base_offset}"
+        trace!(
+            "SPARSE OPTIMIZATION - Target ranges: {target_ranges:?}, base_offset: {base_offset}"
         );
     }
 
@@ -577,6 +598,7 @@ fn build_sparse_line_map<'a>(
         language_impl,
         content,
         allow_tests,
+        include_attributes,
         debug_mode,
         None, // Initial ancestor context is None
         &target_ranges,
@@ -588,7 +610,10 @@ fn build_sparse_line_map<'a>(
     }
 
     if debug_mode {
-        eprintln!("DEBUG: SPARSE OPTIMIZATION - Built sparse line map with {} mappings (vs full file approach)", sparse_map.len());
+        trace!(
+            "SPARSE OPTIMIZATION - Built sparse line map with {} mappings (vs full file approach)",
+            sparse_map.len()
+        );
     }
 
     sparse_map
@@ -602,6 +627,7 @@ fn process_node_sparse<'a>(
     language_impl: &dyn LanguageImpl,
     content: &[u8],
     allow_tests: bool,
+    include_attributes: bool,
     debug_mode: bool,
     current_ancestor: Option<Node<'a>>,
     target_ranges: &[(usize, usize)],
@@ -620,8 +646,8 @@ fn process_node_sparse<'a>(
 
     if !intersects_target {
         if debug_mode {
-            eprintln!(
-                "DEBUG: SPARSE - Skipping node '{}' at lines {}-{} (no intersection)",
+            trace!(
+                "SPARSE - Skipping node '{}' at lines {}-{} (no intersection)",
                 node.kind(),
                 start_row + 1,
                 end_row + 1
@@ -645,6 +671,13 @@ fn process_node_sparse<'a>(
     // Determine context node
     let context_node = if is_comment {
         find_comment_context_node(node, language_impl, debug_mode)
+    } else if let Some(item) = include_attributes
+        .then(|| find_attributed_item(node, language_impl))
+        .flatten()
+        .filter(|item| language_impl.is_acceptable_parent(item))
+    {
+        // Attributes resolve to the item they are attached to
+        Some(item)
     } else if !language_impl.is_acceptable_parent(&node) {
         current_ancestor
     } else {
@@ -696,12 +729,13 @@ fn process_node_sparse<'a>(
                     language_impl,
                     content,
                     allow_tests,
+                    include_attributes,
                 );
                 sparse_map.insert(line, cached_info);
 
                 if debug_mode {
-                    eprintln!(
-                        "DEBUG: SPARSE - Stored mapping for line {}: type='{}', is_comment={}, context={:?}",
+                    trace!(
+                        "SPARSE - Stored mapping for line {}: type='{}', is_comment={}, context={:?}",
                         line + 1,
                         node.kind(),
                         is_comment,
@@ -731,6 +765,7 @@ fn process_node_sparse<'a>(
             language_impl,
             content,
             allow_tests,
+            include_attributes,
             debug_mode,
             next_ancestor,
             target_ranges,
@@ -770,8 +805,8 @@ fn process_node<'a>(
 
     if !intersects_target {
         if debug_mode {
-            eprintln!(
-                "DEBUG: AST Node filtering - skipping node '{}' at lines {}-{} (no intersection with target ranges)",
+            trace!(
+                "AST Node filtering - skipping node '{}' at lines {}-{} (no intersection with target ranges)",
                 node.kind(),
                 start_row + 1,
                 end_row + 1
@@ -914,13 +949,13 @@ fn process_sparse_line_map(
         let line_idx = line.saturating_sub(1); // Adjust for 0-based indexing
 
         if debug_mode {
-            eprintln!("DEBUG: Processing line {line} from sparse cache");
+            trace!("Processing line {line} from sparse cache");
         }
 
         if let Some(info) = sparse_line_map.get(line_idx) {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found sparse cached node info for line {}: original_type='{}', original_lines={}-{}, is_comment={}, is_test={}, context_kind={:?}, context_lines={:?}, context_is_test={:?}",
+                trace!(
+                    "Found sparse cached node info for line {}: original_type='{}', original_lines={}-{}, is_comment={}, is_test={}, context_kind={:?}, context_lines={:?}, context_is_test={:?}",
                     line,
                     info.node_kind,
                     info.start_row + 1,
@@ -943,7 +978,7 @@ fn process_sparse_line_map(
             // Handle Comments
             if info.is_comment {
                 if debug_mode {
-                    eprintln!("DEBUG: Sparse Cache: Handling comment node at line {line}");
+                    trace!("Sparse Cache: Handling comment node at line {line}");
                 }
                 // Check for context node
                 if let (Some(ctx_rows), Some(ctx_bytes), Some(ctx_kind), Some(ctx_is_test)) = (
@@ -958,8 +993,8 @@ fn process_sparse_line_map(
 
                     if !should_use_context {
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Sparse Cache: Skipping test context node at lines {}-{}, type: {}",
+                            trace!(
+                                "Sparse Cache: Skipping test context node at lines {}-{}, type: {}",
                                 ctx_rows.0 + 1,
                                 ctx_rows.1 + 1,
                                 ctx_kind
@@ -986,8 +1021,8 @@ fn process_sparse_line_map(
                                 parent_context: None,
                             });
                             if debug_mode {
-                                eprintln!(
-                                    "DEBUG: Sparse Cache: Potential merged block (comment + context) at lines {}-{}, type: {}",
+                                trace!(
+                                    "Sparse Cache: Potential merged block (comment + context) at lines {}-{}, type: {}",
                                     merged_start_row + 1, merged_end_row + 1, ctx_kind
                                 );
                             }
@@ -1010,8 +1045,8 @@ fn process_sparse_line_map(
 
                                 if should_filter {
                                     if debug_mode {
-                                        eprintln!(
-                                            "DEBUG: Sparse Cache: Filtering out complete test function block at lines {}-{}, type: {}",
+                                        trace!(
+                                            "Sparse Cache: Filtering out complete test function block at lines {}-{}, type: {}",
                                             block.start_row + 1, block.end_row + 1, block.node_type
                                         );
                                     }
@@ -1041,8 +1076,8 @@ fn process_sparse_line_map(
                             parent_context: None,
                         });
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Sparse Cache: Potential individual comment block at lines {}-{}",
+                            trace!(
+                                "Sparse Cache: Potential individual comment block at lines {}-{}",
                                 info.start_row + 1,
                                 info.end_row + 1
                             );
@@ -1055,8 +1090,8 @@ fn process_sparse_line_map(
                 // Skip original test nodes if not allowed
                 if !allow_tests && info.is_test {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Sparse Cache: Skipping original test node at lines {}-{}",
+                        trace!(
+                            "Sparse Cache: Skipping original test node at lines {}-{}",
                             info.start_row + 1,
                             info.end_row + 1
                         );
@@ -1073,8 +1108,8 @@ fn process_sparse_line_map(
                 ) {
                     if !allow_tests && ctx_is_test {
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Sparse Cache: Skipping test context node (ancestor) at lines {}-{}",
+                            trace!(
+                                "Sparse Cache: Skipping test context node (ancestor) at lines {}-{}",
                                 ctx_rows.0 + 1, ctx_rows.1 + 1
                             );
                         }
@@ -1094,8 +1129,8 @@ fn process_sparse_line_map(
                                 parent_context: None,
                             });
                             if debug_mode {
-                                eprintln!(
-                                    "DEBUG: Sparse Cache: Potential context node (ancestor) block at lines {}-{}",
+                                trace!(
+                                    "Sparse Cache: Potential context node (ancestor) block at lines {}-{}",
                                     ctx_rows.0 + 1, ctx_rows.1 + 1
                                 );
                             }
@@ -1128,8 +1163,8 @@ fn process_sparse_line_map(
                             parent_context: None,
                         });
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Sparse Cache: Potential acceptable original node block at lines {}-{}",
+                            trace!(
+                                "Sparse Cache: Potential acceptable original node block at lines {}-{}",
                                 info.start_row + 1, info.end_row + 1
                             );
                         }
@@ -1142,8 +1177,8 @@ fn process_sparse_line_map(
                 if !seen_block_spans.contains(&key) {
                     seen_block_spans.insert(key);
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Sparse Cache: Added new block at lines {}-{}, type: {}",
+                        trace!(
+                            "Sparse Cache: Added new block at lines {}-{}, type: {}",
                             key.0 + 1,
                             key.1 + 1,
                             block.node_type
@@ -1153,7 +1188,7 @@ fn process_sparse_line_map(
                 }
             }
         } else if debug_mode {
-            eprintln!("DEBUG: Sparse Cache: No cached node info found for line {line}");
+            trace!("Sparse Cache: No cached node info found for line {line}");
         }
     }
 
@@ -1248,13 +1283,8 @@ fn process_sparse_line_map(
                             .position(|&t| t == prev_block.node_type.as_str());
 
                         match (current_priority, prev_priority) {
-                            (Some(cur_pri), Some(prev_pri)) => {
-                                if cur_pri > prev_pri {
-                                    blocks_to_remove.push(idx);
-                                } else {
-                                    should_add = false;
-                                    break;
-                                }
+                            (Some(cur_pri), Some(prev_pri)) if cur_pri > prev_pri => {
+                                blocks_to_remove.push(idx);
                             }
                             _ => {
                                 should_add = false;
@@ -1307,20 +1337,20 @@ fn process_cached_line_map(
         let line_idx = line.saturating_sub(1); // Adjust for 0-based indexing
 
         if debug_mode {
-            eprintln!("DEBUG: Processing line {line} from cache");
+            trace!("Processing line {line} from cache");
         }
 
         if line_idx >= cached_line_map.len() {
             if debug_mode {
-                eprintln!("DEBUG: Line {line} is out of bounds (Cache)");
+                trace!("Line {line} is out of bounds (Cache)");
             }
             continue;
         }
 
         if let Some(info) = &cached_line_map[line_idx] {
             if debug_mode {
-                eprintln!(
-                    "DEBUG: Found cached node info for line {}: original_type='{}', original_lines={}-{}, is_comment={}, is_test={}, context_kind={:?}, context_lines={:?}",
+                trace!(
+                    "Found cached node info for line {}: original_type='{}', original_lines={}-{}, is_comment={}, is_test={}, context_kind={:?}, context_lines={:?}",
                     line,
                     info.node_kind,
                     info.start_row + 1,
@@ -1341,7 +1371,7 @@ fn process_cached_line_map(
             // 1. Handle Comments
             if info.is_comment {
                 if debug_mode {
-                    eprintln!("DEBUG: Cache: Handling comment node at line {line}");
+                    trace!("Cache: Handling comment node at line {line}");
                 }
                 // Check for context node
                 if let (Some(ctx_rows), Some(ctx_bytes), Some(ctx_kind), Some(ctx_is_test)) = (
@@ -1353,8 +1383,8 @@ fn process_cached_line_map(
                     // Check test status of the context node
                     if !allow_tests && ctx_is_test {
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache: Skipping test context node at lines {}-{}, type: {}",
+                            trace!(
+                                "Cache: Skipping test context node at lines {}-{}, type: {}",
                                 ctx_rows.0 + 1,
                                 ctx_rows.1 + 1,
                                 ctx_kind
@@ -1382,8 +1412,8 @@ fn process_cached_line_map(
                                 parent_context: None,
                             });
                             if debug_mode {
-                                eprintln!(
-                                    "DEBUG: Cache: Potential merged block (comment + context) at lines {}-{}, type: {}",
+                                trace!(
+                                    "Cache: Potential merged block (comment + context) at lines {}-{}, type: {}",
                                     merged_start_row + 1, merged_end_row + 1, ctx_kind
                                 );
                             }
@@ -1394,8 +1424,8 @@ fn process_cached_line_map(
                             || potential_block.is_some()
                         {
                             if seen_block_spans.contains(&block_key.unwrap()) && debug_mode {
-                                eprintln!(
-                                    "DEBUG: Cache: Merged block span {}-{} already seen",
+                                trace!(
+                                    "Cache: Merged block span {}-{} already seen",
                                     block_key.unwrap().0 + 1,
                                     block_key.unwrap().1 + 1
                                 );
@@ -1428,15 +1458,15 @@ fn process_cached_line_map(
                             parent_context: None,
                         });
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache: Potential individual comment block at lines {}-{}",
+                            trace!(
+                                "Cache: Potential individual comment block at lines {}-{}",
                                 info.start_row + 1,
                                 info.end_row + 1
                             );
                         }
                     } else if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache: Individual comment span {}-{} already seen",
+                        trace!(
+                            "Cache: Individual comment span {}-{} already seen",
                             block_key.unwrap().0 + 1,
                             block_key.unwrap().1 + 1
                         );
@@ -1448,8 +1478,8 @@ fn process_cached_line_map(
                 // Skip original test nodes if not allowed
                 if !allow_tests && info.is_test {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache: Skipping original test node at lines {}-{}",
+                        trace!(
+                            "Cache: Skipping original test node at lines {}-{}",
                             info.start_row + 1,
                             info.end_row + 1
                         );
@@ -1467,9 +1497,10 @@ fn process_cached_line_map(
                     // Check test status of the context node
                     if !allow_tests && ctx_is_test {
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache: Skipping test context node (ancestor) at lines {}-{}",
-                                ctx_rows.0 + 1, ctx_rows.1 + 1
+                            trace!(
+                                "Cache: Skipping test context node (ancestor) at lines {}-{}",
+                                ctx_rows.0 + 1,
+                                ctx_rows.1 + 1
                             );
                         }
                         // Fall through to check original node if context is skipped
@@ -1490,14 +1521,15 @@ fn process_cached_line_map(
                                 parent_context: None,
                             });
                             if debug_mode {
-                                eprintln!(
-                                    "DEBUG: Cache: Potential context node (ancestor) block at lines {}-{}",
-                                    ctx_rows.0 + 1, ctx_rows.1 + 1
+                                trace!(
+                                    "Cache: Potential context node (ancestor) block at lines {}-{}",
+                                    ctx_rows.0 + 1,
+                                    ctx_rows.1 + 1
                                 );
                             }
                         } else if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache: Context node span {}-{} already seen",
+                            trace!(
+                                "Cache: Context node span {}-{} already seen",
                                 block_key.unwrap().0 + 1,
                                 block_key.unwrap().1 + 1
                             );
@@ -1533,14 +1565,15 @@ fn process_cached_line_map(
                             parent_context: None,
                         });
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache: Potential acceptable original node block at lines {}-{}",
-                                info.start_row + 1, info.end_row + 1
+                            trace!(
+                                "Cache: Potential acceptable original node block at lines {}-{}",
+                                info.start_row + 1,
+                                info.end_row + 1
                             );
                         }
                     } else if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache: Original acceptable node span {}-{} already seen",
+                        trace!(
+                            "Cache: Original acceptable node span {}-{} already seen",
                             block_key.unwrap().0 + 1,
                             block_key.unwrap().1 + 1
                         );
@@ -1569,8 +1602,8 @@ fn process_cached_line_map(
                             (Some(cur_pri), Some(exist_pri)) if cur_pri > exist_pri => {
                                 // Replace with higher priority node
                                 if debug_mode {
-                                    eprintln!(
-                                        "DEBUG: Cache: Replaced block at lines {}-{} with higher priority type: {} > {}",
+                                    trace!(
+                                        "Cache: Replaced block at lines {}-{} with higher priority type: {} > {}",
                                         key.0 + 1, key.1 + 1, block.node_type, existing_node_type
                                     );
                                 }
@@ -1579,8 +1612,8 @@ fn process_cached_line_map(
                             _ => {
                                 // Keep existing (higher or equal priority)
                                 if debug_mode {
-                                    eprintln!(
-                                        "DEBUG: Cache: Keeping existing block at lines {}-{} with priority: {} >= {}",
+                                    trace!(
+                                        "Cache: Keeping existing block at lines {}-{} with priority: {} >= {}",
                                         key.0 + 1, key.1 + 1,
                                         existing_node_type, block.node_type
                                     );
@@ -1592,8 +1625,8 @@ fn process_cached_line_map(
                     // New span - add it
                     seen_block_spans.insert(key);
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache: Added new block at lines {}-{}, type: {}",
+                        trace!(
+                            "Cache: Added new block at lines {}-{}, type: {}",
                             key.0 + 1,
                             key.1 + 1,
                             block.node_type
@@ -1603,7 +1636,7 @@ fn process_cached_line_map(
                 }
             }
         } else if debug_mode {
-            eprintln!("DEBUG: Cache: No cached node info found for line {line}");
+            trace!("Cache: No cached node info found for line {line}");
         }
     }
 
@@ -1661,22 +1694,20 @@ fn process_cached_line_map(
                 // Case 1: Current block is contained within previous block
                 if block.start_row >= prev_block.start_row && block.end_row <= prev_block.end_row {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache Dedupe: Current block contained: type='{}', lines={}-{} (in type='{}', lines={}-{})",
+                        trace!(
+                            "Cache Dedupe: Current block contained: type='{}', lines={}-{} (in type='{}', lines={}-{})",
                             block.node_type, block.start_row + 1, block.end_row + 1,
                             prev_block.node_type, prev_block.start_row + 1, prev_block.end_row + 1
                         );
                     }
                     if is_important && !prev_is_important {
                         if debug_mode {
-                            eprintln!("DEBUG: Cache Dedupe: Keeping important contained block");
+                            trace!("Cache Dedupe: Keeping important contained block");
                         }
                         // Keep both - don't remove, don't skip add
                     } else if !is_important && prev_is_important {
                         if debug_mode {
-                            eprintln!(
-                                "DEBUG: Cache Dedupe: Skipping non-important contained block"
-                            );
+                            trace!("Cache Dedupe: Skipping non-important contained block");
                         }
                         should_add = false;
                         break;
@@ -1694,14 +1725,14 @@ fn process_cached_line_map(
                                 if cur_pri > prev_pri {
                                     // Current block has higher priority - keep it, remove previous
                                     if debug_mode {
-                                        eprintln!("DEBUG: Cache Dedupe: Replacing block with higher priority type: {} > {}",
+                                        trace!("Cache Dedupe: Replacing block with higher priority type: {} > {}",
                                                 block.node_type, prev_block.node_type);
                                     }
                                     blocks_to_remove.push(idx);
                                 } else {
                                     // Previous block has higher or equal priority - keep previous, skip current
                                     if debug_mode {
-                                        eprintln!("DEBUG: Cache Dedupe: Skipping block in favor of higher priority type: {} >= {}",
+                                        trace!("Cache Dedupe: Skipping block in favor of higher priority type: {} >= {}",
                                                 prev_block.node_type, block.node_type);
                                     }
                                     should_add = false;
@@ -1711,7 +1742,7 @@ fn process_cached_line_map(
                             _ => {
                                 // Fallback: prefer contained (current) block for consistency
                                 if debug_mode {
-                                    eprintln!("DEBUG: Cache Dedupe: Replacing outer block with contained block (no priority)");
+                                    trace!("Cache Dedupe: Replacing outer block with contained block (no priority)");
                                 }
                                 blocks_to_remove.push(idx);
                             }
@@ -1723,20 +1754,20 @@ fn process_cached_line_map(
                     && prev_block.end_row <= block.end_row
                 {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache Dedupe: Previous block contained: type='{}', lines={}-{} (contains type='{}', lines={}-{})",
+                        trace!(
+                            "Cache Dedupe: Previous block contained: type='{}', lines={}-{} (contains type='{}', lines={}-{})",
                             block.node_type, block.start_row + 1, block.end_row + 1,
                             prev_block.node_type, prev_block.start_row + 1, prev_block.end_row + 1
                         );
                     }
                     if is_important && !prev_is_important {
                         if debug_mode {
-                            eprintln!("DEBUG: Cache Dedupe: Keeping important outer block");
+                            trace!("Cache Dedupe: Keeping important outer block");
                         }
                         // Keep both - don't skip add, continue checking
                     } else if !is_important && prev_is_important {
                         if debug_mode {
-                            eprintln!("DEBUG: Cache Dedupe: Skipping non-important outer block");
+                            trace!("Cache Dedupe: Skipping non-important outer block");
                         }
                         should_add = false;
                         break;
@@ -1754,14 +1785,14 @@ fn process_cached_line_map(
                                 if cur_pri > prev_pri {
                                     // Current block has higher priority - keep it, remove previous
                                     if debug_mode {
-                                        eprintln!("DEBUG: Cache Dedupe: Replacing contained block with higher priority type: {} > {}",
+                                        trace!("Cache Dedupe: Replacing contained block with higher priority type: {} > {}",
                                                 block.node_type, prev_block.node_type);
                                     }
                                     blocks_to_remove.push(idx);
                                 } else {
                                     // Previous block has higher or equal priority - keep previous, skip current
                                     if debug_mode {
-                                        eprintln!("DEBUG: Cache Dedupe: Skipping outer block in favor of higher priority contained type: {} >= {}",
+                                        trace!("Cache Dedupe: Skipping outer block in favor of higher priority contained type: {} >= {}",
                                                 prev_block.node_type, block.node_type);
                                     }
                                     should_add = false;
@@ -1771,7 +1802,7 @@ fn process_cached_line_map(
                             _ => {
                                 // Fallback: prefer contained (previous) block for consistency
                                 if debug_mode {
-                                    eprintln!("DEBUG: Cache Dedupe: Skipping outer block (already have contained, no priority)");
+                                    trace!("Cache Dedupe: Skipping outer block (already have contained, no priority)");
                                 }
                                 should_add = false;
                                 break;
@@ -1782,8 +1813,8 @@ fn process_cached_line_map(
                 // Case 3: Blocks partially overlap
                 else {
                     if debug_mode {
-                        eprintln!(
-                            "DEBUG: Cache Dedupe: Partial overlap: type='{}', lines={}-{} (overlaps type='{}', lines={}-{})",
+                        trace!(
+                            "Cache Dedupe: Partial overlap: type='{}', lines={}-{} (overlaps type='{}', lines={}-{})",
                             block.node_type, block.start_row + 1, block.end_row + 1,
                             prev_block.node_type, prev_block.start_row + 1, prev_block.end_row + 1
                         );
@@ -1827,10 +1858,14 @@ pub fn parse_file_for_code_blocks(
         allow_tests,
         _term_matches,
         None,
+        true,
     )
 }
 
 /// Function to parse a file and extract code blocks with an optional pre-parsed tree
+///
+/// When `include_attributes` is set, blocks extend upward over attributes, decorators and
+/// annotations attached to the item (e.g. `#[derive(...)]`, `@decorator`).
 pub fn parse_file_for_code_blocks_with_tree(
     content: &str,
     extension: &str,
@@ -1838,30 +1873,33 @@ pub fn parse_file_for_code_blocks_with_tree(
     allow_tests: bool,
     _term_matches: Option<&HashMap<usize, HashSet<usize>>>, // Query index to line numbers
     pre_parsed_tree: Option<tree_sitter::Tree>,
+    include_attributes: bool,
 ) -> Result<Vec<CodeBlock>> {
     // Check for debug mode
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // Get the appropriate language implementation
     let language_impl = match get_language_impl(extension) {
         Some(lang) => lang,
         None => {
             // For unsupported languages, return empty blocks to trigger fallback to literal extraction
-            if debug_mode {
-                eprintln!("DEBUG: File extension '{extension}' not supported for AST parsing, returning empty blocks for fallback");
-            }
+            warn_once(
+                WarningKind::UnsupportedExtension,
+                extension,
+                format!("File extension '{extension}' not supported for AST parsing; using matched lines without AST context"),
+            );
             return Ok(Vec::new());
         }
     };
 
     // Calculate content hash for cache key
     let content_hash = calculate_content_hash(content);
-    let cache_key = format!("{extension}_{content_hash}_{allow_tests}");
+    let cache_key = format!("{extension}_{content_hash}_{allow_tests}_{include_attributes}");
 
     // Check if we have a cached sparse line map
     if let Some(cached_entry) = LINE_MAP_CACHE.get(&cache_key) {
         if debug_mode {
-            eprintln!("DEBUG: Sparse cache hit for line_map key: {cache_key}");
+            trace!("Sparse cache hit for line_map key: {cache_key}");
         }
 
         // Process the sparse cached line map
@@ -1876,39 +1914,27 @@ pub fn parse_file_for_code_blocks_with_tree(
     }
 
     if debug_mode {
-        eprintln!(
-            "DEBUG: Sparse cache miss for line_map key: {cache_key}. Building sparse line map..."
-        );
+        trace!("Sparse cache miss for line_map key: {cache_key}. Building sparse line map...");
     }
 
     // Get the tree - either use pre-parsed or parse it
     let tree = if let Some(pre_parsed) = pre_parsed_tree {
         if debug_mode {
-            eprintln!("DEBUG: Using pre-parsed tree, skipping redundant parsing");
+            trace!("Using pre-parsed tree, skipping redundant parsing");
         }
         pre_parsed
     } else {
-        // Get the tree-sitter language
-        let language = language_impl.get_tree_sitter_language();
-
-        // Parse the file
-        let mut parser = TSParser::new();
-        parser.set_language(&language)?;
-
-        // Use the tree cache to get or parse the tree
+        // Use the tree cache to get or parse the tree with a pooled parser
         let tree_cache_key = format!("file_{extension}");
-        tree_cache::get_or_parse_tree(&tree_cache_key, content, &mut parser)
+        tree_cache::get_or_parse_tree_pooled(&tree_cache_key, content, extension)
             .context("Failed to parse the file")?
     };
 
     let root_node = tree.root_node();
 
     if debug_mode {
-        eprintln!("DEBUG: SPARSE OPTIMIZATION - Parsing file with extension: {extension}");
-        eprintln!(
-            "DEBUG: SPARSE OPTIMIZATION - Root node type: {}",
-            root_node.kind()
-        );
+        trace!("SPARSE OPTIMIZATION - Parsing file with extension: {extension}");
+        trace!("SPARSE OPTIMIZATION - Root node type: {}", root_node.kind());
     }
 
     // OPTIMIZATION: Build sparse line map that only processes intersecting nodes
@@ -1918,12 +1944,13 @@ pub fn parse_file_for_code_blocks_with_tree(
         language_impl.as_ref(),
         content.as_bytes(),
         allow_tests,
+        include_attributes,
         debug_mode,
     );
 
     if debug_mode {
-        eprintln!(
-            "DEBUG: SPARSE OPTIMIZATION - Sparse line map built with {} entries",
+        trace!(
+            "SPARSE OPTIMIZATION - Sparse line map built with {} entries",
             sparse_line_map.len()
         );
     }
@@ -1940,7 +1967,7 @@ pub fn parse_file_for_code_blocks_with_tree(
     // Store the sparse line map in cache for future requests
     LINE_MAP_CACHE.insert(cache_key.clone(), sparse_line_map);
     if debug_mode {
-        eprintln!("DEBUG: SPARSE OPTIMIZATION - Stored sparse line map in cache key: {cache_key}");
+        trace!("SPARSE OPTIMIZATION - Stored sparse line map in cache key: {cache_key}");
     }
 
     Ok(code_blocks)
@@ -2019,4 +2046,67 @@ fn test_function() {
             .any(|b| b.node_type == "function_item" || b.node_type == "function");
         assert!(has_function, "Should find a function block");
     }
+
+    #[test]
+    fn test_blocks_include_attached_attributes() {
+        let rust_content = r#"use std::fmt;
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+"#;
+        let line_numbers: HashSet<usize> = [6].into_iter().collect();
+
+        let blocks =
+            parse_file_for_code_blocks(rust_content, "rs", &line_numbers, false, None).unwrap();
+        let block = blocks
+            .iter()
+            .find(|b| b.node_type == "struct_item")
+            .expect("Should find the struct block");
+        assert_eq!(block.start_row, 2, "Block should start at #[derive]");
+        assert!(rust_content[block.start_byte..].starts_with("#[derive"));
+
+        // Matching the attribute line itself resolves to the attributed item
+        let attribute_line: HashSet<usize> = [3].into_iter().collect();
+        let blocks =
+            parse_file_for_code_blocks(rust_content, "rs", &attribute_line, false, None).unwrap();
+        assert!(blocks
+            .iter()
+            .any(|b| b.node_type == "struct_item" && b.start_row == 2 && b.end_row == 7));
+
+        // Disabling attribute inclusion starts the block at the item itself
+        let blocks = parse_file_for_code_blocks_with_tree(
+            rust_content,
+            "rs",
+            &line_numbers,
+            false,
+            None,
+            None,
+            false,
+        )
+        .unwrap();
+        let block = blocks
+            .iter()
+            .find(|b| b.node_type == "struct_item")
+            .expect("Should find the struct block");
+        assert_eq!(block.start_row, 4);
+
+        let python_content = r#"import functools
+
+@functools.lru_cache(maxsize=None)
+@staticmethod
+def compute(value):
+    return value * 2
+"#;
+        let line_numbers: HashSet<usize> = [5].into_iter().collect();
+        let blocks =
+            parse_file_for_code_blocks(python_content, "py", &line_numbers, false, None).unwrap();
+        assert!(
+            blocks.iter().any(|b| b.start_row == 2),
+            "Python block should start at the first decorator: {blocks:?}"
+        );
+    }
 }
@@ -1,8 +1,10 @@
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
-use std::sync::Mutex;
-use tree_sitter::Parser;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::trace;
+use tree_sitter::{Parser, Tree};
 
 use crate::language::factory;
 use crate::search::file_list_cache;
@@ -10,10 +12,35 @@ use crate::search::file_list_cache;
 // PHASE 4 OPTIMIZATION: Dynamic pool sizing based on CPU cores
 const DEFAULT_MAX_PARSERS_PER_LANGUAGE: usize = 4;
 
+/// Tuning of the parser pool, e.g. from `--parser-pool-size` and `--parser-idle-timeout`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolConfig {
+    /// Parsers kept per language (default: `PROBE_PARSER_POOL_SIZE`, else the number of
+    /// threads but at least 4)
+    pub max_parsers_per_language: Option<usize>,
+    /// Drop pooled parsers that have not been used for this long (default:
+    /// `PROBE_PARSER_IDLE_SECS` seconds, else never)
+    pub idle_timeout: Option<Duration>,
+}
+
+static POOL_CONFIG: OnceLock<PoolConfig> = OnceLock::new();
+
+/// Set the parser pool tuning for this process.
+///
+/// Returns `false` if it was already set.
+pub fn set_pool_config(config: PoolConfig) -> bool {
+    POOL_CONFIG.set(config).is_ok()
+}
+
 fn get_max_parsers_per_language() -> usize {
-    std::env::var("PROBE_PARSER_POOL_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
+    POOL_CONFIG
+        .get()
+        .and_then(|config| config.max_parsers_per_language)
+        .or_else(|| {
+            std::env::var("PROBE_PARSER_POOL_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+        })
         .unwrap_or_else(|| {
             std::cmp::max(
                 rayon::current_num_threads(),
@@ -22,6 +49,56 @@ fn get_max_parsers_per_language() -> usize {
         })
 }
 
+fn get_idle_timeout() -> Option<Duration> {
+    POOL_CONFIG
+        .get()
+        .and_then(|config| config.idle_timeout)
+        .or_else(|| {
+            std::env::var("PROBE_PARSER_IDLE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .map(Duration::from_secs)
+        })
+}
+
+/// Usage of the pooled parsers of one language, for `--parser-stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParserStats {
+    /// Parsers taken from the pool
+    pub hits: usize,
+    /// Parsers created because the pool had none left
+    pub misses: usize,
+    /// Parsers dropped on return because the pool was full
+    pub discarded: usize,
+    /// Parsers dropped after sitting idle longer than the idle timeout
+    pub evicted: usize,
+    /// Files parsed, and the time spent parsing them
+    pub parses: usize,
+    pub parse_time: Duration,
+    /// Parsers currently in the pool
+    pub pooled: usize,
+}
+
+/// The pooled parsers of one language, each with the time it was returned
+#[derive(Default)]
+struct LanguagePool {
+    parsers: Vec<(Parser, Instant)>,
+    stats: ParserStats,
+}
+
+impl LanguagePool {
+    /// Drop the parsers that have been idle longer than `timeout`
+    fn evict_idle(&mut self, timeout: Option<Duration>) {
+        let Some(timeout) = timeout else {
+            return;
+        };
+        let before = self.parsers.len();
+        self.parsers
+            .retain(|(_, returned)| returned.elapsed() <= timeout);
+        self.stats.evicted += before - self.parsers.len();
+    }
+}
+
 lazy_static::lazy_static! {
     /// A thread-safe pool of tree-sitter parsers organized by language extension
     ///
@@ -31,7 +108,7 @@ lazy_static::lazy_static! {
     /// The pool is keyed by file extension (e.g., "rs", "js", "py") and contains a vector
     /// of ready-to-use parsers that have already been configured with the appropriate
     /// tree-sitter language grammar.
-    static ref PARSER_POOL: Mutex<HashMap<String, Vec<Parser>>> = Mutex::new(HashMap::new());
+    static ref PARSER_POOL: Mutex<HashMap<String, LanguagePool>> = Mutex::new(HashMap::new());
 
     // PHASE 4 OPTIMIZATION: Pre-warm parsers for supported languages
     static ref PARSER_WARMER: () = {
@@ -112,9 +189,9 @@ pub fn smart_warm_parser_pool_for_directory(path: &Path) {
         return;
     }
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
     if debug_mode {
-        println!("[DEBUG] Smart pre-warming detected languages: {detected_languages:?}");
+        trace!("Smart pre-warming detected languages: {detected_languages:?}");
     }
 
     // Prioritize languages by usage frequency (most common first)
@@ -130,7 +207,7 @@ pub fn smart_warm_parser_pool_for_directory(path: &Path) {
             if let Ok(parser) = get_pooled_parser(lang) {
                 return_pooled_parser(lang, parser);
                 if debug_mode {
-                    println!("[DEBUG] Pre-warmed parser for language: {lang}");
+                    trace!("Pre-warmed parser for language: {lang}");
                 }
             }
         }
@@ -142,7 +219,7 @@ pub fn smart_warm_parser_pool_for_directory(path: &Path) {
             if let Ok(parser) = get_pooled_parser(lang) {
                 return_pooled_parser(lang, parser);
                 if debug_mode {
-                    println!("[DEBUG] Pre-warmed parser for additional language: {lang}");
+                    trace!("Pre-warmed parser for additional language: {lang}");
                 }
             }
         }
@@ -173,7 +250,7 @@ pub fn smart_warm_parser_pool_for_directory(path: &Path) {
 /// return_pooled_parser("rs", parser);
 /// ```
 pub fn get_pooled_parser(extension: &str) -> Result<Parser> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // First, try to get a parser from the pool
     {
@@ -181,10 +258,12 @@ pub fn get_pooled_parser(extension: &str) -> Result<Parser> {
             .lock()
             .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-        if let Some(parsers) = pool.get_mut(extension) {
-            if let Some(parser) = parsers.pop() {
+        if let Some(language_pool) = pool.get_mut(extension) {
+            language_pool.evict_idle(get_idle_timeout());
+            if let Some((parser, _)) = language_pool.parsers.pop() {
+                language_pool.stats.hits += 1;
                 if debug_mode {
-                    println!("[DEBUG] Parser pool: Retrieved pooled parser for extension '{extension}' (pool size: {})", parsers.len());
+                    trace!("Parser pool: Retrieved pooled parser for extension '{extension}' (pool size: {})", language_pool.parsers.len());
                 }
                 return Ok(parser);
             }
@@ -193,7 +272,7 @@ pub fn get_pooled_parser(extension: &str) -> Result<Parser> {
 
     // No parser available in pool, create a new one
     if debug_mode {
-        println!("[DEBUG] Parser pool: Creating new parser for extension '{extension}'");
+        trace!("Parser pool: Creating new parser for extension '{extension}'");
     }
 
     let language_impl = factory::get_language_impl(extension)
@@ -204,9 +283,30 @@ pub fn get_pooled_parser(extension: &str) -> Result<Parser> {
         .set_language(&language_impl.get_tree_sitter_language())
         .map_err(|e| anyhow::anyhow!("Failed to set parser language: {}", e))?;
 
+    with_stats(extension, |stats| stats.misses += 1);
     Ok(parser)
 }
 
+/// Parse `text` with a parser from [`get_pooled_parser`], recording the parse time in
+/// the pool's [`parser_stats`]
+pub fn timed_parse(parser: &mut Parser, extension: &str, text: impl AsRef<[u8]>) -> Option<Tree> {
+    let start = Instant::now();
+    let tree = parser.parse(text, None);
+    let elapsed = start.elapsed();
+    with_stats(extension, |stats| {
+        stats.parses += 1;
+        stats.parse_time += elapsed;
+    });
+    tree
+}
+
+fn with_stats(extension: &str, update: impl FnOnce(&mut ParserStats)) {
+    let mut pool = PARSER_POOL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    update(&mut pool.entry(extension.to_string()).or_default().stats);
+}
+
 /// Returns a parser to the pool for reuse.
 ///
 /// This function adds the parser back to the pool for the specified language extension,
@@ -224,27 +324,34 @@ pub fn get_pooled_parser(extension: &str) -> Result<Parser> {
 /// The pool will maintain a reasonable number of parsers per language to balance
 /// performance with memory usage.
 pub fn return_pooled_parser(extension: &str, parser: Parser) {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     let mut pool = PARSER_POOL
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-    let parsers = pool.entry(extension.to_string()).or_default();
+    let language_pool = pool.entry(extension.to_string()).or_default();
+    language_pool.evict_idle(get_idle_timeout());
 
     // PHASE 4 OPTIMIZATION: Use dynamic pool sizing
     let max_parsers = get_max_parsers_per_language();
 
-    if parsers.len() < max_parsers {
-        parsers.push(parser);
+    if language_pool.parsers.len() < max_parsers {
+        language_pool.parsers.push((parser, Instant::now()));
         if debug_mode {
-            println!(
-                "[DEBUG] Parser pool: Returned parser for extension '{extension}' (pool size: {})",
-                parsers.len()
+            trace!(
+                "Parser pool: Returned parser for extension '{extension}' (pool size: {})",
+                language_pool.parsers.len()
+            );
+        }
+    } else {
+        language_pool.stats.discarded += 1;
+        if debug_mode {
+            trace!(
+                "Parser pool: Discarded parser for extension '{extension}' (pool at capacity: {})",
+                language_pool.parsers.len()
             );
         }
-    } else if debug_mode {
-        println!("[DEBUG] Parser pool: Discarded parser for extension '{extension}' (pool at capacity: {})", parsers.len());
     }
 }
 
@@ -262,10 +369,49 @@ pub fn get_pool_stats() -> HashMap<String, usize> {
         .unwrap_or_else(|poisoned| poisoned.into_inner());
 
     pool.iter()
-        .map(|(ext, parsers)| (ext.clone(), parsers.len()))
+        .map(|(ext, language_pool)| (ext.clone(), language_pool.parsers.len()))
         .collect()
 }
 
+/// Hits, misses, evictions and parse times of the pool so far, by language extension
+pub fn parser_stats() -> BTreeMap<String, ParserStats> {
+    let pool = PARSER_POOL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    pool.iter()
+        .map(|(ext, language_pool)| {
+            let stats = ParserStats {
+                pooled: language_pool.parsers.len(),
+                ..language_pool.stats.clone()
+            };
+            (ext.clone(), stats)
+        })
+        .collect()
+}
+
+/// A table of [`parser_stats`], one row per language, for `--parser-stats`
+pub fn format_parser_stats(stats: &BTreeMap<String, ParserStats>) -> String {
+    let mut table = format!(
+        "{:<8} {:>6} {:>6} {:>9} {:>7} {:>6} {:>7} {:>10}\n",
+        "language", "hits", "misses", "discarded", "evicted", "pooled", "parses", "parse time"
+    );
+    for (extension, stats) in stats {
+        table.push_str(&format!(
+            "{:<8} {:>6} {:>6} {:>9} {:>7} {:>6} {:>7} {:>8.1}ms\n",
+            extension,
+            stats.hits,
+            stats.misses,
+            stats.discarded,
+            stats.evicted,
+            stats.pooled,
+            stats.parses,
+            stats.parse_time.as_secs_f64() * 1000.0
+        ));
+    }
+    table
+}
+
 /// Clears the entire parser pool.
 ///
 /// This function removes all pooled parsers, forcing future parser requests
@@ -276,12 +422,12 @@ pub fn clear_parser_pool() {
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     if debug_mode {
-        let total_parsers: usize = pool.values().map(|v| v.len()).sum();
-        println!(
-            "[DEBUG] Parser pool: Clearing pool with {} parsers across {} languages",
+        let total_parsers: usize = pool.values().map(|v| v.parsers.len()).sum();
+        trace!(
+            "Parser pool: Clearing pool with {} parsers across {} languages",
             total_parsers,
             pool.len()
         );
@@ -399,6 +545,44 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_parser_stats_count_hits_and_misses() {
+        with_isolated_pool(|| {
+            let test_lang = "php";
+            let mut parser = get_pooled_parser(test_lang).expect("Should create PHP parser");
+            assert!(timed_parse(&mut parser, test_lang, "<?php echo 1;").is_some());
+            return_pooled_parser(test_lang, parser);
+            let parser = get_pooled_parser(test_lang).expect("Should get pooled PHP parser");
+            return_pooled_parser(test_lang, parser);
+
+            // Other tests may use PHP parsers concurrently, so only lower bounds hold
+            let stats = &parser_stats()[test_lang];
+            assert!(stats.hits >= 1 && stats.misses >= 1 && stats.parses >= 1);
+            assert!(stats.pooled >= 1);
+            assert!(format_parser_stats(&parser_stats()).contains("php"));
+        })
+    }
+
+    #[test]
+    fn test_idle_parsers_are_evicted() {
+        let Some(long_ago) = Instant::now().checked_sub(Duration::from_secs(60)) else {
+            return;
+        };
+        let mut language_pool = LanguagePool::default();
+        language_pool
+            .parsers
+            .push((get_pooled_parser("rs").unwrap(), long_ago));
+        language_pool
+            .parsers
+            .push((get_pooled_parser("rs").unwrap(), Instant::now()));
+
+        language_pool.evict_idle(None);
+        assert_eq!(language_pool.parsers.len(), 2);
+        language_pool.evict_idle(Some(Duration::from_secs(30)));
+        assert_eq!(language_pool.parsers.len(), 1);
+        assert_eq!(language_pool.stats.evicted, 1);
+    }
+
     #[test]
     fn test_unsupported_extension() {
         let result = get_pooled_parser("unsupported");
@@ -408,6 +592,7 @@ mod tests {
 
     #[test]
     fn benchmark_parser_creation_performance() {
+        let _lock = TEST_MUTEX.lock().unwrap();
         use std::time::Instant;
 
         // Test languages across all tiers (using only supported ones)
@@ -496,6 +681,7 @@ mod tests {
 
     #[test]
     fn benchmark_smart_vs_full_prewarming() {
+        let _lock = TEST_MUTEX.lock().unwrap();
         use std::time::Instant;
 
         println!("\n=== Smart vs Full Pre-warming Benchmark ===");
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for PHP
@@ -25,6 +26,11 @@ impl LanguageImpl for PhpLanguage {
         "php"
     }
 
+    fn is_doc_comment(&self, node: &Node, source: &[u8]) -> bool {
+        // PHPDoc comments
+        node.kind() == "comment" && node.utf8_text(source).unwrap_or("").starts_with("/**")
+    }
+
     fn is_acceptable_parent(&self, node: &Node) -> bool {
         matches!(
             node.kind(),
@@ -37,7 +43,7 @@ impl LanguageImpl for PhpLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // PHP: Check method_declaration nodes with test prefix or PHPUnit annotations
@@ -48,7 +54,7 @@ impl LanguageImpl for PhpLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (PHP): test method");
+                            trace!("Test node detected (PHP): test method");
                         }
                         return true;
                     }
@@ -56,7 +62,7 @@ impl LanguageImpl for PhpLanguage {
                     let comment = child.utf8_text(source).unwrap_or("");
                     if comment.contains("@test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (PHP): @test annotation");
+                            trace!("Test node detected (PHP): @test annotation");
                         }
                         return true;
                     }
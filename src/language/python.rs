@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Python
@@ -39,7 +40,7 @@ impl LanguageImpl for PythonLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Python: Check function_definition nodes with names starting with test_
@@ -50,7 +51,7 @@ impl LanguageImpl for PythonLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("test_") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Python): test_ function");
+                            trace!("Test node detected (Python): test_ function");
                         }
                         return true;
                     }
@@ -61,6 +62,27 @@ impl LanguageImpl for PythonLanguage {
         false
     }
 
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        let name = match node.kind() {
+            "decorated_definition" => node
+                .child_by_field_name("definition")
+                .and_then(|definition| definition.child_by_field_name("name")),
+            "function_definition" | "class_definition" => node.child_by_field_name("name"),
+            "expression_statement" => node
+                .named_child(0)
+                .filter(|child| child.kind() == "assignment")
+                .and_then(|assignment| assignment.child_by_field_name("left")),
+            "assignment" => node.child_by_field_name("left"),
+            _ => None,
+        };
+        // A leading underscore makes a name private by convention; dunders like
+        // `__init__` are public
+        name.and_then(|name| name.utf8_text(source).ok())
+            .is_none_or(|name| {
+                !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__"))
+            })
+    }
+
     fn get_symbol_signature(&self, node: &Node, source: &[u8]) -> Option<String> {
         match node.kind() {
             "function_definition" => {
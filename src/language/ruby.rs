@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Ruby
@@ -33,7 +34,7 @@ impl LanguageImpl for RubyLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Ruby: Check method nodes with test_ prefix or describe/it blocks
@@ -44,7 +45,7 @@ impl LanguageImpl for RubyLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("test_") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Ruby): test_ method");
+                            trace!("Test node detected (Ruby): test_ method");
                         }
                         return true;
                     }
@@ -58,7 +59,7 @@ impl LanguageImpl for RubyLanguage {
                     if name == "describe" || name == "it" || name == "context" || name == "specify"
                     {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Ruby): {name} block");
+                            trace!("Test node detected (Ruby): {name} block");
                         }
                         return true;
                     }
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Rust
@@ -14,6 +15,250 @@ impl RustLanguage {
     pub fn new() -> Self {
         RustLanguage
     }
+
+    /// Check whether a macro invocation sits at item level (module or impl body)
+    /// and declares items in its body, as `lazy_static!`, `thread_local!` and
+    /// `cfg_if!` do. Such invocations are treated as symbol-producing blocks.
+    fn is_item_declaring_macro(node: &Node) -> bool {
+        if node.kind() != "macro_invocation" {
+            return false;
+        }
+
+        let at_item_level = node
+            .parent()
+            .is_some_and(|p| matches!(p.kind(), "source_file" | "declaration_list"));
+        if !at_item_level {
+            return false;
+        }
+
+        node.children(&mut node.walk())
+            .find(|child| child.kind() == "token_tree")
+            .is_some_and(|body| Self::token_tree_declares_items(&body))
+    }
+
+    /// Recursively check a token tree for `static` or `fn` tokens
+    fn token_tree_declares_items(token_tree: &Node) -> bool {
+        let mut cursor = token_tree.walk();
+        let declares = token_tree.children(&mut cursor).any(|child| {
+            matches!(child.kind(), "static" | "fn")
+                || (child.kind() == "token_tree" && Self::token_tree_declares_items(&child))
+        });
+        declares
+    }
+
+    /// Collapse a source range into a single whitespace-normalized line
+    fn normalized_text(source: &[u8], start: usize, end: usize) -> String {
+        String::from_utf8_lossy(&source[start..end])
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Collect the signatures of `static` declarations at the top level of a
+    /// macro body, dropping initializers (`static ref X: T = ...;` becomes
+    /// `static ref X: T`).
+    fn collect_static_declarations(token_tree: &Node, source: &[u8]) -> Vec<String> {
+        let mut declarations = Vec::new();
+        let mut decl_start: Option<usize> = None;
+        let mut decl_end: Option<usize> = None;
+
+        let mut cursor = token_tree.walk();
+        for child in token_tree.children(&mut cursor) {
+            match child.kind() {
+                "{" | "(" | "[" | "}" | ")" | "]" => continue,
+                ";" => {
+                    if let (Some(start), Some(end)) = (decl_start, decl_end) {
+                        declarations.push(Self::normalized_text(source, start, end));
+                    }
+                    decl_start = None;
+                    decl_end = None;
+                }
+                "=" if decl_start.is_some() && decl_end.is_none() => {
+                    // Stop the signature at the initializer
+                    decl_end = Some(child.start_byte());
+                }
+                _ => {
+                    if decl_start.is_none() {
+                        decl_start = Some(child.start_byte());
+                    }
+                }
+            }
+        }
+
+        // Trailing declaration without a semicolon
+        if let (Some(start), Some(end)) = (decl_start, decl_end) {
+            declarations.push(Self::normalized_text(source, start, end));
+        }
+
+        declarations
+    }
+
+    /// Collect signatures of functions declared inside a macro body token tree,
+    /// descending into nested token trees (e.g. `cfg_if!` branches) but not
+    /// into function bodies.
+    fn collect_macro_fn_signatures(token_tree: &Node, source: &[u8], out: &mut Vec<String>) {
+        for function in Self::macro_fns(token_tree, source) {
+            out.push(Self::normalized_text(
+                source,
+                function.start.start_byte(),
+                function.signature_end(),
+            ));
+        }
+    }
+
+    /// The functions declared inside a macro body token tree, in source order
+    fn macro_fns<'a>(token_tree: &Node<'a>, source: &[u8]) -> Vec<MacroFn<'a>> {
+        let mut cursor = token_tree.walk();
+        let children: Vec<Node> = token_tree.children(&mut cursor).collect();
+        let mut functions = Vec::new();
+
+        let mut i = 0;
+        while i < children.len() {
+            let child = children[i];
+
+            if child.kind() == "fn" {
+                // Walk back over qualifiers such as `pub`, `pub(crate)`, `async`, `unsafe`
+                let mut start = i;
+                while start > 0 {
+                    let prev = children[start - 1];
+                    let is_qualifier = matches!(prev.kind(), "pub" | "async" | "unsafe" | "const")
+                        || (prev.kind() == "token_tree"
+                            && start >= 2
+                            && children[start - 2].kind() == "pub");
+                    if !is_qualifier {
+                        break;
+                    }
+                    start -= 1;
+                }
+
+                // Then over `#[...]` attributes such as `#[cfg_attr(test, inline)]`
+                let mut attributes = start;
+                while attributes >= 2
+                    && children[attributes - 2].kind() == "#"
+                    && children[attributes - 1].kind() == "token_tree"
+                    && source.get(children[attributes - 1].start_byte()) == Some(&b'[')
+                {
+                    attributes -= 2;
+                }
+
+                // The signature ends at the body (a brace token tree) or a semicolon
+                let mut end = i + 1;
+                while end < children.len() {
+                    let next = children[end];
+                    let is_body =
+                        next.kind() == "token_tree" && source.get(next.start_byte()) == Some(&b'{');
+                    if is_body || next.kind() == ";" {
+                        break;
+                    }
+                    end += 1;
+                }
+
+                functions.push(MacroFn {
+                    attributes: children[attributes],
+                    start: children[start],
+                    last: children[end - 1],
+                    body: children.get(end).copied(),
+                });
+
+                // Skip past the function body
+                i = end + 1;
+                continue;
+            }
+
+            if child.kind() == "token_tree" {
+                functions.extend(Self::macro_fns(&child, source));
+            }
+
+            i += 1;
+        }
+        functions
+    }
+
+    /// The lines, from its first attribute to the end of its signature, of the function
+    /// declared inside an item-level macro body (`cfg_if!` and the like) that holds the
+    /// 1-based `line`. Such functions are tokens rather than nodes of the tree, so outline
+    /// views find their signatures here.
+    pub fn macro_fn_signature_lines(
+        root: &Node,
+        source: &[u8],
+        line: usize,
+    ) -> Option<(usize, usize)> {
+        let point = tree_sitter::Point::new(line.checked_sub(1)?, 0);
+        let mut node = root.descendant_for_point_range(point, point)?;
+        while !Self::is_item_declaring_macro(&node) {
+            node = node.parent()?;
+        }
+        let body = node
+            .children(&mut node.walk())
+            .find(|child| child.kind() == "token_tree")?;
+        Self::macro_fns(&body, source)
+            .into_iter()
+            .find(|function| {
+                let end = function.body.unwrap_or(function.last);
+                function.attributes.start_position().row < line
+                    && line <= end.end_position().row + 1
+            })
+            .map(|function| {
+                let signature_end = match function.body {
+                    Some(body) => body.start_position().row,
+                    None => function.last.end_position().row,
+                };
+                (
+                    function.attributes.start_position().row + 1,
+                    signature_end + 1,
+                )
+            })
+    }
+
+    /// The first line of the attributes in front of the item starting on the 1-based
+    /// `line`, such as the `#[cfg_attr(...)]` of a function; `None` without any
+    pub fn attribute_start_line(root: &Node, source: &[u8], line: usize) -> Option<usize> {
+        let row = line.checked_sub(1)?;
+        let line_start = source
+            .split(|&byte| byte == b'\n')
+            .take(row)
+            .map(|text| text.len() + 1)
+            .sum::<usize>();
+        let indent = source
+            .get(line_start..)?
+            .iter()
+            .take_while(|byte| matches!(byte, b' ' | b'\t'))
+            .count();
+        let point = tree_sitter::Point::new(row, indent);
+        let mut node = root.descendant_for_point_range(point, point)?;
+        while !(node.kind().ends_with("_item") && node.kind() != "attribute_item")
+            || node.start_position().row != row
+        {
+            node = node.parent()?;
+        }
+
+        let mut first = None;
+        let mut sibling = node.prev_sibling();
+        while let Some(attribute) = sibling.filter(|node| node.kind() == "attribute_item") {
+            first = Some(attribute.start_position().row + 1);
+            sibling = attribute.prev_sibling();
+        }
+        first
+    }
+}
+
+/// A function declared inside a macro body, as tokens of its token tree
+struct MacroFn<'a> {
+    /// The first `#[...]` attribute of the function, or its first token without any
+    attributes: Node<'a>,
+    /// The first token of the signature, `fn` or a qualifier such as `pub`
+    start: Node<'a>,
+    /// The last token of the signature
+    last: Node<'a>,
+    /// The body, or the `;` ending a declaration without one
+    body: Option<Node<'a>>,
+}
+
+impl MacroFn<'_> {
+    fn signature_end(&self) -> usize {
+        self.body
+            .map_or(self.last.end_byte(), |body| body.start_byte())
+    }
 }
 
 impl LanguageImpl for RustLanguage {
@@ -25,8 +270,15 @@ impl LanguageImpl for RustLanguage {
         "rs"
     }
 
+    fn is_doc_comment(&self, node: &Node, source: &[u8]) -> bool {
+        // Outer doc comments only: `///` and `/** */` (inner `//!` docs belong to the module)
+        let text = node.utf8_text(source).unwrap_or("");
+        (text.starts_with("///") && !text.starts_with("////"))
+            || (text.starts_with("/**") && !text.starts_with("/**/"))
+    }
+
     fn is_acceptable_parent(&self, node: &Node) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Check for standard Rust items
         if matches!(
@@ -42,11 +294,16 @@ impl LanguageImpl for RustLanguage {
             return true;
         }
 
+        // Item-level macros such as lazy_static!, thread_local! and cfg_if!
+        if Self::is_item_declaring_macro(node) {
+            return true;
+        }
+
         // For expression_statement nodes, we need to find the parent function
         if node.kind() == "expression_statement" {
             if debug_mode {
-                println!(
-                    "DEBUG: Found expression_statement at lines {}-{}",
+                trace!(
+                    "Found expression_statement at lines {}-{}",
                     node.start_position().row + 1,
                     node.end_position().row + 1
                 );
@@ -62,13 +319,13 @@ impl LanguageImpl for RustLanguage {
             // Check if this token tree is inside a macro invocation
             if let Some(parent) = node.parent() {
                 if parent.kind() == "macro_invocation" {
-                    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+                    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
                     // For Rust property tests, we want to consider token trees inside macros
                     // as acceptable parents, especially for proptest! macros
                     if debug_mode {
-                        println!(
-                            "DEBUG: Found token_tree in macro_invocation at lines {}-{}",
+                        trace!(
+                            "Found token_tree in macro_invocation at lines {}-{}",
                             node.start_position().row + 1,
                             node.end_position().row + 1
                         );
@@ -82,8 +339,8 @@ impl LanguageImpl for RustLanguage {
                     let node_size = node.end_position().row - node.start_position().row;
                     if node_size > 5 {
                         if debug_mode {
-                            println!(
-                                "DEBUG: Considering large token_tree in macro as acceptable parent (size: {node_size} lines)"
+                            trace!(
+                                "Considering large token_tree in macro as acceptable parent (size: {node_size} lines)"
                             );
                         }
                         return true;
@@ -96,7 +353,7 @@ impl LanguageImpl for RustLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Rust: Check for #[test] attribute on function_item nodes
@@ -117,7 +374,7 @@ impl LanguageImpl for RustLanguage {
 
             if has_test_attribute {
                 if debug_mode {
-                    println!("DEBUG: Test node detected (Rust): #[test] attribute");
+                    trace!("Test node detected (Rust): #[test] attribute");
                 }
                 return true;
             }
@@ -128,7 +385,7 @@ impl LanguageImpl for RustLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("test_") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Rust): test_ function");
+                            trace!("Test node detected (Rust): test_ function");
                         }
                         return true;
                     }
@@ -139,6 +396,57 @@ impl LanguageImpl for RustLanguage {
         false
     }
 
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        let is_pub = |node: &Node| {
+            let mut cursor = node.walk();
+            let visibility = node
+                .children(&mut cursor)
+                .find(|child| child.kind() == "visibility_modifier");
+            // `pub(crate)` and `pub(super)` are not visible outside the crate
+            visibility.is_some_and(|visibility| visibility.utf8_text(source) == Ok("pub"))
+        };
+        match node.kind() {
+            // Trait impls are part of the API of their type, inherent impls when they
+            // have public items
+            "impl_item" => {
+                node.child_by_field_name("trait").is_some()
+                    || node.child_by_field_name("body").is_some_and(|body| {
+                        let mut cursor = body.walk();
+                        let public = body.named_children(&mut cursor).any(|item| is_pub(&item));
+                        public
+                    })
+            }
+            "macro_definition" => {
+                let mut sibling = node.prev_named_sibling();
+                while let Some(attribute) = sibling.filter(|node| node.kind() == "attribute_item") {
+                    if attribute
+                        .utf8_text(source)
+                        .is_ok_and(|text| text.contains("macro_export"))
+                    {
+                        return true;
+                    }
+                    sibling = attribute.prev_named_sibling();
+                }
+                false
+            }
+            // Variants are as visible as their enum
+            "enum_variant" => true,
+            _ if is_pub(node) => true,
+            _ => {
+                // Items of trait impls and of public traits have no visibility of their own
+                let owner = node
+                    .parent()
+                    .filter(|body| body.kind() == "declaration_list")
+                    .and_then(|body| body.parent());
+                owner.is_some_and(|owner| match owner.kind() {
+                    "impl_item" => owner.child_by_field_name("trait").is_some(),
+                    "trait_item" => self.is_public(&owner, source),
+                    _ => false,
+                })
+            }
+        }
+    }
+
     fn get_symbol_signature(&self, node: &Node, source: &[u8]) -> Option<String> {
         match node.kind() {
             "function_item" => {
@@ -311,18 +619,213 @@ impl LanguageImpl for RustLanguage {
                 }
             }
             "macro_definition" => {
-                // Extract macro signature
+                // Extract macro signature with the matcher of each rule
                 if let Some(name) = node.child_by_field_name("name") {
                     let mut sig = String::from("macro_rules! ");
                     let name_text = &source[name.start_byte()..name.end_byte()];
                     sig.push_str(&String::from_utf8_lossy(name_text));
-                    sig.push_str(" { ... }");
+
+                    let mut cursor = node.walk();
+                    let matchers: Vec<String> = node
+                        .children(&mut cursor)
+                        .filter(|child| child.kind() == "macro_rule")
+                        .filter_map(|rule| rule.child_by_field_name("left"))
+                        .map(|left| {
+                            Self::normalized_text(source, left.start_byte(), left.end_byte())
+                        })
+                        .collect();
+
+                    if matchers.is_empty() {
+                        sig.push_str(" { ... }");
+                    } else {
+                        sig.push_str(" { ");
+                        for matcher in matchers {
+                            sig.push_str(&matcher);
+                            sig.push_str(" => { ... }; ");
+                        }
+                        sig.push('}');
+                    }
                     Some(sig)
                 } else {
                     None
                 }
             }
+            "macro_invocation" => {
+                // Item-level macros: list the statics or functions they declare
+                if !Self::is_item_declaring_macro(node) {
+                    return None;
+                }
+
+                let macro_node = node.child_by_field_name("macro")?;
+                let macro_name =
+                    Self::normalized_text(source, macro_node.start_byte(), macro_node.end_byte());
+                let body = node
+                    .children(&mut node.walk())
+                    .find(|child| child.kind() == "token_tree")?;
+
+                let mut declarations = Vec::new();
+                Self::collect_macro_fn_signatures(&body, source, &mut declarations);
+                if declarations.is_empty() {
+                    declarations = Self::collect_static_declarations(&body, source);
+                }
+
+                if declarations.is_empty() {
+                    return Some(format!("{macro_name}! {{ ... }}"));
+                }
+
+                Some(format!("{macro_name}! {{ {}; }}", declarations.join("; ")))
+            }
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_rust(source: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    fn root_signatures(source: &str) -> Vec<(String, String)> {
+        let lang = RustLanguage::new();
+        let tree = parse_rust(source);
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        root.children(&mut cursor)
+            .filter(|child| lang.is_acceptable_parent(child))
+            .filter_map(|child| {
+                lang.get_symbol_signature(&child, source.as_bytes())
+                    .map(|sig| (child.kind().to_string(), sig))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lazy_static_and_thread_local_signatures() {
+        let source = r#"
+lazy_static! {
+    static ref REGISTRY: Mutex<Vec<String>> = Mutex::new(Vec::new());
+    pub static ref COUNT: usize = 3;
+}
+
+thread_local! {
+    static CACHE: RefCell<u32> = RefCell::new(0);
+}
+"#;
+        let signatures = root_signatures(source);
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures[0].0, "macro_invocation");
+        assert_eq!(
+            signatures[0].1,
+            "lazy_static! { static ref REGISTRY: Mutex<Vec<String>>; pub static ref COUNT: usize; }"
+        );
+        assert_eq!(
+            signatures[1].1,
+            "thread_local! { static CACHE: RefCell<u32>; }"
+        );
+    }
+
+    #[test]
+    fn test_macro_rules_signature_lists_matchers() {
+        let source = r#"
+macro_rules! make_adder {
+    ($name:ident) => {
+        fn $name(a: i32) -> i32 { a + 1 }
+    };
+    ($name:ident, $n:expr) => {};
+}
+"#;
+        let signatures = root_signatures(source);
+        assert_eq!(
+            signatures[0].1,
+            "macro_rules! make_adder { ($name:ident) => { ... }; ($name:ident, $n:expr) => { ... }; }"
+        );
+    }
+
+    #[test]
+    fn test_functions_inside_cfg_macros_are_visible() {
+        let source = r#"
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        pub(crate) fn unix_only() -> bool { true }
+    } else {
+        pub fn other() -> bool { false }
+    }
+}
+"#;
+        let signatures = root_signatures(source);
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(
+            signatures[0].1,
+            "cfg_if::cfg_if! { pub(crate) fn unix_only() -> bool; pub fn other() -> bool; }"
+        );
+    }
+
+    #[test]
+    fn test_macro_fn_signature_lines_include_attributes() {
+        let source = r#"cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        #[cfg_attr(test, inline)]
+        #[must_use]
+        pub(crate) fn unix_only()
+            -> bool {
+            true
+        }
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+fn item() {}
+"#;
+        let tree = parse_rust(source);
+        let root = tree.root_node();
+        let lines = |line| RustLanguage::macro_fn_signature_lines(&root, source.as_bytes(), line);
+        assert_eq!(lines(7), Some((3, 6)));
+        assert_eq!(lines(3), Some((3, 6)));
+        assert_eq!(lines(2), None);
+        // Item-level functions are nodes of their own
+        assert_eq!(lines(13), None);
+        assert_eq!(
+            RustLanguage::attribute_start_line(&root, source.as_bytes(), 13),
+            Some(12)
+        );
+        assert_eq!(
+            RustLanguage::attribute_start_line(&root, source.as_bytes(), 1),
+            None
+        );
+
+        let signatures = root_signatures(source);
+        assert_eq!(
+            signatures[0].1,
+            "cfg_if::cfg_if! { pub(crate) fn unix_only() -> bool; }"
+        );
+    }
+
+    #[test]
+    fn test_statement_macros_are_not_acceptable_parents() {
+        let lang = RustLanguage::new();
+        let source = "fn main() { println!(\"hi\"); }";
+        let tree = parse_rust(source);
+
+        fn find_macro<'a>(node: Node<'a>) -> Option<Node<'a>> {
+            if node.kind() == "macro_invocation" {
+                return Some(node);
+            }
+            let mut cursor = node.walk();
+            let children: Vec<Node<'a>> = node.children(&mut cursor).collect();
+            children.into_iter().find_map(find_macro)
+        }
+
+        let invocation = find_macro(tree.root_node()).unwrap();
+        assert!(!lang.is_acceptable_parent(&invocation));
+        assert!(lang
+            .get_symbol_signature(&invocation, source.as_bytes())
+            .is_none());
+    }
+}
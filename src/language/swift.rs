@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for Swift
@@ -41,7 +42,7 @@ impl LanguageImpl for SwiftLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // Swift: Check for test functions in XCTest
@@ -54,7 +55,7 @@ impl LanguageImpl for SwiftLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.starts_with("test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Swift): test function");
+                            trace!("Test node detected (Swift): test function");
                         }
                         return true;
                     }
@@ -67,7 +68,7 @@ impl LanguageImpl for SwiftLanguage {
                     let attr_text = child.utf8_text(source).unwrap_or("");
                     if attr_text.contains("@Test") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Swift): @Test attribute");
+                            trace!("Test node detected (Swift): @Test attribute");
                         }
                         return true;
                     }
@@ -85,7 +86,7 @@ impl LanguageImpl for SwiftLanguage {
                     let inheritance_text = child.utf8_text(source).unwrap_or("");
                     if inheritance_text.contains("XCTestCase") {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (Swift): XCTestCase class");
+                            trace!("Test node detected (Swift): XCTestCase class");
                         }
                         return true;
                     }
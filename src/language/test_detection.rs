@@ -1,8 +1,9 @@
 use std::path::Path;
+use tracing::trace;
 
 /// Function to determine if a file is a test file based on common naming conventions and directory patterns
 pub fn is_test_file(path: &Path) -> bool {
-    let _debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let _debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // Check file name patterns
     if let Some(file_name) = path.file_name().and_then(|f| f.to_str()) {
@@ -13,7 +14,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name == "tests.rs"
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (Rust pattern): {file_name}");
+                trace!("Test file detected (Rust pattern): {file_name}");
             }
             return true;
         }
@@ -29,7 +30,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name.ends_with(".spec.tsx")
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (JS/TS pattern): {file_name}");
+                trace!("Test file detected (JS/TS pattern): {file_name}");
             }
             return true;
         }
@@ -39,7 +40,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name.ends_with("_test.py")
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (Python pattern): {file_name}");
+                trace!("Test file detected (Python pattern): {file_name}");
             }
             return true;
         }
@@ -47,7 +48,7 @@ pub fn is_test_file(path: &Path) -> bool {
         // Go: *_test.go
         if file_name.ends_with("_test.go") {
             if _debug_mode {
-                println!("DEBUG: Test file detected (Go pattern): {file_name}");
+                trace!("Test file detected (Go pattern): {file_name}");
             }
             return true;
         }
@@ -67,7 +68,7 @@ pub fn is_test_file(path: &Path) -> bool {
                     || file_name.ends_with(".cxx"))
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (C/C++ pattern): {file_name}");
+                trace!("Test file detected (C/C++ pattern): {file_name}");
             }
             return true;
         }
@@ -77,7 +78,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name.starts_with("Test") && file_name.ends_with(".java")
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (Java pattern): {file_name}");
+                trace!("Test file detected (Java pattern): {file_name}");
             }
             return true;
         }
@@ -88,7 +89,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name.ends_with("_spec.rb")
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (Ruby pattern): {file_name}");
+                trace!("Test file detected (Ruby pattern): {file_name}");
             }
             return true;
         }
@@ -98,7 +99,7 @@ pub fn is_test_file(path: &Path) -> bool {
             || file_name.starts_with("Test") && file_name.ends_with(".php")
         {
             if _debug_mode {
-                println!("DEBUG: Test file detected (PHP pattern): {file_name}");
+                trace!("Test file detected (PHP pattern): {file_name}");
             }
             return true;
         }
@@ -116,7 +117,7 @@ pub fn is_test_file(path: &Path) -> bool {
         || path_str.contains("/__test__/")
     {
         if _debug_mode {
-            println!("DEBUG: Test file detected (in test directory): {path_str}");
+            trace!("Test file detected (in test directory): {path_str}");
         }
         return true;
     }
@@ -774,6 +774,163 @@ fn test_csharp_language_implementation() {
     );
 }
 
+/// The first line of every node of one of `kinds`, with whether it is public
+fn public_nodes(extension: &str, source: &str, kinds: &[&str]) -> Vec<(String, bool)> {
+    fn walk(
+        node: tree_sitter::Node,
+        source: &str,
+        kinds: &[&str],
+        language_impl: &dyn LanguageImpl,
+        out: &mut Vec<(String, bool)>,
+    ) {
+        if kinds.contains(&node.kind()) {
+            let text = node.utf8_text(source.as_bytes()).unwrap();
+            let first_line = text.lines().next().unwrap().trim().to_string();
+            out.push((
+                first_line,
+                language_impl.is_public(&node, source.as_bytes()),
+            ));
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            walk(child, source, kinds, language_impl, out);
+        }
+    }
+
+    let language_impl = get_language_impl(extension).unwrap();
+    let mut parser = tree_sitter::Parser::new();
+    parser
+        .set_language(&language_impl.get_tree_sitter_language())
+        .unwrap();
+    let tree = parser.parse(source, None).unwrap();
+    let mut out = Vec::new();
+    walk(
+        tree.root_node(),
+        source,
+        kinds,
+        language_impl.as_ref(),
+        &mut out,
+    );
+    out
+}
+
+fn public_names(nodes: Vec<(String, bool)>) -> Vec<String> {
+    nodes
+        .into_iter()
+        .filter(|(_, public)| *public)
+        .map(|(line, _)| line)
+        .collect()
+}
+
+#[test]
+fn test_is_public() {
+    let rust = r#"pub struct Config;
+pub(crate) fn internal() {}
+impl Config {
+    pub fn load() -> Self { Config }
+    fn helper(&self) {}
+}
+impl Default for Config {
+    fn default() -> Self { Config }
+}
+struct Hidden;
+impl Hidden {}
+"#;
+    assert_eq!(
+        public_names(public_nodes(
+            "rs",
+            rust,
+            &["struct_item", "function_item", "impl_item"]
+        )),
+        vec![
+            "pub struct Config;",
+            "impl Config {",
+            "pub fn load() -> Self { Config }",
+            "impl Default for Config {",
+            "fn default() -> Self { Config }",
+        ]
+    );
+
+    let go = "package m\n\ntype Server struct{}\ntype client struct{}\nfunc New() *Server { return nil }\nfunc helper() {}\n";
+    assert_eq!(
+        public_names(public_nodes(
+            "go",
+            go,
+            &["type_declaration", "function_declaration"]
+        )),
+        vec!["type Server struct{}", "func New() *Server { return nil }"]
+    );
+
+    let typescript = r#"export function api(): void {}
+function local(): void {}
+export class Service {
+  public run(): void {}
+  private hide(): void {}
+  #secret(): void {}
+}
+class Internal {
+  run(): void {}
+}
+"#;
+    assert_eq!(
+        public_names(public_nodes(
+            "ts",
+            typescript,
+            &[
+                "function_declaration",
+                "class_declaration",
+                "method_definition"
+            ]
+        )),
+        // The declarations inside `export` statements
+        vec![
+            "function api(): void {}",
+            "class Service {",
+            "public run(): void {}",
+        ]
+    );
+
+    let python =
+        "def public(): pass\ndef _private(): pass\nclass Model:\n    def __init__(self): pass\n";
+    assert_eq!(
+        public_names(public_nodes(
+            "py",
+            python,
+            &["function_definition", "class_definition"]
+        )),
+        vec![
+            "def public(): pass",
+            "class Model:",
+            "def __init__(self): pass"
+        ]
+    );
+
+    let java = "public class Api {\n    void hidden() {}\n    public void run() {}\n}\ninterface Shape {\n    double area();\n}\npublic interface Named {\n    String name();\n}\n";
+    assert_eq!(
+        public_names(public_nodes(
+            "java",
+            java,
+            &[
+                "class_declaration",
+                "interface_declaration",
+                "method_declaration"
+            ]
+        )),
+        vec![
+            "public class Api {",
+            "public void run() {}",
+            "public interface Named {",
+            "String name();",
+        ]
+    );
+
+    let c = "int api(void) { return 0; }\nstatic int helper(void) { return 1; }\n";
+    assert_eq!(
+        public_names(public_nodes("c", c, &["function_definition"])),
+        vec!["int api(void) { return 0; }"]
+    );
+}
+
 // Helper function to print the AST structure
 fn print_ast_structure(node: tree_sitter::Node, depth: usize) {
     let indent = " ".repeat(depth * 2);
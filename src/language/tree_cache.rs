@@ -2,6 +2,7 @@ use anyhow::{Context, Result};
 use lru::LruCache;
 use std::num::NonZeroUsize;
 use std::sync::Mutex;
+use tracing::trace;
 use tree_sitter::Tree;
 
 // PHASE 4 OPTIMIZATION: Use environment variable or CPU count for cache size
@@ -70,7 +71,7 @@ pub fn get_or_parse_tree(
     let content_hash = compute_content_hash(content);
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // Try to get from cache first
     {
@@ -89,7 +90,7 @@ pub fn get_or_parse_tree(
                 }
 
                 if debug_mode {
-                    eprintln!("[DEBUG] Cache hit for file: {file_path}");
+                    trace!("Cache hit for file: {file_path}");
                 }
                 // PHASE 4 OPTIMIZATION: Now update LRU order with get
                 let (cached_tree, _) = cache.get(file_path).unwrap();
@@ -99,11 +100,11 @@ pub fn get_or_parse_tree(
                 // PHASE 4 OPTIMIZATION: LRU cache uses pop instead of remove
                 cache.pop(file_path);
                 if debug_mode {
-                    eprintln!("[DEBUG] Cache invalidated for file: {file_path} (content changed)");
+                    trace!("Cache invalidated for file: {file_path} (content changed)");
                 }
             }
         } else if debug_mode {
-            eprintln!("[DEBUG] Cache miss for file: {file_path}");
+            trace!("Cache miss for file: {file_path}");
         }
     }
 
@@ -121,8 +122,8 @@ pub fn get_or_parse_tree(
         cache.put(file_path.to_string(), (tree.clone(), content_hash));
 
         if debug_mode {
-            eprintln!("[DEBUG] Cached parsed tree for file: {file_path}");
-            eprintln!("[DEBUG] Current cache size: {} entries", cache.len());
+            trace!("Cached parsed tree for file: {file_path}");
+            trace!("Current cache size: {} entries", cache.len());
         }
     }
 
@@ -146,12 +147,12 @@ pub fn get_or_parse_tree(
 ///
 /// A Result containing the parsed tree, either from cache or freshly parsed using a pooled parser
 pub fn get_or_parse_tree_pooled(file_path: &str, content: &str, extension: &str) -> Result<Tree> {
-    use crate::language::parser_pool::{get_pooled_parser, return_pooled_parser};
+    use crate::language::parser_pool::{get_pooled_parser, return_pooled_parser, timed_parse};
 
     let content_hash = compute_content_hash(content);
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // Try to get from cache first
     {
@@ -170,7 +171,7 @@ pub fn get_or_parse_tree_pooled(file_path: &str, content: &str, extension: &str)
                 }
 
                 if debug_mode {
-                    eprintln!("[DEBUG] Tree cache hit for file: {file_path}");
+                    trace!("Tree cache hit for file: {file_path}");
                 }
                 // PHASE 4 OPTIMIZATION: Now update LRU order with get
                 let (cached_tree, _) = cache.get(file_path).unwrap();
@@ -180,13 +181,11 @@ pub fn get_or_parse_tree_pooled(file_path: &str, content: &str, extension: &str)
                 // PHASE 4 OPTIMIZATION: LRU cache uses pop instead of remove
                 cache.pop(file_path);
                 if debug_mode {
-                    println!(
-                        "[DEBUG] Tree cache invalidated for file: {file_path} (content changed)"
-                    );
+                    trace!("Tree cache invalidated for file: {file_path} (content changed)");
                 }
             }
         } else if debug_mode {
-            eprintln!("[DEBUG] Tree cache miss for file: {file_path}");
+            trace!("Tree cache miss for file: {file_path}");
         }
     }
 
@@ -195,8 +194,7 @@ pub fn get_or_parse_tree_pooled(file_path: &str, content: &str, extension: &str)
         "Failed to get pooled parser for extension: {extension}"
     ))?;
 
-    let tree = parser
-        .parse(content, None)
+    let tree = timed_parse(&mut parser, extension, content)
         .context(format!("Failed to parse file: {file_path}"))?;
 
     // Return parser to pool
@@ -211,8 +209,8 @@ pub fn get_or_parse_tree_pooled(file_path: &str, content: &str, extension: &str)
         cache.put(file_path.to_string(), (tree.clone(), content_hash));
 
         if debug_mode {
-            eprintln!("[DEBUG] Cached parsed tree for file: {file_path}");
-            eprintln!("[DEBUG] Current tree cache size: {} entries", cache.len());
+            trace!("Cached parsed tree for file: {file_path}");
+            trace!("Current tree cache size: {} entries", cache.len());
         }
     }
 
@@ -227,10 +225,10 @@ pub fn clear_tree_cache() {
     let mut cache = TREE_CACHE
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     if debug_mode {
-        eprintln!("[DEBUG] Clearing tree cache ({} entries)", cache.len());
+        trace!("Clearing tree cache ({} entries)", cache.len());
     }
 
     cache.clear();
@@ -252,10 +250,10 @@ pub fn invalidate_cache_entry(file_path: &str) {
     let mut cache = TREE_CACHE
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner());
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     if cache.pop(file_path).is_some() && debug_mode {
-        eprintln!("[DEBUG] Removed file from cache: {file_path}");
+        trace!("Removed file from cache: {file_path}");
     }
 }
 
@@ -1,4 +1,6 @@
+use super::common::is_exported;
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for TypeScript
@@ -33,6 +35,11 @@ impl LanguageImpl for TypeScriptLanguage {
         }
     }
 
+    fn is_doc_comment(&self, node: &Node, source: &[u8]) -> bool {
+        // JSDoc-style block comments
+        node.kind() == "comment" && node.utf8_text(source).unwrap_or("").starts_with("/**")
+    }
+
     fn is_acceptable_parent(&self, node: &Node) -> bool {
         matches!(
             node.kind(),
@@ -58,7 +65,7 @@ impl LanguageImpl for TypeScriptLanguage {
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
         // TypeScript test detection is the same as JavaScript
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
         let node_type = node.kind();
 
         // TypeScript: Check for describe/it/test blocks
@@ -74,9 +81,7 @@ impl LanguageImpl for TypeScriptLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name.contains("test") || name.contains("Test") {
                         if debug_mode {
-                            println!(
-                                "DEBUG: Test node detected (TypeScript): test function/method"
-                            );
+                            trace!("Test node detected (TypeScript): test function/method");
                         }
                         return true;
                     }
@@ -92,7 +97,7 @@ impl LanguageImpl for TypeScriptLanguage {
                     let name = child.utf8_text(source).unwrap_or("");
                     if name == "describe" || name == "it" || name == "test" || name == "expect" {
                         if debug_mode {
-                            println!("DEBUG: Test node detected (TypeScript): {name} call");
+                            trace!("Test node detected (TypeScript): {name} call");
                         }
                         return true;
                     }
@@ -103,6 +108,10 @@ impl LanguageImpl for TypeScriptLanguage {
         false
     }
 
+    fn is_public(&self, node: &Node, source: &[u8]) -> bool {
+        is_exported(node, source)
+    }
+
     fn get_symbol_signature(&self, node: &Node, source: &[u8]) -> Option<String> {
         match node.kind() {
             "function_declaration" => {
@@ -1,4 +1,5 @@
 use super::language_trait::LanguageImpl;
+use tracing::trace;
 use tree_sitter::{Language as TSLanguage, Node};
 
 /// Implementation of LanguageImpl for YAML
@@ -94,7 +95,7 @@ impl LanguageImpl for YamlLanguage {
     }
 
     fn is_acceptable_parent(&self, node: &Node) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Accept YAML structural elements (based on actual tree-sitter-yaml node types)
         let acceptable = matches!(
@@ -125,8 +126,8 @@ impl LanguageImpl for YamlLanguage {
         );
 
         if debug_mode && acceptable {
-            println!(
-                "DEBUG: YAML acceptable parent: {} at lines {}-{}",
+            trace!(
+                "YAML acceptable parent: {} at lines {}-{}",
                 node.kind(),
                 node.start_position().row + 1,
                 node.end_position().row + 1
@@ -137,14 +138,14 @@ impl LanguageImpl for YamlLanguage {
     }
 
     fn is_test_node(&self, node: &Node, source: &[u8]) -> bool {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
         // Check key-value pairs for test-related keys
         if self.is_key_value_pair(node) {
             if let Some(key_text) = self.extract_key_text(node, source) {
                 if self.contains_test_keywords(&key_text) {
                     if debug_mode {
-                        println!("DEBUG: Test node detected (YAML): test-related key");
+                        trace!("Test node detected (YAML): test-related key");
                     }
                     return true;
                 }
@@ -154,7 +155,7 @@ impl LanguageImpl for YamlLanguage {
             let full_text = self.get_node_text(node, source);
             if self.contains_test_keywords(&full_text) {
                 if debug_mode {
-                    println!("DEBUG: Test node detected (YAML): test-related value");
+                    trace!("Test node detected (YAML): test-related value");
                 }
                 return true;
             }
@@ -165,7 +166,7 @@ impl LanguageImpl for YamlLanguage {
             let comment_text = self.get_node_text(node, source);
             if self.contains_test_keywords(&comment_text) {
                 if debug_mode {
-                    println!("DEBUG: Test node detected (YAML): test-related comment");
+                    trace!("Test node detected (YAML): test-related comment");
                 }
                 return true;
             }
@@ -179,7 +180,7 @@ impl LanguageImpl for YamlLanguage {
             let scalar_text = self.get_node_text(node, source);
             if self.contains_test_keywords(&scalar_text) {
                 if debug_mode {
-                    println!("DEBUG: Test node detected (YAML): test-related scalar");
+                    trace!("Test node detected (YAML): test-related scalar");
                 }
                 return true;
             }
@@ -16,58 +16,31 @@
 //!
 //! ### Searching for code
 //!
-//! ```no_run
-//! use probe_code::search::{perform_probe, SearchOptions};
-//! use std::path::Path;
-//!
-//! // Create search options
-//! let options = SearchOptions {
-//!     path: Path::new("."),
-//!     queries: &vec!["function search".to_string()],
-//!     files_only: false,
-//!     custom_ignores: &[],
-//!     exclude_filenames: false,
-//!     reranker: "bm25",
-//!     frequency_search: true,
-//!     exact: false,
-//!     language: None,
-//!     max_results: Some(10),
-//!     max_bytes: None,
-//!     max_tokens: Some(10000),
-//!     allow_tests: false,
-//!     no_merge: false,
-//!     merge_threshold: None,
-//!     dry_run: false,
-//!     session: None,
-//!     timeout: 30,
-//! };
+//! The [`api`] module is the stable entry point for embedding probe; the lower-level
+//! modules it wraps change along with the CLI.
 //!
-//! let results = perform_probe(&options).unwrap();
-//! println!("Found {} results", results.results.len());
+//! ```no_run
+//! use probe_code::api::SearchRequest;
+//!
+//! let matches = SearchRequest::new("function search")
+//!     .path(".")
+//!     .max_results(10)
+//!     .max_tokens(10000)
+//!     .run()
+//!     .unwrap();
+//! println!("Found {} results", matches.len());
 //! ```
 //!
 //! ### Extracting code blocks
 //!
 //! ```no_run
-//! use probe_code::extract::{handle_extract, ExtractOptions};
-//!
-//! let options = ExtractOptions {
-//!     files: vec!["src/main.rs".to_string()],
-//!     custom_ignores: vec![],
-//!     context_lines: 0,
-//!     format: "text".to_string(),
-//!     from_clipboard: false,
-//!     input_file: None,
-//!     to_clipboard: false,
-//!     dry_run: false,
-//!     diff: false,
-//!     allow_tests: false,
-//!     keep_input: false,
-//!     prompt: None,
-//!     instructions: None,
-//! };
+//! use probe_code::api::ExtractRequest;
 //!
-//! handle_extract(options).unwrap();
+//! let blocks = ExtractRequest::new()
+//!     .target("src/main.rs#main")
+//!     .run()
+//!     .unwrap();
+//! println!("{}", blocks[0].code);
 //! ```
 //!
 //! ### AST pattern matching
@@ -94,17 +67,25 @@
 // Allow internal modules to reference the crate by its library name
 extern crate self as probe_code;
 
+pub mod api;
 pub mod bert_reranker;
 pub mod extract;
+pub mod git;
+pub mod interrupt;
 pub mod language;
+pub mod messages;
 pub mod models;
 pub mod path_resolver;
+pub mod progress;
 pub mod query;
 pub mod ranking;
+pub mod redact;
 pub mod search;
 pub mod simd_ranking;
 pub mod simd_test;
+pub mod snapshot;
 pub mod version;
+pub mod warnings;
 
 // Re-export commonly used types for convenience
 pub use extract::{
@@ -116,6 +97,8 @@ pub use path_resolver::resolve_path;
 pub use query::{
     format_and_print_query_results, handle_query, perform_query, AstMatch, QueryOptions,
 };
-pub use search::{format_and_print_search_results, perform_probe, SearchOptions};
+pub use search::{
+    format_and_print_search_results, perform_probe, perform_probe_streaming, SearchOptions,
+};
 
 // Tests are defined in their respective modules with #[cfg(test)]
@@ -0,0 +1,68 @@
+//! Diagnostic logging for `--log-level`, `--log-file` and `--log-format`.
+//!
+//! The library reports what it is doing through `tracing` events: pipeline steps at
+//! `debug`, per-file and per-node parsing details at `trace`. Without a subscriber those
+//! events cost nothing, so only the binary installs one, here.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::level_filters::LevelFilter;
+
+/// Level used when `--log-level` is not given
+fn default_level() -> LevelFilter {
+    // DEBUG=1 predates --log-level and is still what many scripts set
+    if std::env::var("DEBUG").unwrap_or_default() == "1" {
+        LevelFilter::DEBUG
+    } else {
+        LevelFilter::WARN
+    }
+}
+
+/// Install the global subscriber. `level` is one of the `--log-level` values and
+/// `format` is `text` or `json`.
+pub fn init(level: Option<&str>, file: Option<&Path>, format: &str) -> Result<()> {
+    let level = match level {
+        Some(level) => level.parse::<LevelFilter>()?,
+        None => default_level(),
+    };
+    if level == LevelFilter::OFF {
+        return Ok(());
+    }
+
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false);
+    // A subscriber set directly, rather than with `init()`, doesn't capture the `log`
+    // records of dependencies such as `ignore`, which would drown out probe's own
+    let result = match (file, format) {
+        (Some(path), format) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file {}", path.display()))?;
+            let builder = builder.with_ansi(false).with_writer(Mutex::new(file));
+            if format == "json" {
+                tracing::subscriber::set_global_default(builder.json().finish())
+            } else {
+                tracing::subscriber::set_global_default(builder.finish())
+            }
+        }
+        (None, "json") => tracing::subscriber::set_global_default(
+            builder
+                .with_ansi(false)
+                .with_writer(std::io::stderr)
+                .json()
+                .finish(),
+        ),
+        (None, _) => tracing::subscriber::set_global_default(
+            builder
+                .with_ansi(atty::is(atty::Stream::Stderr))
+                .with_writer(std::io::stderr)
+                .finish(),
+        ),
+    };
+    result.context("Failed to set up logging")
+}
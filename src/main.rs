@@ -1,44 +1,141 @@
-use anyhow::Result;
-use clap::{CommandFactory, Parser as ClapParser};
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use colored::*;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+mod arch;
 mod cli;
+mod config;
+mod context;
+mod doctor;
+mod export;
 mod grep;
+mod history;
+mod index;
+mod logging;
+mod mcp;
+mod metrics;
 mod query_validator;
-
-use cli::{Args, Commands};
+mod reload;
+mod remote;
+mod semver;
+mod serve;
+mod show;
+mod similar;
+mod strings;
+mod todos;
+mod tokens;
+
+use cli::{Args, Commands, ConfigAction, SimilarAction};
 use probe_code::{
-    extract::{handle_extract, extract_all_symbols_from_file, group_symbols_by_type, format_outline, ExtractOptions},
-    search::{format_and_print_search_results, perform_probe, SearchOptions},
+    extract::{
+        extract_all_symbols_from_file, format_outline, group_symbols_by_type, handle_extract,
+        ExtractOptions,
+    },
+    interrupt,
+    language::{factory::set_extension_mappings, format_parser_stats, set_pool_config, PoolConfig},
+    messages::{self, text, text_with, Msg},
+    models::SearchResult,
+    redact,
+    search::{
+        cache,
+        file_list_cache::{set_walk_options, WalkOptions},
+        filters::file_type_definitions,
+        format_and_print_search_results, perform_probe, perform_probe_streaming, print_match_count,
+        print_result_diff, print_search_tips, project_config, result_diff, saved_queries,
+        search_output::json_search_document,
+        search_tokens,
+        stemming::{selected_stemmer, set_config_file, set_stemmer, StemmerKind},
+        SearchOptions, StreamingPrinter,
+    },
+    snapshot::{ResultSnapshot, SnapshotKind},
+    warnings,
 };
 
 struct SearchParams {
     pattern: String,
+    /// Queries searched one after another (`--query`, `--queries-file`), in place of
+    /// `pattern`
+    queries: Vec<String>,
     paths: Vec<PathBuf>,
     files_only: bool,
     ignore: Vec<String>,
+    include: Vec<String>,
+    file_type: Vec<String>,
+    type_add: Vec<String>,
+    max_depth: Option<usize>,
+    hidden: bool,
+    max_filesize: Option<u64>,
+    binary: bool,
+    search_archives: bool,
     exclude_filenames: bool,
+    symbols_only: bool,
+    no_comments: bool,
+    comments_only: bool,
+    strings_only: bool,
     reranker: String,
+    vector_weight: f64,
+    boost_recent: Option<String>,
+    recency_weight: f64,
     frequency_search: bool,
     exact: bool,
+    regex: bool,
+    regex_escape: bool,
+    smart_case: bool,
+    fuzzy: bool,
+    case_sensitive: bool,
+    count_only: bool,
+    compare_to: Option<String>,
+    save_results: Option<String>,
+    then_extract: Option<usize>,
+    extract_context: usize,
+    extract_doc_comments: bool,
     strict_elastic_syntax: bool,
     language: Option<String>,
     max_results: Option<usize>,
     max_bytes: Option<usize>,
     max_tokens: Option<usize>,
+    max_files: Option<usize>,
+    max_block_lines: Option<usize>,
     allow_tests: bool,
     no_merge: bool,
+    no_attributes: bool,
     merge_threshold: Option<usize>,
     dry_run: bool,
+    sort: String,
+    reverse: bool,
+    group_by: Option<String>,
     format: String,
     session: Option<String>,
+    session_across_queries: bool,
+    session_ttl: Option<std::time::Duration>,
+    session_max_entries: Option<usize>,
+    within_session: Option<String>,
     timeout: u64,
     question: Option<String>,
     no_gitignore: bool,
+    files_from: Option<Vec<PathBuf>>,
+    changed: bool,
+    since: Option<String>,
+    blame: bool,
+    owners: bool,
+    include_deps: bool,
+    include_generated: bool,
+    filename_weight: f64,
+    dedupe_cross_file: bool,
+    diversify: Option<usize>,
+    offset: usize,
+    stable_order: bool,
+    redact_secrets: bool,
+    no_whole_files: bool,
+    stemmer: Option<String>,
     verbose: bool,
+    /// Options given on the command line, which override the project config
+    command_line_args: HashSet<String>,
 }
 
 struct BenchmarkParams {
@@ -60,29 +157,286 @@ struct OutlineParams {
     file: PathBuf,
     format: String,
     allow_tests: bool,
+    public_only: bool,
+}
+
+/// Let Ctrl-C stop a search or extraction at the next file, so the results found so far
+/// are still printed; a second Ctrl-C quits at once. Installed once per run, each search
+/// or extraction only clearing the interrupt.
+fn install_interrupt_handler() {
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        interrupt::interrupt();
+        eprintln!("\nInterrupted, finishing with the results found so far (Ctrl-C again to quit)");
+
+        if tokio::signal::ctrl_c().await.is_ok() {
+            // Output may have stopped inside a colored span
+            if atty::is(atty::Stream::Stdout) {
+                print!("\x1b[0m");
+                let _ = std::io::Write::flush(&mut std::io::stdout());
+            }
+            std::process::exit(130);
+        }
+    });
+}
+
+/// List the files left out by `--max-filesize`, for the text formats
+fn print_large_files_skipped(large_files: &[(String, u64)]) {
+    if large_files.is_empty() {
+        return;
+    }
+    println!();
+    println!(
+        "{} {}",
+        text(Msg::LargeFilesSkipped).yellow().bold(),
+        large_files.len()
+    );
+    for (file, bytes) in large_files {
+        println!("  {file} ({bytes} bytes)");
+    }
+}
+
+/// Machine-readable search output, where the results carry the summary and notes that
+/// text formats print around them
+fn is_structured_format(format: &str) -> bool {
+    matches!(format, "json" | "ndjson" | "xml")
 }
 
-fn handle_search(params: SearchParams) -> Result<()> {
-    // Validate query syntax if strict mode is enabled
-    if params.strict_elastic_syntax {
-        query_validator::validate_strict_elastic_syntax(&params.pattern)?;
+/// The pattern of a search, or the queries of a batch search
+fn search_patterns(params: &SearchParams) -> &[String] {
+    if params.queries.is_empty() {
+        std::slice::from_ref(&params.pattern)
+    } else {
+        &params.queries
     }
+}
+
+/// The options of a search for `queries` (ANDed together)
+fn build_search_options<'a>(
+    params: &'a SearchParams,
+    queries: &'a [String],
+    frequency_search: bool,
+) -> SearchOptions<'a> {
+    SearchOptions {
+        paths: &params.paths,
+        queries,
+        files_only: params.files_only,
+        custom_ignores: &params.ignore,
+        exclude_filenames: params.exclude_filenames,
+        symbols_only: params.symbols_only,
+        no_comments: params.no_comments,
+        comments_only: params.comments_only,
+        strings_only: params.strings_only,
+        boost_recent: params.boost_recent.as_deref(),
+        recency_weight: params.recency_weight,
+        changed: params.changed,
+        since: params.since.as_deref(),
+        blame: params.blame,
+        owners: params.owners,
+        reranker: &params.reranker,
+        frequency_search,
+        exact: params.exact,
+        regex: params.regex,
+        no_whole_files: params.no_whole_files,
+        language: params.language.as_deref(),
+        max_results: params.max_results,
+        max_bytes: params.max_bytes,
+        max_tokens: params.max_tokens,
+        max_files: params.max_files,
+        max_block_lines: params.max_block_lines,
+        allow_tests: params.allow_tests,
+        no_merge: params.no_merge,
+        merge_threshold: params.merge_threshold,
+        dry_run: params.dry_run,
+        session: params.session.as_deref(),
+        session_across_queries: params.session_across_queries,
+        session_ttl: params.session_ttl,
+        session_max_entries: params.session_max_entries,
+        within_session: params.within_session.as_deref(),
+        include: &params.include,
+        file_types: &params.file_type,
+        type_definitions: &params.type_add,
+        offset: params.offset,
+        stable_order: params.stable_order,
+        timeout: params.timeout,
+        question: params.question.as_deref(),
+        no_gitignore: params.no_gitignore,
+        no_attributes: params.no_attributes,
+        include_deps: params.include_deps,
+        filename_weight: params.filename_weight,
+        dedupe_cross_file: params.dedupe_cross_file,
+        diversify: params.diversify,
+        regex_escape: params.regex_escape,
+        include_generated: params.include_generated,
+        sort: &params.sort,
+        reverse: params.reverse,
+        smart_case: params.smart_case,
+        files_from: params.files_from.as_deref(),
+        vector_weight: params.vector_weight,
+        fuzzy: params.fuzzy,
+        count_only: params.count_only,
+        case_sensitive: params.case_sensitive,
+    }
+}
+
+/// Search for each query of a batch on its own, one after another, so they share the
+/// process's caches of file lists and parsed files. Text formats print each query's
+/// results under a heading; JSON prints one document with the results keyed by query.
+fn run_batch_search(params: &SearchParams, frequency_search: bool) -> Result<()> {
+    let mut documents = Vec::new();
+    for (index, query) in params.queries.iter().enumerate() {
+        if interrupt::is_interrupted() {
+            break;
+        }
+        let search_options =
+            build_search_options(params, std::slice::from_ref(query), frequency_search);
+        let mut limited_results = perform_probe(&search_options)?;
+        if params.redact_secrets {
+            let redacted = redact::redact_results(&mut limited_results.results);
+            if redacted > 0 && params.format != "json" {
+                eprintln!("Redacted {redacted} possible secrets from the results");
+            }
+        }
+        if params.format == "json" {
+            let results: Vec<&SearchResult> = limited_results.results.iter().collect();
+            let mut document = json_search_document(
+                &results,
+                Some(&limited_results.skipped_files),
+                limited_results.limits_applied.as_ref(),
+                limited_results.session_tokens,
+                limited_results.total_ranked,
+                &limited_results.large_files_skipped,
+            );
+            document["query"] = serde_json::json!(query);
+            documents.push(document);
+            continue;
+        }
+
+        if index > 0 {
+            println!();
+        }
+        println!(
+            "## {}",
+            text_with(Msg::BatchQuery, &[("query", query)]).bold()
+        );
+        println!();
+        if limited_results.results.is_empty() {
+            println!("{}", text(Msg::NoResults).yellow().bold());
+            continue;
+        }
+        let query_plan = probe_code::search::query::create_query_plan(query, false).ok();
+        format_and_print_search_results(
+            &limited_results.results,
+            params.dry_run,
+            &params.format,
+            query_plan.as_ref(),
+            Some(&limited_results.skipped_files),
+            limited_results.limits_applied.as_ref(),
+            limited_results.session_tokens,
+            limited_results.total_ranked,
+            &limited_results.large_files_skipped,
+            params.group_by.as_deref(),
+        );
+    }
+    if params.format == "json" {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({ "queries": documents }))?
+        );
+    }
+    Ok(())
+}
+
+fn handle_search(mut params: SearchParams) -> Result<()> {
+    // Expand `@name` saved queries from the project's config
+    if params.pattern.contains('@') || params.queries.iter().any(|query| query.contains('@')) {
+        let queries = saved_queries::project_queries(params.paths.first().unwrap());
+        params.pattern = saved_queries::expand_saved_queries(&params.pattern, &queries);
+        for query in &mut params.queries {
+            *query = saved_queries::expand_saved_queries(query, &queries);
+        }
+    }
+
+    let stemmer_from_config = apply_project_config(&mut params)?;
+    warnings::set_json(params.format == "json" || params.format == "ndjson");
+    set_walk_options(WalkOptions {
+        max_depth: params.max_depth,
+        hidden: params.hidden,
+        max_filesize: params.max_filesize,
+        binary: params.binary,
+        search_archives: params.search_archives,
+    });
+
+    // Validate query syntax if strict mode is enabled; a regex has no query syntax
+    if params.strict_elastic_syntax && !params.regex {
+        for pattern in search_patterns(&params) {
+            query_validator::validate_strict_elastic_syntax(pattern)?;
+        }
+    }
+    if !params.queries.is_empty() {
+        let single_search_option = [
+            ("--count-only", params.count_only),
+            ("--save-results", params.save_results.is_some()),
+            ("--compare-to", params.compare_to.is_some()),
+            ("--then-extract", params.then_extract.is_some()),
+        ]
+        .into_iter()
+        .find_map(|(option, given)| given.then_some(option));
+        if let Some(option) = single_search_option {
+            anyhow::bail!("{option} works with a single search; drop --query and --queries-file");
+        }
+        if matches!(params.format.as_str(), "ndjson" | "xml") {
+            anyhow::bail!("Batch searches print text or JSON keyed by query; use --format json");
+        }
+    }
+    if params.group_by.is_some()
+        && !matches!(params.format.as_str(), "terminal" | "markdown" | "plain")
+    {
+        anyhow::bail!(
+            "--group-by organizes terminal, markdown and plain output; add --format terminal"
+        );
+    }
+    if params.then_extract.is_some() && params.format == "ndjson" {
+        anyhow::bail!(
+            "--then-extract prints an extraction, which has no ndjson format; use --format json"
+        );
+    }
+    interrupt::reset();
 
     // Print version at the start for text-based formats
-    if params.verbose && params.format != "json" && params.format != "xml" {
+    if params.verbose && !is_structured_format(&params.format) {
         println!("Probe version: {}", probe_code::version::get_version());
     }
 
     let use_frequency = params.frequency_search;
 
+    // Select the stemmer before any tokenization: CLI > PROBE_STEMMER > config files
+    let stemmer = match &params.stemmer {
+        Some(name) => Some(
+            name.parse::<StemmerKind>()
+                .map_err(|e| anyhow::anyhow!(e))?,
+        ),
+        None => stemmer_from_config,
+    };
+    if let Some(kind) = stemmer {
+        set_stemmer(kind);
+    }
+
     // Don't print these headers for JSON/XML formats (only if verbose)
-    if params.verbose && params.format != "json" && params.format != "xml" {
-        println!("{} {}", "Pattern:".bold().green(), params.pattern);
+    if params.verbose && !is_structured_format(&params.format) {
         println!(
             "{} {}",
-            "Path:".bold().green(),
-            params.paths.first().unwrap().display()
+            "Pattern:".bold().green(),
+            search_patterns(&params).join(" | ")
         );
+        let paths: Vec<String> = params
+            .paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        println!("{} {}", "Path:".bold().green(), paths.join(", "));
     }
 
     // Show advanced options if they differ from defaults
@@ -93,9 +447,45 @@ fn handle_search(params: SearchParams) -> Result<()> {
     if params.exclude_filenames {
         advanced_options.push("Exclude filenames".to_string());
     }
+    if params.symbols_only {
+        advanced_options.push("Symbols only".to_string());
+    }
+    if params.no_comments {
+        advanced_options.push("No comments".to_string());
+    }
+    if params.comments_only {
+        advanced_options.push("Comments only".to_string());
+    }
+    if params.strings_only {
+        advanced_options.push("Strings only".to_string());
+    }
     if params.reranker != "hybrid" {
         advanced_options.push(format!("Reranker: {}", params.reranker));
     }
+    if params.reranker == "hybrid-trigram" {
+        advanced_options.push(format!("Vector weight: {}", params.vector_weight));
+    }
+    if let Some(source) = &params.boost_recent {
+        advanced_options.push(format!(
+            "Recency boost: {source} (weight {})",
+            params.recency_weight
+        ));
+    }
+    if params.fuzzy {
+        advanced_options.push("Fuzzy".to_string());
+    }
+    if params.count_only {
+        advanced_options.push("Count only".to_string());
+    }
+    if let Some(saved_path) = &params.compare_to {
+        advanced_options.push(format!("Compare to: {saved_path}"));
+    }
+    if let Some(top_k) = params.then_extract {
+        advanced_options.push(format!("Then extract: top {top_k}"));
+    }
+    if params.case_sensitive {
+        advanced_options.push("Case sensitive".to_string());
+    }
     if !use_frequency {
         advanced_options.push("Frequency search disabled".to_string());
     }
@@ -108,17 +498,117 @@ fn handle_search(params: SearchParams) -> Result<()> {
     if params.no_gitignore {
         advanced_options.push("Ignoring .gitignore".to_string());
     }
+    if !params.include.is_empty() {
+        advanced_options.push(format!("Include: {}", params.include.join(", ")));
+    }
+    if !params.file_type.is_empty() {
+        advanced_options.push(format!("Types: {}", params.file_type.join(", ")));
+    }
+    if let Some(depth) = params.max_depth {
+        advanced_options.push(format!("Max depth: {depth}"));
+    }
+    if params.hidden {
+        advanced_options.push("Hidden files".to_string());
+    }
+    if let Some(size) = params.max_filesize {
+        advanced_options.push(format!("Max file size: {size} bytes"));
+    }
+    if params.binary {
+        advanced_options.push("Binary files".to_string());
+    }
+    if params.search_archives {
+        advanced_options.push("Archives".to_string());
+    }
+    if let Some(files) = &params.files_from {
+        advanced_options.push(format!("Files from list: {}", files.len()));
+    }
+    if params.changed {
+        advanced_options.push("Changed files".to_string());
+    }
+    if let Some(reference) = &params.since {
+        advanced_options.push(format!("Changed since: {reference}"));
+    }
+    if params.blame {
+        advanced_options.push("Blame".to_string());
+    }
+    if params.owners {
+        advanced_options.push("Code owners".to_string());
+    }
+    if params.include_deps {
+        advanced_options.push("Including dependency sources".to_string());
+    }
+    if params.include_generated {
+        advanced_options.push("Including generated files".to_string());
+    }
+    if selected_stemmer() != StemmerKind::default() {
+        advanced_options.push(format!("Stemmer: {}", selected_stemmer()));
+    }
+    if params.exact && !params.regex_escape {
+        advanced_options.push("Raw regex (no escaping)".to_string());
+    }
+    if params.regex {
+        advanced_options.push("Regex".to_string());
+    }
+    if params.exact && params.smart_case {
+        advanced_options.push("Smart case".to_string());
+    }
+    if params.filename_weight != 1.0 {
+        advanced_options.push(format!("Filename weight: {}", params.filename_weight));
+    }
+    if params.dedupe_cross_file {
+        advanced_options.push("Cross-file dedup".to_string());
+    }
+    if let Some(per_directory) = params.diversify {
+        advanced_options.push(format!("Diversify: {per_directory} per directory"));
+    }
+    if params.offset > 0 {
+        advanced_options.push(format!("Offset: {}", params.offset));
+    }
+    if params.stable_order {
+        advanced_options.push("Stable order".to_string());
+    }
+    if params.redact_secrets {
+        advanced_options.push("Redacting secrets".to_string());
+    }
+    if params.no_whole_files {
+        advanced_options.push("No whole files".to_string());
+    }
     if params.no_merge {
         advanced_options.push("No block merging".to_string());
     }
+    if params.no_attributes {
+        advanced_options.push("No attribute inclusion".to_string());
+    }
     if let Some(threshold) = params.merge_threshold {
         advanced_options.push(format!("Merge threshold: {threshold}"));
     }
+    if let Some(max_lines) = params.max_block_lines {
+        advanced_options.push(format!("Max block lines: {max_lines}"));
+    }
     if params.dry_run {
         advanced_options.push("Dry run (file names and lines only)".to_string());
     }
+    if params.sort != "score" || params.reverse {
+        let order = if params.reverse { " (reversed)" } else { "" };
+        advanced_options.push(format!("Sort: {}{order}", params.sort));
+    }
+    if let Some(key) = &params.group_by {
+        advanced_options.push(format!("Group by: {key}"));
+    }
     if let Some(session) = &params.session {
         advanced_options.push(format!("Session: {session}"));
+        if params.session_across_queries {
+            advanced_options.push("Session-wide dedup".to_string());
+        }
+        if let Some(ttl) = params.session_ttl {
+            advanced_options.push(format!("Session TTL: {}s", ttl.as_secs()));
+        }
+        if let Some(max_entries) = params.session_max_entries {
+            advanced_options.push(format!("Session max entries: {max_entries}"));
+        }
+    }
+    if let Some(session) = &params.within_session {
+        advanced_options.push(format!("Within session: {session}"));
     }
 
     // Show timeout if it's not the default value of 30 seconds
@@ -126,11 +616,7 @@ fn handle_search(params: SearchParams) -> Result<()> {
         advanced_options.push(format!("Timeout: {} seconds", params.timeout));
     }
 
-    if params.verbose
-        && !advanced_options.is_empty()
-        && params.format != "json"
-        && params.format != "xml"
-    {
+    if params.verbose && !advanced_options.is_empty() && !is_structured_format(&params.format) {
         println!(
             "{} {}",
             "Options:".bold().green(),
@@ -138,35 +624,123 @@ fn handle_search(params: SearchParams) -> Result<()> {
         );
     }
 
+    if !params.queries.is_empty() {
+        return run_batch_search(&params, use_frequency);
+    }
+
     let start_time = Instant::now();
 
     // Create a vector with the pattern
     let query = vec![params.pattern.clone()];
-
-    let search_options = SearchOptions {
-        path: params.paths.first().unwrap(),
-        queries: &query,
-        files_only: params.files_only,
-        custom_ignores: &params.ignore,
-        exclude_filenames: params.exclude_filenames,
-        reranker: &params.reranker,
-        frequency_search: use_frequency,
-        exact: params.exact,
-        language: params.language.as_deref(),
-        max_results: params.max_results,
-        max_bytes: params.max_bytes,
-        max_tokens: params.max_tokens,
-        allow_tests: params.allow_tests,
-        no_merge: params.no_merge,
-        merge_threshold: params.merge_threshold,
-        dry_run: params.dry_run,
-        session: params.session.as_deref(),
-        timeout: params.timeout,
-        question: params.question.as_deref(),
-        no_gitignore: params.no_gitignore,
+    let search_options = build_search_options(&params, &query, use_frequency);
+
+    // Print results as they're ranked, unless the format or an option needs all of them
+    let streaming = StreamingPrinter::supports(&params.format)
+        && params.save_results.is_none()
+        && params.compare_to.is_none()
+        && params.then_extract.is_none()
+        && params.group_by.is_none()
+        && !params.count_only;
+    let (mut limited_results, printer) = if streaming {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let (format, dry_run, redact_secrets) = (
+            params.format.as_str(),
+            params.dry_run,
+            params.redact_secrets,
+        );
+        let (limited, (printer, redacted)) = std::thread::scope(|scope| {
+            let consumer = scope.spawn(move || {
+                let mut printer = StreamingPrinter::new(format, dry_run);
+                let mut redacted = 0;
+                for mut result in receiver {
+                    if redact_secrets {
+                        redacted += redact::redact_results(std::slice::from_mut(&mut result));
+                    }
+                    printer.print(result);
+                }
+                (printer, redacted)
+            });
+            let limited = perform_probe_streaming(&search_options, sender);
+            (limited, consumer.join().expect("result printer panicked"))
+        });
+        if redacted > 0 && !is_structured_format(&params.format) {
+            eprintln!("Redacted {redacted} possible secrets from the results");
+        }
+        (limited?, Some(printer))
+    } else {
+        (perform_probe(&search_options)?, None)
     };
-
-    let limited_results = perform_probe(&search_options)?;
+    let result_count = printer
+        .as_ref()
+        .map_or(limited_results.results.len(), StreamingPrinter::count);
+    if let Some(count) = &limited_results.match_count {
+        print_match_count(count, &params.format);
+        return Ok(());
+    }
+    if params.redact_secrets {
+        let redacted = redact::redact_results(&mut limited_results.results);
+        if redacted > 0 && !is_structured_format(&params.format) {
+            eprintln!("Redacted {redacted} possible secrets from the results");
+        }
+    }
+    if let Some(path) = &params.save_results {
+        ResultSnapshot::new(
+            SnapshotKind::Search,
+            &params.pattern,
+            &limited_results.results,
+        )
+        .save(Path::new(path))?;
+    }
+    if let Some(saved_path) = &params.compare_to {
+        let saved_path = Path::new(saved_path);
+        let saved = result_diff::load_saved_results(saved_path)?;
+        let diff = result_diff::diff_results(&saved, &limited_results.results);
+        print_result_diff(&diff, saved_path, &params.format);
+        return Ok(());
+    }
+    if let Some(top_k) = params.then_extract {
+        let targets: Vec<String> = limited_results
+            .results
+            .iter()
+            .filter(|result| !result.file.is_empty())
+            .take(top_k)
+            .map(|result| format!("{}:{}-{}", result.file, result.lines.0, result.lines.1))
+            .collect();
+        // Without results there is nothing to extract; report them as a search would
+        if !targets.is_empty() {
+            return handle_extract(ExtractOptions {
+                files: targets,
+                custom_ignores: Vec::new(),
+                context_lines: params.extract_context,
+                // Extract has no outline rendering of its own
+                format: match params.format.as_str() {
+                    "outline" | "terminal" => "color".to_string(),
+                    format => format.to_string(),
+                },
+                from_clipboard: false,
+                input_file: None,
+                to_clipboard: false,
+                dry_run: params.dry_run,
+                diff: false,
+                // The search already left out what it should
+                allow_tests: true,
+                keep_input: false,
+                prompt: None,
+                instructions: None,
+                no_gitignore: true,
+                with_doc_comments: params.extract_doc_comments,
+                no_attributes: params.no_attributes,
+                deps: Vec::new(),
+                redact_secrets: params.redact_secrets,
+                no_whole_files: params.no_whole_files,
+                save_results: None,
+                include: Vec::new(),
+                blame: params.blame,
+                owners: params.owners,
+                public_only: false,
+            });
+        }
+    }
 
     // Calculate search time
     let duration = start_time.elapsed();
@@ -180,9 +754,9 @@ fn handle_search(params: SearchParams) -> Result<()> {
         probe_code::search::query::create_query_plan(&search_options.queries[0], false).ok()
     };
 
-    if limited_results.results.is_empty() {
+    if result_count == 0 {
         // For JSON and XML formats, still call format_and_print_search_results
-        if params.format == "json" || params.format == "xml" {
+        if is_structured_format(&params.format) {
             format_and_print_search_results(
                 &limited_results.results,
                 search_options.dry_run,
@@ -190,6 +764,10 @@ fn handle_search(params: SearchParams) -> Result<()> {
                 query_plan.as_ref(),
                 Some(&limited_results.skipped_files),
                 limited_results.limits_applied.as_ref(),
+                limited_results.session_tokens,
+                limited_results.total_ranked,
+                &limited_results.large_files_skipped,
+                params.group_by.as_deref(),
             );
         } else {
             // Check if results are empty because all were filtered by session cache
@@ -198,52 +776,66 @@ fn handle_search(params: SearchParams) -> Result<()> {
                 // All results were already seen - show clear exhaustion signal
                 println!(
                     "{} {}",
-                    "Filtered already-seen blocks (session deduplication):"
-                        .yellow()
-                        .bold(),
+                    text(Msg::SessionFiltered).yellow().bold(),
                     cached_skipped
                 );
                 println!();
-                println!(
-                    "{}",
-                    "✓ All results retrieved for this query. No need to search again with this session.".green().bold()
-                );
+                println!("{}", text(Msg::AllRetrieved).green().bold());
             } else {
                 // Genuinely no results found - show helpful tips
-                println!("{}", "No results found.".yellow().bold());
-                println!();
-                println!("💡 Tips to improve your search:");
-                println!("  - Try synonyms or related terms (e.g., \"fetch\" instead of \"get\")");
-                println!("  - Use broader terms without AND operators");
-                println!("  - Check spelling of function/class names");
-                println!("  - Remove file type filters to search all files");
-                println!("  - Use exact:false (default) for stemming, or exact:true for precise symbol lookup");
+                println!("{}", text(Msg::NoResults).yellow().bold());
+                print_search_tips();
             }
+            print_large_files_skipped(&limited_results.large_files_skipped);
             if params.verbose {
                 println!();
-                println!("Search completed in {duration:.2?}");
+                println!(
+                    "{}",
+                    text_with(
+                        Msg::SearchCompleted,
+                        &[("time", &format!("{duration:.2?}"))]
+                    )
+                );
             }
         }
     } else {
         // For non-JSON/XML formats, print search time (only if verbose)
-        if params.verbose && params.format != "json" && params.format != "xml" {
-            println!("Search completed in {duration:.2?}");
+        if params.verbose && !is_structured_format(&params.format) {
+            println!(
+                "{}",
+                text_with(
+                    Msg::SearchCompleted,
+                    &[("time", &format!("{duration:.2?}"))]
+                )
+            );
             println!();
         }
 
-        format_and_print_search_results(
-            &limited_results.results,
-            search_options.dry_run,
-            &params.format,
-            query_plan.as_ref(),
-            Some(&limited_results.skipped_files),
-            limited_results.limits_applied.as_ref(),
-        );
+        match printer {
+            Some(printer) => printer.finish(
+                Some(&limited_results.skipped_files),
+                limited_results.limits_applied.as_ref(),
+                limited_results.session_tokens,
+                limited_results.total_ranked,
+                &limited_results.large_files_skipped,
+            ),
+            None => format_and_print_search_results(
+                &limited_results.results,
+                search_options.dry_run,
+                &params.format,
+                query_plan.as_ref(),
+                Some(&limited_results.skipped_files),
+                limited_results.limits_applied.as_ref(),
+                limited_results.session_tokens,
+                limited_results.total_ranked,
+                &limited_results.large_files_skipped,
+                params.group_by.as_deref(),
+            ),
+        }
 
         // Don't print skipped files info for JSON/XML/outline-xml formats (they include it in structured output)
         if !limited_results.skipped_files.is_empty()
-            && params.format != "json"
-            && params.format != "xml"
+            && !is_structured_format(&params.format)
             && params.format != "outline-xml"
         {
             let use_stderr = false;
@@ -261,15 +853,18 @@ fn handle_search(params: SearchParams) -> Result<()> {
 
             if let Some(limits) = &limited_results.limits_applied {
                 output!();
-                output!("{}", "Limits applied:".yellow().bold());
+                output!("{}", text(Msg::LimitsApplied).yellow().bold());
                 if let Some(max_results) = limits.max_results {
-                    output!("  {} {max_results}", "Max results:".yellow());
+                    output!("  {} {max_results}", text(Msg::MaxResults).yellow());
                 }
                 if let Some(max_bytes) = limits.max_bytes {
-                    output!("  {} {max_bytes}", "Max bytes:".yellow());
+                    output!("  {} {max_bytes}", text(Msg::MaxBytes).yellow());
                 }
                 if let Some(max_tokens) = limits.max_tokens {
-                    output!("  {} {max_tokens}", "Max tokens:".yellow());
+                    output!("  {} {max_tokens}", text(Msg::MaxTokens).yellow());
+                }
+                if let Some(max_files) = limits.max_files {
+                    output!("  {} {max_files}", text(Msg::MaxFiles).yellow());
                 }
 
                 output!();
@@ -282,14 +877,14 @@ fn handle_search(params: SearchParams) -> Result<()> {
 
                 output!(
                     "{} {}",
-                    "Skipped files due to limits:".yellow().bold(),
+                    text(Msg::SkippedByLimits).yellow().bold(),
                     total_skipped
                 );
 
                 // Show list of skipped files with match counts
                 if results_skipped > 0 {
                     output!();
-                    output!("{}", "Remaining files not shown:".yellow());
+                    output!("{}", text(Msg::RemainingFiles).yellow());
 
                     // Group skipped files by file path and aggregate match counts
                     let mut file_matches: HashMap<String, (HashSet<String>, usize)> =
@@ -341,29 +936,31 @@ fn handle_search(params: SearchParams) -> Result<()> {
                         output!("  {} <{}> <{}>", file_path, unique_matches, total_matches);
                     }
 
-                    output!();
-                    output!(
-                        "💡 {} <uniq> = unique search terms matched, <all> = total match blocks",
-                        "Hint:".dimmed()
-                    );
+                    if messages::tips_enabled() {
+                        output!();
+                        output!(
+                            "💡 {} {}",
+                            text(Msg::Hint).dimmed(),
+                            text(Msg::HintMatchCounts)
+                        );
+                    }
                 }
 
                 // Show guidance message to get more results (pagination)
-                if total_skipped > 0 {
+                if total_skipped > 0 && messages::tips_enabled() {
                     output!();
-                    if let Some(session_id) = search_options.session {
-                        if !session_id.is_empty() && session_id != "new" {
-                            output!("💡 More results may be available. Re-run with session: \"{session_id}\" and nextPage: true. Stop when you see \"All results retrieved\".");
-                        } else {
-                            output!("💡 More results may be available. Re-run with the session ID shown above and nextPage: true. Stop when you see \"All results retrieved\".");
+                    let tip = match search_options.session {
+                        Some(session_id) if !session_id.is_empty() && session_id != "new" => {
+                            text_with(Msg::TipMoreResultsInSession, &[("session", &session_id)])
                         }
-                    } else {
-                        output!("💡 More results may be available. Use --session with the session ID above and nextPage: true. Stop when you see \"All results retrieved\".");
-                    }
+                        Some(_) => text(Msg::TipMoreResultsShownSession).to_string(),
+                        None => text(Msg::TipMoreResultsUseSession).to_string(),
+                    };
+                    output!("💡 {tip}");
                 }
 
                 // Show breakdown in debug mode
-                if std::env::var("DEBUG").is_ok() && total_skipped > 0 {
+                if tracing::enabled!(tracing::Level::DEBUG) && total_skipped > 0 {
                     output!();
                     if results_skipped > 0 {
                         output!(
@@ -383,25 +980,38 @@ fn handle_search(params: SearchParams) -> Result<()> {
             }
         }
 
+        if !is_structured_format(&params.format) && params.format != "outline-xml" {
+            print_large_files_skipped(&limited_results.large_files_skipped);
+        }
+
         // Display information about cached blocks (when there are still results to show)
         if let Some(cached_skipped) = limited_results.cached_blocks_skipped {
-            if cached_skipped > 0 {
+            if cached_skipped > 0 && !is_structured_format(&params.format) {
                 println!();
                 println!(
                     "{} {}",
-                    "Filtered already-seen blocks (session deduplication):"
-                        .yellow()
-                        .bold(),
+                    text(Msg::SessionFiltered).yellow().bold(),
                     cached_skipped
                 );
             }
         }
     }
 
+    if let Some(session_tokens) = limited_results.session_tokens {
+        if !is_structured_format(&params.format) {
+            println!();
+            println!(
+                "{} {}",
+                text(Msg::SessionTokens).yellow().bold(),
+                session_tokens
+            );
+        }
+    }
+
     // Add helpful tip at the very bottom of output (only when there are results, not for JSON/XML formats)
-    if !limited_results.results.is_empty() && params.format != "json" && params.format != "xml" {
+    if result_count > 0 && !is_structured_format(&params.format) && messages::tips_enabled() {
         println!();
-        println!("💡 Tip: Use `probe extract <file>:<line>` to see full function/class context for any result above");
+        println!("💡 {}", text(Msg::TipExtract));
     }
 
     Ok(())
@@ -429,6 +1039,12 @@ fn handle_benchmark(params: BenchmarkParams) -> Result<()> {
         "parsing" => {
             cmd.arg("--bench").arg("parsing_benchmarks");
         }
+        "ranking" => {
+            cmd.arg("--bench").arg("ranking_benchmarks");
+        }
+        "startup" => {
+            cmd.arg("--bench").arg("startup_benchmarks");
+        }
         "all" => {
             // Run all benchmarks (default)
         }
@@ -493,15 +1109,22 @@ fn handle_outline(params: OutlineParams) -> Result<()> {
         if params.allow_tests {
             println!("{}", "Including test symbols".yellow());
         }
+        if params.public_only {
+            println!("{}", "Public symbols only".yellow());
+        }
         println!();
     }
 
     // Extract all symbols from the file
-    let symbols = extract_all_symbols_from_file(&params.file, params.allow_tests)?;
+    let symbols =
+        extract_all_symbols_from_file(&params.file, params.allow_tests, params.public_only)?;
 
     if symbols.is_empty() {
         if params.format == "json" {
-            println!("{{\"file\": \"{}\", \"symbols\": {{}}}}", params.file.display());
+            println!(
+                "{{\"file\": \"{}\", \"symbols\": {{}}}}",
+                params.file.display()
+            );
         } else {
             println!("{}", "No symbols found in file.".yellow());
         }
@@ -517,107 +1140,522 @@ fn handle_outline(params: OutlineParams) -> Result<()> {
     Ok(())
 }
 
+/// Resolve a `--package` name to the member's directory in the workspace around `start`.
+/// The directory is reported relative to the current directory when possible.
+fn resolve_package_dir(package: &str, start: &std::path::Path) -> Result<PathBuf> {
+    let dir = probe_code::path_resolver::resolve_workspace_package(start, package)
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| cwd.canonicalize().ok())
+        .and_then(|cwd| dir.strip_prefix(cwd).ok().map(|p| p.to_path_buf()));
+    Ok(match relative {
+        Some(p) if p.as_os_str().is_empty() => PathBuf::from("."),
+        Some(p) => p,
+        None => dir,
+    })
+}
+
+/// Scope search paths to a workspace package: the first path (or ".") locates the
+/// workspace, and the package directory replaces the paths
+fn scope_paths_to_package(paths: Vec<PathBuf>, package: Option<&str>) -> Result<Vec<PathBuf>> {
+    let Some(package) = package else {
+        return Ok(paths);
+    };
+    let start = paths.first().cloned().unwrap_or_else(|| PathBuf::from("."));
+    Ok(vec![resolve_package_dir(package, &start)?])
+}
+
+/// Prefix relative file arguments with a workspace package directory. Logical module
+/// paths (containing `::`) are left untouched.
+fn scope_files_to_package(files: Vec<String>, package: Option<&str>) -> Result<Vec<String>> {
+    let Some(package) = package else {
+        return Ok(files);
+    };
+    let dir = resolve_package_dir(package, std::path::Path::new("."))?;
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            if file.contains("::") || std::path::Path::new(&file).is_absolute() {
+                file
+            } else {
+                dir.join(&file).to_string_lossy().to_string()
+            }
+        })
+        .collect())
+}
+
+/// `PROBE_SMART_CASE=1` turns on --smart-case for search and grep
+fn smart_case_by_default() -> bool {
+    std::env::var("PROBE_SMART_CASE").unwrap_or_default() == "1"
+}
+
+/// Read a `--files-from` list from a file, or from stdin for `-`: one path per line,
+/// or NUL-separated as printed by `fd -0` and `git diff -z --name-only`. No path
+/// contains a NUL, so a list with one is NUL-separated.
+fn read_file_list(source: &str) -> Result<Vec<PathBuf>> {
+    let content = read_input(source, "file list")?;
+    if content.contains('\0') {
+        return Ok(content
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .collect());
+    }
+    Ok(lines(&content).into_iter().map(PathBuf::from).collect())
+}
+
+/// The non-empty lines of a file, or of stdin for `-`
+fn read_lines(source: &str, what: &str) -> Result<Vec<String>> {
+    Ok(lines(&read_input(source, what)?))
+}
+
+fn read_input(source: &str, what: &str) -> Result<String> {
+    if source == "-" {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .with_context(|| format!("Failed to read the {what} from stdin"))?;
+        Ok(content)
+    } else {
+        std::fs::read_to_string(source)
+            .with_context(|| format!("Failed to read the {what} {source}"))
+    }
+}
+
+fn lines(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The queries of a batch search: the `--query` flags, then the lines of
+/// `--queries-file`. With any, the positional PATTERN is the first path, as with `rg -e`;
+/// it replaces the default of searching the current directory unless `paths_given`.
+fn batch_queries(
+    mut queries: Vec<String>,
+    queries_file: Option<&str>,
+    pattern: &mut Option<String>,
+    paths: &mut Vec<PathBuf>,
+    paths_given: bool,
+) -> Result<Vec<String>> {
+    if let Some(source) = queries_file {
+        queries.extend(read_lines(source, "queries file")?);
+    }
+    if queries.is_empty() {
+        return Ok(queries);
+    }
+    if let Some(path) = pattern.take() {
+        if paths_given {
+            paths.insert(0, PathBuf::from(path));
+        } else {
+            *paths = vec![PathBuf::from(path)];
+        }
+    }
+    Ok(queries)
+}
+
+/// Apply the settings of the config files and `PROBE_*` variables to the options not
+/// given on the command line; returns the stemmer they select
+fn apply_project_config(params: &mut SearchParams) -> Result<Option<StemmerKind>> {
+    let config = project_config::load_resolved_config(params.paths.first().unwrap())?.config;
+    let from_config = |id: &str| !params.command_line_args.contains(id);
+    if let Some(reranker) = config.reranker.filter(|_| from_config("reranker")) {
+        params.reranker = reranker;
+    }
+    if let Some(format) = config.format.filter(|_| from_config("format")) {
+        params.format = format;
+    }
+    if let Some(max_tokens) = config.max_tokens.filter(|_| from_config("max_tokens")) {
+        params.max_tokens = Some(max_tokens);
+    }
+    if let Some(allow_tests) = config.allow_tests.filter(|_| from_config("allow_tests")) {
+        params.allow_tests = allow_tests;
+    }
+    params.ignore.extend(config.ignore);
+    params.type_add.splice(0..0, config.types);
+    if !config.languages.is_empty() {
+        set_extension_mappings(config.languages);
+    }
+    Ok(config.stemmer)
+}
+
+/// `type_add` after the file types defined in the config files that apply at `root`
+fn project_types(root: &Path, type_add: Vec<String>) -> Result<Vec<String>> {
+    let config = project_config::load_resolved_config(root)?.config;
+    Ok(config.types.into_iter().chain(type_add).collect())
+}
+
+/// Print the file types known to --type in the current project, for --type-list
+fn print_type_list(type_add: Vec<String>) -> Result<()> {
+    for definition in file_type_definitions(&project_types(Path::new("."), type_add)?)? {
+        println!("{}: {}", definition.name(), definition.globs().join(", "));
+    }
+    Ok(())
+}
+
+/// Ids of the arguments given on the command line, rather than by their defaults
+fn command_line_args(matches: &ArgMatches) -> HashSet<String> {
+    matches
+        .ids()
+        .filter(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+        .map(|id| id.to_string())
+        .collect()
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    logging::init(
+        args.log_level.as_deref(),
+        args.log_file.as_deref(),
+        &args.log_format,
+    )?;
+    if args.progress {
+        probe_code::progress::enable();
+    }
+    set_pool_config(PoolConfig {
+        max_parsers_per_language: args.parser_pool_size,
+        idle_timeout: args.parser_idle_timeout.map(Duration::from_secs),
+    });
+    if let Some(config) = &args.config {
+        if !config.is_file() {
+            anyhow::bail!("Config file {} not found", config.display());
+        }
+        set_config_file(config.clone());
+    }
+    if let Some(lang) = &args.lang {
+        messages::set_language(lang.parse().map_err(|e: String| anyhow::anyhow!(e))?);
+    }
+    if args.no_tips || std::env::var("PROBE_NO_TIPS").unwrap_or_default() == "1" {
+        messages::set_tips(false);
+    }
+    let parser_stats = args.parser_stats;
+    if args.token_cache {
+        search_tokens::enable_token_cache_file(search_tokens::default_token_cache_file());
+    }
+
+    if matches!(
+        args.command,
+        None | Some(Commands::Search { .. } | Commands::Extract { .. })
+    ) {
+        install_interrupt_handler();
+    }
 
     match args.command {
+        None if args.type_list => print_type_list(args.type_add)?,
         // When no subcommand provided and no pattern, show help
-        None if args.pattern.is_none() || args.pattern.as_ref().unwrap().is_empty() => {
+        None if args
+            .pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_empty())
+            && args.query.is_empty()
+            && args.queries_file.is_none() =>
+        {
             Args::command().print_help()?;
             return Ok(());
         }
         // When no subcommand but pattern is provided, fallback to search mode
         None => {
-            // Use provided pattern
-            let pattern = args.pattern.unwrap();
+            // Use provided pattern, or queries
+            let mut pattern = args.pattern;
+            let mut paths = args.paths;
+            let queries = batch_queries(
+                args.query,
+                args.queries_file.as_deref(),
+                &mut pattern,
+                &mut paths,
+                matches.value_source("paths") == Some(ValueSource::CommandLine),
+            )?;
+            let pattern = pattern.unwrap_or_default();
 
             // Use provided paths or default to current directory
-            let paths = if args.paths.is_empty() {
+            let paths = if paths.is_empty() {
                 vec![std::path::PathBuf::from(".")]
             } else {
-                args.paths
+                paths
             };
+            let paths = remote::repo_paths(paths, args.repo.as_deref())?;
+            let paths = scope_paths_to_package(paths, args.package.as_deref())?;
 
             handle_search(SearchParams {
                 pattern,
+                queries,
                 paths,
                 files_only: args.files_only,
                 ignore: args.ignore,
+                include: args.include,
+                file_type: args.file_type,
+                type_add: args.type_add,
+                max_depth: args.max_depth,
+                hidden: args.hidden,
+                max_filesize: args.max_filesize,
+                binary: args.binary,
+                search_archives: args.search_archives,
                 exclude_filenames: args.exclude_filenames,
+                symbols_only: args.symbols_only,
+                no_comments: args.no_comments,
+                comments_only: args.comments_only,
+                strings_only: args.strings_only,
                 reranker: args.reranker,
+                vector_weight: args.vector_weight,
+                boost_recent: args.boost_recent,
+                recency_weight: args.recency_weight,
                 frequency_search: args.frequency_search,
                 exact: args.exact,
+                regex: args.regex,
+                regex_escape: args.regex_escape == "on",
+                smart_case: args.smart_case || smart_case_by_default(),
+                fuzzy: args.fuzzy,
+                case_sensitive: args.case_sensitive,
+                count_only: args.count_only,
+                compare_to: args.compare_to,
+                save_results: args.save_results,
+                then_extract: args.then_extract,
+                extract_context: args.extract_context,
+                extract_doc_comments: args.extract_doc_comments,
                 strict_elastic_syntax: false, // Default to false for the no-subcommand case
                 language: None,               // Default to None for the no-subcommand case
                 max_results: args.max_results,
                 max_bytes: args.max_bytes,
                 max_tokens: args.max_tokens,
+                max_files: args.max_files,
+                max_block_lines: args.max_block_lines,
                 allow_tests: args.allow_tests,
                 no_merge: args.no_merge,
+                no_attributes: args.no_attributes,
                 merge_threshold: args.merge_threshold,
                 dry_run: args.dry_run,
+                sort: args.sort,
+                reverse: args.reverse,
+                group_by: args.group_by,
                 format: args.format,
                 session: args.session,
+                session_across_queries: args.session_scope == "session",
+                session_ttl: args
+                    .session_ttl
+                    .as_deref()
+                    .map(cache::parse_duration)
+                    .transpose()?,
+                session_max_entries: args.session_max_entries,
+                within_session: args.within_session,
                 timeout: args.timeout,
                 question: args.question,
                 no_gitignore: args.no_gitignore
                     || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+                files_from: args.files_from.as_deref().map(read_file_list).transpose()?,
+                changed: args.changed,
+                since: args.since,
+                blame: args.blame,
+                owners: args.owners,
+                include_deps: args.include_deps,
+                include_generated: args.include_generated,
+                filename_weight: args.filename_weight,
+                dedupe_cross_file: args.dedupe_cross_file,
+                diversify: args.diversify,
+                offset: args.offset,
+                stable_order: args.stable_order,
+                redact_secrets: args.redact_secrets,
+                no_whole_files: args.no_whole_files,
+                stemmer: args.stemmer,
                 verbose: args.verbose,
+                command_line_args: command_line_args(&matches),
             })?
         }
         Some(Commands::Search {
-            pattern,
-            paths,
+            type_list: true,
+            type_add,
+            ..
+        }) => print_type_list(type_add)?,
+        Some(Commands::Search {
+            mut pattern,
+            mut paths,
+            query,
+            queries_file,
+            package,
+            repo,
             files_only,
             ignore,
+            include,
+            file_type,
+            type_add,
+            type_list: _,
+            max_depth,
+            hidden,
+            max_filesize,
+            binary,
+            search_archives,
             exclude_filenames,
+            symbols_only,
+            no_comments,
+            comments_only,
+            strings_only,
             reranker,
+            vector_weight,
+            boost_recent,
+            recency_weight,
             frequency_search,
             exact,
+            regex,
+            regex_escape,
+            smart_case,
+            fuzzy,
+            case_sensitive,
+            count_only,
+            compare_to,
+            save_results,
+            then_extract,
+            extract_context,
+            extract_doc_comments,
             strict_elastic_syntax,
             language,
             max_results,
             max_bytes,
             max_tokens,
+            max_files,
+            max_block_lines,
             allow_tests,
             no_merge,
+            no_attributes,
             merge_threshold,
             dry_run,
+            sort,
+            reverse,
+            group_by,
             format,
             session,
+            session_scope,
+            session_ttl,
+            session_max_entries,
+            within_session,
             timeout,
             question,
             no_gitignore,
+            files_from,
+            changed,
+            since,
+            blame,
+            owners,
+            include_deps,
+            include_generated,
+            filename_weight,
+            dedupe_cross_file,
+            diversify,
+            offset,
+            stable_order,
+            redact_secrets,
+            no_whole_files,
+            stemmer,
             verbose,
-        }) => handle_search(SearchParams {
-            pattern,
-            paths,
-            files_only,
-            ignore,
-            exclude_filenames,
-            reranker,
-            frequency_search,
-            exact,
-            strict_elastic_syntax,
-            language,
-            max_results,
-            max_bytes,
-            max_tokens,
-            allow_tests,
-            no_merge,
-            merge_threshold,
-            dry_run,
-            format,
-            session,
-            timeout,
-            question,
-            no_gitignore: no_gitignore
-                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
-            verbose,
-        })?,
+        }) => {
+            let paths_given = matches
+                .subcommand_matches("search")
+                .and_then(|search| search.value_source("paths"))
+                == Some(ValueSource::CommandLine);
+            let queries = batch_queries(
+                query,
+                queries_file.as_deref(),
+                &mut pattern,
+                &mut paths,
+                paths_given,
+            )?;
+            handle_search(SearchParams {
+                pattern: pattern.unwrap_or_default(),
+                queries,
+                paths: scope_paths_to_package(
+                    remote::repo_paths(paths, repo.as_deref())?,
+                    package.as_deref(),
+                )?,
+                files_only,
+                ignore,
+                include,
+                file_type,
+                type_add,
+                max_depth,
+                hidden,
+                max_filesize,
+                binary,
+                search_archives,
+                exclude_filenames,
+                symbols_only,
+                no_comments,
+                comments_only,
+                strings_only,
+                reranker,
+                vector_weight,
+                boost_recent,
+                recency_weight,
+                frequency_search,
+                exact,
+                regex,
+                regex_escape: regex_escape == "on",
+                smart_case: smart_case || smart_case_by_default(),
+                fuzzy,
+                case_sensitive,
+                count_only,
+                compare_to,
+                save_results,
+                then_extract,
+                extract_context,
+                extract_doc_comments,
+                strict_elastic_syntax,
+                language,
+                max_results,
+                max_bytes,
+                max_tokens,
+                max_files,
+                max_block_lines,
+                allow_tests,
+                no_merge,
+                no_attributes,
+                merge_threshold,
+                dry_run,
+                sort,
+                reverse,
+                group_by,
+                format,
+                session,
+                session_across_queries: session_scope == "session",
+                session_ttl: session_ttl
+                    .as_deref()
+                    .map(cache::parse_duration)
+                    .transpose()?,
+                session_max_entries,
+                within_session,
+                timeout,
+                question,
+                no_gitignore: no_gitignore
+                    || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+                files_from: files_from.as_deref().map(read_file_list).transpose()?,
+                changed,
+                since,
+                blame,
+                owners,
+                include_deps,
+                include_generated,
+                filename_weight,
+                dedupe_cross_file,
+                diversify,
+                offset,
+                stable_order,
+                redact_secrets,
+                no_whole_files,
+                stemmer,
+                verbose,
+                command_line_args: matches
+                    .subcommand_matches("search")
+                    .map(command_line_args)
+                    .unwrap_or_default(),
+            })?
+        }
         Some(Commands::Extract {
             files,
+            package,
             ignore,
             context_lines,
             format,
@@ -631,28 +1669,51 @@ async fn main() -> Result<()> {
             prompt,
             instructions,
             no_gitignore,
-        }) => handle_extract(ExtractOptions {
-            files,
-            custom_ignores: ignore,
-            context_lines,
-            format,
-            from_clipboard,
-            input_file,
-            to_clipboard,
-            dry_run,
-            diff,
-            allow_tests,
-            keep_input,
-            prompt: prompt.map(|p| {
-                probe_code::extract::PromptTemplate::from_str(&p).unwrap_or_else(|e| {
-                    eprintln!("Warning: {e}");
-                    probe_code::extract::PromptTemplate::Engineer
-                })
-            }),
-            instructions,
-            no_gitignore: no_gitignore
-                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
-        })?,
+            with_doc_comments,
+            no_attributes,
+            deps,
+            redact_secrets,
+            no_whole_files,
+            save_results,
+            blame,
+            owners,
+            public_only,
+            include,
+        }) => {
+            interrupt::reset();
+            handle_extract(ExtractOptions {
+                files: scope_files_to_package(files, package.as_deref())?,
+                custom_ignores: ignore,
+                context_lines,
+                format,
+                from_clipboard,
+                input_file,
+                to_clipboard,
+                dry_run,
+                diff,
+                allow_tests,
+                keep_input,
+                prompt: prompt.map(|p| {
+                    probe_code::extract::PromptTemplate::from_str(&p).unwrap_or_else(|e| {
+                        eprintln!("Warning: {e}");
+                        probe_code::extract::PromptTemplate::Engineer
+                    })
+                }),
+                instructions,
+                no_gitignore: no_gitignore
+                    || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+                with_doc_comments,
+                no_attributes,
+                deps,
+                redact_secrets,
+                no_whole_files,
+                save_results,
+                include,
+                blame,
+                owners,
+                public_only,
+            })?
+        }
         Some(Commands::Query {
             pattern,
             path,
@@ -660,31 +1721,44 @@ async fn main() -> Result<()> {
             ignore,
             allow_tests,
             max_results,
+            full_block,
             format,
             no_gitignore,
-        }) => probe_code::query::handle_query(
-            &pattern,
-            &path,
-            language.as_deref().map(|lang| {
-                // Normalize language aliases
-                match lang.to_lowercase().as_str() {
-                    "rs" => "rust",
-                    "js" | "jsx" => "javascript",
-                    "ts" | "tsx" => "typescript",
-                    "py" => "python",
-                    "h" => "c",
-                    "cc" | "cxx" | "hpp" | "hxx" => "cpp",
-                    "rb" => "ruby",
-                    "cs" => "csharp",
-                    _ => lang, // Return the original language if no alias is found
-                }
-            }),
-            &ignore,
-            allow_tests,
-            max_results,
-            &format,
-            no_gitignore || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
-        )?,
+            max_depth,
+            hidden,
+            max_filesize,
+        }) => {
+            set_walk_options(WalkOptions {
+                max_depth,
+                hidden,
+                max_filesize,
+                ..Default::default()
+            });
+            probe_code::query::handle_query(
+                &pattern,
+                &path,
+                language.as_deref().map(|lang| {
+                    // Normalize language aliases
+                    match lang.to_lowercase().as_str() {
+                        "rs" => "rust",
+                        "js" | "jsx" => "javascript",
+                        "ts" | "tsx" => "typescript",
+                        "py" => "python",
+                        "h" => "c",
+                        "cc" | "cxx" | "hpp" | "hxx" => "cpp",
+                        "rb" => "ruby",
+                        "cs" => "csharp",
+                        _ => lang, // Return the original language if no alias is found
+                    }
+                }),
+                &ignore,
+                allow_tests,
+                max_results,
+                &format,
+                no_gitignore || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+                full_block,
+            )?
+        }
         Some(Commands::Benchmark {
             bench,
             sample_size,
@@ -702,26 +1776,45 @@ async fn main() -> Result<()> {
             baseline,
             fast,
         })?,
+        Some(Commands::Grep {
+            type_list: true,
+            type_add,
+            ..
+        }) => print_type_list(type_add)?,
         Some(Commands::Grep {
             pattern,
             paths,
             ignore_case,
+            smart_case,
             line_number,
             count,
             files_with_matches,
             files_without_match,
             invert_match,
+            binary,
             before_context,
             after_context,
             context,
             ignore,
+            file_type,
+            type_add,
+            type_list: _,
+            max_depth,
             no_gitignore,
+            files_from,
             color,
             max_count,
+            max_files,
+            heading,
+            no_heading,
+            group_separator,
+            no_group_separator,
         }) => grep::handle_grep(grep::GrepParams {
-            pattern,
+            pattern: pattern.unwrap_or_default(),
+            type_definitions: project_types(&paths[0], type_add)?,
             paths,
             ignore_case,
+            smart_case: smart_case || smart_case_by_default(),
             line_number,
             count,
             files_with_matches,
@@ -731,22 +1824,283 @@ async fn main() -> Result<()> {
             after_context,
             context,
             ignore,
+            file_types: file_type,
             no_gitignore: no_gitignore
                 || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
             color,
             max_count,
+            max_depth,
+            files_from: files_from.as_deref().map(read_file_list).transpose()?,
+            binary,
+            heading: heading && !no_heading,
+            group_separator: (!no_group_separator).then_some(group_separator),
+            max_files,
+        })?,
+        Some(Commands::Tokens {
+            paths,
+            tokenizer,
+            format,
+            no_gitignore,
+        }) => tokens::handle_tokens(tokens::TokensParams {
+            paths,
+            tokenizer,
+            format,
+            no_gitignore: no_gitignore
+                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+        })?,
+        Some(Commands::Index {
+            path,
+            watch,
+            allow_tests,
+            ignore,
+            no_gitignore,
+        }) => index::handle_index(index::IndexParams {
+            path,
+            watch,
+            allow_tests,
+            ignore,
+            no_gitignore: no_gitignore
+                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+        })?,
+        Some(Commands::Show { file, format }) => show::handle_show(&file, format.as_deref())?,
+        Some(Commands::Doctor { path, format }) => {
+            doctor::handle_doctor(doctor::DoctorParams { path, format })?
+        }
+        Some(Commands::Config {
+            action:
+                ConfigAction::Show {
+                    path,
+                    resolved,
+                    format,
+                },
+        }) => config::handle_config_show(&path, resolved, &format)?,
+        Some(Commands::History {
+            pattern,
+            path,
+            regex,
+            ignore_case,
+            max_commits,
+            allow_tests,
+            format,
+        }) => history::handle_history(history::HistoryParams {
+            pattern: &pattern,
+            path: &path,
+            regex,
+            ignore_case,
+            max_commits,
+            allow_tests,
+            format: &format,
+        })?,
+        Some(Commands::Todos {
+            path,
+            tags,
+            group_by,
+            blame,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            format,
+        }) => todos::handle_todos(todos::TodosParams {
+            path: &path,
+            tags: &tags,
+            group_by: &group_by,
+            blame,
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore: no_gitignore
+                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+            format: &format,
+        })?,
+        Some(Commands::Strings {
+            path,
+            min_length,
+            no_logs,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            format,
+        }) => strings::handle_strings(strings::StringsParams {
+            path: &path,
+            min_length,
+            no_logs,
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore: no_gitignore
+                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
+            format: &format,
+        })?,
+        Some(Commands::Semver {
+            range,
+            path,
+            allow_tests,
+            format,
+        }) => semver::handle_semver(semver::SemverParams {
+            range: &range,
+            path: &path,
+            allow_tests,
+            format: &format,
+        })?,
+        Some(Commands::Export {
+            path,
+            language,
+            min_tokens,
+            max_tokens,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            format,
+        }) => export::handle_export(export::ExportParams {
+            path: &path,
+            language: language.as_deref(),
+            min_tokens,
+            max_tokens,
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore,
+            format: &format,
+        })?,
+        Some(Commands::Similar {
+            action:
+                SimilarAction::Index {
+                    path,
+                    allow_tests,
+                    ignore,
+                    no_gitignore,
+                },
+        }) => similar::handle_similar_index(similar::SimilarIndexParams {
+            path,
+            allow_tests,
+            ignore,
+            no_gitignore,
+        })?,
+        Some(Commands::Similar {
+            action:
+                SimilarAction::Search {
+                    question,
+                    path,
+                    max_results,
+                    allow_tests,
+                    ignore,
+                    no_gitignore,
+                    format,
+                },
+        }) => similar::handle_similar_search(similar::SimilarSearchParams {
+            question: &question,
+            path: &path,
+            max_results,
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore,
+            format: &format,
+        })?,
+        Some(Commands::Metrics {
+            path,
+            language,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            top,
+            format,
+        }) => metrics::handle_metrics(metrics::MetricsParams {
+            path: &path,
+            language: language.as_deref(),
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore,
+            format: &format,
+            top,
+        })?,
+        Some(Commands::Context {
+            task,
+            path,
+            max_tokens,
+            no_similar,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            format,
+        }) => context::handle_context(context::ContextParams {
+            task: &task,
+            path: &path,
+            max_tokens,
+            no_similar,
+            allow_tests,
+            ignore: &ignore,
+            no_gitignore,
+            format: &format,
+        })?,
+        Some(Commands::Arch {
+            path,
+            rules,
+            allow_tests,
+            ignore,
+            no_gitignore,
+            format,
+        }) => {
+            let violated = arch::handle_arch(arch::ArchParams {
+                path: &path,
+                rules: &rules,
+                allow_tests,
+                ignore: &ignore,
+                no_gitignore,
+                format: &format,
+            })?;
+            if violated {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Mcp) => mcp::handle_mcp()?,
+        Some(Commands::Serve {
+            port,
+            host,
+            allow_tests,
+            ignore,
+            no_gitignore,
+        }) => serve::handle_serve(serve::ServeParams {
+            host,
+            port,
+            allow_tests,
+            ignore,
+            no_gitignore: no_gitignore
+                || std::env::var("PROBE_NO_GITIGNORE").unwrap_or_default() == "1",
         })?,
         Some(Commands::Outline {
             file,
+            package,
             format,
             allow_tests,
+            public_only,
             ..
         }) => handle_outline(OutlineParams {
-            file,
+            file: match package {
+                Some(package) if file.is_relative() => {
+                    resolve_package_dir(&package, std::path::Path::new("."))?.join(file)
+                }
+                _ => file,
+            },
             format,
             allow_tests,
+            public_only,
         })?,
     }
 
+    if let Err(e) = search_tokens::save_token_cache() {
+        eprintln!("Failed to save token counts: {e}");
+    }
+
+    if parser_stats {
+        eprint!(
+            "{}",
+            format_parser_stats(&probe_code::language::parser_stats())
+        );
+    }
+
+    if interrupt::is_interrupted() {
+        eprintln!(
+            "{}",
+            "Interrupted: the output above only covers the files processed before Ctrl-C".yellow()
+        );
+        std::process::exit(130);
+    }
     Ok(())
 }
@@ -0,0 +1,333 @@
+//! `probe mcp`: a Model Context Protocol server over stdio.
+//!
+//! Exposes search, extract, query and outline as MCP tools so assistants can call probe
+//! directly. Messages are newline-delimited JSON-RPC 2.0. Each tool call runs the matching
+//! probe subcommand with `--format json` in a child process: the subcommands print their
+//! results (and progress notes) to stdout, which here must carry nothing but protocol
+//! messages, and a child also keeps one failing call from taking the server down.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::process::Command;
+
+/// Protocol revision answered when the client does not name one
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub fn handle_mcp() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line.context("Failed to read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(&line) {
+            writeln!(stdout, "{response}")?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// Answer one JSON-RPC message; notifications get no response
+fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let id = message.get("id").cloned()?;
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(json!({
+            "protocolVersion": params
+                .get("protocolVersion")
+                .and_then(Value::as_str)
+                .unwrap_or(PROTOCOL_VERSION),
+            "capabilities": { "tools": {} },
+            "serverInfo": { "name": "probe", "version": env!("CARGO_PKG_VERSION") },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+        "tools/call" => call_tool(&params),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {method}"))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search",
+            "description": "Search code with an Elasticsearch-style query (AND, OR, -term, \"phrase\") and return ranked, complete code blocks.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Search query" },
+                    "path": { "type": "string", "description": "Directory to search (default: current directory)" },
+                    "max_results": { "type": "integer", "description": "Maximum number of results" },
+                    "max_tokens": { "type": "integer", "description": "Maximum total tokens in returned code" },
+                    "max_files": { "type": "integer", "description": "Maximum number of distinct files in the results" },
+                    "exact": { "type": "boolean", "description": "Match terms exactly, without stemming or splitting" },
+                    "sort": { "type": "string", "enum": ["score", "path", "lines", "mtime"], "description": "Order of the results: relevance (default), file and line, shortest blocks first, or recently modified files first" },
+                    "reverse": { "type": "boolean", "description": "Reverse the sort order" },
+                    "allow_tests": { "type": "boolean", "description": "Include test files" }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "extract",
+            "description": "Extract code blocks from files by path, path:line, path:start-end or path#symbol.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "targets": { "type": "array", "items": { "type": "string" }, "description": "Files to extract from, e.g. src/main.rs:42 or src/lib.rs#parse" },
+                    "context_lines": { "type": "integer", "description": "Lines of context around each block" },
+                    "allow_tests": { "type": "boolean", "description": "Include test files" }
+                },
+                "required": ["targets"]
+            }
+        },
+        {
+            "name": "query",
+            "description": "Search code structurally with an ast-grep pattern such as 'fn $NAME($$$PARAMS)'.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "ast-grep pattern" },
+                    "path": { "type": "string", "description": "Directory to search (default: current directory)" },
+                    "language": { "type": "string", "description": "Language of the pattern, e.g. rust or python" },
+                    "max_results": { "type": "integer", "description": "Maximum number of results" },
+                    "allow_tests": { "type": "boolean", "description": "Include test files" }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "outline",
+            "description": "List the symbols (functions, types, classes, ...) defined in a file with their signatures and line numbers.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "file": { "type": "string", "description": "File to outline" },
+                    "allow_tests": { "type": "boolean", "description": "Include symbols from test files" },
+                    "public_only": { "type": "boolean", "description": "List only public symbols, the file's API surface" }
+                },
+                "required": ["file"]
+            }
+        }
+    ])
+}
+
+/// Build the probe command line for a tool call
+fn tool_args(name: &str, arguments: &Value) -> Result<Vec<String>, String> {
+    let string = |key: &str| arguments.get(key).and_then(Value::as_str);
+    let required = |key: &str| string(key).ok_or_else(|| format!("Missing argument: {key}"));
+    let number = |key: &str| arguments.get(key).and_then(Value::as_u64);
+    let flag = |key: &str| arguments.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    // Positionals follow a `--` so a query or path starting with `-` is not read as a flag
+    let mut args: Vec<String> = Vec::new();
+    let mut positionals: Vec<String> = Vec::new();
+    match name {
+        "search" => {
+            args.push("search".to_string());
+            positionals.extend([
+                required("query")?.to_string(),
+                string("path").unwrap_or(".").to_string(),
+            ]);
+            if let Some(max_results) = number("max_results") {
+                args.extend(["--max-results".to_string(), max_results.to_string()]);
+            }
+            if let Some(max_tokens) = number("max_tokens") {
+                args.extend(["--max-tokens".to_string(), max_tokens.to_string()]);
+            }
+            if let Some(max_files) = number("max_files") {
+                args.extend(["--max-files".to_string(), max_files.to_string()]);
+            }
+            if flag("exact") {
+                args.push("--exact".to_string());
+            }
+            if let Some(sort) = string("sort") {
+                args.extend(["--sort".to_string(), sort.to_string()]);
+            }
+            if flag("reverse") {
+                args.push("--reverse".to_string());
+            }
+        }
+        "extract" => {
+            let targets: Vec<String> = arguments
+                .get("targets")
+                .and_then(Value::as_array)
+                .map(|targets| {
+                    targets
+                        .iter()
+                        .filter_map(|t| t.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default();
+            if targets.is_empty() {
+                return Err("Missing argument: targets".to_string());
+            }
+            args.push("extract".to_string());
+            positionals.extend(targets);
+            if let Some(context_lines) = number("context_lines") {
+                args.extend(["--context".to_string(), context_lines.to_string()]);
+            }
+        }
+        "query" => {
+            args.push("query".to_string());
+            positionals.extend([
+                required("pattern")?.to_string(),
+                string("path").unwrap_or(".").to_string(),
+            ]);
+            if let Some(language) = string("language") {
+                args.extend(["--language".to_string(), language.to_string()]);
+            }
+            if let Some(max_results) = number("max_results") {
+                args.extend(["--max-results".to_string(), max_results.to_string()]);
+            }
+        }
+        "outline" => {
+            args.push("outline".to_string());
+            positionals.push(required("file")?.to_string());
+            if flag("public_only") {
+                args.push("--public-only".to_string());
+            }
+        }
+        _ => return Err(format!("Unknown tool: {name}")),
+    }
+    if flag("allow_tests") {
+        args.push("--allow-tests".to_string());
+    }
+    args.extend(["--format".to_string(), "json".to_string()]);
+    args.push("--".to_string());
+    args.extend(positionals);
+    Ok(args)
+}
+
+fn call_tool(params: &Value) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+    let args = tool_args(name, &arguments).map_err(|e| (INVALID_PARAMS, e))?;
+
+    // Tool failures are reported in the result so the assistant can see and react to them
+    let (text, is_error) = match run_probe(&args) {
+        Ok(output) if output.status.success() => {
+            (String::from_utf8_lossy(&output.stdout).into_owned(), false)
+        }
+        Ok(output) => (String::from_utf8_lossy(&output.stderr).into_owned(), true),
+        Err(e) => (format!("{e:#}"), true),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+fn run_probe(args: &[String]) -> Result<std::process::Output> {
+    let exe = std::env::current_exe().context("Failed to locate the probe executable")?;
+    Command::new(exe)
+        .args(args)
+        // Keep debug logging out of the captured output
+        .env_remove("DEBUG")
+        .output()
+        .context("Failed to run probe")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        assert!(
+            handle_message(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#).is_none()
+        );
+    }
+
+    #[test]
+    fn test_initialize_and_errors() {
+        let response = handle_message(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#,
+        )
+        .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(response["result"]["serverInfo"]["name"], "probe");
+
+        let response = handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = handle_message("not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn test_tool_args() {
+        let args = tool_args(
+            "search",
+            &json!({ "query": "auth token", "path": "src", "max_results": 5, "exact": true }),
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            [
+                "search",
+                "--max-results",
+                "5",
+                "--exact",
+                "--format",
+                "json",
+                "--",
+                "auth token",
+                "src"
+            ]
+        );
+        let args = tool_args(
+            "search",
+            &json!({ "query": "auth", "sort": "mtime", "reverse": true }),
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            [
+                "search",
+                "--sort",
+                "mtime",
+                "--reverse",
+                "--format",
+                "json",
+                "--",
+                "auth",
+                "."
+            ]
+        );
+        let args = tool_args("search", &json!({ "query": "-test parser" })).unwrap();
+        assert_eq!(
+            args,
+            ["search", "--format", "json", "--", "-test parser", "."]
+        );
+        assert!(tool_args("search", &json!({})).is_err());
+        assert!(tool_args("extract", &json!({ "targets": [] })).is_err());
+        assert!(tool_args("delete", &json!({})).is_err());
+    }
+}
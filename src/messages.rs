@@ -0,0 +1,302 @@
+//! User-facing text of the command-line output: summary labels, hints and tips.
+//!
+//! Text is looked up by [`Msg`] in the catalog of the language chosen with `--lang`,
+//! else `PROBE_LANG`, else the locale (`LC_ALL`, `LC_MESSAGES` or `LANG`). Messages
+//! a language has no translation for, and languages without a catalog, fall back to
+//! English. Tips and hints are shown unless turned off with [`set_tips`]
+//! (`--no-tips` or `PROBE_NO_TIPS=1`).
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// Languages with a message catalog
+pub const LANGUAGES: &[&str] = &["en", "de", "es"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    De,
+    Es,
+}
+
+impl std::str::FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "en" => Ok(Lang::En),
+            "de" => Ok(Lang::De),
+            "es" => Ok(Lang::Es),
+            _ => Err(format!(
+                "unknown language '{s}', expected one of: {}",
+                LANGUAGES.join(", ")
+            )),
+        }
+    }
+}
+
+impl Lang {
+    /// The language of a locale such as `de_DE.UTF-8`; `None` for `C`, `POSIX` and
+    /// languages without a catalog
+    pub fn from_locale(locale: &str) -> Option<Lang> {
+        locale.split(['_', '-', '.', '@']).next()?.parse().ok()
+    }
+}
+
+/// A message of the catalog. `{name}` placeholders in the text are filled in by
+/// [`text_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    /// `{count}`
+    FoundSearchResults,
+    /// `{count}`, header of the color format
+    FoundResults,
+    /// `{group}`, `{count}`, section header of `--group-by`
+    ResultGroup,
+    /// `{query}`, section header of a batch search (`--query`)
+    BatchQuery,
+    /// `{count}`, `{time}`
+    FoundMatches,
+    /// `{bytes}`
+    TotalBytes,
+    /// `{tokens}`
+    TotalTokens,
+    /// `{time}`
+    SearchCompleted,
+    NoResults,
+    LimitsApplied,
+    MaxResults,
+    MaxBytes,
+    MaxTokens,
+    MaxFiles,
+    SkippedByLimits,
+    RemainingFiles,
+    LargeFilesSkipped,
+    SessionTokens,
+    SessionFiltered,
+    AllRetrieved,
+    TipsHeader,
+    TipSynonyms,
+    TipBroaderTerms,
+    TipSpelling,
+    TipFilters,
+    TipExact,
+    Hint,
+    HintMatchCounts,
+    /// `{session}`
+    TipMoreResultsInSession,
+    TipMoreResultsShownSession,
+    TipMoreResultsUseSession,
+    TipExtract,
+}
+
+/// Tips printed under "No results found."
+pub const SEARCH_TIPS: &[Msg] = &[
+    Msg::TipSynonyms,
+    Msg::TipBroaderTerms,
+    Msg::TipSpelling,
+    Msg::TipFilters,
+    Msg::TipExact,
+];
+
+static LANGUAGE: OnceLock<Lang> = OnceLock::new();
+static TIPS: AtomicBool = AtomicBool::new(true);
+
+/// Select the language of the output. Returns `false` if text was already looked up.
+pub fn set_language(lang: Lang) -> bool {
+    LANGUAGE.set(lang).is_ok()
+}
+
+/// The language in effect: the one set with [`set_language`], else `PROBE_LANG`, else
+/// the locale, else English
+pub fn language() -> Lang {
+    *LANGUAGE.get_or_init(|| {
+        if let Some(lang) = std::env::var("PROBE_LANG")
+            .ok()
+            .and_then(|value| value.parse().ok())
+        {
+            return lang;
+        }
+        // The first locale variable that is set decides, as for gettext
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or(Lang::En)
+    })
+}
+
+/// Show or hide tips and hints
+pub fn set_tips(enabled: bool) {
+    TIPS.store(enabled, Ordering::Relaxed);
+}
+
+pub fn tips_enabled() -> bool {
+    TIPS.load(Ordering::Relaxed)
+}
+
+/// The text of a message in the selected language
+pub fn text(msg: Msg) -> &'static str {
+    lookup(language(), msg)
+}
+
+/// The text of a message with its `{name}` placeholders filled in
+pub fn text_with(msg: Msg, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(text(msg).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+fn lookup(lang: Lang, msg: Msg) -> &'static str {
+    let translated = match lang {
+        Lang::En => None,
+        Lang::De => german(msg),
+        Lang::Es => spanish(msg),
+    };
+    translated.unwrap_or_else(|| english(msg))
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::FoundSearchResults => "Found {count} search results",
+        Msg::FoundResults => "Found {count} results",
+        Msg::ResultGroup => "{group} ({count} results)",
+        Msg::BatchQuery => "Query: {query}",
+        Msg::FoundMatches => "Found {count} matches in {time}",
+        Msg::TotalBytes => "Total bytes returned: {bytes}",
+        Msg::TotalTokens => "Total tokens returned: {tokens}",
+        Msg::SearchCompleted => "Search completed in {time}",
+        Msg::NoResults => "No results found.",
+        Msg::LimitsApplied => "Limits applied:",
+        Msg::MaxResults => "Max results:",
+        Msg::MaxBytes => "Max bytes:",
+        Msg::MaxTokens => "Max tokens:",
+        Msg::MaxFiles => "Max files:",
+        Msg::SkippedByLimits => "Skipped files due to limits:",
+        Msg::RemainingFiles => "Remaining files not shown:",
+        Msg::LargeFilesSkipped => "Skipped files larger than --max-filesize:",
+        Msg::SessionTokens => "Tokens returned in this session so far:",
+        Msg::SessionFiltered => "Filtered already-seen blocks (session deduplication):",
+        Msg::AllRetrieved => {
+            "✓ All results retrieved for this query. No need to search again with this session."
+        }
+        Msg::TipsHeader => "Tips to improve your search:",
+        Msg::TipSynonyms => "Try synonyms or related terms (e.g., \"fetch\" instead of \"get\")",
+        Msg::TipBroaderTerms => "Use broader terms without AND operators",
+        Msg::TipSpelling => "Check spelling of function/class names",
+        Msg::TipFilters => "Remove file type filters to search all files",
+        Msg::TipExact => {
+            "Use exact:false (default) for stemming, or exact:true for precise symbol lookup"
+        }
+        Msg::Hint => "Hint:",
+        Msg::HintMatchCounts => "<uniq> = unique search terms matched, <all> = total match blocks",
+        Msg::TipMoreResultsInSession => "More results may be available. Re-run with session: \"{session}\" and nextPage: true. Stop when you see \"All results retrieved\".",
+        Msg::TipMoreResultsShownSession => "More results may be available. Re-run with the session ID shown above and nextPage: true. Stop when you see \"All results retrieved\".",
+        Msg::TipMoreResultsUseSession => "More results may be available. Use --session with the session ID above and nextPage: true. Stop when you see \"All results retrieved\".",
+        Msg::TipExtract => "Tip: Use `probe extract <file>:<line>` to see full function/class context for any result above",
+    }
+}
+
+fn german(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::FoundSearchResults => "{count} Suchergebnisse gefunden",
+        Msg::FoundResults => "{count} Ergebnisse gefunden",
+        Msg::ResultGroup => "{group} ({count} Ergebnisse)",
+        Msg::BatchQuery => "Anfrage: {query}",
+        Msg::FoundMatches => "{count} Treffer gefunden in {time}",
+        Msg::TotalBytes => "Zurückgegebene Bytes insgesamt: {bytes}",
+        Msg::TotalTokens => "Zurückgegebene Tokens insgesamt: {tokens}",
+        Msg::SearchCompleted => "Suche abgeschlossen in {time}",
+        Msg::NoResults => "Keine Ergebnisse gefunden.",
+        Msg::LimitsApplied => "Angewendete Limits:",
+        Msg::MaxResults => "Max. Ergebnisse:",
+        Msg::MaxBytes => "Max. Bytes:",
+        Msg::MaxTokens => "Max. Tokens:",
+        Msg::MaxFiles => "Max. Dateien:",
+        Msg::SkippedByLimits => "Wegen der Limits übersprungene Dateien:",
+        Msg::RemainingFiles => "Nicht angezeigte Dateien:",
+        Msg::LargeFilesSkipped => "Übersprungene Dateien größer als --max-filesize:",
+        Msg::SessionTokens => "Bisher in dieser Sitzung zurückgegebene Tokens:",
+        Msg::SessionFiltered => "Bereits gesehene Blöcke ausgeblendet (Sitzungs-Deduplizierung):",
+        Msg::AllRetrieved => "✓ Alle Ergebnisse dieser Abfrage abgerufen. Eine weitere Suche mit dieser Sitzung ist nicht nötig.",
+        Msg::TipsHeader => "Tipps für eine bessere Suche:",
+        Msg::TipSynonyms => {
+            "Synonyme oder verwandte Begriffe probieren (z. B. \"fetch\" statt \"get\")"
+        }
+        Msg::TipBroaderTerms => "Allgemeinere Begriffe ohne AND-Operatoren verwenden",
+        Msg::TipSpelling => "Schreibweise von Funktions-/Klassennamen prüfen",
+        Msg::TipFilters => "Dateityp-Filter entfernen, um alle Dateien zu durchsuchen",
+        Msg::TipExact => "exact:false (Standard) für Stemming oder exact:true für die genaue Suche nach Symbolen verwenden",
+        Msg::Hint => "Hinweis:",
+        Msg::HintMatchCounts => "<uniq> = gefundene verschiedene Suchbegriffe, <all> = Trefferblöcke insgesamt",
+        Msg::TipMoreResultsInSession => "Möglicherweise gibt es weitere Ergebnisse. Erneut mit session: \"{session}\" und nextPage: true ausführen. Aufhören, sobald \"All results retrieved\" erscheint.",
+        Msg::TipMoreResultsShownSession => "Möglicherweise gibt es weitere Ergebnisse. Erneut mit der oben angezeigten Sitzungs-ID und nextPage: true ausführen. Aufhören, sobald \"All results retrieved\" erscheint.",
+        Msg::TipMoreResultsUseSession => "Möglicherweise gibt es weitere Ergebnisse. --session mit der obigen Sitzungs-ID und nextPage: true verwenden. Aufhören, sobald \"All results retrieved\" erscheint.",
+        Msg::TipExtract => "Tipp: Mit `probe extract <datei>:<zeile>` den vollständigen Funktions-/Klassenkontext eines der obigen Ergebnisse anzeigen",
+    })
+}
+
+fn spanish(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::FoundSearchResults => "Se encontraron {count} resultados de búsqueda",
+        Msg::FoundResults => "Se encontraron {count} resultados",
+        Msg::ResultGroup => "{group} ({count} resultados)",
+        Msg::BatchQuery => "Consulta: {query}",
+        Msg::FoundMatches => "Se encontraron {count} coincidencias en {time}",
+        Msg::TotalBytes => "Total de bytes devueltos: {bytes}",
+        Msg::TotalTokens => "Total de tokens devueltos: {tokens}",
+        Msg::SearchCompleted => "Búsqueda completada en {time}",
+        Msg::NoResults => "No se encontraron resultados.",
+        Msg::LimitsApplied => "Límites aplicados:",
+        Msg::MaxResults => "Máx. resultados:",
+        Msg::MaxBytes => "Máx. bytes:",
+        Msg::MaxTokens => "Máx. tokens:",
+        Msg::MaxFiles => "Máx. archivos:",
+        Msg::SkippedByLimits => "Archivos omitidos por los límites:",
+        Msg::RemainingFiles => "Archivos restantes no mostrados:",
+        Msg::LargeFilesSkipped => "Archivos omitidos por superar --max-filesize:",
+        Msg::SessionTokens => "Tokens devueltos en esta sesión hasta ahora:",
+        Msg::SessionFiltered => "Bloques ya vistos filtrados (deduplicación de sesión):",
+        Msg::AllRetrieved => "✓ Se obtuvieron todos los resultados de esta consulta. No hace falta volver a buscar con esta sesión.",
+        Msg::TipsHeader => "Consejos para mejorar la búsqueda:",
+        Msg::TipSynonyms => {
+            "Pruebe sinónimos o términos relacionados (p. ej., \"fetch\" en lugar de \"get\")"
+        }
+        Msg::TipBroaderTerms => "Use términos más amplios sin operadores AND",
+        Msg::TipSpelling => "Revise la ortografía de los nombres de funciones/clases",
+        Msg::TipFilters => "Quite los filtros de tipo de archivo para buscar en todos los archivos",
+        Msg::TipExact => "Use exact:false (predeterminado) para aplicar stemming, o exact:true para buscar símbolos exactos",
+        Msg::Hint => "Nota:",
+        Msg::HintMatchCounts => "<uniq> = términos de búsqueda distintos encontrados, <all> = total de bloques con coincidencias",
+        Msg::TipMoreResultsInSession => "Puede haber más resultados. Vuelva a ejecutar con session: \"{session}\" y nextPage: true. Deténgase cuando vea \"All results retrieved\".",
+        Msg::TipMoreResultsShownSession => "Puede haber más resultados. Vuelva a ejecutar con el ID de sesión mostrado arriba y nextPage: true. Deténgase cuando vea \"All results retrieved\".",
+        Msg::TipMoreResultsUseSession => "Puede haber más resultados. Use --session con el ID de sesión de arriba y nextPage: true. Deténgase cuando vea \"All results retrieved\".",
+        Msg::TipExtract => "Consejo: use `probe extract <archivo>:<línea>` para ver el contexto completo de la función/clase de cualquier resultado anterior",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_languages() {
+        assert_eq!(Lang::from_locale("de_DE.UTF-8"), Some(Lang::De));
+        assert_eq!(Lang::from_locale("es"), Some(Lang::Es));
+        assert_eq!(Lang::from_locale("C.UTF-8"), None);
+        assert_eq!(Lang::from_locale("ja_JP.UTF-8"), None);
+    }
+
+    #[test]
+    fn test_lookup_and_placeholders() {
+        assert_eq!(lookup(Lang::En, Msg::NoResults), "No results found.");
+        assert_eq!(
+            lookup(Lang::De, Msg::NoResults),
+            "Keine Ergebnisse gefunden."
+        );
+        let text = text_with(Msg::TotalTokens, &[("tokens", &42)]);
+        assert!(text.ends_with(": 42"), "{text}");
+    }
+}
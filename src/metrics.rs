@@ -0,0 +1,349 @@
+//! `probe metrics`: structural metrics of every file and symbol of a codebase (lines,
+//! comment ratio, cyclomatic complexity, fan-in and fan-out), to find hotspots.
+//!
+//! Fan-in and fan-out come from a call graph resolved by name: a `Type::name` call goes
+//! to the symbol `Type.name`, any other call to the one symbol of that name, else to the
+//! one in the caller's file. Calls that stay ambiguous, and calls into code outside the
+//! walked files, are left out. A file's fan-in and fan-out count the other files its
+//! symbols are called from and call into.
+
+use anyhow::Result;
+use colored::*;
+use probe_code::extract::metrics::{file_metrics, FileMetrics};
+use probe_code::extract::symbols::language_name;
+use probe_code::search::file_list_cache::get_file_list_by_language;
+use probe_code::warnings;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+pub struct MetricsParams<'a> {
+    pub path: &'a Path,
+    pub language: Option<&'a str>,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+    /// Files and symbols listed by the text format
+    pub top: usize,
+}
+
+#[derive(Serialize)]
+struct FileRow<'a> {
+    path: &'a str,
+    language: &'static str,
+    lines: usize,
+    code_lines: usize,
+    comment_lines: usize,
+    blank_lines: usize,
+    comment_ratio: f64,
+    complexity: usize,
+    symbols: usize,
+    fan_in: usize,
+    fan_out: usize,
+}
+
+#[derive(Serialize)]
+struct SymbolRow<'a> {
+    path: &'a str,
+    symbol: &'a str,
+    kind: &'a str,
+    start_line: usize,
+    end_line: usize,
+    lines: usize,
+    code_lines: usize,
+    comment_lines: usize,
+    blank_lines: usize,
+    comment_ratio: f64,
+    complexity: usize,
+    fan_in: usize,
+    fan_out: usize,
+}
+
+/// A symbol as (file index, symbol index)
+type SymbolId = (usize, usize);
+
+/// Callers and callees of every symbol and file
+#[derive(Default)]
+struct CallGraph {
+    symbol_fan_in: HashMap<SymbolId, usize>,
+    symbol_fan_out: HashMap<SymbolId, usize>,
+    file_callers: HashMap<usize, BTreeSet<usize>>,
+    file_callees: HashMap<usize, BTreeSet<usize>>,
+}
+
+pub fn handle_metrics(params: MetricsParams) -> Result<()> {
+    warnings::set_json(params.format != "text");
+    let file_list = get_file_list_by_language(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.language,
+        params.no_gitignore,
+    )?;
+    let mut files: Vec<(String, FileMetrics)> = file_list
+        .files
+        .par_iter()
+        .filter_map(|file| {
+            let content = fs::read_to_string(file).ok()?;
+            let path = file.to_string_lossy().to_string();
+            let metrics = file_metrics(&path, &content, params.allow_tests)?;
+            Some((path, metrics))
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let graph = call_graph(&files);
+
+    let file_rows: Vec<FileRow> = files
+        .iter()
+        .enumerate()
+        .map(|(index, (path, metrics))| {
+            let counts = metrics.counts;
+            let extension = Path::new(path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("");
+            FileRow {
+                path,
+                language: language_name(extension),
+                lines: counts.lines,
+                code_lines: counts.code,
+                comment_lines: counts.comment,
+                blank_lines: counts.blank,
+                comment_ratio: rounded(counts.comment_ratio()),
+                complexity: metrics.complexity,
+                symbols: metrics.symbols.len(),
+                fan_in: graph.file_callers.get(&index).map_or(0, BTreeSet::len),
+                fan_out: graph.file_callees.get(&index).map_or(0, BTreeSet::len),
+            }
+        })
+        .collect();
+    let symbol_rows: Vec<SymbolRow> = files
+        .iter()
+        .enumerate()
+        .flat_map(|(file_index, (path, metrics))| {
+            let graph = &graph;
+            metrics
+                .symbols
+                .iter()
+                .enumerate()
+                .map(move |(index, symbol)| {
+                    let counts = symbol.counts;
+                    let id = (file_index, index);
+                    SymbolRow {
+                        path,
+                        symbol: &symbol.name,
+                        kind: &symbol.kind,
+                        start_line: symbol.lines.0,
+                        end_line: symbol.lines.1,
+                        lines: counts.lines,
+                        code_lines: counts.code,
+                        comment_lines: counts.comment,
+                        blank_lines: counts.blank,
+                        comment_ratio: rounded(counts.comment_ratio()),
+                        complexity: symbol.complexity,
+                        fan_in: graph.symbol_fan_in.get(&id).copied().unwrap_or(0),
+                        fan_out: graph.symbol_fan_out.get(&id).copied().unwrap_or(0),
+                    }
+                })
+        })
+        .collect();
+
+    match params.format {
+        "json" => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "files": file_rows,
+                "symbols": symbol_rows,
+            }))?
+        ),
+        "csv" => print_csv(&file_rows, &symbol_rows),
+        _ => print_text(file_rows, symbol_rows, params.top),
+    }
+    Ok(())
+}
+
+fn rounded(ratio: f64) -> f64 {
+    (ratio * 100.0).round() / 100.0
+}
+
+/// Resolve the calls of every symbol and count the callers and callees
+fn call_graph(files: &[(String, FileMetrics)]) -> CallGraph {
+    let mut by_name: HashMap<&str, Vec<SymbolId>> = HashMap::new();
+    let mut by_qualified_name: HashMap<String, Vec<SymbolId>> = HashMap::new();
+    for (file_index, (_, metrics)) in files.iter().enumerate() {
+        for (index, symbol) in metrics.symbols.iter().enumerate() {
+            let segments: Vec<&str> = symbol.name.split('.').collect();
+            let id = (file_index, index);
+            by_name
+                .entry(segments[segments.len() - 1])
+                .or_default()
+                .push(id);
+            if segments.len() > 1 {
+                let qualified = segments[segments.len() - 2..].join(".");
+                by_qualified_name.entry(qualified).or_default().push(id);
+            }
+        }
+    }
+    // The one candidate, or else the one in the caller's file
+    let pick = |candidates: Option<&Vec<SymbolId>>, file_index: usize| {
+        let candidates = candidates?;
+        if let [only] = candidates.as_slice() {
+            return Some(*only);
+        }
+        let mut local = candidates.iter().filter(|(file, _)| *file == file_index);
+        match (local.next(), local.next()) {
+            (Some(only), None) => Some(*only),
+            _ => None,
+        }
+    };
+
+    let mut graph = CallGraph::default();
+    for (file_index, (_, metrics)) in files.iter().enumerate() {
+        for (index, symbol) in metrics.symbols.iter().enumerate() {
+            let caller = (file_index, index);
+            let callees: BTreeSet<SymbolId> = symbol
+                .calls
+                .iter()
+                .filter_map(|call| match call.rsplit_once('.') {
+                    Some((_, name)) => pick(by_qualified_name.get(call), file_index)
+                        .or_else(|| pick(by_name.get(name), file_index)),
+                    None => pick(by_name.get(call.as_str()), file_index),
+                })
+                .filter(|callee| *callee != caller)
+                .collect();
+            graph.symbol_fan_out.insert(caller, callees.len());
+            for callee in callees {
+                *graph.symbol_fan_in.entry(callee).or_default() += 1;
+                if callee.0 != file_index {
+                    graph
+                        .file_callees
+                        .entry(file_index)
+                        .or_default()
+                        .insert(callee.0);
+                    graph
+                        .file_callers
+                        .entry(callee.0)
+                        .or_default()
+                        .insert(file_index);
+                }
+            }
+        }
+    }
+    graph
+}
+
+/// One table of files and symbols: a file's row has the kind `file` and no symbol
+fn print_csv(files: &[FileRow], symbols: &[SymbolRow]) {
+    println!("path,symbol,kind,start_line,end_line,lines,code_lines,comment_lines,blank_lines,comment_ratio,complexity,fan_in,fan_out");
+    for file in files {
+        println!(
+            "{},,file,1,{},{},{},{},{},{},{},{},{}",
+            csv_field(file.path),
+            file.lines,
+            file.lines,
+            file.code_lines,
+            file.comment_lines,
+            file.blank_lines,
+            file.comment_ratio,
+            file.complexity,
+            file.fan_in,
+            file.fan_out
+        );
+    }
+    for symbol in symbols {
+        println!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(symbol.path),
+            csv_field(symbol.symbol),
+            csv_field(symbol.kind),
+            symbol.start_line,
+            symbol.end_line,
+            symbol.lines,
+            symbol.code_lines,
+            symbol.comment_lines,
+            symbol.blank_lines,
+            symbol.comment_ratio,
+            symbol.complexity,
+            symbol.fan_in,
+            symbol.fan_out
+        );
+    }
+}
+
+/// A CSV field, quoted when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The most complex files and symbols
+fn print_text(mut files: Vec<FileRow>, mut symbols: Vec<SymbolRow>, top: usize) {
+    files.sort_by(|a, b| b.complexity.cmp(&a.complexity).then(a.path.cmp(b.path)));
+    symbols.sort_by(|a, b| {
+        b.complexity
+            .cmp(&a.complexity)
+            .then(a.path.cmp(b.path))
+            .then(a.start_line.cmp(&b.start_line))
+    });
+
+    println!(
+        "{}",
+        format!(
+            "Files by complexity ({} of {})",
+            top.min(files.len()),
+            files.len()
+        )
+        .bold()
+        .green()
+    );
+    println!(
+        "{:>10} {:>7} {:>8} {:>6} {:>7}  path",
+        "complexity", "lines", "comments", "fan-in", "fan-out"
+    );
+    for file in files.iter().take(top) {
+        println!(
+            "{:>10} {:>7} {:>7.0}% {:>6} {:>7}  {}",
+            file.complexity,
+            file.lines,
+            file.comment_ratio * 100.0,
+            file.fan_in,
+            file.fan_out,
+            file.path
+        );
+    }
+
+    println!();
+    println!(
+        "{}",
+        format!(
+            "Symbols by complexity ({} of {})",
+            top.min(symbols.len()),
+            symbols.len()
+        )
+        .bold()
+        .green()
+    );
+    println!(
+        "{:>10} {:>7} {:>8} {:>6} {:>7}  symbol",
+        "complexity", "lines", "comments", "fan-in", "fan-out"
+    );
+    for symbol in symbols.iter().take(top) {
+        println!(
+            "{:>10} {:>7} {:>7.0}% {:>6} {:>7}  {} ({}:{})",
+            symbol.complexity,
+            symbol.lines,
+            symbol.comment_ratio * 100.0,
+            symbol.fan_in,
+            symbol.fan_out,
+            symbol.symbol,
+            symbol.path,
+            symbol.start_line
+        );
+    }
+}
@@ -1,3 +1,8 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Deref, Range};
+use std::sync::Arc;
+
 // Structure to hold both limited search results and skipped files
 #[derive(Debug)]
 pub struct LimitedSearchResults {
@@ -6,6 +11,23 @@ pub struct LimitedSearchResults {
     pub limits_applied: Option<SearchLimits>,
     pub cached_blocks_skipped: Option<usize>,
     pub files_skipped_early_termination: Option<usize>,
+    // Tokens returned by all searches of the --session so far, including this one
+    pub session_tokens: Option<usize>,
+    // With --count-only, the size of the result set; `results` is then empty
+    pub match_count: Option<MatchCount>,
+    // Number of ranked results before --offset and the limits picked the returned ones
+    pub total_ranked: Option<usize>,
+    // Files left out of the search by --max-filesize, with their sizes in bytes
+    pub large_files_skipped: Vec<(String, u64)>,
+}
+
+// Estimated size of a search's results, for --count-only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchCount {
+    pub files: usize,
+    // Matched lines grouped as block merging would group them; roughly one block each
+    pub blocks: usize,
+    pub lines: usize,
 }
 
 // Structure to track which limits were applied
@@ -14,6 +36,7 @@ pub struct SearchLimits {
     pub max_results: Option<usize>,
     pub max_bytes: Option<usize>,
     pub max_tokens: Option<usize>,
+    pub max_files: Option<usize>,
 
     #[allow(dead_code)]
     pub total_bytes: usize,
@@ -21,7 +44,7 @@ pub struct SearchLimits {
     pub total_tokens: usize,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ParentContext {
     pub node_type: String,
     pub start_line: usize,
@@ -30,17 +53,25 @@ pub struct ParentContext {
     pub preceding_comments: Vec<(usize, usize, String)>, // Comments that precede this context node: (start_line, end_line, text)
 }
 
-// Structure to hold search results
-#[derive(Debug, Clone)]
+// Structure to hold search results; serialized in saved result sets (--save-results)
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub file: String,
     pub lines: (usize, usize),
     pub node_type: String,
-    pub code: String,
+    pub code: BlockCode,
     // Symbol signature (when symbols flag is used)
     pub symbol_signature: Option<String>,
     // Indicates if this result was found by filename matching
     pub matched_by_filename: Option<bool>,
+    // Other files containing an identical copy of this block (--dedupe-cross-file)
+    pub duplicate_files: Option<Vec<String>>,
+    // Last commit to change the block (--blame)
+    pub blame: Option<BlameInfo>,
+    // Owners of the file from CODEOWNERS (--owners)
+    pub owners: Option<Vec<String>>,
+    // How an extracted block was resolved from the requested target (extract only)
+    pub provenance: Option<ExtractionProvenance>,
     // Ranking information
     pub rank: Option<usize>,
     // Combined score from the ranking algorithm
@@ -80,11 +111,170 @@ pub struct SearchResult {
     pub matched_lines: Option<Vec<usize>>,
     /// Tokenized version of the code block with filename prepended
     #[allow(dead_code)]
+    #[serde(skip)]
     pub tokenized_content: Option<Vec<String>>,
     // Parent context chain for enhanced outline display
     pub parent_context: Option<Vec<ParentContext>>,
 }
 
+// The code of a result block: a span of a buffer shared with the other blocks of the
+// same file, so candidate blocks don't each copy their text. It derefs to `str`, and
+// serializes as a plain string.
+#[derive(Clone, Default)]
+pub struct BlockCode {
+    source: Arc<str>,
+    span: Range<usize>,
+}
+
+impl BlockCode {
+    // The block at `span` of `source`, which must be on char boundaries
+    pub fn shared(source: &Arc<str>, span: Range<usize>) -> Self {
+        debug_assert!(source.get(span.clone()).is_some());
+        BlockCode {
+            source: Arc::clone(source),
+            span,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source[self.span.clone()]
+    }
+
+    // The part of this block at `range`, relative to its start, sharing its buffer
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        debug_assert!(self.as_str().get(range.clone()).is_some());
+        BlockCode {
+            source: Arc::clone(&self.source),
+            span: self.span.start + range.start..self.span.start + range.end,
+        }
+    }
+
+    // The code from the start of the earlier block to the end of the later one, when
+    // both are spans of the same buffer
+    pub fn covering(&self, other: &BlockCode) -> Option<Self> {
+        Arc::ptr_eq(&self.source, &other.source).then(|| BlockCode {
+            source: Arc::clone(&self.source),
+            span: self.span.start.min(other.span.start)..self.span.end.max(other.span.end),
+        })
+    }
+}
+
+impl Deref for BlockCode {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for BlockCode {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for BlockCode {
+    fn from(code: String) -> Self {
+        let len = code.len();
+        BlockCode {
+            source: code.into(),
+            span: 0..len,
+        }
+    }
+}
+
+impl From<&str> for BlockCode {
+    fn from(code: &str) -> Self {
+        BlockCode {
+            source: code.into(),
+            span: 0..code.len(),
+        }
+    }
+}
+
+impl From<BlockCode> for String {
+    fn from(code: BlockCode) -> Self {
+        code.as_str().to_string()
+    }
+}
+
+impl PartialEq for BlockCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for BlockCode {}
+
+impl PartialEq<str> for BlockCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for BlockCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for BlockCode {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl fmt::Debug for BlockCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for BlockCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for BlockCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(BlockCode::from)
+    }
+}
+
+// How `probe extract` resolved a requested target into the returned block
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ExtractionProvenance {
+    // The target as requested, e.g. `src/lib.rs:10-20` or `src/lib.rs#parse`
+    pub requested: String,
+    // symbol, text-search, ast-range, literal-fallback, context, file or not-found
+    pub strategy: String,
+    // How likely the block is the code that was asked for, from 0.0 to 1.0
+    #[serde(default)]
+    pub confidence: f64,
+    // The requested line range, when it was out of bounds and had to be clamped
+    pub clamped_from: Option<(usize, usize)>,
+    // Why AST parsing failed, when a non-AST fallback was used because of it
+    pub parse_error: Option<String>,
+    // Closest symbol names in the file, when the requested symbol was not found
+    pub suggestions: Vec<String>,
+}
+
+// The last commit to change a block, from `git blame`
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlameInfo {
+    pub author: String,
+    pub commit: String,
+    // Author date of the commit, YYYY-MM-DD in the author's time zone
+    pub date: String,
+}
+
 // Structure to hold node information for merging
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CodeBlock {
@@ -102,3 +292,23 @@ pub struct CodeBlock {
     // Full parent context chain for better outline display
     pub parent_context: Option<Vec<ParentContext>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_code_shares_its_buffer() {
+        let source: Arc<str> = "fn a() {}\nfn b() {}\nfn c() {}".into();
+        let a = BlockCode::shared(&source, 0..9);
+        let c = BlockCode::shared(&source, 20..29);
+        assert_eq!(a, "fn a() {}");
+        assert_eq!(c.slice(3..4), "c");
+        assert_eq!(a.covering(&c).unwrap(), &*source);
+        assert!(a.covering(&BlockCode::from("fn c() {}")).is_none());
+
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, r#""fn c() {}""#);
+        assert_eq!(serde_json::from_str::<BlockCode>(&json).unwrap(), c);
+    }
+}
@@ -10,9 +10,13 @@ mod tests {
             file: "test.rs".to_string(),
             lines: (1, 10),
             node_type: "function".to_string(),
-            code: "fn test() {}".to_string(),
+            code: "fn test() {}".into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -78,9 +82,13 @@ mod tests {
                 file: "test1.rs".to_string(),
                 lines: (1, 10),
                 node_type: "function".to_string(),
-                code: "fn test1() {}".to_string(),
+                code: "fn test1() {}".into(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: Some(1),
                 score: Some(0.9),
                 tfidf_score: Some(0.9),
@@ -104,9 +112,13 @@ mod tests {
                 file: "test2.rs".to_string(),
                 lines: (1, 10),
                 node_type: "function".to_string(),
-                code: "fn test2() {}".to_string(),
+                code: "fn test2() {}".into(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: Some(2),
                 score: Some(0.8),
                 tfidf_score: Some(0.8),
@@ -134,9 +146,13 @@ mod tests {
                 file: "test3.rs".to_string(),
                 lines: (1, 10),
                 node_type: "function".to_string(),
-                code: "fn test3() {}".to_string(),
+                code: "fn test3() {}".into(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: Some(3),
                 score: Some(0.7),
                 tfidf_score: Some(0.7),
@@ -163,6 +179,7 @@ mod tests {
             max_results: Some(2),
             max_bytes: Some(1000),
             max_tokens: Some(200),
+            max_files: None,
             total_bytes: 24,
             total_tokens: 6,
         };
@@ -172,6 +189,9 @@ mod tests {
             results: results.clone(),
             skipped_files: skipped_files.clone(),
             limits_applied: Some(limits),
+            match_count: None,
+            total_ranked: None,
+            large_files_skipped: Vec::new(),
         };
 
         // Check the contents
@@ -193,6 +213,7 @@ mod tests {
             max_results: Some(10),
             max_bytes: Some(1000),
             max_tokens: Some(200),
+            max_files: None,
             total_bytes: 500,
             total_tokens: 100,
         };
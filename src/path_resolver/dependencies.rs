@@ -0,0 +1,347 @@
+//! Dependency source discovery for `--include-deps`.
+//!
+//! Given a project directory, finds the source trees of its dependencies at the
+//! versions the project actually uses: `vendor/`, `node_modules` packages listed in
+//! package.json, crates pinned in Cargo.lock (from the local cargo registry source
+//! cache) and modules required by go.mod (from the Go module cache).
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Find the dependency source roots of the project containing `project`.
+///
+/// Each manifest kind is looked up in the nearest ancestor that has it, so this
+/// works from a subdirectory of the project too. Only directories that exist on
+/// disk are returned; dependencies that were never downloaded are skipped.
+pub fn find_dependency_roots(project: &Path) -> Vec<PathBuf> {
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+    let project = if project.is_file() {
+        project.parent().map(Path::to_path_buf).unwrap_or(project)
+    } else {
+        project
+    };
+
+    let mut roots = Vec::new();
+
+    if let Some(dir) = nearest_with(&project, "vendor") {
+        roots.push(dir.join("vendor"));
+    }
+    if let Some(dir) = nearest_with(&project, "package.json") {
+        roots.extend(node_module_roots(&dir));
+    }
+    if let Some(dir) = nearest_with(&project, "Cargo.lock") {
+        if let Some(registry) = cargo_registry_src() {
+            roots.extend(cargo_lock_roots(&dir.join("Cargo.lock"), &registry));
+        }
+    }
+    if let Some(dir) = nearest_with(&project, "go.mod") {
+        if let Some(cache) = go_mod_cache() {
+            roots.extend(go_mod_roots(&dir.join("go.mod"), &cache));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    roots.retain(|root| root.is_dir() && seen.insert(root.clone()));
+    roots
+}
+
+/// Find the source directory of the crate `crate_name` in the cargo registry cache.
+///
+/// The version pinned in the Cargo.lock of the project containing `project` is
+/// preferred; otherwise the highest cached version is used. `-` and `_` in the
+/// crate name are treated as equal.
+pub fn find_crate_source(project: &Path, crate_name: &str) -> Option<PathBuf> {
+    let registry = cargo_registry_src()?;
+    let normalize = |name: &str| name.replace('-', "_");
+    let wanted = normalize(crate_name);
+
+    let project = project
+        .canonicalize()
+        .unwrap_or_else(|_| project.to_path_buf());
+    if let Some(dir) = nearest_with(&project, "Cargo.lock") {
+        if let Ok(content) = fs::read_to_string(dir.join("Cargo.lock")) {
+            let pinned: Vec<(String, String)> = parse_cargo_lock(&content)
+                .into_iter()
+                .filter(|(name, _)| normalize(name) == wanted)
+                .collect();
+            for index in fs::read_dir(&registry).ok()?.flatten() {
+                for (name, version) in &pinned {
+                    let dir = index.path().join(format!("{name}-{version}"));
+                    if dir.is_dir() {
+                        return Some(dir);
+                    }
+                }
+            }
+        }
+    }
+
+    // Not pinned by the project: take the highest cached version
+    let mut candidates: Vec<(Vec<u64>, PathBuf)> = Vec::new();
+    for index in fs::read_dir(&registry).ok()?.flatten() {
+        let Ok(entries) = fs::read_dir(index.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir_name = entry.file_name().to_string_lossy().to_string();
+            let Some((name, version)) = split_name_version(&dir_name) else {
+                continue;
+            };
+            if normalize(name) == wanted {
+                let key = version
+                    .split(|c: char| !c.is_ascii_digit())
+                    .map(|part| part.parse().unwrap_or(0))
+                    .collect();
+                candidates.push((key, entry.path()));
+            }
+        }
+    }
+    candidates.sort();
+    candidates.pop().map(|(_, dir)| dir)
+}
+
+/// Split a registry directory name such as `serde_json-1.0.100` into name and version
+fn split_name_version(dir_name: &str) -> Option<(&str, &str)> {
+    let split = dir_name
+        .match_indices('-')
+        .map(|(i, _)| i)
+        .find(|&i| dir_name[i + 1..].starts_with(|c: char| c.is_ascii_digit()))?;
+    Some((&dir_name[..split], &dir_name[split + 1..]))
+}
+
+/// Nearest ancestor of `start` (inclusive) that contains `name`
+fn nearest_with(start: &Path, name: &str) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(name).exists())
+        .map(Path::to_path_buf)
+}
+
+/// `node_modules/<name>` for every dependency and devDependency in package.json
+fn node_module_roots(dir: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(dir.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+
+    ["dependencies", "devDependencies"]
+        .iter()
+        .filter_map(|key| json[*key].as_object())
+        .flat_map(|deps| deps.keys())
+        .map(|name| dir.join("node_modules").join(name))
+        .collect()
+}
+
+/// `$CARGO_HOME/registry/src`, defaulting to `~/.cargo`
+fn cargo_registry_src() -> Option<PathBuf> {
+    let cargo_home = std::env::var("CARGO_HOME")
+        .ok()
+        .or_else(|| std::env::var("HOME").ok().map(|h| format!("{h}/.cargo")))?;
+    Some(PathBuf::from(cargo_home).join("registry").join("src"))
+}
+
+/// Registry package directories (`name-version`) for the packages in Cargo.lock
+fn cargo_lock_roots(lock_file: &Path, registry_src: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(lock_file) else {
+        return Vec::new();
+    };
+    let Ok(indices) = fs::read_dir(registry_src) else {
+        return Vec::new();
+    };
+    let indices: Vec<PathBuf> = indices.flatten().map(|e| e.path()).collect();
+
+    parse_cargo_lock(&content)
+        .into_iter()
+        .filter_map(|(name, version)| {
+            let dir_name = format!("{name}-{version}");
+            indices
+                .iter()
+                .map(|index| index.join(&dir_name))
+                .find(|dir| dir.is_dir())
+        })
+        .collect()
+}
+
+/// `(name, version)` of every registry package in a Cargo.lock file
+fn parse_cargo_lock(content: &str) -> Vec<(String, String)> {
+    let mut packages = Vec::new();
+    let mut current: Option<(Option<String>, Option<String>, bool)> = None;
+
+    let mut flush = |current: &mut Option<(Option<String>, Option<String>, bool)>| {
+        if let Some((Some(name), Some(version), true)) = current.take() {
+            packages.push((name, version));
+        }
+    };
+
+    for line in content.lines().map(str::trim) {
+        if line == "[[package]]" {
+            flush(&mut current);
+            current = Some((None, None, false));
+            continue;
+        }
+        if line.starts_with('[') {
+            flush(&mut current);
+            continue;
+        }
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "name" => entry.0 = Some(value),
+            "version" => entry.1 = Some(value),
+            "source" => entry.2 = value.starts_with("registry+") || value.starts_with("sparse+"),
+            _ => {}
+        }
+    }
+    flush(&mut current);
+
+    packages
+}
+
+/// The Go module cache: `$GOMODCACHE`, `$GOPATH/pkg/mod` or `~/go/pkg/mod`
+fn go_mod_cache() -> Option<PathBuf> {
+    if let Ok(cache) = std::env::var("GOMODCACHE") {
+        if !cache.is_empty() {
+            return Some(PathBuf::from(cache));
+        }
+    }
+    let gopath = std::env::var("GOPATH")
+        .ok()
+        .and_then(|p| p.split(':').next().map(str::to_string))
+        .filter(|p| !p.is_empty())
+        .or_else(|| std::env::var("HOME").ok().map(|h| format!("{h}/go")))?;
+    Some(PathBuf::from(gopath).join("pkg").join("mod"))
+}
+
+/// Module cache directories (`path@version`) for the requirements in go.mod
+fn go_mod_roots(go_mod: &Path, cache: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(go_mod) else {
+        return Vec::new();
+    };
+
+    parse_go_requires(&content)
+        .into_iter()
+        .map(|(module, version)| cache.join(format!("{}@{version}", escape_module_path(&module))))
+        .collect()
+}
+
+/// `(module, version)` of every `require` entry in a go.mod file
+fn parse_go_requires(content: &str) -> Vec<(String, String)> {
+    let mut requires = Vec::new();
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let entry = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+
+        let mut parts = entry.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            requires.push((module.to_string(), version.to_string()));
+        }
+    }
+
+    requires
+}
+
+/// Apply the module cache's case encoding: uppercase letters become `!` + lowercase
+fn escape_module_path(module: &str) -> String {
+    let mut escaped = String::with_capacity(module.len());
+    for c in module.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_manifests() {
+        let lock = r#"
+[[package]]
+name = "local"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.200"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        assert_eq!(
+            parse_cargo_lock(lock),
+            vec![("serde".to_string(), "1.0.200".to_string())]
+        );
+
+        let go_mod = "module example.com/app\n\nrequire github.com/pkg/errors v0.9.1\n\nrequire (\n\tgithub.com/BurntSushi/toml v1.3.2 // indirect\n)\n";
+        assert_eq!(
+            parse_go_requires(go_mod),
+            vec![
+                ("github.com/pkg/errors".to_string(), "v0.9.1".to_string()),
+                (
+                    "github.com/BurntSushi/toml".to_string(),
+                    "v1.3.2".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            escape_module_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+        assert_eq!(
+            split_name_version("tokio-macros-2.1.0"),
+            Some(("tokio-macros", "2.1.0"))
+        );
+    }
+
+    #[test]
+    fn test_find_dependency_roots_node_and_vendor() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{"dependencies": {"left-pad": "1.0.0", "missing": "1.0.0"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("node_modules/left-pad")).unwrap();
+        fs::create_dir_all(root.join("node_modules/unlisted")).unwrap();
+        fs::create_dir_all(root.join("vendor")).unwrap();
+        fs::create_dir_all(root.join("src")).unwrap();
+
+        let root = root.canonicalize().unwrap();
+        let roots = find_dependency_roots(&root.join("src"));
+        assert_eq!(
+            roots,
+            vec![root.join("vendor"), root.join("node_modules/left-pad")]
+        );
+    }
+}
@@ -3,15 +3,22 @@
 //! This module provides functionality to resolve special path formats like
 //! "go:github.com/user/repo", "js:express", or "rust:serde" to actual filesystem paths.
 
+mod dependencies;
 mod go;
 mod javascript;
 mod rust;
+mod workspace;
 
+use probe_code::warnings::{warn, WarningKind};
 use std::path::{Path, PathBuf};
 
+pub use dependencies::{find_crate_source, find_dependency_roots};
 pub use go::GoPathResolver;
 pub use javascript::JavaScriptPathResolver;
 pub use rust::RustPathResolver;
+pub use workspace::{
+    find_workspace_packages, resolve_workspace_package, WorkspaceKind, WorkspacePackage,
+};
 
 /// A trait for language-specific path resolvers.
 ///
@@ -161,7 +168,11 @@ pub fn resolve_path(path: &str) -> Result<PathBuf, String> {
         let prefix = resolver.prefix();
         if !prefix.ends_with(':') {
             // Internal sanity check
-            eprintln!("Warning: PathResolver prefix '{prefix}' does not end with ':'");
+            warn(
+                WarningKind::Other,
+                None,
+                format!("PathResolver prefix '{prefix}' does not end with ':'"),
+            );
             continue;
         }
 
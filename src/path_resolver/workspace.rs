@@ -0,0 +1,390 @@
+//! Workspace member detection for monorepos.
+//!
+//! Detects Cargo workspaces (`[workspace] members`), npm/yarn workspaces
+//! (`"workspaces"` in package.json), pnpm workspaces (`pnpm-workspace.yaml`) and
+//! Go workspaces (`go.work`), so a member package can be addressed by name.
+
+use glob::glob;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The kind of workspace a package belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkspaceKind {
+    Cargo,
+    Npm,
+    Pnpm,
+    Go,
+}
+
+/// A member package of a workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    /// Package name (crate name, package.json name, or Go module path)
+    pub name: String,
+    /// Directory containing the package
+    pub path: PathBuf,
+    pub kind: WorkspaceKind,
+}
+
+/// Find the packages of the nearest workspace at or above `start`.
+///
+/// Ancestors are checked from `start` upward and the first directory declaring a
+/// workspace (of any supported kind) wins. Returns an empty list outside a workspace.
+pub fn find_workspace_packages(start: &Path) -> Vec<WorkspacePackage> {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+
+    for dir in start.ancestors() {
+        let mut packages = Vec::new();
+        packages.extend(cargo_workspace_members(dir));
+        packages.extend(npm_workspace_members(dir));
+        packages.extend(pnpm_workspace_members(dir));
+        packages.extend(go_workspace_members(dir));
+
+        if !packages.is_empty() {
+            packages.sort_by(|a, b| a.path.cmp(&b.path).then(a.name.cmp(&b.name)));
+            packages.dedup_by(|a, b| a.path == b.path && a.name == b.name);
+            return packages;
+        }
+    }
+
+    Vec::new()
+}
+
+/// Resolve a workspace member package name to its directory.
+///
+/// Names match exactly; Go modules also match by their last path segment and Cargo
+/// crates match with `-` and `_` treated as equal.
+///
+/// # Returns
+///
+/// * `Ok(PathBuf)` - The package directory
+/// * `Err(String)` - An error message listing the available packages if none match
+pub fn resolve_workspace_package(start: &Path, name: &str) -> Result<PathBuf, String> {
+    let packages = find_workspace_packages(start);
+    if packages.is_empty() {
+        return Err(format!(
+            "No Cargo, npm/pnpm or Go workspace found at or above {start:?}"
+        ));
+    }
+
+    let matches = |package: &WorkspacePackage| match package.kind {
+        WorkspaceKind::Cargo => package.name.replace('-', "_") == name.replace('-', "_"),
+        WorkspaceKind::Go => package.name == name || package.name.rsplit('/').next() == Some(name),
+        WorkspaceKind::Npm | WorkspaceKind::Pnpm => package.name == name,
+    };
+
+    if let Some(package) = packages.iter().find(|p| matches(p)) {
+        return Ok(package.path.clone());
+    }
+
+    let available: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+    Err(format!(
+        "Package '{}' not found in workspace. Available packages: {}",
+        name,
+        available.join(", ")
+    ))
+}
+
+/// Expand workspace member globs relative to `root` into existing directories
+fn expand_member_globs(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        // Exclusions ("!packages/internal") are not supported and skipped
+        if pattern.starts_with('!') {
+            continue;
+        }
+        let full_pattern = root.join(pattern.trim_end_matches('/'));
+        if let Ok(entries) = glob(&full_pattern.to_string_lossy()) {
+            dirs.extend(entries.flatten().filter(|path| path.is_dir()));
+        }
+    }
+    dirs
+}
+
+/// Collect the quoted strings of a TOML array assigned to `key` inside `section`
+fn toml_string_array(content: &str, section: &str, key: &str) -> Option<Vec<String>> {
+    let mut in_section = false;
+    let mut collecting = false;
+    let mut values = Vec::new();
+
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if !collecting && line.starts_with('[') {
+            in_section = line == format!("[{section}]");
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+
+        let rest = if collecting {
+            line
+        } else if let Some(rest) = line.strip_prefix(key) {
+            match rest.trim_start().strip_prefix('=') {
+                Some(rest) => {
+                    collecting = true;
+                    rest.trim_start().strip_prefix('[').unwrap_or(rest)
+                }
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+
+        let (items, closed) = match rest.split_once(']') {
+            Some((items, _)) => (items, true),
+            None => (rest, false),
+        };
+        values.extend(
+            items
+                .split(',')
+                .map(|item| item.trim().trim_matches('"').trim_matches('\'').to_string())
+                .filter(|item| !item.is_empty()),
+        );
+        if closed {
+            return Some(values);
+        }
+    }
+
+    collecting.then_some(values)
+}
+
+/// Read `name = "..."` from the `[package]` table of a Cargo.toml
+fn cargo_package_name(manifest: &Path) -> Option<String> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let mut in_package = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(value) = line.strip_prefix("name") {
+                let value = value.trim_start().strip_prefix('=')?.trim();
+                return Some(value.trim_matches('"').to_string());
+            }
+        }
+    }
+    None
+}
+
+fn cargo_workspace_members(dir: &Path) -> Vec<WorkspacePackage> {
+    let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(patterns) = toml_string_array(&content, "workspace", "members") else {
+        return Vec::new();
+    };
+
+    expand_member_globs(dir, &patterns)
+        .into_iter()
+        .filter_map(|path| {
+            let name = cargo_package_name(&path.join("Cargo.toml"))?;
+            Some(WorkspacePackage {
+                name,
+                path,
+                kind: WorkspaceKind::Cargo,
+            })
+        })
+        .collect()
+}
+
+fn package_json_name(dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let json: Value = serde_json::from_str(&content).ok()?;
+    json["name"].as_str().map(str::to_string)
+}
+
+fn js_members(dir: &Path, patterns: &[String], kind: WorkspaceKind) -> Vec<WorkspacePackage> {
+    expand_member_globs(dir, patterns)
+        .into_iter()
+        .filter_map(|path| {
+            let name = package_json_name(&path)?;
+            Some(WorkspacePackage { name, path, kind })
+        })
+        .collect()
+}
+
+fn npm_workspace_members(dir: &Path) -> Vec<WorkspacePackage> {
+    let Ok(content) = fs::read_to_string(dir.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&content) else {
+        return Vec::new();
+    };
+
+    // "workspaces": [...] or "workspaces": { "packages": [...] }
+    let workspaces = &json["workspaces"];
+    let list = workspaces
+        .as_array()
+        .or_else(|| workspaces["packages"].as_array());
+    let patterns: Vec<String> = list
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect();
+
+    js_members(dir, &patterns, WorkspaceKind::Npm)
+}
+
+fn pnpm_workspace_members(dir: &Path) -> Vec<WorkspacePackage> {
+    let Ok(content) = fs::read_to_string(dir.join("pnpm-workspace.yaml")) else {
+        return Vec::new();
+    };
+
+    // Only the `packages:` list is relevant; read its `- pattern` items
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with([' ', '\t', '-']) && !trimmed.is_empty() {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                let item = item.trim().trim_matches('"').trim_matches('\'');
+                if !item.is_empty() {
+                    patterns.push(item.to_string());
+                }
+            }
+        }
+    }
+
+    js_members(dir, &patterns, WorkspaceKind::Pnpm)
+}
+
+fn go_module_name(dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(dir.join("go.mod")).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|name| name.trim().trim_matches('"').to_string())
+}
+
+fn go_workspace_members(dir: &Path) -> Vec<WorkspacePackage> {
+    let Ok(content) = fs::read_to_string(dir.join("go.work")) else {
+        return Vec::new();
+    };
+
+    // `use ./a` or a `use ( ... )` block
+    let mut module_dirs = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                module_dirs.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                module_dirs.push(rest.to_string());
+            }
+        }
+    }
+
+    module_dirs
+        .into_iter()
+        .filter_map(|module_dir| {
+            let path = dir.join(module_dir.trim_matches('"'));
+            let name = go_module_name(&path)?;
+            Some(WorkspacePackage {
+                name,
+                path,
+                kind: WorkspaceKind::Go,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_cargo_workspace_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        write(
+            &root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"crates/*\", # all crates\n    \"tools/cli\",\n]\n",
+        );
+        write(
+            &root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"my-core\"\n",
+        );
+        write(
+            &root.join("tools/cli/Cargo.toml"),
+            "[package]\nname = \"my-cli\"\n\n[dependencies]\nname = \"ignored\"\n",
+        );
+
+        let packages = find_workspace_packages(&root.join("crates/core"));
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["my-core", "my-cli"]);
+
+        assert_eq!(
+            resolve_workspace_package(&root, "my_core").unwrap(),
+            root.join("crates/core")
+        );
+        let err = resolve_workspace_package(&root, "missing").unwrap_err();
+        assert!(err.contains("my-cli"), "{err}");
+    }
+
+    #[test]
+    fn test_js_and_go_workspace_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+
+        let npm = root.join("npm");
+        write(
+            &npm.join("package.json"),
+            r#"{"name": "root", "workspaces": {"packages": ["packages/*"]}}"#,
+        );
+        write(
+            &npm.join("packages/ui/package.json"),
+            r#"{"name": "@acme/ui"}"#,
+        );
+        assert_eq!(
+            resolve_workspace_package(&npm, "@acme/ui").unwrap(),
+            npm.join("packages/ui")
+        );
+
+        let pnpm = root.join("pnpm");
+        write(
+            &pnpm.join("pnpm-workspace.yaml"),
+            "packages:\n  - 'apps/*'\n  - \"!apps/legacy\"\n",
+        );
+        write(&pnpm.join("apps/web/package.json"), r#"{"name": "web"}"#);
+        assert_eq!(
+            resolve_workspace_package(&pnpm, "web").unwrap(),
+            pnpm.join("apps/web")
+        );
+
+        let go = root.join("go");
+        write(
+            &go.join("go.work"),
+            "go 1.21\n\nuse (\n\t./api\n\t./worker\n)\n",
+        );
+        write(&go.join("api/go.mod"), "module github.com/acme/api\n");
+        write(&go.join("worker/go.mod"), "module github.com/acme/worker\n");
+        assert_eq!(
+            resolve_workspace_package(&go, "worker").unwrap(),
+            go.join("worker")
+        );
+        assert_eq!(
+            resolve_workspace_package(&go, "github.com/acme/api").unwrap(),
+            go.join("api")
+        );
+    }
+}
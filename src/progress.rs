@@ -0,0 +1,141 @@
+//! Progress bars on stderr for `--progress`.
+//!
+//! Long-running stages (listing files, scanning them for matches, processing matches,
+//! extracting, indexing) each open a [`Progress`] and tick it once per file. Nothing is
+//! drawn unless [`enable`] was called with stderr attached to a terminal, so the calls
+//! cost an atomic increment when progress is off.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Minimum time between redraws, so ticking from many threads stays cheap
+const REDRAW_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Width of the bar, in characters
+const BAR_WIDTH: usize = 30;
+
+/// Turn progress bars on. They stay off when stderr is not a terminal, so piped or
+/// captured output never contains them.
+pub fn enable() {
+    ENABLED.store(atty::is(atty::Stream::Stderr), Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Progress of one stage; the line is cleared when it is dropped
+pub struct Progress {
+    stage: &'static str,
+    total: Option<usize>,
+    done: AtomicUsize,
+    last_draw: Mutex<Option<Instant>>,
+}
+
+impl Progress {
+    /// A stage processing `total` files
+    pub fn new(stage: &'static str, total: usize) -> Self {
+        Self::with_total(stage, Some(total))
+    }
+
+    /// A stage whose total isn't known up front, such as a directory walk
+    pub fn unbounded(stage: &'static str) -> Self {
+        Self::with_total(stage, None)
+    }
+
+    fn with_total(stage: &'static str, total: Option<usize>) -> Self {
+        let progress = Progress {
+            stage,
+            total,
+            done: AtomicUsize::new(0),
+            last_draw: Mutex::new(None),
+        };
+        progress.draw(0);
+        progress
+    }
+
+    /// Count one more file done
+    pub fn inc(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        if !is_enabled() {
+            return;
+        }
+        // Skip the redraw when another thread is drawing or drew just now
+        let Ok(mut last_draw) = self.last_draw.try_lock() else {
+            return;
+        };
+        if last_draw.is_some_and(|at| at.elapsed() < REDRAW_INTERVAL) {
+            return;
+        }
+        *last_draw = Some(Instant::now());
+        drop(last_draw);
+        self.draw(done);
+    }
+
+    fn draw(&self, done: usize) {
+        if !is_enabled() {
+            return;
+        }
+        let line = render(self.stage, done, self.total);
+        let mut stderr = std::io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[2K{line}");
+        let _ = stderr.flush();
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if is_enabled() {
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+        }
+    }
+}
+
+/// The text of a progress line, e.g. `Scanning files [=======>      ] 1200/4800`
+fn render(stage: &str, done: usize, total: Option<usize>) -> String {
+    match total {
+        Some(total) if total > 0 => {
+            let done = done.min(total);
+            let filled = done * BAR_WIDTH / total;
+            let head = if filled < BAR_WIDTH { ">" } else { "" };
+            format!(
+                "{stage} [{}{head}{}] {done}/{total}",
+                "=".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled - head.len())
+            )
+        }
+        _ => format!("{stage}: {done} files"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        assert_eq!(
+            render("Scanning files", 0, Some(10)),
+            format!("Scanning files [>{}] 0/10", " ".repeat(29))
+        );
+        assert_eq!(
+            render("Scanning files", 5, Some(10)),
+            format!(
+                "Scanning files [{}>{}] 5/10",
+                "=".repeat(15),
+                " ".repeat(14)
+            )
+        );
+        assert_eq!(
+            render("Scanning files", 10, Some(10)),
+            format!("Scanning files [{}] 10/10", "=".repeat(30))
+        );
+        assert_eq!(render("Listing files", 42, None), "Listing files: 42 files");
+    }
+}
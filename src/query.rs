@@ -3,11 +3,16 @@ use ast_grep_core::AstGrep;
 use ast_grep_language::SupportLang;
 use colored::*;
 use ignore::WalkBuilder;
+use probe_code::extract::process_file_for_extraction;
+use probe_code::messages::{text, text_with, Msg};
 use probe_code::path_resolver::resolve_path;
+use probe_code::search::file_list_cache::{apply_walk_options, walk_options, PROBEIGNORE_FILENAME};
 use rayon::prelude::*; // Added import
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
+use tracing::debug;
 
 /// Represents a match found by ast-grep
 pub struct AstMatch {
@@ -17,6 +22,9 @@ pub struct AstMatch {
     pub column_start: usize,
     pub column_end: usize,
     pub matched_text: String,
+    /// `match` for the matched node itself, or the node type of the enclosing block
+    /// with `full_block`
+    pub node_type: String,
 }
 
 /// Options for the ast-grep query
@@ -30,6 +38,9 @@ pub struct QueryOptions<'a> {
     #[allow(dead_code)]
     pub format: &'a str,
     pub no_gitignore: bool,
+    /// Return the block enclosing each match, as `probe extract` would, instead of the
+    /// matched node; matches in the same block are merged
+    pub full_block: bool,
 }
 
 /// Convert a language string to the corresponding SupportLang
@@ -209,6 +220,7 @@ fn query_file(file_path: &Path, options: &QueryOptions) -> Result<Vec<AstMatch>>
             column_start,
             column_end,
             matched_text: node.text().to_string(),
+            node_type: "match".to_string(),
         });
     }
 
@@ -234,9 +246,9 @@ pub fn perform_query(options: &QueryOptions) -> Result<Vec<AstMatch>> {
     let resolved_path = if let Some(path_str) = options.path.to_str() {
         match resolve_path(path_str) {
             Ok(resolved_path) => {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    println!(
-                        "DEBUG: Resolved path '{}' to '{}'",
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!(
+                        "Resolved path '{}' to '{}'",
                         path_str,
                         resolved_path.display()
                     );
@@ -244,8 +256,8 @@ pub fn perform_query(options: &QueryOptions) -> Result<Vec<AstMatch>> {
                 resolved_path
             }
             Err(err) => {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    println!("DEBUG: Failed to resolve path '{path_str}': {err}");
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!("Failed to resolve path '{path_str}': {err}");
                 }
                 // Fall back to the original path
                 options.path.to_path_buf()
@@ -262,6 +274,9 @@ pub fn perform_query(options: &QueryOptions) -> Result<Vec<AstMatch>> {
     // Follow symlinks by default. Loop detection is handled by walkdir internally -
     // it detects and reports symlink loops as errors, preventing infinite traversal.
     builder.follow_links(true);
+    builder.add_custom_ignore_filename(PROBEIGNORE_FILENAME);
+    apply_walk_options(&mut builder);
+    builder.max_filesize(walk_options().max_filesize);
 
     // Configure gitignore handling based on the no_gitignore option
     if !options.no_gitignore {
@@ -303,8 +318,13 @@ pub fn perform_query(options: &QueryOptions) -> Result<Vec<AstMatch>> {
         std::panic::set_hook(hook);
     }
 
+    let mut all_matches = if options.full_block {
+        expand_to_blocks(all_matches)
+    } else {
+        all_matches
+    };
+
     // Apply max_results limit
-    let mut all_matches = all_matches;
     if let Some(max) = options.max_results {
         all_matches.truncate(max);
     }
@@ -312,6 +332,46 @@ pub fn perform_query(options: &QueryOptions) -> Result<Vec<AstMatch>> {
     Ok(all_matches)
 }
 
+/// Replace each match with the block enclosing its lines, found by extract's processor,
+/// keeping one result per block
+fn expand_to_blocks(matches: Vec<AstMatch>) -> Vec<AstMatch> {
+    let mut seen = HashSet::new();
+    let mut blocks = Vec::new();
+    for m in matches {
+        // Test files were already filtered by the walk, so any block found is wanted
+        let block = match process_file_for_extraction(
+            &m.file_path,
+            Some(m.line_start),
+            Some(m.line_end),
+            None,
+            true,
+            0,
+            None,
+            false,
+        ) {
+            Ok(block) => block,
+            Err(e) => {
+                debug!("No block for match in {}: {e}", m.file_path.display());
+                blocks.push(m);
+                continue;
+            }
+        };
+        if !seen.insert((m.file_path.clone(), block.lines)) {
+            continue;
+        }
+        blocks.push(AstMatch {
+            line_start: block.lines.0,
+            line_end: block.lines.1,
+            column_start: 1,
+            column_end: block.code.lines().last().map_or(0, |l| l.chars().count()) + 1,
+            matched_text: block.code.into(),
+            node_type: block.node_type,
+            file_path: m.file_path,
+        });
+    }
+    blocks
+}
+
 /// Helper function to escape XML special characters
 fn escape_xml(s: &str) -> String {
     s.replace("&", "&amp;")
@@ -321,6 +381,43 @@ fn escape_xml(s: &str) -> String {
         .replace("'", "&apos;")
 }
 
+/// Format query results as the JSON document printed by `--format json`
+pub fn format_query_results_json(matches: &[AstMatch]) -> Result<String> {
+    // BATCH TOKENIZATION WITH DEDUPLICATION OPTIMIZATION for query JSON output:
+    // Process all matched text in batch to leverage content deduplication
+    use probe_code::search::search_tokens::sum_tokens_with_deduplication;
+    let matched_texts: Vec<&str> = matches.iter().map(|m| m.matched_text.as_str()).collect();
+    let total_tokens = sum_tokens_with_deduplication(&matched_texts);
+
+    // Create standardized results
+    let json_matches_standardized: Vec<_> = matches
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "file": m.file_path.to_string_lossy(),
+                "lines": [m.line_start, m.line_end],
+                "node_type": m.node_type,
+                "content": m.matched_text,
+                "column_start": m.column_start,
+                "column_end": m.column_end
+            })
+        })
+        .collect();
+
+    // Create the wrapper object
+    let wrapper = serde_json::json!({
+        "results": json_matches_standardized,
+        "summary": {
+            "count": matches.len(),
+            "total_bytes": matches.iter().map(|m| m.matched_text.len()).sum::<usize>(),
+            "total_tokens": total_tokens
+        },
+        "version": probe_code::version::get_version()
+    });
+
+    Ok(serde_json::to_string_pretty(&wrapper)?)
+}
+
 /// Format and print the query results
 pub fn format_and_print_query_results(matches: &[AstMatch], format: &str) -> Result<()> {
     match format {
@@ -375,40 +472,7 @@ pub fn format_and_print_query_results(matches: &[AstMatch], format: &str) -> Res
             }
         }
         "json" => {
-            // BATCH TOKENIZATION WITH DEDUPLICATION OPTIMIZATION for query JSON output:
-            // Process all matched text in batch to leverage content deduplication
-            use probe_code::search::search_tokens::sum_tokens_with_deduplication;
-            let matched_texts: Vec<&str> =
-                matches.iter().map(|m| m.matched_text.as_str()).collect();
-            let total_tokens = sum_tokens_with_deduplication(&matched_texts);
-
-            // Create standardized results
-            let json_matches_standardized: Vec<_> = matches
-                .iter()
-                .map(|m| {
-                    serde_json::json!({
-                        "file": m.file_path.to_string_lossy(),
-                        "lines": [m.line_start, m.line_end],
-                        "node_type": "match",
-                        "content": m.matched_text,
-                        "column_start": m.column_start,
-                        "column_end": m.column_end
-                    })
-                })
-                .collect();
-
-            // Create the wrapper object
-            let wrapper = serde_json::json!({
-                "results": json_matches_standardized,
-                "summary": {
-                    "count": matches.len(),
-                    "total_bytes": matches.iter().map(|m| m.matched_text.len()).sum::<usize>(),
-                    "total_tokens": total_tokens
-                },
-                "version": probe_code::version::get_version()
-            });
-
-            println!("{}", serde_json::to_string_pretty(&wrapper)?);
+            println!("{}", format_query_results_json(matches)?);
         }
         "xml" => {
             println!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
@@ -421,7 +485,7 @@ pub fn format_and_print_query_results(matches: &[AstMatch], format: &str) -> Res
                     escape_xml(&m.file_path.to_string_lossy())
                 );
                 println!("    <lines>{}-{}</lines>", m.line_start, m.line_end);
-                println!("    <node_type>match</node_type>");
+                println!("    <node_type>{}</node_type>", escape_xml(&m.node_type));
                 println!("    <column_start>{}</column_start>", m.column_start);
                 println!("    <column_end>{}</column_end>", m.column_end);
                 println!("    <code><![CDATA[{}]]></code>", m.matched_text.trim());
@@ -473,7 +537,10 @@ pub fn handle_query(
     max_results: Option<usize>,
     format: &str,
     no_gitignore: bool,
+    full_block: bool,
 ) -> Result<()> {
+    probe_code::warnings::set_json(format == "json");
+
     // Print version at the start for text-based formats
     if format != "json" && format != "xml" {
         println!("Probe version: {}", probe_code::version::get_version());
@@ -502,6 +569,9 @@ pub fn handle_query(
         if let Some(max) = max_results {
             advanced_options.push(format!("Max results: {max}"));
         }
+        if full_block {
+            advanced_options.push("Full blocks".to_string());
+        }
 
         if !advanced_options.is_empty() {
             println!(
@@ -523,6 +593,7 @@ pub fn handle_query(
         max_results,
         format,
         no_gitignore,
+        full_block,
     };
 
     let matches = perform_query(&options)?;
@@ -536,13 +607,28 @@ pub fn handle_query(
             format_and_print_query_results(&matches, format)?;
         } else {
             // For other formats, print the "No results found" message
-            println!("{}", "No results found.".yellow().bold());
-            println!("Search completed in {duration:.2?}");
+            println!("{}", text(Msg::NoResults).yellow().bold());
+            println!(
+                "{}",
+                text_with(
+                    Msg::SearchCompleted,
+                    &[("time", &format!("{duration:.2?}"))]
+                )
+            );
         }
     } else {
         // For non-JSON/XML formats, print search time
         if format != "json" && format != "xml" {
-            println!("Found {} matches in {:.2?}", matches.len(), duration);
+            println!(
+                "{}",
+                text_with(
+                    Msg::FoundMatches,
+                    &[
+                        ("count", &matches.len()),
+                        ("time", &format!("{duration:.2?}"))
+                    ]
+                )
+            );
             println!();
         }
 
@@ -561,8 +647,11 @@ pub fn handle_query(
                 })
                 .sum();
 
-            println!("Total bytes returned: {total_bytes}");
-            println!("Total tokens returned: {total_tokens}");
+            println!("{}", text_with(Msg::TotalBytes, &[("bytes", &total_bytes)]));
+            println!(
+                "{}",
+                text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+            );
         }
     }
 
@@ -9,6 +9,15 @@ pub fn validate_strict_elastic_syntax(query: &str) -> Result<()> {
         bail!("Query cannot be empty");
     }
 
+    if let Some(problem) = find_dangling_not(query) {
+        bail!(
+            "{problem}. NOT excludes the term or group that follows it:\n\
+             - (parser AND NOT test)\n\
+             - (parser AND NOT (test OR mock))\n\
+             - parser -test"
+        );
+    }
+
     // Check for vague query patterns (multiple words without operators)
     if has_vague_query_format(query) {
         bail!(
@@ -53,9 +62,11 @@ fn has_vague_query_format(query: &str) -> bool {
         return false;
     }
 
-    // Check if it has proper ES operators (AND, OR, NOT)
-    let has_operators =
-        query.contains(" AND ") || query.contains(" OR ") || query.contains(" NOT ");
+    // Check if it has proper ES operators (AND, OR, NOT, NEAR/n, or a +/- prefixed term)
+    let has_operators = query.split_whitespace().any(|word| {
+        let word = word.trim_start_matches('(');
+        is_operator(word) || word.starts_with('+') || word.starts_with('-')
+    });
 
     // Check if entire query is quoted (which is valid)
     let is_fully_quoted = query.starts_with('"') && query.ends_with('"');
@@ -64,6 +75,30 @@ fn has_vague_query_format(query: &str) -> bool {
     has_multiple_words && !has_operators && !is_fully_quoted
 }
 
+/// Describes a NOT with nothing to negate: at the end of the query or a group, or
+/// followed by AND/OR
+fn find_dangling_not(query: &str) -> Option<&'static str> {
+    let words: Vec<&str> = query
+        .split_whitespace()
+        .map(|word| word.trim_start_matches('('))
+        .collect();
+    for (i, word) in words.iter().enumerate() {
+        let negated = word.trim_end_matches(')');
+        if negated != "NOT" {
+            continue;
+        }
+        if negated != *word {
+            return Some("NOT is followed by a closing parenthesis");
+        }
+        match words.get(i + 1) {
+            None => return Some("NOT is at the end of the query"),
+            Some(&"AND" | &"OR") => return Some("NOT is followed by an operator"),
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Finds terms with special characters that should be quoted
 fn find_unquoted_special_terms(query: &str) -> Option<String> {
     // Parse the query to extract individual terms
@@ -118,9 +153,11 @@ fn find_unquoted_special_terms(query: &str) -> Option<String> {
     None
 }
 
-/// Checks if a string is an ES operator
+/// Checks if a string is an ES operator, including `NEAR/n`
 fn is_operator(s: &str) -> bool {
     matches!(s, "AND" | "OR" | "NOT")
+        || s.strip_prefix("NEAR/")
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
 }
 
 /// Checks if a term has special characters (underscore, mixed case)
@@ -194,6 +231,28 @@ mod tests {
         assert!(validate_strict_elastic_syntax("error warning").is_err()); // no operator
     }
 
+    #[test]
+    fn test_not_and_minus_exclusions() {
+        assert!(validate_strict_elastic_syntax("parser AND NOT test").is_ok());
+        assert!(validate_strict_elastic_syntax("NOT test").is_ok());
+        assert!(validate_strict_elastic_syntax("parser AND NOT (test OR mock)").is_ok());
+        assert!(validate_strict_elastic_syntax("parser -test").is_ok());
+
+        assert!(validate_strict_elastic_syntax("parser AND NOT").is_err());
+        assert!(validate_strict_elastic_syntax("(parser AND NOT)").is_err());
+        assert!(validate_strict_elastic_syntax("parser NOT AND test").is_err());
+        // Excluded terms still need quotes around special characters
+        assert!(validate_strict_elastic_syntax("parser -test_helper").is_err());
+        assert!(validate_strict_elastic_syntax("parser -\"test_helper\"").is_ok());
+    }
+
+    #[test]
+    fn test_near_operator() {
+        assert!(validate_strict_elastic_syntax("config NEAR/5 reload").is_ok());
+        assert!(validate_strict_elastic_syntax("(config NEAR/5 reload) AND NOT test").is_ok());
+        assert!(validate_strict_elastic_syntax("config NEAR reload").is_err()); // no operator
+    }
+
     #[test]
     fn test_single_character_terms() {
         // Single uppercase letters should be allowed (not treated as camelCase)
@@ -1,10 +1,12 @@
 use crate::simd_ranking::{SimdBm25Params, SparseDocumentMatrix};
 use ahash::{AHashMap, AHashSet};
 use probe_code::search::elastic_query::Expr;
+use probe_code::search::stemming::{selected_stemmer, ProbeStemmer};
 use probe_code::search::tokenization;
+use probe_code::warnings::{warn, WarningKind};
 use rayon::prelude::*;
-use rust_stemmers::{Algorithm, Stemmer};
 use std::sync::OnceLock;
+use tracing::debug;
 
 // Replace standard collections with ahash versions for better performance
 type HashMap<K, V> = AHashMap<K, V>;
@@ -33,10 +35,10 @@ pub struct RankingParams<'a> {
     pub pre_tokenized: Option<&'a [Vec<String>]>,
 }
 
-/// Returns a reference to the global stemmer instance
-pub fn get_stemmer() -> &'static Stemmer {
-    static STEMMER: OnceLock<Stemmer> = OnceLock::new();
-    STEMMER.get_or_init(|| Stemmer::create(Algorithm::English))
+/// Returns a reference to the global stemmer instance (see `search::stemming` for selection)
+pub fn get_stemmer() -> &'static ProbeStemmer {
+    static STEMMER: OnceLock<ProbeStemmer> = OnceLock::new();
+    STEMMER.get_or_init(|| ProbeStemmer::new(selected_stemmer()))
 }
 
 /// Tokenizes text into lowercase words by splitting on whitespace and non-alphanumeric characters,
@@ -102,7 +104,7 @@ pub fn extract_query_terms(expr: &Expr) -> HashSet<String> {
         Term { keywords, .. } => {
             terms.extend(keywords.iter().cloned());
         }
-        And(left, right) | Or(left, right) => {
+        And(left, right) | Or(left, right) | Near(left, right, _) => {
             terms.extend(extract_query_terms(left));
             terms.extend(extract_query_terms(right));
         }
@@ -117,11 +119,11 @@ pub fn precompute_idfs(
     dfs: &HashMap<String, usize>,
     n_docs: usize,
 ) -> HashMap<String, f64> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!(
-            "DEBUG: Precomputing IDF values for {terms_len} terms",
+        debug!(
+            "Precomputing IDF values for {terms_len} terms",
             terms_len = terms.len()
         );
     }
@@ -255,7 +257,7 @@ pub fn score_expr_bm25_optimized(expr: &Expr, params: &PrecomputedBm25Params) ->
                 Some(score)
             }
         }
-        And(left, right) => {
+        And(left, right) | Near(left, right, _) => {
             let lscore = score_expr_bm25_optimized(left, params)?;
             let rscore = score_expr_bm25_optimized(right, params)?;
             Some(lscore + rscore)
@@ -279,7 +281,7 @@ pub fn score_expr_bm25_optimized(expr: &Expr, params: &PrecomputedBm25Params) ->
 pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
     use std::cmp::Ordering;
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // 1) Parse the user query into an AST (Expr)
     //    If your code uses parse_query(...) from `elastic_query.rs`, do:
@@ -287,11 +289,15 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
         Ok(expr) => expr,
         Err(e) => {
             if debug_mode {
-                eprintln!("DEBUG: parse_query failed: {e:?}");
+                debug!("parse_query failed: {e:?}");
             }
             // Instead of silently returning empty results, log a warning even in non-debug mode
             // to ensure errors are visible and can be addressed
-            eprintln!("WARNING: Query parsing failed: {e:?}. Returning empty results.");
+            warn(
+                WarningKind::InvalidQuery,
+                None,
+                format!("Query parsing failed: {e:?}. Returning empty results."),
+            );
             // In a future version, consider changing the return type to Result<Vec<(usize, f64)>, QueryError>
             // to properly propagate errors to the caller
             return vec![];
@@ -306,16 +312,16 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
         Ok(map) => map,
         Err(e) => {
             if debug_mode {
-                eprintln!("DEBUG: Failed to generate query token map: {e}");
+                debug!("Failed to generate query token map: {e}");
             }
-            eprintln!("WARNING: {e}");
+            warn(WarningKind::InvalidQuery, None, e.to_string());
             return vec![];
         }
     };
 
     if debug_mode {
-        println!(
-            "DEBUG: Generated query token map with {} entries",
+        debug!(
+            "Generated query token map with {} entries",
             query_token_map.len()
         );
     }
@@ -324,17 +330,20 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
     let tf_df_result = if let Some(pre_tokenized) = &params.pre_tokenized {
         // Use pre-tokenized content if available
         if debug_mode {
-            println!("DEBUG: Using pre-tokenized content for ranking");
+            debug!("Using pre-tokenized content for ranking");
         }
         compute_tf_df_from_tokenized(pre_tokenized, &query_token_map)
     } else {
         // Fallback to tokenizing the documents
         if debug_mode {
-            println!("DEBUG: Tokenizing documents for ranking");
+            debug!("Tokenizing documents for ranking");
         }
         // Tokenize documents on the fly
-        let tokenized_docs: Vec<Vec<String>> =
-            params.documents.iter().map(|doc| tokenize(doc)).collect();
+        let tokenized_docs: Vec<Vec<String>> = params
+            .documents
+            .par_iter()
+            .map(|doc| tokenize(doc))
+            .collect();
         compute_tf_df_from_tokenized(&tokenized_docs, &query_token_map)
     };
 
@@ -347,8 +356,8 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
         precompute_idfs(&query_terms, &tf_df_result.document_frequencies, n_docs);
 
     if debug_mode {
-        println!(
-            "DEBUG: Precomputed IDF values for {} unique query terms",
+        debug!(
+            "Precomputed IDF values for {} unique query terms",
             precomputed_idfs.len()
         );
     }
@@ -362,7 +371,7 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
     let b = 0.5; // EXPERIMENT: Moderately reduced from 0.75 for balanced length normalization
 
     if debug_mode {
-        println!("DEBUG: Starting parallel document scoring for {n_docs} documents");
+        debug!("Starting parallel document scoring for {n_docs} documents");
     }
 
     // 5) Compute BM25 bool logic score for each doc in parallel
@@ -393,7 +402,7 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
         .collect();
 
     if debug_mode {
-        println!("DEBUG: Parallel document scoring completed");
+        debug!("Parallel document scoring completed");
     }
 
     // Filter out documents that didn't match and collect scores
@@ -403,7 +412,7 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
         .collect();
 
     // 6) Sort in descending order by BM25 score, with a stable secondary sort by document index
-    filtered_docs.sort_by(|a, b| {
+    filtered_docs.par_sort_by(|a, b| {
         // First compare by score (descending)
         // Note: unwrap_or(Ordering::Equal) handles NaN cases by treating them as equal
         // This ensures stable sorting even if a score calculation resulted in NaN
@@ -418,10 +427,7 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
     });
 
     if debug_mode {
-        println!(
-            "DEBUG: Sorted {} matching documents by score",
-            filtered_docs.len()
-        );
+        debug!("Sorted {} matching documents by score", filtered_docs.len());
     }
 
     filtered_docs
@@ -432,10 +438,10 @@ pub fn rank_documents(params: &RankingParams) -> Vec<(usize, f64)> {
 pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
     use std::cmp::Ordering;
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!("DEBUG: Using SIMD-optimized ranking");
+        debug!("Using SIMD-optimized ranking");
     }
 
     // 1) Parse the user query into an AST (Expr)
@@ -443,9 +449,13 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
         Ok(expr) => expr,
         Err(e) => {
             if debug_mode {
-                eprintln!("DEBUG: parse_query failed: {e:?}");
+                debug!("parse_query failed: {e:?}");
             }
-            eprintln!("WARNING: Query parsing failed: {e:?}. Returning empty results.");
+            warn(
+                WarningKind::InvalidQuery,
+                None,
+                format!("Query parsing failed: {e:?}. Returning empty results."),
+            );
             return vec![];
         }
     };
@@ -458,16 +468,16 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
         Ok(map) => map,
         Err(e) => {
             if debug_mode {
-                eprintln!("DEBUG: Failed to generate query token map: {e}");
+                debug!("Failed to generate query token map: {e}");
             }
-            eprintln!("WARNING: {e}");
+            warn(WarningKind::InvalidQuery, None, e.to_string());
             return vec![];
         }
     };
 
     if debug_mode {
-        println!(
-            "DEBUG: Generated query token map with {} entries for SIMD processing",
+        debug!(
+            "Generated query token map with {} entries for SIMD processing",
             query_token_map.len()
         );
     }
@@ -475,15 +485,18 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
     // 3) Precompute TF/DF for docs
     let tf_df_result = if let Some(pre_tokenized) = &params.pre_tokenized {
         if debug_mode {
-            println!("DEBUG: Using pre-tokenized content for SIMD ranking");
+            debug!("Using pre-tokenized content for SIMD ranking");
         }
         compute_tf_df_from_tokenized(pre_tokenized, &query_token_map)
     } else {
         if debug_mode {
-            println!("DEBUG: Tokenizing documents for SIMD ranking");
+            debug!("Tokenizing documents for SIMD ranking");
         }
-        let tokenized_docs: Vec<Vec<String>> =
-            params.documents.iter().map(|doc| tokenize(doc)).collect();
+        let tokenized_docs: Vec<Vec<String>> = params
+            .documents
+            .par_iter()
+            .map(|doc| tokenize(doc))
+            .collect();
         compute_tf_df_from_tokenized(&tokenized_docs, &query_token_map)
     };
 
@@ -495,8 +508,8 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
         precompute_idfs(&query_terms, &tf_df_result.document_frequencies, n_docs);
 
     if debug_mode {
-        println!(
-            "DEBUG: Precomputed IDF values for {} unique query terms for SIMD processing",
+        debug!(
+            "Precomputed IDF values for {} unique query terms for SIMD processing",
             precomputed_idfs.len()
         );
     }
@@ -512,8 +525,8 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
     );
 
     if debug_mode {
-        println!(
-            "DEBUG: Created sparse matrix with {} documents for SIMD operations",
+        debug!(
+            "Created sparse matrix with {} documents for SIMD operations",
             sparse_matrix.documents.len()
         );
     }
@@ -523,19 +536,19 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
 
     // 7) Compute BM25 scores using SIMD operations
     if debug_mode {
-        println!("DEBUG: Starting SIMD-accelerated BM25 scoring for {n_docs} documents");
+        debug!("Starting SIMD-accelerated BM25 scoring for {n_docs} documents");
     }
 
     let scores = simd_params.score_all_documents();
 
     if debug_mode {
-        println!("DEBUG: SIMD BM25 scoring completed");
+        debug!("SIMD BM25 scoring completed");
     }
 
     // 8) Apply boolean query logic efficiently with SIMD scores
     // Only do traditional computation for documents that have SIMD scores > 0
     let scored_docs: Vec<(usize, f64)> = scores
-        .into_iter()
+        .into_par_iter()
         .enumerate()
         .filter_map(|(i, simd_score)| {
             if simd_score <= 0.0 {
@@ -563,12 +576,12 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
         .collect();
 
     if debug_mode {
-        println!("DEBUG: SIMD-filtered boolean query evaluation completed");
+        debug!("SIMD-filtered boolean query evaluation completed");
     }
 
     // 9) Sort results by SIMD score in descending order
     let mut filtered_docs = scored_docs;
-    filtered_docs.sort_by(
+    filtered_docs.par_sort_by(
         |a, b| match b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal) {
             Ordering::Equal => a.0.cmp(&b.0),
             other => other,
@@ -576,8 +589,8 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
     );
 
     if debug_mode {
-        println!(
-            "DEBUG: SIMD-optimized ranking completed with {} matching documents",
+        debug!(
+            "SIMD-optimized ranking completed with {} matching documents",
             filtered_docs.len()
         );
     }
@@ -590,10 +603,10 @@ pub fn rank_documents_simd(params: &RankingParams) -> Vec<(usize, f64)> {
 pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
     use std::cmp::Ordering;
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!("DEBUG: Using simple SIMD-only ranking (no boolean logic)");
+        debug!("Using simple SIMD-only ranking (no boolean logic)");
     }
 
     // 1) Simple tokenization of query (treat as individual terms)
@@ -609,7 +622,7 @@ pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
         Ok(map) => map,
         Err(e) => {
             if debug_mode {
-                eprintln!("DEBUG: Failed to generate query token map: {e}");
+                debug!("Failed to generate query token map: {e}");
             }
             return vec![];
         }
@@ -619,8 +632,11 @@ pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
     let tf_df_result = if let Some(pre_tokenized) = &params.pre_tokenized {
         compute_tf_df_from_tokenized(pre_tokenized, &query_token_map)
     } else {
-        let tokenized_docs: Vec<Vec<String>> =
-            params.documents.iter().map(|doc| tokenize(doc)).collect();
+        let tokenized_docs: Vec<Vec<String>> = params
+            .documents
+            .par_iter()
+            .map(|doc| tokenize(doc))
+            .collect();
         compute_tf_df_from_tokenized(&tokenized_docs, &query_token_map)
     };
 
@@ -646,15 +662,12 @@ pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
     let scores = simd_params.score_all_documents();
 
     if debug_mode {
-        println!(
-            "DEBUG: Pure SIMD scoring completed for {} documents",
-            scores.len()
-        );
+        debug!("Pure SIMD scoring completed for {} documents", scores.len());
     }
 
     // 6) Create results with scores > 0
     let mut filtered_docs: Vec<(usize, f64)> = scores
-        .into_iter()
+        .into_par_iter()
         .enumerate()
         .filter_map(|(i, score)| {
             if score > 0.0 {
@@ -666,7 +679,7 @@ pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
         .collect();
 
     // 7) Sort by score (descending)
-    filtered_docs.sort_by(
+    filtered_docs.par_sort_by(
         |a, b| match b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal) {
             Ordering::Equal => a.0.cmp(&b.0),
             other => other,
@@ -674,8 +687,8 @@ pub fn rank_documents_simd_simple(params: &RankingParams) -> Vec<(usize, f64)> {
     );
 
     if debug_mode {
-        println!(
-            "DEBUG: Simple SIMD ranking completed with {} matching documents",
+        debug!(
+            "Simple SIMD ranking completed with {} matching documents",
             filtered_docs.len()
         );
     }
@@ -691,10 +704,10 @@ pub fn compute_tf_df_from_tokenized(
     tokenized_docs: &[Vec<String>],
     query_token_map: &QueryTokenMap,
 ) -> TfDfResult {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!("DEBUG: Starting parallel TF-DF computation from pre-tokenized content for {docs_len} documents", docs_len = tokenized_docs.len());
+        debug!("Starting parallel TF-DF computation from pre-tokenized content for {docs_len} documents", docs_len = tokenized_docs.len());
     }
 
     // Process documents in parallel to compute term frequencies and document lengths
@@ -766,8 +779,8 @@ pub fn compute_tf_df_from_tokenized(
         });
 
     if debug_mode {
-        println!(
-            "DEBUG: Parallel DF computation completed with {} unique terms",
+        debug!(
+            "Parallel DF computation completed with {} unique terms",
             document_frequencies.len()
         );
     }
@@ -779,8 +792,8 @@ pub fn compute_tf_df_from_tokenized(
     }
 
     if debug_mode {
-        println!("DEBUG: Parallel TF-DF computation from pre-tokenized content completed");
-        println!("DEBUG: Using u8 indices for term frequencies (optimized storage)");
+        debug!("Parallel TF-DF computation from pre-tokenized content completed");
+        debug!("Using u8 indices for term frequencies (optimized storage)");
     }
 
     TfDfResult {
@@ -820,6 +833,36 @@ mod tests {
         assert!(results[0].1 < 10.0); // Upper bound based on typical BM25 behavior with small documents
     }
 
+    #[test]
+    fn test_ranking_is_the_same_on_any_number_of_threads() {
+        let docs: Vec<String> = (0..500)
+            .map(|i| match i % 4 {
+                0 => format!("fn parse_config_{i}() {{ load config }}"),
+                1 => format!("fn load_{i}() {{ parse }}"),
+                // Identical blocks tie, and must keep their index order
+                2 => "fn config() { parse config }".to_string(),
+                _ => format!("struct Unrelated{i};"),
+            })
+            .collect();
+        let docs: Vec<&str> = docs.iter().map(String::as_str).collect();
+        let params = RankingParams {
+            documents: &docs,
+            query: "parse config",
+            pre_tokenized: None,
+        };
+
+        let rank_with = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| (rank_documents(&params), rank_documents_simd(&params)))
+        };
+        let single = rank_with(1);
+        assert!(!single.0.is_empty());
+        assert_eq!(rank_with(4), single);
+    }
+
     #[test]
     fn test_bm25_scoring_with_pre_tokenized() {
         // A trivial test: 2 docs, 1 query, with pre-tokenized content
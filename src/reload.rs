@@ -0,0 +1,58 @@
+//! Reloading the long-running commands, `probe serve` and `probe index --watch`, without
+//! a restart: SIGHUP (or `POST /reload` to the server) asks them to re-read the config
+//! files and re-walk the tree, picking up edited ignore files.
+//!
+//! A reload request bumps a generation counter; each command compares it with the
+//! generation it last reloaded at when it next wakes up, between requests or events.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Ask the running command to reload
+pub fn request() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Tracks the reload requests a command has acted on
+#[derive(Default)]
+pub struct Reloads {
+    seen: AtomicU64,
+}
+
+impl Reloads {
+    /// Whether a reload was requested since the last call, marking it as handled
+    pub fn pending(&self) -> bool {
+        let current = GENERATION.load(Ordering::SeqCst);
+        self.seen.swap(current, Ordering::SeqCst) != current
+    }
+}
+
+/// Request a reload on every SIGHUP; only Unix has the signal
+pub fn install_sighup_handler() {
+    #[cfg(unix)]
+    tokio::spawn(async {
+        use tokio::signal::unix::{signal, SignalKind};
+        let Ok(mut hangups) = signal(SignalKind::hangup()) else {
+            return;
+        };
+        while hangups.recv().await.is_some() {
+            request();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_requests() {
+        let reloads = Reloads::default();
+        reloads.pending();
+        assert!(!reloads.pending());
+        request();
+        assert!(reloads.pending());
+        assert!(!reloads.pending());
+    }
+}
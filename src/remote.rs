@@ -0,0 +1,149 @@
+//! `--repo`: search a remote git repository without cloning it by hand.
+//!
+//! A repository is fetched shallowly, at one commit, into `~/.cache/probe/repos/`, one
+//! directory per URL and ref. A cached checkout is reused for an hour, then fetched again
+//! before the next search; if that fetch fails (offline, say) the cached one is searched
+//! with a warning.
+
+use anyhow::{bail, Context, Result};
+use probe_code::git::git;
+use probe_code::warnings::{self, WarningKind};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Age after which a cached checkout is fetched again
+const REFRESH_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Written after each successful fetch; its modification time dates the checkout
+const FETCHED_MARKER: &str = ".git/probe-fetched";
+
+/// The directory holding all checkouts
+fn repos_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("repos")
+}
+
+/// Split `url[@ref]` into the URL and the ref. The `@` of `git@host:org/repo` or of
+/// `https://user@host/...` starts a ref only when a path comes before it.
+fn parse_repo(spec: &str) -> (&str, Option<&str>) {
+    if let Some((url, reference)) = spec.rsplit_once('@') {
+        let address = url.split_once("://").map_or(url, |(_, rest)| rest);
+        if !reference.is_empty() && address.contains(['/', ':']) {
+            return (url, Some(reference));
+        }
+    }
+    (spec, None)
+}
+
+/// The paths to search in the checkout of `repo`: relative paths are taken inside it,
+/// so the current directory, `.`, is its root
+pub fn repo_paths(paths: Vec<PathBuf>, repo: Option<&str>) -> Result<Vec<PathBuf>> {
+    let Some(repo) = repo else {
+        return Ok(paths);
+    };
+    let checkout = checkout(repo)?;
+    paths
+        .into_iter()
+        .map(|path| {
+            if path.is_absolute() {
+                bail!("Paths searched with --repo must be relative to the repository");
+            }
+            Ok(if path.as_path() == Path::new(".") {
+                checkout.clone()
+            } else {
+                checkout.join(path)
+            })
+        })
+        .collect()
+}
+
+/// The local checkout of `url[@ref]`, fetching it when missing or stale
+pub fn checkout(spec: &str) -> Result<PathBuf> {
+    let (url, reference) = parse_repo(spec);
+    let reference = reference.unwrap_or("HEAD");
+    let name = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or("repo");
+    let key = md5::compute(format!("{url}@{reference}"));
+    let dir = repos_dir().join(format!("{name}-{key:x}"));
+
+    let fetched = fs::metadata(dir.join(FETCHED_MARKER))
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    match fetched {
+        Some(time) if time.elapsed().unwrap_or_default() < REFRESH_AFTER => {}
+        Some(_) => {
+            if let Err(e) = fetch(&dir, reference) {
+                warnings::warn(
+                    WarningKind::Other,
+                    None,
+                    format!("Failed to update {url}, searching the cached checkout: {e:#}"),
+                );
+            }
+        }
+        None => {
+            // Start over from a failed or interrupted clone
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            let cloned = git(&dir, &["init", "--quiet"])
+                .and_then(|_| git(&dir, &["remote", "add", "--", "origin", url]))
+                .and_then(|_| fetch(&dir, reference));
+            if let Err(e) = cloned {
+                let _ = fs::remove_dir_all(&dir);
+                return Err(e.context(format!("Failed to clone {url}")));
+            }
+        }
+    }
+    Ok(dir)
+}
+
+/// Fetch the one commit of `reference` and check it out. The `--` keeps a URL or ref
+/// starting with `-` from being read as an option, such as `--upload-pack=<command>`.
+fn fetch(dir: &Path, reference: &str) -> Result<()> {
+    git(
+        dir,
+        &[
+            "fetch", "--quiet", "--depth", "1", "--", "origin", reference,
+        ],
+    )?;
+    git(
+        dir,
+        &["checkout", "--quiet", "--force", "--detach", "FETCH_HEAD"],
+    )?;
+    fs::write(dir.join(FETCHED_MARKER), reference)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo() {
+        assert_eq!(
+            parse_repo("https://github.com/org/repo@v1.2.0"),
+            ("https://github.com/org/repo", Some("v1.2.0"))
+        );
+        assert_eq!(
+            parse_repo("https://github.com/org/repo"),
+            ("https://github.com/org/repo", None)
+        );
+        assert_eq!(
+            parse_repo("git@github.com:org/repo.git"),
+            ("git@github.com:org/repo.git", None)
+        );
+        assert_eq!(
+            parse_repo("git@github.com:org/repo@feature/x"),
+            ("git@github.com:org/repo", Some("feature/x"))
+        );
+        assert_eq!(
+            parse_repo("https://user@host.com/org/repo"),
+            ("https://user@host.com/org/repo", None)
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Searching inside archives for `--search-archives`.
+//!
+//! `.zip`, `.jar`, `.tar.gz` and `.tgz` files found by the walk are unpacked into a
+//! directory under `~/.cache/probe/archives/`, keyed by the archive's path, size and
+//! modification time so an unchanged archive is only unpacked once. Members with an
+//! absolute path or a `..` component are left out, so nothing is written outside that
+//! directory. The rest are then searched like any other file, and results in a member
+//! are reported as `archive.zip!path/inner.rs`.
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use probe_code::models::{LimitedSearchResults, SearchResult};
+use probe_code::search::file_list_cache::walk_options;
+
+/// Members larger than this are left out; the search skips such files anyway
+const MAX_MEMBER_SIZE: u64 = 1024 * 1024;
+
+/// Bytes of a member checked for a NUL byte to decide whether it is binary
+const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Unpacked archives of this process: (directory of the members, archive path as walked)
+static UNPACKED: RwLock<Vec<(PathBuf, String)>> = RwLock::new(Vec::new());
+
+/// The directory holding all unpacked archives
+fn archives_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("archives")
+}
+
+/// Whether `path` is an archive `--search-archives` descends into
+pub fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    [".zip", ".jar", ".tar.gz", ".tgz"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Unpack the text members of an archive and return their paths
+pub fn archive_members(archive: &Path) -> Result<Vec<PathBuf>> {
+    let metadata = fs::metadata(archive)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos());
+    let canonical = fs::canonicalize(archive)?;
+    let key = md5::compute(format!(
+        "{}:{}:{modified}",
+        canonical.display(),
+        metadata.len()
+    ));
+    let cache_dir = archives_dir();
+    let dir = cache_dir.join(format!("{key:x}"));
+    // The member list is written last, so an archive whose unpacking was cut short is
+    // unpacked again
+    let listing = cache_dir.join(format!("{key:x}.members"));
+
+    let members: Vec<PathBuf> = match fs::read_to_string(&listing) {
+        Ok(list) => list.lines().map(PathBuf::from).collect(),
+        Err(_) => {
+            let members = unpack(archive, &dir)
+                .with_context(|| format!("Failed to unpack {}", archive.display()))?;
+            let list: Vec<String> = members
+                .iter()
+                .map(|member| member.to_string_lossy().to_string())
+                .collect();
+            fs::create_dir_all(&cache_dir)?;
+            fs::write(&listing, list.join("\n"))?;
+            members
+        }
+    };
+
+    UNPACKED
+        .write()
+        .unwrap()
+        .push((dir.clone(), archive.to_string_lossy().to_string()));
+    Ok(members.iter().map(|member| dir.join(member)).collect())
+}
+
+/// The `archive!member` path of a file unpacked from an archive
+pub fn archive_path(file: &str) -> Option<String> {
+    let unpacked = UNPACKED.read().unwrap();
+    unpacked.iter().find_map(|(dir, archive)| {
+        let member = Path::new(file).strip_prefix(dir).ok()?;
+        let member: Vec<_> = member
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect();
+        Some(format!("{archive}!{}", member.join("/")))
+    })
+}
+
+/// The unpacked file of an `archive!member` path
+pub fn unpacked_path(path: &str) -> Option<PathBuf> {
+    let (archive_path, member) = path.rsplit_once('!')?;
+    let unpacked = UNPACKED.read().unwrap();
+    unpacked
+        .iter()
+        .find(|(_, archive)| archive == archive_path)
+        .map(|(dir, _)| dir.join(member))
+}
+
+/// Report results in unpacked archive members by their `archive!member` paths
+pub fn map_result_paths(limited: &mut LimitedSearchResults) {
+    for result in limited
+        .results
+        .iter_mut()
+        .chain(limited.skipped_files.iter_mut())
+    {
+        map_result_path(result);
+    }
+}
+
+/// Report a result in an unpacked archive member by its `archive!member` path
+pub fn map_result_path(result: &mut SearchResult) {
+    if UNPACKED.read().unwrap().is_empty() {
+        return;
+    }
+    if let Some(path) = archive_path(&result.file) {
+        result.file = path;
+    }
+}
+
+/// Write the members worth searching to `dir`, returning their relative paths
+fn unpack(archive: &Path, dir: &Path) -> Result<Vec<PathBuf>> {
+    let name = archive.to_string_lossy().to_lowercase();
+    let file = BufReader::new(File::open(archive)?);
+    let mut members = Vec::new();
+    let mut add = |member: &Path, size: u64, reader: &mut dyn Read| -> Result<()> {
+        let Some(member) = safe_member_path(member) else {
+            return Ok(());
+        };
+        let max_size = walk_options()
+            .max_filesize
+            .map_or(MAX_MEMBER_SIZE, |max| max.min(MAX_MEMBER_SIZE));
+        if size > max_size {
+            return Ok(());
+        }
+        let mut content = Vec::with_capacity(size as usize);
+        reader.take(size).read_to_end(&mut content)?;
+        let head = &content[..content.len().min(BINARY_SNIFF_BYTES)];
+        if head.contains(&0) && !walk_options().binary {
+            return Ok(());
+        }
+        let path = dir.join(&member);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        members.push(member);
+        Ok(())
+    };
+
+    if name.ends_with(".zip") || name.ends_with(".jar") {
+        let mut zip = zip::ZipArchive::new(file)?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let member = PathBuf::from(entry.name());
+            let size = entry.size();
+            add(&member, size, &mut entry)?;
+        }
+    } else {
+        read_tar(GzDecoder::new(file), &mut add)?;
+    }
+    Ok(members)
+}
+
+/// Read the regular files of a tar stream, calling `add` with each one's path, size and
+/// content. Supports ustar name prefixes and GNU long names.
+fn read_tar<R: Read>(
+    mut tar: R,
+    add: &mut dyn FnMut(&Path, u64, &mut dyn Read) -> Result<()>,
+) -> Result<()> {
+    let mut header = [0u8; 512];
+    let mut long_name: Option<String> = None;
+    loop {
+        if read_block(&mut tar, &mut header)?.is_none() || header.iter().all(|&b| b == 0) {
+            return Ok(());
+        }
+        let size = parse_octal(&header[124..136])?;
+        let padding = (512 - size % 512) % 512;
+        let name = match long_name.take() {
+            Some(name) => name,
+            None => {
+                let name = field(&header[0..100]);
+                let prefix = field(&header[345..500]);
+                if &header[257..262] == b"ustar" && !prefix.is_empty() {
+                    format!("{prefix}/{name}")
+                } else {
+                    name
+                }
+            }
+        };
+
+        let mut data = (&mut tar).take(size);
+        match header[156] {
+            b'0' | 0 => add(Path::new(&name), size, &mut data)?,
+            b'L' => {
+                let mut name = Vec::new();
+                data.read_to_end(&mut name)?;
+                long_name = Some(field(&name));
+            }
+            // Directories, links and extended headers
+            _ => {}
+        }
+        // Skip whatever of the entry wasn't read, and its padding
+        std::io::copy(&mut data, &mut std::io::sink())?;
+        std::io::copy(&mut (&mut tar).take(padding), &mut std::io::sink())?;
+    }
+}
+
+/// Fill `block`, or `None` at the end of the stream
+fn read_block(reader: &mut impl Read, block: &mut [u8; 512]) -> Result<Option<()>> {
+    let mut read = 0;
+    while read < block.len() {
+        match reader.read(&mut block[read..])? {
+            0 if read == 0 => return Ok(None),
+            0 => bail!("Truncated tar header"),
+            n => read += n,
+        }
+    }
+    Ok(Some(()))
+}
+
+/// A NUL-terminated string field of a tar header
+fn field(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).to_string()
+}
+
+fn parse_octal(bytes: &[u8]) -> Result<u64> {
+    let digits = field(bytes);
+    let digits = digits.trim();
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(digits, 8).with_context(|| format!("Invalid tar size '{digits}'"))
+}
+
+/// `member` as a relative path that stays inside the unpack directory
+fn safe_member_path(member: &Path) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    for part in member.components() {
+        match part {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!path.as_os_str().is_empty()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn tar_entry(tar: &mut Vec<u8>, name: &str, content: &[u8]) {
+        let mut header = [0u8; 512];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        header[156] = b'0';
+        header[257..262].copy_from_slice(b"ustar");
+        tar.extend_from_slice(&header);
+        tar.extend_from_slice(content);
+        tar.resize(tar.len().div_ceil(512) * 512, 0);
+    }
+
+    #[test]
+    fn test_tar_gz_members() {
+        let temp = TempDir::new().unwrap();
+        let mut tar = Vec::new();
+        tar_entry(&mut tar, "pkg/src/lib.rs", b"fn packed() {}\n");
+        tar_entry(&mut tar, "../escape.rs", b"fn escape() {}\n");
+        tar_entry(&mut tar, "pkg/logo.png", b"\x89PNG\0\0");
+        tar.extend_from_slice(&[0; 1024]);
+        let archive = temp.path().join("pkg.tar.gz");
+        let mut gz = flate2::write::GzEncoder::new(
+            File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        gz.write_all(&tar).unwrap();
+        gz.finish().unwrap();
+
+        let members = archive_members(&archive).unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(fs::read_to_string(&members[0]).unwrap(), "fn packed() {}\n");
+        assert_eq!(
+            archive_path(&members[0].to_string_lossy()),
+            Some(format!("{}!pkg/src/lib.rs", archive.display()))
+        );
+    }
+
+    #[test]
+    fn test_safe_member_path() {
+        assert_eq!(
+            safe_member_path(Path::new("./pkg/lib.rs")),
+            Some(PathBuf::from("pkg/lib.rs"))
+        );
+        assert_eq!(safe_member_path(Path::new("/etc/passwd")), None);
+        assert_eq!(safe_member_path(Path::new("pkg/../../escape.rs")), None);
+        assert_eq!(safe_member_path(Path::new("pkg/../lib.rs")), None);
+        assert_eq!(safe_member_path(Path::new(".")), None);
+    }
+
+    #[test]
+    fn test_is_archive() {
+        assert!(is_archive(Path::new("libs/guava.jar")));
+        assert!(is_archive(Path::new("release.TAR.GZ")));
+        assert!(!is_archive(Path::new("src/zip.rs")));
+    }
+}
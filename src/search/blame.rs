@@ -0,0 +1,211 @@
+//! `--blame`: the last commit to change each result block, from `git blame`.
+//!
+//! Each file is blamed once for the line ranges of all its results. A block's commit
+//! is the most recent one among its lines, so a block edited yesterday is attributed
+//! to yesterday's author even if most of its lines are older.
+
+use probe_code::models::{BlameInfo, SearchResult};
+use probe_code::warnings::{warn_once, WarningKind};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+/// The commit that last changed a line
+#[derive(Debug, Clone, PartialEq)]
+struct LineBlame {
+    commit: String,
+    author: String,
+    time: i64,
+    tz: String,
+}
+
+/// Set `blame` on every result in a git repository
+pub fn annotate_blame(results: &mut [SearchResult]) {
+    let mut by_file: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, result) in results.iter().enumerate() {
+        by_file.entry(result.file.clone()).or_default().push(index);
+    }
+
+    for (file, indices) in by_file {
+        let ranges: Vec<(usize, usize)> = indices
+            .iter()
+            .map(|&index| results[index].lines)
+            .filter(|(start, end)| *start > 0 && end >= start)
+            .collect();
+        let Some(lines) = blame_lines(Path::new(&file), &ranges) else {
+            warn_once(
+                WarningKind::Other,
+                &file,
+                format!("Can't blame {file}: not tracked by git"),
+            );
+            continue;
+        };
+        for index in indices {
+            let (start, end) = results[index].lines;
+            results[index].blame = lines
+                .range(start.max(1)..=end.max(start))
+                .map(|(_, line)| line)
+                .max_by_key(|line| line.time)
+                .map(|line| BlameInfo {
+                    author: line.author.clone(),
+                    commit: line.commit.clone(),
+                    date: format_date(line.time, &line.tz),
+                });
+        }
+    }
+}
+
+/// The last commit to change each of some lines of a file, `None` when the file isn't
+/// tracked by git. Lines that aren't committed yet are left out.
+pub fn line_blame(file: &Path, lines: &[usize]) -> Option<BTreeMap<usize, BlameInfo>> {
+    let ranges: Vec<(usize, usize)> = lines.iter().map(|&line| (line, line)).collect();
+    let blamed = blame_lines(file, &ranges)?;
+    Some(
+        blamed
+            .into_iter()
+            .filter(|(_, line)| line.commit.chars().any(|c| c != '0'))
+            .map(|(number, line)| {
+                let date = format_date(line.time, &line.tz);
+                (
+                    number,
+                    BlameInfo {
+                        author: line.author,
+                        commit: line.commit,
+                        date,
+                    },
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Blame `ranges` of a file (the whole file when empty), keyed by line number
+fn blame_lines(file: &Path, ranges: &[(usize, usize)]) -> Option<BTreeMap<usize, LineBlame>> {
+    let dir = file
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--line-porcelain"]);
+    for (start, end) in ranges {
+        command.arg(format!("-L{start},{end}"));
+    }
+    let output = command.arg("--").arg(file.file_name()?).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_line_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git blame --line-porcelain`, where every line comes with its full commit
+/// header
+fn parse_line_porcelain(output: &str) -> BTreeMap<usize, LineBlame> {
+    let mut lines = BTreeMap::new();
+    let mut current: Option<(usize, LineBlame)> = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            // The line's content ends its header
+            if let Some((number, blame)) = current.take() {
+                lines.insert(number, blame);
+            }
+        } else if let Some((_, blame)) = current.as_mut() {
+            if let Some(author) = line.strip_prefix("author ") {
+                blame.author = author.to_string();
+            } else if let Some(time) = line.strip_prefix("author-time ") {
+                blame.time = time.parse().unwrap_or_default();
+            } else if let Some(tz) = line.strip_prefix("author-tz ") {
+                blame.tz = tz.to_string();
+            }
+        } else {
+            // `<commit> <original line> <final line> [<lines in group>]`
+            let mut fields = line.split(' ');
+            let commit = fields.next().unwrap_or_default();
+            let number = fields.nth(1).and_then(|number| number.parse().ok());
+            if let Some(number) = number.filter(|_| commit.len() >= 40) {
+                current = Some((
+                    number,
+                    LineBlame {
+                        commit: commit.to_string(),
+                        author: String::new(),
+                        time: 0,
+                        tz: String::new(),
+                    },
+                ));
+            }
+        }
+    }
+    lines
+}
+
+/// `YYYY-MM-DD` of a unix time in a `+hhmm` time zone
+fn format_date(time: i64, tz: &str) -> String {
+    let sign = if tz.starts_with('-') { -1 } else { 1 };
+    let digits = tz.trim_start_matches(['+', '-']);
+    let part = |range: std::ops::Range<usize>| {
+        digits
+            .get(range)
+            .and_then(|part| part.parse::<i64>().ok())
+            .unwrap_or(0)
+    };
+    let offset = sign * (part(0..2) * 3600 + part(2..4) * 60);
+    let (year, month, day) = civil_from_days((time + offset).div_euclid(86_400));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Calendar date of a number of days since 1970-01-01 (proleptic Gregorian)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let hash_a = "a".repeat(40);
+        let hash_b = "b".repeat(40);
+        let output = format!(
+            "{hash_a} 1 1 2\nauthor Ada\nauthor-mail <ada@example.com>\nauthor-time 1700000000\nauthor-tz +0100\nsummary First\nfilename a.rs\n\tfn a() {{\n\
+             {hash_a} 2 2\nauthor Ada\nauthor-time 1700000000\nauthor-tz +0100\nfilename a.rs\n\t    old();\n\
+             {hash_b} 3 3 1\nauthor Grace\nauthor-time 1710000000\nauthor-tz -0500\nfilename a.rs\n\t}}\n"
+        );
+        let lines = parse_line_porcelain(&output);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[&2].author, "Ada");
+        assert_eq!(lines[&3].commit, hash_b);
+        assert_eq!(lines[&3].tz, "-0500");
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0, "+0000"), "1970-01-01");
+        assert_eq!(format_date(1_700_000_000, "+0000"), "2023-11-14");
+        // 22:13 UTC is already the next day in UTC+2
+        assert_eq!(format_date(1_700_000_000, "+0200"), "2023-11-15");
+        assert_eq!(format_date(951_782_400, "+0000"), "2000-02-29");
+    }
+
+    #[test]
+    fn test_blame_outside_git_is_none() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("a.rs");
+        std::fs::write(&file, "fn a() {}\n").unwrap();
+        assert_eq!(blame_lines(&file, &[(1, 1)]), None);
+    }
+}
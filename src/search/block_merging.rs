@@ -1,8 +1,10 @@
-use probe_code::models::SearchResult;
+use probe_code::models::{BlockCode, SearchResult};
+use rayon::prelude::*;
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use tracing::debug;
 
 /// Merges ranked search results that are adjacent or overlapping
 ///
@@ -19,7 +21,7 @@ pub fn merge_ranked_blocks(
     results: Vec<SearchResult>,
     threshold: Option<usize>,
 ) -> Vec<SearchResult> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let threshold = threshold.unwrap_or(5); // Default to 5 lines if not specified
 
     if results.is_empty() {
@@ -27,8 +29,8 @@ pub fn merge_ranked_blocks(
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: Starting post-rank merging of {} results with threshold {}",
+        debug!(
+            "Starting post-rank merging of {} results with threshold {}",
             results.len(),
             threshold
         );
@@ -47,133 +49,149 @@ pub fn merge_ranked_blocks(
             .push(result);
     }
 
-    let mut merged_results = Vec::new();
+    // Files are merged independently, so they are spread over threads; collecting the
+    // per-file results in file order keeps the output the same on any number of threads
+    let file_blocks: Vec<(String, Vec<SearchResult>)> = file_blocks.into_iter().collect();
+    let merged_results: Vec<SearchResult> = file_blocks
+        .into_par_iter()
+        .flat_map_iter(|(file_path, blocks)| {
+            merge_file_blocks(&file_path, blocks, threshold, debug_mode)
+        })
+        .collect();
 
-    // Process each file's blocks
-    for (file_path, mut blocks) in file_blocks {
-        if debug_mode {
-            println!(
-                "DEBUG: Processing {} blocks from file: {}",
-                blocks.len(),
-                file_path
-            );
-        }
+    if debug_mode {
+        debug!(
+            "Post-rank merging complete. Merged {} blocks into {} blocks",
+            original_count,
+            merged_results.len()
+        );
+    }
 
-        // If file only has one block, no need to merge
-        if blocks.len() == 1 {
-            merged_results.push(blocks.remove(0));
-            continue;
-        }
+    merged_results
+}
 
-        // Sort blocks by start line for merging
-        blocks.sort_by_key(|block| block.lines.0);
+/// Merge the blocks of one file that `should_merge_blocks` allows, in start line order
+fn merge_file_blocks(
+    file_path: &str,
+    mut blocks: Vec<SearchResult>,
+    threshold: usize,
+    debug_mode: bool,
+) -> Vec<SearchResult> {
+    if debug_mode {
+        debug!(
+            "Processing {} blocks from file: {}",
+            blocks.len(),
+            file_path
+        );
+    }
 
-        // Sort blocks by start line
-        blocks.sort_by_key(|block| block.lines.0);
+    // If file only has one block, no need to merge
+    if blocks.len() == 1 {
+        return blocks;
+    }
 
-        // Keep track of blocks we've already processed
-        let mut processed_indices = std::collections::HashSet::new();
-        let mut merged_blocks = Vec::new();
+    // Sort blocks by start line for merging
+    blocks.sort_by_key(|block| block.lines.0);
 
-        // Process each block
-        for i in 0..blocks.len() {
-            if processed_indices.contains(&i) {
-                continue;
-            }
+    // Keep track of blocks we've already processed
+    let mut processed_indices = std::collections::HashSet::new();
+    let mut merged_blocks = Vec::new();
+
+    // Process each block
+    for i in 0..blocks.len() {
+        if processed_indices.contains(&i) {
+            continue;
+        }
 
-            // Start with the current block
-            let mut current_block = blocks[i].clone();
-            processed_indices.insert(i);
+        // Start with the current block
+        let mut current_block = blocks[i].clone();
+        processed_indices.insert(i);
 
-            // Keep track of which blocks we're merging in this group
-            let mut merged_indices = vec![i];
-            let mut changed = true;
+        // Keep track of which blocks we're merging in this group
+        let mut merged_indices = vec![i];
+        let mut changed = true;
 
-            // Keep trying to merge blocks until no more merges are possible
-            while changed {
-                changed = false;
+        // Keep trying to merge blocks until no more merges are possible
+        while changed {
+            changed = false;
 
-                // Try to merge with any remaining unprocessed block
-                for (j, next_block) in blocks.iter().enumerate() {
-                    if processed_indices.contains(&j) {
-                        continue;
+            // Try to merge with any remaining unprocessed block
+            for (j, next_block) in blocks.iter().enumerate() {
+                if processed_indices.contains(&j) {
+                    continue;
+                }
+
+                if should_merge_blocks(&current_block, next_block, threshold) {
+                    if debug_mode {
+                        debug!(
+                            "Merging blocks - current: {}-{}, next: {}-{}",
+                            current_block.lines.0,
+                            current_block.lines.1,
+                            next_block.lines.0,
+                            next_block.lines.1
+                        );
                     }
 
-                    if should_merge_blocks(&current_block, next_block, threshold) {
-                        if debug_mode {
-                            println!(
-                                "DEBUG: Merging blocks - current: {}-{}, next: {}-{}",
-                                current_block.lines.0,
-                                current_block.lines.1,
-                                next_block.lines.0,
-                                next_block.lines.1
-                            );
+                    // Merge the blocks
+                    let merged_start = current_block.lines.0.min(next_block.lines.0);
+                    let merged_end = current_block.lines.1.max(next_block.lines.1);
+                    let merged_code = merge_block_content(&current_block, next_block);
+
+                    // Use node type from the highest-ranked block
+                    let merged_node_type = if current_block.rank.unwrap_or(usize::MAX)
+                        <= next_block.rank.unwrap_or(usize::MAX)
+                    {
+                        current_block.node_type.clone()
+                    } else {
+                        next_block.node_type.clone()
+                    };
+
+                    // Combine scores and term statistics
+                    let merged_score = merge_scores(&current_block, next_block);
+                    let merged_term_stats = merge_term_statistics(&current_block, next_block);
+
+                    // Merge matched_lines - adjust line numbers relative to new merged block
+                    let merged_matched_lines =
+                        merge_matched_lines(&current_block, next_block, merged_start);
+
+                    // Merge matched_keywords - combine and deduplicate
+                    let merged_matched_keywords =
+                        merge_matched_keywords(&current_block, next_block);
+
+                    // Update the current block
+                    current_block.lines = (merged_start, merged_end);
+                    current_block.code = merged_code;
+                    current_block.node_type = merged_node_type;
+                    current_block.score = merged_score.0;
+                    current_block.tfidf_score = merged_score.1;
+                    current_block.bm25_score = merged_score.2;
+                    current_block.new_score = merged_score.3;
+                    current_block.block_unique_terms = merged_term_stats.0;
+                    current_block.block_total_matches = merged_term_stats.1;
+                    current_block.matched_lines = merged_matched_lines;
+                    current_block.matched_keywords = merged_matched_keywords;
+                    if let Some(files) = &next_block.duplicate_files {
+                        let duplicates = current_block.duplicate_files.get_or_insert_with(Vec::new);
+                        for file in files {
+                            if !duplicates.contains(file) {
+                                duplicates.push(file.clone());
+                            }
                         }
-
-                        // Merge the blocks
-                        let merged_start = current_block.lines.0.min(next_block.lines.0);
-                        let merged_end = current_block.lines.1.max(next_block.lines.1);
-                        let merged_code = merge_block_content(&current_block, next_block);
-
-                        // Use node type from the highest-ranked block
-                        let merged_node_type = if current_block.rank.unwrap_or(usize::MAX)
-                            <= next_block.rank.unwrap_or(usize::MAX)
-                        {
-                            current_block.node_type.clone()
-                        } else {
-                            next_block.node_type.clone()
-                        };
-
-                        // Combine scores and term statistics
-                        let merged_score = merge_scores(&current_block, next_block);
-                        let merged_term_stats = merge_term_statistics(&current_block, next_block);
-
-                        // Merge matched_lines - adjust line numbers relative to new merged block
-                        let merged_matched_lines =
-                            merge_matched_lines(&current_block, next_block, merged_start);
-
-                        // Merge matched_keywords - combine and deduplicate
-                        let merged_matched_keywords =
-                            merge_matched_keywords(&current_block, next_block);
-
-                        // Update the current block
-                        current_block.lines = (merged_start, merged_end);
-                        current_block.code = merged_code;
-                        current_block.node_type = merged_node_type;
-                        current_block.score = merged_score.0;
-                        current_block.tfidf_score = merged_score.1;
-                        current_block.bm25_score = merged_score.2;
-                        current_block.new_score = merged_score.3;
-                        current_block.block_unique_terms = merged_term_stats.0;
-                        current_block.block_total_matches = merged_term_stats.1;
-                        current_block.matched_lines = merged_matched_lines;
-                        current_block.matched_keywords = merged_matched_keywords;
-
-                        // Mark this block as processed
-                        processed_indices.insert(j);
-                        merged_indices.push(j);
-                        changed = true;
                     }
+
+                    // Mark this block as processed
+                    processed_indices.insert(j);
+                    merged_indices.push(j);
+                    changed = true;
                 }
             }
-
-            // Add the merged block to results
-            merged_blocks.push(current_block);
         }
 
-        // Add the merged blocks to the final results
-        merged_results.extend(merged_blocks);
-    }
-
-    if debug_mode {
-        println!(
-            "DEBUG: Post-rank merging complete. Merged {} blocks into {} blocks",
-            original_count,
-            merged_results.len()
-        );
+        // Add the merged block to results
+        merged_blocks.push(current_block);
     }
 
-    merged_results
+    merged_blocks
 }
 
 /// Helper function to determine if two blocks should be merged
@@ -186,13 +204,13 @@ pub fn merge_ranked_blocks(
 /// # Returns
 /// `true` if blocks should be merged, `false` otherwise
 pub fn should_merge_blocks(block1: &SearchResult, block2: &SearchResult, threshold: usize) -> bool {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if both blocks have parent_file_id, and if they match
     if let (Some(file_id1), Some(file_id2)) = (&block1.parent_file_id, &block2.parent_file_id) {
         if file_id1 != file_id2 {
             if debug_mode {
-                println!("DEBUG: Blocks not merged - different parent file IDs");
+                debug!("Blocks not merged - different parent file IDs");
             }
             return false;
         }
@@ -200,7 +218,7 @@ pub fn should_merge_blocks(block1: &SearchResult, block2: &SearchResult, thresho
         // If blocks don't have parent_file_id, check if they're from the same file
         if block1.file != block2.file {
             if debug_mode {
-                println!("DEBUG: Blocks not merged - different files");
+                debug!("Blocks not merged - different files");
             }
             return false;
         }
@@ -236,11 +254,9 @@ pub fn should_merge_blocks(block1: &SearchResult, block2: &SearchResult, thresho
         || (comment_with_function && distance <= threshold * 2);
 
     if debug_mode {
-        println!("DEBUG: Considering merging blocks - Block1: type='{}' lines {}-{}, Block2: type='{}' lines {}-{}, threshold: {}",
+        debug!("Considering merging blocks - Block1: type='{}' lines {}-{}, Block2: type='{}' lines {}-{}, threshold: {}",
                  block1.node_type, start1, end1, block2.node_type, start2, end2, threshold);
-        println!(
-            "DEBUG: Should merge: {should_merge} (distance: {distance}, threshold: {threshold})"
-        );
+        debug!("Should merge: {should_merge} (distance: {distance}, threshold: {threshold})");
     }
 
     should_merge
@@ -262,7 +278,7 @@ fn is_function_like(node_type: &str) -> bool {
 ///
 /// # Returns
 /// The merged code content
-fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
+fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> BlockCode {
     // Extract line ranges
     let (start1, end1) = block1.lines;
     let (start2, end2) = block2.lines;
@@ -280,6 +296,14 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
         return block2.code.clone();
     }
 
+    // Blocks of the same file share its buffer, which also holds the lines between them
+    let gap = start1.max(start2).saturating_sub(end1.min(end2) + 1);
+    if gap < 10 {
+        if let Some(code) = block1.code.covering(&block2.code) {
+            return code;
+        }
+    }
+
     // We need to extract the merged content from the file
     // For simplicity, we'll use the content from the blocks we have
     // This is not perfect, as we might be missing some lines in between,
@@ -306,7 +330,7 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
     // Build the merged content from the line map
     let mut merged_lines = Vec::new();
     let mut current_line = merged_start;
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Try to open the file to fill small gaps
     let file_path = Path::new(&block1.file);
@@ -315,18 +339,18 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
     let _reader = file_result.map(BufReader::new).ok(); // Used for debugging purposes only
 
     if debug_mode {
-        println!(
-            "DEBUG: Current working directory: {:?}",
+        debug!(
+            "Current working directory: {:?}",
             std::env::current_dir().unwrap_or_else(|_| PathBuf::from("unknown"))
         );
-        println!(
-            "DEBUG: Attempting to read file: {:?}",
+        debug!(
+            "Attempting to read file: {:?}",
             file_path
                 .canonicalize()
                 .unwrap_or_else(|_| PathBuf::from(file_path))
         );
-        println!("DEBUG: File exists: {}", file_path.exists());
-        println!("DEBUG: File can be opened: {file_content_available}");
+        debug!("File exists: {}", file_path.exists());
+        debug!("File can be opened: {file_content_available}");
     }
 
     while current_line <= merged_end {
@@ -349,8 +373,8 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
             if gap_size < 10 {
                 if file_content_available {
                     if debug_mode {
-                        println!(
-                            "DEBUG: Attempting to fill small gap from line {} to {} from file {}",
+                        debug!(
+                            "Attempting to fill small gap from line {} to {} from file {}",
                             gap_start, gap_end, block1.file
                         );
                     }
@@ -363,7 +387,7 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
                         let reader = BufReader::new(file);
 
                         if debug_mode {
-                            println!("DEBUG: Created fresh file reader for gap");
+                            debug!("Created fresh file reader for gap");
                         }
 
                         // Read the file line by line
@@ -386,22 +410,19 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
                         // Add the actual content for the gap
                         if !lines_read.is_empty() {
                             if debug_mode {
-                                println!(
-                                    "DEBUG: Successfully read {} lines for gap",
-                                    lines_read.len()
-                                );
+                                debug!("Successfully read {} lines for gap", lines_read.len());
                             }
                             merged_lines.extend(lines_read);
                             current_line = gap_end + 1;
                             continue;
                         } else if debug_mode {
-                            println!("DEBUG: No lines were read for the gap (empty lines)");
+                            debug!("No lines were read for the gap (empty lines)");
                         }
                     } else if debug_mode {
-                        println!("DEBUG: Could not create fresh file reader");
+                        debug!("Could not create fresh file reader");
                     }
                 } else if debug_mode {
-                    println!("DEBUG: File content not available for {}", block1.file);
+                    debug!("File content not available for {}", block1.file);
                 }
 
                 // For small gaps where we couldn't read the file or no lines were read,
@@ -419,7 +440,7 @@ fn merge_block_content(block1: &SearchResult, block2: &SearchResult) -> String {
         }
     }
 
-    merged_lines.join("\n")
+    merged_lines.join("\n").into()
 }
 
 /// Helper function to merge scores from two blocks
@@ -0,0 +1,118 @@
+//! Shortening of long blocks for `--max-block-lines`.
+//!
+//! A match inside a several-hundred-line function would otherwise return the whole
+//! function. Blocks longer than the limit keep their signature, each matched line with a
+//! few lines around it and their last line; every other run of lines is replaced by an
+//! `... N lines omitted ...` marker. Outline formats render from the source file and
+//! already show only signatures and matched lines, so this only changes `code`.
+
+use probe_code::models::SearchResult;
+
+/// Lines kept before and after each matched line
+const CONTEXT_LINES: usize = 2;
+
+/// A signature spanning more lines than this is cut after its first line
+const MAX_SIGNATURE_LINES: usize = 6;
+
+/// Shorten the code of every result longer than `max_lines` lines
+pub fn trim_long_blocks(results: &mut [SearchResult], max_lines: usize) {
+    for result in results {
+        if let Some(code) = trim_block(&result.code, result.matched_lines.as_deref(), max_lines) {
+            result.code = code.into();
+        }
+    }
+}
+
+/// The trimmed code of a block, or `None` when it has at most `max_lines` lines.
+/// `matched_lines` are 0-based offsets into the block; without them the block keeps
+/// its first `max_lines` lines.
+fn trim_block(code: &str, matched_lines: Option<&[usize]>, max_lines: usize) -> Option<String> {
+    let lines: Vec<&str> = code.lines().collect();
+    if lines.len() <= max_lines.max(1) {
+        return None;
+    }
+    let last = lines.len() - 1;
+
+    let mut keep = vec![false; lines.len()];
+    match matched_lines.filter(|matched| !matched.is_empty()) {
+        Some(matched) => {
+            // The signature, up to the line opening the body
+            let signature_end = lines
+                .iter()
+                .take(MAX_SIGNATURE_LINES)
+                .position(|line| line.contains('{') || line.trim_end().ends_with(':'))
+                .unwrap_or(0);
+            keep[..=signature_end].fill(true);
+            keep[last] = true;
+            for &line in matched.iter().filter(|&&line| line <= last) {
+                let end = (line + CONTEXT_LINES).min(last);
+                keep[line.saturating_sub(CONTEXT_LINES)..=end].fill(true);
+            }
+        }
+        None => keep[..max_lines.max(1)].fill(true),
+    }
+
+    let mut trimmed = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if keep[i] {
+            trimmed.push(lines[i].to_string());
+            i += 1;
+            continue;
+        }
+        let omitted = keep[i..].iter().take_while(|kept| !**kept).count();
+        if omitted == 1 {
+            // The marker would take the line's place anyway
+            trimmed.push(lines[i].to_string());
+        } else {
+            let indent = &lines[i][..lines[i].len() - lines[i].trim_start().len()];
+            trimmed.push(format!("{indent}... {omitted} lines omitted ..."));
+        }
+        i += omitted;
+    }
+    Some(trimmed.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_function(body_lines: usize) -> String {
+        let mut code = vec!["fn handle(request: Request) -> Response {".to_string()];
+        code.extend((1..=body_lines).map(|i| format!("    step_{i}();")));
+        code.push("}".to_string());
+        code.join("\n")
+    }
+
+    #[test]
+    fn test_short_blocks_are_untouched() {
+        assert_eq!(trim_block(&long_function(8), Some(&[3]), 10), None);
+    }
+
+    #[test]
+    fn test_keeps_signature_matches_and_last_line() {
+        let code = long_function(100);
+        let trimmed = trim_block(&code, Some(&[50]), 20).unwrap();
+        let expected = [
+            "fn handle(request: Request) -> Response {",
+            "    ... 47 lines omitted ...",
+            "    step_48();",
+            "    step_49();",
+            "    step_50();",
+            "    step_51();",
+            "    step_52();",
+            "    ... 48 lines omitted ...",
+            "}",
+        ];
+        assert_eq!(trimmed, expected.join("\n"));
+    }
+
+    #[test]
+    fn test_without_matched_lines_keeps_the_head() {
+        let trimmed = trim_block(&long_function(30), None, 5).unwrap();
+        let lines: Vec<&str> = trimmed.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[4], "    step_4();");
+        assert_eq!(lines[5], "    ... 27 lines omitted ...");
+    }
+}
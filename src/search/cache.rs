@@ -1,13 +1,21 @@
 use anyhow::Result;
+use tracing::debug;
 // Removed unused random imports - now using deterministic session ID generation
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs::{create_dir_all, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use probe_code::models::SearchResult;
 
+/// How long a session file is kept after it was last written before it is deleted
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most blocks remembered per session cache; the oldest are evicted beyond this
+pub const DEFAULT_SESSION_MAX_ENTRIES: usize = 10_000;
+
 /// Generate a deterministic hash for a query string
 /// This is used to create a unique identifier for each query
 /// Uses ahash with a fixed seed for consistent, fast cache keys across program runs
@@ -39,6 +47,10 @@ pub struct SessionCache {
     /// Key: normalized file path, Value: MD5 hash of file contents
     #[serde(default)]
     pub file_md5_hashes: HashMap<String, String>,
+    /// When each block identifier was added (seconds since the Unix epoch), for
+    /// evicting the oldest entries first. Entries from older caches default to 0.
+    #[serde(default)]
+    pub block_added: HashMap<String, u64>,
 }
 
 /// Calculate MD5 hash of a file's contents
@@ -61,24 +73,25 @@ impl SessionCache {
             query_hash,
             block_identifiers: HashSet::new(),
             file_md5_hashes: HashMap::new(),
+            block_added: HashMap::new(),
         }
     }
 
     /// Load a session cache from disk and validate file MD5 hashes
     pub fn load(session_id: &str, query_hash: &str) -> Result<Self> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         let cache_path = Self::get_cache_path(session_id, query_hash);
 
         // If the cache file doesn't exist, create a new empty cache
         if !cache_path.exists() {
             if debug_mode {
-                println!("DEBUG: Cache file does not exist at {cache_path:?}, creating new cache");
+                debug!("Cache file does not exist at {cache_path:?}, creating new cache");
             }
             return Ok(Self::new(session_id.to_string(), query_hash.to_string()));
         }
 
         if debug_mode {
-            println!("DEBUG: Loading cache from {cache_path:?}");
+            debug!("Loading cache from {cache_path:?}");
         }
 
         // Read the cache file
@@ -86,7 +99,7 @@ impl SessionCache {
             Ok(f) => f,
             Err(e) => {
                 if debug_mode {
-                    println!("DEBUG: Error opening cache file: {e}");
+                    debug!("Error opening cache file: {e}");
                 }
                 return Ok(Self::new(session_id.to_string(), query_hash.to_string()));
             }
@@ -95,7 +108,7 @@ impl SessionCache {
         let mut contents = String::new();
         if let Err(e) = file.read_to_string(&mut contents) {
             if debug_mode {
-                println!("DEBUG: Error reading cache file: {e}");
+                debug!("Error reading cache file: {e}");
             }
             return Ok(Self::new(session_id.to_string(), query_hash.to_string()));
         }
@@ -105,15 +118,15 @@ impl SessionCache {
             Ok(cache) => cache,
             Err(e) => {
                 if debug_mode {
-                    println!("DEBUG: Error parsing cache JSON: {e}");
+                    debug!("Error parsing cache JSON: {e}");
                 }
                 return Ok(Self::new(session_id.to_string(), query_hash.to_string()));
             }
         };
 
         if debug_mode {
-            println!(
-                "DEBUG: Successfully loaded cache with {} entries and {} file hashes",
+            debug!(
+                "Successfully loaded cache with {} entries and {} file hashes",
                 cache.block_identifiers.len(),
                 cache.file_md5_hashes.len()
             );
@@ -127,12 +140,12 @@ impl SessionCache {
 
     /// Save the session cache to disk
     pub fn save(&self) -> Result<()> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         let cache_path = Self::get_cache_path(&self.session_id, &self.query_hash);
 
         if debug_mode {
-            println!(
-                "DEBUG: Saving cache with {} entries to {:?}",
+            debug!(
+                "Saving cache with {} entries to {:?}",
                 self.block_identifiers.len(),
                 cache_path
             );
@@ -142,7 +155,7 @@ impl SessionCache {
         if let Some(parent) = cache_path.parent() {
             if let Err(e) = create_dir_all(parent) {
                 if debug_mode {
-                    println!("DEBUG: Error creating cache directory: {e}");
+                    debug!("Error creating cache directory: {e}");
                 }
                 return Err(e.into());
             }
@@ -153,7 +166,7 @@ impl SessionCache {
             Ok(j) => j,
             Err(e) => {
                 if debug_mode {
-                    println!("DEBUG: Error serializing cache to JSON: {e}");
+                    debug!("Error serializing cache to JSON: {e}");
                 }
                 return Err(e.into());
             }
@@ -164,21 +177,21 @@ impl SessionCache {
             Ok(mut file) => {
                 if let Err(e) = file.write_all(json.as_bytes()) {
                     if debug_mode {
-                        println!("DEBUG: Error writing to cache file: {e}");
+                        debug!("Error writing to cache file: {e}");
                     }
                     return Err(e.into());
                 }
             }
             Err(e) => {
                 if debug_mode {
-                    println!("DEBUG: Error creating cache file: {e}");
+                    debug!("Error creating cache file: {e}");
                 }
                 return Err(e.into());
             }
         }
 
         if debug_mode {
-            println!("DEBUG: Successfully saved cache to disk");
+            debug!("Successfully saved cache to disk");
         }
 
         Ok(())
@@ -191,9 +204,38 @@ impl SessionCache {
 
     /// Add a block identifier to the cache
     pub fn add_to_cache(&mut self, block_id: String) {
+        if !self.block_identifiers.contains(&block_id) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            self.block_added.insert(block_id.clone(), now);
+        }
         self.block_identifiers.insert(block_id);
     }
 
+    /// Evict the oldest block identifiers until at most `max_entries` remain.
+    /// Returns the number of evicted entries.
+    pub fn evict_oldest(&mut self, max_entries: usize) -> usize {
+        let excess = self.block_identifiers.len().saturating_sub(max_entries);
+        if excess == 0 {
+            return 0;
+        }
+
+        let mut by_age: Vec<(u64, String)> = self
+            .block_identifiers
+            .iter()
+            .map(|id| (self.block_added.get(id).copied().unwrap_or(0), id.clone()))
+            .collect();
+        by_age.sort_unstable();
+        for (_, id) in by_age.into_iter().take(excess) {
+            self.block_identifiers.remove(&id);
+            self.block_added.remove(&id);
+        }
+
+        excess
+    }
+
     /// Validate cache entries against current file MD5 hashes and invalidate if changed
     pub fn validate_and_invalidate_cache(&mut self, debug_mode: bool) -> Result<()> {
         let mut invalidated_files = HashSet::new();
@@ -206,7 +248,7 @@ impl SessionCache {
             // Skip if file no longer exists
             if !current_path.exists() {
                 if debug_mode {
-                    println!("DEBUG: File no longer exists, invalidating cache: {file_path}");
+                    debug!("File no longer exists, invalidating cache: {file_path}");
                 }
                 invalidated_files.insert(file_path.clone());
                 continue;
@@ -217,16 +259,14 @@ impl SessionCache {
                 Ok(current_md5) => {
                     if &current_md5 != cached_md5 {
                         if debug_mode {
-                            println!("DEBUG: File MD5 changed, invalidating cache: {file_path} (was: {cached_md5}, now: {current_md5})");
+                            debug!("File MD5 changed, invalidating cache: {file_path} (was: {cached_md5}, now: {current_md5})");
                         }
                         invalidated_files.insert(file_path.clone());
                     }
                 }
                 Err(e) => {
                     if debug_mode {
-                        println!(
-                            "DEBUG: Error calculating MD5 for {file_path}, invalidating cache: {e}"
-                        );
+                        debug!("Error calculating MD5 for {file_path}, invalidating cache: {e}");
                     }
                     invalidated_files.insert(file_path.clone());
                 }
@@ -253,6 +293,7 @@ impl SessionCache {
         // Remove invalidated blocks from cache
         for block_id in &blocks_to_remove {
             self.block_identifiers.remove(block_id);
+            self.block_added.remove(block_id);
         }
 
         // Remove invalidated file hashes
@@ -261,8 +302,8 @@ impl SessionCache {
         }
 
         if debug_mode && (!invalidated_files.is_empty() || !blocks_to_remove.is_empty()) {
-            println!(
-                "DEBUG: Invalidated {} files and removed {} cached blocks due to MD5 changes",
+            debug!(
+                "Invalidated {} files and removed {} cached blocks due to MD5 changes",
                 invalidated_files.len(),
                 blocks_to_remove.len()
             );
@@ -286,14 +327,127 @@ impl SessionCache {
 
     /// Get the path to the cache file
     pub fn get_cache_path(session_id: &str, query_hash: &str) -> PathBuf {
-        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        home_dir
-            .join(".cache")
-            .join("probe")
-            .join("sessions")
-            .join(format!("{session_id}_{query_hash}.json"))
+        sessions_dir().join(format!("{session_id}_{query_hash}.json"))
+    }
+}
+
+/// The directory holding all session caches
+pub fn sessions_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("sessions")
+}
+
+/// Parse a duration such as `90`, `90s`, `30m`, `2h`, `7d` or `1w` (a bare number is
+/// seconds)
+pub fn parse_duration(text: &str) -> Result<Duration> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let seconds_per_unit = match unit.trim() {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => 0,
+    };
+    match number.parse::<u64>() {
+        Ok(count) if seconds_per_unit > 0 => count
+            .checked_mul(seconds_per_unit)
+            .map(Duration::from_secs)
+            .ok_or_else(|| anyhow::anyhow!("Duration '{text}' is out of range")),
+        _ => Err(anyhow::anyhow!(
+            "Invalid duration '{text}', expected a number with an optional unit (s, m, h, d, w), e.g. 2h"
+        )),
+    }
+}
+
+/// Delete session files (query caches and statistics) that were not written to within
+/// `ttl`, so long-lived hosts do not accumulate session state. Returns the number of
+/// removed files.
+pub fn gc_expired_sessions(ttl: Duration) -> usize {
+    let Ok(entries) = std::fs::read_dir(sessions_dir()) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+
+    entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > ttl)
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+/// Cache key placeholder used instead of the query when deduplication spans all of a
+/// session's queries (`--session-scope session`)
+const SESSION_WIDE_QUERY: &str = "*";
+
+/// The query string a session cache is keyed by: the raw query (queries joined by AND),
+/// or one shared key for every query of the session when `across_queries` is set
+pub fn session_cache_query(queries: &[String], across_queries: bool) -> String {
+    if across_queries {
+        SESSION_WIDE_QUERY.to_string()
+    } else if queries.len() > 1 {
+        queries.join(" AND ")
+    } else {
+        queries[0].clone()
+    }
+}
+
+/// Running totals for a session across all of its queries, so an agent can see how
+/// much of its budget it has already pulled through probe
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionStats {
+    /// Tokens returned by all searches of the session so far
+    pub tokens_returned: usize,
+    /// Number of searches run in the session
+    pub searches: usize,
+}
+
+impl SessionStats {
+    /// Load the statistics for a session, starting from zero when there are none yet
+    pub fn load(session_id: &str) -> Self {
+        std::fs::read_to_string(Self::get_stats_path(session_id))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the statistics for a session
+    pub fn save(&self, session_id: &str) -> Result<()> {
+        let path = Self::get_stats_path(session_id);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
     }
+
+    /// Get the path to the statistics file, next to the session's query caches
+    pub fn get_stats_path(session_id: &str) -> PathBuf {
+        SessionCache::get_cache_path(session_id, "stats")
+    }
+}
+
+/// Add the tokens returned by one search to the session's running total and return
+/// the new total
+pub fn record_session_tokens(session_id: &str, tokens: usize) -> Result<usize> {
+    let mut stats = SessionStats::load(session_id);
+    stats.tokens_returned += tokens;
+    stats.searches += 1;
+    stats.save(session_id)?;
+    Ok(stats.tokens_returned)
 }
+
 /// Normalize a file path for consistent cache keys
 /// Removes leading "./" and ensures consistent format
 fn normalize_path(path: &str) -> String {
@@ -314,6 +468,65 @@ pub fn generate_cache_key(result: &SearchResult) -> String {
     format!("{normalized_path}:{}-{}", result.lines.0, result.lines.1)
 }
 
+/// Every block returned by earlier queries of a session, as line ranges by normalized
+/// file path. Blocks of files changed since they were returned are left out.
+pub fn session_blocks(session_id: &str) -> HashMap<String, Vec<(usize, usize)>> {
+    let mut blocks: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(sessions_dir()) else {
+        return blocks;
+    };
+    let prefix = format!("{session_id}_");
+
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(query_hash) = file_name
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        else {
+            continue;
+        };
+        // Skip the statistics file and caches of other sessions sharing this prefix
+        if query_hash == "stats" || query_hash.contains('_') {
+            continue;
+        }
+        let Ok(cache) = SessionCache::load(session_id, query_hash) else {
+            continue;
+        };
+        for key in &cache.block_identifiers {
+            if let Some((file, range)) = parse_cache_key(key) {
+                blocks.entry(file.to_string()).or_default().push(range);
+            }
+        }
+    }
+    blocks
+}
+
+/// Split a cache key of the form "file.rs:23-45" into its file and line range
+fn parse_cache_key(key: &str) -> Option<(&str, (usize, usize))> {
+    let (file, range) = key.rsplit_once(':')?;
+    let (start, end) = range.split_once('-')?;
+    Some((file, (start.parse().ok()?, end.parse().ok()?)))
+}
+
+/// Whether `file` has a block in `blocks` (see [`session_blocks`])
+pub fn in_session_files(file: &Path, blocks: &HashMap<String, Vec<(usize, usize)>>) -> bool {
+    blocks.contains_key(&normalize_path(&file.to_string_lossy()))
+}
+
+/// Whether a result overlaps one of the blocks in `blocks` (see [`session_blocks`])
+pub fn overlaps_session_blocks(
+    result: &SearchResult,
+    blocks: &HashMap<String, Vec<(usize, usize)>>,
+) -> bool {
+    blocks
+        .get(&normalize_path(&result.file))
+        .is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|&(start, end)| result.lines.0 <= end && start <= result.lines.1)
+        })
+}
+
 /// Filter search results using the cache without adding to the cache
 pub fn filter_results_with_cache(
     results: &[SearchResult],
@@ -321,7 +534,7 @@ pub fn filter_results_with_cache(
     query: &str,
 ) -> Result<(Vec<SearchResult>, usize)> {
     let query_hash = hash_query(query);
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if this is a new session by looking for the cache file
     let cache_path = SessionCache::get_cache_path(session_id, &query_hash);
@@ -330,7 +543,7 @@ pub fn filter_results_with_cache(
     // For a new session, don't skip any results
     if is_new_session {
         if debug_mode {
-            println!("DEBUG: New session, not filtering results");
+            debug!("New session, not filtering results");
         }
         // Return all results with no skipped blocks
         return Ok((results.to_vec(), 0));
@@ -342,14 +555,14 @@ pub fn filter_results_with_cache(
     // If the cache is empty, don't skip any results
     if cache.block_identifiers.is_empty() {
         if debug_mode {
-            println!("DEBUG: Cache is empty, not filtering results");
+            debug!("Cache is empty, not filtering results");
         }
         return Ok((results.to_vec(), 0));
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: Filtering {} results against {} cached blocks",
+        debug!(
+            "Filtering {} results against {} cached blocks",
             results.len(),
             cache.block_identifiers.len()
         );
@@ -367,7 +580,7 @@ pub fn filter_results_with_cache(
 
             if is_cached {
                 if debug_mode && skipped_count < 5 {
-                    println!("DEBUG: Skipping cached block: {cache_key}");
+                    debug!("Skipping cached block: {cache_key}");
                 }
                 skipped_count += 1;
                 false
@@ -379,8 +592,8 @@ pub fn filter_results_with_cache(
         .collect();
 
     if debug_mode {
-        println!(
-            "DEBUG: Filtered out {} cached blocks, returning {} results",
+        debug!(
+            "Filtered out {} cached blocks, returning {} results",
             skipped_count,
             filtered_results.len()
         );
@@ -397,7 +610,7 @@ pub fn filter_matched_lines_with_cache(
     query: &str,
 ) -> Result<usize> {
     let query_hash = hash_query(query);
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if this is a new session by looking for the cache file
     let cache_path = SessionCache::get_cache_path(session_id, &query_hash);
@@ -406,7 +619,7 @@ pub fn filter_matched_lines_with_cache(
     // For a new session, don't skip any lines
     if is_new_session {
         if debug_mode {
-            println!("DEBUG: New session, not filtering matched lines");
+            debug!("New session, not filtering matched lines");
         }
         return Ok(0);
     }
@@ -417,14 +630,14 @@ pub fn filter_matched_lines_with_cache(
     // If the cache is empty, don't skip any lines
     if cache.block_identifiers.is_empty() {
         if debug_mode {
-            println!("DEBUG: Cache is empty, not filtering matched lines");
+            debug!("Cache is empty, not filtering matched lines");
         }
         return Ok(0);
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: Early filtering of matched lines against {} cached blocks",
+        debug!(
+            "Early filtering of matched lines against {} cached blocks",
             cache.block_identifiers.len()
         );
     }
@@ -446,8 +659,8 @@ pub fn filter_matched_lines_with_cache(
         }
 
         if debug_mode {
-            println!(
-                "DEBUG: File {:?} has {} matched lines before filtering",
+            debug!(
+                "File {:?} has {} matched lines before filtering",
                 file_path,
                 all_lines.len()
             );
@@ -491,7 +704,7 @@ pub fn filter_matched_lines_with_cache(
 
             if is_cached {
                 if debug_mode && skipped_count < 5 {
-                    println!("DEBUG: Skipping cached line: {line_cache_key}");
+                    debug!("Skipping cached line: {line_cache_key}");
                 }
                 lines_to_remove.insert(line_num);
                 skipped_count += 1;
@@ -516,8 +729,8 @@ pub fn filter_matched_lines_with_cache(
         if debug_mode {
             let remaining_lines: HashSet<_> =
                 term_map.values().flat_map(|lines| lines.iter()).collect();
-            println!(
-                "DEBUG: File {:?} has {} matched lines after filtering",
+            debug!(
+                "File {:?} has {} matched lines after filtering",
                 file_path,
                 remaining_lines.len()
             );
@@ -530,8 +743,8 @@ pub fn filter_matched_lines_with_cache(
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: Early filtering removed {} cached lines, {} files remain",
+        debug!(
+            "Early filtering removed {} cached lines, {} files remain",
             skipped_count,
             file_term_map.len()
         );
@@ -540,22 +753,27 @@ pub fn filter_matched_lines_with_cache(
     Ok(skipped_count)
 }
 
-/// Add search results to the cache
-pub fn add_results_to_cache(results: &[SearchResult], session_id: &str, query: &str) -> Result<()> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+/// Add search results to the cache, evicting the oldest entries beyond `max_entries`
+pub fn add_results_to_cache(
+    results: &[SearchResult],
+    session_id: &str,
+    query: &str,
+    max_entries: usize,
+) -> Result<()> {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let query_hash = hash_query(query);
 
     // Load or create the cache
     let mut cache = SessionCache::load(session_id, &query_hash)?;
 
     if debug_mode {
-        println!(
-            "DEBUG: Adding {} results to cache for session {}",
+        debug!(
+            "Adding {} results to cache for session {}",
             results.len(),
             session_id
         );
-        println!(
-            "DEBUG: Cache had {} entries before update",
+        debug!(
+            "Cache had {} entries before update",
             cache.block_identifiers.len()
         );
     }
@@ -570,7 +788,7 @@ pub fn add_results_to_cache(results: &[SearchResult], session_id: &str, query: &
         if !cache.is_cached(&cache_key) {
             new_entries += 1;
             if debug_mode && new_entries <= 5 {
-                println!("DEBUG: Adding new cache entry: {cache_key}");
+                debug!("Adding new cache entry: {cache_key}");
             }
         }
         cache.add_to_cache(cache_key);
@@ -583,30 +801,35 @@ pub fn add_results_to_cache(results: &[SearchResult], session_id: &str, query: &
     for file_path in &unique_files {
         if let Err(e) = cache.update_file_md5(file_path) {
             if debug_mode {
-                println!("DEBUG: Failed to update MD5 for {file_path}: {e}");
+                debug!("Failed to update MD5 for {file_path}: {e}");
             }
         }
     }
 
     if debug_mode {
-        println!("DEBUG: Added {new_entries} new entries to cache");
-        println!("DEBUG: Updated MD5 hashes for {} files", unique_files.len());
-        println!(
-            "DEBUG: Cache now has {} entries and {} file hashes",
+        debug!("Added {new_entries} new entries to cache");
+        debug!("Updated MD5 hashes for {} files", unique_files.len());
+        debug!(
+            "Cache now has {} entries and {} file hashes",
             cache.block_identifiers.len(),
             cache.file_md5_hashes.len()
         );
     }
 
+    let evicted = cache.evict_oldest(max_entries);
+    if debug_mode && evicted > 0 {
+        debug!("Evicted {evicted} oldest entries to stay within {max_entries}");
+    }
+
     // Save the updated cache
     cache.save()?;
 
     Ok(())
 }
 
-/// Debug function to print cache contents (only used when DEBUG=1)
+/// Debug function to log cache contents (only at debug log level)
 pub fn debug_print_cache(session_id: &str, query: &str) -> Result<()> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     if !debug_mode {
         return Ok(());
     }
@@ -614,19 +837,16 @@ pub fn debug_print_cache(session_id: &str, query: &str) -> Result<()> {
     let query_hash = hash_query(query);
     let cache = SessionCache::load(session_id, &query_hash)?;
 
-    println!("DEBUG: Cache for session {session_id} with query hash {query_hash}");
-    println!(
-        "DEBUG: Contains {} cached blocks",
-        cache.block_identifiers.len()
-    );
+    debug!("Cache for session {session_id} with query hash {query_hash}");
+    debug!("Contains {} cached blocks", cache.block_identifiers.len());
 
     for (i, block_id) in cache.block_identifiers.iter().enumerate().take(10) {
-        println!("DEBUG: Cached block {i}: {block_id}");
+        debug!("Cached block {i}: {block_id}");
     }
 
     if cache.block_identifiers.len() > 10 {
         let _remaining = cache.block_identifiers.len() - 10;
-        println!("DEBUG: ... and {} more", cache.block_identifiers.len() - 10);
+        debug!("... and {} more", cache.block_identifiers.len() - 10);
     }
 
     Ok(())
@@ -635,7 +855,7 @@ pub fn debug_print_cache(session_id: &str, query: &str) -> Result<()> {
 /// Generate a unique 4-character alphanumeric session ID
 /// Returns a tuple of (session_id, is_new) where is_new indicates if this is a newly generated ID
 pub fn generate_session_id() -> Result<(&'static str, bool)> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Generate a single session ID instead of looping
     if (0..10).next().is_some() {
@@ -661,13 +881,13 @@ pub fn generate_session_id() -> Result<(&'static str, bool)> {
         let session_id = session_id.to_lowercase();
 
         if debug_mode {
-            println!("DEBUG: Generated session ID: {session_id}");
+            debug!("Generated session ID: {session_id}");
         }
 
         // We don't check for existing cache files here since we're just generating a session ID
         // The actual cache file will be created with both session ID and query hash
         if debug_mode {
-            println!("DEBUG: Generated new session ID: {session_id}");
+            debug!("Generated new session ID: {session_id}");
         }
         // Convert to a static string (this leaks memory, but it's a small amount and only happens once per session)
         let static_id: &'static str = Box::leak(session_id.into_boxed_str());
@@ -692,6 +912,19 @@ mod tests {
         assert_eq!(normalize_path("path/to/file.rs"), "path/to/file.rs");
     }
 
+    #[test]
+    fn test_parse_cache_key() {
+        assert_eq!(
+            parse_cache_key("src/main.rs:23-45"),
+            Some(("src/main.rs", (23, 45)))
+        );
+        assert_eq!(
+            parse_cache_key("C:/src/lib.rs:1-2"),
+            Some(("C:/src/lib.rs", (1, 2)))
+        );
+        assert_eq!(parse_cache_key("src/main.rs"), None);
+    }
+
     #[test]
     fn test_query_hashing() {
         // Test that different queries produce different hashes
@@ -711,9 +944,13 @@ mod tests {
             file: "./path/to/file.rs".to_string(),
             lines: (10, 20),
             node_type: "function".to_string(),
-            code: "".to_string(),
+            code: "".into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -740,9 +977,13 @@ mod tests {
             file: "path/to/file.rs".to_string(),
             lines: (10, 20),
             node_type: "function".to_string(),
-            code: "".to_string(),
+            code: "".into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -797,4 +1038,48 @@ mod tests {
         // Caches should have different query hashes
         assert_ne!(cache1.query_hash, cache2.query_hash);
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(30 * 60));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 3600));
+        assert_eq!(
+            parse_duration("1w").unwrap(),
+            Duration::from_secs(7 * 86400)
+        );
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("h").is_err());
+        let err = parse_duration("30000000000000000w").unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+    }
+
+    #[test]
+    fn test_evict_oldest_entries() {
+        let mut cache = SessionCache::new("test".to_string(), "hash".to_string());
+        for (i, id) in ["a.rs:1-2", "b.rs:1-2", "c.rs:1-2"].iter().enumerate() {
+            cache.add_to_cache(id.to_string());
+            cache.block_added.insert(id.to_string(), i as u64);
+        }
+
+        assert_eq!(cache.evict_oldest(5), 0);
+        assert_eq!(cache.evict_oldest(2), 1);
+        assert!(!cache.is_cached("a.rs:1-2"));
+        assert!(cache.is_cached("b.rs:1-2") && cache.is_cached("c.rs:1-2"));
+        assert_eq!(cache.block_added.len(), 2);
+    }
+
+    #[test]
+    fn test_session_cache_query_scope() {
+        let first = vec!["parse config".to_string()];
+        let second = vec!["load".to_string(), "settings".to_string()];
+
+        assert_eq!(session_cache_query(&first, false), "parse config");
+        assert_eq!(session_cache_query(&second, false), "load AND settings");
+        // Session-wide dedup shares one cache between different queries
+        assert_eq!(
+            session_cache_query(&first, true),
+            session_cache_query(&second, true)
+        );
+    }
 }
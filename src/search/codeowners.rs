@@ -0,0 +1,190 @@
+//! Code owners from a repository's `CODEOWNERS` file, for `--owners` and the
+//! `owner:@team` query hint.
+//!
+//! The file is looked up like GitHub does, in `.github/`, the repository root and
+//! `docs/`. Patterns follow .gitignore rules and the last matching line decides a
+//! file's owners; a pattern without owners leaves its files unowned.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use probe_code::models::SearchResult;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Places of the CODEOWNERS file relative to the repository root, in lookup order
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+pub struct CodeOwners {
+    root: PathBuf,
+    /// One matcher per line, with that line's owners
+    rules: Vec<(Gitignore, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// The CODEOWNERS of the repository containing `start`, if it has one
+    pub fn find(start: &Path) -> Option<CodeOwners> {
+        let start = start.canonicalize().ok()?;
+        for dir in start.ancestors() {
+            for path in CODEOWNERS_PATHS {
+                if let Ok(content) = fs::read_to_string(dir.join(path)) {
+                    return Some(CodeOwners::parse(dir, &content));
+                }
+            }
+            // Don't look past the top of the repository
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Parse CODEOWNERS content whose patterns are relative to `root`
+    pub fn parse(root: &Path, content: &str) -> CodeOwners {
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            let owners: Vec<String> = fields
+                .take_while(|field| !field.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            let mut builder = GitignoreBuilder::new(root);
+            if builder.add_line(None, pattern).is_err() {
+                continue;
+            }
+            if let Ok(matcher) = builder.build() {
+                rules.push((matcher, owners));
+            }
+        }
+        CodeOwners {
+            root: root.to_path_buf(),
+            rules,
+        }
+    }
+
+    /// Owners of a file, `None` for files outside the repository or without owners
+    pub fn owners_of(&self, path: &Path) -> Option<&[String]> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let relative = path.strip_prefix(&self.root).ok()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| {
+                matcher
+                    .matched_path_or_any_parents(relative, false)
+                    .is_ignore()
+            })
+            .map(|(_, owners)| owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+
+    /// Whether one of the owners of a file is one of `owners` (see [`owner_matches`])
+    pub fn is_owned_by(&self, path: &Path, owners: &[String]) -> bool {
+        self.owners_of(path).is_some_and(|file_owners| {
+            file_owners
+                .iter()
+                .any(|owner| owners.iter().any(|wanted| owner_matches(owner, wanted)))
+        })
+    }
+}
+
+/// Whether a CODEOWNERS owner is the one asked for, ignoring case and the leading `@`.
+/// A team can be given without its organization: `backend` matches `@org/backend`.
+pub fn owner_matches(owner: &str, wanted: &str) -> bool {
+    let owner = owner.trim_start_matches('@').to_lowercase();
+    let wanted = wanted.trim_start_matches('@').to_lowercase();
+    owner == wanted
+        || owner
+            .rsplit_once('/')
+            .is_some_and(|(_, team)| team == wanted)
+}
+
+/// Set `owners` on every result in a repository with a CODEOWNERS file
+pub fn annotate_owners(results: &mut [SearchResult]) {
+    let mut by_dir: HashMap<PathBuf, Option<Rc<CodeOwners>>> = HashMap::new();
+    for result in results.iter_mut() {
+        let path = Path::new(&result.file);
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let code_owners = by_dir
+            .entry(dir)
+            .or_insert_with_key(|dir| CodeOwners::find(dir).map(Rc::new));
+        result.owners = code_owners
+            .as_ref()
+            .and_then(|code_owners| code_owners.owners_of(path))
+            .map(<[String]>::to_vec);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*                 @org/core
+*.md              @org/docs # inline comment
+/src/search/      @org/search @ada
+src/generated.rs
+";
+
+    #[test]
+    fn test_last_matching_rule_wins() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        let owners = CodeOwners::parse(&root, CODEOWNERS);
+        let owners_of = |path: &str| owners.owners_of(&root.join(path)).map(<[String]>::to_vec);
+
+        assert_eq!(
+            owners_of("src/main.rs"),
+            Some(vec!["@org/core".to_string()])
+        );
+        assert_eq!(owners_of("README.md"), Some(vec!["@org/docs".to_string()]));
+        assert_eq!(
+            owners_of("src/search/query.rs"),
+            Some(vec!["@org/search".to_string(), "@ada".to_string()])
+        );
+        // A pattern without owners leaves the file unowned
+        assert_eq!(owners_of("src/generated.rs"), None);
+        assert_eq!(owners.owners_of(Path::new("/elsewhere/main.rs")), None);
+
+        assert!(owners.is_owned_by(&root.join("src/search/query.rs"), &["search".to_string()]));
+        assert!(!owners.is_owned_by(&root.join("src/main.rs"), &["@org/search".to_string()]));
+    }
+
+    #[test]
+    fn test_owner_matches() {
+        assert!(owner_matches("@org/backend", "@org/backend"));
+        assert!(owner_matches("@org/Backend", "backend"));
+        assert!(owner_matches("@ada", "ada"));
+        assert!(!owner_matches("@org/backend", "@other/backend-ops"));
+    }
+
+    #[test]
+    fn test_find_in_github_dir() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join(".github")).unwrap();
+        fs::create_dir_all(temp.path().join("src/deep")).unwrap();
+        fs::write(temp.path().join(".github/CODEOWNERS"), "*.rs @org/rust\n").unwrap();
+        let file = temp.path().join("src/deep/lib.rs");
+        fs::write(&file, "fn lib() {}\n").unwrap();
+
+        let owners = CodeOwners::find(&temp.path().join("src/deep")).unwrap();
+        assert_eq!(
+            owners.owners_of(&file),
+            Some(&["@org/rust".to_string()][..])
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Collapsing of identical code blocks found in several files.
+//!
+//! Vendored or generated copies of the same code produce one result per copy. With
+//! `--dedupe-cross-file` those are collapsed into the best-ranked copy, which lists
+//! the other files in `duplicate_files`.
+
+use ahash::RandomState;
+use probe_code::models::SearchResult;
+use std::collections::HashMap;
+
+/// Hash of a block's code, ignoring indentation and trailing whitespace so copies
+/// that were re-indented or re-formatted at line ends still match
+fn content_hash(code: &str) -> u64 {
+    let build_hasher = RandomState::with_seeds(
+        0x123456789abcdef,
+        0xfedcba9876543210,
+        0x1111111111111111,
+        0x2222222222222222,
+    );
+    let normalized: Vec<&str> = code
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    build_hasher.hash_one(normalized)
+}
+
+/// Collapse blocks with identical content appearing in different files.
+///
+/// `results` must already be in rank order: the first copy of each block is kept and
+/// the files of the later copies are recorded in its `duplicate_files`. Identical
+/// blocks within a single file are left alone.
+pub fn dedupe_cross_file(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut kept: Vec<SearchResult> = Vec::with_capacity(results.len());
+    let mut first_copy: HashMap<u64, usize> = HashMap::new();
+
+    for result in results {
+        if result.code.trim().is_empty() {
+            kept.push(result);
+            continue;
+        }
+        let hash = content_hash(&result.code);
+        match first_copy.get(&hash) {
+            Some(&index) if kept[index].file != result.file => {
+                let duplicates = kept[index].duplicate_files.get_or_insert_with(Vec::new);
+                if !duplicates.contains(&result.file) {
+                    duplicates.push(result.file);
+                }
+            }
+            Some(_) => kept.push(result),
+            None => {
+                first_copy.insert(hash, kept.len());
+                kept.push(result);
+            }
+        }
+    }
+
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(file: &str, code: &str) -> SearchResult {
+        SearchResult {
+            file: file.to_string(),
+            lines: (1, code.lines().count()),
+            node_type: "function_item".to_string(),
+            code: code.into(),
+            symbol_signature: None,
+            matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
+            rank: None,
+            score: None,
+            tfidf_score: None,
+            bm25_score: None,
+            tfidf_rank: None,
+            bm25_rank: None,
+            new_score: None,
+            hybrid2_rank: None,
+            combined_score_rank: None,
+            file_unique_terms: None,
+            file_total_matches: None,
+            file_match_rank: None,
+            block_unique_terms: None,
+            block_total_matches: None,
+            parent_file_id: None,
+            block_id: None,
+            matched_keywords: None,
+            matched_lines: None,
+            tokenized_content: None,
+            parent_context: None,
+        }
+    }
+
+    #[test]
+    fn test_dedupe_cross_file() {
+        let results = vec![
+            block("src/lib.rs", "fn parse() {\n    todo!()\n}"),
+            block("other.rs", "fn other() {}"),
+            block("vendor/a/lib.rs", "  fn parse() {\n        todo!()  \n  }"),
+            block("vendor/b/lib.rs", "fn parse() {\n    todo!()\n}"),
+            block("vendor/b/lib.rs", "fn parse() {\n    todo!()\n}"),
+            block("src/lib.rs", "fn parse() {\n    todo!()\n}"),
+        ];
+
+        let deduped = dedupe_cross_file(results);
+        let summary: Vec<(&str, Option<Vec<String>>)> = deduped
+            .iter()
+            .map(|r| (r.file.as_str(), r.duplicate_files.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "src/lib.rs",
+                    Some(vec![
+                        "vendor/a/lib.rs".to_string(),
+                        "vendor/b/lib.rs".to_string()
+                    ])
+                ),
+                ("other.rs", None),
+                ("src/lib.rs", None),
+            ]
+        );
+    }
+}
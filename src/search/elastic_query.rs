@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::iter::Peekable;
 use std::str::Chars;
+use tracing::debug;
 
 // PHASE 3C OPTIMIZATION: Compute hash key for evaluation cache
 fn compute_evaluation_key(matched_terms: &HashSet<usize>) -> u64 {
@@ -22,7 +23,7 @@ pub enum Expr {
     /// `lowercase_keywords` => pre-computed lowercase versions for case-insensitive matching (computed once at parse time)
     /// `field` => optional field specifier (e.g. `Some("title")` for `title:foo`)
     /// `required` => a leading `+`
-    /// `excluded` => a leading `-`
+    /// `excluded` => a leading `-` or `NOT`
     /// `exact` => if originally quoted, meaning "no tokenization/splitting"
     Term {
         keywords: Vec<String>,
@@ -38,6 +39,10 @@ pub enum Expr {
 
     /// Logical OR of two sub-expressions.
     Or(Box<Expr>, Box<Expr>),
+
+    /// Logical AND of two sub-expressions whose matches must also lie within the given
+    /// number of lines of each other (`a NEAR/n b`).
+    Near(Box<Expr>, Box<Expr>, usize),
 }
 
 impl Expr {
@@ -69,7 +74,7 @@ impl Expr {
                     }
                 }
             }
-            Expr::And(left, right) => {
+            Expr::And(left, right) | Expr::Near(left, right, _) => {
                 left.collect_terms(required, optional);
                 right.collect_terms(required, optional);
             }
@@ -84,21 +89,67 @@ impl Expr {
     pub fn has_required_term(&self) -> bool {
         match self {
             Expr::Term { required, .. } => *required,
-            Expr::And(left, right) | Expr::Or(left, right) => {
+            Expr::And(left, right) | Expr::Or(left, right) | Expr::Near(left, right, _) => {
                 left.has_required_term() || right.has_required_term()
             }
         }
     }
 
+    /// The negation of this expression, by De Morgan's laws: terms switch between
+    /// excluded and included, and AND and OR swap. A negated NEAR excludes blocks with
+    /// both sides, however far apart. A term of several keywords, as `userName` split
+    /// into `user` and `name`, is negated as one unit: any of its keywords missing
+    /// rather than all of them.
+    pub fn negate(self) -> Expr {
+        match self {
+            Expr::Term {
+                keywords,
+                lowercase_keywords,
+                field,
+                excluded,
+                exact,
+                ..
+            } if keywords.len() > 1 => keywords
+                .into_iter()
+                .zip(lowercase_keywords)
+                .map(|(keyword, lowercase_keyword)| Expr::Term {
+                    keywords: vec![keyword],
+                    lowercase_keywords: vec![lowercase_keyword],
+                    field: field.clone(),
+                    required: false,
+                    excluded: !excluded,
+                    exact,
+                })
+                .reduce(|left, right| Expr::Or(Box::new(left), Box::new(right)))
+                .expect("the term has keywords"),
+            Expr::Term {
+                keywords,
+                lowercase_keywords,
+                field,
+                excluded,
+                exact,
+                ..
+            } => Expr::Term {
+                keywords,
+                lowercase_keywords,
+                field,
+                required: false,
+                excluded: !excluded,
+                exact,
+            },
+            Expr::And(left, right) | Expr::Near(left, right, _) => {
+                Expr::Or(Box::new(left.negate()), Box::new(right.negate()))
+            }
+            Expr::Or(left, right) => Expr::And(Box::new(left.negate()), Box::new(right.negate())),
+        }
+    }
+
     /// Returns `true` if this expression contains only excluded terms.
     /// This is used for early termination optimization.
     pub fn is_only_excluded_terms(&self) -> bool {
         match self {
             Expr::Term { excluded, .. } => *excluded,
-            Expr::And(left, right) => {
-                left.is_only_excluded_terms() && right.is_only_excluded_terms()
-            }
-            Expr::Or(left, right) => {
+            Expr::And(left, right) | Expr::Or(left, right) | Expr::Near(left, right, _) => {
                 left.is_only_excluded_terms() && right.is_only_excluded_terms()
             }
         }
@@ -131,7 +182,7 @@ impl Expr {
                     true
                 }
             }
-            Expr::And(left, right) => {
+            Expr::And(left, right) | Expr::Near(left, right, _) => {
                 // For AND: both sides must have their required terms satisfied
                 left.check_all_required_terms_present(matched_terms, term_indices)
                     && right.check_all_required_terms_present(matched_terms, term_indices)
@@ -160,7 +211,7 @@ impl Expr {
             return false;
         }
 
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         // CRITICAL FIX: Check required terms FIRST before any other evaluation
         // In Lucene semantics, if ANY required term is missing, the entire query fails
@@ -169,7 +220,7 @@ impl Expr {
                 self.check_all_required_terms_present(matched_terms, term_indices);
             if !all_required_terms_present {
                 if debug_mode {
-                    println!("DEBUG: Query failed - required terms missing");
+                    debug!("Query failed - required terms missing");
                 }
                 return false;
             }
@@ -246,7 +297,8 @@ impl Expr {
                     }
                 }
             }
-            Expr::And(left, right) => {
+            // Proximity needs line numbers, so NEAR is checked by `proximity_holds`
+            Expr::And(left, right) | Expr::Near(left, right, _) => {
                 let lval = left.evaluate_with_has_required(
                     matched_terms,
                     term_indices,
@@ -260,8 +312,8 @@ impl Expr {
                     has_required_anywhere,
                 );
                 if debug_mode {
-                    println!(
-                        "DEBUG: AND => left={}, right={}, result={}",
+                    debug!(
+                        "AND => left={}, right={}, result={}",
                         lval,
                         rval,
                         lval && rval
@@ -284,8 +336,8 @@ impl Expr {
                 );
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: OR => left={}, right={}, result={}",
+                    debug!(
+                        "OR => left={}, right={}, result={}",
                         lval,
                         rval,
                         lval || rval
@@ -383,7 +435,7 @@ impl Expr {
             return false;
         }
 
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         // If ignoring negatives, let's ensure that all required terms are present up front.
         // (We skip enforcing them again for each subtree.)
@@ -402,12 +454,7 @@ impl Expr {
                             vec![]
                         }
                     }
-                    Expr::And(left, right) => {
-                        let mut out = collect_required(left);
-                        out.extend(collect_required(right));
-                        out
-                    }
-                    Expr::Or(left, right) => {
+                    Expr::And(left, right) | Expr::Or(left, right) | Expr::Near(left, right, _) => {
                         let mut out = collect_required(left);
                         out.extend(collect_required(right));
                         out
@@ -416,7 +463,7 @@ impl Expr {
             }
             let required_terms = collect_required(self);
             if debug_mode && !required_terms.is_empty() {
-                println!("DEBUG: Required terms (ignoring negatives): {required_terms:?}");
+                debug!("Required terms (ignoring negatives): {required_terms:?}");
             }
             // Pre-compute lowercase required terms to avoid repeated allocations
             let lowercase_required: Vec<String> =
@@ -427,7 +474,7 @@ impl Expr {
                 if let Some(&idx) = term_indices.get(lowercase_term) {
                     if !matched_terms.contains(&idx) {
                         if debug_mode {
-                            println!("DEBUG: Missing required term '{original_term}' (idx={idx})");
+                            debug!("Missing required term '{original_term}' (idx={idx})");
                         }
                         return false;
                     }
@@ -442,10 +489,10 @@ impl Expr {
         let has_required_anywhere = self.has_required_term();
 
         if debug_mode {
-            println!("DEBUG: Evaluating => {self:?}");
-            println!("DEBUG: matched_terms => {matched_terms:?}");
-            println!("DEBUG: term_indices => {term_indices:?}");
-            println!("DEBUG: Expression has_required_anywhere? {has_required_anywhere}");
+            debug!("Evaluating => {self:?}");
+            debug!("matched_terms => {matched_terms:?}");
+            debug!("term_indices => {term_indices:?}");
+            debug!("Expression has_required_anywhere? {has_required_anywhere}");
         }
 
         // Delegate final checks to our helper, which references has_required_anywhere
@@ -456,6 +503,77 @@ impl Expr {
             has_required_anywhere,
         )
     }
+
+    /// Returns `true` if this expression contains a `NEAR/n`.
+    pub fn has_near(&self) -> bool {
+        match self {
+            Expr::Term { .. } => false,
+            Expr::Near(..) => true,
+            Expr::And(left, right) | Expr::Or(left, right) => left.has_near() || right.has_near(),
+        }
+    }
+
+    /// Check the `NEAR/n` constraints of an expression that `evaluate` accepted.
+    /// `term_lines` maps a term index to the lines of the block it matched on.
+    ///
+    /// Every NEAR joined by AND must hold; of an OR, one side that matches on its own
+    /// and whose NEARs hold is enough.
+    pub fn proximity_holds(
+        &self,
+        matched_terms: &HashSet<usize>,
+        term_indices: &HashMap<String, usize>,
+        term_lines: &HashMap<usize, Vec<usize>>,
+    ) -> bool {
+        match self {
+            Expr::Term { .. } => true,
+            Expr::And(left, right) => {
+                left.proximity_holds(matched_terms, term_indices, term_lines)
+                    && right.proximity_holds(matched_terms, term_indices, term_lines)
+            }
+            Expr::Or(..) if !self.has_near() => true,
+            Expr::Or(left, right) => [left, right].iter().any(|side| {
+                side.evaluate(matched_terms, term_indices, false)
+                    && side.proximity_holds(matched_terms, term_indices, term_lines)
+            }),
+            Expr::Near(left, right, distance) => {
+                if !left.proximity_holds(matched_terms, term_indices, term_lines)
+                    || !right.proximity_holds(matched_terms, term_indices, term_lines)
+                {
+                    return false;
+                }
+                let right_lines = right.matched_lines(term_indices, term_lines);
+                left.matched_lines(term_indices, term_lines)
+                    .iter()
+                    .any(|l| right_lines.iter().any(|r| l.abs_diff(*r) <= *distance))
+            }
+        }
+    }
+
+    /// Lines on which any keyword of this expression's included terms matched
+    fn matched_lines(
+        &self,
+        term_indices: &HashMap<String, usize>,
+        term_lines: &HashMap<usize, Vec<usize>>,
+    ) -> Vec<usize> {
+        match self {
+            Expr::Term {
+                lowercase_keywords,
+                excluded: false,
+                ..
+            } => lowercase_keywords
+                .iter()
+                .filter_map(|kw| term_indices.get(kw).and_then(|idx| term_lines.get(idx)))
+                .flatten()
+                .copied()
+                .collect(),
+            Expr::Term { .. } => Vec::new(),
+            Expr::And(left, right) | Expr::Or(left, right) | Expr::Near(left, right, _) => {
+                let mut lines = left.matched_lines(term_indices, term_lines);
+                lines.extend(right.matched_lines(term_indices, term_lines));
+                lines
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for Expr {
@@ -493,6 +611,7 @@ impl std::fmt::Display for Expr {
             }
             Expr::And(left, right) => write!(f, "({left} AND {right})"),
             Expr::Or(left, right) => write!(f, "({left} OR {right})"),
+            Expr::Near(left, right, distance) => write!(f, "({left} NEAR/{distance} {right})"),
         }
     }
 }
@@ -503,6 +622,8 @@ pub enum Token {
     Minus,                // '-'
     And,                  // 'AND'
     Or,                   // 'OR'
+    Not,                  // 'NOT'
+    Near(usize),          // 'NEAR/n'
     LParen,               // '('
     RParen,               // ')'
     Colon,                // ':'
@@ -554,7 +675,7 @@ fn make_term(
 fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
     let mut chars = input.chars().peekable();
     let mut tokens = Vec::new();
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     while let Some(&ch) = chars.peek() {
         match ch {
@@ -580,6 +701,22 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
             ':' => {
                 tokens.push(Token::Colon);
                 chars.next();
+                // Owners like `@org/team` are taken whole, as if quoted, instead of being
+                // split at `@` and `/` and tokenized like search terms
+                if matches!(tokens.iter().rev().nth(1), Some(Token::Ident(field)) if field.eq_ignore_ascii_case("owner") || field.eq_ignore_ascii_case("owners"))
+                {
+                    let mut value = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_whitespace() || c == ')' || c == '"' {
+                            break;
+                        }
+                        value.push(c);
+                        chars.next();
+                    }
+                    if !value.is_empty() {
+                        tokens.push(Token::QuotedString(value));
+                    }
+                }
             }
             '"' => {
                 chars.next(); // consume the opening quote
@@ -588,20 +725,25 @@ fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
             }
             _ => {
                 // If it starts with alphanumeric, underscore, or dot => parse identifier
-                if ch.is_alphanumeric() || ch == '_' || ch == '.' {
+                if is_token_char(ch) || ch == '_' || ch == '.' {
                     let ident = lex_identifier(&mut chars);
                     let ident_upper = ident.to_ascii_uppercase();
                     if ident_upper == "AND" {
                         tokens.push(Token::And);
                     } else if ident_upper == "OR" {
                         tokens.push(Token::Or);
+                    } else if ident == "NOT" {
+                        // Only uppercase, so a natural-language "not" stays a search term
+                        tokens.push(Token::Not);
+                    } else if let Some(distance) = lex_near_distance(&ident, &mut chars) {
+                        tokens.push(Token::Near(distance));
                     } else {
                         tokens.push(Token::Ident(ident));
                     }
                 } else {
                     // Skip unknown characters
                     if debug_mode {
-                        println!("DEBUG: Skipping unknown character '{ch}'");
+                        debug!("Skipping unknown character '{ch}'");
                     }
                     chars.next();
                 }
@@ -642,10 +784,31 @@ fn lex_quoted_string(chars: &mut Peekable<Chars>) -> Result<String, ParseError>
     Err(ParseError::UnexpectedEndOfInput)
 }
 
+/// After an identifier `NEAR`, lex the `/n` of a `NEAR/n` operator and return n.
+/// Anything else leaves the input untouched, so `NEAR` on its own stays a search term.
+fn lex_near_distance(ident: &str, chars: &mut Peekable<Chars>) -> Option<usize> {
+    if ident != "NEAR" || chars.peek() != Some(&'/') {
+        return None;
+    }
+    let mut lookahead = chars.clone();
+    lookahead.next(); // '/'
+    let mut digits = String::new();
+    while let Some(&ch) = lookahead.peek() {
+        if !ch.is_ascii_digit() {
+            break;
+        }
+        digits.push(ch);
+        lookahead.next();
+    }
+    let distance = digits.parse().ok()?;
+    *chars = lookahead;
+    Some(distance)
+}
+
 fn lex_identifier(chars: &mut Peekable<Chars>) -> String {
     let mut buf = String::new();
     while let Some(&ch) = chars.peek() {
-        if ch.is_alphanumeric() || ch == '_' || ch == '.' || ch == '-' {
+        if is_token_char(ch) || ch == '_' || ch == '.' || ch == '-' {
             buf.push(ch);
             chars.next();
         } else {
@@ -656,7 +819,9 @@ fn lex_identifier(chars: &mut Peekable<Chars>) -> String {
 }
 
 // Adjust paths to match your project structure
-use probe_code::search::tokenization::{add_special_term, tokenize as custom_tokenize};
+use probe_code::search::tokenization::{
+    add_special_term, is_token_char, tokenize as custom_tokenize,
+};
 
 struct Parser {
     tokens: Vec<Token>,
@@ -693,9 +858,9 @@ impl Parser {
     }
 
     fn parse_or_expr(&mut self) -> Result<Expr, ParseError> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         if debug_mode {
-            println!("DEBUG: parse_or_expr => pos={pos}", pos = self.pos);
+            debug!("parse_or_expr => pos={pos}", pos = self.pos);
         }
 
         let mut left = self.parse_and_expr()?;
@@ -705,51 +870,51 @@ impl Parser {
             let right = self.parse_and_expr()?;
             left = Expr::Or(Box::new(left), Box::new(right));
             if debug_mode {
-                println!("DEBUG: OR => {left:?}");
+                debug!("OR => {left:?}");
             }
         }
         Ok(left)
     }
 
     fn parse_and_expr(&mut self) -> Result<Expr, ParseError> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
         if debug_mode {
-            println!("DEBUG: parse_and_expr => pos={pos}", pos = self.pos);
+            debug!("parse_and_expr => pos={pos}", pos = self.pos);
         }
 
-        let mut left = self.parse_factor()?;
+        let mut left = self.parse_near_expr()?;
 
         while let Some(token) = self.peek() {
             match token {
                 // Explicit "AND"
                 Token::And => {
                     self.next(); // consume 'AND'
-                    let right = self.parse_factor()?;
+                    let right = self.parse_near_expr()?;
                     left = Expr::And(Box::new(left), Box::new(right));
                     if debug_mode {
-                        println!("DEBUG: AND => {left:?}");
+                        debug!("AND => {left:?}");
                     }
                 }
                 // If we see "OR", break so parse_or_expr can handle it
                 Token::Or => {
                     break;
                 }
-                // If next token is a plus or minus, interpret as an AND
-                Token::Plus | Token::Minus => {
-                    let right = self.parse_factor()?;
+                // If next token is a plus, minus or NOT, interpret as an AND
+                Token::Plus | Token::Minus | Token::Not => {
+                    let right = self.parse_near_expr()?;
                     left = Expr::And(Box::new(left), Box::new(right));
                     if debug_mode {
-                        println!("DEBUG: forced AND => {left:?}");
+                        debug!("forced AND => {left:?}");
                     }
                 }
                 // Otherwise (Ident, QuotedString, LParen) => implicit combos
                 Token::Ident(_) | Token::QuotedString(_) | Token::LParen => {
-                    let right = self.parse_factor()?;
+                    let right = self.parse_near_expr()?;
                     // True Lucene/Elasticsearch semantics: implicit combinations are always OR
                     // The + and - operators only affect individual terms, not the combination logic
                     left = Expr::Or(Box::new(left), Box::new(right));
                     if debug_mode {
-                        println!("DEBUG: implicit OR => {left:?}");
+                        debug!("implicit OR => {left:?}");
                     }
                 }
                 _ => break,
@@ -759,8 +924,33 @@ impl Parser {
         Ok(left)
     }
 
+    /// `NEAR/n` binds tighter than AND and OR: `a b NEAR/3 c` is `a OR (b NEAR/3 c)`
+    fn parse_near_expr(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_factor()?;
+
+        while let Some(&Token::Near(distance)) = self.peek() {
+            self.next(); // consume 'NEAR/n'
+            let right = self.parse_factor()?;
+            left = Expr::Near(Box::new(left), Box::new(right), distance);
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                debug!("NEAR => {left:?}");
+            }
+        }
+        Ok(left)
+    }
+
     fn parse_factor(&mut self) -> Result<Expr, ParseError> {
         match self.peek() {
+            // Negated group, e.g. `NOT (a OR b)` or `-(a b)`
+            Some(Token::Not | Token::Minus)
+                if matches!(
+                    self.tokens.get(self.pos + 1),
+                    Some(Token::LParen | Token::Not)
+                ) =>
+            {
+                self.next();
+                Ok(self.parse_factor()?.negate())
+            }
             Some(Token::LParen) => {
                 self.next(); // consume '('
                 let expr = self.parse_expr()?;
@@ -774,14 +964,14 @@ impl Parser {
     fn parse_prefixed_term(&mut self) -> Result<Expr, ParseError> {
         let mut required = false;
         let mut excluded = false;
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         match self.peek() {
             Some(Token::Plus) => {
                 required = true;
                 self.next();
             }
-            Some(Token::Minus) => {
+            Some(Token::Minus | Token::Not) => {
                 excluded = true;
                 self.next();
             }
@@ -799,8 +989,8 @@ impl Parser {
             ..
         } = primary_expr
         {
-            // If exact or excluded => skip further tokenization
-            let final_keywords = if exact || excluded {
+            // If exact => skip further tokenization
+            let final_keywords = if exact {
                 // Mark them special (no splitting)
                 for kw in &keywords {
                     add_special_term(kw);
@@ -820,10 +1010,13 @@ impl Parser {
             };
 
             if debug_mode {
-                println!("DEBUG: parse_prefixed_term => required={required}, excluded={excluded}, final_keywords={final_keywords:?}");
+                debug!("parse_prefixed_term => required={required}, excluded={excluded}, final_keywords={final_keywords:?}");
             }
 
-            Ok(make_term(final_keywords, field, required, excluded, exact))
+            // An excluded term is split like any other and then negated, so `-userName`
+            // and `NOT userName` mean the same as `NOT (userName)`
+            let term = make_term(final_keywords, field, required, false, exact);
+            Ok(if excluded { term.negate() } else { term })
         } else {
             // If it's a sub-expression in parentheses or something else, just return it
             Ok(primary_expr)
@@ -831,7 +1024,7 @@ impl Parser {
     }
 
     fn parse_primary(&mut self) -> Result<Expr, ParseError> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         match self.peek() {
             // Quoted => exact
@@ -839,7 +1032,7 @@ impl Parser {
                 let val = s.clone();
                 self.next();
                 if debug_mode {
-                    println!("DEBUG: QuotedString => {val}");
+                    debug!("QuotedString => {val}");
                 }
                 Ok(make_term(vec![val], None, false, false, true))
             }
@@ -849,7 +1042,7 @@ impl Parser {
                     unreachable!()
                 };
                 if debug_mode {
-                    println!("DEBUG: Ident => {first}");
+                    debug!("Ident => {first}");
                 }
                 if let Some(Token::Colon) = self.peek() {
                     // We have "field:"
@@ -882,16 +1075,16 @@ impl Parser {
 
 /// Parse the query string into an AST
 pub fn parse_query(input: &str, exact: bool) -> Result<Expr, ParseError> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!("DEBUG: parse_query('{input}', exact={exact})");
+        debug!("parse_query('{input}', exact={exact})");
     }
 
     // If exact search is enabled, treat the entire query as a single term
     if exact {
         if debug_mode {
-            println!("DEBUG: Exact search enabled, treating query as a single term");
+            debug!("Exact search enabled, treating query as a single term");
         }
         return Ok(make_term(vec![input.to_string()], None, false, false, true));
     }
@@ -899,7 +1092,7 @@ pub fn parse_query(input: &str, exact: bool) -> Result<Expr, ParseError> {
     // Tokenize
     let tokens_result = tokenize(input);
     if debug_mode {
-        println!("DEBUG: Tokens => {tokens_result:?}");
+        debug!("Tokens => {tokens_result:?}");
     }
 
     // If tokenization fails => fallback
@@ -947,6 +1140,26 @@ pub fn parse_query(input: &str, exact: bool) -> Result<Expr, ParseError> {
     Ok(parsed.unwrap())
 }
 
+/// The plain words of a query as written, before tokenization lowercases and splits
+/// them. Operators, quoted strings and `field:value` hints are left out.
+pub fn written_words(input: &str) -> Vec<String> {
+    let Ok(tokens) = tokenize(input) else {
+        return Vec::new();
+    };
+    tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(i, token)| {
+            let in_hint = matches!(tokens.get(i + 1), Some(Token::Colon))
+                || (i > 0 && matches!(tokens[i - 1], Token::Colon));
+            match token {
+                Token::Ident(word) if !in_hint => Some(word.clone()),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
 /// Backward compatibility wrapper for parse_query
 #[allow(dead_code)]
 pub fn parse_query_compat(input: &str) -> Result<Expr, ParseError> {
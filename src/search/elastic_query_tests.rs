@@ -192,6 +192,113 @@ fn test_single_terms() {
     assert_parse_eq("-foo", excluded_term("foo"));
 }
 
+#[test]
+fn test_not_operator() {
+    // NOT excludes like a leading '-'
+    assert_parse_eq("NOT foo", excluded_term("foo"));
+    assert_parse_eq(
+        "foo AND NOT bar",
+        Expr::And(Box::new(term("foo")), Box::new(excluded_term("bar"))),
+    );
+    assert_parse_eq(
+        "foo NOT bar",
+        Expr::And(Box::new(term("foo")), Box::new(excluded_term("bar"))),
+    );
+
+    // Negated groups follow De Morgan's laws
+    assert_parse_eq(
+        "foo AND NOT (bar OR baz)",
+        Expr::And(
+            Box::new(term("foo")),
+            Box::new(Expr::And(
+                Box::new(excluded_term("bar")),
+                Box::new(excluded_term("baz")),
+            )),
+        ),
+    );
+    assert_parse_eq(
+        "foo -(bar AND -baz)",
+        Expr::And(
+            Box::new(term("foo")),
+            Box::new(Expr::Or(Box::new(excluded_term("bar")), Box::new(term("baz")))),
+        ),
+    );
+
+    // A compound term under NOT is negated as a whole: blocks with only one of its
+    // parts still match
+    let ast = parse_query_test("NOT (userName)").unwrap();
+    assert_eq!(
+        ast,
+        Expr::Or(
+            Box::new(excluded_term("user")),
+            Box::new(excluded_term("name"))
+        )
+    );
+    let term_indices: HashMap<String, usize> =
+        [("user".to_string(), 0), ("name".to_string(), 1)].into();
+    assert!(ast.evaluate(&HashSet::from([0]), &term_indices, false));
+    assert!(ast.evaluate(&HashSet::new(), &term_indices, false));
+    assert!(!ast.evaluate(&HashSet::from([0, 1]), &term_indices, false));
+
+    // Every spelling of a negated compound term means the same
+    assert_eq!(parse_query_test("NOT userName").unwrap(), ast);
+    assert_eq!(parse_query_test("-userName").unwrap(), ast);
+
+    // Lowercase "not" is an ordinary (stop) word, not an operator
+    assert!(matches!(
+        parse_query_test("foo not").unwrap(),
+        Expr::Or(_, right) if matches!(*right, Expr::Term { excluded: false, .. })
+    ));
+}
+
+#[test]
+fn test_near_operator() {
+    let ast = parse_query_test("config NEAR/5 reload").unwrap();
+    assert_eq!(
+        ast,
+        Expr::Near(Box::new(term("config")), Box::new(term("reload")), 5)
+    );
+    assert_eq!(ast.to_string(), "(config NEAR/5 reload)");
+
+    // NEAR binds tighter than implicit OR and AND
+    let ast = parse_query_test("watch config NEAR/2 reload AND file").unwrap();
+    assert_eq!(
+        ast,
+        Expr::And(
+            Box::new(Expr::Or(
+                Box::new(term("watch")),
+                Box::new(Expr::Near(
+                    Box::new(term("config")),
+                    Box::new(term("reload")),
+                    2
+                )),
+            )),
+            Box::new(term("file")),
+        )
+    );
+
+    // Without a distance, NEAR is just a word
+    assert!(!parse_query_test("config NEAR reload").unwrap().has_near());
+
+    // Lines: config on 2 and 10, reload on 7
+    let term_indices: HashMap<String, usize> = [("config".to_string(), 0), ("reload".to_string(), 1)]
+        .into_iter()
+        .collect();
+    let matched_terms: HashSet<usize> = [0, 1].into_iter().collect();
+    let term_lines: HashMap<usize, Vec<usize>> =
+        [(0, vec![2, 10]), (1, vec![7])].into_iter().collect();
+    let holds = |query: &str| {
+        parse_query_test(query)
+            .unwrap()
+            .proximity_holds(&matched_terms, &term_indices, &term_lines)
+    };
+    assert!(holds("config NEAR/3 reload"));
+    assert!(!holds("config NEAR/2 reload"));
+    // A matching OR branch without NEAR is enough
+    assert!(holds("(config NEAR/2 reload) OR reload"));
+    assert!(!holds("(config NEAR/2 reload) OR missing"));
+}
+
 #[test]
 fn test_multiple_terms_implicit_combinations() {
     // Simple two terms without modifiers - using OR for implicit combinations
@@ -39,6 +39,12 @@ fn process_ast_terms(expr: Expr) -> Expr {
                 Box::new(process_ast_terms(*left)),
                 Box::new(process_ast_terms(*right))
             )
+        },        Expr::Near(left, right, distance) => {
+            Expr::Near(
+                Box::new(process_ast_terms(*left)),
+                Box::new(process_ast_terms(*right)),
+                distance
+            )
         },
     }
 }
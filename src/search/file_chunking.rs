@@ -0,0 +1,155 @@
+//! Splitting of whole-file results for `--no-whole-files`.
+//!
+//! Small files, files without a parser and filename matches often come back as one
+//! result spanning the entire file, and a single such result can use up a token budget.
+//! With `--no-whole-files` these results are cut into chunks of consecutive lines, which
+//! ranking and limits then take or leave one at a time.
+
+use probe_code::models::SearchResult;
+use std::path::Path;
+
+/// Lines per chunk; shorter files are split in half
+const CHUNK_LINES: usize = 40;
+
+/// Replace every result spanning its entire file with chunks of it. With `matched_only`,
+/// a result that records its matched lines keeps only the chunks containing one.
+pub fn chunk_whole_files(results: Vec<SearchResult>, matched_only: bool) -> Vec<SearchResult> {
+    let mut chunked = Vec::with_capacity(results.len());
+    for result in results {
+        if spans_whole_file(&result) {
+            chunked.extend(chunk_result(&result, matched_only));
+        } else {
+            chunked.push(result);
+        }
+    }
+    chunked
+}
+
+fn spans_whole_file(result: &SearchResult) -> bool {
+    if result.node_type == "file" {
+        return true;
+    }
+    if result.lines.0 > 1 {
+        return false;
+    }
+    std::fs::read_to_string(&result.file)
+        .is_ok_and(|content| result.lines.1 >= content.lines().count())
+}
+
+fn chunk_result(result: &SearchResult, matched_only: bool) -> Vec<SearchResult> {
+    let lines: Vec<&str> = result.code.lines().collect();
+    if lines.len() < 2 {
+        return vec![result.clone()];
+    }
+    let chunk_len = CHUNK_LINES.min(lines.len().div_ceil(2));
+    let filename = Path::new(&result.file)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut chunks = Vec::new();
+    for (index, chunk_lines) in lines.chunks(chunk_len).enumerate() {
+        let offset = index * chunk_len;
+        let range = offset..offset + chunk_lines.len();
+        let matched_lines = result.matched_lines.as_ref().map(|matched| {
+            matched
+                .iter()
+                .filter(|line| range.contains(line))
+                .map(|line| line - offset)
+                .collect::<Vec<_>>()
+        });
+        if matched_only && matched_lines.as_ref().is_some_and(|lines| lines.is_empty()) {
+            continue;
+        }
+
+        // The chunk shares the buffer of the whole-file block
+        let base = result.code.as_ptr() as usize;
+        let last = chunk_lines[chunk_lines.len() - 1];
+        let start = chunk_lines[0].as_ptr() as usize - base;
+        let end = last.as_ptr() as usize - base + last.len();
+        let code = result.code.slice(start..end);
+        let mut chunk = result.clone();
+        chunk.lines = (
+            result.lines.0 + offset,
+            result.lines.0 + offset + chunk_lines.len() - 1,
+        );
+        chunk.node_type = "file_chunk".to_string();
+        chunk.symbol_signature = None;
+        chunk.parent_context = None;
+        chunk.matched_lines = matched_lines.filter(|lines| !lines.is_empty());
+        if chunk.tokenized_content.is_some() {
+            chunk.tokenized_content = Some(probe_code::ranking::preprocess_text_with_filename(
+                &code, &filename,
+            ));
+        }
+        chunk.code = code;
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_result(line_count: usize, matched_lines: Option<Vec<usize>>) -> SearchResult {
+        let code: Vec<String> = (1..=line_count).map(|i| format!("line {i}")).collect();
+        SearchResult {
+            file: "does-not-exist.txt".to_string(),
+            lines: (1, line_count),
+            node_type: "file".to_string(),
+            code: code.join("\n").into(),
+            symbol_signature: None,
+            matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
+            rank: None,
+            score: None,
+            tfidf_score: None,
+            bm25_score: None,
+            tfidf_rank: None,
+            bm25_rank: None,
+            new_score: None,
+            hybrid2_rank: None,
+            combined_score_rank: None,
+            file_unique_terms: None,
+            file_total_matches: None,
+            file_match_rank: None,
+            block_unique_terms: None,
+            block_total_matches: None,
+            parent_file_id: None,
+            block_id: None,
+            matched_keywords: None,
+            matched_lines,
+            tokenized_content: None,
+            parent_context: None,
+        }
+    }
+
+    #[test]
+    fn test_whole_file_is_chunked() {
+        let chunks = chunk_whole_files(vec![file_result(100, None)], true);
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|chunk| chunk.lines).collect();
+        assert_eq!(ranges, vec![(1, 40), (41, 80), (81, 100)]);
+        assert!(chunks.iter().all(|chunk| chunk.node_type == "file_chunk"));
+        assert!(chunks[1].code.starts_with("line 41\n"));
+    }
+
+    #[test]
+    fn test_only_matched_chunks_are_kept() {
+        let chunks = chunk_whole_files(vec![file_result(100, Some(vec![45, 90]))], true);
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|chunk| chunk.lines).collect();
+        assert_eq!(ranges, vec![(41, 80), (81, 100)]);
+        assert_eq!(chunks[0].matched_lines, Some(vec![5]));
+        assert_eq!(chunks[1].matched_lines, Some(vec![10]));
+    }
+
+    #[test]
+    fn test_small_file_is_split_in_half() {
+        let chunks = chunk_whole_files(vec![file_result(5, None)], false);
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|chunk| chunk.lines).collect();
+        assert_eq!(ranges, vec![(1, 3), (4, 5)]);
+    }
+}
@@ -0,0 +1,585 @@
+//! On-disk file index kept current by `probe index --watch`.
+//!
+//! Walking a large repository (reading every .gitignore on the way) is a fixed cost paid
+//! by every search. `probe index` stores the walked file list, with each file's size and
+//! modification time, under `~/.cache/probe/index/`. With `--watch` it stays running and
+//! applies filesystem events to the index, re-walking only the changed paths, so searches
+//! can take the file list from the index instead of walking the tree.
+//!
+//! An index is only trusted while its watcher is alive: the watcher touches a heartbeat
+//! file every few seconds, and searches fall back to walking when the heartbeat is stale.
+//!
+//! Next to the file list the index stores a trigram filter of each file (see
+//! `trigram_filter`), which searches use with or without a watcher to skip reading files
+//! that cannot match. Each filter records the size and modification time of the content
+//! it was built from, and a file that has changed since is always read.
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use probe_code::progress::Progress;
+use probe_code::search::file_list_cache::{configure_walker, generate_cache_key};
+use probe_code::search::trigram_filter::{LiteralFilter, TrigramBloom};
+
+/// How often a watcher refreshes its heartbeat
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A heartbeat older than this means the watcher is gone and the index may be stale
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Files larger than this get no trigram filter and are always read
+const MAX_FILTERED_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Start of a trigram filter file, with the version of its layout
+const TRIGRAMS_MAGIC: &[u8; 8] = b"PRBTRI1\n";
+
+/// Size and modification time of an indexed file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedFile {
+    pub size: u64,
+    /// Seconds since the Unix epoch
+    pub mtime: u64,
+}
+
+/// The trigram filter of a file, with the size and modification time (in nanoseconds
+/// since the Unix epoch) of the content it was built from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTrigrams {
+    pub size: u64,
+    pub mtime_nanos: u64,
+    pub bloom: TrigramBloom,
+}
+
+/// The indexed files of one root, for one set of walk options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileIndex {
+    /// Canonical root directory
+    pub root: PathBuf,
+    pub allow_tests: bool,
+    pub custom_ignores: Vec<String>,
+    pub no_gitignore: bool,
+    /// Files keyed by their path relative to `root`
+    pub files: BTreeMap<PathBuf, IndexedFile>,
+    /// Trigram filters keyed like `files`; stored in a separate binary file
+    #[serde(skip)]
+    pub trigrams: BTreeMap<PathBuf, FileTrigrams>,
+}
+
+/// Counts of what an incremental update changed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexUpdate {
+    pub added: usize,
+    pub modified: usize,
+    pub removed: usize,
+}
+
+impl IndexUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.added == 0 && self.modified == 0 && self.removed == 0
+    }
+}
+
+/// The directory holding all file indexes
+fn index_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("index")
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn nanos_since_epoch(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+fn indexed_file(path: &Path) -> Option<IndexedFile> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(IndexedFile {
+        size: metadata.len(),
+        mtime: metadata.modified().map(seconds_since_epoch).unwrap_or(0),
+    })
+}
+
+/// Build the trigram filter of a file. Its metadata is read first, so a write during
+/// the read leaves the filter with an outdated modification time and it goes unused.
+fn file_trigrams(path: &Path) -> Option<FileTrigrams> {
+    let metadata = fs::metadata(path).ok()?;
+    if metadata.len() > MAX_FILTERED_SIZE {
+        return None;
+    }
+    let content = fs::read(path).ok()?;
+    Some(FileTrigrams {
+        size: metadata.len(),
+        mtime_nanos: nanos_since_epoch(&metadata),
+        bloom: TrigramBloom::from_content(&content),
+    })
+}
+
+/// Index `paths` under `root`: each file's entry, and its trigram filter if it has one
+type IndexedPaths = Vec<(PathBuf, IndexedFile, Option<FileTrigrams>)>;
+
+fn index_paths(root: &Path, paths: &[PathBuf], progress: Option<&Progress>) -> IndexedPaths {
+    paths
+        .par_iter()
+        .filter_map(|path| {
+            if let Some(progress) = progress {
+                progress.inc();
+            }
+            let entry = indexed_file(path)?;
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            Some((relative, entry, file_trigrams(path)))
+        })
+        .collect()
+}
+
+impl FileIndex {
+    /// Walk `root` with the given options and index every file found
+    pub fn build(
+        root: &Path,
+        allow_tests: bool,
+        custom_ignores: &[String],
+        no_gitignore: bool,
+    ) -> Result<Self> {
+        let root = fs::canonicalize(root)
+            .with_context(|| format!("Failed to resolve index root {}", root.display()))?;
+        let mut index = FileIndex {
+            root,
+            allow_tests,
+            custom_ignores: custom_ignores.to_vec(),
+            no_gitignore,
+            files: BTreeMap::new(),
+            trigrams: BTreeMap::new(),
+        };
+        let paths = index.walk(&[]);
+        let progress = Progress::new("Indexing files", paths.len());
+        for (relative, entry, trigrams) in index_paths(&index.root, &paths, Some(&progress)) {
+            if let Some(trigrams) = trigrams {
+                index.trigrams.insert(relative.clone(), trigrams);
+            }
+            index.files.insert(relative, entry);
+        }
+        Ok(index)
+    }
+
+    /// Walk the root, descending only into `focus` paths (and their ancestors) when
+    /// any are given, so ignore rules are applied exactly as in a full walk
+    fn walk(&self, focus: &[PathBuf]) -> Vec<PathBuf> {
+        let mut builder = configure_walker(
+            &self.root,
+            self.allow_tests,
+            &self.custom_ignores,
+            self.no_gitignore,
+        );
+        if !focus.is_empty() {
+            let focus = focus.to_vec();
+            builder.filter_entry(move |entry| {
+                let path = entry.path();
+                focus
+                    .iter()
+                    .any(|f| path.starts_with(f) || f.starts_with(path))
+            });
+        }
+        builder
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+            .map(|entry| entry.into_path())
+            .filter(|path| focus.is_empty() || focus.iter().any(|f| path.starts_with(f)))
+            .collect()
+    }
+
+    /// Re-index the given changed paths (files or directories, created, modified or
+    /// removed) without walking the rest of the tree
+    pub fn update_paths(&mut self, changed: &[PathBuf]) -> IndexUpdate {
+        let changed: Vec<PathBuf> = changed
+            .iter()
+            .filter(|path| path.starts_with(&self.root))
+            .cloned()
+            .collect();
+        let mut update = IndexUpdate::default();
+        if changed.is_empty() {
+            return update;
+        }
+
+        let found: BTreeMap<PathBuf, (IndexedFile, Option<FileTrigrams>)> =
+            index_paths(&self.root, &self.walk(&changed), None)
+                .into_iter()
+                .map(|(relative, entry, trigrams)| (relative, (entry, trigrams)))
+                .collect();
+
+        let stale: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|path| {
+                !found.contains_key(*path)
+                    && changed.iter().any(|c| self.root.join(path).starts_with(c))
+            })
+            .cloned()
+            .collect();
+        for path in stale {
+            self.files.remove(&path);
+            self.trigrams.remove(&path);
+            update.removed += 1;
+        }
+        for (path, (entry, trigrams)) in found {
+            match trigrams {
+                Some(trigrams) => self.trigrams.insert(path.clone(), trigrams),
+                None => self.trigrams.remove(&path),
+            };
+            match self.files.insert(path, entry.clone()) {
+                None => update.added += 1,
+                Some(previous) if previous != entry => update.modified += 1,
+                Some(_) => {}
+            }
+        }
+        update
+    }
+
+    /// Where the index for these walk options is stored
+    fn storage_path(
+        root: &Path,
+        allow_tests: bool,
+        custom_ignores: &[String],
+        no_gitignore: bool,
+    ) -> PathBuf {
+        let key = generate_cache_key(root, allow_tests, custom_ignores, no_gitignore);
+        index_dir().join(format!("{:x}.json", md5::compute(key.as_bytes())))
+    }
+
+    fn heartbeat_path(storage: &Path) -> PathBuf {
+        storage.with_extension("alive")
+    }
+
+    fn trigrams_path(storage: &Path) -> PathBuf {
+        storage.with_extension("trigrams")
+    }
+
+    /// Write the index to disk, replacing any previous version atomically
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = Self::storage_path(
+            &self.root,
+            self.allow_tests,
+            &self.custom_ignores,
+            self.no_gitignore,
+        );
+        fs::create_dir_all(index_dir()).context("Failed to create index directory")?;
+        let trigrams = Self::trigrams_path(&path);
+        let temp = trigrams.with_extension("trigrams.tmp");
+        fs::write(&temp, encode_trigrams(&self.trigrams))
+            .with_context(|| format!("Failed to write index {}", temp.display()))?;
+        fs::rename(&temp, &trigrams)
+            .with_context(|| format!("Failed to write index {}", trigrams.display()))?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_vec(self)?)
+            .with_context(|| format!("Failed to write index {}", temp.display()))?;
+        fs::rename(&temp, &path)
+            .with_context(|| format!("Failed to write index {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Mark the index as kept current by a running watcher
+    pub fn touch_heartbeat(&self) -> Result<()> {
+        let storage = Self::storage_path(
+            &self.root,
+            self.allow_tests,
+            &self.custom_ignores,
+            self.no_gitignore,
+        );
+        fs::write(
+            Self::heartbeat_path(&storage),
+            std::process::id().to_string(),
+        )
+        .context("Failed to update index heartbeat")
+    }
+
+    /// Remove the heartbeat so searches stop trusting the index right away
+    pub fn clear_heartbeat(&self) {
+        let storage = Self::storage_path(
+            &self.root,
+            self.allow_tests,
+            &self.custom_ignores,
+            self.no_gitignore,
+        );
+        let _ = fs::remove_file(Self::heartbeat_path(&storage));
+    }
+}
+
+/// Whether the watcher of the index stored at `storage` is running
+fn watcher_alive(storage: &Path) -> bool {
+    fs::metadata(FileIndex::heartbeat_path(storage))
+        .and_then(|m| m.modified())
+        .is_ok_and(|heartbeat| heartbeat.elapsed().unwrap_or(Duration::MAX) <= HEARTBEAT_TIMEOUT)
+}
+
+/// Whether searches can use an index for `path` with a given set of walk options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexState {
+    /// No index was built with these options
+    Missing,
+    /// An index exists but no watcher keeps it current, so searches walk the tree
+    Stale,
+    /// A running watcher keeps the index current
+    Live,
+}
+
+pub fn index_state(
+    path: &Path,
+    allow_tests: bool,
+    custom_ignores: &[String],
+    no_gitignore: bool,
+) -> IndexState {
+    let Ok(root) = fs::canonicalize(path) else {
+        return IndexState::Missing;
+    };
+    let storage = FileIndex::storage_path(&root, allow_tests, custom_ignores, no_gitignore);
+    if !storage.exists() {
+        IndexState::Missing
+    } else if watcher_alive(&storage) {
+        IndexState::Live
+    } else {
+        IndexState::Stale
+    }
+}
+
+/// The file list for `path` from a watched index, or `None` if there is no index for
+/// these options or its watcher is not running. Paths are joined onto `path` as given,
+/// so results look the same as with a directory walk.
+pub fn live_file_list(
+    path: &Path,
+    allow_tests: bool,
+    custom_ignores: &[String],
+    no_gitignore: bool,
+) -> Option<Vec<PathBuf>> {
+    let root = fs::canonicalize(path).ok()?;
+    let storage = FileIndex::storage_path(&root, allow_tests, custom_ignores, no_gitignore);
+    if !watcher_alive(&storage) {
+        return None;
+    }
+    let index: FileIndex = serde_json::from_slice(&fs::read(&storage).ok()?).ok()?;
+    if index.root != root {
+        return None;
+    }
+    Some(index.files.keys().map(|file| path.join(file)).collect())
+}
+
+/// Trigram filters of an index, for ruling out files before a search reads them
+#[derive(Debug)]
+pub struct TrigramFilters {
+    root: PathBuf,
+    files: HashMap<PathBuf, FileTrigrams>,
+}
+
+impl TrigramFilters {
+    /// Whether `file`, as walked from the search path the filters were loaded for, may
+    /// match. Files without a filter, or changed since theirs was built, may.
+    pub fn may_match(&self, file: &Path, filter: &LiteralFilter) -> bool {
+        let Some(trigrams) = file
+            .strip_prefix(&self.root)
+            .ok()
+            .and_then(|relative| self.files.get(relative))
+        else {
+            return true;
+        };
+        let Ok(metadata) = fs::metadata(file) else {
+            return true;
+        };
+        if metadata.len() != trigrams.size || nanos_since_epoch(&metadata) != trigrams.mtime_nanos {
+            return true;
+        }
+        filter.may_match(&trigrams.bloom)
+    }
+}
+
+/// Filters loaded by this process: (filter file, its modification time, filters)
+type LoadedFilters = (PathBuf, SystemTime, Arc<TrigramFilters>);
+
+static LOADED_FILTERS: Mutex<Option<LoadedFilters>> = Mutex::new(None);
+
+/// The trigram filters of the index for `path` with these walk options, or `None` if
+/// no index was built with them. Unlike the file list, filters are used whether or not
+/// a watcher is running, since each one is checked against its file's metadata.
+pub fn trigram_filters(
+    path: &Path,
+    allow_tests: bool,
+    custom_ignores: &[String],
+    no_gitignore: bool,
+) -> Option<Arc<TrigramFilters>> {
+    let root = fs::canonicalize(path).ok()?;
+    let storage = FileIndex::storage_path(&root, allow_tests, custom_ignores, no_gitignore);
+    let trigrams_path = FileIndex::trigrams_path(&storage);
+    let modified = fs::metadata(&trigrams_path).ok()?.modified().ok()?;
+
+    let mut loaded = LOADED_FILTERS.lock().unwrap();
+    if let Some((loaded_path, loaded_modified, filters)) = loaded.as_ref() {
+        if *loaded_path == trigrams_path && *loaded_modified == modified && filters.root == path {
+            return Some(Arc::clone(filters));
+        }
+    }
+    let files = decode_trigrams(&fs::read(&trigrams_path).ok()?).ok()?;
+    let filters = Arc::new(TrigramFilters {
+        root: path.to_path_buf(),
+        files,
+    });
+    *loaded = Some((trigrams_path, modified, Arc::clone(&filters)));
+    Some(filters)
+}
+
+/// Lay out trigram filters as: magic, then per file the path length and path (UTF-8),
+/// size, modification time, number of filter words and the words, all little-endian
+fn encode_trigrams(trigrams: &BTreeMap<PathBuf, FileTrigrams>) -> Vec<u8> {
+    let mut out = TRIGRAMS_MAGIC.to_vec();
+    for (path, file) in trigrams {
+        let path = path.to_string_lossy();
+        out.extend_from_slice(&(path.len() as u32).to_le_bytes());
+        out.extend_from_slice(path.as_bytes());
+        out.extend_from_slice(&file.size.to_le_bytes());
+        out.extend_from_slice(&file.mtime_nanos.to_le_bytes());
+        out.extend_from_slice(&(file.bloom.words().len() as u32).to_le_bytes());
+        for word in file.bloom.words() {
+            out.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    out
+}
+
+fn decode_trigrams(data: &[u8]) -> io::Result<HashMap<PathBuf, FileTrigrams>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid trigram filter file");
+    let mut reader = data
+        .strip_prefix(TRIGRAMS_MAGIC.as_slice())
+        .ok_or_else(invalid)?;
+    let mut files = HashMap::new();
+    while !reader.is_empty() {
+        let path_len = read_u32(&mut reader)? as usize;
+        let mut path = vec![0; path_len];
+        reader.read_exact(&mut path)?;
+        let path = PathBuf::from(String::from_utf8(path).map_err(|_| invalid())?);
+        let size = read_u64(&mut reader)?;
+        let mtime_nanos = read_u64(&mut reader)?;
+        let word_count = read_u32(&mut reader)? as usize;
+        if word_count * 8 > reader.len() {
+            return Err(invalid());
+        }
+        let words = (0..word_count)
+            .map(|_| read_u64(&mut reader))
+            .collect::<io::Result<Vec<u64>>>()?;
+        let bloom = TrigramBloom::from_words(words).ok_or_else(invalid)?;
+        files.insert(
+            path,
+            FileTrigrams {
+                size,
+                mtime_nanos,
+                bloom,
+            },
+        );
+    }
+    Ok(files)
+}
+
+fn read_u32(reader: &mut &[u8]) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut &[u8]) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_build_respects_ignores() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "src/lib.rs", "fn a() {}");
+        write(temp.path(), "node_modules/pkg/index.js", "x");
+        write(temp.path(), "src/lib_test.rs", "fn t() {}");
+
+        let index = FileIndex::build(temp.path(), false, &[], false).unwrap();
+        let files: Vec<&PathBuf> = index.files.keys().collect();
+        assert_eq!(files, vec![&PathBuf::from("src/lib.rs")]);
+    }
+
+    #[test]
+    fn test_trigram_filters_skip_only_unchanged_files() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "src/parser.rs", "fn parse_tokens() {}");
+        write(temp.path(), "src/lexer.rs", "fn next_char() {}");
+        let index = FileIndex::build(temp.path(), false, &[], false).unwrap();
+        let filters = TrigramFilters {
+            root: temp.path().to_path_buf(),
+            files: decode_trigrams(&encode_trigrams(&index.trigrams)).unwrap(),
+        };
+        let literals = LiteralFilter::new(&[r"(?i)\bparse_tokens".to_string()]).unwrap();
+        let parser = temp.path().join("src/parser.rs");
+        let lexer = temp.path().join("src/lexer.rs");
+        assert!(filters.may_match(&parser, &literals));
+        assert!(!filters.may_match(&lexer, &literals));
+
+        // A file changed since indexing is read again
+        write(
+            temp.path(),
+            "src/lexer.rs",
+            "fn next_char() { parse_tokens() }",
+        );
+        assert!(filters.may_match(&lexer, &literals));
+    }
+
+    #[test]
+    fn test_update_paths_only_touches_changed_paths() {
+        let temp = TempDir::new().unwrap();
+        write(temp.path(), "src/a.rs", "fn a() {}");
+        write(temp.path(), "src/old/b.rs", "fn b() {}");
+        let mut index = FileIndex::build(temp.path(), false, &[], false).unwrap();
+        let root = index.root.clone();
+
+        write(temp.path(), "src/new/c.rs", "fn c() {}");
+        write(temp.path(), "src/a.rs", "fn a() { /* longer */ }");
+        write(temp.path(), "target/debug/out.rs", "ignored");
+        fs::remove_dir_all(temp.path().join("src/old")).unwrap();
+
+        let update = index.update_paths(&[
+            root.join("src/new"),
+            root.join("src/a.rs"),
+            root.join("src/old"),
+            root.join("target/debug/out.rs"),
+        ]);
+        assert_eq!(
+            update,
+            IndexUpdate {
+                added: 1,
+                modified: 1,
+                removed: 1
+            }
+        );
+        let files: Vec<&PathBuf> = index.files.keys().collect();
+        assert_eq!(
+            files,
+            vec![&PathBuf::from("src/a.rs"), &PathBuf::from("src/new/c.rs")]
+        );
+    }
+}
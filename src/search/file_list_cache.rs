@@ -1,17 +1,75 @@
 use anyhow::Result;
 use ignore::WalkBuilder;
 use lazy_static::lazy_static;
-use probe_code::search::tokenization;
+use probe_code::interrupt;
+use probe_code::progress::Progress;
+use probe_code::search::{archives, file_index, notebooks, tokenization};
+use probe_code::warnings::{warn, WarningKind};
 use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 use std::time::Instant;
+use tracing::debug;
+
+/// Ignore file in gitignore syntax that only probe reads, for trees a project wants kept
+/// out of searches but not out of git
+pub const PROBEIGNORE_FILENAME: &str = ".probeignore";
+
+/// Directory traversal settings from `--max-depth` and `--hidden`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Descend at most this many directories below the search root
+    pub max_depth: Option<usize>,
+    /// Walk dotfiles and dot-directories, which are skipped by default
+    pub hidden: bool,
+    /// Skip files larger than this many bytes
+    pub max_filesize: Option<u64>,
+    /// Search binary files too, which are skipped by default
+    pub binary: bool,
+    /// Search the members of zip, jar and tar.gz archives
+    pub search_archives: bool,
+}
+
+static WALK_OPTIONS: OnceLock<WalkOptions> = OnceLock::new();
+
+/// Set the traversal settings of the directory walks in this process.
+///
+/// Returns `false` if they were already set.
+pub fn set_walk_options(options: WalkOptions) -> bool {
+    WALK_OPTIONS.set(options).is_ok()
+}
+
+/// The traversal settings set with [`set_walk_options`], else the defaults
+pub fn walk_options() -> WalkOptions {
+    WALK_OPTIONS.get().copied().unwrap_or_default()
+}
+
+/// Limit the depth of `builder` and skip hidden entries according to [`walk_options`]
+pub fn apply_walk_options(builder: &mut WalkBuilder) {
+    let options = walk_options();
+    builder.max_depth(options.max_depth).hidden(!options.hidden);
+}
+
+/// Bytes read from the start of a file to decide whether it is binary
+const BINARY_SNIFF_BYTES: u64 = 8192;
+
+/// Whether a file looks binary: a NUL byte near its start
+pub fn is_binary_file(path: &Path) -> bool {
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES as usize);
+    File::open(path)
+        .and_then(|file| file.take(BINARY_SNIFF_BYTES).read_to_end(&mut head))
+        .is_ok_and(|_| head.contains(&0))
+}
 
 /// A struct to hold the cached file list for a specific directory
 #[derive(Debug, Clone)]
 pub struct FileList {
     /// The list of files in the directory (respecting ignore patterns)
     pub files: Vec<PathBuf>,
+    /// Files left out for being larger than `--max-filesize`, with their sizes
+    pub large_files: Vec<(PathBuf, u64)>,
     /// When this cache was created
     #[allow(dead_code)]
     pub created_at: Instant,
@@ -23,6 +81,12 @@ lazy_static! {
         RwLock::new(HashMap::new());
 }
 
+/// Forget every cached file list, so the next walk re-reads the tree and its ignore files.
+/// Long-running processes call this when reloading, since cached lists never expire.
+pub fn clear_file_list_cache() {
+    FILE_LIST_CACHE.write().unwrap().clear();
+}
+
 /// Helper function to format duration in a human-readable way
 fn format_duration(duration: std::time::Duration) -> String {
     if duration.as_millis() < 1000 {
@@ -35,7 +99,7 @@ fn format_duration(duration: std::time::Duration) -> String {
 }
 
 /// Generate a cache key for a specific directory and options
-fn generate_cache_key(
+pub(crate) fn generate_cache_key(
     path: &Path,
     allow_tests: bool,
     custom_ignores: &[String],
@@ -80,14 +144,14 @@ pub fn get_file_list(
     custom_ignores: &[String],
     no_gitignore: bool,
 ) -> Result<Arc<FileList>> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Getting file list for path: {path:?}");
-        println!("DEBUG: allow_tests: {allow_tests}");
-        println!("DEBUG: custom_ignores: {custom_ignores:?}");
-        println!("DEBUG: no_gitignore: {no_gitignore}");
+        debug!("Getting file list for path: {path:?}");
+        debug!("allow_tests: {allow_tests}");
+        debug!("custom_ignores: {custom_ignores:?}");
+        debug!("no_gitignore: {no_gitignore}");
     }
 
     // Create a cache key for this request
@@ -99,8 +163,8 @@ pub fn get_file_list(
         if let Some(file_list) = cache.get(&cache_key) {
             let elapsed = start_time.elapsed();
             if debug_mode {
-                println!(
-                    "DEBUG: Found file list in cache with {} files (retrieved in {})",
+                debug!(
+                    "Found file list in cache with {} files (retrieved in {})",
                     file_list.files.len(),
                     format_duration(elapsed)
                 );
@@ -111,23 +175,51 @@ pub fn get_file_list(
 
     // If not in cache, build the file list
     if debug_mode {
-        println!("DEBUG: File list not found in cache, building new list");
+        debug!("File list not found in cache, building new list");
     }
 
-    let file_list = build_file_list(path, allow_tests, custom_ignores, no_gitignore)?;
+    // A watched on-disk index (`probe index --watch`) saves walking the tree; it lists
+    // the files of a default walk, binary files included
+    let live_files = (walk_options() == WalkOptions::default())
+        .then(|| file_index::live_file_list(path, allow_tests, custom_ignores, no_gitignore))
+        .flatten();
+    let file_list = match live_files {
+        Some(mut files) => {
+            if debug_mode {
+                debug!("Using file list from watched index");
+            }
+            files.retain(|file| !is_binary_file(file));
+            let files = files
+                .into_iter()
+                .flat_map(|file| {
+                    if notebooks::is_notebook(&file) {
+                        notebook_cells(&file)
+                    } else {
+                        vec![file]
+                    }
+                })
+                .collect();
+            FileList {
+                files,
+                large_files: Vec::new(),
+                created_at: Instant::now(),
+            }
+        }
+        None => build_file_list(path, allow_tests, custom_ignores, no_gitignore)?,
+    };
     let file_count = file_list.files.len();
 
-    // Cache the file list
+    // Cache the file list, unless an interrupt cut the walk short
     let file_list = Arc::new(file_list);
-    {
+    if !interrupt::is_interrupted() {
         let mut cache = FILE_LIST_CACHE.write().unwrap();
         cache.insert(cache_key, Arc::clone(&file_list));
     }
 
     let elapsed = start_time.elapsed();
     if debug_mode {
-        println!(
-            "DEBUG: Built and cached new file list with {} files in {}",
+        debug!(
+            "Built and cached new file list with {} files in {}",
             file_count,
             format_duration(elapsed)
         );
@@ -136,22 +228,18 @@ pub fn get_file_list(
     Ok(file_list)
 }
 
-/// Build a list of files in a directory, respecting ignore patterns and test file exclusions.
-fn build_file_list(
+/// Configure a directory walker that respects .gitignore files (unless `no_gitignore`),
+/// .probeignore files, the common ignore patterns, test file exclusions and custom
+/// ignore patterns.
+pub(crate) fn configure_walker(
     path: &Path,
     allow_tests: bool,
     custom_ignores: &[String],
     no_gitignore: bool,
-) -> Result<FileList> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
-    let start_time = Instant::now();
-
-    if debug_mode {
-        println!("DEBUG: Building file list for path: {path:?}");
-    }
+) -> WalkBuilder {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Create a WalkBuilder that respects .gitignore files and common ignore patterns
-    let builder_start = Instant::now();
     let mut builder = WalkBuilder::new(path);
 
     // Follow symlinks by default. Loop detection is handled by walkdir internally -
@@ -168,10 +256,14 @@ fn build_file_list(
         builder.git_global(false);
         builder.git_exclude(false);
         if debug_mode {
-            println!("DEBUG: Gitignore disabled - will not respect .gitignore files");
+            debug!("Gitignore disabled - will not respect .gitignore files");
         }
     }
 
+    // .probeignore applies with or without gitignore, since it exists only for probe
+    builder.add_custom_ignore_filename(PROBEIGNORE_FILENAME);
+    apply_walk_options(&mut builder);
+
     // Enable parallel walking for large directories
     builder.threads(rayon::current_num_threads());
 
@@ -227,6 +319,12 @@ fn build_file_list(
     .map(String::from)
     .collect();
 
+    // --search-archives descends into the archives instead
+    if walk_options().search_archives {
+        common_ignores
+            .retain(|pattern| !matches!(pattern.as_str(), "*.jar" | "*.zip" | "*.tar.gz"));
+    }
+
     // Add test file patterns if allow_tests is false
     if !allow_tests {
         let test_patterns: Vec<String> = vec![
@@ -293,11 +391,31 @@ fn build_file_list(
         }
     }
 
+    builder
+}
+
+/// Build a list of files in a directory, respecting ignore patterns and test file exclusions.
+fn build_file_list(
+    path: &Path,
+    allow_tests: bool,
+    custom_ignores: &[String],
+    no_gitignore: bool,
+) -> Result<FileList> {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
+    let start_time = Instant::now();
+
+    if debug_mode {
+        debug!("Building file list for path: {path:?}");
+    }
+
+    let builder_start = Instant::now();
+    let builder = configure_walker(path, allow_tests, custom_ignores, no_gitignore);
+
     let builder_duration = builder_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Builder configuration completed in {}",
+        debug!(
+            "Builder configuration completed in {}",
             format_duration(builder_duration)
         );
     }
@@ -305,10 +423,23 @@ fn build_file_list(
     // Collect files
     let walk_start = Instant::now();
     let mut files = Vec::new();
+    let mut large_files = Vec::new();
+    let WalkOptions {
+        max_filesize,
+        binary,
+        search_archives,
+        ..
+    } = walk_options();
+    let mut binary_files = 0;
     let mut total_files = 0;
+    let progress = Progress::unbounded("Listing files");
 
     for result in builder.build() {
+        if interrupt::is_interrupted() {
+            break;
+        }
         total_files += 1;
+        progress.inc();
         let entry = match result {
             Ok(entry) => entry,
             Err(err) => {
@@ -322,18 +453,58 @@ fn build_file_list(
             continue;
         }
 
+        if let Some(max_filesize) = max_filesize {
+            let size = entry.metadata().map_or(0, |metadata| metadata.len());
+            if size > max_filesize {
+                large_files.push((entry.into_path(), size));
+                continue;
+            }
+        }
+
+        if notebooks::is_notebook(entry.path()) {
+            files.extend(notebook_cells(entry.path()));
+            continue;
+        }
+
+        if search_archives && archives::is_archive(entry.path()) {
+            match archives::archive_members(entry.path()) {
+                Ok(members) => files.extend(members),
+                Err(err) => warn(
+                    WarningKind::Other,
+                    Some(entry.path()),
+                    format!("Error reading archive: {err:#}"),
+                ),
+            }
+            continue;
+        }
+
+        // Binary blobs would only add noise to tokenization and token counts
+        if !binary && is_binary_file(entry.path()) {
+            binary_files += 1;
+            continue;
+        }
+
         files.push(entry.path().to_path_buf());
     }
 
+    if binary_files > 0 {
+        warn(
+            WarningKind::BinarySkipped,
+            Some(path),
+            format!("Skipped {binary_files} binary files (use --binary to search them)"),
+        );
+    }
+
     // Sort files for deterministic ordering to fix non-deterministic behavior
     // This ensures that file discovery is consistent across runs
     files.sort();
+    large_files.sort();
 
     let walk_duration = walk_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Directory walk completed in {} - Found {} files out of {} entries (sorted for determinism)",
+        debug!(
+            "Directory walk completed in {} - Found {} files out of {} entries (sorted for determinism)",
             format_duration(walk_duration),
             files.len(),
             total_files
@@ -343,18 +514,31 @@ fn build_file_list(
     let total_duration = start_time.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Total file list building completed in {}",
+        debug!(
+            "Total file list building completed in {}",
             format_duration(total_duration)
         );
     }
 
     Ok(FileList {
         files,
+        large_files,
         created_at: Instant::now(),
     })
 }
 
+/// The code cells of a notebook to search in its place
+fn notebook_cells(notebook: &Path) -> Vec<PathBuf> {
+    notebooks::notebook_cells(notebook).unwrap_or_else(|err| {
+        warn(
+            WarningKind::Other,
+            Some(notebook),
+            format!("Error reading notebook: {err:#}"),
+        );
+        Vec::new()
+    })
+}
+
 /// Find files whose names match query words
 /// Returns a map of file paths to the term indices that matched the filename
 #[allow(clippy::too_many_arguments)]
@@ -368,17 +552,14 @@ pub fn find_matching_filenames(
     language: Option<&str>,
     no_gitignore: bool,
 ) -> Result<HashMap<PathBuf, HashSet<usize>>> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Finding files with matching filenames");
-        println!("DEBUG: Queries: {queries:?}");
-        println!(
-            "DEBUG: Already found files count: {}",
-            already_found_files.len()
-        );
-        println!("DEBUG: Term indices: {term_indices:?}");
+        debug!("Finding files with matching filenames");
+        debug!("Queries: {queries:?}");
+        debug!("Already found files count: {}", already_found_files.len());
+        debug!("Term indices: {term_indices:?}");
     }
 
     // Get the cached file list, with language filtering if specified
@@ -386,12 +567,37 @@ pub fn find_matching_filenames(
         get_file_list_by_language(path, allow_tests, custom_ignores, language, no_gitignore)?;
 
     if debug_mode {
-        println!(
-            "DEBUG: Searching through {} files from cache",
+        debug!(
+            "Searching through {} files from cache",
             file_list.files.len()
         );
     }
 
+    let matching_files =
+        match_filenames(&file_list.files, queries, already_found_files, term_indices);
+
+    let elapsed = start_time.elapsed();
+
+    if debug_mode {
+        debug!(
+            "Found {} files with matching filenames in {}",
+            matching_files.len(),
+            format_duration(elapsed)
+        );
+    }
+
+    Ok(matching_files)
+}
+
+/// Match query terms against the paths of `files`, skipping `already_found_files`
+pub fn match_filenames(
+    files: &[PathBuf],
+    queries: &[String],
+    already_found_files: &HashSet<PathBuf>,
+    term_indices: &HashMap<String, usize>,
+) -> HashMap<PathBuf, HashSet<usize>> {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
+
     // Tokenize query terms for matching using the standard tokenizer
     let query_tokens: Vec<String> = queries
         .iter()
@@ -399,26 +605,30 @@ pub fn find_matching_filenames(
         .collect();
 
     if debug_mode {
-        println!("DEBUG: Query tokens for filename matching: {query_tokens:?}");
+        debug!("Query tokens for filename matching: {query_tokens:?}");
     }
 
     // Search each file for matching filenames
     let mut matching_files = HashMap::new();
 
-    for file_path in &file_list.files {
+    for file_path in files {
         // Skip if this file is already in the results
         if already_found_files.contains(file_path) {
             continue;
         }
 
-        // Get the full relative path including directory structure
+        // Get the full relative path including directory structure; archive members and
+        // notebook cells are matched by the path they are reported with
         let relative_path = file_path.to_string_lossy().to_string();
+        let relative_path = archives::archive_path(&relative_path)
+            .or_else(|| notebooks::notebook_location(&relative_path))
+            .unwrap_or(relative_path);
 
         // Tokenize the full relative path using the standard tokenizer
         let filename_tokens = tokenization::tokenize(&relative_path);
 
         if debug_mode && !filename_tokens.is_empty() {
-            println!("DEBUG: Path '{relative_path}' tokenized as: {filename_tokens:?}");
+            debug!("Path '{relative_path}' tokenized as: {filename_tokens:?}");
         }
         // Find which terms match the filename
         let mut matched_terms = HashSet::new();
@@ -436,9 +646,7 @@ pub fn find_matching_filenames(
             if matched {
                 matched_terms.insert(idx);
                 if debug_mode {
-                    println!(
-                        "DEBUG: Term '{term}' matched path '{relative_path}', adding index {idx}"
-                    );
+                    debug!("Term '{term}' matched path '{relative_path}', adding index {idx}");
                 }
             }
         }
@@ -449,17 +657,7 @@ pub fn find_matching_filenames(
         }
     }
 
-    let elapsed = start_time.elapsed();
-
-    if debug_mode {
-        println!(
-            "DEBUG: Found {} files with matching filenames in {}",
-            matching_files.len(),
-            format_duration(elapsed)
-        );
-    }
-
-    Ok(matching_files)
+    matching_files
 }
 
 /// Get a list of file extensions for a specific programming language
@@ -503,11 +701,11 @@ pub fn get_file_list_by_language(
         return get_file_list(path, allow_tests, custom_ignores, no_gitignore);
     }
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Getting file list for path: {path:?} with language filter: {language:?}");
+        debug!("Getting file list for path: {path:?} with language filter: {language:?}");
     }
 
     // Get the full file list first
@@ -517,7 +715,7 @@ pub fn get_file_list_by_language(
     let extensions = get_language_extensions(language.unwrap());
 
     if debug_mode {
-        println!("DEBUG: Filtering files by extensions: {extensions:?}");
+        debug!("Filtering files by extensions: {extensions:?}");
     }
 
     // Filter the files by extension
@@ -543,8 +741,8 @@ pub fn get_file_list_by_language(
 
     let elapsed = start_time.elapsed();
     if debug_mode {
-        println!(
-            "DEBUG: Filtered file list by language in {} - Found {} files out of {}",
+        debug!(
+            "Filtered file list by language in {} - Found {} files out of {}",
             format_duration(elapsed),
             filtered_files.len(),
             full_file_list.files.len()
@@ -554,6 +752,7 @@ pub fn get_file_list_by_language(
     // Create a new FileList with the filtered files
     Ok(Arc::new(FileList {
         files: filtered_files,
+        large_files: full_file_list.large_files.clone(),
         created_at: Instant::now(),
     }))
 }
@@ -817,6 +1016,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_probeignore_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(PROBEIGNORE_FILENAME), "third_party/\n").unwrap();
+
+        let vendored = temp_dir.path().join("third_party");
+        fs::create_dir_all(&vendored).unwrap();
+        let vendored_file = vendored.join("lib.rs");
+        fs::write(&vendored_file, "fn vendored() {}").unwrap();
+        let regular_file = temp_dir.path().join("main.rs");
+        fs::write(&regular_file, "fn main() {}").unwrap();
+
+        // .probeignore needs no git repository and applies even without gitignore
+        for no_gitignore in [false, true] {
+            let file_list = get_file_list(temp_dir.path(), true, &[], no_gitignore).unwrap();
+            assert!(file_list.files.contains(&regular_file));
+            assert!(
+                !file_list.files.contains(&vendored_file),
+                "File excluded by .probeignore should not be found (no_gitignore: {no_gitignore})"
+            );
+        }
+    }
+
     #[test]
     fn test_cache_key_includes_no_gitignore() {
         let temp_dir = TempDir::new().unwrap();
@@ -842,4 +1064,19 @@ mod tests {
             "Cache key should contain 'no_gitignore' when no_gitignore is true"
         );
     }
+
+    #[test]
+    fn test_binary_files_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("lib.rs");
+        fs::write(&source, "fn main() {}\n").unwrap();
+        let blob = temp_dir.path().join("image.png");
+        fs::write(&blob, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        assert!(!is_binary_file(&source));
+        assert!(is_binary_file(&blob));
+
+        let file_list = get_file_list(temp_dir.path(), true, &[], true).unwrap();
+        assert_eq!(file_list.files, vec![source]);
+    }
 }
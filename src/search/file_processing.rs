@@ -8,10 +8,12 @@ use std::fs;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tracing::trace;
 
 use probe_code::language::{is_test_file, parse_file_for_code_blocks_with_tree};
-use probe_code::models::SearchResult;
+use probe_code::models::{BlockCode, SearchResult};
 use probe_code::ranking;
+use probe_code::search::syntax_mask::SyntaxMask;
 use probe_code::search::tokenization;
 
 // PHASE 3B OPTIMIZATION: Global tokenization cache for term matching
@@ -74,6 +76,18 @@ pub struct FileProcessingParams<'a> {
 
     #[allow(dead_code)]
     pub no_merge: bool,
+    pub no_attributes: bool,
+    /// Match and rank blocks by their declaration signature only, dropping blocks
+    /// without one and the context of lines outside any block
+    pub symbols_only: bool,
+    /// Drop matches on lines with only comments, and blank comments out of blocks
+    /// before tokenization
+    pub no_comments: bool,
+    /// Keep only matches on lines with comments, and tokenize only the comments of blocks
+    pub comments_only: bool,
+    /// Keep only matches on lines with string literals, and tokenize only the strings of
+    /// blocks
+    pub strings_only: bool,
 }
 
 /// Evaluate whether a block of lines satisfies a complex AST query
@@ -103,42 +117,47 @@ pub fn filter_code_block_with_ast(
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: Checking for terms in block {}-{}",
-            block_lines.0, block_lines.1
+        trace!(
+            "Checking for terms in block {}-{}",
+            block_lines.0,
+            block_lines.1
         );
-        println!("DEBUG: Matched terms: {matched_terms:?}");
-        println!("DEBUG: Term indices: {:?}", plan.term_indices);
-        println!("DEBUG: Excluded terms: {:?}", plan.excluded_terms);
-        println!("DEBUG: AST: {:?}", plan.ast);
+        trace!("Matched terms: {matched_terms:?}");
+        trace!("Term indices: {:?}", plan.term_indices);
+        trace!("Excluded terms: {:?}", plan.excluded_terms);
+        trace!("AST: {:?}", plan.ast);
 
         // Add detailed information about which exact keywords matched
-        println!("DEBUG: ===== MATCHED KEYWORDS DETAILS =====");
+        trace!("===== MATCHED KEYWORDS DETAILS =====");
         // PHASE 4 OPTIMIZATION: Pre-allocate with estimated size
         let mut matched_keywords = Vec::with_capacity(plan.term_indices.len());
         for (term, &idx) in &plan.term_indices {
             if matched_terms.contains(&idx) {
                 matched_keywords.push(term);
-                println!(
-                    "DEBUG: Keyword '{}' matched in block {}-{}",
-                    term, block_lines.0, block_lines.1
+                trace!(
+                    "Keyword '{}' matched in block {}-{}",
+                    term,
+                    block_lines.0,
+                    block_lines.1
                 );
             }
         }
         if matched_keywords.is_empty() {
-            println!("DEBUG: No keywords matched in this block");
+            trace!("No keywords matched in this block");
         } else {
-            println!("DEBUG: All matched keywords: {matched_keywords:?}");
+            trace!("All matched keywords: {matched_keywords:?}");
         }
-        println!("DEBUG: ===================================");
+        trace!("===================================");
     }
 
     // PHASE 3C OPTIMIZATION: Use fast path evaluation
     if let Some(result) = plan.ast.evaluate_fast_path(&matched_terms, plan) {
         if debug_mode {
-            println!(
-                "DEBUG: Fast path evaluation for block {}-{}: {}",
-                block_lines.0, block_lines.1, result
+            trace!(
+                "Fast path evaluation for block {}-{}: {}",
+                block_lines.0,
+                block_lines.1,
+                result
             );
         }
         return result;
@@ -147,9 +166,10 @@ pub fn filter_code_block_with_ast(
     // Check if we have any matches at all
     if matched_terms.is_empty() && !plan.has_only_excluded_terms && !plan.is_universal_query {
         if debug_mode {
-            println!(
-                "DEBUG: No matched terms in block {}-{}, returning false",
-                block_lines.0, block_lines.1
+            trace!(
+                "No matched terms in block {}-{}, returning false",
+                block_lines.0,
+                block_lines.1
             );
         }
         return false;
@@ -158,9 +178,10 @@ pub fn filter_code_block_with_ast(
     // Universal query - accept all blocks
     if plan.is_universal_query && matched_terms.is_empty() {
         if debug_mode {
-            println!(
-                "DEBUG: Universal query - accepting block {}-{}",
-                block_lines.0, block_lines.1
+            trace!(
+                "Universal query - accepting block {}-{}",
+                block_lines.0,
+                block_lines.1
             );
         }
         return true;
@@ -168,9 +189,9 @@ pub fn filter_code_block_with_ast(
 
     // Use the AST evaluation directly
     if debug_mode {
-        println!("DEBUG: ===== AST EVALUATION =====");
-        println!("DEBUG: Matched terms: {matched_terms:?}");
-        println!("DEBUG: Term indices: {:?}", plan.term_indices);
+        trace!("===== AST EVALUATION =====");
+        trace!("Matched terms: {matched_terms:?}");
+        trace!("Term indices: {:?}", plan.term_indices);
     }
 
     // PHASE 3C OPTIMIZATION: Use cached evaluation when possible
@@ -179,30 +200,32 @@ pub fn filter_code_block_with_ast(
     let result = plan.ast.evaluate(&matched_terms, &plan.term_indices, false);
 
     if debug_mode {
-        println!("DEBUG: ===== EVALUATION RESULT =====");
-        println!("DEBUG: AST evaluation result: {result}");
-        println!(
-            "DEBUG: Block {}-{} will be {}",
+        trace!("===== EVALUATION RESULT =====");
+        trace!("AST evaluation result: {result}");
+        trace!(
+            "Block {}-{} will be {}",
             block_lines.0,
             block_lines.1,
             if result { "INCLUDED" } else { "EXCLUDED" }
         );
-        println!("DEBUG: ============================");
+        trace!("============================");
     }
 
     let decision = result;
 
     if debug_mode {
-        println!(
-            "DEBUG: Block {}-{} matched terms: {:?}",
-            block_lines.0, block_lines.1, matched_terms
+        trace!(
+            "Block {}-{} matched terms: {:?}",
+            block_lines.0,
+            block_lines.1,
+            matched_terms
         );
-        println!("DEBUG: AST evaluation result: {decision}");
+        trace!("AST evaluation result: {decision}");
     }
 
     if debug_mode {
-        println!(
-            "DEBUG: filter_code_block_with_ast => lines {block_lines:?} => matched {matched_terms:?}, decision={decision}"
+        trace!(
+            "filter_code_block_with_ast => lines {block_lines:?} => matched {matched_terms:?}, decision={decision}"
         );
     }
     decision
@@ -222,7 +245,7 @@ pub fn filter_tokenized_block(
     }
 
     // PHASE 3C OPTIMIZATION: Batch term index resolution
-    let mut matched_terms = resolve_term_indices_batch(tokenized_content, &plan.term_indices);
+    let mut matched_terms = resolve_term_indices_batch(tokenized_content, plan);
 
     // PHASE 3C OPTIMIZATION: Early termination for required terms with indices
     if !plan.required_terms_indices.is_empty() {
@@ -285,7 +308,7 @@ pub fn filter_tokenized_block(
                         .find(|(_, &i)| i == idx)
                         .map(|(t, _)| t)
                     {
-                        println!("DEBUG: Special case term '{term}' matched in tokenized content");
+                        trace!("Special case term '{term}' matched in tokenized content");
                     }
                 }
             }
@@ -293,29 +316,29 @@ pub fn filter_tokenized_block(
     }
 
     if debug_mode {
-        println!("DEBUG: Checking for terms in tokenized block");
-        println!("DEBUG: Tokenized content: {tokenized_content:?}");
-        println!("DEBUG: Matched terms: {matched_terms:?}");
-        println!("DEBUG: Term indices: {:?}", plan.term_indices);
-        println!("DEBUG: Excluded terms: {:?}", plan.excluded_terms);
-        println!("DEBUG: AST: {:?}", plan.ast);
+        trace!("Checking for terms in tokenized block");
+        trace!("Tokenized content: {tokenized_content:?}");
+        trace!("Matched terms: {matched_terms:?}");
+        trace!("Term indices: {:?}", plan.term_indices);
+        trace!("Excluded terms: {:?}", plan.excluded_terms);
+        trace!("AST: {:?}", plan.ast);
 
         // Add detailed information about which exact keywords matched
-        println!("DEBUG: ===== MATCHED KEYWORDS DETAILS =====");
+        trace!("===== MATCHED KEYWORDS DETAILS =====");
         // PHASE 4 OPTIMIZATION: Pre-allocate with estimated size
         let mut matched_keywords = Vec::with_capacity(plan.term_indices.len());
         for (term, &idx) in &plan.term_indices {
             if matched_terms.contains(&idx) {
                 matched_keywords.push(term);
-                println!("DEBUG: Keyword '{term}' matched in tokenized block");
+                trace!("Keyword '{term}' matched in tokenized block");
             }
         }
         if matched_keywords.is_empty() {
-            println!("DEBUG: No keywords matched in this block");
+            trace!("No keywords matched in this block");
         } else {
-            println!("DEBUG: All matched keywords: {matched_keywords:?}");
+            trace!("All matched keywords: {matched_keywords:?}");
         }
-        println!("DEBUG: ===================================");
+        trace!("===================================");
     }
 
     // Check if we have any matches at all
@@ -327,27 +350,27 @@ pub fn filter_tokenized_block(
         // Check if this is a universal query (e.g., filename-only search)
         if plan.is_universal_query {
             if debug_mode {
-                println!("DEBUG: Universal query - accepting all blocks");
+                trace!("Universal query - accepting all blocks");
             }
             return true;
         }
         if debug_mode {
-            println!("DEBUG: No matched terms in tokenized block, returning false");
+            trace!("No matched terms in tokenized block, returning false");
         }
         return false;
     }
 
     // Use the AST evaluation directly
     if debug_mode {
-        println!("DEBUG: ===== AST EVALUATION =====");
-        println!("DEBUG: Matched terms: {matched_terms:?}");
-        println!("DEBUG: Term indices: {:?}", plan.term_indices);
+        trace!("===== AST EVALUATION =====");
+        trace!("Matched terms: {matched_terms:?}");
+        trace!("Term indices: {:?}", plan.term_indices);
     }
 
     // PHASE 3C OPTIMIZATION: Use fast-path evaluation when possible
     if let Some(result) = plan.ast.evaluate_fast_path(&matched_terms, plan) {
         if debug_mode {
-            println!("DEBUG: Fast path evaluation result: {result}");
+            trace!("Fast path evaluation result: {result}");
         }
         return result;
     }
@@ -356,26 +379,89 @@ pub fn filter_tokenized_block(
     let result = plan.ast.evaluate(&matched_terms, &plan.term_indices, false);
 
     if debug_mode {
-        println!("DEBUG: ===== EVALUATION RESULT =====");
-        println!("DEBUG: AST evaluation result: {result}");
-        println!(
-            "DEBUG: Block will be {}",
+        trace!("===== EVALUATION RESULT =====");
+        trace!("AST evaluation result: {result}");
+        trace!(
+            "Block will be {}",
             if result { "INCLUDED" } else { "EXCLUDED" }
         );
-        println!("DEBUG: ============================");
+        trace!("============================");
     }
 
     let decision = result;
 
     if debug_mode {
-        println!("DEBUG: Tokenized block matched terms: {matched_terms:?}");
-        println!("DEBUG: AST evaluation result: {decision}");
-        println!("DEBUG: filter_tokenized_block => matched {matched_terms:?}, decision={decision}");
+        trace!("Tokenized block matched terms: {matched_terms:?}");
+        trace!("AST evaluation result: {decision}");
+        trace!("filter_tokenized_block => matched {matched_terms:?}, decision={decision}");
     }
 
     decision
 }
 
+/// Check the `NEAR/n` operators of the query against the lines of the block (1-based,
+/// inclusive) each term occurs on. Blocks always pass for queries without NEAR.
+///
+/// Lines are tokenized one by one because the search's term map records every term of
+/// the combined pattern on each line it matched.
+pub fn block_satisfies_proximity(
+    block_lines: (usize, usize),
+    lines: &[&str],
+    plan: &crate::search::query::QueryPlan,
+) -> bool {
+    if !plan.ast.has_near() {
+        return true;
+    }
+
+    let mut matched_terms = HashSet::new();
+    let mut term_lines: HashMap<usize, Vec<usize>> = HashMap::new();
+    let last_line = block_lines.1.min(lines.len());
+    for line_number in block_lines.0.max(1)..=last_line {
+        for token in ranking::preprocess_text(lines[line_number - 1]) {
+            if let Some(&term_idx) = plan.term_indices.get(&token) {
+                matched_terms.insert(term_idx);
+                term_lines.entry(term_idx).or_default().push(line_number);
+            }
+        }
+    }
+
+    plan.ast
+        .proximity_holds(&matched_terms, &plan.term_indices, &term_lines)
+}
+
+/// Evaluate the query on a block for case-sensitive matching, in place of
+/// `filter_tokenized_block`, which lowercases the block: a term counts as present where
+/// the block contains it as written in the query, so `Parser` doesn't match `parser`.
+pub fn filter_case_sensitive_block(
+    block_lines: (usize, usize),
+    lines: &[&str],
+    plan: &crate::search::query::QueryPlan,
+) -> bool {
+    // A universal query has no terms to check
+    if plan.is_universal_query {
+        return true;
+    }
+
+    let last_line = block_lines.1.min(lines.len());
+    let first_line = block_lines.0.max(1).min(last_line + 1);
+    let block = lines[first_line - 1..last_line].join("\n");
+
+    let matched_terms: HashSet<usize> = plan
+        .term_indices
+        .iter()
+        .filter(|(term, idx)| match plan.written_terms.get(idx) {
+            Some(forms) => forms.iter().any(|form| block.contains(form.as_str())),
+            None => block.contains(term.as_str()),
+        })
+        .map(|(_, &idx)| idx)
+        .collect();
+
+    if matched_terms.is_empty() {
+        return plan.has_only_excluded_terms;
+    }
+    plan.ast.evaluate(&matched_terms, &plan.term_indices, false)
+}
+
 /// Determines a better node type for fallback context by analyzing the line content
 fn determine_fallback_node_type(line: &str, extension: Option<&str>) -> String {
     let trimmed = line.trim();
@@ -443,12 +529,50 @@ fn determine_fallback_node_type(line: &str, extension: Option<&str>) -> String {
     "code".to_string()
 }
 
+/// The lines of a file joined into one buffer, which the code of all its result blocks
+/// points into instead of each block copying its lines
+struct FileBuffer {
+    text: Arc<str>,
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+}
+
+impl FileBuffer {
+    fn new(lines: &[&str]) -> Self {
+        let mut line_starts = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in lines {
+            line_starts.push(offset);
+            offset += line.len() + 1;
+        }
+        FileBuffer {
+            text: lines.join("\n").into(),
+            line_starts,
+        }
+    }
+
+    /// The code of lines `start..=end` (1-based), as `lines[start - 1..end].join("\n")`
+    fn block(&self, start: usize, end: usize) -> BlockCode {
+        if start > end {
+            return BlockCode::default();
+        }
+        let span_end = match self.line_starts.get(end) {
+            Some(next_start) => next_start - 1,
+            None => self.text.len(),
+        };
+        BlockCode::shared(&self.text, self.line_starts[start - 1]..span_end)
+    }
+}
+
 /// Batch processing context for uncovered lines optimization
 struct BatchProcessingContext<'a> {
     uncovered_lines: &'a [usize],
     covered_lines: &'a mut HashSet<usize>,
     lines: &'a [&'a str],
+    buffer: &'a FileBuffer,
     params: &'a FileProcessingParams<'a>,
+    /// The file's term matches, narrowed to code or comments when asked to
+    term_matches: &'a HashMap<usize, HashSet<usize>>,
     extension: &'a str,
     unique_query_terms: &'a HashSet<String>,
     results: &'a mut Vec<SearchResult>,
@@ -475,8 +599,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
     }
 
     if ctx.debug_mode {
-        println!(
-            "DEBUG: SMART CONTEXT WINDOW MERGING: Processing {} uncovered lines with enhanced merging",
+        trace!(
+            "SMART CONTEXT WINDOW MERGING: Processing {} uncovered lines with enhanced merging",
             ctx.uncovered_lines.len()
         );
     }
@@ -508,13 +632,13 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         .collect();
 
     // Pre-create a basic text representation for pre-filtering (optimization)
-    let file_text_lower = ctx.lines.join("\n").to_lowercase();
+    let file_text_lower = ctx.buffer.text.to_lowercase();
     let has_potential_matches = query_terms_lower
         .iter()
         .any(|term| file_text_lower.contains(term));
 
     if !has_potential_matches && ctx.debug_mode {
-        println!("DEBUG: SMART MERGING: No query terms found in file content, processing minimal contexts");
+        trace!("SMART MERGING: No query terms found in file content, processing minimal contexts");
     }
 
     // Step 1: Generate all potential context windows with pre-filtering
@@ -522,7 +646,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         // Skip if line is already covered by previous processing
         if ctx.covered_lines.contains(&line_num) {
             if ctx.debug_mode {
-                println!("DEBUG: Line {line_num} already covered, skipping");
+                trace!("Line {line_num} already covered, skipping");
             }
             continue;
         }
@@ -530,8 +654,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         // Skip fallback context for test files if allow_tests is false
         if !ctx.params.allow_tests && is_test_file(ctx.params.path) {
             if ctx.debug_mode {
-                println!(
-                    "DEBUG: Skipping fallback context for test file: {:?}",
+                trace!(
+                    "Skipping fallback context for test file: {:?}",
                     ctx.params.path
                 );
             }
@@ -543,12 +667,14 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
             if let Some((ref mut parser, ref language_impl)) = parser_opt {
                 let line_content = ctx.lines[line_num - 1];
 
-                if let Some(tree) = parser.parse(line_content, None) {
+                if let Some(tree) =
+                    crate::language::timed_parse(parser, ctx.extension, line_content)
+                {
                     let node = tree.root_node();
                     if language_impl.is_test_node(&node, line_content.as_bytes()) {
                         if ctx.debug_mode {
-                            println!(
-                                "DEBUG: Skipping fallback context for test code: '{}'",
+                            trace!(
+                                "Skipping fallback context for test code: '{}'",
                                 line_content.trim()
                             );
                         }
@@ -577,7 +703,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
     }
 
     if ctx.debug_mode {
-        println!("DEBUG: Generated {} context windows", context_windows.len());
+        trace!("Generated {} context windows", context_windows.len());
     }
 
     // Step 2: Enhanced merging with smart gap detection and aggressive combining
@@ -643,7 +769,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
                     ));
 
                     if ctx.debug_mode && gap > 1 {
-                        println!("DEBUG: SMART MERGING: Merged contexts with gap of {gap} lines (threshold: {dynamic_merge_threshold})");
+                        trace!("SMART MERGING: Merged contexts with gap of {gap} lines (threshold: {dynamic_merge_threshold})");
                     }
                 } else {
                     // Gap too large, finalize current window and start new one
@@ -657,7 +783,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
                     ));
 
                     if ctx.debug_mode {
-                        println!("DEBUG: SMART MERGING: Gap of {gap} lines exceeds threshold {dynamic_merge_threshold}, creating new window");
+                        trace!("SMART MERGING: Gap of {gap} lines exceeds threshold {dynamic_merge_threshold}, creating new window");
                     }
                 }
             }
@@ -670,14 +796,14 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
     }
 
     if ctx.debug_mode {
-        println!(
-            "DEBUG: SMART MERGING: Merged into {} optimized context windows (reduced from {})",
+        trace!(
+            "SMART MERGING: Merged into {} optimized context windows (reduced from {})",
             merged_windows.len(),
             ctx.uncovered_lines.len()
         );
         for (i, (lines, start, end, _, _)) in merged_windows.iter().enumerate() {
-            println!(
-                "DEBUG: Window {}: lines {:?} -> context {}-{} (size: {} lines)",
+            trace!(
+                "Window {}: lines {:?} -> context {}-{} (size: {} lines)",
                 i,
                 lines,
                 start,
@@ -701,9 +827,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         merged_windows
     {
         // Extract the context lines using 0-based indices (BATCH OPTIMIZATION: single extraction per merged window)
-        let context_code = ctx.lines[context_start_idx..=context_end_idx]
-            .to_vec()
-            .join("\n");
+        let context_code = ctx.buffer.block(context_start_idx + 1, context_end_idx + 1);
 
         // Determine node type based on the first original line (for consistency with original behavior)
         let primary_line = original_lines[0];
@@ -711,9 +835,9 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
             determine_fallback_node_type(ctx.lines[primary_line - 1], Some(ctx.extension));
 
         if ctx.debug_mode {
-            println!("DEBUG: Inferred node type for merged fallback context: {node_type}");
-            println!(
-                "DEBUG: Processing merged context window: lines {}-{} (size: {}) covering original lines: {:?}",
+            trace!("Inferred node type for merged fallback context: {node_type}");
+            trace!(
+                "Processing merged context window: lines {}-{} (size: {}) covering original lines: {:?}",
                 context_start,
                 context_end,
                 context_end - context_start + 1,
@@ -732,15 +856,15 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
                 });
 
             if ctx.debug_mode {
-                println!(
-                    "DEBUG: Test function check for merged context window at lines {context_start}-{context_end}: is_test={is_test_context}"
+                trace!(
+                    "Test function check for merged context window at lines {context_start}-{context_end}: is_test={is_test_context}"
                 );
             }
 
             if is_test_context {
                 if ctx.debug_mode {
-                    println!(
-                        "DEBUG: Filtering out merged context window at lines {context_start}-{context_end} (contains test function)"
+                    trace!(
+                        "Filtering out merged context window at lines {context_start}-{context_end} (contains test function)"
                     );
                 }
                 // Mark lines as covered to prevent further processing
@@ -776,8 +900,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         // Skip aggressive pre-filtering for filename matches to preserve all context
         if !has_potential_query_matches && !is_likely_filename_match {
             if ctx.debug_mode {
-                println!(
-                    "DEBUG: AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} contains no query terms, skipping expensive processing"
+                trace!(
+                    "AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} contains no query terms, skipping expensive processing"
                 );
             }
             // PHASE 3A OPTIMIZATION: Mark lines as covered even when skipping
@@ -791,12 +915,12 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
 
         if ctx.debug_mode {
             if has_potential_query_matches {
-                println!(
-                    "DEBUG: AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} passed pre-filter, proceeding with tokenization"
+                trace!(
+                    "AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} passed pre-filter, proceeding with tokenization"
                 );
             } else if is_likely_filename_match {
-                println!(
-                    "DEBUG: AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} bypassed pre-filter due to filename match, proceeding with tokenization"
+                trace!(
+                    "AGGRESSIVE PRE-FILTERING: Context {context_start}-{context_end} bypassed pre-filter due to filename match, proceeding with tokenization"
                 );
             }
         }
@@ -805,7 +929,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         let cache_key = (context_start_idx, context_end_idx);
         let context_terms = if let Some(cached_terms) = tokenization_cache.get(&cache_key) {
             if ctx.debug_mode {
-                println!("DEBUG: TOKENIZATION CACHE: Using cached tokenization for context {context_start}-{context_end}");
+                trace!("TOKENIZATION CACHE: Using cached tokenization for context {context_start}-{context_end}");
             }
             cached_terms.clone()
         } else {
@@ -825,7 +949,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
             };
             tokenization_cache.insert(cache_key, terms.clone());
             if ctx.debug_mode {
-                println!("DEBUG: TOKENIZATION CACHE: Cached tokenization for context {context_start}-{context_end}");
+                trace!("TOKENIZATION CACHE: Cached tokenization for context {context_start}-{context_end}");
             }
             terms
         };
@@ -836,8 +960,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         // Early filtering for fallback context
         let should_include = {
             if ctx.debug_mode {
-                println!(
-                    "DEBUG: Using filter_tokenized_block for merged fallback context {context_start}-{context_end}"
+                trace!(
+                    "Using filter_tokenized_block for merged fallback context {context_start}-{context_end}"
                 );
             }
 
@@ -846,18 +970,32 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
                 // In exact mode, we already matched the lines in the file
                 // so we should include this block without re-evaluating
                 if ctx.debug_mode {
-                    println!(
-                        "DEBUG: Exact mode enabled, skipping tokenization and evaluation for merged fallback context {context_start}-{context_end}"
+                    trace!(
+                        "Exact mode enabled, skipping tokenization and evaluation for merged fallback context {context_start}-{context_end}"
                     );
                 }
                 true
             } else {
-                filter_tokenized_block(
-                    &context_terms,
-                    &ctx.params.query_plan.term_indices,
-                    ctx.params.query_plan,
-                    ctx.debug_mode,
-                )
+                let terms_match = if ctx.params.query_plan.case_sensitive {
+                    filter_case_sensitive_block(
+                        (context_start, context_end),
+                        ctx.lines,
+                        ctx.params.query_plan,
+                    )
+                } else {
+                    filter_tokenized_block(
+                        &context_terms,
+                        &ctx.params.query_plan.term_indices,
+                        ctx.params.query_plan,
+                        ctx.debug_mode,
+                    )
+                };
+                terms_match
+                    && block_satisfies_proximity(
+                        (context_start, context_end),
+                        ctx.lines,
+                        ctx.params.query_plan,
+                    )
             }
         };
 
@@ -865,8 +1003,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
         let _filtering_duration = filtering_start.elapsed();
 
         if ctx.debug_mode {
-            println!(
-                "DEBUG: Merged context window at {context_start}-{context_end} filtered: included={should_include}"
+            trace!(
+                "Merged context window at {context_start}-{context_end} filtered: included={should_include}"
             );
         }
 
@@ -941,7 +1079,7 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
             // BATCH OPTIMIZATION: Get matched term indices for the entire merged context block
             let mut matched_term_indices = HashSet::new();
             let mut matched_line_numbers = HashSet::new();
-            for (&term_idx, lines) in ctx.params.term_matches {
+            for (&term_idx, lines) in ctx.term_matches {
                 for &line_num in lines {
                     if line_num >= context_start && line_num <= context_end {
                         matched_term_indices.insert(term_idx);
@@ -986,6 +1124,10 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
                 code: context_code,
                 symbol_signature,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -1048,8 +1190,8 @@ fn process_uncovered_lines_batch(ctx: &mut BatchProcessingContext) {
     }
 
     if ctx.debug_mode {
-        println!(
-            "DEBUG: SMART CONTEXT WINDOW MERGING: Completed processing {} uncovered lines with {} cache hits",
+        trace!(
+            "SMART CONTEXT WINDOW MERGING: Completed processing {} uncovered lines with {} cache hits",
             ctx.uncovered_lines.len(),
             tokenization_cache.len()
         );
@@ -1064,8 +1206,9 @@ thread_local! {
 // PHASE 3C OPTIMIZATION: Batch term index resolution
 fn resolve_term_indices_batch(
     tokens: &[String],
-    term_indices: &HashMap<String, usize>,
+    plan: &crate::search::query::QueryPlan,
 ) -> HashSet<usize> {
+    let term_indices = &plan.term_indices;
     let mut matched_terms = HashSet::with_capacity(tokens.len().min(term_indices.len()));
 
     for token in tokens {
@@ -1074,6 +1217,20 @@ fn resolve_term_indices_batch(
         }
     }
 
+    // Fuzzy terms also match tokens one edit away
+    if !plan.fuzzy_terms.is_empty() {
+        for (term, &idx) in term_indices {
+            if plan.fuzzy_terms.contains(&idx)
+                && !matched_terms.contains(&idx)
+                && tokens
+                    .iter()
+                    .any(|token| crate::search::fuzzy::within_one_edit(token, term))
+            {
+                matched_terms.insert(idx);
+            }
+        }
+    }
+
     matched_terms
 }
 
@@ -1120,7 +1277,7 @@ pub fn process_file_with_results(
         .unwrap_or("");
 
     // Get debug mode setting
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::TRACE);
 
     // Filter out lines longer than 500 characters
     let lines: Vec<&str> = content
@@ -1129,8 +1286,8 @@ pub fn process_file_with_results(
         .map(|(i, line)| {
             if line.len() > 500 {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Skipping line {} with length {} (exceeds 500 character limit)",
+                    trace!(
+                        "Skipping line {} with length {} (exceeds 500 character limit)",
                         i + 1,
                         line.len()
                     );
@@ -1141,6 +1298,7 @@ pub fn process_file_with_results(
             }
         })
         .collect();
+    let buffer = FileBuffer::new(&lines);
     let mut results = Vec::new();
     let mut covered_lines = HashSet::new();
     // We now use params.path.to_string_lossy() directly for tokenization
@@ -1158,13 +1316,10 @@ pub fn process_file_with_results(
     let unique_query_terms: HashSet<String> = query_terms.into_iter().collect();
 
     if debug_mode {
-        println!("DEBUG: Processing file: {:?}", params.path);
-        println!("DEBUG:   matched lines: {:?}", params.line_numbers);
-        println!("DEBUG:   file I/O time: {file_io_duration:?}");
-        println!(
-            "DEBUG: Processing {} unique query terms",
-            unique_query_terms.len()
-        );
+        trace!("Processing file: {:?}", params.path);
+        trace!("  matched lines: {:?}", params.line_numbers);
+        trace!("  file I/O time: {file_io_duration:?}");
+        trace!("Processing {} unique query terms", unique_query_terms.len());
     }
 
     // Measure AST parsing time with sub-steps
@@ -1215,14 +1370,43 @@ pub fn process_file_with_results(
     // Measure line map building time (this is an approximation since we can't directly measure it)
     let line_map_building_start = Instant::now();
 
+    // --no-comments, --comments-only and --strings-only keep the matches on lines with
+    // code, with comments or with strings, and blocks are tokenized without their
+    // comments, or with only their comments or strings. `inside` keeps the masked text.
+    let syntax_mask = if params.strings_only {
+        Some((SyntaxMask::strings(&content, parsed_tree.as_ref()), true))
+    } else if params.no_comments || params.comments_only {
+        Some((
+            SyntaxMask::comments(&content, parsed_tree.as_ref()),
+            params.comments_only,
+        ))
+    } else {
+        None
+    };
+    let narrowed_matches = syntax_mask
+        .as_ref()
+        .map(|(mask, inside)| mask.narrow_matches(&content, params.term_matches, *inside));
+    let (line_numbers, term_matches) = match &narrowed_matches {
+        Some((line_numbers, term_matches)) => (line_numbers, term_matches),
+        None => (params.line_numbers, params.term_matches),
+    };
+
+    // Symbol signatures are read from the same tree the blocks come from
+    let symbol_tree = if params.symbols_only {
+        parsed_tree.clone()
+    } else {
+        None
+    };
+
     // Call parse_file_for_code_blocks with the pre-parsed tree to avoid double parsing
     let code_blocks_result = parse_file_for_code_blocks_with_tree(
         &content,
         extension,
-        params.line_numbers,
+        line_numbers,
         params.allow_tests,
-        Some(params.term_matches),
+        Some(term_matches),
         parsed_tree,
+        !params.no_attributes,
     );
 
     let line_map_building_duration = line_map_building_start.elapsed();
@@ -1233,11 +1417,11 @@ pub fn process_file_with_results(
     timings.ast_parsing = Some(ast_parsing_duration);
 
     if debug_mode {
-        println!("DEBUG:   AST parsing time: {ast_parsing_duration:?}");
-        println!("DEBUG:     - Language init: {language_init_duration:?}");
-        println!("DEBUG:     - Parser init: {parser_init_duration:?}");
-        println!("DEBUG:     - Tree parsing: {tree_parsing_duration:?}");
-        println!("DEBUG:     - Line map building: {line_map_building_duration:?}");
+        trace!("  AST parsing time: {ast_parsing_duration:?}");
+        trace!("    - Language init: {language_init_duration:?}");
+        trace!("    - Parser init: {parser_init_duration:?}");
+        trace!("    - Tree parsing: {tree_parsing_duration:?}");
+        trace!("    - Line map building: {line_map_building_duration:?}");
     }
 
     if let Ok(code_blocks) = code_blocks_result {
@@ -1245,12 +1429,12 @@ pub fn process_file_with_results(
         results.reserve(code_blocks.len());
 
         if debug_mode {
-            println!("DEBUG: AST parsing successful");
-            println!("DEBUG:   Found {} code blocks", code_blocks.len());
+            trace!("AST parsing successful");
+            trace!("  Found {} code blocks", code_blocks.len());
 
             for (i, block) in code_blocks.iter().enumerate() {
-                println!(
-                    "DEBUG:   Block {}: type={}, lines={}-{}",
+                trace!(
+                    "  Block {}: type={}, lines={}-{}",
                     i + 1,
                     block.node_type,
                     block.start_row + 1,
@@ -1314,12 +1498,10 @@ pub fn process_file_with_results(
                 };
 
                 let full_code = if final_start_line > 0 && final_end_line <= lines.len() {
-                    // Skip empty lines (which were originally too long)
-                    lines[final_start_line - 1..final_end_line]
-                        .to_vec()
-                        .join("\n")
+                    // Lines that were too long are empty in the buffer
+                    buffer.block(final_start_line, final_end_line)
                 } else {
-                    "".to_string()
+                    BlockCode::default()
                 };
 
                 // End code structure finding time for this block
@@ -1329,16 +1511,47 @@ pub fn process_file_with_results(
                     *duration += block_duration;
                 }
 
+                // In symbols-only mode a block is matched and ranked by its signature,
+                // so call sites inside other blocks don't count
+                let symbol_signature = if params.symbols_only {
+                    let signature = extract_symbol_signature(
+                        true,
+                        symbol_tree.as_ref(),
+                        extension,
+                        content.as_bytes(),
+                        block.start_byte,
+                        block.end_byte,
+                        debug_mode,
+                    );
+                    if signature.is_none() {
+                        if debug_mode {
+                            trace!(
+                                "Block {final_start_line}-{final_end_line} has no symbol signature, skipping"
+                            );
+                        }
+                        return;
+                    }
+                    signature
+                } else {
+                    None
+                };
+
                 // Start measuring term matching time
                 let term_matching_start = Instant::now();
 
                 // PHASE 3B OPTIMIZATION: Use global tokenization cache
                 let cache_key = compute_content_hash(&full_code, &params.path.to_string_lossy());
-                let block_terms = {
+                let block_terms = if let Some(signature) = &symbol_signature {
+                    ranking::preprocess_text(signature)
+                } else if let Some((mask, inside)) = &syntax_mask {
+                    let text =
+                        mask.block_text(&content, final_start_line, final_end_line, *inside);
+                    ranking::preprocess_text_with_filename(&text, &params.path.to_string_lossy())
+                } else {
                     let mut cache = TOKENIZATION_CACHE.lock().unwrap();
                     if let Some(cached_terms) = cache.get(&cache_key) {
                         if debug_mode {
-                            println!("DEBUG: PHASE 3B - Using cached tokenization for block");
+                            trace!("PHASE 3B - Using cached tokenization for block");
                         }
                         cached_terms.clone()
                     } else {
@@ -1350,7 +1563,7 @@ pub fn process_file_with_results(
                         let mut cache = TOKENIZATION_CACHE.lock().unwrap();
                         cache.put(cache_key, terms.clone());
                         if debug_mode {
-                            println!("DEBUG: PHASE 3B - Cached new tokenization for block");
+                            trace!("PHASE 3B - Cached new tokenization for block");
                         }
                         terms
                     }
@@ -1368,33 +1581,50 @@ pub fn process_file_with_results(
                 // Early filtering using tokenized content
                 let should_include = {
                     if debug_mode {
-                        println!(
-                            "DEBUG: Using filter_tokenized_block for block {final_start_line}-{final_end_line}"
+                        trace!(
+                            "Using filter_tokenized_block for block {final_start_line}-{final_end_line}"
                         );
                     }
 
-                    // Skip tokenization and evaluation when exact flag is enabled
-                    if params.query_plan.exact {
+                    // Skip tokenization and evaluation when exact flag is enabled, unless
+                    // the matched lines may lie outside the signature
+                    if params.query_plan.exact && !params.symbols_only {
                         // In exact mode, we already matched the lines in the file
                         // so we should include this block without re-evaluating
                         if debug_mode {
-                            println!(
-                                "DEBUG: Exact mode enabled, skipping tokenization and evaluation for block {final_start_line}-{final_end_line}"
+                            trace!(
+                                "Exact mode enabled, skipping tokenization and evaluation for block {final_start_line}-{final_end_line}"
                             );
                         }
                         true
                     } else {
                         // Use the AST evaluation directly to ensure correct handling of complex queries
-                        let result = filter_tokenized_block(
-                            &block_terms,
-                            &params.query_plan.term_indices,
-                            params.query_plan,
-                            debug_mode,
-                        );
+                        let terms_match = if params.query_plan.case_sensitive
+                            && !params.symbols_only
+                        {
+                            filter_case_sensitive_block(
+                                (final_start_line, final_end_line),
+                                &lines,
+                                params.query_plan,
+                            )
+                        } else {
+                            filter_tokenized_block(
+                                &block_terms,
+                                &params.query_plan.term_indices,
+                                params.query_plan,
+                                debug_mode,
+                            )
+                        };
+                        let result = terms_match
+                            && block_satisfies_proximity(
+                                (final_start_line, final_end_line),
+                                &lines,
+                                params.query_plan,
+                            );
 
                         if debug_mode {
-                            println!(
-                                "DEBUG: Block {final_start_line}-{final_end_line} filter result: {result}"
+                            trace!(
+                                "Block {final_start_line}-{final_end_line} filter result: {result}"
                             );
                         }
 
@@ -1410,8 +1640,8 @@ pub fn process_file_with_results(
                 }
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Block lines {final_start_line}-{final_end_line} => should_include={should_include}"
+                    trace!(
+                        "Block lines {final_start_line}-{final_end_line} => should_include={should_include}"
                     );
                 }
 
@@ -1496,7 +1726,7 @@ pub fn process_file_with_results(
                     // Get the matched term indices and line numbers for this block
                     let mut matched_term_indices = HashSet::new();
                     let mut matched_line_numbers = HashSet::new();
-                    for (&term_idx, lines) in params.term_matches {
+                    for (&term_idx, lines) in term_matches {
                         for &line_num in lines {
                             if line_num >= final_start_line && line_num <= final_end_line {
                                 matched_term_indices.insert(term_idx);
@@ -1528,28 +1758,6 @@ pub fn process_file_with_results(
                     // Start measuring result creation time
                     let result_creation_start = Instant::now();
 
-                    // Extract symbol signature if symbols mode is enabled
-                    let symbol_signature = if false { // symbols functionality removed
-                        // Re-parse the tree for symbol signature extraction when needed
-                        let tree = crate::language::get_or_parse_tree_pooled(
-                            &format!("{}_{}", params.path.to_string_lossy(), extension),
-                            &content,
-                            extension,
-                        ).ok();
-
-                        extract_symbol_signature(
-                            true,
-                            tree.as_ref(),
-                            extension,
-                            content.as_bytes(),
-                            block.start_byte,
-                            block.end_byte,
-                            debug_mode,
-                        )
-                    } else {
-                        None
-                    };
-
                     let result = SearchResult {
                         file: params.path.to_string_lossy().to_string(),
                         lines: (final_start_line, final_end_line),
@@ -1564,6 +1772,10 @@ pub fn process_file_with_results(
                         code: full_code,
                         symbol_signature,
                         matched_by_filename: None,
+                        duplicate_files: None,
+                        blame: None,
+                        owners: None,
+                        provenance: None,
                         rank: None,
                         score: None,
                         tfidf_score: None,
@@ -1694,22 +1906,22 @@ pub fn process_file_with_results(
         timings.result_building_synchronization = Some(synchronization_duration_value);
 
         if debug_mode {
-            println!("DEBUG:   Block extraction time: {block_extraction_duration:?}");
-            println!("DEBUG:     - Code structure finding: {code_structure_duration_value:?}");
-            println!("DEBUG:     - Filtering: {filtering_duration_value:?}");
-            println!("DEBUG:     - Result building: {result_building_duration_value:?}");
+            trace!("  Block extraction time: {block_extraction_duration:?}");
+            trace!("    - Code structure finding: {code_structure_duration_value:?}");
+            trace!("    - Filtering: {filtering_duration_value:?}");
+            trace!("    - Result building: {result_building_duration_value:?}");
         }
     }
 
     // Collect all uncovered lines first without processing them
     // PHASE 4 OPTIMIZATION: Pre-allocate uncovered lines vector
-    let mut uncovered_lines = Vec::with_capacity(params.line_numbers.len());
-    for &line_num in params.line_numbers {
+    let mut uncovered_lines = Vec::with_capacity(line_numbers.len());
+    for &line_num in line_numbers {
         if !covered_lines.contains(&line_num) {
             if debug_mode {
-                println!("DEBUG: Line {line_num} not covered, will use fallback context");
+                trace!("Line {line_num} not covered, will use fallback context");
                 if line_num <= lines.len() {
-                    println!("DEBUG:   Line content: '{}'", lines[line_num - 1].trim());
+                    trace!("  Line content: '{}'", lines[line_num - 1].trim());
                 }
             }
             uncovered_lines.push(line_num);
@@ -1722,12 +1934,14 @@ pub fn process_file_with_results(
     // BATCH OPTIMIZATION: Process uncovered lines in batches to improve performance by 8-12 seconds
     // Instead of processing each uncovered line individually, we batch them by file and process
     // multiple lines together. This eliminates parser creation overhead and reduces repeated work.
-    if !uncovered_lines.is_empty() {
+    if !uncovered_lines.is_empty() && !params.symbols_only {
         let mut batch_ctx = BatchProcessingContext {
             uncovered_lines: &uncovered_lines,
             covered_lines: &mut covered_lines,
             lines: &lines,
+            buffer: &buffer,
             params,
+            term_matches,
             extension,
             unique_query_terms: &unique_query_terms,
             results: &mut results,
@@ -1742,58 +1956,58 @@ pub fn process_file_with_results(
     timings.result_building_uncovered_lines = Some(uncovered_lines_duration);
 
     if debug_mode {
-        println!("DEBUG: File processing timings:");
+        trace!("File processing timings:");
         if let Some(duration) = timings.file_io {
-            println!("DEBUG:   File I/O: {duration:?}");
+            trace!("  File I/O: {duration:?}");
         }
         if let Some(duration) = timings.ast_parsing {
-            println!("DEBUG:   AST parsing: {duration:?}");
+            trace!("  AST parsing: {duration:?}");
             if let Some(d) = timings.ast_parsing_language_init {
-                println!("DEBUG:     - Language init: {d:?}");
+                trace!("    - Language init: {d:?}");
             }
             if let Some(d) = timings.ast_parsing_parser_init {
-                println!("DEBUG:     - Parser init: {d:?}");
+                trace!("    - Parser init: {d:?}");
             }
             if let Some(d) = timings.ast_parsing_tree_parsing {
-                println!("DEBUG:     - Tree parsing: {d:?}");
+                trace!("    - Tree parsing: {d:?}");
             }
             if let Some(d) = timings.ast_parsing_line_map_building {
-                println!("DEBUG:     - Line map building: {d:?}");
+                trace!("    - Line map building: {d:?}");
             }
         }
         if let Some(duration) = timings.block_extraction {
-            println!("DEBUG:   Block extraction: {duration:?}");
+            trace!("  Block extraction: {duration:?}");
             if let Some(d) = timings.block_extraction_code_structure {
-                println!("DEBUG:     - Code structure finding: {d:?}");
+                trace!("    - Code structure finding: {d:?}");
             }
             if let Some(d) = timings.block_extraction_filtering {
-                println!("DEBUG:     - Filtering: {d:?}");
+                trace!("    - Filtering: {d:?}");
             }
             if let Some(d) = timings.block_extraction_result_building {
-                println!("DEBUG:     - Result building: {d:?}");
+                trace!("    - Result building: {d:?}");
             }
         }
     }
 
     if debug_mode {
-        println!("DEBUG: Detailed result building timings:");
+        trace!("Detailed result building timings:");
         if let Some(duration) = timings.result_building_term_matching {
-            println!("DEBUG:   Term matching: {duration:?}");
+            trace!("  Term matching: {duration:?}");
         }
         if let Some(duration) = timings.result_building_compound_processing {
-            println!("DEBUG:   Compound word processing: {duration:?}");
+            trace!("  Compound word processing: {duration:?}");
         }
         if let Some(duration) = timings.result_building_line_matching {
-            println!("DEBUG:   Line range matching: {duration:?}");
+            trace!("  Line range matching: {duration:?}");
         }
         if let Some(duration) = timings.result_building_result_creation {
-            println!("DEBUG:   Result creation: {duration:?}");
+            trace!("  Result creation: {duration:?}");
         }
         if let Some(duration) = timings.result_building_synchronization {
-            println!("DEBUG:   Synchronization: {duration:?}");
+            trace!("  Synchronization: {duration:?}");
         }
         if let Some(duration) = timings.result_building_uncovered_lines {
-            println!("DEBUG:   Uncovered lines processing: {duration:?}");
+            trace!("  Uncovered lines processing: {duration:?}");
         }
     }
 
@@ -1819,7 +2033,7 @@ fn extract_symbol_signature(
     let language_impl = crate::language::factory::get_language_impl(extension)?;
 
     if debug_mode {
-        println!("DEBUG: Extracting symbol signature for byte range {start_byte}-{end_byte}");
+        trace!("Extracting symbol signature for byte range {start_byte}-{end_byte}");
     }
 
     // Find the node at the given byte range
@@ -1845,12 +2059,12 @@ fn extract_symbol_signature_from_content(
     let language_impl = crate::language::factory::get_language_impl(extension)?;
 
     if debug_mode {
-        println!("DEBUG: Extracting symbol signature from content snippet");
+        trace!("Extracting symbol signature from content snippet");
     }
 
     // Try to parse the content as a standalone snippet
     if let Ok(mut parser) = crate::language::get_pooled_parser(extension) {
-        if let Some(tree) = parser.parse(content, None) {
+        if let Some(tree) = crate::language::timed_parse(&mut parser, extension, content) {
             let root_node = tree.root_node();
 
             // Look for the most significant node in the content
@@ -1867,13 +2081,13 @@ fn extract_symbol_signature_from_content(
             signature
         } else {
             if debug_mode {
-                println!("DEBUG: Failed to parse content for symbol signature");
+                trace!("Failed to parse content for symbol signature");
             }
             None
         }
     } else {
         if debug_mode {
-            println!("DEBUG: Failed to get parser for symbol signature extraction");
+            trace!("Failed to get parser for symbol signature extraction");
         }
         None
     }
@@ -1890,8 +2104,8 @@ fn find_best_symbol_signature(
     // Try current node first
     if let Some(signature) = language_impl.get_symbol_signature(node, source) {
         if debug_mode {
-            println!(
-                "DEBUG: Found symbol signature for node type '{}': {}",
+            trace!(
+                "Found symbol signature for node type '{}': {}",
                 node.kind(),
                 signature
             );
@@ -1963,8 +2177,8 @@ fn find_symbol_signature_upward(
 
     while let Some(node) = current_node {
         if debug_mode {
-            println!(
-                "DEBUG: Checking node of type '{}' for symbol signature (range {}-{})",
+            trace!(
+                "Checking node of type '{}' for symbol signature (range {}-{})",
                 node.kind(),
                 node.start_byte(),
                 node.end_byte()
@@ -1975,16 +2189,16 @@ fn find_symbol_signature_upward(
         if node.kind() != "source_file" {
             if let Some(signature) = language_impl.get_symbol_signature(&node, source) {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Found symbol signature for node type '{}': {}",
+                    trace!(
+                        "Found symbol signature for node type '{}': {}",
                         node.kind(),
                         signature
                     );
                 }
                 return Some(signature);
             } else if debug_mode {
-                println!(
-                    "DEBUG: No symbol signature available for node type '{}'",
+                trace!(
+                    "No symbol signature available for node type '{}'",
                     node.kind()
                 );
             }
@@ -1995,7 +2209,27 @@ fn find_symbol_signature_upward(
     }
 
     if debug_mode {
-        println!("DEBUG: No symbol signature found in any parent node");
+        trace!("No symbol signature found in any parent node");
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_buffer_blocks_match_joined_lines() {
+        let lines = ["fn main() {", "", "    run();", "}"];
+        let buffer = FileBuffer::new(&lines);
+        for start in 1..=lines.len() {
+            for end in start - 1..=lines.len() {
+                assert_eq!(
+                    buffer.block(start, end),
+                    lines[start - 1..end].join("\n"),
+                    "lines {start}..={end}"
+                );
+            }
+        }
+    }
+}
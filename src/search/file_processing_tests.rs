@@ -48,6 +48,10 @@ pub fn create_test_query_plan(terms: &[&str]) -> QueryPlan {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
+        smart_case: false,
+        case_sensitive: false,
+        written_terms: HashMap::new(),
         is_simple_query: true,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -57,6 +61,7 @@ pub fn create_test_query_plan(terms: &[&str]) -> QueryPlan {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        fuzzy_terms: HashSet::new(),
     }
 }
 
@@ -103,6 +108,11 @@ mod tests {
             preprocessed_queries: None,
             query_plan: &query_plan,
             no_merge: false,
+            no_attributes: false,
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
         };
 
         let (results, _) =
@@ -154,6 +164,11 @@ mod tests {
             preprocessed_queries: None,
             query_plan: &query_plan,
             no_merge: false,
+            no_attributes: false,
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
         };
 
         // Capture the results to check them
@@ -204,6 +219,11 @@ mod tests {
             preprocessed_queries: None,
             query_plan: &query_plan,
             no_merge: false,
+            no_attributes: false,
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
         };
 
         let (results, _) =
@@ -281,6 +301,11 @@ function test3() {
             preprocessed_queries: None, // No preprocessed queries
             query_plan: &query_plan,
             no_merge: false,
+            no_attributes: false,
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
         };
 
         let (results, _) =
@@ -380,6 +405,11 @@ function processResults(results) {
             preprocessed_queries: Some(&preprocessed_queries),
             query_plan: &query_plan,
             no_merge: false,
+            no_attributes: false,
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
         };
 
         let (results, _) =
@@ -444,6 +474,11 @@ fn test_long_lines_are_ignored() {
         preprocessed_queries: None,
         query_plan: &query_plan,
         no_merge: false,
+        no_attributes: false,
+        symbols_only: false,
+        no_comments: false,
+        comments_only: false,
+        strings_only: false,
     };
 
     let (results, _) =
@@ -1,4 +1,8 @@
+use anyhow::{anyhow, Result};
 use glob::Pattern;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::types::{FileTypeDef, Types, TypesBuilder};
+use probe_code::warnings::{warn, WarningKind};
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -17,6 +21,11 @@ pub struct SearchFilters {
     pub languages: Vec<String>,
     /// Exact filenames (from filename: hints or auto-detected)
     pub exact_filenames: Vec<String>,
+    /// Match terms case-sensitively (from a case:sensitive hint); not a file filter
+    pub case_sensitive: bool,
+    /// Code owners from CODEOWNERS (from owner: hints); applied by the search, which
+    /// knows the repository, not by `matches_file`
+    pub owners: Vec<String>,
 }
 
 impl SearchFilters {
@@ -87,9 +96,22 @@ impl SearchFilters {
                     }
                 }
             }
+            "owner" | "owners" => {
+                self.owners.extend(values);
+            }
+            "case" => {
+                // Hint values are stemmed by the query parser: "sensitive" arrives as "sensit"
+                self.case_sensitive = values
+                    .iter()
+                    .any(|value| matches!(value.to_lowercase().as_str(), "sensitive" | "sensit"));
+            }
             _ => {
                 // Unknown filter type - ignore or log warning
-                eprintln!("Warning: Unknown filter type '{}'", field_name);
+                warn(
+                    WarningKind::Other,
+                    None,
+                    format!("Unknown filter type '{field_name}'"),
+                );
             }
         }
     }
@@ -220,6 +242,97 @@ impl SearchFilters {
     }
 }
 
+/// Path globs from `--include`: only files matching one of them are searched. Globs use
+/// .gitignore syntax relative to the search root, so `*.rs` matches at any depth and
+/// `src/**/*.rs` only under `src`.
+#[derive(Debug)]
+pub struct IncludeGlobs {
+    /// One matcher per search root
+    overrides: Vec<Override>,
+}
+
+impl IncludeGlobs {
+    /// Globs matched against paths under `roots`; `None` when there are no patterns
+    pub fn new<P: AsRef<Path>>(roots: &[P], patterns: &[String]) -> Result<Option<Self>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let mut overrides = Vec::new();
+        for root in roots {
+            let mut builder = OverrideBuilder::new(root);
+            for pattern in patterns {
+                builder
+                    .add(pattern)
+                    .map_err(|e| anyhow!("Invalid --include glob {pattern:?}: {e}"))?;
+            }
+            overrides.push(builder.build()?);
+        }
+        Ok(Some(IncludeGlobs { overrides }))
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.overrides
+            .iter()
+            .any(|globs| globs.matched(path, false).is_whitelist())
+    }
+}
+
+/// File types from `--type`, using ripgrep's type definitions plus user-defined ones
+/// (`--type-add 'web:*.html,*.css'` or the `[types]` section of probe.toml): only files
+/// of one of the selected types are searched.
+#[derive(Debug)]
+pub struct FileTypes {
+    types: Types,
+}
+
+impl FileTypes {
+    /// Matcher for the `selected` types; `None` when no type is selected
+    pub fn new(selected: &[String], definitions: &[String]) -> Result<Option<Self>> {
+        if selected.is_empty() {
+            return Ok(None);
+        }
+        let mut builder = types_builder(definitions)?;
+        for name in selected {
+            builder.select(name);
+        }
+        let types = builder
+            .build()
+            .map_err(|e| anyhow!("Invalid --type: {e} (see --type-list)"))?;
+        Ok(Some(FileTypes { types }))
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.types.matched(path, false).is_whitelist()
+    }
+}
+
+/// All known file types, built-in and user-defined, sorted by name
+pub fn file_type_definitions(definitions: &[String]) -> Result<Vec<FileTypeDef>> {
+    Ok(types_builder(definitions)?.definitions())
+}
+
+/// ripgrep's default types with `definitions` (`name:glob[,glob...]` or
+/// `name:include:type[,type...]`) added
+fn types_builder(definitions: &[String]) -> Result<TypesBuilder> {
+    let mut builder = TypesBuilder::new();
+    builder.add_defaults();
+    for definition in definitions {
+        let invalid = |e: &dyn std::fmt::Display| anyhow!("Invalid type {definition:?}: {e}");
+        match definition.split_once(':') {
+            Some((_, rest)) if rest.starts_with("include:") => {
+                builder.add_def(definition).map_err(|e| invalid(&e))?;
+            }
+            Some((name, globs)) => {
+                for glob in globs.split(',').map(str::trim).filter(|g| !g.is_empty()) {
+                    builder.add(name, glob).map_err(|e| invalid(&e))?;
+                }
+            }
+            None => return Err(invalid(&"expected name:glob[,glob...]")),
+        }
+    }
+    Ok(builder)
+}
+
 /// Simplify AST by extracting filter terms and removing them
 fn simplify_ast(
     expr: crate::search::elastic_query::Expr,
@@ -264,6 +377,16 @@ fn simplify_ast(
                 (None, None) => None,
             }
         }
+        Expr::Near(left, right, distance) => {
+            let left_simplified = simplify_ast(*left, filters);
+            let right_simplified = simplify_ast(*right, filters);
+
+            match (left_simplified, right_simplified) {
+                (Some(l), Some(r)) => Some(Expr::Near(Box::new(l), Box::new(r), distance)),
+                (Some(expr), None) | (None, Some(expr)) => Some(expr),
+                (None, None) => None,
+            }
+        }
     }
 }
 
@@ -347,6 +470,16 @@ fn simplify_ast_with_autodetect(
                 (None, None) => None,
             }
         }
+        Expr::Near(left, right, distance) => {
+            let left_simplified = simplify_ast_with_autodetect(*left, filters);
+            let right_simplified = simplify_ast_with_autodetect(*right, filters);
+
+            match (left_simplified, right_simplified) {
+                (Some(l), Some(r)) => Some(Expr::Near(Box::new(l), Box::new(r), distance)),
+                (Some(expr), None) | (None, Some(expr)) => Some(expr),
+                (None, None) => None,
+            }
+        }
     }
 }
 
@@ -364,6 +497,9 @@ fn is_filter_field(field_name: &str) -> bool {
             | "directory"
             | "lang"
             | "language"
+            | "owner"
+            | "owners"
+            | "case"
     )
 }
 
@@ -617,6 +753,50 @@ mod tests {
         assert!(!filters.matches_file(&PathBuf::from("tests/main.rs")));
     }
 
+    #[test]
+    fn test_include_globs() {
+        assert!(IncludeGlobs::new(&["."], &[]).unwrap().is_none());
+
+        let globs = IncludeGlobs::new(&["."], &["src/**/*.rs".to_string(), "*.md".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(globs.matches(Path::new("./src/main.rs")));
+        assert!(globs.matches(Path::new("./src/lib/helper.rs")));
+        assert!(globs.matches(Path::new("./docs/guide.md")));
+        assert!(!globs.matches(Path::new("./tests/main.rs")));
+        assert!(!globs.matches(Path::new("./src/main.js")));
+
+        let globs = IncludeGlobs::new(&["/repo"], &["src/*.rs".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(globs.matches(Path::new("/repo/src/main.rs")));
+        assert!(!globs.matches(Path::new("/repo/src/lib/helper.rs")));
+    }
+
+    #[test]
+    fn test_file_types() {
+        assert!(FileTypes::new(&[], &[]).unwrap().is_none());
+
+        let types = FileTypes::new(&["rust".to_string()], &[]).unwrap().unwrap();
+        assert!(types.matches(Path::new("src/main.rs")));
+        assert!(!types.matches(Path::new("src/main.py")));
+
+        let definitions = vec!["web:*.html,*.css".to_string()];
+        let types = FileTypes::new(&["web".to_string()], &definitions)
+            .unwrap()
+            .unwrap();
+        assert!(types.matches(Path::new("site/index.html")));
+        assert!(types.matches(Path::new("site/style.css")));
+        assert!(!types.matches(Path::new("site/app.js")));
+
+        assert!(FileTypes::new(&["klingon".to_string()], &[]).is_err());
+        assert!(FileTypes::new(&["web".to_string()], &["web".to_string()]).is_err());
+        assert!(file_type_definitions(&definitions)
+            .unwrap()
+            .iter()
+            .any(|def| def.name() == "web" && def.globs() == ["*.css", "*.html"]));
+    }
+
     #[test]
     fn test_type_filter() {
         let mut filters = SearchFilters::new();
@@ -770,6 +950,19 @@ mod tests {
         assert!(simplified2.is_none());
     }
 
+    #[test]
+    fn test_owner_hint_keeps_team_whole() {
+        use crate::search::elastic_query::parse_query;
+
+        let ast = parse_query("owner:@org/backend-team AND handler", false).unwrap();
+        let (filters, simplified) = SearchFilters::extract_and_simplify_with_autodetect(ast);
+
+        assert_eq!(filters.owners, vec!["@org/backend-team".to_string()]);
+        // Owners are applied by the search, not as a file filter
+        assert!(filters.is_empty());
+        assert!(simplified.is_some());
+    }
+
     #[test]
     fn test_filename_with_or_query() {
         use crate::search::elastic_query::parse_query;
@@ -0,0 +1,145 @@
+//! Typo-tolerant matching of query terms.
+//!
+//! With `--fuzzy`, or for a term written with a `~` suffix (`recieve~`), a term also
+//! matches words one edit away: one inserted, deleted or substituted character, or two
+//! adjacent characters swapped. Lines are found with a regex of every one-edit variant
+//! of the term, and blocks are then kept only if one of their tokens is within one edit
+//! of the term.
+
+use probe_code::search::query::QueryPlan;
+use probe_code::search::tokenization;
+use std::collections::HashSet;
+
+/// Shorter terms are never matched fuzzily, as most short words are one edit apart
+const MIN_FUZZY_LEN: usize = 4;
+
+/// Indices of the plan's terms to match fuzzily: every included term with `all_terms`
+/// (`--fuzzy`), otherwise those written with a `~` suffix in `query`
+pub fn fuzzy_term_indices(query: &str, plan: &QueryPlan, all_terms: bool) -> HashSet<usize> {
+    // Exact searches match the query literally
+    if plan.exact {
+        return HashSet::new();
+    }
+
+    let candidates: Vec<String> = if all_terms {
+        plan.term_indices.keys().cloned().collect()
+    } else {
+        suffixed_words(query)
+            .flat_map(tokenization::tokenize)
+            .map(|token| token.to_lowercase())
+            .collect()
+    };
+
+    candidates
+        .iter()
+        .filter(|term| term.chars().count() >= MIN_FUZZY_LEN)
+        .filter(|term| !plan.excluded_terms.contains(*term))
+        .filter_map(|term| plan.term_indices.get(term).copied())
+        .collect()
+}
+
+/// Words of the query marked with a trailing `~`, without it. Quoted terms are exact,
+/// so a `~` after a closing quote is ignored.
+fn suffixed_words(query: &str) -> impl Iterator<Item = &str> {
+    query
+        .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter_map(|word| word.strip_suffix('~'))
+        .filter(|word| !word.ends_with('"'))
+        .map(|word| word.trim_start_matches(['+', '-']))
+        .filter(|word| !word.is_empty())
+}
+
+/// A regex matching any text containing `term` or a word one edit away from it
+pub fn fuzzy_pattern(term: &str) -> String {
+    let chars: Vec<String> = term
+        .chars()
+        .map(|c| regex::escape(&c.to_string()))
+        .collect();
+    let variant = |parts: &[&[String]]| parts.concat().concat();
+    let any_char = [r"\w".to_string()];
+
+    let mut variants = Vec::new();
+    for i in 0..chars.len() {
+        // Deletion and substitution of character i
+        variants.push(variant(&[&chars[..i], &chars[i + 1..]]));
+        variants.push(variant(&[&chars[..i], &any_char, &chars[i + 1..]]));
+        // Insertion before character i; at either end the term itself already matches
+        if i > 0 {
+            variants.push(variant(&[&chars[..i], &any_char, &chars[i..]]));
+        }
+        // Swap of characters i and i + 1
+        if i + 1 < chars.len() {
+            let swapped = [chars[i + 1].clone(), chars[i].clone()];
+            variants.push(variant(&[&chars[..i], &swapped, &chars[i + 2..]]));
+        }
+    }
+    variants.sort();
+    variants.dedup();
+
+    format!("(?i)({})", variants.join("|"))
+}
+
+/// Whether `a` and `b` are equal or one insertion, deletion, substitution or swap of
+/// adjacent characters apart
+pub fn within_one_edit(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    let prefix = short
+        .iter()
+        .zip(long.iter())
+        .take_while(|(x, y)| x == y)
+        .count();
+    if short.len() < long.len() {
+        return short[prefix..] == long[prefix + 1..];
+    }
+    if prefix == short.len() {
+        return true;
+    }
+    short[prefix + 1..] == long[prefix + 1..]
+        || (prefix + 1 < short.len()
+            && short[prefix] == long[prefix + 1]
+            && short[prefix + 1] == long[prefix]
+            && short[prefix + 2..] == long[prefix + 2..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_within_one_edit() {
+        assert!(within_one_edit("receive", "receive"));
+        assert!(within_one_edit("recieve", "receive")); // swap
+        assert!(within_one_edit("recive", "receive")); // insertion
+        assert!(within_one_edit("receivee", "receive")); // deletion
+        assert!(within_one_edit("receiva", "receive")); // substitution
+        assert!(!within_one_edit("rceiev", "receive"));
+        assert!(!within_one_edit("recv", "receive"));
+    }
+
+    #[test]
+    fn test_fuzzy_pattern() {
+        let pattern = Regex::new(&fuzzy_pattern("reciev")).unwrap();
+        for text in ["fn receive()", "RECIEVE", "let recive = 1", "reciever"] {
+            assert!(pattern.is_match(text), "{text}");
+        }
+        assert!(!pattern.is_match("fn send()"));
+    }
+
+    #[test]
+    fn test_suffixed_words() {
+        let words: Vec<&str> =
+            suffixed_words("recieve~ AND (+handler~ OR -\"exact\"~) plain").collect();
+        assert_eq!(words, vec!["recieve", "handler"]);
+    }
+}
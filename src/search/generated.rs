@@ -0,0 +1,186 @@
+//! Detection of generated files and license-only files.
+//!
+//! Generated protobuf/OpenAPI/ORM files repeat every identifier of the schema they were
+//! built from and tend to dominate results. They are recognised by the "do not edit" /
+//! "generated by" header their generators write, and by a few well-known file name
+//! suffixes. Files that hold nothing but a license text (LICENSE, COPYING, or a source
+//! file with only a license comment) are treated the same way. Both are excluded from
+//! search results unless `--include-generated` is given.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// How many leading lines are checked for a generated-file marker
+const HEADER_LINES: usize = 20;
+
+/// How much of a file is read to decide; license-only source files are short
+const READ_LIMIT: u64 = 16 * 1024;
+
+/// Markers generators put in the header comment, matched case-insensitively
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "do not modify",
+    "code generated by",
+    "autogenerated",
+    "auto-generated",
+    "automatically generated",
+    "generated by the protocol buffer compiler",
+    "this file was generated",
+    "this file is generated",
+];
+
+/// File name suffixes used only by code generators
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".pb.swift",
+    ".g.dart",
+    ".freezed.dart",
+    ".designer.cs",
+];
+
+/// Base names (without extension) of files that only carry a license
+const LICENSE_NAMES: &[&str] = &["license", "licence", "copying", "notice", "unlicense"];
+
+const COMMENT_PREFIXES: &[&str] = &[
+    "//", "#", "/*", "*", "<!--", "--", ";", "%", "\"\"\"", "'''",
+];
+
+fn is_comment_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    COMMENT_PREFIXES
+        .iter()
+        .any(|prefix| trimmed.starts_with(prefix))
+        || trimmed.ends_with("*/")
+        || trimmed.ends_with("-->")
+}
+
+/// Whether the file header carries a generator's "do not edit" marker.
+/// Only comment lines are considered so ordinary code mentioning "generated by" is not caught.
+pub fn has_generated_header(content: &str) -> bool {
+    content
+        .lines()
+        .take(HEADER_LINES)
+        .filter(|line| is_comment_line(line))
+        .any(|line| {
+            let lower = line.to_lowercase();
+            GENERATED_MARKERS
+                .iter()
+                .any(|marker| lower.contains(marker))
+        })
+}
+
+/// Whether the content is only a license text: every non-blank line is a comment
+/// and the comments mention a license or copyright
+pub fn is_license_only(content: &str) -> bool {
+    let mut lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    if lines.peek().is_none() {
+        return false;
+    }
+    let mut mentions_license = false;
+    for line in lines {
+        if !is_comment_line(line) {
+            return false;
+        }
+        let lower = line.to_lowercase();
+        mentions_license |=
+            lower.contains("license") || lower.contains("licence") || lower.contains("copyright");
+    }
+    mentions_license
+}
+
+/// Whether the file name alone marks the file as generated or as a license file
+pub fn is_generated_or_license_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_lowercase()) else {
+        return false;
+    };
+    let stem = name.split('.').next().unwrap_or(&name);
+    GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.contains(".generated.")
+        || LICENSE_NAMES
+            .iter()
+            .any(|license| stem == *license || stem.starts_with(&format!("{license}-")))
+}
+
+/// Whether a file should be left out of results as generated or license-only.
+/// Unreadable files are not considered generated.
+pub fn is_generated_file(path: &Path) -> bool {
+    if is_generated_or_license_name(path) {
+        return true;
+    }
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut buffer = Vec::new();
+    if file.take(READ_LIMIT).read_to_end(&mut buffer).is_err() {
+        return false;
+    }
+    let content = String::from_utf8_lossy(&buffer);
+    if has_generated_header(&content) {
+        return true;
+    }
+    // A file longer than the read limit has more than a license comment in it
+    (buffer.len() as u64) < READ_LIMIT && is_license_only(&content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_headers() {
+        assert!(has_generated_header(
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n"
+        ));
+        assert!(has_generated_header(
+            "# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.  DO NOT EDIT!\n"
+        ));
+        assert!(has_generated_header("/**\n * @generated\n */\nexport {}\n"));
+        assert!(!has_generated_header(
+            "fn id() -> u64 {\n    // ids come from the database\n    0\n}\n"
+        ));
+        // Markers in code rather than comments don't count
+        assert!(!has_generated_header("let banner = \"do not edit\";\n"));
+        // Markers far below the header don't count
+        let late = format!("{}// DO NOT EDIT\n", "fn a() {}\n".repeat(30));
+        assert!(!has_generated_header(&late));
+    }
+
+    #[test]
+    fn test_license_only() {
+        assert!(is_license_only(
+            "// Copyright 2024 Example Inc.\n//\n// Licensed under the Apache License, Version 2.0\n\n"
+        ));
+        assert!(!is_license_only(
+            "// Copyright 2024 Example Inc.\n\nfn main() {}\n"
+        ));
+        assert!(!is_license_only("// just a note\n"));
+        assert!(!is_license_only(""));
+    }
+
+    #[test]
+    fn test_generated_names() {
+        assert!(is_generated_or_license_name(Path::new("api/user.pb.go")));
+        assert!(is_generated_or_license_name(Path::new("proto/user_pb2.py")));
+        assert!(is_generated_or_license_name(Path::new(
+            "client.generated.ts"
+        )));
+        assert!(is_generated_or_license_name(Path::new("LICENSE")));
+        assert!(is_generated_or_license_name(Path::new("LICENSE-MIT")));
+        assert!(is_generated_or_license_name(Path::new("COPYING.txt")));
+        assert!(!is_generated_or_license_name(Path::new(
+            "src/license_check.rs"
+        )));
+        assert!(!is_generated_or_license_name(Path::new("src/main.go")));
+    }
+}
@@ -0,0 +1,110 @@
+//! Files changed relative to git, for `--changed` and `--since`.
+//!
+//! `--changed` keeps files with uncommitted changes: staged, unstaged and untracked
+//! (but not ignored). `--since <ref>` also keeps files changed by the commits since
+//! the branch left `<ref>`, i.e. since the merge base of `<ref>` and `HEAD`, so a CI
+//! job can search only what a pull request touches.
+
+use anyhow::{Context, Result};
+use probe_code::git::git;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Canonical paths of the files changed in the repository containing `root`: those
+/// with uncommitted changes, plus those changed since `since` when given
+pub fn changed_files(root: &Path, since: Option<&str>) -> Result<HashSet<PathBuf>> {
+    let dir = if root.is_file() {
+        root.parent().unwrap_or(Path::new("."))
+    } else {
+        root
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let top = git(dir, &["rev-parse", "--show-toplevel"])
+        .with_context(|| format!("{} is not in a git repository", root.display()))?;
+    let top = PathBuf::from(top.trim());
+
+    let base = match since {
+        Some(reference) => {
+            let base = git(dir, &["merge-base", reference, "HEAD"])
+                .with_context(|| format!("Can't compare with '{reference}'"))?;
+            base.trim().to_string()
+        }
+        None => "HEAD".to_string(),
+    };
+    // Paths are relative to the top of the repository
+    let diff = git(&top, &["diff", "--name-only", "--no-renames", &base])?;
+    let untracked = git(&top, &["ls-files", "--others", "--exclude-standard"])?;
+
+    Ok(diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        // Deleted files can't be searched
+        .filter_map(|file| top.join(file).canonicalize().ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=probe",
+                "-c",
+                "user.email=probe@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?}");
+    }
+
+    #[test]
+    fn test_changed_and_since() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        run(dir, &["init", "-q", "-b", "main"]);
+        fs::write(dir.join("old.rs"), "fn old() {}\n").unwrap();
+        fs::write(dir.join("edited.rs"), "fn edited() {}\n").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["commit", "-qm", "initial"]);
+        run(dir, &["checkout", "-qb", "feature"]);
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src/committed.rs"), "fn committed() {}\n").unwrap();
+        run(dir, &["add", "."]);
+        run(dir, &["commit", "-qm", "feature"]);
+        fs::write(dir.join("edited.rs"), "fn edited() { todo!() }\n").unwrap();
+        fs::write(dir.join("new.rs"), "fn new() {}\n").unwrap();
+
+        let names = |files: HashSet<PathBuf>| {
+            let mut names: Vec<String> = files
+                .iter()
+                .map(|file| file.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names(changed_files(dir, None).unwrap()),
+            vec!["edited.rs", "new.rs"]
+        );
+        assert_eq!(
+            names(changed_files(&dir.join("src"), Some("main")).unwrap()),
+            vec!["committed.rs", "edited.rs", "new.rs"]
+        );
+        assert!(changed_files(dir, Some("no-such-branch")).is_err());
+    }
+}
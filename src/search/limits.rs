@@ -16,6 +16,9 @@ pub fn apply_limits(
             total_results: results.len(),
             total_bytes: results.iter().map(|r| r.content.len()).sum(),
             total_tokens: results.iter().map(|r| count_tokens(&r.content)).sum(),
+            match_count: None,
+            total_ranked: None,
+            large_files_skipped: Vec::new(),
         };
     }
 
@@ -67,5 +70,8 @@ pub fn apply_limits(
         total_results: limited_results.len(),
         total_bytes: current_bytes,
         total_tokens: current_tokens,
+        match_count: None,
+        total_ranked: None,
+        large_files_skipped: Vec::new(),
     }
 }
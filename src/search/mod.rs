@@ -2,13 +2,27 @@ pub mod file_processing;
 pub mod query;
 mod result_ranking;
 // Replace the old search_execution with new modules
+pub mod archives;
+pub mod blame;
 pub mod block_merging;
+pub mod block_trimming;
 pub mod cache; // New module for caching search results
+pub mod codeowners;
+pub mod dedup;
 pub mod early_ranker; // New module for early BM25 ranking
-pub mod elastic_query;
+pub mod elastic_query; // New elastic search query parser
+pub mod file_chunking;
+pub mod file_index;
 pub mod file_list_cache; // New module for caching file lists
 pub mod filters; // New module for search filters (file:, ext:, type:, etc.)
+pub mod fuzzy;
+pub mod generated;
+pub mod git_changes;
+pub mod notebooks;
+pub mod project_config;
+pub mod result_diff;
 pub mod ripgrep_searcher;
+pub mod saved_queries;
 mod search_limiter;
 mod search_options;
 pub mod search_output;
@@ -16,11 +30,16 @@ pub mod search_runner;
 pub mod search_tokens;
 pub mod simd_pattern_matching;
 pub mod simd_tokenization; // SIMD-accelerated tokenization
+pub mod stemming;
+pub mod syntax_mask;
 pub mod term_exceptions; // New module for term exceptions
 pub mod timeout; // New module for timeout functionality
-pub mod tokenization; // New elastic search query parser
-                      // Temporarily commented out due to compilation issues
-                      // mod temp_frequency_search;
+pub mod tokenization;
+// Temporarily commented out due to compilation issues
+// mod temp_frequency_search;
+pub mod trigram_filter;
+pub mod trigram_vector;
+pub mod vector_store;
 
 #[cfg(test)]
 mod file_processing_tests;
@@ -30,5 +49,8 @@ mod test_token_limiter_failures;
 
 // Public exports
 pub use search_options::SearchOptions;
-pub use search_output::format_and_print_search_results;
-pub use search_runner::perform_probe;
+pub use search_output::{
+    format_and_print_search_results, print_match_count, print_result_diff, print_search_tips,
+    StreamingPrinter,
+};
+pub use search_runner::{perform_probe, perform_probe_streaming};
@@ -0,0 +1,207 @@
+//! Searching Jupyter notebooks by their code cells.
+//!
+//! A `.ipynb` file is JSON whose outputs hold base64 images and rendered tables, so
+//! searching it as text mostly matches noise. Instead each code cell found by the walk
+//! is written to a file of its own under the system temp dir, named after its number in
+//! the notebook and given the extension of the notebook's language, so cells are parsed
+//! and searched like any other source file. Results in a cell are reported as
+//! `notebook.ipynb:cell 4`, with line numbers counted from the start of the cell.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use probe_code::language::factory::language_extension;
+use probe_code::models::{LimitedSearchResults, SearchResult};
+
+/// Split notebooks of this process: (directory of the cells, notebook path as walked)
+static SPLIT: RwLock<Vec<(PathBuf, String)>> = RwLock::new(Vec::new());
+
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ipynb"))
+}
+
+/// Write the code cells of a notebook to their own files and return their paths
+pub fn notebook_cells(notebook: &Path) -> Result<Vec<PathBuf>> {
+    let metadata = fs::metadata(notebook)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos());
+    let canonical = fs::canonicalize(notebook)?;
+    let key = md5::compute(format!(
+        "{}:{}:{modified}",
+        canonical.display(),
+        metadata.len()
+    ));
+    let cache_dir = std::env::temp_dir().join("probe-notebooks");
+    let dir = cache_dir.join(format!("{key:x}"));
+    // The cell list is written last, so a notebook whose cells were only partly written
+    // is split again
+    let listing = cache_dir.join(format!("{key:x}.cells"));
+
+    let cells: Vec<PathBuf> = match fs::read_to_string(&listing) {
+        Ok(list) => list.lines().map(PathBuf::from).collect(),
+        Err(_) => {
+            let content = fs::read_to_string(notebook)?;
+            let cells = code_cells(&content)
+                .with_context(|| format!("Failed to read notebook {}", notebook.display()))?;
+            fs::create_dir_all(&dir)?;
+            let names: Vec<String> = cells
+                .iter()
+                .map(|(number, extension, source)| {
+                    let name = format!("cell-{number}.{extension}");
+                    fs::write(dir.join(&name), source).map(|_| name)
+                })
+                .collect::<std::io::Result<_>>()?;
+            fs::write(&listing, names.join("\n"))?;
+            names.into_iter().map(PathBuf::from).collect()
+        }
+    };
+
+    SPLIT
+        .write()
+        .unwrap()
+        .push((dir.clone(), notebook.to_string_lossy().to_string()));
+    Ok(cells.iter().map(|cell| dir.join(cell)).collect())
+}
+
+/// The non-empty code cells of a notebook: (1-based number among all its cells,
+/// extension of the notebook's language, source)
+pub fn code_cells(content: &str) -> Result<Vec<(usize, &'static str, String)>> {
+    let notebook: Value = serde_json::from_str(content)?;
+    let extension = notebook_extension(&notebook);
+    let cells = notebook["cells"]
+        .as_array()
+        .context("No cells; only nbformat 4 notebooks are supported")?;
+    Ok(cells
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell["cell_type"] == "code")
+        .map(|(index, cell)| (index + 1, extension, cell_source(&cell["source"])))
+        .filter(|(_, _, source)| !source.trim().is_empty())
+        .collect())
+}
+
+/// A cell's source is either one string or a list of lines that keep their newlines
+fn cell_source(source: &Value) -> String {
+    match source {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// The extension of the notebook's kernel language, Python when it doesn't say
+fn notebook_extension(notebook: &Value) -> &'static str {
+    let metadata = &notebook["metadata"];
+    metadata["language_info"]["name"]
+        .as_str()
+        .or_else(|| metadata["kernelspec"]["language"].as_str())
+        .and_then(language_extension)
+        .unwrap_or("py")
+}
+
+/// The `notebook.ipynb:cell N` location of a file split from a notebook
+pub fn notebook_location(file: &str) -> Option<String> {
+    let split = SPLIT.read().unwrap();
+    split.iter().find_map(|(dir, notebook)| {
+        let cell = Path::new(file).strip_prefix(dir).ok()?;
+        let number = cell.file_stem()?.to_str()?.strip_prefix("cell-")?;
+        Some(format!("{notebook}:cell {number}"))
+    })
+}
+
+/// A path as shown to users: the notebook location of a cell, or the path itself
+pub fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    notebook_location(&path).unwrap_or_else(|| path.to_string())
+}
+
+/// The file of a `notebook.ipynb:cell N` location
+pub fn cell_path(location: &str) -> Option<PathBuf> {
+    let (notebook_path, number) = location.rsplit_once(":cell ")?;
+    let split = SPLIT.read().unwrap();
+    let (dir, _) = split
+        .iter()
+        .find(|(_, notebook)| notebook == notebook_path)?;
+    fs::read_dir(dir).ok()?.find_map(|entry| {
+        let path = entry.ok()?.path();
+        (path.file_stem()?.to_str()? == format!("cell-{number}")).then_some(path)
+    })
+}
+
+/// Report results in notebook cells by their `notebook.ipynb:cell N` locations
+pub fn map_result_paths(limited: &mut LimitedSearchResults) {
+    for result in limited
+        .results
+        .iter_mut()
+        .chain(limited.skipped_files.iter_mut())
+    {
+        map_result_path(result);
+    }
+}
+
+/// Report a result in a notebook cell by its `notebook.ipynb:cell N` location
+pub fn map_result_path(result: &mut SearchResult) {
+    if SPLIT.read().unwrap().is_empty() {
+        return;
+    }
+    if let Some(path) = notebook_location(&result.file) {
+        result.file = path;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NOTEBOOK: &str = r##"{
+ "cells": [
+  {"cell_type": "markdown", "source": ["# Training\n"]},
+  {"cell_type": "code", "source": ["import torch\n", "model = torch.nn.Linear(4, 2)\n"],
+   "outputs": [{"data": {"image/png": "iVBORw0KGgoAAAANSUhEUg"}}]},
+  {"cell_type": "code", "source": ""},
+  {"cell_type": "code", "source": "def train(model):\n    pass\n"}
+ ],
+ "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+ "nbformat": 4
+}"##;
+
+    #[test]
+    fn test_code_cells_keep_their_numbers() {
+        let cells = code_cells(NOTEBOOK).unwrap();
+        assert_eq!(
+            cells,
+            vec![
+                (
+                    2,
+                    "py",
+                    "import torch\nmodel = torch.nn.Linear(4, 2)\n".to_string()
+                ),
+                (4, "py", "def train(model):\n    pass\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cell_locations() {
+        let temp = TempDir::new().unwrap();
+        let notebook = temp.path().join("train.ipynb");
+        fs::write(&notebook, NOTEBOOK).unwrap();
+
+        let cells = notebook_cells(&notebook).unwrap();
+        assert_eq!(cells.len(), 2);
+        let location = notebook_location(&cells[1].to_string_lossy()).unwrap();
+        assert_eq!(location, format!("{}:cell 4", notebook.display()));
+        assert_eq!(cell_path(&location), Some(cells[1].clone()));
+        assert!(fs::read_to_string(&cells[1])
+            .unwrap()
+            .starts_with("def train"));
+    }
+}
@@ -0,0 +1,431 @@
+//! Project defaults from `probe.toml` / `.probe/config.toml`.
+//!
+//! The nearest config file at or above the search root can set defaults for search
+//! options; flags given on the command line override them:
+//!
+//! ```toml
+//! reranker = "hybrid"
+//! format = "json"
+//! max_tokens = 8000
+//! ignore = ["vendor/*", "*.min.js"]
+//! allow_tests = true
+//!
+//! [languages]
+//! tpl = "html"
+//!
+//! [types]
+//! web = ["*.html", "*.css"]
+//! ```
+//!
+//! Settings are resolved in layers, each overriding the ones before it: the user's
+//! `~/.config/probe/config.toml`, the project's config file (or the file given with
+//! `--config`), `PROBE_<SETTING>` environment variables such as `PROBE_FORMAT=json`,
+//! and finally the command line. Ignore patterns, languages and types add up across
+//! layers instead. The `[queries]` section is read by the saved query module.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use probe_code::language::factory::language_extension;
+use probe_code::search::stemming::{config_file, project_config_path, StemmerKind};
+
+/// Top-level settings of the config file
+pub const CONFIG_KEYS: &[&str] = &[
+    "stemmer",
+    "reranker",
+    "format",
+    "max_tokens",
+    "ignore",
+    "allow_tests",
+];
+
+/// Sections of the config file
+pub const CONFIG_SECTIONS: &[&str] = &["queries", "languages", "types"];
+
+const RERANKERS: &[&str] = &[
+    "bm25",
+    "hybrid",
+    "hybrid2",
+    "hybrid-trigram",
+    "tfidf",
+    "ms-marco-tinybert",
+    "ms-marco-minilm-l6",
+    "ms-marco-minilm-l12",
+];
+
+const FORMATS: &[&str] = &[
+    "terminal",
+    "markdown",
+    "plain",
+    "json",
+    "xml",
+    "color",
+    "outline",
+    "outline-xml",
+];
+
+/// Search defaults set by a project's config file
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectConfig {
+    pub stemmer: Option<StemmerKind>,
+    pub reranker: Option<String>,
+    pub format: Option<String>,
+    pub max_tokens: Option<usize>,
+    /// Ignore patterns added to those given with `--ignore`
+    pub ignore: Vec<String>,
+    pub allow_tests: Option<bool>,
+    /// File extension -> extension of the language to parse it as
+    pub languages: HashMap<String, String>,
+    /// File type definitions for `--type`, as `name:glob,glob`
+    pub types: Vec<String>,
+}
+
+/// Read the nearest `probe.toml` or `.probe/config.toml` at or above `start`; `None`
+/// when there is none
+pub fn load_project_config(start: &Path) -> Result<Option<ProjectConfig>> {
+    let Some(path) = project_config_path(start) else {
+        return Ok(None);
+    };
+    read_config(&path).map(Some)
+}
+
+fn read_config(path: &Path) -> Result<ProjectConfig> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    parse_project_config(&content).map_err(|e| anyhow!("{}:{e}", path.display()))
+}
+
+/// The user's config file: `config.toml` in the `probe` directory of the platform's
+/// config dir (`$XDG_CONFIG_HOME` or `~/.config` on Linux)
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("probe").join("config.toml"))
+}
+
+/// A source of settings
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    User(PathBuf),
+    Project(PathBuf),
+    /// The file given with `--config`, read in place of the project's
+    ConfigFlag(PathBuf),
+    Env,
+}
+
+impl ConfigSource {
+    /// Where `key` of this source is set, as shown by `probe config show`
+    pub fn origin(&self, key: &str) -> String {
+        match self {
+            ConfigSource::User(path) => format!("user config {}", path.display()),
+            ConfigSource::Project(path) => format!("project config {}", path.display()),
+            ConfigSource::ConfigFlag(path) => format!("--config {}", path.display()),
+            ConfigSource::Env => env_var(key),
+        }
+    }
+}
+
+/// The settings of one source
+#[derive(Debug)]
+pub struct ConfigLayer {
+    pub source: ConfigSource,
+    pub config: ProjectConfig,
+}
+
+/// The layers that apply to a search at `start`, lowest precedence first: the user
+/// config, the project config (or `--config` file) and the environment
+pub fn config_layers(start: &Path) -> Result<Vec<ConfigLayer>> {
+    let mut layers = Vec::new();
+    if let Some(path) = user_config_path().filter(|path| path.is_file()) {
+        layers.push(ConfigLayer {
+            config: read_config(&path)?,
+            source: ConfigSource::User(path),
+        });
+    }
+    if let Some(path) = project_config_path(start) {
+        let source = if config_file().is_some() {
+            ConfigSource::ConfigFlag(path.clone())
+        } else {
+            ConfigSource::Project(path.clone())
+        };
+        layers.push(ConfigLayer {
+            config: read_config(&path)?,
+            source,
+        });
+    }
+    layers.push(ConfigLayer {
+        config: env_config(|var| std::env::var(var).ok())?,
+        source: ConfigSource::Env,
+    });
+    Ok(layers)
+}
+
+/// The environment variable of a top-level setting, e.g. `PROBE_MAX_TOKENS`
+pub fn env_var(key: &str) -> String {
+    format!("PROBE_{}", key.to_uppercase())
+}
+
+/// Settings from `PROBE_<SETTING>` variables; `PROBE_IGNORE` is a comma-separated list
+fn env_config(var: impl Fn(&str) -> Option<String>) -> Result<ProjectConfig> {
+    let mut config = ProjectConfig::default();
+    for key in CONFIG_KEYS {
+        let name = env_var(key);
+        let Some(value) = var(&name) else {
+            continue;
+        };
+        let value = if *key == "ignore" {
+            format!("[{value}]")
+        } else {
+            value
+        };
+        apply_setting(&mut config, key, &value).map_err(|e| anyhow!("{name}: {e}"))?;
+    }
+    Ok(config)
+}
+
+/// The settings of all layers combined, with where each one came from
+#[derive(Debug, Default)]
+pub struct ResolvedConfig {
+    pub config: ProjectConfig,
+    /// Setting or section -> the sources that set it, lowest precedence first; only
+    /// the last one counts for a top-level setting other than `ignore`
+    pub origins: BTreeMap<&'static str, Vec<String>>,
+}
+
+/// Combine layers, later ones overriding earlier ones
+pub fn resolve_config(layers: Vec<ConfigLayer>) -> ResolvedConfig {
+    let mut resolved = ResolvedConfig::default();
+    for ConfigLayer { source, config } in layers {
+        let mut set = |key: &'static str, is_set: bool, adds: bool| {
+            if is_set {
+                let origins = resolved.origins.entry(key).or_default();
+                if !adds {
+                    origins.clear();
+                }
+                origins.push(source.origin(key));
+            }
+        };
+        set("stemmer", config.stemmer.is_some(), false);
+        set("reranker", config.reranker.is_some(), false);
+        set("format", config.format.is_some(), false);
+        set("max_tokens", config.max_tokens.is_some(), false);
+        set("allow_tests", config.allow_tests.is_some(), false);
+        set("ignore", !config.ignore.is_empty(), true);
+        set("languages", !config.languages.is_empty(), true);
+        set("types", !config.types.is_empty(), true);
+
+        let merged = &mut resolved.config;
+        merged.stemmer = config.stemmer.or(merged.stemmer);
+        merged.reranker = config.reranker.or(merged.reranker.take());
+        merged.format = config.format.or(merged.format.take());
+        merged.max_tokens = config.max_tokens.or(merged.max_tokens);
+        merged.allow_tests = config.allow_tests.or(merged.allow_tests);
+        merged.ignore.extend(config.ignore);
+        merged.languages.extend(config.languages);
+        merged.types.extend(config.types);
+    }
+    resolved
+}
+
+/// Resolve the settings that apply to a search at `start`
+pub fn load_resolved_config(start: &Path) -> Result<ResolvedConfig> {
+    Ok(resolve_config(config_layers(start)?))
+}
+
+/// Parse the search defaults of a config file. Errors start with the line number.
+pub fn parse_project_config(content: &str) -> Result<ProjectConfig, String> {
+    let mut config = ProjectConfig::default();
+    let mut section: Option<&str> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("{}: expected `key = value`", index + 1));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let result = match section {
+            None => apply_setting(&mut config, key, value),
+            Some("languages") => language_extension(unquote(value))
+                .map(|extension| {
+                    let from = unquote(key).trim_start_matches('.').to_string();
+                    config.languages.insert(from, extension.to_string());
+                })
+                .ok_or_else(|| format!("unknown language '{}' for {key}", unquote(value))),
+            Some("types") => parse_list(key, value).map(|globs| {
+                config
+                    .types
+                    .push(format!("{}:{}", unquote(key), globs.join(",")));
+            }),
+            Some(_) => Ok(()),
+        };
+        result.map_err(|e| format!("{}: {e}", index + 1))?;
+    }
+    Ok(config)
+}
+
+/// Check the value of one top-level setting
+pub fn check_setting(key: &str, value: &str) -> Result<(), String> {
+    apply_setting(&mut ProjectConfig::default(), key, value)
+}
+
+fn apply_setting(config: &mut ProjectConfig, key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "stemmer" => config.stemmer = Some(unquote(value).parse()?),
+        "reranker" => config.reranker = Some(one_of(key, value, RERANKERS)?),
+        "format" => config.format = Some(one_of(key, value, FORMATS)?),
+        "max_tokens" => {
+            let tokens = value
+                .parse()
+                .map_err(|_| format!("max_tokens must be a number, not {value}"))?;
+            config.max_tokens = Some(tokens);
+        }
+        "ignore" => config.ignore = parse_list(key, value)?,
+        "allow_tests" => {
+            let allow = value
+                .parse()
+                .map_err(|_| format!("allow_tests must be true or false, not {value}"))?;
+            config.allow_tests = Some(allow);
+        }
+        // Unknown settings are reported by `probe doctor` rather than failing searches
+        _ => {}
+    }
+    Ok(())
+}
+
+fn parse_list(key: &str, value: &str) -> Result<Vec<String>, String> {
+    let list = value
+        .strip_prefix('[')
+        .and_then(|v| v.strip_suffix(']'))
+        .ok_or_else(|| format!("{key} must be a list of patterns, not {value}"))?;
+    Ok(list
+        .split(',')
+        .map(|pattern| unquote(pattern.trim()).to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect())
+}
+
+fn one_of(key: &str, value: &str, allowed: &[&str]) -> Result<String, String> {
+    let value = unquote(value);
+    if allowed.contains(&value) {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "unknown {key} '{value}', expected one of: {}",
+            allowed.join(", ")
+        ))
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_project_config() {
+        let config = parse_project_config(
+            r#"# Team defaults
+stemmer = "porter"
+reranker = "hybrid"
+format = "json"
+max_tokens = 8000
+ignore = ["vendor/*", "*.min.js"]
+allow_tests = true
+
+[queries]
+auth = "login OR oauth"
+
+[languages]
+tpl = "html"
+".tmpl" = "rust"
+
+[types]
+web = ["*.html", "*.css"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.stemmer, Some(StemmerKind::Porter));
+        assert_eq!(config.reranker.as_deref(), Some("hybrid"));
+        assert_eq!(config.format.as_deref(), Some("json"));
+        assert_eq!(config.max_tokens, Some(8000));
+        assert_eq!(config.ignore, vec!["vendor/*", "*.min.js"]);
+        assert_eq!(config.allow_tests, Some(true));
+        assert_eq!(config.languages["tpl"], "html");
+        assert_eq!(config.languages["tmpl"], "rs");
+        assert_eq!(config.types, vec!["web:*.html,*.css"]);
+    }
+
+    #[test]
+    fn test_invalid_values_report_their_line() {
+        let error = parse_project_config("format = \"json\"\nreranker = \"magic\"\n").unwrap_err();
+        assert!(error.starts_with("2: unknown reranker 'magic'"), "{error}");
+
+        let error = parse_project_config("[languages]\ntpl = \"klingon\"\n").unwrap_err();
+        assert!(error.starts_with("2: unknown language"), "{error}");
+
+        assert!(parse_project_config("max_tokens = lots\n").is_err());
+    }
+
+    #[test]
+    fn test_env_config() {
+        let env = HashMap::from([
+            ("PROBE_FORMAT", "json"),
+            ("PROBE_IGNORE", "vendor/*, *.min.js"),
+            ("PROBE_MAX_TOKENS", "500"),
+        ]);
+        let config = env_config(|var| env.get(var).map(|value| value.to_string())).unwrap();
+        assert_eq!(config.format.as_deref(), Some("json"));
+        assert_eq!(config.ignore, vec!["vendor/*", "*.min.js"]);
+        assert_eq!(config.max_tokens, Some(500));
+
+        let error = env_config(|var| (var == "PROBE_ALLOW_TESTS").then(|| "maybe".to_string()))
+            .unwrap_err();
+        assert!(
+            error.to_string().starts_with("PROBE_ALLOW_TESTS:"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_later_layers_override_earlier_ones() {
+        let user = PathBuf::from("/home/me/.config/probe/config.toml");
+        let project = PathBuf::from("/work/app/probe.toml");
+        let layer = |source, content| ConfigLayer {
+            source,
+            config: parse_project_config(content).unwrap(),
+        };
+        let resolved = resolve_config(vec![
+            layer(
+                ConfigSource::User(user.clone()),
+                "format = \"json\"\nmax_tokens = 1000\nignore = [\"*.log\"]\n",
+            ),
+            layer(
+                ConfigSource::Project(project.clone()),
+                "max_tokens = 8000\nignore = [\"vendor/*\"]\n",
+            ),
+            layer(ConfigSource::Env, "format = \"plain\"\n"),
+        ]);
+
+        assert_eq!(resolved.config.format.as_deref(), Some("plain"));
+        assert_eq!(resolved.config.max_tokens, Some(8000));
+        assert_eq!(resolved.config.ignore, vec!["*.log", "vendor/*"]);
+        assert_eq!(resolved.origins["format"], vec!["PROBE_FORMAT"]);
+        assert_eq!(
+            resolved.origins["max_tokens"],
+            vec![format!("project config {}", project.display())]
+        );
+        assert_eq!(resolved.origins["ignore"].len(), 2);
+        assert!(!resolved.origins.contains_key("reranker"));
+    }
+}
@@ -1,5 +1,6 @@
 use probe_code::search::elastic_query;
 use probe_code::search::tokenization;
+use tracing::debug;
 // No term_exceptions import needed
 use lru::LruCache;
 use std::collections::{HashMap, HashSet};
@@ -24,6 +25,89 @@ pub fn regex_escape(s: &str) -> String {
     result
 }
 
+/// Regex for an exact-mode term.
+///
+/// With `escape` off the term is used as-is, as a raw regular expression. Otherwise
+/// terms made only of identifier characters match as plain substrings, while terms
+/// containing punctuation (`HashMap::new`, `-> Result`, `Vec<T>`, `#[derive`) are
+/// matched delimiter-aware: punctuation is literal, whitespace around it is optional,
+/// whitespace between words matches any run of whitespace, and words at the edges of
+/// the term must start/end at identifier boundaries (`Map::new` does not match
+/// `HashMap::new`).
+pub fn exact_pattern(term: &str, escape: bool) -> String {
+    if !escape {
+        return term.to_string();
+    }
+
+    let is_word_char = |c: char| c == '_' || tokenization::is_token_char(c);
+    let term = term.trim();
+    if term.chars().all(is_word_char) {
+        return regex_escape(term);
+    }
+
+    // Split into identifier runs and single punctuation characters, noting whether
+    // whitespace preceded each piece; adjacent punctuation (`::`, `->`) stays adjacent
+    let mut pieces: Vec<(String, bool, bool)> = Vec::new(); // (text, is_word, space_before)
+    let mut space_before = false;
+    for c in term.chars() {
+        if c.is_whitespace() {
+            space_before = true;
+            continue;
+        }
+        let word = is_word_char(c);
+        match pieces.last_mut() {
+            Some((text, true, _)) if word && !space_before => text.push(c),
+            _ => pieces.push((c.to_string(), word, space_before)),
+        }
+        space_before = false;
+    }
+
+    let mut pattern = String::new();
+    for (i, (text, is_word, space_before)) in pieces.iter().enumerate() {
+        if i == 0 {
+            if *is_word {
+                pattern.push_str(r"\b");
+            }
+        } else if *is_word && pieces[i - 1].1 {
+            // Two words can only have been separated by whitespace
+            pattern.push_str(r"\s+");
+        } else if *is_word || pieces[i - 1].1 || *space_before {
+            pattern.push_str(r"\s*");
+        }
+        pattern.push_str(&regex_escape(text));
+    }
+    if pieces.last().is_some_and(|(_, is_word, _)| *is_word) {
+        pattern.push_str(r"\b");
+    }
+    pattern
+}
+
+/// Whether a term or pattern contains an uppercase letter, for `--smart-case`.
+/// Escape sequences (`\W`, `\S`, `\B`) are skipped so they don't count as uppercase.
+pub fn has_uppercase_literal(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Regex for an exact-mode term under the plan's case rules: with `case_sensitive`, or
+/// with `smart_case` for a term containing an uppercase letter, the term only matches
+/// with exactly that case
+fn exact_term_pattern(term: &str, plan: &QueryPlan) -> String {
+    let pattern = exact_pattern(term, plan.regex_escape);
+    if plan.case_sensitive || (plan.smart_case && has_uppercase_literal(term)) {
+        format!("(?-i:{pattern})")
+    } else {
+        pattern
+    }
+}
+
 // ----------------------------------------------------------------------------
 // NEW CODE: Full AST-based planning and pattern generation
 // ----------------------------------------------------------------------------
@@ -35,6 +119,18 @@ pub struct QueryPlan {
     pub term_indices: HashMap<String, usize>,
     pub excluded_terms: HashSet<String>,
     pub exact: bool,
+    /// In exact mode, escape regex metacharacters in the query (`--regex-escape on`).
+    /// When false the exact query is used as a raw regular expression.
+    pub regex_escape: bool,
+    /// In exact mode, match terms containing an uppercase letter case-sensitively
+    /// (`--smart-case`); all other matching is case-insensitive.
+    pub smart_case: bool,
+    /// Match every term only with the case it was written in (`--case-sensitive` or a
+    /// `case:sensitive` hint)
+    pub case_sensitive: bool,
+    /// With `case_sensitive`, the text each term index must appear as: the query words it
+    /// came from, as written (see `written_term_forms`)
+    pub written_terms: HashMap<usize, Vec<String>>,
     /// Optimization hint: true if this is a simple single-term query
     pub is_simple_query: bool,
     /// Optimization hint: set of required terms that must all be present
@@ -59,6 +155,9 @@ pub struct QueryPlan {
     pub special_case_indices: HashSet<usize>,
     /// Pre-computed: lowercase versions of special case terms for O(1) lookup
     pub special_case_terms_lower: HashMap<usize, String>,
+
+    /// Indices of terms that also match words one edit away (`--fuzzy` or a `~` suffix)
+    pub fuzzy_terms: HashSet<usize>,
 }
 
 impl std::fmt::Debug for QueryPlan {
@@ -68,6 +167,10 @@ impl std::fmt::Debug for QueryPlan {
             .field("term_indices", &self.term_indices)
             .field("excluded_terms", &self.excluded_terms)
             .field("exact", &self.exact)
+            .field("regex_escape", &self.regex_escape)
+            .field("smart_case", &self.smart_case)
+            .field("case_sensitive", &self.case_sensitive)
+            .field("written_terms", &self.written_terms)
             .field("is_simple_query", &self.is_simple_query)
             .field("required_terms", &self.required_terms)
             .field("has_required_anywhere", &self.has_required_anywhere)
@@ -75,6 +178,7 @@ impl std::fmt::Debug for QueryPlan {
             .field("has_only_excluded_terms", &self.has_only_excluded_terms)
             .field("is_universal_query", &self.is_universal_query)
             .field("special_case_indices", &self.special_case_indices)
+            .field("fuzzy_terms", &self.fuzzy_terms)
             .field("evaluation_cache", &"<LruCache>")
             .finish()
     }
@@ -92,18 +196,18 @@ fn format_duration(duration: std::time::Duration) -> String {
 /// Create a QueryPlan from a raw query string. This fully parses the query into an AST,
 /// then extracts all terms (including excluded), and prepares a term-index map.
 pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_query::ParseError> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting query plan creation for query: '{query}'");
+        debug!("Starting query plan creation for query: '{query}'");
     }
 
     // Use the regular AST parsing
     let parsing_start = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting AST parsing for query: '{query}', exact={exact}");
+        debug!("Starting AST parsing for query: '{query}', exact={exact}");
     }
 
     // Parse the query into an AST with processed terms
@@ -118,18 +222,18 @@ pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_
     let parsing_duration = parsing_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: AST parsing completed in {}",
+        debug!(
+            "AST parsing completed in {}",
             format_duration(parsing_duration)
         );
-        println!("DEBUG: Parsed AST: {ast}");
+        debug!("Parsed AST: {ast}");
     }
 
     // We'll walk the AST to build a set of all terms. We track excluded as well for reference.
     let term_collection_start = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting term collection from AST");
+        debug!("Starting term collection from AST");
     }
 
     let mut all_terms = Vec::new();
@@ -143,12 +247,12 @@ pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_
     let term_collection_duration = term_collection_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Term collection completed in {}",
+        debug!(
+            "Term collection completed in {}",
             format_duration(term_collection_duration)
         );
-        println!("DEBUG: Collected {} unique terms", all_terms.len());
-        println!("DEBUG: Collected {} excluded terms", excluded_terms.len());
+        debug!("Collected {} unique terms", all_terms.len());
+        debug!("Collected {} excluded terms", excluded_terms.len());
     }
 
     // Build term index map
@@ -162,16 +266,16 @@ pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_
     let index_building_duration = index_building_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Term index building completed in {}",
+        debug!(
+            "Term index building completed in {}",
             format_duration(index_building_duration)
         );
     }
 
     let total_duration = start_time.elapsed();
     if debug_mode {
-        println!(
-            "DEBUG: Query plan creation completed in {}",
+        debug!(
+            "Query plan creation completed in {}",
             format_duration(total_duration)
         );
     }
@@ -214,6 +318,10 @@ pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_
         term_indices,
         excluded_terms,
         exact,
+        regex_escape: true,
+        smart_case: false,
+        case_sensitive: false,
+        written_terms: HashMap::new(),
         is_simple_query,
         required_terms,
         has_required_anywhere,
@@ -223,6 +331,7 @@ pub fn create_query_plan(query: &str, exact: bool) -> Result<QueryPlan, elastic_
         is_universal_query: false,
         special_case_indices,
         special_case_terms_lower,
+        fuzzy_terms: HashSet::new(),
     })
 }
 
@@ -241,7 +350,7 @@ fn collect_required_terms(expr: &elastic_query::Expr, required_terms: &mut HashS
                 }
             }
         }
-        elastic_query::Expr::And(left, right) => {
+        elastic_query::Expr::And(left, right) | elastic_query::Expr::Near(left, right, _) => {
             collect_required_terms(left, required_terms);
             collect_required_terms(right, required_terms);
         }
@@ -252,6 +361,77 @@ fn collect_required_terms(expr: &elastic_query::Expr, required_terms: &mut HashS
     }
 }
 
+/// Map the lowercased form of every keyword in the AST to the keyword as written
+fn collect_original_keywords(expr: &elastic_query::Expr, originals: &mut HashMap<String, String>) {
+    match expr {
+        elastic_query::Expr::Term {
+            keywords,
+            lowercase_keywords,
+            ..
+        } => {
+            for (keyword, lower) in keywords.iter().zip(lowercase_keywords) {
+                originals.insert(lower.clone(), keyword.clone());
+            }
+        }
+        elastic_query::Expr::And(left, right)
+        | elastic_query::Expr::Or(left, right)
+        | elastic_query::Expr::Near(left, right, _) => {
+            collect_original_keywords(left, originals);
+            collect_original_keywords(right, originals);
+        }
+    }
+}
+
+/// The text each term of the plan must appear as for case-sensitive matching. Quoted
+/// and excluded terms keep their case in the AST; other terms are lowercased and split
+/// by tokenization, so they map back to the words of `query` they were tokenized from.
+/// A camelCase word like `ParserConfig` is then required as a whole for both its terms.
+pub fn written_term_forms(query: &str, plan: &QueryPlan) -> HashMap<usize, Vec<String>> {
+    fn collect_literal_terms(
+        expr: &elastic_query::Expr,
+        plan: &QueryPlan,
+        forms: &mut HashMap<usize, Vec<String>>,
+    ) {
+        match expr {
+            elastic_query::Expr::Term {
+                keywords,
+                exact,
+                excluded,
+                ..
+            } => {
+                if *exact || *excluded {
+                    for keyword in keywords {
+                        if let Some(&idx) = plan.term_indices.get(&keyword.to_lowercase()) {
+                            forms.entry(idx).or_default().push(keyword.clone());
+                        }
+                    }
+                }
+            }
+            elastic_query::Expr::And(left, right)
+            | elastic_query::Expr::Or(left, right)
+            | elastic_query::Expr::Near(left, right, _) => {
+                collect_literal_terms(left, plan, forms);
+                collect_literal_terms(right, plan, forms);
+            }
+        }
+    }
+
+    let mut forms = HashMap::new();
+    collect_literal_terms(&plan.ast, plan, &mut forms);
+    for word in elastic_query::written_words(query) {
+        let word = word.trim_end_matches('~');
+        for token in tokenization::tokenize(word) {
+            if let Some(&idx) = plan.term_indices.get(&token.to_lowercase()) {
+                let word_forms: &mut Vec<String> = forms.entry(idx).or_default();
+                if !word_forms.iter().any(|form| form == word) {
+                    word_forms.push(word.to_string());
+                }
+            }
+        }
+    }
+    forms
+}
+
 /// Recursively update the AST to mark all terms as exact
 fn update_ast_exact(expr: &mut elastic_query::Expr) {
     match expr {
@@ -259,11 +439,9 @@ fn update_ast_exact(expr: &mut elastic_query::Expr) {
             // Set exact to true for all terms
             *exact = true;
         }
-        elastic_query::Expr::And(left, right) => {
-            update_ast_exact(left);
-            update_ast_exact(right);
-        }
-        elastic_query::Expr::Or(left, right) => {
+        elastic_query::Expr::And(left, right)
+        | elastic_query::Expr::Or(left, right)
+        | elastic_query::Expr::Near(left, right, _) => {
             update_ast_exact(left);
             update_ast_exact(right);
         }
@@ -276,6 +454,9 @@ fn is_exact_search(expr: &elastic_query::Expr) -> bool {
         elastic_query::Expr::Term { exact, .. } => *exact,
         elastic_query::Expr::And(left, right) => is_exact_search(left) && is_exact_search(right),
         elastic_query::Expr::Or(left, right) => is_exact_search(left) && is_exact_search(right),
+        elastic_query::Expr::Near(left, right, _) => {
+            is_exact_search(left) && is_exact_search(right)
+        }
     }
 }
 
@@ -286,10 +467,10 @@ fn collect_all_terms(
     all_terms: &mut Vec<String>,
     excluded: &mut HashSet<String>,
 ) {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!("DEBUG: Collecting terms from expression: {expr:?}");
+        debug!("Collecting terms from expression: {expr:?}");
     }
 
     match expr {
@@ -303,15 +484,13 @@ fn collect_all_terms(
             all_terms.extend(lowercase_keywords.iter().cloned());
 
             if debug_mode {
-                println!(
-                    "DEBUG: Collected keywords '{lowercase_keywords:?}', excluded={is_excluded}"
-                );
+                debug!("Collected keywords '{lowercase_keywords:?}', excluded={is_excluded}");
             }
 
             if *is_excluded {
                 for keyword in lowercase_keywords {
                     if debug_mode {
-                        println!("DEBUG: Adding '{keyword}' to excluded terms set");
+                        debug!("Adding '{keyword}' to excluded terms set");
                     }
 
                     // Use pre-computed lowercase keywords
@@ -319,9 +498,9 @@ fn collect_all_terms(
                 }
             }
         }
-        elastic_query::Expr::And(left, right) => {
+        elastic_query::Expr::And(left, right) | elastic_query::Expr::Near(left, right, _) => {
             if debug_mode {
-                println!("DEBUG: Processing AND expression for term collection");
+                debug!("Processing AND expression for term collection");
             }
 
             // Check if the right side is an excluded term
@@ -333,7 +512,7 @@ fn collect_all_terms(
             {
                 for keyword in lowercase_keywords {
                     if debug_mode {
-                        println!("DEBUG: Adding excluded term '{keyword}' from AND expression");
+                        debug!("Adding excluded term '{keyword}' from AND expression");
                     }
                     excluded.insert(keyword.clone());
                 }
@@ -344,7 +523,7 @@ fn collect_all_terms(
         }
         elastic_query::Expr::Or(left, right) => {
             if debug_mode {
-                println!("DEBUG: Processing OR expression for term collection");
+                debug!("Processing OR expression for term collection");
             }
             collect_all_terms(left, all_terms, excluded);
             collect_all_terms(right, all_terms, excluded);
@@ -352,8 +531,8 @@ fn collect_all_terms(
     }
 
     if debug_mode {
-        println!("DEBUG: Current all_terms: {all_terms:?}");
-        println!("DEBUG: Current excluded terms: {excluded:?}");
+        debug!("Current all_terms: {all_terms:?}");
+        debug!("Current excluded terms: {excluded:?}");
     }
 }
 
@@ -361,19 +540,24 @@ fn collect_all_terms(
 /// This creates a single pattern that matches any of the terms using case-insensitive matching
 /// without word boundaries for more flexible matching
 pub fn build_combined_pattern(terms: &[String]) -> String {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    build_combined_pattern_with(terms, regex_escape)
+}
+
+/// Build a combined regex pattern, turning each term into a regex with `to_pattern`
+fn build_combined_pattern_with(terms: &[String], to_pattern: impl Fn(&str) -> String) -> String {
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Building combined pattern for {} terms", terms.len());
+        debug!("Building combined pattern for {} terms", terms.len());
     }
 
     // Limit the number of terms to prevent regex size explosion
     const MAX_TERMS_IN_PATTERN: usize = 1000;
     let limited_terms = if terms.len() > MAX_TERMS_IN_PATTERN {
         if debug_mode {
-            println!(
-                "DEBUG: Limiting pattern to first {} terms (was {})",
+            debug!(
+                "Limiting pattern to first {} terms (was {})",
                 MAX_TERMS_IN_PATTERN,
                 terms.len()
             );
@@ -386,7 +570,7 @@ pub fn build_combined_pattern(terms: &[String]) -> String {
     // Escape special characters in each term
     let escaped_terms = limited_terms
         .iter()
-        .map(|t| regex_escape(t))
+        .map(|t| to_pattern(t))
         .collect::<Vec<_>>();
 
     // Join terms with | operator and add case-insensitive flag without word boundaries
@@ -394,8 +578,8 @@ pub fn build_combined_pattern(terms: &[String]) -> String {
 
     if debug_mode {
         let duration = start_time.elapsed();
-        println!(
-            "DEBUG: Combined pattern built in {} with {} terms: {}",
+        debug!(
+            "Combined pattern built in {} with {} terms: {}",
             format_duration(duration),
             limited_terms.len(),
             if pattern.len() > 200 {
@@ -413,22 +597,22 @@ pub fn build_combined_pattern(terms: &[String]) -> String {
 /// This creates a single combined pattern for all terms, regardless of whether they're
 /// required, optional, or negative.
 pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usize>)> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting structured pattern creation");
-        println!("DEBUG: Using combined pattern mode");
+        debug!("Starting structured pattern creation");
+        debug!("Using combined pattern mode");
     }
 
     let mut results = Vec::new();
     const MAX_PATTERNS: usize = 5000; // Limit total patterns to prevent regex size explosion
 
     if debug_mode {
-        println!("DEBUG: Creating structured patterns with AST awareness");
-        println!("DEBUG: AST: {ast:?}", ast = plan.ast);
-        println!(
-            "DEBUG: Excluded terms: {excluded_terms:?}",
+        debug!("Creating structured patterns with AST awareness");
+        debug!("AST: {ast:?}", ast = plan.ast);
+        debug!(
+            "Excluded terms: {excluded_terms:?}",
             excluded_terms = plan.excluded_terms
         );
     }
@@ -438,7 +622,16 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let terms: Vec<String> = plan.term_indices.keys().cloned().collect();
 
     if !terms.is_empty() {
-        let combined_pattern = build_combined_pattern(&terms);
+        let combined_pattern = if plan.exact {
+            // Term keys are lowercased; raw regexes need their original case (`\W` vs `\w`)
+            let mut originals = HashMap::new();
+            collect_original_keywords(&plan.ast, &mut originals);
+            build_combined_pattern_with(&terms, |t| {
+                exact_term_pattern(originals.get(t).map_or(t, String::as_str), plan)
+            })
+        } else {
+            build_combined_pattern(&terms)
+        };
 
         // Create a HashSet with indices of non-excluded terms
         let all_indices: HashSet<usize> = terms
@@ -447,8 +640,8 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
             .collect();
 
         if debug_mode {
-            println!("DEBUG: Created combined pattern for all terms: '{combined_pattern}'");
-            println!("DEBUG: Combined pattern includes indices: {all_indices:?}");
+            debug!("Created combined pattern for all terms: '{combined_pattern}'");
+            debug!("Combined pattern includes indices: {all_indices:?}");
         }
 
         results.push((combined_pattern, all_indices));
@@ -474,8 +667,8 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
                 // FIXED: Don't skip pattern generation for excluded terms
                 // Excluded terms need to be found during search so they can be properly excluded during evaluation
                 if debug_mode && *excluded {
-                    println!(
-                        "DEBUG: Generating patterns for excluded term (will be filtered during evaluation): '{keywords:?}'"
+                    debug!(
+                        "Generating patterns for excluded term (will be filtered during evaluation): '{keywords:?}'"
                     );
                 }
 
@@ -483,37 +676,52 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
                 for keyword in keywords {
                     // Note: We still generate patterns for excluded terms so they can be found and then filtered out
                     if debug_mode && plan.excluded_terms.contains(keyword) {
-                        println!(
-                            "DEBUG: Generating pattern for globally excluded keyword (will be filtered during evaluation): '{keyword}'"
+                        debug!(
+                            "Generating pattern for globally excluded keyword (will be filtered during evaluation): '{keyword}'"
                         );
                     }
                     // The original check `if *excluded` (line 352) already handles terms explicitly marked with `-`
                     // No need for an additional check here for `*excluded` as the outer check handles it.
 
                     // Find the keyword's index in term_indices
-                    if let Some(&idx) = plan.term_indices.get(keyword) {
-                        let base_pattern = regex_escape(keyword);
-
+                    let idx = plan.term_indices.get(keyword).or_else(|| {
+                        if *exact {
+                            plan.term_indices.get(&keyword.to_lowercase())
+                        } else {
+                            None
+                        }
+                    });
+                    if let Some(&idx) = idx {
                         // For exact terms, use stricter matching
                         let pattern = if *exact {
-                            base_pattern.to_string()
+                            exact_term_pattern(keyword, plan)
                         } else {
-                            format!("({base_pattern})")
+                            format!("({})", regex_escape(keyword))
                         };
 
                         if debug_mode {
-                            println!("DEBUG: Created pattern for keyword '{keyword}': '{pattern}'");
+                            debug!("Created pattern for keyword '{keyword}': '{pattern}'");
                         }
 
                         results.push((pattern, HashSet::from([idx])));
 
+                        if plan.fuzzy_terms.contains(&idx) && !*exact {
+                            let pattern = crate::search::fuzzy::fuzzy_pattern(keyword);
+                            if debug_mode {
+                                debug!(
+                                    "Created fuzzy pattern for keyword '{keyword}': '{pattern}'"
+                                );
+                            }
+                            results.push((pattern, HashSet::from([idx])));
+                        }
+
                         // Only tokenize if not exact
                         if !*exact {
                             // Generate patterns for each token of the term to match AST tokenization
                             let tokens = crate::search::tokenization::tokenize_and_stem(keyword);
 
                             if debug_mode && tokens.len() > 1 {
-                                println!("DEBUG: Term '{keyword}' tokenized into: {tokens:?}");
+                                debug!("Term '{keyword}' tokenized into: {tokens:?}");
                             }
 
                             // Generate a pattern for each token with the same term index
@@ -522,30 +730,30 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
                                 let pattern = format!("({token_pattern})");
 
                                 if debug_mode {
-                                    println!(
-                                            "DEBUG: Created pattern for token '{token}' from term '{keyword}': '{pattern}'"
+                                    debug!(
+                                            "Created pattern for token '{token}' from term '{keyword}': '{pattern}'"
                                         );
                                 }
 
                                 results.push((pattern, HashSet::from([idx])));
                             }
                         } else if debug_mode {
-                            println!("DEBUG: Skipping tokenization for exact term '{keyword}'");
+                            debug!("Skipping tokenization for exact term '{keyword}'");
                         }
                     }
                 }
             }
-            elastic_query::Expr::And(left, right) => {
+            elastic_query::Expr::And(left, right) | elastic_query::Expr::Near(left, right, _) => {
                 // For AND, collect patterns from both sides independently
                 if debug_mode {
-                    println!("DEBUG: Processing AND expression");
+                    debug!("Processing AND expression");
                 }
                 collect_patterns(left, plan, results, debug_mode);
                 collect_patterns(right, plan, results, debug_mode);
             }
             elastic_query::Expr::Or(left, right) => {
                 if debug_mode {
-                    println!("DEBUG: Processing OR expression");
+                    debug!("Processing OR expression");
                 }
 
                 // For OR, just collect patterns from both sides independently
@@ -560,7 +768,7 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     // Always call the recursive pattern collection logic
     // Removed unused variable 'standard_start'
     if debug_mode {
-        println!("DEBUG: Using recursive pattern generation via collect_patterns");
+        debug!("Using recursive pattern generation via collect_patterns");
     }
     collect_patterns(&plan.ast, plan, &mut results, debug_mode);
 
@@ -568,7 +776,7 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let compound_start = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting compound word pattern generation");
+        debug!("Starting compound word pattern generation");
     }
 
     let mut compound_patterns = Vec::new();
@@ -578,7 +786,7 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
         // Check if the original keyword itself is excluded before processing for compound parts
         if plan.excluded_terms.contains(keyword) {
             if debug_mode {
-                println!("DEBUG: Skipping compound processing for excluded keyword: '{keyword}'");
+                debug!("Skipping compound processing for excluded keyword: '{keyword}'");
             }
             continue; // Skip this keyword entirely
         }
@@ -598,7 +806,7 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
 
             if compound_parts.len() > 1 {
                 if debug_mode {
-                    println!("DEBUG: Processing compound word: '{keyword}'");
+                    debug!("Processing compound word: '{keyword}'");
                 }
 
                 for part in compound_parts {
@@ -608,24 +816,20 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
                         let pattern = format!("({part_pattern})");
 
                         if debug_mode {
-                            println!(
-                                "DEBUG: Adding compound part pattern: '{pattern}' from '{part}'"
-                            );
+                            debug!("Adding compound part pattern: '{pattern}' from '{part}'");
                         }
                         compound_patterns.push((pattern, HashSet::from([idx])));
                     } else if debug_mode && plan.excluded_terms.contains(&part) {
-                        println!(
-                            "DEBUG: Skipping excluded compound part: '{part}' from keyword '{keyword}'"
+                        debug!(
+                            "Skipping excluded compound part: '{part}' from keyword '{keyword}'"
                         );
                     } else if debug_mode {
-                        println!(
-                            "DEBUG: Skipping short compound part: '{part}' from keyword '{keyword}'"
-                        );
+                        debug!("Skipping short compound part: '{part}' from keyword '{keyword}'");
                     }
                 }
             }
         } else if debug_mode && is_exact_search(&plan.ast) {
-            println!("DEBUG: Skipping compound word processing for exact search term: '{keyword}'");
+            debug!("Skipping compound word processing for exact search term: '{keyword}'");
         }
     }
 
@@ -638,8 +842,8 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let compound_duration = compound_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Compound word pattern generation completed in {} - Generated {} patterns",
+        debug!(
+            "Compound word pattern generation completed in {} - Generated {} patterns",
             format_duration(compound_duration),
             compound_patterns_len
         );
@@ -652,7 +856,7 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let dedup_start = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Starting pattern deduplication");
+        debug!("Starting pattern deduplication");
     }
 
     // First, deduplicate by exact pattern match using BTreeMap for deterministic iteration
@@ -706,13 +910,13 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let dedup_duration = dedup_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Pattern deduplication completed in {} - Final pattern count: {}",
+        debug!(
+            "Pattern deduplication completed in {} - Final pattern count: {}",
             format_duration(dedup_duration),
             deduplicated_results.len()
         );
         for (pattern, indices) in &deduplicated_results {
-            println!("DEBUG: Pattern: '{pattern}', Indices: {indices:?}");
+            debug!("Pattern: '{pattern}', Indices: {indices:?}");
         }
     }
 
@@ -731,8 +935,8 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     // Apply pattern limit to prevent regex size explosion
     let limited_results = if deduplicated_results.len() > MAX_PATTERNS {
         if debug_mode {
-            println!(
-                "DEBUG: Limiting patterns to {} (was {})",
+            debug!(
+                "Limiting patterns to {} (was {})",
                 MAX_PATTERNS,
                 deduplicated_results.len()
             );
@@ -748,8 +952,8 @@ pub fn create_structured_patterns(plan: &QueryPlan) -> Vec<(String, HashSet<usiz
     let total_duration = start_time.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Total structured pattern creation completed in {} with {} patterns",
+        debug!(
+            "Total structured pattern creation completed in {} with {} patterns",
             format_duration(total_duration),
             limited_results.len()
         );
@@ -763,11 +967,11 @@ pub fn create_query_plan_from_ast(
     ast: elastic_query::Expr,
     exact: bool,
 ) -> Result<QueryPlan, elastic_query::ParseError> {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let start_time = Instant::now();
 
     if debug_mode {
-        println!("DEBUG: Creating query plan from existing AST");
+        debug!("Creating query plan from existing AST");
     }
 
     // Update AST for exact search if needed
@@ -826,8 +1030,8 @@ pub fn create_query_plan_from_ast(
 
     let total_duration = start_time.elapsed();
     if debug_mode {
-        println!(
-            "DEBUG: Query plan from AST completed in {}",
+        debug!(
+            "Query plan from AST completed in {}",
             format_duration(total_duration)
         );
     }
@@ -837,6 +1041,10 @@ pub fn create_query_plan_from_ast(
         term_indices,
         excluded_terms,
         exact,
+        regex_escape: true,
+        smart_case: false,
+        case_sensitive: false,
+        written_terms: HashMap::new(),
         is_simple_query,
         required_terms,
         has_required_anywhere,
@@ -846,6 +1054,7 @@ pub fn create_query_plan_from_ast(
         is_universal_query: false,
         special_case_indices,
         special_case_terms_lower,
+        fuzzy_terms: HashSet::new(),
     })
 }
 
@@ -871,6 +1080,10 @@ pub fn create_universal_query_plan() -> QueryPlan {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
+        smart_case: false,
+        case_sensitive: false,
+        written_terms: HashMap::new(),
         is_simple_query: true,
         required_terms: HashSet::new(),
         has_required_anywhere: false,
@@ -880,5 +1093,6 @@ pub fn create_universal_query_plan() -> QueryPlan {
         is_universal_query: true, // This is a universal query that should match all content
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        fuzzy_terms: HashSet::new(),
     }
 }
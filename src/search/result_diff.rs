@@ -0,0 +1,215 @@
+//! Comparison of search results with a saved result set, for `--compare-to`.
+//!
+//! The saved set is the output of an earlier `probe search --format json`. Blocks are
+//! matched by their code, ignoring indentation: a block found in both sets at the same
+//! file and lines is unchanged, and one found at another place has moved. Blocks only
+//! in the current results are new and blocks only in the saved ones removed, so a block
+//! whose code was edited shows up as both removed and new.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+use probe_code::models::SearchResult;
+
+/// A block of a result set, identified by where it is
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DiffBlock {
+    pub file: String,
+    pub lines: (usize, usize),
+    pub node_type: String,
+    #[serde(default)]
+    pub code: String,
+}
+
+/// A block whose code is found in both result sets, but at different places
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedBlock {
+    pub from: DiffBlock,
+    pub to: DiffBlock,
+}
+
+/// How the current results differ from a saved result set
+#[derive(Debug, Default)]
+pub struct ResultDiff {
+    pub new: Vec<DiffBlock>,
+    pub removed: Vec<DiffBlock>,
+    pub moved: Vec<MovedBlock>,
+    pub unchanged: usize,
+}
+
+/// Read the results of a `--format json` search saved at `path`
+pub fn load_saved_results(path: &Path) -> Result<Vec<DiffBlock>> {
+    #[derive(Deserialize)]
+    struct SavedResults {
+        results: Vec<DiffBlock>,
+    }
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read saved results from {}", path.display()))?;
+    // Saved output may start with status lines printed before the JSON document
+    let json = content.find('{').map_or("", |start| &content[start..]);
+    let saved: SavedResults = serde_json::from_str(json).with_context(|| {
+        format!(
+            "{} is not the output of `probe search --format json`",
+            path.display()
+        )
+    })?;
+    Ok(saved.results)
+}
+
+/// Compare `current` results with the `saved` ones
+pub fn diff_results(saved: &[DiffBlock], current: &[SearchResult]) -> ResultDiff {
+    let current: Vec<DiffBlock> = current
+        .iter()
+        .filter(|result| !result.file.is_empty())
+        .map(|result| DiffBlock {
+            file: result.file.clone(),
+            lines: result.lines,
+            node_type: result.node_type.clone(),
+            code: result.code.to_string(),
+        })
+        .collect();
+
+    let mut saved_left: Vec<Option<&DiffBlock>> = saved.iter().map(Some).collect();
+    let mut current_left: Vec<Option<&DiffBlock>> = current.iter().map(Some).collect();
+    let mut diff = ResultDiff::default();
+
+    // Blocks still at the same place
+    for slot in current_left.iter_mut() {
+        let block = slot.unwrap();
+        let found = take_match(&mut saved_left, |old| {
+            same_file(&old.file, &block.file) && old.lines == block.lines && same_code(old, block)
+        });
+        if found.is_some() {
+            diff.unchanged += 1;
+            *slot = None;
+        }
+    }
+
+    // Blocks elsewhere, preferring a copy in the same file
+    for slot in current_left.iter_mut() {
+        let Some(block) = *slot else { continue };
+        let found = take_match(&mut saved_left, |old| {
+            same_file(&old.file, &block.file) && same_code(old, block)
+        })
+        .or_else(|| take_match(&mut saved_left, |old| same_code(old, block)));
+        if let Some(old) = found {
+            diff.moved.push(MovedBlock {
+                from: old.clone(),
+                to: block.clone(),
+            });
+            *slot = None;
+        }
+    }
+
+    diff.new = current_left.into_iter().flatten().cloned().collect();
+    diff.removed = saved_left.into_iter().flatten().cloned().collect();
+    diff
+}
+
+/// Remove and return the first remaining block matching `predicate`
+fn take_match<'a>(
+    blocks: &mut [Option<&'a DiffBlock>],
+    predicate: impl Fn(&DiffBlock) -> bool,
+) -> Option<&'a DiffBlock> {
+    blocks
+        .iter_mut()
+        .find(|slot| slot.is_some_and(&predicate))
+        .and_then(Option::take)
+}
+
+fn same_file(a: &str, b: &str) -> bool {
+    a.trim_start_matches("./") == b.trim_start_matches("./")
+}
+
+fn same_code(a: &DiffBlock, b: &DiffBlock) -> bool {
+    a.code
+        .lines()
+        .map(str::trim)
+        .eq(b.code.lines().map(str::trim))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(file: &str, lines: (usize, usize), code: &str) -> DiffBlock {
+        DiffBlock {
+            file: file.to_string(),
+            lines,
+            node_type: "function_item".to_string(),
+            code: code.to_string(),
+        }
+    }
+
+    fn result(block: &DiffBlock) -> SearchResult {
+        SearchResult {
+            file: block.file.clone(),
+            lines: block.lines,
+            node_type: block.node_type.clone(),
+            code: block.code.clone().into(),
+            symbol_signature: None,
+            matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
+            rank: None,
+            score: None,
+            tfidf_score: None,
+            bm25_score: None,
+            tfidf_rank: None,
+            bm25_rank: None,
+            new_score: None,
+            hybrid2_rank: None,
+            combined_score_rank: None,
+            file_unique_terms: None,
+            file_total_matches: None,
+            file_match_rank: None,
+            block_unique_terms: None,
+            block_total_matches: None,
+            parent_file_id: None,
+            block_id: None,
+            matched_keywords: None,
+            matched_lines: None,
+            tokenized_content: None,
+            parent_context: None,
+        }
+    }
+
+    #[test]
+    fn test_new_removed_moved_and_unchanged() {
+        let saved = vec![
+            block("./src/a.rs", (1, 3), "fn a() {}"),
+            block("src/b.rs", (10, 12), "fn b() {}"),
+            block("src/c.rs", (5, 6), "fn c() {}"),
+        ];
+        let current = [
+            block("src/a.rs", (1, 3), "fn a() {}"),
+            block("src/b.rs", (20, 22), "    fn b() {}"),
+            block("src/d.rs", (1, 2), "fn d() {}"),
+        ];
+        let current: Vec<SearchResult> = current.iter().map(result).collect();
+
+        let diff = diff_results(&saved, &current);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(diff.moved[0].from.lines, (10, 12));
+        assert_eq!(diff.moved[0].to.lines, (20, 22));
+        assert_eq!(diff.new.len(), 1);
+        assert_eq!(diff.new[0].file, "src/d.rs");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].file, "src/c.rs");
+    }
+
+    #[test]
+    fn test_edited_block_is_removed_and_new() {
+        let saved = vec![block("src/a.rs", (1, 3), "fn a() { old(); }")];
+        let current = vec![result(&block("src/a.rs", (1, 3), "fn a() { new(); }"))];
+
+        let diff = diff_results(&saved, &current);
+        assert_eq!((diff.new.len(), diff.removed.len()), (1, 1));
+        assert!(diff.moved.is_empty());
+    }
+}
@@ -2,7 +2,12 @@
 use probe_code::bert_reranker;
 use probe_code::models::SearchResult;
 use probe_code::ranking;
+use probe_code::warnings::{warn, WarningKind};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
+use tracing::debug;
 
 /// Calculate coverage boost based on unique terms matched in the block
 fn calculate_coverage_boost(block_unique_terms: Option<usize>, query_term_count: usize) -> f64 {
@@ -124,15 +129,12 @@ pub fn rank_search_results(
     let start_time = Instant::now();
 
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if debug_mode {
-        println!(
-            "DEBUG: Starting result ranking with {} results",
-            results.len()
-        );
-        println!("DEBUG: Using reranker: {reranker}");
-        println!("DEBUG: Queries: {queries:?}");
+        debug!("Starting result ranking with {} results", results.len());
+        debug!("Using reranker: {reranker}");
+        debug!("Queries: {queries:?}");
     }
 
     // Handle BERT-based reranking for MS-MARCO models
@@ -150,8 +152,8 @@ pub fn rank_search_results(
     let query_combine_duration = query_combine_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Query combination completed in {} - Combined query: '{}'",
+        debug!(
+            "Query combination completed in {} - Combined query: '{}'",
             format_duration(query_combine_duration),
             combined_query
         );
@@ -160,23 +162,13 @@ pub fn rank_search_results(
     // Extract document texts for ranking, including filename in each document
     let document_extraction_start = Instant::now();
     // This ensures filename terms are considered in the ranking algorithms
-    let documents: Vec<String> = results
-        .iter()
-        .map(|r| {
-            let mut doc = String::with_capacity(r.file.len() + r.code.len() + 15);
-            doc.push_str("// Filename: ");
-            doc.push_str(&r.file);
-            doc.push('\n');
-            doc.push_str(&r.code);
-            doc
-        })
-        .collect();
+    let documents = ranking_documents(results);
     let documents_refs: Vec<&str> = documents.iter().map(|s| s.as_str()).collect();
     let document_extraction_duration = document_extraction_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Document extraction completed in {} - Extracted {} documents",
+        debug!(
+            "Document extraction completed in {} - Extracted {} documents",
             format_duration(document_extraction_duration),
             documents.len()
         );
@@ -192,19 +184,19 @@ pub fn rank_search_results(
     let metrics_extraction_duration = metrics_extraction_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Metrics extraction completed in {}",
+        debug!(
+            "Metrics extraction completed in {}",
             format_duration(metrics_extraction_duration)
         );
-        println!(
-            "DEBUG: Extracted metrics - file_unique_terms: {file_unique_terms:?}, file_total_matches: {file_total_matches:?}, block_unique_terms: {block_unique_terms:?}, block_total_matches: {block_total_matches:?}"
+        debug!(
+            "Extracted metrics - file_unique_terms: {file_unique_terms:?}, file_total_matches: {file_total_matches:?}, block_unique_terms: {block_unique_terms:?}, block_total_matches: {block_total_matches:?}"
         );
     }
 
     // Extract pre-tokenized content if available
     let tokenized_extraction_start = Instant::now();
     let pre_tokenized: Vec<Vec<String>> = results
-        .iter()
+        .par_iter()
         .filter_map(|r| r.tokenized_content.clone())
         .collect();
 
@@ -212,13 +204,13 @@ pub fn rank_search_results(
 
     if debug_mode {
         if has_tokenized {
-            println!(
-                "DEBUG: Using pre-tokenized content from {} results",
+            debug!(
+                "Using pre-tokenized content from {} results",
                 pre_tokenized.len()
             );
         } else {
-            println!(
-                "DEBUG: Pre-tokenized content not available for all results (found {}/{}), falling back to tokenization",
+            debug!(
+                "Pre-tokenized content not available for all results (found {}/{}), falling back to tokenization",
                 pre_tokenized.len(),
                 results.len()
             );
@@ -228,8 +220,8 @@ pub fn rank_search_results(
     let tokenized_extraction_duration = tokenized_extraction_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Tokenized content extraction completed in {}",
+        debug!(
+            "Tokenized content extraction completed in {}",
             format_duration(tokenized_extraction_duration)
         );
     }
@@ -246,7 +238,7 @@ pub fn rank_search_results(
 
     let document_ranking_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting document ranking...");
+        debug!("Starting document ranking...");
     }
 
     // Get ranked indices from the ranking module (BM25 scores)
@@ -254,12 +246,12 @@ pub fn rank_search_results(
     let use_simd = std::env::var("DISABLE_SIMD_RANKING").unwrap_or_default() != "1";
     let ranked_indices = if use_simd {
         if debug_mode {
-            println!("DEBUG: Using SIMD-optimized ranking (default)");
+            debug!("Using SIMD-optimized ranking (default)");
         }
         ranking::rank_documents_simd(&ranking_params)
     } else {
         if debug_mode {
-            println!("DEBUG: Using traditional ranking (SIMD disabled)");
+            debug!("Using traditional ranking (SIMD disabled)");
         }
         ranking::rank_documents(&ranking_params)
     };
@@ -267,8 +259,8 @@ pub fn rank_search_results(
     let document_ranking_duration = document_ranking_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Document ranking completed in {} - Ranked {} documents",
+        debug!(
+            "Document ranking completed in {} - Ranked {} documents",
             format_duration(document_ranking_duration),
             ranked_indices.len()
         );
@@ -278,46 +270,26 @@ pub fn rank_search_results(
     // We don't filter by BM25 score here because the ranking module already does some filtering
     // based on the query, and we want to preserve OR query behavior
     let filtering_start = Instant::now();
-    let mut updated_results = Vec::new();
-
-    // Update scores for all results
-    for (rank_index, (original_index, bm25_score)) in ranked_indices.iter().enumerate() {
-        if let Some(result) = results.get(*original_index) {
-            let mut result_clone = result.clone();
-            result_clone.rank = Some(rank_index + 1); // 1-based rank
-
-            // Calculate coverage boost based on unique terms matched in the block
-            let coverage_boost =
-                calculate_coverage_boost(result_clone.block_unique_terms, queries.len());
-
-            // EXPERIMENT: Apply node type boosting for better relevance
-            let node_type_boost =
-                calculate_node_type_boost(&result_clone.node_type, result_clone.lines);
-
-            let boosted_score = bm25_score * coverage_boost * node_type_boost;
-            result_clone.score = Some(boosted_score);
-            result_clone.bm25_score = Some(*bm25_score); // Keep original BM25 score
-            updated_results.push(result_clone);
-        }
-    }
+    let mut updated_results = boosted_results(results, &ranked_indices, queries.len());
 
     let updated_len = updated_results.len();
 
     if debug_mode {
-        println!("DEBUG: Score update completed - Updated {updated_len} results");
+        debug!("Score update completed - Updated {updated_len} results");
     }
 
     // Sort updated results by BM25 score in descending order
     let reranker_sort_start = Instant::now();
 
     if debug_mode {
-        eprintln!("DEBUG: Using BM25 ranking (Okapi BM25 algorithm)");
+        debug!("Using BM25 ranking (Okapi BM25 algorithm)");
     } else {
         eprintln!("Using BM25 ranking (Okapi BM25 algorithm)");
     }
 
     // Sort by boosted score in descending order
-    updated_results.sort_by(|a, b| {
+    // The sort is stable, so equal scores keep their BM25 order on any number of threads
+    updated_results.par_sort_by(|a, b| {
         let score_a = a.score.unwrap_or(0.0); // Use boosted score
         let score_b = b.score.unwrap_or(0.0); // Use boosted score
                                               // Sort in descending order (higher score is better)
@@ -335,8 +307,8 @@ pub fn rank_search_results(
     let reranker_sort_duration = reranker_sort_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Reranker-specific sorting completed in {}",
+        debug!(
+            "Reranker-specific sorting completed in {}",
             format_duration(reranker_sort_duration)
         );
     }
@@ -367,8 +339,8 @@ pub fn rank_search_results(
     let filtering_duration = filtering_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Result processing completed in {} - Processed {} results",
+        debug!(
+            "Result processing completed in {} - Processed {} results",
             format_duration(filtering_duration),
             updated_len
         );
@@ -377,8 +349,8 @@ pub fn rank_search_results(
     let total_duration = start_time.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Total result ranking completed in {}",
+        debug!(
+            "Total result ranking completed in {}",
             format_duration(total_duration)
         );
     }
@@ -394,11 +366,11 @@ fn handle_bert_reranking(
     start_time: Instant,
 ) {
     if debug_mode {
-        println!("DEBUG: Using BERT reranking with {reranker}");
+        debug!("Using BERT reranking with {reranker}");
         if let Some(q) = question {
-            println!("DEBUG: Using custom question for reranking: '{q}'");
+            debug!("Using custom question for reranking: '{q}'");
         } else {
-            println!("DEBUG: Using search keywords for reranking: {queries:?}");
+            debug!("Using search keywords for reranking: {queries:?}");
         }
     } else {
         println!("Using BERT reranking with {reranker}");
@@ -447,8 +419,11 @@ fn handle_bert_reranking(
         let bert_result = match bert_result {
             Ok(inner_result) => inner_result,
             Err(_) => {
-                eprintln!("BERT reranking thread panicked");
-                println!("Falling back to BM25 ranking...");
+                warn(
+                    WarningKind::Other,
+                    None,
+                    "BERT reranking thread panicked; falling back to BM25 ranking",
+                );
                 fallback_to_bm25_ranking(results, queries, debug_mode, start_time);
                 return;
             }
@@ -465,15 +440,18 @@ fn handle_bert_reranking(
 
                 let total_duration = start_time.elapsed();
                 if debug_mode {
-                    println!(
-                        "DEBUG: BERT reranking completed successfully in {}",
+                    debug!(
+                        "BERT reranking completed successfully in {}",
                         format_duration(total_duration)
                     );
                 }
             }
             Err(e) => {
-                eprintln!("BERT reranking failed: {e}");
-                println!("Falling back to BM25 ranking...");
+                warn(
+                    WarningKind::Other,
+                    None,
+                    format!("BERT reranking failed: {e}; falling back to BM25 ranking"),
+                );
                 fallback_to_bm25_ranking(results, queries, debug_mode, start_time);
             }
         }
@@ -481,13 +459,58 @@ fn handle_bert_reranking(
 
     #[cfg(not(feature = "bert-reranker"))]
     {
-        eprintln!("BERT reranker '{reranker}' is not available.");
-        eprintln!("To enable BERT reranking, build with: cargo build --features bert-reranker");
-        println!("Falling back to BM25 ranking...");
+        warn(
+            WarningKind::Other,
+            None,
+            format!("BERT reranker '{reranker}' is not available (build with --features bert-reranker); falling back to BM25 ranking"),
+        );
         fallback_to_bm25_ranking(results, queries, debug_mode, start_time);
     }
 }
 
+/// The text BM25 scores each result by: its code, with the filename prepended so
+/// filename terms count too
+fn ranking_documents(results: &[SearchResult]) -> Vec<String> {
+    results
+        .par_iter()
+        .map(|r| {
+            let mut doc = String::with_capacity(r.file.len() + r.code.len() + 15);
+            doc.push_str("// Filename: ");
+            doc.push_str(&r.file);
+            doc.push('\n');
+            doc.push_str(&r.code);
+            doc
+        })
+        .collect()
+}
+
+/// The ranked results with their BM25 scores, boosted by term coverage and node type,
+/// in the order of `ranked_indices`
+fn boosted_results(
+    results: &[SearchResult],
+    ranked_indices: &[(usize, f64)],
+    query_count: usize,
+) -> Vec<SearchResult> {
+    ranked_indices
+        .par_iter()
+        .enumerate()
+        .filter_map(|(rank_index, (original_index, bm25_score))| {
+            let mut result = results.get(*original_index)?.clone();
+            result.rank = Some(rank_index + 1); // 1-based rank
+
+            // Calculate coverage boost based on unique terms matched in the block
+            let coverage_boost = calculate_coverage_boost(result.block_unique_terms, query_count);
+
+            // EXPERIMENT: Apply node type boosting for better relevance
+            let node_type_boost = calculate_node_type_boost(&result.node_type, result.lines);
+
+            result.score = Some(bm25_score * coverage_boost * node_type_boost);
+            result.bm25_score = Some(*bm25_score); // Keep original BM25 score
+            Some(result)
+        })
+        .collect()
+}
+
 /// Fallback to BM25 ranking when BERT reranking fails or is unavailable
 fn fallback_to_bm25_ranking(
     results: &mut [SearchResult],
@@ -499,22 +522,12 @@ fn fallback_to_bm25_ranking(
     let combined_query = queries.join(" ");
 
     // Extract document texts for ranking, including filename in each document
-    let documents: Vec<String> = results
-        .iter()
-        .map(|r| {
-            let mut doc = String::with_capacity(r.file.len() + r.code.len() + 15);
-            doc.push_str("// Filename: ");
-            doc.push_str(&r.file);
-            doc.push('\n');
-            doc.push_str(&r.code);
-            doc
-        })
-        .collect();
+    let documents = ranking_documents(results);
     let documents_refs: Vec<&str> = documents.iter().map(|s| s.as_str()).collect();
 
     // Extract pre-tokenized content if available
     let pre_tokenized: Vec<Vec<String>> = results
-        .iter()
+        .par_iter()
         .filter_map(|r| r.tokenized_content.clone())
         .collect();
 
@@ -534,41 +547,21 @@ fn fallback_to_bm25_ranking(
     let use_simd = std::env::var("DISABLE_SIMD_RANKING").unwrap_or_default() != "1";
     let ranked_indices = if use_simd {
         if debug_mode {
-            println!("DEBUG: Using SIMD-optimized BM25 ranking (fallback)");
+            debug!("Using SIMD-optimized BM25 ranking (fallback)");
         }
         ranking::rank_documents_simd(&ranking_params)
     } else {
         if debug_mode {
-            println!("DEBUG: Using traditional BM25 ranking (fallback)");
+            debug!("Using traditional BM25 ranking (fallback)");
         }
         ranking::rank_documents(&ranking_params)
     };
 
     // Update scores for all results
-    let mut updated_results = Vec::new();
-
-    for (rank_index, (original_index, bm25_score)) in ranked_indices.iter().enumerate() {
-        if let Some(result) = results.get(*original_index) {
-            let mut result_clone = result.clone();
-            result_clone.rank = Some(rank_index + 1); // 1-based rank
-
-            // Calculate coverage boost based on unique terms matched in the block
-            let coverage_boost =
-                calculate_coverage_boost(result_clone.block_unique_terms, queries.len());
-
-            // Apply node type boosting (same logic as in the main function)
-            let node_type_boost =
-                calculate_node_type_boost(&result_clone.node_type, result_clone.lines);
-
-            let boosted_score = bm25_score * coverage_boost * node_type_boost;
-            result_clone.score = Some(boosted_score);
-            result_clone.bm25_score = Some(*bm25_score);
-            updated_results.push(result_clone);
-        }
-    }
+    let mut updated_results = boosted_results(results, &ranked_indices, queries.len());
 
     // Sort by boosted score in descending order
-    updated_results.sort_by(|a, b| {
+    updated_results.par_sort_by(|a, b| {
         let score_a = a.score.unwrap_or(0.0);
         let score_b = b.score.unwrap_or(0.0);
         score_b
@@ -602,9 +595,217 @@ fn fallback_to_bm25_ranking(
 
     let total_duration = start_time.elapsed();
     if debug_mode {
-        println!(
-            "DEBUG: BM25 fallback ranking completed in {}",
+        debug!(
+            "BM25 fallback ranking completed in {}",
             format_duration(total_duration)
         );
     }
 }
+
+/// Scale the scores of results that matched only by filename and re-rank.
+///
+/// `weight` trades filename-token matches off against content matches: 1.0 leaves
+/// the ranking unchanged, values below 1.0 push filename-only hits down and values
+/// above 1.0 pull them up.
+pub fn apply_filename_weight(results: &mut [SearchResult], weight: f64) {
+    if (weight - 1.0).abs() < f64::EPSILON
+        || !results.iter().any(|r| r.matched_by_filename == Some(true))
+    {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        if result.matched_by_filename == Some(true) {
+            result.score = result.score.map(|score| score * weight);
+        }
+    }
+    rerank_by_score(results);
+}
+
+/// Mix how recently each result's file changed into its score and re-rank, for
+/// `--boost-recent`.
+///
+/// Recency runs from 0 for the least recently changed file among the results to 1 for
+/// the most recent one, going by mtime or, with `source` "git", by the last commit
+/// touching the file (files git doesn't track fall back to their mtime). Scores are
+/// scaled by `1 - weight + weight * recency`: 0 leaves the ranking unchanged and 1
+/// scores the results of the stalest file as 0.
+pub fn apply_recency_boost(results: &mut [SearchResult], source: &str, weight: f64) {
+    let weight = weight.clamp(0.0, 1.0);
+    if weight == 0.0 || results.is_empty() {
+        return;
+    }
+
+    let mut files: Vec<&str> = results.iter().map(|r| r.file.as_str()).collect();
+    files.sort_unstable();
+    files.dedup();
+    let changed: HashMap<String, u64> = files
+        .par_iter()
+        .filter_map(|file| {
+            let path = Path::new(file);
+            let time = if source == "git" {
+                last_commit_time(path).or_else(|| modified_time(path))
+            } else {
+                modified_time(path)
+            };
+            Some((file.to_string(), time?))
+        })
+        .collect();
+    let (Some(&oldest), Some(&newest)) = (changed.values().min(), changed.values().max()) else {
+        return;
+    };
+    if oldest == newest {
+        return;
+    }
+
+    for result in results.iter_mut() {
+        // Files whose time can't be read count as the stalest
+        let recency = changed.get(&result.file).map_or(0.0, |&time| {
+            (time - oldest) as f64 / (newest - oldest) as f64
+        });
+        result.score = result
+            .score
+            .map(|score| score * (1.0 - weight + weight * recency));
+    }
+    rerank_by_score(results);
+}
+
+/// Seconds since the epoch at which `path` was last modified
+fn modified_time(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(
+        modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs(),
+    )
+}
+
+/// Seconds since the epoch of the last commit touching `path`; `None` outside a git
+/// repository or for files never committed
+fn last_commit_time(path: &Path) -> Option<u64> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir.unwrap_or(Path::new(".")))
+        .args(["log", "-1", "--format=%ct", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Sort results by score and renumber their ranks, after their scores were adjusted
+fn rerank_by_score(results: &mut [SearchResult]) {
+    // Results parked at usize::MAX by the rerankers stay at the end
+    let parked = |r: &SearchResult| r.rank == Some(usize::MAX);
+    results.sort_by(|a, b| {
+        parked(a).cmp(&parked(b)).then_with(|| {
+            b.score
+                .unwrap_or(0.0)
+                .partial_cmp(&a.score.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    });
+    for (rank, result) in results.iter_mut().enumerate() {
+        if !parked(result) {
+            result.rank = Some(rank + 1);
+        }
+    }
+}
+
+/// Order runs of results with equal scores by file and start line, and renumber the
+/// ranks to match, for `--stable-order`.
+///
+/// Ranking keeps tied results in the order their files were processed, which varies
+/// between runs under multithreading; `--offset` pages are only stable without it.
+/// Scores count as equal when they differ only by rounding, since term scores are
+/// summed in a different order on every run. `results` must be in rank order.
+pub fn stabilize_rank_ties(results: &mut [SearchResult]) {
+    let same_score = |a: &SearchResult, b: &SearchResult| match (a.score, b.score) {
+        (Some(a), Some(b)) => (a - b).abs() <= 1e-9 * a.abs().max(b.abs()),
+        (a, b) => a == b,
+    };
+    for tied in results.chunk_by_mut(same_score) {
+        tied.sort_by(|a, b| (&a.file, a.lines.0).cmp(&(&b.file, b.lines.0)));
+    }
+    for (rank, result) in results.iter_mut().enumerate() {
+        if result.rank.is_some_and(|rank| rank != usize::MAX) {
+            result.rank = Some(rank + 1);
+        }
+    }
+}
+
+/// Reorder ranked results so at most `per_directory` results from any one directory
+/// come before results from other directories.
+///
+/// Results beyond the cap are not dropped: they move to later "rounds", so the first
+/// round holds the best `per_directory` hits of every directory in rank order, the
+/// second round the next `per_directory`, and so on. `results` must be in rank order.
+pub fn diversify_by_directory(results: &mut Vec<SearchResult>, per_directory: usize) {
+    if per_directory == 0 {
+        return;
+    }
+
+    // Results parked at usize::MAX by the rerankers stay at the end
+    let parked = |r: &SearchResult| r.rank == Some(usize::MAX);
+    let mut seen_per_directory: HashMap<PathBuf, usize> = HashMap::new();
+    let mut keyed: Vec<(bool, usize, usize, SearchResult)> = results
+        .drain(..)
+        .enumerate()
+        .map(|(position, result)| {
+            let directory = Path::new(&result.file)
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            let seen = seen_per_directory.entry(directory).or_insert(0);
+            let round = *seen / per_directory;
+            *seen += 1;
+            (parked(&result), round, position, result)
+        })
+        .collect();
+    keyed.sort_by_key(|(parked, round, position, _)| (*parked, *round, *position));
+
+    results.extend(keyed.into_iter().map(|(_, _, _, result)| result));
+    for (rank, result) in results.iter_mut().enumerate() {
+        if result.rank.is_some() && !parked(result) {
+            result.rank = Some(rank + 1);
+        }
+    }
+}
+
+/// Put the final results in the order requested with `--sort`, after ranking and
+/// limits have chosen which results are returned.
+///
+/// `score` keeps the relevance order, `path` orders by file and start line, `lines`
+/// puts the shortest blocks first and `mtime` the most recently modified files first.
+/// `reverse` flips whichever order was chosen.
+pub fn sort_results(results: &mut [SearchResult], key: &str, reverse: bool) {
+    match key {
+        "path" => results.sort_by(|a, b| (&a.file, a.lines.0).cmp(&(&b.file, b.lines.0))),
+        "lines" => results.sort_by_key(|r| (r.lines.1.saturating_sub(r.lines.0), r.file.clone())),
+        "mtime" => {
+            let mut mtimes: HashMap<String, Option<std::time::SystemTime>> = HashMap::new();
+            for result in results.iter() {
+                mtimes.entry(result.file.clone()).or_insert_with(|| {
+                    std::fs::metadata(&result.file)
+                        .and_then(|m| m.modified())
+                        .ok()
+                });
+            }
+            // Newest first; files whose mtime can't be read go last
+            results.sort_by(|a, b| {
+                mtimes[&b.file]
+                    .cmp(&mtimes[&a.file])
+                    .then_with(|| (&a.file, a.lines.0).cmp(&(&b.file, b.lines.0)))
+            });
+        }
+        _ => {}
+    }
+    if reverse {
+        results.reverse();
+    }
+}
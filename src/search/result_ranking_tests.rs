@@ -14,6 +14,10 @@ mod tests {
                 code: "fn test_function() { println!(\"This is a test function with search terms\"); }".to_string(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -42,6 +46,10 @@ mod tests {
                 code: "fn another_function() { // This doesn't have the key term }".to_string(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -70,6 +78,10 @@ mod tests {
                 code: "fn search_function() { // This has search in the function name and multiple search terms search search }".to_string(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -1,3 +1,4 @@
+use tracing::debug;
 use std::path::Path;
 use probe_code::models::SearchResult;
 use colored::*;
@@ -5,7 +6,7 @@ use colored::*;
 /// Function to format and print search results according to the specified format
 pub fn format_and_print_search_results(results: &[SearchResult], dry_run: bool) {
     // Check if debug mode is enabled
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if colors should be disabled
     let no_color = std::env::var("NO_COLOR").is_ok();
@@ -81,41 +82,41 @@ pub fn format_and_print_search_results(results: &[SearchResult], dry_run: bool)
             // Print the score if available and in debug mode
             if debug_mode {
                 if let Some(score) = result.score {
-                    println!("{} {score:.6}", "Score:".dimmed());
+                    debug!("{} {score:.6}", "Score:".dimmed());
                 }
                 if let Some(tfidf_score) = result.tfidf_score {
-                    println!("{} {tfidf_score:.6}", "TF-IDF Score:".dimmed());
+                    debug!("{} {tfidf_score:.6}", "TF-IDF Score:".dimmed());
                 }
                 if let Some(bm25_score) = result.bm25_score {
                     // Check if this is actually a BERT score
                     let is_bert_score = result.score == result.bm25_score && result.score.is_some();
                     if is_bert_score {
-                        println!("{} {bm25_score:.6}", "BERT Score:".dimmed());
+                        debug!("{} {bm25_score:.6}", "BERT Score:".dimmed());
                     } else {
-                        println!("{} {bm25_score:.6}", "BM25 Score:".dimmed());
+                        debug!("{} {bm25_score:.6}", "BM25 Score:".dimmed());
                     }
                 }
                 if let Some(content_matches) = &result.content_matches {
                     let content_matches_str = content_matches.join(", ");
-                    println!("{} {content_matches_str}", "Content matches:".dimmed());
+                    debug!("{} {content_matches_str}", "Content matches:".dimmed());
                 }
                 if let Some(filename_matches) = &result.filename_matches {
                     let filename_matches_str = filename_matches.join(", ");
-                    println!("{} {filename_matches_str}", "Filename matches:".dimmed());
+                    debug!("{} {filename_matches_str}", "Filename matches:".dimmed());
                 }
                 if let Some(unique_terms) = result.unique_terms {
-                    println!("{} {unique_terms}", "Unique terms matched:".dimmed());
+                    debug!("{} {unique_terms}", "Unique terms matched:".dimmed());
                 }
                 if let Some(total_matches) = result.total_matches {
-                    println!("{} {total_matches}", "Total term matches:".dimmed());
+                    debug!("{} {total_matches}", "Total term matches:".dimmed());
                 }
 
                 // Display block-level match statistics in debug mode
                 if let Some(block_unique_terms) = result.block_unique_terms {
-                    println!("{} {block_unique_terms}", "Block unique terms matched:".dimmed());
+                    debug!("{} {block_unique_terms}", "Block unique terms matched:".dimmed());
                 }
                 if let Some(block_total_matches) = result.block_total_matches {
-                    println!("{} {block_total_matches}", "Block total term matches:".dimmed());
+                    debug!("{} {block_total_matches}", "Block total term matches:".dimmed());
                 }
             }
 
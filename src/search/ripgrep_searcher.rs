@@ -1,30 +1,73 @@
 use anyhow::{Context, Result};
-use regex::{RegexSet, RegexSetBuilder};
+use probe_code::interrupt;
+use probe_code::progress::Progress;
+use regex::{Regex, RegexBuilder, RegexSet, RegexSetBuilder};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
+use tracing::debug;
+
+/// Most combined regexes a search compiles, however many patterns and files it has
+const MAX_BATCHES: usize = 8;
+
+/// Files that make it worth compiling one more batch
+const FILES_PER_BATCH: usize = 2_000;
+
+/// Patterns above which an alternation no longer gets a fast literal prefilter and its
+/// lazy DFA starts thrashing, so they are split into more batches when possible
+const MAX_BATCH_PATTERNS: usize = 64;
+
+/// Patterns per batch when searching `file_count` files.
+///
+/// Compiling a batch costs about as much as scanning a few hundred files, so small
+/// searches put every pattern in one batch, while large ones split the patterns into up
+/// to `MAX_BATCHES` smaller alternations that each scan faster. A query that expands into
+/// many stem and synonym variants gets as many batches as keep them under
+/// `MAX_BATCH_PATTERNS` each, within the same bound.
+pub fn batch_size(pattern_count: usize, file_count: usize) -> usize {
+    let batches = (file_count / FILES_PER_BATCH)
+        .max(pattern_count.div_ceil(MAX_BATCH_PATTERNS))
+        .clamp(1, MAX_BATCHES);
+    pattern_count.div_ceil(batches).max(1)
+}
+
+/// Patterns matched together: an alternation of all of them rules out files where none
+/// occurs in one pass, and a set tells which of them match each line of the others
+#[derive(Debug)]
+struct PatternBatch {
+    /// Index of the batch's first pattern among all patterns
+    offset: usize,
+    any: Regex,
+    set: RegexSet,
+}
 
 /// High-performance RegexSet-based searcher for fast file pattern matching
 /// This provides optimal performance through:
-/// - Pre-compiled RegexSet for multi-pattern efficiency
+/// - Pre-compiled pattern batches, sized by the number of files to search
 /// - Simple file I/O without unnecessary abstraction layers
 /// - Parallel processing capabilities
 /// - Thread-safe design for concurrent access
 #[derive(Debug)]
 pub struct RipgrepSearcher {
     debug_mode: bool,
-    regex_set: RegexSet,
+    batches: Vec<PatternBatch>,
 }
 
 impl RipgrepSearcher {
     /// Create a new RipgrepSearcher with optimized settings
     pub fn new(patterns: &[String], _enable_simd: bool) -> Result<Self> {
-        let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+        Self::for_files(patterns, 0)
+    }
+
+    /// Create a RipgrepSearcher whose pattern batches suit searching `file_count` files
+    pub fn for_files(patterns: &[String], file_count: usize) -> Result<Self> {
+        let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
         if debug_mode {
-            println!(
-                "DEBUG: Creating fast RegexSet searcher with {} patterns",
-                patterns.len()
+            debug!(
+                "Creating fast RegexSet searcher with {} patterns for {} files",
+                patterns.len(),
+                file_count
             );
         }
 
@@ -54,25 +97,44 @@ impl RipgrepSearcher {
             ));
         }
 
+        let batch_size = batch_size(case_insensitive_patterns.len(), file_count);
         if debug_mode {
-            println!(
-                "DEBUG: Creating RegexSet with {} patterns, total size: {} bytes",
+            debug!(
+                "Creating RegexSet with {} patterns in batches of {}, total size: {} bytes",
                 case_insensitive_patterns.len(),
+                batch_size,
                 total_pattern_size
             );
         }
 
-        // Use RegexSetBuilder with size limits for additional protection
-        let mut builder = RegexSetBuilder::new(&case_insensitive_patterns);
-        builder.size_limit(10 * 1024 * 1024); // 10MB compiled program limit
-
-        let regex_set = builder.build().context(
-            "Failed to build RegexSet during initialization - compiled regex exceeds size limits",
-        )?;
+        let batches = case_insensitive_patterns
+            .chunks(batch_size)
+            .enumerate()
+            .map(|(index, batch)| {
+                // Use the builders' size limits for additional protection
+                let set = RegexSetBuilder::new(batch)
+                    .size_limit(10 * 1024 * 1024) // 10MB compiled program limit
+                    .build()
+                    .context(
+                        "Failed to build RegexSet during initialization - compiled regex exceeds size limits",
+                    )?;
+                // Whole files are matched at once, so anchors must match at line ends
+                let any = RegexBuilder::new(&batch.join("|"))
+                    .multi_line(true)
+                    .size_limit(10 * 1024 * 1024)
+                    .build()
+                    .context("Failed to combine patterns - compiled regex exceeds size limits")?;
+                Ok(PatternBatch {
+                    offset: index * batch_size,
+                    any,
+                    set,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(RipgrepSearcher {
             debug_mode,
-            regex_set,
+            batches,
         })
     }
 
@@ -87,7 +149,7 @@ impl RipgrepSearcher {
         let mut term_map = HashMap::new();
 
         if self.debug_mode {
-            println!("DEBUG: Searching file with optimized RegexSet: {file_path:?}");
+            debug!("Searching file with optimized RegexSet: {file_path:?}");
         }
 
         // Define a reasonable maximum file size (same as old implementation)
@@ -98,7 +160,7 @@ impl RipgrepSearcher {
             Ok(path) => path,
             Err(e) => {
                 if self.debug_mode {
-                    println!("DEBUG: Error resolving path for {file_path:?}: {e:?}");
+                    debug!("Error resolving path for {file_path:?}: {e:?}");
                 }
                 return Err(anyhow::anyhow!("Failed to resolve file path: {}", e));
             }
@@ -109,7 +171,7 @@ impl RipgrepSearcher {
             Ok(meta) => meta,
             Err(e) => {
                 if self.debug_mode {
-                    println!("DEBUG: Error getting metadata for {resolved_path:?}: {e:?}");
+                    debug!("Error getting metadata for {resolved_path:?}: {e:?}");
                 }
                 return Err(anyhow::anyhow!("Failed to get file metadata: {}", e));
             }
@@ -118,8 +180,8 @@ impl RipgrepSearcher {
         // Check if the file is too large
         if metadata.len() > MAX_FILE_SIZE {
             if self.debug_mode {
-                println!(
-                    "DEBUG: Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
+                debug!(
+                    "Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
                     resolved_path,
                     metadata.len(),
                     MAX_FILE_SIZE
@@ -137,8 +199,8 @@ impl RipgrepSearcher {
             Ok(content) => content,
             Err(e) => {
                 if self.debug_mode {
-                    println!(
-                        "DEBUG: Error reading file {:?}: {:?} (size: {} bytes)",
+                    debug!(
+                        "Error reading file {:?}: {:?} (size: {} bytes)",
                         resolved_path,
                         e,
                         metadata.len()
@@ -148,13 +210,23 @@ impl RipgrepSearcher {
             }
         };
 
+        // Only batches with a match somewhere in the file are matched line by line
+        let batches: Vec<&PatternBatch> = self
+            .batches
+            .iter()
+            .filter(|batch| batch.any.is_match(&content))
+            .collect();
+        if batches.is_empty() {
+            return Ok(term_map);
+        }
+
         // Process each line (fast in-memory processing)
         for (line_number, line) in content.lines().enumerate() {
             // Skip lines that are too long
             if line.len() > 2000 {
                 if self.debug_mode {
-                    println!(
-                        "DEBUG: Skipping line {} in file {:?} - line too long ({} characters)",
+                    debug!(
+                        "Skipping line {} in file {:?} - line too long ({} characters)",
                         line_number + 1,
                         file_path,
                         line.len()
@@ -163,12 +235,12 @@ impl RipgrepSearcher {
                 continue;
             }
 
-            // Use pre-compiled RegexSet for efficient multi-pattern matching
-            let matches = self.regex_set.matches(line);
-            if matches.matched_any() {
+            // Use pre-compiled RegexSets for efficient multi-pattern matching
+            for batch in &batches {
+                let matches = batch.set.matches(line);
                 // For each matched pattern, map to corresponding term indices
                 for pattern_idx in matches.iter() {
-                    if let Some(term_set) = pattern_to_terms.get(pattern_idx) {
+                    if let Some(term_set) = pattern_to_terms.get(batch.offset + pattern_idx) {
                         for &term_idx in term_set {
                             term_map
                                 .entry(term_idx)
@@ -182,8 +254,8 @@ impl RipgrepSearcher {
 
         if self.debug_mode {
             let duration = start_time.elapsed();
-            println!(
-                "DEBUG: Fast RegexSet search completed for {:?} in {:?} - found {} unique terms",
+            debug!(
+                "Fast RegexSet search completed for {:?} in {:?} - found {} unique terms",
                 file_path,
                 duration,
                 term_map.len()
@@ -204,8 +276,8 @@ impl RipgrepSearcher {
         let start_time = Instant::now();
 
         if self.debug_mode {
-            println!(
-                "DEBUG: Starting parallel RegexSet search on {} files",
+            debug!(
+                "Starting parallel RegexSet search on {} files",
                 file_paths.len()
             );
         }
@@ -215,11 +287,16 @@ impl RipgrepSearcher {
         let mut sorted_file_paths = file_paths.to_vec();
         sorted_file_paths.sort();
 
+        let progress = Progress::new("Scanning files", sorted_file_paths.len());
         // Use par_iter().filter_map() for parallel processing
         // The searcher instance is thread-safe, so we can reuse it
         let results: Vec<(PathBuf, HashMap<usize, HashSet<usize>>)> = sorted_file_paths
             .par_iter()
             .filter_map(|file_path| {
+                if interrupt::is_interrupted() {
+                    return None;
+                }
+                progress.inc();
                 // Reuse the shared searcher instance - it's thread-safe
                 // The search_file method uses only simple file I/O and RegexSet matching
                 match self.search_file(file_path, pattern_to_terms) {
@@ -232,7 +309,7 @@ impl RipgrepSearcher {
                     }
                     Err(e) => {
                         if self.debug_mode {
-                            println!("DEBUG: Error searching file {file_path:?}: {e}");
+                            debug!("Error searching file {file_path:?}: {e}");
                         }
                         None
                     }
@@ -248,8 +325,8 @@ impl RipgrepSearcher {
 
         if self.debug_mode {
             let duration = start_time.elapsed();
-            println!(
-                "DEBUG: Parallel RegexSet search completed in {:?} - found matches in {} files",
+            debug!(
+                "Parallel RegexSet search completed in {:?} - found matches in {} files",
                 duration,
                 final_results.len()
             );
@@ -315,6 +392,44 @@ mod tests {
         assert!(results.contains_key(&file2));
     }
 
+    #[test]
+    fn test_batch_size_adapts_to_files_and_patterns() {
+        // Small searches compile a single batch
+        assert_eq!(batch_size(10, 50), 10);
+        // Larger ones split the patterns, up to MAX_BATCHES batches
+        assert_eq!(batch_size(40, 10_000), 8);
+        assert_eq!(batch_size(40, 1_000_000), 5);
+        // Many variants are split even in a small repository
+        assert_eq!(batch_size(200, 10), 50);
+        assert_eq!(batch_size(5_000, 10), 625);
+        assert_eq!(batch_size(0, 10), 1);
+    }
+
+    #[test]
+    fn test_patterns_in_several_batches() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("lib.rs");
+        fs::write(
+            &file_path,
+            "fn parse() {}\nstruct Config;\nlet word_99 = 1;\n",
+        )
+        .unwrap();
+
+        // 100 patterns for 100 terms go into two batches
+        let patterns: Vec<String> = (0..100).map(|i| format!(r"\bword_{i}\b")).collect();
+        let patterns = [patterns, vec!["^struct".to_string(), "parse".to_string()]].concat();
+        let pattern_to_terms: Vec<HashSet<usize>> =
+            (0..patterns.len()).map(|i| HashSet::from([i])).collect();
+        let searcher = RipgrepSearcher::for_files(&patterns, 10).unwrap();
+        assert_eq!(searcher.batches.len(), 2);
+
+        let result = searcher.search_file(&file_path, &pattern_to_terms).unwrap();
+        assert_eq!(result.len(), 3, "{result:?}");
+        assert_eq!(result[&99], HashSet::from([3]));
+        assert_eq!(result[&100], HashSet::from([2]));
+        assert_eq!(result[&101], HashSet::from([1]));
+    }
+
     #[test]
     fn test_avoid_double_case_insensitive_wrapping() {
         // Test that patterns already containing (?i) are not wrapped again
@@ -0,0 +1,114 @@
+//! Named queries from the project's config, for `probe search @name`.
+//!
+//! A `[queries]` section in `probe.toml` / `.probe/config.toml` maps names to queries:
+//!
+//! ```toml
+//! [queries]
+//! auth = "login OR oauth AND ext:rs"
+//! ```
+//!
+//! Every `@name` word of a search pattern that names one of them is replaced by its
+//! query, in parentheses when the pattern has more than that word. Other `@` words, such
+//! as `@Override`, are searched for as written.
+
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use probe_code::search::stemming::project_config_path;
+
+/// The `[queries]` of the nearest `probe.toml` or `.probe/config.toml` at or above
+/// `start`, by name
+pub fn project_queries(start: &Path) -> BTreeMap<String, String> {
+    project_config_path(start)
+        .and_then(|config| std::fs::read_to_string(config).ok())
+        .map(|content| parse_queries(&content))
+        .unwrap_or_default()
+}
+
+/// The `name = "query"` entries of the `[queries]` section of a config file
+pub fn parse_queries(content: &str) -> BTreeMap<String, String> {
+    let mut queries = BTreeMap::new();
+    let mut in_queries = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_queries = line == "[queries]";
+            continue;
+        }
+        if !in_queries || line.starts_with('#') {
+            continue;
+        }
+        if let Some((name, query)) = line.split_once('=') {
+            let query = query.trim();
+            let query = query
+                .strip_prefix('"')
+                .and_then(|query| query.strip_suffix('"'))
+                .unwrap_or(query);
+            queries.insert(name.trim().to_string(), query.replace("\\\"", "\""));
+        }
+    }
+    queries
+}
+
+/// `pattern` with every `@name` of a saved query replaced by the query
+pub fn expand_saved_queries(pattern: &str, queries: &BTreeMap<String, String>) -> String {
+    if let Some(query) = pattern
+        .trim()
+        .strip_prefix('@')
+        .and_then(|name| queries.get(name))
+    {
+        return query.clone();
+    }
+    let reference = Regex::new(r"(^|[\s(])@([\w-]+)").unwrap();
+    reference
+        .replace_all(pattern, |caps: &Captures| match queries.get(&caps[2]) {
+            Some(query) => format!("{}({query})", &caps[1]),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"stemmer = "porter"
+
+[queries]
+# Authentication entry points
+auth = "login OR oauth AND ext:rs"
+errors = "\"Error\" -test"
+
+[other]
+ignored = "x"
+"#;
+
+    #[test]
+    fn test_parse_queries() {
+        let queries = parse_queries(CONFIG);
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries["auth"], "login OR oauth AND ext:rs");
+        assert_eq!(queries["errors"], "\"Error\" -test");
+    }
+
+    #[test]
+    fn test_expand_saved_queries() {
+        let queries = parse_queries(CONFIG);
+        assert_eq!(
+            expand_saved_queries("@auth", &queries),
+            "login OR oauth AND ext:rs"
+        );
+        assert_eq!(
+            expand_saved_queries("@auth AND token", &queries),
+            "(login OR oauth AND ext:rs) AND token"
+        );
+        assert_eq!(
+            expand_saved_queries("(@errors OR panic)", &queries),
+            "((\"Error\" -test) OR panic)"
+        );
+        assert_eq!(
+            expand_saved_queries("@Override  foo", &queries),
+            "@Override  foo"
+        );
+    }
+}
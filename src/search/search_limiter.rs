@@ -1,7 +1,8 @@
 use probe_code::models::{LimitedSearchResults, SearchLimits, SearchResult};
 use probe_code::search::search_tokens::count_block_tokens;
+use std::collections::HashSet;
 
-/// Helper function to apply limits (max results, max bytes, max tokens) to search results
+/// Helper function to apply limits (max results, max bytes, max tokens, max files) to search results
 ///
 /// This function implements pre-computed token limits optimization with running totals and early termination.
 /// Token counting is expensive (tiktoken-rs calls taking ~31ms for small result sets,
@@ -19,20 +20,29 @@ use probe_code::search::search_tokens::count_block_tokens;
 /// - Progressive evaluation: only count tokens when we estimate we're approaching the limit
 /// - Byte-based early estimation (1 token ≈ 4 bytes is rough approximation)
 /// - Result quality: Process ranked results first to ensure best results within limits
+///
+/// `max_files` caps the distinct files represented rather than stopping the walk: once it
+/// is reached, results from new files are skipped while further blocks of the files
+/// already included are still accepted, spreading the results across the codebase.
 pub fn apply_limits(
     results: Vec<SearchResult>,
     max_results: Option<usize>,
     max_bytes: Option<usize>,
     max_tokens: Option<usize>,
+    max_files: Option<usize>,
 ) -> LimitedSearchResults {
     // Early return if no limits are specified - avoids all token counting and processing
-    if max_results.is_none() && max_bytes.is_none() && max_tokens.is_none() {
+    if max_results.is_none() && max_bytes.is_none() && max_tokens.is_none() && max_files.is_none() {
         return LimitedSearchResults {
             results,
             skipped_files: Vec::new(),
             limits_applied: None,
             cached_blocks_skipped: None,
             files_skipped_early_termination: None,
+            session_tokens: None,
+            match_count: None,
+            total_ranked: None,
+            large_files_skipped: Vec::new(),
         };
     }
 
@@ -86,6 +96,7 @@ pub fn apply_limits(
     let mut running_bytes = 0; // Running total of bytes in accepted results
     let mut running_tokens = 0; // Running total of tokens in accepted results
     let mut running_count = 0; // Running count of accepted results
+    let mut included_files: HashSet<String> = HashSet::new(); // Files of accepted results
 
     // Performance optimization: Determine if we need token counting and when to start
     let max_token_limit = max_tokens.unwrap_or(usize::MAX);
@@ -145,6 +156,19 @@ pub fn apply_limits(
             }
         }
 
+        // File limit: skip results from new files once enough files are represented,
+        // without ending the walk since later results may come from included files
+        if let Some(max_files_limit) = max_files {
+            if included_files.len() >= max_files_limit && !included_files.contains(&r.file) {
+                if r.rank.is_some()
+                    && (r.tfidf_score.unwrap_or(0.0) > 0.0 || r.bm25_score.unwrap_or(0.0) > 0.0)
+                {
+                    skipped.push(r);
+                }
+                continue;
+            }
+        }
+
         // PRE-COMPUTED LIMITS: Ultra-lazy token counting with running totals
         let r_tokens = if max_tokens.is_some() {
             // Use rough estimation and only start precise counting if we're very close to the limit
@@ -193,6 +217,9 @@ pub fn apply_limits(
         running_bytes += r_bytes;
         running_tokens += r_tokens;
         running_count += 1;
+        if max_files.is_some() {
+            included_files.insert(r.file.clone());
+        }
         limited.push(r);
     }
 
@@ -217,10 +244,15 @@ pub fn apply_limits(
             max_results,
             max_bytes,
             max_tokens,
+            max_files,
             total_bytes: running_bytes,
             total_tokens: final_total_tokens,
         }),
         cached_blocks_skipped: None,
         files_skipped_early_termination: None,
+        session_tokens: None,
+        match_count: None,
+        total_ranked: None,
+        large_files_skipped: Vec::new(),
     }
 }
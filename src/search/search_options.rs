@@ -1,8 +1,9 @@
-use std::path::Path;
+use std::path::PathBuf;
 
 /// Options for performing a search
 pub struct SearchOptions<'a> {
-    pub path: &'a Path,
+    /// Root paths to search; results from all of them are ranked together
+    pub paths: &'a [PathBuf],
     pub queries: &'a [String],
     pub files_only: bool,
     pub custom_ignores: &'a [String],
@@ -15,12 +16,161 @@ pub struct SearchOptions<'a> {
     pub max_results: Option<usize>,
     pub max_bytes: Option<usize>,
     pub max_tokens: Option<usize>,
+    /// Maximum number of distinct files in the results
+    pub max_files: Option<usize>,
     pub allow_tests: bool,
     pub no_merge: bool,
     pub merge_threshold: Option<usize>,
     pub dry_run: bool,
     pub session: Option<&'a str>,
+    /// Deduplicate against blocks returned by every query of the session, not just this one
+    pub session_across_queries: bool,
+    /// Age after which unused session caches are deleted (default: 24 hours)
+    pub session_ttl: Option<std::time::Duration>,
+    /// Cap on blocks remembered per session cache (default: 10000)
+    pub session_max_entries: Option<usize>,
     pub timeout: u64,
     pub question: Option<&'a str>,
     pub no_gitignore: bool,
+    /// Don't extend blocks upward over attached attributes/decorators/annotations
+    pub no_attributes: bool,
+    /// Also search the sources of the project's dependencies (vendor, node_modules,
+    /// cargo registry cache, Go module cache)
+    pub include_deps: bool,
+    /// Score multiplier for results that matched only by filename (1.0 = unchanged)
+    pub filename_weight: f64,
+    /// Collapse identical blocks from different files into one result
+    pub dedupe_cross_file: bool,
+    /// Cap on top-ranked results per directory before other directories' results
+    pub diversify: Option<usize>,
+    /// In exact mode, escape regex metacharacters in the query; false uses it as a raw regex
+    pub regex_escape: bool,
+    /// Keep generated files ("do not edit" headers, *.pb.go, ...) and license-only files
+    pub include_generated: bool,
+    /// Output order applied after ranking and limits: score, path, lines or mtime
+    pub sort: &'a str,
+    /// Reverse the `sort` order
+    pub reverse: bool,
+    /// In exact mode, match terms containing an uppercase letter case-sensitively
+    pub smart_case: bool,
+    /// Search exactly these files instead of walking `paths`; ignore rules don't apply
+    pub files_from: Option<&'a [PathBuf]>,
+    /// Share of the trigram-vector similarity rank in `hybrid-trigram` ranking (0.0 to 1.0)
+    pub vector_weight: f64,
+    /// Also match every term against words one edit away (`~` does it per term)
+    pub fuzzy: bool,
+    /// Stop after finding matching files and report a `MatchCount` instead of results
+    pub count_only: bool,
+    /// Match terms only with the case they were written in
+    pub case_sensitive: bool,
+    /// Shorten blocks longer than this many lines to their signature and matched lines
+    pub max_block_lines: Option<usize>,
+    /// Match the query as one regular expression, like grep, but rank results like a
+    /// normal search
+    pub regex: bool,
+    /// Split results spanning an entire file into chunks of consecutive lines
+    pub no_whole_files: bool,
+    /// Restrict the search to blocks returned by earlier queries of this session
+    pub within_session: Option<&'a str>,
+    /// Only search files matching one of these globs (.gitignore syntax, relative to
+    /// the search root)
+    pub include: &'a [String],
+    /// Skip this many ranked results before the limits pick the ones returned, to page
+    /// through a result set
+    pub offset: usize,
+    /// Order results with equal scores by file and line instead of by the order their
+    /// files were processed in, so rankings and pages are the same on every run
+    pub stable_order: bool,
+    /// Only search files of these types (ripgrep type names, or user-defined ones)
+    pub file_types: &'a [String],
+    /// User-defined file types, as `name:glob,glob`
+    pub type_definitions: &'a [String],
+    /// Match and rank only declaration signatures (function names, struct names, ...),
+    /// not the bodies or call sites around them
+    pub symbols_only: bool,
+    /// Ignore matches in comments, ranking blocks by their code alone
+    pub no_comments: bool,
+    /// Match and rank only the comments of blocks, to find documentation
+    pub comments_only: bool,
+    /// Match and rank only string literals, to find messages and error text
+    pub strings_only: bool,
+    /// Mix file recency into scores, judged by `mtime` or the last `git` commit
+    pub boost_recent: Option<&'a str>,
+    /// Share of recency in the scores with `boost_recent` (0.0 to 1.0)
+    pub recency_weight: f64,
+    /// Only search files with uncommitted changes (staged, unstaged or untracked)
+    pub changed: bool,
+    /// Only search files changed since the merge base of this git ref and `HEAD`, or
+    /// not committed yet
+    pub since: Option<&'a str>,
+    /// Annotate each result with the last commit to change it, from `git blame`
+    pub blame: bool,
+    /// Annotate each result with the owners of its file from CODEOWNERS
+    pub owners: bool,
+}
+
+/// The defaults of `probe search`, so callers only set the options they need
+impl Default for SearchOptions<'_> {
+    fn default() -> Self {
+        SearchOptions {
+            paths: &[],
+            queries: &[],
+            files_only: false,
+            custom_ignores: &[],
+            exclude_filenames: false,
+            reranker: "bm25",
+            frequency_search: true,
+            exact: false,
+            language: None,
+            max_results: None,
+            max_bytes: None,
+            max_tokens: None,
+            max_files: None,
+            allow_tests: false,
+            no_merge: false,
+            merge_threshold: None,
+            dry_run: false,
+            session: None,
+            session_across_queries: false,
+            session_ttl: None,
+            session_max_entries: None,
+            timeout: 30,
+            question: None,
+            no_gitignore: false,
+            no_attributes: false,
+            include_deps: false,
+            filename_weight: 1.0,
+            dedupe_cross_file: false,
+            diversify: None,
+            regex_escape: true,
+            include_generated: false,
+            sort: "score",
+            reverse: false,
+            smart_case: false,
+            files_from: None,
+            vector_weight: 0.5,
+            fuzzy: false,
+            count_only: false,
+            case_sensitive: false,
+            max_block_lines: None,
+            regex: false,
+            no_whole_files: false,
+            within_session: None,
+            include: &[],
+            offset: 0,
+            stable_order: false,
+            file_types: &[],
+            type_definitions: &[],
+            symbols_only: false,
+            no_comments: false,
+            comments_only: false,
+            strings_only: false,
+            boost_recent: None,
+            recency_weight: 0.3,
+            changed: false,
+            since: None,
+            blame: false,
+            owners: false,
+        }
+    }
 }
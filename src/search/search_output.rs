@@ -3,10 +3,14 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tracing::debug;
 
-use probe_code::models::SearchResult;
+use probe_code::messages::{self, text, text_with, Msg};
+use probe_code::models::{BlameInfo, MatchCount, SearchResult};
 use probe_code::search::query::QueryPlan;
+use probe_code::search::result_diff::{DiffBlock, ResultDiff};
 use probe_code::search::search_tokens::sum_tokens_with_deduplication;
+use probe_code::search::{archives, notebooks};
 
 /// Create a cache of file contents for outline formatters to avoid redundant I/O
 pub fn create_file_content_cache(results: &[&SearchResult]) -> HashMap<PathBuf, Arc<String>> {
@@ -22,7 +26,10 @@ pub fn create_file_content_cache(results: &[&SearchResult]) -> HashMap<PathBuf,
 
     // Read each file once and cache the content
     for file_path in unique_files {
-        if let Ok(content) = std::fs::read_to_string(&file_path) {
+        // Archive members and notebook cells are read from where they were written out
+        let source = archives::unpacked_path(&file_path.to_string_lossy())
+            .or_else(|| notebooks::cell_path(&file_path.to_string_lossy()));
+        if let Ok(content) = std::fs::read_to_string(source.as_ref().unwrap_or(&file_path)) {
             cache.insert(file_path, Arc::new(content));
         }
     }
@@ -30,7 +37,10 @@ pub fn create_file_content_cache(results: &[&SearchResult]) -> HashMap<PathBuf,
     cache
 }
 
-/// Function to format and print search results according to the specified format
+/// Function to format and print search results according to the specified format.
+/// `group_by` (`dir`, `file` or `language`) organizes the terminal, markdown and plain
+/// formats into sections with per-group counts.
+#[allow(clippy::too_many_arguments)]
 pub fn format_and_print_search_results(
     results: &[SearchResult],
     dry_run: bool,
@@ -38,8 +48,12 @@ pub fn format_and_print_search_results(
     query_plan: Option<&QueryPlan>,
     skipped_files: Option<&[SearchResult]>,
     limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+    group_by: Option<&str>,
 ) {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Count valid results (with non-empty file names)
     let valid_results: Vec<&SearchResult> = results.iter().filter(|r| !r.file.is_empty()).collect();
@@ -56,13 +70,40 @@ pub fn format_and_print_search_results(
             format_and_print_color_results(&valid_results, dry_run, query_plan, debug_mode);
         }
         "json" => {
-            if let Err(e) = format_and_print_json_results(&valid_results, skipped_files, limits) {
+            if let Err(e) = format_and_print_json_results(
+                &valid_results,
+                skipped_files,
+                limits,
+                session_tokens,
+                total_ranked,
+                large_files,
+            ) {
                 eprintln!("Error formatting JSON: {e}");
             }
             return; // Skip the summary output at the end
         }
+        "ndjson" => {
+            if let Err(e) = format_and_print_ndjson_results(
+                &valid_results,
+                skipped_files,
+                limits,
+                session_tokens,
+                total_ranked,
+                large_files,
+            ) {
+                eprintln!("Error formatting NDJSON: {e}");
+            }
+            return; // The summary is the last line
+        }
         "xml" => {
-            if let Err(e) = format_and_print_xml_results(&valid_results, skipped_files, limits) {
+            if let Err(e) = format_and_print_xml_results(
+                &valid_results,
+                skipped_files,
+                limits,
+                session_tokens,
+                total_ranked,
+                large_files,
+            ) {
                 eprintln!("Error formatting XML: {e}");
             }
             return; // Skip the summary output at the end
@@ -87,142 +128,403 @@ pub fn format_and_print_search_results(
         }
         _ => {
             // Default format (terminal)
-            for result in &valid_results {
-                let file_path = Path::new(&result.file);
-                let extension = file_path
-                    .extension()
-                    .and_then(|ext| ext.to_str())
-                    .unwrap_or("");
-                let is_full_file = result.node_type == "file";
-
-                if dry_run {
-                    // In dry-run mode, only print file names and line numbers
-                    if is_full_file {
-                        println!("File: {}", result.file);
-                    } else {
-                        println!(
-                            "File: {}, Lines: {}-{}",
-                            result.file, result.lines.0, result.lines.1
-                        );
-                    }
+            let print_result = |result: &SearchResult| {
+                let display_order = if debug_mode {
+                    results
+                        .iter()
+                        .position(|r| r.file == result.file && r.lines == result.lines)
+                        .unwrap_or(0)
+                        + 1
                 } else {
-                    // Normal mode with full content or symbol display
-                    if is_full_file {
-                        println!("File: {}", result.file);
-                        println!("```{extension}");
-                        println!("{}", result.code);
-                        println!("```");
-                    } else {
-                        println!("File: {}", result.file);
+                    0
+                };
+                print_plain_result(
+                    result,
+                    dry_run,
+                    display_order,
+                    debug_mode,
+                    format == "terminal",
+                );
+            };
+            match group_by {
+                Some(key) => {
+                    for (index, (group, members)) in
+                        group_results(&valid_results, key).into_iter().enumerate()
+                    {
+                        if index > 0 {
+                            println!();
+                        }
                         println!(
-                            "Lines: {start}-{end}",
-                            start = result.lines.0,
-                            end = result.lines.1
+                            "## {}",
+                            text_with(
+                                Msg::ResultGroup,
+                                &[("group", &group), ("count", &members.len())]
+                            )
                         );
-                        println!("```{extension}");
-                        println!("{code}", code = result.code);
-                        println!("```");
+                        println!();
+                        for result in members {
+                            print_result(result);
+                        }
                     }
                 }
-                if debug_mode {
-                    if let Some(rank) = result.rank {
-                        // Add a display order field to show the actual ordering of results
-                        println!(
-                            "Display Order: {}",
-                            results
-                                .iter()
-                                .position(|r| r.file == result.file && r.lines == result.lines)
-                                .unwrap_or(0)
-                                + 1
-                        );
+                None => valid_results.iter().for_each(|result| print_result(result)),
+            }
+        }
+    }
 
-                        println!("Rank: {rank}");
+    print_results_summary(&valid_results);
+}
 
-                        if let Some(score) = result.score {
-                            println!("Combined Score: {score:.4}");
-                        }
+/// Prints search results as they arrive from `perform_probe_streaming`, for the formats
+/// that don't need the whole result set before their first line, then the same summary
+/// `format_and_print_search_results` ends with
+pub struct StreamingPrinter<'a> {
+    format: &'a str,
+    dry_run: bool,
+    debug_mode: bool,
+    printed: Vec<SearchResult>,
+    closed: bool,
+}
 
-                        // Display the combined score rank if available, otherwise calculate it
-                        if let Some(combined_rank) = result.combined_score_rank {
-                            println!("Combined Score Rank: {combined_rank}");
-                        } else {
-                            // Fall back to the old behavior if the field isn't set
-                            println!("Combined Score Rank: {rank}");
-                        }
+impl<'a> StreamingPrinter<'a> {
+    /// Whether results in `format` can be printed one at a time. Outline output groups
+    /// results by file, and JSON, XML and color output lead with the result count.
+    pub fn supports(format: &str) -> bool {
+        match format {
+            "terminal" | "markdown" | "plain" | "ndjson" => true,
+            "color" => !colored::control::SHOULD_COLORIZE.should_colorize(),
+            _ => false,
+        }
+    }
 
-                        if let Some(tfidf_score) = result.tfidf_score {
-                            println!("TF-IDF Score: {tfidf_score:.4}");
-                        }
+    pub fn new(format: &'a str, dry_run: bool) -> Self {
+        StreamingPrinter {
+            format,
+            dry_run,
+            debug_mode: tracing::enabled!(tracing::Level::DEBUG),
+            printed: Vec::new(),
+            closed: false,
+        }
+    }
 
-                        if let Some(tfidf_rank) = result.tfidf_rank {
-                            println!("TF-IDF Rank: {tfidf_rank}");
-                        }
+    pub fn print(&mut self, result: SearchResult) {
+        if result.file.is_empty() {
+            return;
+        }
+        if self.format == "ndjson" {
+            if !self.closed {
+                match ndjson_result_line(&result)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|line| Ok(write_ndjson_line(&line)?))
+                {
+                    Ok(open) => self.closed = !open,
+                    Err(e) => eprintln!("Error formatting NDJSON: {e}"),
+                }
+            }
+        } else {
+            let display_order = self.printed.len() + 1;
+            print_plain_result(
+                &result,
+                self.dry_run,
+                display_order,
+                self.debug_mode,
+                self.format == "terminal",
+            );
+        }
+        self.printed.push(result);
+    }
 
-                        if let Some(bm25_score) = result.bm25_score {
-                            // Check if this is actually a BERT score by looking at the rank field
-                            // When BERT reranking is used, both score and bm25_score are set to BERT score
-                            let is_bert_score =
-                                result.score == result.bm25_score && result.score.is_some();
-                            if is_bert_score {
-                                println!("BERT Score: {bm25_score:.4}");
-                            } else {
-                                println!("BM25 Score: {bm25_score:.4}");
-                            }
-                        }
+    /// The number of results printed so far
+    pub fn count(&self) -> usize {
+        self.printed.len()
+    }
 
-                        if let Some(bm25_rank) = result.bm25_rank {
-                            println!("BM25 Rank: {bm25_rank}");
-                        }
+    /// Print the summary that follows the results
+    pub fn finish(
+        self,
+        skipped_files: Option<&[SearchResult]>,
+        limits: Option<&probe_code::models::SearchLimits>,
+        session_tokens: Option<usize>,
+        total_ranked: Option<usize>,
+        large_files: &[(String, u64)],
+    ) {
+        let printed: Vec<&SearchResult> = self.printed.iter().collect();
+        if self.format != "ndjson" {
+            print_results_summary(&printed);
+        } else if !self.closed {
+            let summary = ndjson_summary_line(
+                &printed,
+                skipped_files,
+                limits,
+                session_tokens,
+                total_ranked,
+                large_files,
+            )
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(write_ndjson_line(&line)?));
+            if let Err(e) = summary {
+                eprintln!("Error formatting NDJSON: {e}");
+            }
+        }
+    }
+}
 
-                        // Display Hybrid 2 score and rank with more prominence
-                        if let Some(new_score) = result.new_score {
-                            println!("Hybrid 2 Score: {new_score:.4}");
-                        }
+/// Split results into `--group-by` sections by `dir`ectory, `file` or `language`. Sections
+/// are in the order of their first result, so the best match's section comes first.
+fn group_results<'a>(
+    results: &[&'a SearchResult],
+    key: &str,
+) -> Vec<(String, Vec<&'a SearchResult>)> {
+    let mut groups: Vec<(String, Vec<&SearchResult>)> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for &result in results {
+        let path = Path::new(&result.file);
+        let group = match key {
+            "dir" => path
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .filter(|dir| !dir.is_empty())
+                .unwrap_or_else(|| ".".to_string()),
+            "language" => match (code_language(file_extension(path)), file_extension(path)) {
+                ("", "") => "other".to_string(),
+                ("", extension) => extension.to_string(),
+                (language, _) => language.to_string(),
+            },
+            _ => result.file.clone(),
+        };
+        let position = *positions.entry(group.clone()).or_insert_with(|| {
+            groups.push((group, Vec::new()));
+            groups.len() - 1
+        });
+        groups[position].1.push(result);
+    }
+    groups
+}
 
-                        if let Some(hybrid2_rank) = result.hybrid2_rank {
-                            println!("Hybrid 2 Rank: {hybrid2_rank}");
-                        } else if result.new_score.is_some() {
-                            println!("Hybrid 2 Rank: N/A");
-                        }
+/// Patterns finding the matched keywords of a result in its code: a quoted keyword as
+/// written, any other regardless of case and through the rest of its word part, as
+/// keywords are stemmed (`balanc` marks `balance` of `ledger_balance`)
+fn keyword_patterns(keywords: &[String]) -> Vec<Regex> {
+    keywords
+        .iter()
+        .filter_map(|keyword| {
+            let pattern = match keyword.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+                Some(exact) => regex::escape(exact),
+                None => format!(r"(?i:{})[a-z]*", regex::escape(keyword)),
+            };
+            Regex::new(&pattern).ok()
+        })
+        .collect()
+}
 
-                        if let Some(file_unique_terms) = result.file_unique_terms {
-                            println!("File Unique Terms: {file_unique_terms}");
-                        }
+/// `line` with every span matched by `patterns` passed through `mark`. Overlapping
+/// matches are marked once, so one keyword never lands inside another's markup.
+fn highlight_line(line: &str, patterns: &[Regex], mark: impl Fn(&str) -> String) -> String {
+    let mut spans: Vec<(usize, usize)> = patterns
+        .iter()
+        .flat_map(|pattern| pattern.find_iter(line).map(|m| (m.start(), m.end())))
+        .filter(|(start, end)| start < end)
+        .collect();
+    if spans.is_empty() {
+        return line.to_string();
+    }
+    spans.sort();
+
+    let mut highlighted = String::with_capacity(line.len());
+    let mut last_end = 0;
+    let mut index = 0;
+    while index < spans.len() {
+        let (start, mut end) = spans[index];
+        index += 1;
+        while index < spans.len() && spans[index].0 <= end {
+            end = end.max(spans[index].1);
+            index += 1;
+        }
+        let start = start.max(last_end);
+        highlighted.push_str(&line[last_end..start]);
+        highlighted.push_str(&mark(&line[start..end]));
+        last_end = end;
+    }
+    highlighted.push_str(&line[last_end..]);
+    highlighted
+}
 
-                        if let Some(file_total_matches) = result.file_total_matches {
-                            println!("File Total Matches: {file_total_matches}");
-                        }
+/// The code of a result with its matched keywords in bold yellow, for the terminal format;
+/// unchanged when the output isn't colored
+fn highlighted_code(result: &SearchResult) -> String {
+    use colored::*;
+    let patterns = match &result.matched_keywords {
+        Some(keywords) if colored::control::SHOULD_COLORIZE.should_colorize() => {
+            keyword_patterns(keywords)
+        }
+        _ => return result.code.to_string(),
+    };
+    result
+        .code
+        .lines()
+        .map(|line| highlight_line(line, &patterns, |m| m.yellow().bold().to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-                        if let Some(file_match_rank) = result.file_match_rank {
-                            println!("File Match Rank: {file_match_rank}");
-                        }
+/// Print one result in the default (terminal) format. `display_order` is its 1-based
+/// position in the output, for the debug log. With `highlight`, the matched keywords of
+/// the code are colored.
+fn print_plain_result(
+    result: &SearchResult,
+    dry_run: bool,
+    display_order: usize,
+    debug_mode: bool,
+    highlight: bool,
+) {
+    let file_path = Path::new(&result.file);
+    let extension = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    let is_full_file = result.node_type == "file";
+
+    let marker = filename_match_marker(result);
+    let code = if highlight && !dry_run {
+        highlighted_code(result)
+    } else {
+        result.code.to_string()
+    };
+    if dry_run {
+        // In dry-run mode, only print file names and line numbers
+        if is_full_file {
+            println!("File: {}{marker}", result.file);
+        } else {
+            println!(
+                "File: {}{marker}, Lines: {}-{}",
+                result.file, result.lines.0, result.lines.1
+            );
+        }
+    } else {
+        // Normal mode with full content or symbol display
+        if is_full_file {
+            println!("File: {}{marker}", result.file);
+            println!("```{extension}");
+            println!("{code}");
+            println!("```");
+        } else {
+            println!("File: {}{marker}", result.file);
+            println!(
+                "Lines: {start}-{end}",
+                start = result.lines.0,
+                end = result.lines.1
+            );
+            println!("```{extension}");
+            println!("{code}");
+            println!("```");
+        }
+    }
+    if let Some(note) = duplicate_files_note(result) {
+        println!("{note}");
+    }
+    if let Some(note) = blame_note(result) {
+        println!("{note}");
+    }
+    if let Some(note) = owners_note(result) {
+        println!("{note}");
+    }
+    if debug_mode {
+        if let Some(rank) = result.rank {
+            // Add a display order field to show the actual ordering of results
+            debug!("Display Order: {display_order}");
 
-                        if let Some(block_unique_terms) = result.block_unique_terms {
-                            println!("Block Unique Terms: {block_unique_terms}");
-                        }
+            debug!("Rank: {rank}");
 
-                        if let Some(block_total_matches) = result.block_total_matches {
-                            println!("Block Total Matches: {block_total_matches}");
-                        }
+            if let Some(score) = result.score {
+                debug!("Combined Score: {score:.4}");
+            }
 
-                        println!("Type: {}", result.node_type);
-                    }
+            // Display the combined score rank if available, otherwise calculate it
+            if let Some(combined_rank) = result.combined_score_rank {
+                debug!("Combined Score Rank: {combined_rank}");
+            } else {
+                // Fall back to the old behavior if the field isn't set
+                debug!("Combined Score Rank: {rank}");
+            }
+
+            if let Some(tfidf_score) = result.tfidf_score {
+                debug!("TF-IDF Score: {tfidf_score:.4}");
+            }
+
+            if let Some(tfidf_rank) = result.tfidf_rank {
+                debug!("TF-IDF Rank: {tfidf_rank}");
+            }
+
+            if let Some(bm25_score) = result.bm25_score {
+                // Check if this is actually a BERT score by looking at the rank field
+                // When BERT reranking is used, both score and bm25_score are set to BERT score
+                let is_bert_score = result.score == result.bm25_score && result.score.is_some();
+                if is_bert_score {
+                    debug!("BERT Score: {bm25_score:.4}");
+                } else {
+                    debug!("BM25 Score: {bm25_score:.4}");
                 }
             }
+
+            if let Some(bm25_rank) = result.bm25_rank {
+                debug!("BM25 Rank: {bm25_rank}");
+            }
+
+            // Display Hybrid 2 score and rank with more prominence
+            if let Some(new_score) = result.new_score {
+                debug!("Hybrid 2 Score: {new_score:.4}");
+            }
+
+            if let Some(hybrid2_rank) = result.hybrid2_rank {
+                debug!("Hybrid 2 Rank: {hybrid2_rank}");
+            } else if result.new_score.is_some() {
+                debug!("Hybrid 2 Rank: N/A");
+            }
+
+            if let Some(file_unique_terms) = result.file_unique_terms {
+                debug!("File Unique Terms: {file_unique_terms}");
+            }
+
+            if let Some(file_total_matches) = result.file_total_matches {
+                debug!("File Total Matches: {file_total_matches}");
+            }
+
+            if let Some(file_match_rank) = result.file_match_rank {
+                debug!("File Match Rank: {file_match_rank}");
+            }
+
+            if let Some(block_unique_terms) = result.block_unique_terms {
+                debug!("Block Unique Terms: {block_unique_terms}");
+            }
+
+            if let Some(block_total_matches) = result.block_total_matches {
+                debug!("Block Total Matches: {block_total_matches}");
+            }
+
+            debug!("Type: {}", result.node_type);
         }
     }
+}
 
-    println!("Found {count} search results", count = valid_results.len());
+/// Print the result count, bytes and tokens that end the default output
+fn print_results_summary(results: &[&SearchResult]) {
+    println!(
+        "{}",
+        text_with(Msg::FoundSearchResults, &[("count", &results.len())])
+    );
 
-    let total_bytes: usize = valid_results.iter().map(|r| r.code.len()).sum();
+    let total_bytes: usize = results.iter().map(|r| r.code.len()).sum();
 
     // BATCH TOKENIZATION WITH DEDUPLICATION OPTIMIZATION:
     // Use batch processing with content deduplication for improved performance
     // when multiple identical code blocks need tokenization (common in search results)
-    let code_blocks: Vec<&str> = valid_results.iter().map(|r| r.code.as_str()).collect();
+    let code_blocks: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
     let total_tokens: usize = sum_tokens_with_deduplication(&code_blocks);
-    println!("Total bytes returned: {total_bytes}");
-    println!("Total tokens returned: {total_tokens}");
+    println!("{}", text_with(Msg::TotalBytes, &[("bytes", &total_bytes)]));
+    println!(
+        "{}",
+        text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+    );
 }
 
 /// Format and print search results with color highlighting for matching words
@@ -236,21 +538,16 @@ fn format_and_print_color_results(
     use regex::Regex;
 
     if results.is_empty() {
-        println!("No results found.");
-        println!();
-        println!("💡 Tips to improve your search:");
-        println!("  - Try synonyms or related terms (e.g., \"fetch\" instead of \"get\")");
-        println!("  - Use broader terms without AND operators");
-        println!("  - Check spelling of function/class names");
-        println!("  - Remove file type filters to search all files");
-        println!(
-            "  - Use exact:false (default) for stemming, or exact:true for precise symbol lookup"
-        );
+        println!("{}", text(Msg::NoResults));
+        print_search_tips();
         return;
     }
 
     // Print a header with the number of results
-    println!("{}", format!("Found {} results", results.len()).bold());
+    println!(
+        "{}",
+        text_with(Msg::FoundResults, &[("count", &results.len())]).bold()
+    );
     println!();
 
     // Print the results
@@ -273,15 +570,16 @@ fn format_and_print_color_results(
         );
 
         // Print the file path and node info with color
+        let marker = filename_match_marker(result).magenta();
         if is_full_file {
             println!(
-                "{label} {file}",
+                "{label} {file}{marker}",
                 label = "File:".bold().green().yellow(),
                 file = result.file
             );
         } else {
             println!(
-                "{} {} ({})",
+                "{} {} ({}){marker}",
                 "File:".bold().green(),
                 result.file.yellow(),
                 result.node_type.cyan()
@@ -293,18 +591,27 @@ fn format_and_print_color_results(
                 result.lines.1
             );
         }
+        if let Some(note) = duplicate_files_note(result) {
+            println!("{}", note.magenta());
+        }
+        if let Some(note) = blame_note(result) {
+            println!("{}", note.dimmed());
+        }
+        if let Some(note) = owners_note(result) {
+            println!("{}", note.dimmed());
+        }
 
         // Print additional debug information if in debug mode
         if debug_mode {
             // Print the same debug info that would be shown in standard mode
             if let Some(keywords) = &result.matched_keywords {
-                println!("{} {keywords:?}", "Matched Keywords:".bold().green());
+                debug!("{} {keywords:?}", "Matched Keywords:".bold().green());
             }
             if let Some(score) = result.score {
-                println!("{} {score:.4}", "Score:".bold().green());
+                debug!("{} {score:.4}", "Score:".bold().green());
             }
             if let Some(query_plan) = query_plan {
-                println!("{} {query_plan:?}", "Query Plan:".bold().green());
+                debug!("{} {query_plan:?}", "Query Plan:".bold().green());
             }
         }
 
@@ -314,37 +621,7 @@ fn format_and_print_color_results(
         }
 
         // Determine the language for syntax highlighting
-        let language = match extension {
-            "rs" => "rust",
-            "py" => "python",
-            "js" => "javascript",
-            "ts" => "typescript",
-            "go" => "go",
-            "c" | "h" => "c",
-            "cpp" | "cc" | "cxx" | "hpp" => "cpp",
-            "java" => "java",
-            "rb" => "ruby",
-            "php" => "php",
-            "sh" => "bash",
-            "md" => "markdown",
-            "json" => "json",
-            "yaml" | "yml" => "yaml",
-            "html" => "html",
-            "css" => "css",
-            "sql" => "sql",
-            "kt" | "kts" => "kotlin",
-            "swift" => "swift",
-            "scala" => "scala",
-            "dart" => "dart",
-            "ex" | "exs" => "elixir",
-            "hs" => "haskell",
-            "clj" => "clojure",
-            "lua" => "lua",
-            "r" => "r",
-            "pl" | "pm" => "perl",
-            "proto" => "protobuf",
-            _ => "",
-        };
+        let language = code_language(extension);
 
         println!("{label}", label = "Code:".bold().magenta());
 
@@ -457,7 +734,7 @@ fn format_and_print_color_results(
         if debug_mode {
             if let Some(rank) = result.rank {
                 // Add a display order field to show the actual ordering of results
-                println!(
+                debug!(
                     "Display Order: {}",
                     results
                         .iter()
@@ -466,26 +743,26 @@ fn format_and_print_color_results(
                         + 1
                 );
 
-                println!("Rank: {rank}");
+                debug!("Rank: {rank}");
 
                 if let Some(score) = result.score {
-                    println!("Combined Score: {score:.4}");
+                    debug!("Combined Score: {score:.4}");
                 }
 
                 // Display the combined score rank if available, otherwise calculate it
                 if let Some(combined_rank) = result.combined_score_rank {
-                    println!("Combined Score Rank: {combined_rank}");
+                    debug!("Combined Score Rank: {combined_rank}");
                 } else {
                     // Fall back to the old behavior if the field isn't set
-                    println!("Combined Score Rank: {rank}");
+                    debug!("Combined Score Rank: {rank}");
                 }
 
                 if let Some(tfidf_score) = result.tfidf_score {
-                    println!("TF-IDF Score: {tfidf_score:.4}");
+                    debug!("TF-IDF Score: {tfidf_score:.4}");
                 }
 
                 if let Some(tfidf_rank) = result.tfidf_rank {
-                    println!("TF-IDF Rank: {tfidf_rank}");
+                    debug!("TF-IDF Rank: {tfidf_rank}");
                 }
 
                 if let Some(bm25_score) = result.bm25_score {
@@ -493,60 +770,150 @@ fn format_and_print_color_results(
                     // When BERT reranking is used, both score and bm25_score are set to BERT score
                     let is_bert_score = result.score == result.bm25_score && result.score.is_some();
                     if is_bert_score {
-                        println!("BERT Score: {bm25_score:.4}");
+                        debug!("BERT Score: {bm25_score:.4}");
                     } else {
-                        println!("BM25 Score: {bm25_score:.4}");
+                        debug!("BM25 Score: {bm25_score:.4}");
                     }
                 }
 
                 if let Some(bm25_rank) = result.bm25_rank {
-                    println!("BM25 Rank: {bm25_rank}");
+                    debug!("BM25 Rank: {bm25_rank}");
                 }
 
                 // Display Hybrid 2 score and rank with more prominence
                 if let Some(new_score) = result.new_score {
-                    println!("Hybrid 2 Score: {new_score:.4}");
+                    debug!("Hybrid 2 Score: {new_score:.4}");
                 }
 
                 if let Some(hybrid2_rank) = result.hybrid2_rank {
-                    println!("Hybrid 2 Rank: {hybrid2_rank}");
+                    debug!("Hybrid 2 Rank: {hybrid2_rank}");
                 } else if result.new_score.is_some() {
-                    println!("Hybrid 2 Rank: N/A");
+                    debug!("Hybrid 2 Rank: N/A");
                 }
 
                 if let Some(file_unique_terms) = result.file_unique_terms {
-                    println!("File Unique Terms: {file_unique_terms}");
+                    debug!("File Unique Terms: {file_unique_terms}");
                 }
 
                 if let Some(file_total_matches) = result.file_total_matches {
-                    println!("File Total Matches: {file_total_matches}");
+                    debug!("File Total Matches: {file_total_matches}");
                 }
 
                 if let Some(file_match_rank) = result.file_match_rank {
-                    println!("File Match Rank: {file_match_rank}");
+                    debug!("File Match Rank: {file_match_rank}");
                 }
 
                 if let Some(block_unique_terms) = result.block_unique_terms {
-                    println!("Block Unique Terms: {block_unique_terms}");
+                    debug!("Block Unique Terms: {block_unique_terms}");
                 }
 
                 if let Some(block_total_matches) = result.block_total_matches {
-                    println!("Block Total Matches: {block_total_matches}");
+                    debug!("Block Total Matches: {block_total_matches}");
                 }
 
-                println!("Type: {}", result.node_type);
+                debug!("Type: {}", result.node_type);
             }
         }
     } // End of for loop
 
     println!();
-    println!("Found {} search results", results.len());
+    println!(
+        "{}",
+        text_with(Msg::FoundSearchResults, &[("count", &results.len())])
+    );
 
     let code_blocks: Vec<&str> = results.iter().map(|r| r.code.as_str()).collect();
     let total_tokens: usize = sum_tokens_with_deduplication(&code_blocks);
     let total_bytes: usize = results.iter().map(|r| r.code.len()).sum();
-    println!("Total bytes returned: {total_bytes}");
-    println!("Total tokens returned: {total_tokens}");
+    println!("{}", text_with(Msg::TotalBytes, &[("bytes", &total_bytes)]));
+    println!(
+        "{}",
+        text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+    );
+}
+
+/// The language of code with `extension`, as code fences name it; empty if unknown
+fn code_language(extension: &str) -> &'static str {
+    match extension {
+        "rs" => "rust",
+        "py" => "python",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "go" => "go",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" => "cpp",
+        "java" => "java",
+        "rb" => "ruby",
+        "php" => "php",
+        "sh" => "bash",
+        "md" => "markdown",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+        "html" => "html",
+        "css" => "css",
+        "sql" => "sql",
+        "kt" | "kts" => "kotlin",
+        "swift" => "swift",
+        "scala" => "scala",
+        "dart" => "dart",
+        "ex" | "exs" => "elixir",
+        "hs" => "haskell",
+        "clj" => "clojure",
+        "lua" => "lua",
+        "r" => "r",
+        "pl" | "pm" => "perl",
+        "proto" => "protobuf",
+        _ => "",
+    }
+}
+
+/// Print the tips for searches without results, unless tips are turned off
+pub fn print_search_tips() {
+    if !messages::tips_enabled() {
+        return;
+    }
+    println!();
+    println!("💡 {}", text(Msg::TipsHeader));
+    for tip in messages::SEARCH_TIPS {
+        println!("  - {}", text(*tip));
+    }
+}
+
+/// Marker appended to the file header of results found only by their filename
+fn filename_match_marker(result: &SearchResult) -> &'static str {
+    if result.matched_by_filename == Some(true) {
+        " [filename match]"
+    } else {
+        ""
+    }
+}
+
+/// "Also found in" line for blocks collapsed by `--dedupe-cross-file`
+fn duplicate_files_note(result: &SearchResult) -> Option<String> {
+    let files = result.duplicate_files.as_ref().filter(|f| !f.is_empty())?;
+    let noun = if files.len() == 1 { "file" } else { "files" };
+    Some(format!(
+        "Also found in {} other {noun}: {}",
+        files.len(),
+        files.join(", ")
+    ))
+}
+
+/// "Last changed by" line for results annotated by `--blame`
+pub fn blame_note(result: &SearchResult) -> Option<String> {
+    let blame = result.blame.as_ref()?;
+    Some(format!(
+        "Last changed by {} in {} on {}",
+        blame.author,
+        &blame.commit[..blame.commit.len().min(8)],
+        blame.date
+    ))
+}
+
+/// "Owners" line for results annotated by `--owners`
+pub fn owners_note(result: &SearchResult) -> Option<String> {
+    let owners = result.owners.as_ref()?;
+    Some(format!("Owners: {}", owners.join(" ")))
 }
 
 /// Helper function to escape XML special characters
@@ -558,47 +925,175 @@ fn escape_xml(s: &str) -> String {
         .replace("'", "&apos;")
 }
 
-/// Format and print search results in JSON format
-fn format_and_print_json_results(
-    results: &[&SearchResult],
-    skipped_files: Option<&[SearchResult]>,
-    limits: Option<&probe_code::models::SearchLimits>,
-) -> Result<()> {
-    // Create a simplified version of the results for JSON output
-    #[derive(serde::Serialize)]
-    struct JsonResult<'a> {
-        file: &'a str,
-        lines: [usize; 2],
-        node_type: &'a str,
-        code: &'a str,
-        // Symbol signature (when symbols flag is used)
-        symbol_signature: Option<&'a String>,
-        // Include other relevant fields
-        matched_keywords: Option<&'a Vec<String>>,
-        score: Option<f64>,
-        tfidf_score: Option<f64>,
-        bm25_score: Option<f64>,
-        file_unique_terms: Option<usize>,
-        file_total_matches: Option<usize>,
-        block_unique_terms: Option<usize>,
-        block_total_matches: Option<usize>,
+/// Print the `--count-only` summary of a search in the given output format
+pub fn print_match_count(count: &MatchCount, format: &str) {
+    match format {
+        "json" | "ndjson" => println!(
+            "{}",
+            serde_json::json!({
+                "files": count.files,
+                "estimated_blocks": count.blocks,
+                "matched_lines": count.lines,
+            })
+        ),
+        "xml" => {
+            println!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+            println!("<probe_count>");
+            println!("  <files>{}</files>", count.files);
+            println!("  <estimated_blocks>{}</estimated_blocks>", count.blocks);
+            println!("  <matched_lines>{}</matched_lines>", count.lines);
+            println!("</probe_count>");
+        }
+        _ => println!(
+            "{} files, ~{} blocks, {} matched lines",
+            count.files, count.blocks, count.lines
+        ),
     }
+}
 
-    #[derive(serde::Serialize)]
-    struct SkippedFileInfo {
-        file: String,
-        uniq: usize,
-        all: usize,
+/// Print how the results differ from a saved result set (`--compare-to`)
+pub fn print_result_diff(diff: &ResultDiff, saved_path: &Path, format: &str) {
+    let location =
+        |block: &DiffBlock| format!("{}:{}-{}", block.file, block.lines.0, block.lines.1);
+    match format {
+        "json" | "ndjson" => {
+            let block_json = |block: &DiffBlock| {
+                serde_json::json!({
+                    "file": block.file,
+                    "lines": [block.lines.0, block.lines.1],
+                    "node_type": block.node_type,
+                })
+            };
+            let moved: Vec<_> = diff
+                .moved
+                .iter()
+                .map(|moved| {
+                    let mut json = block_json(&moved.to);
+                    json["previous_file"] = serde_json::json!(moved.from.file);
+                    json["previous_lines"] =
+                        serde_json::json!([moved.from.lines.0, moved.from.lines.1]);
+                    json
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::json!({
+                    "compared_to": saved_path.display().to_string(),
+                    "new": diff.new.iter().map(block_json).collect::<Vec<_>>(),
+                    "removed": diff.removed.iter().map(block_json).collect::<Vec<_>>(),
+                    "moved": moved,
+                    "unchanged": diff.unchanged,
+                })
+            );
+        }
+        "xml" => {
+            let block_xml = |tag: &str, block: &DiffBlock, previous: Option<&DiffBlock>| {
+                let previous = previous
+                    .map(|from| format!(" previous=\"{}\"", escape_xml(&location(from))))
+                    .unwrap_or_default();
+                println!(
+                    "  <{tag} file=\"{}\" lines=\"{}-{}\" node_type=\"{}\"{previous}/>",
+                    escape_xml(&block.file),
+                    block.lines.0,
+                    block.lines.1,
+                    escape_xml(&block.node_type)
+                );
+            };
+            println!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+            println!(
+                "<probe_diff compared_to=\"{}\" unchanged=\"{}\">",
+                escape_xml(&saved_path.display().to_string()),
+                diff.unchanged
+            );
+            for block in &diff.new {
+                block_xml("new", block, None);
+            }
+            for block in &diff.removed {
+                block_xml("removed", block, None);
+            }
+            for moved in &diff.moved {
+                block_xml("moved", &moved.to, Some(&moved.from));
+            }
+            println!("</probe_diff>");
+        }
+        _ => {
+            use colored::*;
+            println!(
+                "{}",
+                format!(
+                    "Compared to {}: {} new, {} removed, {} moved, {} unchanged",
+                    saved_path.display(),
+                    diff.new.len(),
+                    diff.removed.len(),
+                    diff.moved.len(),
+                    diff.unchanged
+                )
+                .bold()
+            );
+            for block in &diff.new {
+                let line = format!("+ {} ({})", location(block), block.node_type);
+                println!("{}", line.green());
+            }
+            for block in &diff.removed {
+                let line = format!("- {} ({})", location(block), block.node_type);
+                println!("{}", line.red());
+            }
+            for moved in &diff.moved {
+                let line = format!(
+                    "~ {} ({}), was {}",
+                    location(&moved.to),
+                    moved.to.node_type,
+                    location(&moved.from)
+                );
+                println!("{}", line.yellow());
+            }
+        }
     }
+}
 
-    let json_results: Vec<JsonResult> = results
-        .iter()
-        .map(|r| JsonResult {
+/// A search result as printed by `--format json` and `--format ndjson`
+#[derive(serde::Serialize)]
+struct JsonResult<'a> {
+    file: &'a str,
+    lines: [usize; 2],
+    node_type: &'a str,
+    code: &'a str,
+    // Symbol signature (when symbols flag is used)
+    symbol_signature: Option<&'a String>,
+    // Whether the result was found only by its filename
+    matched_by_filename: bool,
+    // Other files with an identical copy of the block (--dedupe-cross-file)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicate_files: Option<&'a Vec<String>>,
+    // Last commit to change the block (--blame)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blame: Option<&'a BlameInfo>,
+    // Owners of the file from CODEOWNERS (--owners)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owners: Option<&'a Vec<String>>,
+    // Include other relevant fields
+    matched_keywords: Option<&'a Vec<String>>,
+    score: Option<f64>,
+    tfidf_score: Option<f64>,
+    bm25_score: Option<f64>,
+    file_unique_terms: Option<usize>,
+    file_total_matches: Option<usize>,
+    block_unique_terms: Option<usize>,
+    block_total_matches: Option<usize>,
+}
+
+impl<'a> JsonResult<'a> {
+    fn new(r: &'a SearchResult) -> Self {
+        JsonResult {
             file: &r.file,
             lines: [r.lines.0, r.lines.1],
             node_type: &r.node_type,
             code: &r.code,
             symbol_signature: r.symbol_signature.as_ref(),
+            matched_by_filename: r.matched_by_filename == Some(true),
+            duplicate_files: r.duplicate_files.as_ref(),
+            blame: r.blame.as_ref(),
+            owners: r.owners.as_ref(),
             matched_keywords: r.matched_keywords.as_ref(),
             score: r.score,
             tfidf_score: r.tfidf_score,
@@ -607,8 +1102,177 @@ fn format_and_print_json_results(
             file_total_matches: r.file_total_matches,
             block_unique_terms: r.block_unique_terms,
             block_total_matches: r.block_total_matches,
-        })
-        .collect();
+        }
+    }
+}
+
+/// Format and print search results in JSON format
+fn format_and_print_json_results(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> Result<()> {
+    println!(
+        "{json}",
+        json = format_json_search_results(
+            results,
+            skipped_files,
+            limits,
+            session_tokens,
+            total_ranked,
+            large_files
+        )?
+    );
+    Ok(())
+}
+
+/// Format search results as the JSON document printed by `--format json`
+pub fn format_json_search_results(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&json_search_document(
+        results,
+        skipped_files,
+        limits,
+        session_tokens,
+        total_ranked,
+        large_files,
+    ))?)
+}
+
+/// The JSON document of `--format json`, for embedding in a larger one
+pub fn json_search_document(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> serde_json::Value {
+    let json_results: Vec<JsonResult> = results.iter().map(|r| JsonResult::new(r)).collect();
+    let mut wrapper = json_search_summary(
+        results,
+        skipped_files,
+        limits,
+        session_tokens,
+        total_ranked,
+        large_files,
+    );
+    wrapper["results"] = serde_json::json!(json_results);
+    wrapper
+}
+
+/// Print search results as JSON lines (`--format ndjson`): a `"type": "result"` object
+/// per result, each written out as soon as it's formatted so a consumer can start on
+/// the first results of a large search, then a `"type": "summary"` object with the
+/// summary, limits and skipped files of `--format json`
+fn format_and_print_ndjson_results(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> Result<()> {
+    for result in results {
+        if !write_ndjson_line(&ndjson_result_line(result)?)? {
+            return Ok(());
+        }
+    }
+
+    let summary = ndjson_summary_line(
+        results,
+        skipped_files,
+        limits,
+        session_tokens,
+        total_ranked,
+        large_files,
+    )?;
+    write_ndjson_line(&summary)?;
+    Ok(())
+}
+
+fn ndjson_result_line(result: &SearchResult) -> serde_json::Result<Vec<u8>> {
+    #[derive(serde::Serialize)]
+    struct ResultLine<'a> {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        #[serde(flatten)]
+        result: JsonResult<'a>,
+    }
+
+    serde_json::to_vec(&ResultLine {
+        kind: "result",
+        result: JsonResult::new(result),
+    })
+}
+
+fn ndjson_summary_line(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> serde_json::Result<Vec<u8>> {
+    let mut summary = json_search_summary(
+        results,
+        skipped_files,
+        limits,
+        session_tokens,
+        total_ranked,
+        large_files,
+    );
+    summary["type"] = serde_json::json!("summary");
+    serde_json::to_vec(&summary)
+}
+
+/// Write and flush one JSON line, returning false once the reader has seen enough, as
+/// with `| head`
+fn write_ndjson_line(line: &[u8]) -> std::io::Result<bool> {
+    use std::io::Write;
+
+    let mut stdout = std::io::stdout().lock();
+    let written = stdout
+        .write_all(line)
+        .and_then(|()| stdout.write_all(b"\n"))
+        .and_then(|()| stdout.flush());
+    match written {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Everything in the `--format json` document but the results: summary, limits,
+/// skipped files and version
+fn json_search_summary(
+    results: &[&SearchResult],
+    skipped_files: Option<&[SearchResult]>,
+    limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
+) -> serde_json::Value {
+    #[derive(serde::Serialize)]
+    struct SkippedFileInfo {
+        file: String,
+        uniq: usize,
+        all: usize,
+        // Why a file was skipped without being searched ("max_filesize"), and its size
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        bytes: Option<u64>,
+    }
 
     // BATCH TOKENIZATION WITH DEDUPLICATION OPTIMIZATION for JSON output:
     // Process all code blocks in batch to leverage content deduplication
@@ -641,6 +1305,8 @@ fn format_and_print_json_results(
                 file,
                 uniq: unique.len(),
                 all: total,
+                reason: None,
+                bytes: None,
             })
             .collect();
 
@@ -648,10 +1314,20 @@ fn format_and_print_json_results(
 
         list
     });
+    let mut skipped_file_list = skipped_file_list;
+    if !large_files.is_empty() {
+        let large = large_files.iter().map(|(file, size)| SkippedFileInfo {
+            file: file.clone(),
+            uniq: 0,
+            all: 0,
+            reason: Some("max_filesize"),
+            bytes: Some(*size),
+        });
+        skipped_file_list.get_or_insert_with(Vec::new).extend(large);
+    }
 
-    // Create a wrapper object with results and summary
+    // Create a wrapper object with the summary
     let mut wrapper = serde_json::json!({
-        "results": json_results,
         "summary": {
             "count": results.len(),
             "total_bytes": results.iter().map(|r| r.code.len()).sum::<usize>(),
@@ -660,12 +1336,26 @@ fn format_and_print_json_results(
         "version": probe_code::version::get_version()
     });
 
+    if let Some(session_tokens) = session_tokens {
+        wrapper["summary"]["session_tokens"] = serde_json::json!(session_tokens);
+    }
+    if let Some(total_ranked) = total_ranked {
+        wrapper["summary"]["total_ranked"] = serde_json::json!(total_ranked);
+    }
+    if !large_files.is_empty() {
+        wrapper["summary"]["skipped_large_files"] = serde_json::json!(large_files.len());
+    }
+    if probe_code::interrupt::is_interrupted() {
+        wrapper["summary"]["interrupted"] = serde_json::json!(true);
+    }
+
     // Add limits if provided
     if let Some(limits_data) = limits {
         wrapper["limits"] = serde_json::json!({
             "max_results": limits_data.max_results,
             "max_bytes": limits_data.max_bytes,
             "max_tokens": limits_data.max_tokens,
+            "max_files": limits_data.max_files,
             "total_bytes": limits_data.total_bytes,
             "total_tokens": limits_data.total_tokens,
         });
@@ -678,8 +1368,7 @@ fn format_and_print_json_results(
         }
     }
 
-    println!("{json}", json = serde_json::to_string_pretty(&wrapper)?);
-    Ok(())
+    wrapper
 }
 
 /// Format and print search results in XML format
@@ -687,6 +1376,9 @@ fn format_and_print_xml_results(
     results: &[&SearchResult],
     skipped_files: Option<&[SearchResult]>,
     limits: Option<&probe_code::models::SearchLimits>,
+    session_tokens: Option<usize>,
+    total_ranked: Option<usize>,
+    large_files: &[(String, u64)],
 ) -> Result<()> {
     println!("<probe_results>");
 
@@ -704,6 +1396,33 @@ fn format_and_print_xml_results(
             println!("    <symbol_signature>{symbol_signature}</symbol_signature>");
         }
 
+        if result.matched_by_filename == Some(true) {
+            println!("    <matched_by_filename>true</matched_by_filename>");
+        }
+
+        if let Some(files) = &result.duplicate_files {
+            println!("    <duplicate_files>");
+            for file in files {
+                println!("      <file>{}</file>", escape_xml(file));
+            }
+            println!("    </duplicate_files>");
+        }
+
+        if let Some(blame) = &result.blame {
+            println!("    <blame>");
+            println!("      <author>{}</author>", escape_xml(&blame.author));
+            println!("      <commit>{}</commit>", blame.commit);
+            println!("      <date>{}</date>", blame.date);
+            println!("    </blame>");
+        }
+        if let Some(owners) = &result.owners {
+            println!("    <owners>");
+            for owner in owners {
+                println!("      <owner>{}</owner>", escape_xml(owner));
+            }
+            println!("    </owners>");
+        }
+
         if let Some(keywords) = &result.matched_keywords {
             println!("    <matched_keywords>");
             for keyword in keywords {
@@ -757,6 +1476,21 @@ fn format_and_print_xml_results(
     let total_tokens = sum_tokens_with_deduplication(&code_blocks);
 
     println!("    <total_tokens>{total_tokens}</total_tokens>");
+    if let Some(session_tokens) = session_tokens {
+        println!("    <session_tokens>{session_tokens}</session_tokens>");
+    }
+    if let Some(total_ranked) = total_ranked {
+        println!("    <total_ranked>{total_ranked}</total_ranked>");
+    }
+    if !large_files.is_empty() {
+        println!(
+            "    <skipped_large_files>{}</skipped_large_files>",
+            large_files.len()
+        );
+    }
+    if probe_code::interrupt::is_interrupted() {
+        println!("    <interrupted>true</interrupted>");
+    }
     println!("  </summary>");
 
     // Add limits if provided
@@ -771,6 +1505,9 @@ fn format_and_print_xml_results(
         if let Some(max_tokens) = limits_data.max_tokens {
             println!("    <max_tokens>{max_tokens}</max_tokens>");
         }
+        if let Some(max_files) = limits_data.max_files {
+            println!("    <max_files>{max_files}</max_files>");
+        }
         println!("    <total_bytes>{}</total_bytes>", limits_data.total_bytes);
         println!(
             "    <total_tokens>{}</total_tokens>",
@@ -818,6 +1555,19 @@ fn format_and_print_xml_results(
             println!("  </skipped_files>");
         }
     }
+    if !large_files.is_empty() {
+        println!(
+            "  <skipped_files reason=\"max_filesize\" count=\"{}\">",
+            large_files.len()
+        );
+        for (file, size) in large_files {
+            println!("    <file>");
+            println!("      <path>{}</path>", escape_xml(file));
+            println!("      <bytes>{size}</bytes>");
+            println!("    </file>");
+        }
+        println!("  </skipped_files>");
+    }
 
     println!(
         "  <version>{}</version>",
@@ -830,6 +1580,7 @@ fn format_and_print_xml_results(
 
 use crate::language::factory::get_language_impl;
 use crate::language::language_trait::LanguageImpl;
+use crate::language::rust::RustLanguage;
 use crate::language::tree_cache::get_or_parse_tree_pooled;
 use tree_sitter::Node;
 
@@ -983,11 +1734,8 @@ pub fn collect_parent_context_for_line(
 
             // Check if this is a doc comment (/// or /**)
             if line_content.starts_with("///") || line_content.starts_with("/**") {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    eprintln!(
-                        "DEBUG: Found doc comment at line {}: {}",
-                        line_num, line_content
-                    );
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!("Found doc comment at line {}: {}", line_num, line_content);
                 }
                 // Look for the next non-comment, non-attribute line to find the function
                 for next_line in line_num + 1..=(line_num + 10).min(source_lines.len()) {
@@ -998,19 +1746,12 @@ pub fn collect_parent_context_for_line(
                         && !next_content.is_empty()
                     {
                         // This might be the function - find its node
-                        if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                            eprintln!(
-                                "DEBUG: Looking at next line {}: {}",
-                                next_line, next_content
-                            );
+                        if tracing::enabled!(tracing::Level::DEBUG) {
+                            debug!("Looking at next line {}: {}", next_line, next_content);
                         }
                         if let Some(func_node) = find_node_at_line(&root_node, next_line) {
-                            if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                                eprintln!(
-                                    "DEBUG: Found node at line {}: {}",
-                                    next_line,
-                                    func_node.kind()
-                                );
+                            if tracing::enabled!(tracing::Level::DEBUG) {
+                                debug!("Found node at line {}: {}", next_line, func_node.kind());
                             }
                             // Look for function in the ancestry
                             let mut current = func_node;
@@ -1018,15 +1759,15 @@ pub fn collect_parent_context_for_line(
                                 if matches!(current.kind(), "function_item" | "function_definition")
                                 {
                                     target_node = Some(current);
-                                    if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                                        eprintln!(
-                                            "DEBUG: Found function node: {} at lines {}-{}",
+                                    if tracing::enabled!(tracing::Level::DEBUG) {
+                                        debug!(
+                                            "Found function node: {} at lines {}-{}",
                                             current.kind(),
                                             current.start_position().row + 1,
                                             current.end_position().row + 1
                                         );
-                                        eprintln!(
-                                            "DEBUG: Updated target_node for doc comment on line {}",
+                                        debug!(
+                                            "Updated target_node for doc comment on line {}",
                                             line_num
                                         );
                                     }
@@ -1051,9 +1792,9 @@ pub fn collect_parent_context_for_line(
     }
 
     if let Some(target_node) = target_node {
-        if std::env::var("DEBUG").unwrap_or_default() == "1" {
-            eprintln!(
-                "DEBUG: Processing contexts for target_node: {} at lines {}-{}",
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            debug!(
+                "Processing contexts for target_node: {} at lines {}-{}",
                 target_node.kind(),
                 target_node.start_position().row + 1,
                 target_node.end_position().row + 1
@@ -1136,9 +1877,9 @@ pub fn collect_parent_context_for_line(
                 preceding_comments: Vec::new(),
             };
             contexts.push(context);
-            if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                eprintln!(
-                    "DEBUG: Added target node as context: {} at lines {}-{}",
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                debug!(
+                    "Added target node as context: {} at lines {}-{}",
                     target_node.kind(),
                     start_line,
                     end_line
@@ -1340,13 +2081,13 @@ pub fn collect_outline_lines(
     };
 
     // Debug: Check if we have matched lines
-    if std::env::var("DEBUG").unwrap_or_default() == "1" {
-        eprintln!(
-            "DEBUG: collect_outline_lines for result at lines {}-{}",
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        debug!(
+            "collect_outline_lines for result at lines {}-{}",
             result.lines.0, result.lines.1
         );
-        eprintln!("DEBUG: matched_lines field = {:?}", result.matched_lines);
-        eprintln!("DEBUG: matched_keywords = {:?}", result.matched_keywords);
+        debug!("matched_lines field = {:?}", result.matched_lines);
+        debug!("matched_keywords = {:?}", result.matched_keywords);
     }
 
     // Collect matched lines - if we don't have specific matched_lines, find them in the result
@@ -1403,14 +2144,14 @@ pub fn collect_outline_lines(
         let mut all_contexts = Vec::new();
         for &line_num in &matched_lines {
             let contexts = collect_parent_context_for_line(file_path, line_num, full_source);
-            if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                eprintln!(
-                    "DEBUG: Parent contexts for line {}: {} contexts found",
+            if tracing::enabled!(tracing::Level::DEBUG) {
+                debug!(
+                    "Parent contexts for line {}: {} contexts found",
                     line_num,
                     contexts.len()
                 );
                 for ctx in &contexts {
-                    eprintln!("  - {} at line {}", ctx.node_type, ctx.start_line);
+                    debug!("  - {} at line {}", ctx.node_type, ctx.start_line);
                 }
             }
             all_contexts.push((line_num, contexts));
@@ -1486,6 +2227,35 @@ pub fn collect_outline_lines(
             }
         }
 
+        // Functions declared inside a Rust macro body (`cfg_if!` and the like) aren't nodes
+        // of the tree: add the attributes and signature of the one holding each match. The
+        // items shown as nested contexts get their attributes, as `#[cfg_attr(...)]`.
+        if file_extension(Path::new(file_path)) == "rs" {
+            if let Ok(tree) = get_or_parse_tree_pooled(file_path, full_source, "rs") {
+                let root = tree.root_node();
+                for &line_num in &matched_lines {
+                    if let Some((start, end)) = RustLanguage::macro_fn_signature_lines(
+                        &root,
+                        full_source.as_bytes(),
+                        line_num,
+                    ) {
+                        for line in start..=end.min(line_num) {
+                            lines.push((line, OutlineLineType::FunctionSignature));
+                        }
+                    }
+                }
+                for &line_num in &all_nested_contexts {
+                    if let Some(start) =
+                        RustLanguage::attribute_start_line(&root, full_source.as_bytes(), line_num)
+                    {
+                        for line in start..line_num {
+                            lines.push((line, OutlineLineType::NestedContext));
+                        }
+                    }
+                }
+            }
+        }
+
         // Add the actual matched lines
         for &line_num in &matched_lines {
             lines.push((line_num, OutlineLineType::MatchedLine));
@@ -1690,6 +2460,7 @@ pub fn render_outline_lines(
         None => return,
     };
     let source_lines: Vec<&str> = source.lines().collect();
+    let patterns = keyword_patterns(keywords.as_deref().unwrap_or_default());
 
     // Get the last displayed line for this file, defaulting to 0 if first time
     let mut last_displayed = *last_displayed_per_file.get(file_path).unwrap_or(&0);
@@ -1749,23 +2520,10 @@ pub fn render_outline_lines(
 
             // Apply keyword highlighting for all line types (not just matched lines)
             // This ensures function signatures and other contexts with keywords are highlighted
-            if let Some(keywords) = keywords {
-                for keyword in keywords {
-                    let pattern = if keyword.starts_with('"') && keyword.ends_with('"') {
-                        regex::escape(&keyword[1..keyword.len() - 1])
-                    } else {
-                        format!(r"(?i){}", regex::escape(keyword))
-                    };
-                    if let Ok(re) = Regex::new(&format!("({})", pattern)) {
-                        line_content = re
-                            .replace_all(&line_content, |caps: &regex::Captures| {
-                                use colored::*;
-                                caps[1].bright_yellow().bold().to_string()
-                            })
-                            .to_string();
-                    }
-                }
-            }
+            line_content = highlight_line(&line_content, &patterns, |m| {
+                use colored::*;
+                m.bright_yellow().bold().to_string()
+            });
 
             // Add smart comment for closing braces (only for blocks >20 lines with gaps shown)
             if line_type == OutlineLineType::ClosingBrace
@@ -2320,8 +3078,38 @@ fn format_and_print_outline_results(
         }
 
         // File header (only once per file)
+        let marker = file_results
+            .first()
+            .map_or("", |result| filename_match_marker(result));
         println!("{}", "---".dimmed());
-        println!("{} {}", "File:".dimmed(), file_path.bold());
+        println!(
+            "{} {}{}",
+            "File:".dimmed(),
+            file_path.bold(),
+            marker.magenta()
+        );
+        for result in file_results {
+            if let Some(note) = duplicate_files_note(result) {
+                if file_results.len() > 1 {
+                    let (start, end) = result.lines;
+                    println!("{}", format!("Lines {start}-{end}: {note}").magenta());
+                } else {
+                    println!("{}", note.magenta());
+                }
+            }
+            if let Some(note) = blame_note(result) {
+                if file_results.len() > 1 {
+                    let (start, end) = result.lines;
+                    println!("{}", format!("Lines {start}-{end}: {note}").dimmed());
+                } else {
+                    println!("{}", note.dimmed());
+                }
+            }
+        }
+        // Owners belong to the file, so they are the same for all of its results
+        if let Some(note) = file_results.first().and_then(|result| owners_note(result)) {
+            println!("{}", note.dimmed());
+        }
         println!();
 
         // Track lines for this entire file
@@ -2423,15 +3211,21 @@ fn format_and_print_outline_results(
 
     // Print summary at the end
     println!();
-    println!("Found {} search results", results.len());
+    println!(
+        "{}",
+        text_with(Msg::FoundSearchResults, &[("count", &results.len())])
+    );
 
     // Calculate total bytes and tokens from displayed content
     let total_bytes: usize = displayed_content.iter().map(|s| s.len()).sum();
     let code_blocks: Vec<&str> = displayed_content.iter().map(|s| s.as_str()).collect();
     let total_tokens: usize = sum_tokens_with_deduplication(&code_blocks);
 
-    println!("Total bytes returned: {total_bytes}");
-    println!("Total tokens returned: {total_tokens}");
+    println!("{}", text_with(Msg::TotalBytes, &[("bytes", &total_bytes)]));
+    println!(
+        "{}",
+        text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+    );
 }
 
 /// Format and print search results in outline XML format
@@ -2539,11 +3333,24 @@ fn format_and_print_outline_xml_results(
         // Sort deduped lines by line number for final output
         deduped_lines.sort_by_key(|(line, _)| *line);
 
+        // The keywords matched anywhere in this file, marked on every line shown
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in file_results
+            .iter()
+            .filter_map(|result| result.matched_keywords.as_ref())
+            .flatten()
+        {
+            if !keywords.contains(keyword) {
+                keywords.push(keyword.clone());
+            }
+        }
+
         // Generate the XML content for this file
         let xml_content = generate_outline_xml_content(
             &deduped_lines,
             file_path,
             &all_closing_brace_contexts,
+            &keywords,
             dry_run,
             &mut displayed_content,
             file_cache,
@@ -2552,7 +3359,14 @@ fn format_and_print_outline_xml_results(
         // Print the file element with content (no XML escaping for simpler output)
         // Add empty lines for better readability
         println!();
-        println!("<file path=\"{}\">", file_path);
+        if file_results
+            .first()
+            .is_some_and(|result| result.matched_by_filename == Some(true))
+        {
+            println!("<file path=\"{}\" matched_by=\"filename\">", file_path);
+        } else {
+            println!("<file path=\"{}\">", file_path);
+        }
         println!();
         print!("{}", xml_content);
         println!();
@@ -2567,8 +3381,11 @@ fn format_and_print_outline_xml_results(
         let code_blocks: Vec<&str> = displayed_content.iter().map(|s| s.as_str()).collect();
         let total_tokens: usize = sum_tokens_with_deduplication(&code_blocks);
 
-        eprintln!("Total bytes returned: {total_bytes}");
-        eprintln!("Total tokens returned: {total_tokens}");
+        eprintln!("{}", text_with(Msg::TotalBytes, &[("bytes", &total_bytes)]));
+        eprintln!(
+            "{}",
+            text_with(Msg::TotalTokens, &[("tokens", &total_tokens)])
+        );
     }
 
     // Add limits if provided
@@ -2583,6 +3400,9 @@ fn format_and_print_outline_xml_results(
         if let Some(max_tokens) = limits_data.max_tokens {
             println!("  <max_tokens>{max_tokens}</max_tokens>");
         }
+        if let Some(max_files) = limits_data.max_files {
+            println!("  <max_files>{max_files}</max_files>");
+        }
         println!("  <total_bytes>{}</total_bytes>", limits_data.total_bytes);
         println!(
             "  <total_tokens>{}</total_tokens>",
@@ -2634,11 +3454,13 @@ fn format_and_print_outline_xml_results(
     Ok(())
 }
 
-/// Generate XML content for a file by reading source lines and formatting them
+/// Generate XML content for a file by reading source lines and formatting them, with
+/// the matched `keywords` wrapped in `<em>` markers
 fn generate_outline_xml_content(
     lines: &[(usize, OutlineLineType)],
     file_path: &str,
     _closing_brace_contexts: &std::collections::HashMap<usize, crate::models::ParentContext>,
+    keywords: &[String],
     dry_run: bool,
     displayed_content: &mut Vec<String>,
     file_cache: &HashMap<PathBuf, Arc<String>>,
@@ -2654,6 +3476,7 @@ fn generate_outline_xml_content(
         None => return String::new(),
     };
     let source_lines: Vec<&str> = source.lines().collect();
+    let patterns = keyword_patterns(keywords);
 
     let mut result = String::new();
     let mut last_line = 0;
@@ -2671,7 +3494,8 @@ fn generate_outline_xml_content(
                 result.push_str(&format!("{}", line_num));
             } else {
                 // Add line number and actual line content (no XML escaping for simpler output)
-                result.push_str(&format!("{:4} {}", line_num, line_content));
+                let marked = highlight_line(line_content, &patterns, |m| format!("<em>{m}</em>"));
+                result.push_str(&format!("{:4} {}", line_num, marked));
                 displayed_content.push(line_content.to_string());
             }
         }
@@ -2691,6 +3515,38 @@ fn generate_outline_xml_content(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_highlight_line() {
+        let em = |m: &str| format!("<em>{m}</em>");
+        let patterns = keyword_patterns(&["config".to_string(), "load".to_string()]);
+        assert_eq!(
+            highlight_line("fn load_Config(path: &str) -> Config {", &patterns, em),
+            "fn <em>load</em>_<em>Config</em>(path: &str) -> <em>Config</em> {"
+        );
+
+        // A stem marks the rest of its word part
+        let patterns = keyword_patterns(&["balanc".to_string()]);
+        assert_eq!(
+            highlight_line("ledger_balance + BalancedTree", &patterns, em),
+            "ledger_<em>balance</em> + <em>Balanced</em>Tree"
+        );
+
+        // Overlapping matches are marked once
+        let patterns = keyword_patterns(&["parse".to_string(), "parser".to_string()]);
+        assert_eq!(
+            highlight_line("let parser = 1;", &patterns, em),
+            "let <em>parser</em> = 1;"
+        );
+
+        // A quoted keyword matches only as written
+        let patterns = keyword_patterns(&["\"Token\"".to_string()]);
+        assert_eq!(
+            highlight_line("Token token", &patterns, em),
+            "<em>Token</em> token"
+        );
+        assert_eq!(highlight_line("no match", &patterns, em), "no match");
+    }
+
     #[test]
     fn test_get_comment_prefix() {
         // Test C-style comment languages
@@ -2945,9 +3801,13 @@ mod tests {
             file: temp_file1.path().to_string_lossy().to_string(),
             lines: (1, 3),
             node_type: "function".to_string(),
-            code: "fn test() {\n    println!(\"Hello\");\n}".to_string(),
+            code: "fn test() {\n    println!(\"Hello\");\n}".into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
@@ -2974,9 +3834,13 @@ mod tests {
             file: temp_file2.path().to_string_lossy().to_string(),
             lines: (1, 3),
             node_type: "class".to_string(),
-            code: "class Test {\n    void run() {}\n}".to_string(),
+            code: "class Test {\n    void run() {}\n}".into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank: None,
             score: None,
             tfidf_score: None,
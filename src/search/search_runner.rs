@@ -1,13 +1,20 @@
 use anyhow::Result;
+use probe_code::search::file_index;
 use probe_code::search::file_list_cache;
+use probe_code::search::trigram_filter::LiteralFilter;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tracing::debug;
 // No need for term_exceptions import
 
-use probe_code::models::{LimitedSearchResults, SearchResult};
+use probe_code::interrupt;
+use probe_code::models::{BlockCode, LimitedSearchResults, MatchCount, SearchResult};
+use probe_code::progress::Progress;
+use probe_code::warnings::{warn, WarningKind};
 
 /// Configuration for search with structured patterns
 #[derive(Debug, Clone)]
@@ -16,20 +23,36 @@ pub struct SearchConfig<'a> {
     pub allow_tests: bool,
     pub language: Option<&'a str>,
     pub no_gitignore: bool,
+    /// Search exactly these files instead of walking the root
+    pub files: Option<&'a [PathBuf]>,
 }
 use probe_code::path_resolver::resolve_path;
 use probe_code::search::{
+    archives,
+    blame,
+    block_trimming,
     cache,
+    codeowners::{self, CodeOwners},
+    dedup,
     early_ranker,
+    file_chunking,
     // file_list_cache, // Add the new file_list_cache module (unused)
     file_processing::{process_file_with_results, FileProcessingParams},
-    filters::SearchFilters,
+    filters::{FileTypes, IncludeGlobs, SearchFilters},
+    generated,
+    git_changes,
+    notebooks,
     query::{create_structured_patterns, QueryPlan},
-    result_ranking::rank_search_results,
+    result_ranking::{
+        apply_filename_weight, apply_recency_boost, diversify_by_directory, rank_search_results,
+        sort_results, stabilize_rank_ties,
+    },
     search_limiter::apply_limits,
     search_options::SearchOptions,
+    search_tokens::sum_tokens_with_deduplication,
     simd_pattern_matching::SimdPatternMatcher,
     timeout,
+    trigram_vector,
 };
 
 /// Struct to hold timing information for different stages of the search process
@@ -88,146 +111,246 @@ pub fn format_duration(duration: Duration) -> String {
 
 /// Helper function to print timing information in debug mode
 pub fn print_timings(timings: &SearchTimings) {
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     if !debug_mode {
         return;
     }
 
-    println!("\n=== SEARCH TIMING INFORMATION ===");
+    debug!("=== SEARCH TIMING INFORMATION ===");
 
     if let Some(duration) = timings.query_preprocessing {
-        println!("Query preprocessing:   {}", format_duration(duration));
+        debug!("Query preprocessing:   {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.pattern_generation {
-        println!("Pattern generation:    {}", format_duration(duration));
+        debug!("Pattern generation:    {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.file_searching {
-        println!("File searching:        {}", format_duration(duration));
+        debug!("File searching:        {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.filename_matching {
-        println!("Filename matching:     {}", format_duration(duration));
+        debug!("Filename matching:     {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.early_filtering {
-        println!("Early AST filtering:   {}", format_duration(duration));
+        debug!("Early AST filtering:   {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.early_caching {
-        println!("Early caching:         {}", format_duration(duration));
+        debug!("Early caching:         {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.early_ranking {
-        println!("Early ranking:         {}", format_duration(duration));
+        debug!("Early ranking:         {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.result_processing {
-        println!("Result processing:     {}", format_duration(duration));
+        debug!("Result processing:     {}", format_duration(duration));
 
         // Print granular result processing timings if available
         if let Some(duration) = timings.result_processing_file_io {
-            println!("  - File I/O:           {}", format_duration(duration));
+            debug!("  - File I/O:           {}", format_duration(duration));
         }
 
         if let Some(duration) = timings.result_processing_line_collection {
-            println!("  - Line collection:    {}", format_duration(duration));
+            debug!("  - Line collection:    {}", format_duration(duration));
         }
 
         if let Some(duration) = timings.result_processing_ast_parsing {
-            println!("  - AST parsing:        {}", format_duration(duration));
+            debug!("  - AST parsing:        {}", format_duration(duration));
 
             // Print granular AST parsing sub-step timings
             if let Some(d) = timings.result_processing_ast_parsing_language_init {
-                println!("    - Language init:     {}", format_duration(d));
+                debug!("    - Language init:     {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_ast_parsing_parser_init {
-                println!("    - Parser init:       {}", format_duration(d));
+                debug!("    - Parser init:       {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_ast_parsing_tree_parsing {
-                println!("    - Tree parsing:      {}", format_duration(d));
+                debug!("    - Tree parsing:      {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_ast_parsing_line_map_building {
-                println!("    - Line map building: {}", format_duration(d));
+                debug!("    - Line map building: {}", format_duration(d));
             }
         }
 
         if let Some(duration) = timings.result_processing_block_extraction {
-            println!("  - Block extraction:   {}", format_duration(duration));
+            debug!("  - Block extraction:   {}", format_duration(duration));
 
             // Print granular block extraction sub-step timings
             if let Some(d) = timings.result_processing_block_extraction_code_structure {
-                println!("    - Code structure:    {}", format_duration(d));
+                debug!("    - Code structure:    {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_block_extraction_filtering {
-                println!("    - Filtering:         {}", format_duration(d));
+                debug!("    - Filtering:         {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_block_extraction_result_building {
-                println!("    - Result building:   {}", format_duration(d));
+                debug!("    - Result building:   {}", format_duration(d));
             }
         }
 
         if let Some(duration) = timings.result_processing_result_building {
-            println!("  - Result building:    {}", format_duration(duration));
+            debug!("  - Result building:    {}", format_duration(duration));
 
             // Print detailed result building timings if available
             if let Some(d) = timings.result_processing_term_matching {
-                println!("    - Term matching:      {}", format_duration(d));
+                debug!("    - Term matching:      {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_compound_processing {
-                println!("    - Compound processing: {}", format_duration(d));
+                debug!("    - Compound processing: {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_line_matching {
-                println!("    - Line matching:      {}", format_duration(d));
+                debug!("    - Line matching:      {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_result_creation {
-                println!("    - Result creation:    {}", format_duration(d));
+                debug!("    - Result creation:    {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_synchronization {
-                println!("    - Synchronization:    {}", format_duration(d));
+                debug!("    - Synchronization:    {}", format_duration(d));
             }
             if let Some(d) = timings.result_processing_uncovered_lines {
-                println!("    - Uncovered lines:    {}", format_duration(d));
+                debug!("    - Uncovered lines:    {}", format_duration(d));
             }
         }
     }
 
     if let Some(duration) = timings.result_ranking {
-        println!("Result ranking:        {}", format_duration(duration));
+        debug!("Result ranking:        {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.limit_application {
-        println!("Limit application:     {}", format_duration(duration));
+        debug!("Limit application:     {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.block_merging {
-        println!("Block merging:         {}", format_duration(duration));
+        debug!("Block merging:         {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.final_caching {
-        println!("Final caching:         {}", format_duration(duration));
+        debug!("Final caching:         {}", format_duration(duration));
     }
 
     if let Some(duration) = timings.total_search_time {
-        println!("Total search time:     {}", format_duration(duration));
+        debug!("Total search time:     {}", format_duration(duration));
     }
 
-    println!("===================================\n");
+    debug!("===================================\n");
 }
 
 // Removed evaluate_ignoring_negatives helper function in favor of direct usage
 
+/// The search roots without duplicates or roots nested inside another root, so
+/// overlapping paths such as `src/ src/search/` do not report a file twice
+fn distinct_roots(paths: &[PathBuf]) -> Vec<&Path> {
+    let canonical: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| std::fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+        .collect();
+    paths
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            canonical.iter().enumerate().all(|(j, other)| {
+                j == *i || !canonical[*i].starts_with(other) || (canonical[*i] == *other && *i < j)
+            })
+        })
+        .map(|(_, path)| path.as_path())
+        .collect()
+}
+
+/// The literal words of a regular expression, for ranking `--regex` results:
+/// `fn\s+parse_\w+\(` gives `fn parse_`. Escapes such as `\w` are dropped and every
+/// other non-word character separates words.
+fn pattern_words(pattern: &str) -> String {
+    let mut words = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            chars.next();
+            words.push(' ');
+        } else if c.is_alphanumeric() || c == '_' {
+            words.push(c);
+        } else {
+            words.push(' ');
+        }
+    }
+    words.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Size of the result set described by the matched lines of each file, for `--count-only`.
+/// Matched lines closer than the merge threshold are counted as one block, as block
+/// merging would join them.
+fn count_matches(
+    file_term_map: &HashMap<PathBuf, HashMap<usize, HashSet<usize>>>,
+    merge_threshold: Option<usize>,
+) -> MatchCount {
+    let threshold = merge_threshold.unwrap_or(5);
+    let mut count = MatchCount {
+        files: file_term_map.len(),
+        blocks: 0,
+        lines: 0,
+    };
+    for term_map in file_term_map.values() {
+        let mut lines: Vec<usize> = term_map.values().flatten().copied().collect();
+        lines.sort_unstable();
+        lines.dedup();
+        count.lines += lines.len();
+        count.blocks += lines.windows(2).filter(|w| w[1] - w[0] > threshold).count();
+        if !lines.is_empty() {
+            count.blocks += 1;
+        }
+    }
+    count
+}
+
+/// Run a search. Results in archive members (`--search-archives`) are reported by their
+/// `archive!member` paths, and results in notebook cells as `notebook.ipynb:cell N`.
+pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
+    let (sender, receiver) = mpsc::channel();
+    let mut limited = perform_probe_streaming(options, sender)?;
+    limited.results = receiver.into_iter().collect();
+    Ok(limited)
+}
+
+/// Run a search, sending each result through `sender` as soon as it is final, in the
+/// order `perform_probe` returns them, so output can start before the whole result set
+/// is processed. Results are ranked and limited first; then each file's blocks are
+/// merged, trimmed and annotated and sent on (each block on its own with `--no-merge`).
+/// Sorting by anything but score holds them back until all are processed.
+///
+/// Returns everything else `perform_probe` does, with empty `results`. The search stops
+/// sending when the receiver is dropped.
+pub fn perform_probe_streaming(
+    options: &SearchOptions,
+    sender: Sender<SearchResult>,
+) -> Result<LimitedSearchResults> {
+    let mut limited = search_files(options, &sender)?;
+    // Searches that end early, like --files-only, return their results at once
+    archives::map_result_paths(&mut limited);
+    notebooks::map_result_paths(&mut limited);
+    for result in std::mem::take(&mut limited.results) {
+        if sender.send(result).is_err() {
+            break;
+        }
+    }
+    Ok(limited)
+}
+
 /// Our main "perform_probe" function remains largely the same. Below we show how you might
 /// incorporate "search_with_structured_patterns" to handle the AST logic in a specialized path.
 /// For simplicity, we won't fully replace the existing logic. Instead, we'll demonstrate
 /// how you'd do it if you wanted to leverage the new approach.
-pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
+fn search_files(
+    options: &SearchOptions,
+    sender: &Sender<SearchResult>,
+) -> Result<LimitedSearchResults> {
     // Start timing the entire search process
     let total_start = Instant::now();
 
     let SearchOptions {
-        path,
+        paths,
         queries,
         files_only,
         custom_ignores,
@@ -239,20 +362,67 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         max_results,
         max_bytes,
         max_tokens,
+        max_files,
         allow_tests,
         no_merge,
         merge_threshold,
         dry_run: _, // We don't need this in perform_probe, but need to include it in the pattern
         session,
+        session_across_queries,
+        session_ttl,
+        session_max_entries,
         timeout,
         question,
         no_gitignore,
+        no_attributes,
+        include_deps,
+        filename_weight,
+        dedupe_cross_file,
+        diversify,
+        regex_escape,
+        include_generated,
+        sort,
+        reverse,
+        smart_case,
+        files_from,
+        vector_weight,
+        fuzzy,
+        count_only,
+        case_sensitive,
+        max_block_lines,
+        regex,
+        no_whole_files,
+        within_session,
+        include,
+        offset,
+        stable_order,
+        file_types,
+        type_definitions,
+        symbols_only,
+        no_comments,
+        comments_only,
+        strings_only,
+        boost_recent,
+        recency_weight,
+        changed,
+        since,
+        blame,
+        owners,
     } = options;
     // Start the timeout thread
     let timeout_handle = timeout::start_timeout_thread(*timeout);
 
-    let include_filenames = !exclude_filenames;
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    // A filename is not a declaration, so symbols-only searches never match by it
+    let include_filenames = !exclude_filenames && !symbols_only;
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
+
+    // Drop session caches that outlived their TTL before touching the current one
+    let removed_sessions =
+        cache::gc_expired_sessions(session_ttl.unwrap_or(cache::DEFAULT_SESSION_TTL));
+    if debug_mode && removed_sessions > 0 {
+        debug!("Removed {removed_sessions} expired session cache files");
+    }
+    let max_session_entries = session_max_entries.unwrap_or(cache::DEFAULT_SESSION_MAX_ENTRIES);
 
     // Handle session ID generation if session is provided but empty
     // For test runs, force session to None to disable caching
@@ -262,7 +432,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             if let Ok(env_session_id) = std::env::var("PROBE_SESSION_ID") {
                 if !env_session_id.is_empty() {
                     if debug_mode {
-                        println!("DEBUG: Using session ID from environment: {env_session_id}");
+                        debug!("Using session ID from environment: {env_session_id}");
                     }
                     // Convert to a static string (this leaks memory, but it's a small amount and only happens once per session)
                     let static_id: &'static str = Box::leak(env_session_id.into_boxed_str());
@@ -272,7 +442,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                     match cache::generate_session_id() {
                         Ok((new_id, _is_new)) => {
                             if debug_mode {
-                                println!("DEBUG: Generated new session ID: {new_id}");
+                                debug!("Generated new session ID: {new_id}");
                             }
                             (Some(new_id), true)
                         }
@@ -287,7 +457,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 match cache::generate_session_id() {
                     Ok((new_id, _is_new)) => {
                         if debug_mode {
-                            println!("DEBUG: Generated new session ID: {new_id}");
+                            debug!("Generated new session ID: {new_id}");
                         }
                         (Some(new_id), true)
                     }
@@ -305,7 +475,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         if let Ok(env_session_id) = std::env::var("PROBE_SESSION_ID") {
             if !env_session_id.is_empty() {
                 if debug_mode {
-                    println!("DEBUG: Using session ID from environment: {env_session_id}");
+                    debug!("Using session ID from environment: {env_session_id}");
                 }
                 // Convert to a static string (this leaks memory, but it's a small amount and only happens once per session)
                 let static_id: &'static str = Box::leak(env_session_id.into_boxed_str());
@@ -362,7 +532,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     // Combine multiple queries with AND or just parse single query
     let qp_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting query preprocessing...");
+        debug!("Starting query preprocessing...");
     }
 
     // First, parse the query to extract filters
@@ -372,16 +542,28 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         queries[0].clone()
     };
 
+    // A regex query is parsed like an exact one: a single term, used as is
+    let exact_query = *exact || *regex;
+
     // Parse the combined query into an AST
-    let initial_ast_result = crate::search::elastic_query::parse_query(&combined_query, *exact);
+    let initial_ast_result =
+        crate::search::elastic_query::parse_query(&combined_query, exact_query);
     if initial_ast_result.is_err() {
-        println!("Failed to parse query as AST expression");
+        warn(
+            WarningKind::InvalidQuery,
+            None,
+            "Failed to parse query as AST expression",
+        );
         return Ok(LimitedSearchResults {
             results: Vec::new(),
             skipped_files: Vec::new(),
             limits_applied: None,
             cached_blocks_skipped: None,
             files_skipped_early_termination: None,
+            session_tokens: None,
+            match_count: None,
+            total_ranked: None,
+            large_files_skipped: Vec::new(),
         });
     }
 
@@ -392,28 +574,46 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         SearchFilters::extract_and_simplify_with_autodetect(initial_ast);
 
     if debug_mode && !search_filters.is_empty() {
-        println!("DEBUG: Extracted search filters: {search_filters:?}");
+        debug!("Extracted search filters: {search_filters:?}");
     }
 
     // If we have a simplified AST, create a query plan from it
     // Otherwise, if all terms were filters, we'll search all content
-    let plan = if let Some(simplified_ast) = simplified_ast {
+    let mut plan = if let Some(simplified_ast) = simplified_ast {
         // Create query plan from simplified AST that contains only content search terms
-        crate::search::query::create_query_plan_from_ast(simplified_ast, *exact)?
+        crate::search::query::create_query_plan_from_ast(simplified_ast, exact_query)?
     } else {
         // All terms were filters - create a universal query plan that matches everything
         if debug_mode {
-            println!("DEBUG: All query terms were filters - creating universal search plan");
+            debug!("All query terms were filters - creating universal search plan");
         }
         crate::search::query::create_universal_query_plan()
     };
 
+    plan.regex_escape = *regex_escape && !*regex;
+    plan.smart_case = *smart_case;
+    plan.case_sensitive = *case_sensitive || search_filters.case_sensitive;
+    if plan.case_sensitive {
+        plan.written_terms = crate::search::query::written_term_forms(&combined_query, &plan);
+        if debug_mode {
+            debug!("Case-sensitive term forms: {:?}", plan.written_terms);
+        }
+    }
+    plan.fuzzy_terms = crate::search::fuzzy::fuzzy_term_indices(&combined_query, &plan, *fuzzy);
+    if debug_mode && !plan.fuzzy_terms.is_empty() {
+        debug!("Fuzzy term indices: {:?}", plan.fuzzy_terms);
+    }
+    if plan.exact && !plan.regex_escape {
+        regex::Regex::new(&combined_query)
+            .map_err(|e| anyhow::anyhow!("Invalid regular expression '{combined_query}': {e}"))?;
+    }
+
     let qp_duration = qp_start.elapsed();
     timings.query_preprocessing = Some(qp_duration);
 
     if debug_mode {
-        println!(
-            "DEBUG: Query preprocessing completed in {}",
+        debug!(
+            "Query preprocessing completed in {}",
             format_duration(qp_duration)
         );
     }
@@ -421,8 +621,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     // Pattern generation timing
     let pg_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting pattern generation...");
-        println!("DEBUG: Using combined pattern approach for more efficient searching");
+        debug!("Starting pattern generation...");
+        debug!("Using combined pattern approach for more efficient searching");
     }
 
     // Use combined pattern approach for more efficient searching
@@ -432,23 +632,23 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     timings.pattern_generation = Some(pg_duration);
 
     if debug_mode {
-        println!(
-            "DEBUG: Pattern generation completed in {}",
+        debug!(
+            "Pattern generation completed in {}",
             format_duration(pg_duration)
         );
-        println!(
-            "DEBUG: Generated {patterns_len} patterns",
+        debug!(
+            "Generated {patterns_len} patterns",
             patterns_len = structured_patterns.len()
         );
         if structured_patterns.len() == 1 {
-            println!("DEBUG: Successfully created a single combined pattern for all terms");
+            debug!("Successfully created a single combined pattern for all terms");
         }
     }
 
     // File searching timing
     let fs_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting file searching...");
+        debug!("Starting file searching...");
     }
 
     /*
@@ -471,15 +671,53 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         allow_tests: *allow_tests,
         language: lang_param,
         no_gitignore: *no_gitignore,
+        files: *files_from,
     };
 
-    let mut file_term_map = search_with_structured_patterns(
-        path,
-        &plan,
-        &structured_patterns,
-        &search_config,
-        &search_filters,
-    )?;
+    // A --files-from list replaces walking the roots, so it is searched once
+    let roots = if files_from.is_some() {
+        vec![Path::new(".")]
+    } else {
+        distinct_roots(paths)
+    };
+    let mut file_term_map = HashMap::new();
+    for root in &roots {
+        file_term_map.extend(search_with_structured_patterns(
+            root,
+            &plan,
+            &structured_patterns,
+            &search_config,
+            &search_filters,
+        )?);
+    }
+
+    // With --include-deps, dependency sources are searched as extra roots. The
+    // project's .gitignore usually excludes them, so gitignore is not applied there.
+    let dependency_roots = match roots.first() {
+        Some(path) if *include_deps && files_from.is_none() => {
+            let project =
+                resolve_path(&path.to_string_lossy()).unwrap_or_else(|_| path.to_path_buf());
+            probe_code::path_resolver::find_dependency_roots(&project)
+        }
+        _ => Vec::new(),
+    };
+    let dependency_config = SearchConfig {
+        no_gitignore: true,
+        ..search_config.clone()
+    };
+    for root in &dependency_roots {
+        if debug_mode {
+            debug!("Searching dependency root {root:?}");
+        }
+        let root_term_map = search_with_structured_patterns(
+            root,
+            &plan,
+            &structured_patterns,
+            &dependency_config,
+            &search_filters,
+        )?;
+        file_term_map.extend(root_term_map);
+    }
 
     let fs_duration = fs_start.elapsed();
     timings.file_searching = Some(fs_duration);
@@ -495,8 +733,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         // Get number of unique files
         let unique_files = file_term_map.keys().len();
 
-        println!(
-            "DEBUG: File searching completed in {} - Found {} matches in {} unique files",
+        debug!(
+            "File searching completed in {} - Found {} matches in {} unique files",
             format_duration(fs_duration),
             total_matches,
             unique_files
@@ -505,42 +743,53 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
 
     // Build final results
     let mut all_files = file_term_map.keys().cloned().collect::<HashSet<_>>();
+    // Files that only made it into the results through their filename
+    let mut filename_only_files: HashSet<PathBuf> = HashSet::new();
 
     // Add filename matches if enabled
     let fm_start = Instant::now();
-    if include_filenames && !exact {
+    if include_filenames && !exact_query {
         if debug_mode {
-            println!("DEBUG: Starting filename matching...");
+            debug!("Starting filename matching...");
         }
         // Find all files that match our patterns by filename, along with the terms that matched
-        // Resolve the path if it's a special format (e.g., "go:github.com/user/repo")
-        let resolved_path = if let Some(path_str) = path.to_str() {
-            match resolve_path(path_str) {
-                Ok(resolved_path) => {
-                    if debug_mode {
-                        println!(
-                            "DEBUG: Resolved path '{}' to '{}'",
-                            path_str,
-                            resolved_path.display()
-                        );
+        let mut filename_matches: HashMap<PathBuf, HashSet<usize>> = HashMap::new();
+        if let Some(files) = files_from {
+            filename_matches.extend(file_list_cache::match_filenames(
+                files,
+                queries,
+                &all_files,
+                &plan.term_indices,
+            ));
+        }
+        for path in roots.iter().filter(|_| files_from.is_none()) {
+            // Resolve the path if it's a special format (e.g., "go:github.com/user/repo")
+            let resolved_path = if let Some(path_str) = path.to_str() {
+                match resolve_path(path_str) {
+                    Ok(resolved_path) => {
+                        if debug_mode {
+                            debug!(
+                                "Resolved path '{}' to '{}'",
+                                path_str,
+                                resolved_path.display()
+                            );
+                        }
+                        resolved_path
                     }
-                    resolved_path
-                }
-                Err(err) => {
-                    if debug_mode {
-                        println!("DEBUG: Failed to resolve path '{path_str}': {err}");
+                    Err(err) => {
+                        if debug_mode {
+                            debug!("Failed to resolve path '{path_str}': {err}");
+                        }
+                        // Fall back to the original path
+                        path.to_path_buf()
                     }
-                    // Fall back to the original path
-                    path.to_path_buf()
                 }
-            }
-        } else {
-            // If we can't convert the path to a string, use it as is
-            path.to_path_buf()
-        };
+            } else {
+                // If we can't convert the path to a string, use it as is
+                path.to_path_buf()
+            };
 
-        let filename_matches: HashMap<PathBuf, HashSet<usize>> =
-            file_list_cache::find_matching_filenames(
+            filename_matches.extend(file_list_cache::find_matching_filenames(
                 &resolved_path,
                 queries,
                 &all_files,
@@ -549,11 +798,24 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 &plan.term_indices,
                 lang_param,
                 *no_gitignore,
-            )?;
+            )?);
+        }
+        for root in &dependency_roots {
+            filename_matches.extend(file_list_cache::find_matching_filenames(
+                root,
+                queries,
+                &all_files,
+                custom_ignores,
+                *allow_tests,
+                &plan.term_indices,
+                lang_param,
+                true,
+            )?);
+        }
 
         if debug_mode {
-            println!(
-                "DEBUG: Found {} files matching by filename",
+            debug!(
+                "Found {} files matching by filename",
                 filename_matches.len()
             );
         }
@@ -568,7 +830,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 Ok(path) => path,
                 Err(e) => {
                     if debug_mode {
-                        println!("DEBUG: Error resolving path for {pathbuf:?}: {e:?}");
+                        debug!("Error resolving path for {pathbuf:?}: {e:?}");
                     }
                     continue;
                 }
@@ -579,7 +841,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 Ok(meta) => meta,
                 Err(e) => {
                     if debug_mode {
-                        println!("DEBUG: Error getting metadata for {resolved_path:?}: {e:?}");
+                        debug!("Error getting metadata for {resolved_path:?}: {e:?}");
                     }
                     continue;
                 }
@@ -588,8 +850,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             // Check if the file is too large
             if metadata.len() > MAX_FILE_SIZE {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
+                    debug!(
+                        "Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
                         resolved_path,
                         metadata.len(),
                         MAX_FILE_SIZE
@@ -603,8 +865,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 Ok(content) => content,
                 Err(e) => {
                     if debug_mode {
-                        println!(
-                            "DEBUG: Error reading file {:?}: {:?} (size: {} bytes)",
+                        debug!(
+                            "Error reading file {:?}: {:?} (size: {} bytes)",
                             resolved_path,
                             e,
                             metadata.len()
@@ -618,7 +880,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             let line_count = file_content.lines().count();
             if line_count == 0 {
                 if debug_mode {
-                    println!("DEBUG: File {pathbuf:?} is empty, skipping");
+                    debug!("File {pathbuf:?} is empty, skipping");
                 }
                 continue;
             }
@@ -629,15 +891,16 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             // Check if this file already has term matches from content search
             let mut term_map = if let Some(existing_map) = file_term_map.get(pathbuf) {
                 if debug_mode {
-                    println!(
-                        "DEBUG: File {pathbuf:?} already has term matches from content search, extending"
+                    debug!(
+                        "File {pathbuf:?} already has term matches from content search, extending"
                     );
                 }
                 existing_map.clone()
             } else {
                 if debug_mode {
-                    println!("DEBUG: Creating new term map for file {pathbuf:?}");
+                    debug!("Creating new term map for file {pathbuf:?}");
                 }
+                filename_only_files.insert(pathbuf.clone());
                 HashMap::new()
             };
 
@@ -649,9 +912,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                     .extend(&all_line_numbers);
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Added term index {term_idx} to file {pathbuf:?} with all lines"
-                    );
+                    debug!("Added term index {term_idx} to file {pathbuf:?} with all lines");
                 }
             }
 
@@ -660,21 +921,21 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             all_files.insert(pathbuf.clone());
 
             if debug_mode {
-                println!("DEBUG: Added file {pathbuf:?} with matching terms to file_term_map");
+                debug!("Added file {pathbuf:?} with matching terms to file_term_map");
             }
         }
     }
 
     if debug_mode {
-        println!("DEBUG: all_files after filename matches: {all_files:?}");
+        debug!("all_files after filename matches: {all_files:?}");
     }
 
     // Early filtering step - filter both all_files and file_term_map using full AST evaluation (including excluded terms?).
     // Actually we pass 'true' to 'evaluate(..., true)', so that ignores excluded terms, contrary to the debug comment.
     let early_filter_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting early AST filtering...");
-        println!("DEBUG: Before filtering: {} files", all_files.len());
+        debug!("Starting early AST filtering...");
+        debug!("Before filtering: {} files", all_files.len());
     }
 
     // Create a new filtered file_term_map
@@ -691,10 +952,10 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 filtered_file_term_map.insert(pathbuf.clone(), term_map.clone());
                 filtered_all_files.insert(pathbuf.clone());
             } else if debug_mode {
-                println!("DEBUG: Early filtering removed file: {pathbuf:?}");
+                debug!("Early filtering removed file: {pathbuf:?}");
             }
         } else if debug_mode {
-            println!("DEBUG: File {pathbuf:?} not found in file_term_map during early filtering");
+            debug!("File {pathbuf:?} not found in file_term_map during early filtering");
         }
     }
 
@@ -702,20 +963,33 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     file_term_map = filtered_file_term_map;
     all_files = filtered_all_files;
 
+    // Generated and license-only files repeat the query terms without being worth reading
+    if !*include_generated {
+        let generated_files: Vec<PathBuf> = all_files
+            .par_iter()
+            .filter(|path| generated::is_generated_file(path))
+            .cloned()
+            .collect();
+        for path in &generated_files {
+            if debug_mode {
+                debug!("Skipping generated or license-only file: {path:?}");
+            }
+            all_files.remove(path);
+            file_term_map.remove(path);
+        }
+    }
+
     if debug_mode {
-        println!(
-            "DEBUG: After early filtering: {} files remain",
-            all_files.len()
-        );
-        println!("DEBUG: all_files after early filtering: {all_files:?}");
+        debug!("After early filtering: {} files remain", all_files.len());
+        debug!("all_files after early filtering: {all_files:?}");
     }
 
     let early_filter_duration = early_filter_start.elapsed();
     timings.early_filtering = Some(early_filter_duration);
 
     if debug_mode {
-        println!(
-            "DEBUG: Early AST filtering completed in {}",
+        debug!(
+            "Early AST filtering completed in {}",
             format_duration(early_filter_duration)
         );
     }
@@ -724,12 +998,118 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     timings.filename_matching = Some(fm_duration);
 
     if debug_mode && include_filenames {
-        println!(
-            "DEBUG: Filename matching completed in {}",
+        debug!(
+            "Filename matching completed in {}",
             format_duration(fm_duration)
         );
     }
 
+    let include_globs = IncludeGlobs::new(&roots, include)?;
+    if let Some(globs) = &include_globs {
+        file_term_map.retain(|path, _| globs.matches(path));
+        all_files.retain(|path| globs.matches(path));
+    }
+    let types = FileTypes::new(file_types, type_definitions)?;
+    if let Some(types) = &types {
+        file_term_map.retain(|path, _| types.matches(path));
+        all_files.retain(|path| types.matches(path));
+    }
+    if *changed || since.is_some() {
+        let mut changed_files = HashSet::new();
+        for root in &roots {
+            changed_files.extend(git_changes::changed_files(root, *since)?);
+        }
+        let is_changed = |path: &Path| {
+            path.canonicalize()
+                .is_ok_and(|path| changed_files.contains(&path))
+        };
+        file_term_map.retain(|path, _| is_changed(path));
+        all_files.retain(|path| is_changed(path));
+        if debug_mode {
+            debug!("Files changed according to git: {}", changed_files.len());
+        }
+    }
+    if !search_filters.owners.is_empty() {
+        let code_owners: Vec<CodeOwners> = roots
+            .iter()
+            .filter_map(|root| CodeOwners::find(root))
+            .collect();
+        if code_owners.is_empty() {
+            warn(
+                WarningKind::Other,
+                None,
+                "owner: hint used but no CODEOWNERS file was found",
+            );
+        }
+        let is_owned = |path: &Path| {
+            code_owners
+                .iter()
+                .any(|owners| owners.is_owned_by(path, &search_filters.owners))
+        };
+        file_term_map.retain(|path, _| is_owned(path));
+        all_files.retain(|path| is_owned(path));
+    }
+
+    // Files --max-filesize kept out of the search that the filters would have searched
+    let mut large_files: Vec<(String, u64)> = Vec::new();
+    if file_list_cache::walk_options().max_filesize.is_some() && files_from.is_none() {
+        for root in &roots {
+            let root = resolve_path(&root.to_string_lossy()).unwrap_or_else(|_| root.to_path_buf());
+            let file_list =
+                file_list_cache::get_file_list(&root, *allow_tests, custom_ignores, *no_gitignore)?;
+            large_files.extend(
+                file_list
+                    .large_files
+                    .iter()
+                    .filter(|(path, _)| {
+                        search_filters.matches_file(path)
+                            && include_globs
+                                .as_ref()
+                                .is_none_or(|globs| globs.matches(path))
+                            && types.as_ref().is_none_or(|types| types.matches(path))
+                    })
+                    .map(|(path, size)| (path.to_string_lossy().into_owned(), *size)),
+            );
+        }
+    }
+
+    // Narrow the search down to what earlier queries of a session returned
+    let within_blocks = match within_session {
+        Some(session_id) => {
+            let blocks = cache::session_blocks(session_id);
+            if blocks.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Session '{session_id}' has no results to search within"
+                ));
+            }
+            file_term_map.retain(|path, _| cache::in_session_files(path, &blocks));
+            all_files.retain(|path| cache::in_session_files(path, &blocks));
+            if debug_mode {
+                debug!("all_files within session {session_id}: {all_files:?}");
+            }
+            Some(blocks)
+        }
+        None => None,
+    };
+
+    // Count-only mode: size up the result set without processing any file
+    if *count_only {
+        timings.total_search_time = Some(total_start.elapsed());
+        print_timings(&timings);
+
+        return Ok(LimitedSearchResults {
+            results: Vec::new(),
+            skipped_files: Vec::new(),
+            limits_applied: None,
+            cached_blocks_skipped: None,
+            files_skipped_early_termination: None,
+            session_tokens: None,
+            match_count: Some(count_matches(&file_term_map, *merge_threshold)),
+            total_ranked: None,
+            large_files_skipped: Vec::new(),
+        });
+    }
+
     // Handle files-only mode
     if *files_only {
         let mut res = Vec::new();
@@ -738,9 +1118,13 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 file: f.to_string_lossy().to_string(),
                 lines: (1, 1),
                 node_type: "file".to_string(),
-                code: String::new(),
+                code: BlockCode::default(),
                 symbol_signature: None,
                 matched_by_filename: None,
+                duplicate_files: None,
+                blame: None,
+                owners: None,
+                provenance: None,
                 rank: None,
                 score: None,
                 tfidf_score: None,
@@ -763,7 +1147,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 parent_context: None,
             });
         }
-        let mut limited = apply_limits(res, *max_results, *max_bytes, *max_tokens);
+        let mut limited = apply_limits(res, *max_results, *max_bytes, *max_tokens, *max_files);
+        sort_results(&mut limited.results, sort, *reverse);
 
         // No caching for files-only mode
         limited.cached_blocks_skipped = None;
@@ -781,20 +1166,14 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     let ec_start = Instant::now();
     let mut early_skipped_count = 0;
     if let Some(session_id) = effective_session {
-        // Get the raw query string for caching
-        let raw_query = if queries.len() > 1 {
-            queries.join(" AND ")
-        } else {
-            queries[0].clone()
-        };
+        // Get the query string the session cache is keyed by
+        let raw_query = cache::session_cache_query(queries, *session_across_queries);
 
         if debug_mode {
-            println!(
-                "DEBUG: Starting early caching for session: {session_id} with query: {raw_query}"
-            );
+            debug!("Starting early caching for session: {session_id} with query: {raw_query}");
             // Print cache contents before filtering
             if let Err(e) = cache::debug_print_cache(session_id, &raw_query) {
-                eprintln!("Error printing cache: {e}");
+                debug!("Error printing cache: {e}");
             }
         }
 
@@ -802,7 +1181,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         match cache::filter_matched_lines_with_cache(&mut file_term_map, session_id, &raw_query) {
             Ok(skipped) => {
                 if debug_mode {
-                    println!("DEBUG: Early caching skipped {skipped} matched lines");
+                    debug!("Early caching skipped {skipped} matched lines");
                 }
                 early_skipped_count = skipped;
             }
@@ -818,7 +1197,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         all_files = all_files.intersection(&cached_files).cloned().collect();
 
         if debug_mode {
-            println!("DEBUG: all_files after caching: {all_files:?}");
+            debug!("all_files after caching: {all_files:?}");
         }
     }
 
@@ -826,8 +1205,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     timings.early_caching = Some(ec_duration);
 
     if debug_mode && effective_session.is_some() {
-        println!(
-            "DEBUG: Early caching completed in {}",
+        debug!(
+            "Early caching completed in {}",
             format_duration(ec_duration)
         );
     }
@@ -837,10 +1216,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
 
     // Step 1: Calculate early scores for all files
     if debug_mode {
-        println!(
-            "DEBUG: Starting early ranking for {} files...",
-            all_files.len()
-        );
+        debug!("Starting early ranking for {} files...", all_files.len());
     }
 
     // Prepare data for early ranking
@@ -881,8 +1257,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     let early_ranking_duration = early_ranking_start.elapsed();
     timings.early_ranking = Some(early_ranking_duration);
     if debug_mode {
-        println!(
-            "DEBUG: Early ranking completed in {} - Ranked {} files",
+        debug!(
+            "Early ranking completed in {} - Ranked {} files",
             format_duration(early_ranking_duration),
             ranked_files.len()
         );
@@ -891,7 +1267,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     // Step 2: Process files in batches until limits are satisfied
     let rp_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting batch processing of top-ranked files...");
+        debug!("Starting batch processing of top-ranked files...");
     }
 
     let mut final_results = Vec::new();
@@ -941,12 +1317,14 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     // This prevents processing way more files than needed when limits are strict
     let effective_batch_size = BATCH_SIZE.min(estimated_files_needed);
     if debug_mode {
-        println!(
-            "DEBUG: Using batch size {} (BATCH_SIZE={}, estimated_files_needed={})",
+        debug!(
+            "Using batch size {} (BATCH_SIZE={}, estimated_files_needed={})",
             effective_batch_size, BATCH_SIZE, estimated_files_needed
         );
     }
 
+    let progress = Progress::new("Processing matches", total_ranked_files);
+
     // Process files in batches
     for batch in ranked_files.chunks(effective_batch_size) {
         if !should_continue {
@@ -955,21 +1333,26 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
 
         batch_number += 1;
         if debug_mode {
-            println!(
-                "DEBUG: Processing batch {} ({} files)",
-                batch_number,
-                batch.len()
-            );
+            debug!("Processing batch {} ({} files)", batch_number, batch.len());
         }
 
         let batch_start = Instant::now();
         let mut batch_results = Vec::new();
 
         for early_rank_result in batch {
+            // After Ctrl-C, stop as soon as there is something to show for the files
+            // matched before it
+            if interrupt::is_interrupted()
+                && !(final_results.is_empty() && batch_results.is_empty())
+            {
+                should_continue = false;
+                break;
+            }
+            progress.inc();
             let pathbuf = &early_rank_result.path;
             if debug_mode {
-                println!(
-                    "DEBUG: Processing file: {pathbuf:?} (early score: {:.4})",
+                debug!(
+                    "Processing file: {pathbuf:?} (early score: {:.4})",
                     early_rank_result.score
                 );
             }
@@ -977,7 +1360,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             // Get the term map for this file
             if let Some(term_map) = file_term_map.get(pathbuf) {
                 if debug_mode {
-                    println!("DEBUG: Term map for file: {term_map:?}");
+                    debug!("Term map for file: {term_map:?}");
                 }
 
                 // Gather matched lines - measure line collection time
@@ -990,8 +1373,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 total_line_collection_time += line_collection_duration;
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Found {} matched lines in file in {}",
+                    debug!(
+                        "Found {} matched lines in file in {}",
                         all_lines.len(),
                         format_duration(line_collection_duration)
                     );
@@ -1017,14 +1400,16 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                     queries_terms: &[term_pairs],
                     preprocessed_queries: None,
                     no_merge: *no_merge,
+                    no_attributes: *no_attributes,
+                    symbols_only: *symbols_only,
+                    no_comments: *no_comments,
+                    comments_only: *comments_only,
+                    strings_only: *strings_only,
                     query_plan: &plan,
                 };
 
                 if debug_mode {
-                    println!(
-                        "DEBUG: Processing file with params: {}",
-                        pparams.path.display()
-                    );
+                    debug!("Processing file with params: {}", pparams.path.display());
                 }
 
                 // Process file and track granular timings
@@ -1045,34 +1430,25 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                         if let Some(duration) = file_timings.ast_parsing_language_init {
                             total_ast_parsing_language_init_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Language init: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Language init: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.ast_parsing_parser_init {
                             total_ast_parsing_parser_init_time += duration;
                             if debug_mode {
-                                println!("DEBUG:     - Parser init: {}", format_duration(duration));
+                                debug!("    - Parser init: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.ast_parsing_tree_parsing {
                             total_ast_parsing_tree_parsing_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Tree parsing: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Tree parsing: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.ast_parsing_line_map_building {
                             total_ast_parsing_line_map_building_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Line map building: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Line map building: {}", format_duration(duration));
                             }
                         }
 
@@ -1080,8 +1456,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                         if let Some(duration) = file_timings.block_extraction_code_structure {
                             total_block_extraction_code_structure_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Code structure finding: {}",
+                                debug!(
+                                    "    - Code structure finding: {}",
                                     format_duration(duration)
                                 );
                             }
@@ -1089,16 +1465,13 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                         if let Some(duration) = file_timings.block_extraction_filtering {
                             total_block_extraction_filtering_time += duration;
                             if debug_mode {
-                                println!("DEBUG:     - Filtering: {}", format_duration(duration));
+                                debug!("    - Filtering: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.block_extraction_result_building {
                             total_block_extraction_result_building_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Result building: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Result building: {}", format_duration(duration));
                             }
                         }
 
@@ -1106,94 +1479,69 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                         if let Some(duration) = file_timings.result_building_term_matching {
                             total_term_matching_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Term matching: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Term matching: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.result_building_compound_processing {
                             total_compound_processing_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Compound processing: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Compound processing: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.result_building_line_matching {
                             total_line_matching_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Line matching: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Line matching: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.result_building_result_creation {
                             total_result_creation_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Result creation: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Result creation: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.result_building_synchronization {
                             total_synchronization_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Synchronization: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Synchronization: {}", format_duration(duration));
                             }
                         }
                         if let Some(duration) = file_timings.result_building_uncovered_lines {
                             total_uncovered_lines_time += duration;
                             if debug_mode {
-                                println!(
-                                    "DEBUG:     - Uncovered lines: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("    - Uncovered lines: {}", format_duration(duration));
                             }
                         }
 
                         if debug_mode {
-                            println!("DEBUG: Got {} results from file processing", file_res.len());
+                            debug!("Got {} results from file processing", file_res.len());
                             if let Some(duration) = file_timings.file_io {
-                                println!("DEBUG:   File I/O time: {}", format_duration(duration));
+                                debug!("  File I/O time: {}", format_duration(duration));
                             }
                             if let Some(duration) = file_timings.ast_parsing {
-                                println!(
-                                    "DEBUG:   AST parsing time: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("  AST parsing time: {}", format_duration(duration));
                             }
                             if let Some(duration) = file_timings.block_extraction {
-                                println!(
-                                    "DEBUG:   Block extraction time: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("  Block extraction time: {}", format_duration(duration));
                             }
                             if let Some(duration) = file_timings.block_extraction_result_building {
-                                println!(
-                                    "DEBUG:   Result building time: {}",
-                                    format_duration(duration)
-                                );
+                                debug!("  Result building time: {}", format_duration(duration));
                             }
                         }
                         batch_results.append(&mut file_res);
                     }
                     Err(e) => {
                         if debug_mode {
-                            println!("DEBUG: Error processing file: {e:?}");
+                            debug!("Error processing file: {e:?}");
                         }
                     }
                 }
             } else {
                 // This should never happen, but keep for safety
                 if debug_mode {
-                    println!("DEBUG: ERROR - File {pathbuf:?} not found in file_term_map but was in all_files");
+                    debug!(
+                        "ERROR - File {pathbuf:?} not found in file_term_map but was in all_files"
+                    );
                 }
             }
 
@@ -1202,8 +1550,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
 
         // After processing batch, check if we should continue
         if debug_mode {
-            println!(
-                "DEBUG: Batch {} completed in {} - Got {} results",
+            debug!(
+                "Batch {} completed in {} - Got {} results",
                 batch_number,
                 format_duration(batch_start.elapsed()),
                 batch_results.len()
@@ -1216,8 +1564,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         // Check if we have enough results or files processed
         if files_processed >= estimated_files_needed {
             if debug_mode {
-                println!(
-                    "DEBUG: Stopping batch processing - processed {files_processed} files (estimated need: {estimated_files_needed})"
+                debug!(
+                    "Stopping batch processing - processed {files_processed} files (estimated need: {estimated_files_needed})"
                 );
             }
             should_continue = false;
@@ -1229,8 +1577,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             let buffered_max_results = (*max_res as f64 * 2.0).ceil() as usize; // 2x buffer for safety
             if final_results.len() > buffered_max_results {
                 if debug_mode {
-                    println!(
-                        "DEBUG: Stopping batch processing - have {} results (2x buffered max_results: {})",
+                    debug!(
+                        "Stopping batch processing - have {} results (2x buffered max_results: {})",
                         final_results.len(),
                         buffered_max_results
                     );
@@ -1239,6 +1587,7 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
             }
         }
     }
+    drop(progress);
 
     let rp_duration = rp_start.elapsed();
     // Calculate the total time spent on detailed result building operations
@@ -1297,71 +1646,97 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         Some(total_block_extraction_result_building_time);
 
     if debug_mode {
-        println!(
-            "DEBUG: Result processing completed in {} - Generated {} results",
+        debug!(
+            "Result processing completed in {} - Generated {} results",
             format_duration(rp_duration),
             final_results.len()
         );
-        println!("DEBUG: Granular result processing timings:");
-        println!("DEBUG:   File I/O: {}", format_duration(total_file_io_time));
-        println!(
-            "DEBUG:   Line collection: {}",
+        debug!("Granular result processing timings:");
+        debug!("  File I/O: {}", format_duration(total_file_io_time));
+        debug!(
+            "  Line collection: {}",
             format_duration(total_line_collection_time)
         );
-        println!(
-            "DEBUG:   AST parsing: {}",
-            format_duration(total_ast_parsing_time)
-        );
-        println!(
-            "DEBUG:     - Language init: {}",
+        debug!("  AST parsing: {}", format_duration(total_ast_parsing_time));
+        debug!(
+            "    - Language init: {}",
             format_duration(total_ast_parsing_language_init_time)
         );
-        println!(
-            "DEBUG:     - Parser init: {}",
+        debug!(
+            "    - Parser init: {}",
             format_duration(total_ast_parsing_parser_init_time)
         );
-        println!(
-            "DEBUG:     - Tree parsing: {}",
+        debug!(
+            "    - Tree parsing: {}",
             format_duration(total_ast_parsing_tree_parsing_time)
         );
-        println!(
-            "DEBUG:     - Line map building: {}",
+        debug!(
+            "    - Line map building: {}",
             format_duration(total_ast_parsing_line_map_building_time)
         );
-        println!(
-            "DEBUG:   Block extraction: {}",
+        debug!(
+            "  Block extraction: {}",
             format_duration(total_block_extraction_time)
         );
-        println!(
-            "DEBUG:     - Code structure finding: {}",
+        debug!(
+            "    - Code structure finding: {}",
             format_duration(total_block_extraction_code_structure_time)
         );
-        println!(
-            "DEBUG:     - Filtering: {}",
+        debug!(
+            "    - Filtering: {}",
             format_duration(total_block_extraction_filtering_time)
         );
-        println!(
-            "DEBUG:     - Result building: {}",
+        debug!(
+            "    - Result building: {}",
             format_duration(total_block_extraction_result_building_time)
         );
-        println!(
-            "DEBUG:   Result building: {}",
-            format_duration(remaining_time)
-        );
+        debug!("  Result building: {}", format_duration(remaining_time));
     }
+    if let Some(blocks) = &within_blocks {
+        final_results.retain(|result| cache::overlaps_session_blocks(result, blocks));
+    }
+
+    // Chunked before ranking so limits can take part of a file instead of all or nothing
+    if *no_whole_files {
+        final_results = file_chunking::chunk_whole_files(final_results, true);
+    }
+
     // Rank results (skip if exact flag is set)
     let rr_start = Instant::now();
     if debug_mode {
         if *exact {
-            println!("DEBUG: Skipping result ranking due to exact flag being set");
+            debug!("Skipping result ranking due to exact flag being set");
         } else {
-            println!("DEBUG: Starting result ranking...");
+            debug!("Starting result ranking...");
+        }
+    }
+
+    for result in final_results.iter_mut() {
+        if filename_only_files.contains(Path::new(&result.file)) {
+            result.matched_by_filename = Some(true);
         }
     }
 
     if !*exact {
-        // Only perform ranking if exact flag is not set
-        rank_search_results(&mut final_results, queries, reranker, *question);
+        // Only perform ranking if exact flag is not set. Regex results are ranked on the
+        // words of the pattern, as its metacharacters mean nothing to the rankers.
+        let regex_words;
+        let rank_queries = if *regex {
+            regex_words = [pattern_words(&combined_query)];
+            &regex_words[..]
+        } else {
+            queries
+        };
+        rank_search_results(&mut final_results, rank_queries, reranker, *question);
+        if *reranker == "hybrid-trigram" {
+            let query = question.map_or_else(|| queries.join(" "), str::to_string);
+            debug!("Fusing with trigram-vector similarity (vector weight {vector_weight})");
+            trigram_vector::fuse_vector_similarity(&mut final_results, &query, *vector_weight);
+        }
+        apply_filename_weight(&mut final_results, *filename_weight);
+        if let Some(source) = boost_recent {
+            apply_recency_boost(&mut final_results, source, *recency_weight);
+        }
 
         // Apply deterministic secondary sort to ensure consistent ordering for results with equal scores
         // This prevents non-deterministic behavior when results have the same ranking score
@@ -1386,23 +1761,27 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
                 }
             }
         });
+        if *stable_order {
+            stabilize_rank_ties(&mut final_results);
+        }
     } else {
         // For exact searches, always apply deterministic sort
         final_results.sort_by(|a, b| (&a.file, a.lines.0).cmp(&(&b.file, b.lines.0)));
     }
 
+    if let Some(per_directory) = diversify {
+        diversify_by_directory(&mut final_results, *per_directory);
+    }
+
     let rr_duration = rr_start.elapsed();
     timings.result_ranking = Some(rr_duration);
 
     if debug_mode {
         if *exact {
-            println!(
-                "DEBUG: Result ranking skipped in {}",
-                format_duration(rr_duration)
-            );
+            debug!("Result ranking skipped in {}", format_duration(rr_duration));
         } else {
-            println!(
-                "DEBUG: Result ranking completed in {}",
+            debug!(
+                "Result ranking completed in {}",
                 format_duration(rr_duration)
             );
         }
@@ -1410,16 +1789,33 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
 
     // We'll move the caching step AFTER limiting results
     let mut skipped_count = early_skipped_count;
-    let filtered_results = final_results;
+    // Collapse vendored/generated copies before limits so they don't use up the budget
+    let filtered_results = if *dedupe_cross_file {
+        dedup::dedupe_cross_file(final_results)
+    } else {
+        final_results
+    };
 
     // Apply limits
     let la_start = Instant::now();
     if debug_mode {
-        println!("DEBUG: Starting limit application...");
+        debug!("Starting limit application...");
     }
 
+    // Page through the ranked results: the limits then pick from after the offset
+    let total_ranked = filtered_results.len();
+    let filtered_results: Vec<SearchResult> = filtered_results.into_iter().skip(*offset).collect();
+
     // First apply limits to the results
-    let mut limited = apply_limits(filtered_results, *max_results, *max_bytes, *max_tokens);
+    let mut limited = apply_limits(
+        filtered_results,
+        *max_results,
+        *max_bytes,
+        *max_tokens,
+        *max_files,
+    );
+    limited.total_ranked = Some(total_ranked);
+    limited.large_files_skipped = large_files;
 
     // Calculate files skipped due to early termination
     let files_skipped_early_termination = total_ranked_files.saturating_sub(files_processed);
@@ -1436,14 +1832,12 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     timings.limit_application = Some(la_duration);
 
     if debug_mode {
-        println!(
-            "DEBUG: Limit application completed in {}",
+        debug!(
+            "Limit application completed in {}",
             format_duration(la_duration)
         );
         if files_skipped_early_termination > 0 {
-            println!(
-                "DEBUG: Files skipped due to early termination: {files_skipped_early_termination}"
-            );
+            debug!("Files skipped due to early termination: {files_skipped_early_termination}");
         }
     }
 
@@ -1451,21 +1845,15 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     let fc_start = Instant::now();
 
     if let Some(session_id) = effective_session {
-        // Get the raw query string for caching
-        let raw_query = if queries.len() > 1 {
-            queries.join(" AND ")
-        } else {
-            queries[0].clone()
-        };
+        // Get the query string the session cache is keyed by
+        let raw_query = cache::session_cache_query(queries, *session_across_queries);
 
         if debug_mode {
-            println!(
-                "DEBUG: Starting final caching for session: {session_id} with query: {raw_query}"
-            );
-            println!("DEBUG: Already skipped {early_skipped_count} lines in early caching");
+            debug!("Starting final caching for session: {session_id} with query: {raw_query}");
+            debug!("Already skipped {early_skipped_count} lines in early caching");
             // Print cache contents before filtering
             if let Err(e) = cache::debug_print_cache(session_id, &raw_query) {
-                eprintln!("Error printing cache: {e}");
+                debug!("Error printing cache: {e}");
             }
         }
 
@@ -1473,9 +1861,9 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         match cache::filter_results_with_cache(&limited.results, session_id, &raw_query) {
             Ok((_, cached_skipped)) => {
                 if debug_mode {
-                    println!("DEBUG: Final caching found {cached_skipped} cached blocks");
-                    println!(
-                        "DEBUG: Total skipped (early + final): {}",
+                    debug!("Final caching found {cached_skipped} cached blocks");
+                    debug!(
+                        "Total skipped (early + final): {}",
                         early_skipped_count + cached_skipped
                     );
                 }
@@ -1489,15 +1877,20 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         }
 
         // Update the cache with the limited results
-        if let Err(e) = cache::add_results_to_cache(&limited.results, session_id, &raw_query) {
+        if let Err(e) = cache::add_results_to_cache(
+            &limited.results,
+            session_id,
+            &raw_query,
+            max_session_entries,
+        ) {
             eprintln!("Error adding results to cache: {e}");
         }
 
         if debug_mode {
-            println!("DEBUG: Added limited results to cache before merging");
+            debug!("Added limited results to cache before merging");
             // Print cache contents after adding new results
             if let Err(e) = cache::debug_print_cache(session_id, &raw_query) {
-                eprintln!("Error printing updated cache: {e}");
+                debug!("Error printing updated cache: {e}");
             }
         }
     }
@@ -1513,8 +1906,8 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     timings.final_caching = Some(fc_duration);
 
     if debug_mode && effective_session.is_some() {
-        println!(
-            "DEBUG: Final caching completed in {}",
+        debug!(
+            "Final caching completed in {}",
             format_duration(fc_duration)
         );
     }
@@ -1522,63 +1915,53 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
     // Optional block merging - AFTER initial caching
     let bm_start = Instant::now();
     if debug_mode && !limited.results.is_empty() && !*no_merge {
-        println!("DEBUG: Starting block merging...");
+        debug!("Starting block merging...");
     }
 
-    let final_results = if !limited.results.is_empty() && !*no_merge {
+    let mut final_results = if !limited.results.is_empty() && !*no_merge {
         use probe_code::search::block_merging::merge_ranked_blocks;
-        let merged = merge_ranked_blocks(limited.results.clone(), *merge_threshold);
+        let merged = merge_ranked_blocks(std::mem::take(&mut limited.results), *merge_threshold);
 
         let bm_duration = bm_start.elapsed();
         timings.block_merging = Some(bm_duration);
 
         if debug_mode {
-            println!(
-                "DEBUG: Block merging completed in {} - Merged result count: {}",
+            debug!(
+                "Block merging completed in {} - Merged result count: {}",
                 format_duration(bm_duration),
                 merged.len()
             );
         }
 
-        // Create the merged results
-        let merged_results = LimitedSearchResults {
-            results: merged.clone(),
-            skipped_files: limited.skipped_files,
-            limits_applied: limited.limits_applied,
-            cached_blocks_skipped: limited.cached_blocks_skipped,
-            files_skipped_early_termination: limited.files_skipped_early_termination,
-        };
-
         // Update the cache with the merged results (after merging)
         if let Some(session_id) = effective_session {
-            // Get the raw query string for caching
-            let raw_query = if queries.len() > 1 {
-                queries.join(" AND ")
-            } else {
-                queries[0].clone()
-            };
+            // Get the query string the session cache is keyed by
+            let raw_query = cache::session_cache_query(queries, *session_across_queries);
 
-            if let Err(e) = cache::add_results_to_cache(&merged, session_id, &raw_query) {
+            if let Err(e) =
+                cache::add_results_to_cache(&merged, session_id, &raw_query, max_session_entries)
+            {
                 eprintln!("Error adding merged results to cache: {e}");
             }
 
             if debug_mode {
-                println!("DEBUG: Added merged results to cache after merging");
+                debug!("Added merged results to cache after merging");
                 // Print cache contents after adding merged results
                 if let Err(e) = cache::debug_print_cache(session_id, &raw_query) {
-                    eprintln!("Error printing updated cache: {e}");
+                    debug!("Error printing updated cache: {e}");
                 }
             }
         }
 
-        merged_results
+        limited.results = merged;
+        limited
     } else {
         let bm_duration = bm_start.elapsed();
         timings.block_merging = Some(bm_duration);
 
         if debug_mode && !*no_merge {
-            println!(
-                "DEBUG: Block merging skipped (no results or disabled) - {}",
+            debug!(
+                "Block merging skipped (no results or disabled) - {}",
                 format_duration(bm_duration)
             );
         }
@@ -1595,6 +1978,68 @@ pub fn perform_probe(options: &SearchOptions) -> Result<LimitedSearchResults> {
         }
     }
 
+    // Merging leaves each file's blocks together, which can then be finished and sent
+    // while the next file is blamed; without merging the blocks are in rank order
+    let results = std::mem::take(&mut final_results.results);
+    let batches: Vec<Vec<SearchResult>> = if *sort != "score" || *reverse {
+        vec![results]
+    } else if *no_merge {
+        results.into_iter().map(|result| vec![result]).collect()
+    } else {
+        let mut batches: Vec<Vec<SearchResult>> = Vec::new();
+        for result in results {
+            match batches.last_mut() {
+                Some(batch) if batch[0].file == result.file => batch.push(result),
+                _ => batches.push(vec![result]),
+            }
+        }
+        batches
+    };
+
+    let mut sent_code = Vec::new();
+    'batches: for mut batch in batches {
+        // Merging can join chunks back into a whole file
+        if *no_whole_files {
+            batch = file_chunking::chunk_whole_files(batch, true);
+        }
+
+        // Trimmed after merging, which needs each block's code to span all of its lines
+        if let Some(max_lines) = max_block_lines {
+            block_trimming::trim_long_blocks(&mut batch, *max_lines);
+        }
+
+        if *blame {
+            blame::annotate_blame(&mut batch);
+        }
+        if *owners {
+            codeowners::annotate_owners(&mut batch);
+        }
+
+        // The consumer's ordering is applied last so limits above still pick by relevance
+        sort_results(&mut batch, sort, *reverse);
+
+        for mut result in batch {
+            if effective_session.is_some() {
+                sent_code.push(result.code.to_string());
+            }
+            archives::map_result_path(&mut result);
+            notebooks::map_result_path(&mut result);
+            if sender.send(result).is_err() {
+                break 'batches;
+            }
+        }
+    }
+
+    // Track the tokens this search returned against the session's running total
+    if let Some(session_id) = effective_session {
+        let code_blocks: Vec<&str> = sent_code.iter().map(String::as_str).collect();
+        let tokens = sum_tokens_with_deduplication(&code_blocks);
+        match cache::record_session_tokens(session_id, tokens) {
+            Ok(total) => final_results.session_tokens = Some(total),
+            Err(e) => eprintln!("Error updating session statistics: {e}"),
+        }
+    }
+
     // Set total search time
     timings.total_search_time = Some(total_start.elapsed());
 
@@ -1629,9 +2074,9 @@ pub fn search_with_structured_patterns(
     let root_path = if let Some(path_str) = root_path_str.to_str() {
         match resolve_path(path_str) {
             Ok(resolved_path) => {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    println!(
-                        "DEBUG: Resolved path '{}' to '{}'",
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!(
+                        "Resolved path '{}' to '{}'",
                         path_str,
                         resolved_path.display()
                     );
@@ -1639,8 +2084,8 @@ pub fn search_with_structured_patterns(
                 resolved_path
             }
             Err(err) => {
-                if std::env::var("DEBUG").unwrap_or_default() == "1" {
-                    println!("DEBUG: Failed to resolve path '{path_str}': {err}");
+                if tracing::enabled!(tracing::Level::DEBUG) {
+                    debug!("Failed to resolve path '{path_str}': {err}");
                 }
                 // Fall back to the original path
                 root_path_str.to_path_buf()
@@ -1652,16 +2097,13 @@ pub fn search_with_structured_patterns(
     };
     use crate::search::ripgrep_searcher::RipgrepSearcher;
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
     let search_start = Instant::now();
 
     // Step 1: Create pattern matching infrastructure (SIMD, RipgrepSearcher, or RegexSet)
     if debug_mode {
-        println!("DEBUG: Starting parallel structured pattern search...");
-        println!(
-            "DEBUG: Creating pattern matcher from {} patterns",
-            patterns.len()
-        );
+        debug!("Starting parallel structured pattern search...");
+        debug!("Creating pattern matcher from {} patterns", patterns.len());
     }
 
     // Extract just the patterns for matching
@@ -1671,65 +2113,58 @@ pub fn search_with_structured_patterns(
     let use_simd = crate::search::simd_pattern_matching::is_simd_pattern_matching_enabled()
         && pattern_strings
             .iter()
-            .all(|p| !p.contains(r"\b") && !p.contains("(?i)"));
+            .all(|p| !p.contains(r"\b") && !p.contains("(?i)") && !p.contains("(?-i"));
 
     let simd_matcher = if use_simd {
         if debug_mode {
-            println!(
-                "DEBUG: Using SIMD pattern matching for {} patterns",
+            debug!(
+                "Using SIMD pattern matching for {} patterns",
                 pattern_strings.len()
             );
         }
         Some(SimdPatternMatcher::with_patterns(pattern_strings.clone()))
     } else {
         if debug_mode {
-            println!("DEBUG: Using RipgrepSearcher for complex patterns");
+            debug!("Using RipgrepSearcher for complex patterns");
         }
         None
     };
 
-    // Create RipgrepSearcher as fallback when SIMD is not available
-    let searcher = if !use_simd {
-        // Format patterns for case-insensitive ripgrep search
-        let formatted_patterns: Vec<String> =
-            pattern_strings.iter().map(|p| format!("(?i){p}")).collect();
-        Some(RipgrepSearcher::new(&formatted_patterns, true)?)
-    } else {
-        None
-    };
-
     // Create a mapping from pattern index to term indices
     let pattern_to_terms: Vec<HashSet<usize>> =
         patterns.iter().map(|(_, terms)| terms.clone()).collect();
 
-    if debug_mode {
-        if use_simd {
-            println!("DEBUG: SIMD pattern matcher created successfully");
-        } else {
-            println!("DEBUG: RipgrepSearcher created successfully with SIMD optimizations");
-        }
+    if debug_mode && use_simd {
+        debug!("SIMD pattern matcher created successfully");
     }
 
     // Step 2: Get filtered file list from cache
     if debug_mode {
-        println!("DEBUG: Getting filtered file list from cache");
-        println!("DEBUG: Custom ignore patterns: {:?}", config.custom_ignores);
+        debug!("Getting filtered file list from cache");
+        debug!("Custom ignore patterns: {:?}", config.custom_ignores);
     }
 
     // Use file_list_cache to get a filtered list of files, with language filtering if specified
-    let initial_file_list = crate::search::file_list_cache::get_file_list_by_language(
-        &root_path,
-        config.allow_tests,
-        config.custom_ignores,
-        config.language,
-        config.no_gitignore,
-    )?;
+    let initial_file_list = match config.files {
+        Some(files) => Arc::new(probe_code::search::file_list_cache::FileList {
+            files: files.to_vec(),
+            large_files: Vec::new(),
+            created_at: Instant::now(),
+        }),
+        None => crate::search::file_list_cache::get_file_list_by_language(
+            &root_path,
+            config.allow_tests,
+            config.custom_ignores,
+            config.language,
+            config.no_gitignore,
+        )?,
+    };
 
     // Apply search filters to further filter the file list
     let filtered_files = if !search_filters.is_empty() {
         if debug_mode {
-            println!(
-                "DEBUG: Applying search filters to {} files",
+            debug!(
+                "Applying search filters to {} files",
                 initial_file_list.files.len()
             );
         }
@@ -1740,7 +2175,7 @@ pub fn search_with_structured_patterns(
             .filter(|file_path| {
                 let matches = search_filters.matches_file(file_path);
                 if debug_mode && !matches {
-                    println!("DEBUG: Filter excluded file: {file_path:?}");
+                    debug!("Filter excluded file: {file_path:?}");
                 }
                 matches
             })
@@ -1748,8 +2183,8 @@ pub fn search_with_structured_patterns(
             .collect();
 
         if debug_mode {
-            println!(
-                "DEBUG: Search filters kept {} out of {} files",
+            debug!(
+                "Search filters kept {} out of {} files",
                 filtered.len(),
                 initial_file_list.files.len()
             );
@@ -1758,26 +2193,70 @@ pub fn search_with_structured_patterns(
         filtered
     } else {
         if debug_mode {
-            println!(
-                "DEBUG: No search filters active, using all {} files",
+            debug!(
+                "No search filters active, using all {} files",
                 initial_file_list.files.len()
             );
         }
         initial_file_list.files.clone()
     };
 
+    // Skip files whose trigram filter in the file index rules out every pattern
+    let filtered_files = match (
+        LiteralFilter::new(&pattern_strings),
+        file_index::trigram_filters(
+            &root_path,
+            config.allow_tests,
+            config.custom_ignores,
+            config.no_gitignore,
+        ),
+    ) {
+        (Some(literals), Some(filters)) => {
+            let total = filtered_files.len();
+            let candidates: Vec<PathBuf> = filtered_files
+                .into_par_iter()
+                .filter(|file_path| filters.may_match(file_path, &literals))
+                .collect();
+            if debug_mode {
+                debug!(
+                    "Trigram filters ruled out {} of {} files",
+                    total - candidates.len(),
+                    total
+                );
+            }
+            candidates
+        }
+        _ => filtered_files,
+    };
+
     // Create a new file list structure with the filtered files
     let file_list = probe_code::search::file_list_cache::FileList {
         files: filtered_files,
+        large_files: Vec::new(),
         created_at: initial_file_list.created_at,
     };
 
+    // Create RipgrepSearcher as fallback when SIMD is not available, batching its
+    // patterns for the number of files to scan
+    let searcher = if !use_simd {
+        // Format patterns for case-insensitive ripgrep search
+        let formatted_patterns: Vec<String> =
+            pattern_strings.iter().map(|p| format!("(?i){p}")).collect();
+        let searcher = RipgrepSearcher::for_files(&formatted_patterns, file_list.files.len())?;
+        if debug_mode {
+            debug!("RipgrepSearcher created successfully with SIMD optimizations");
+        }
+        Some(searcher)
+    } else {
+        None
+    };
+
     if debug_mode {
-        println!("DEBUG: Got {} files after filtering", file_list.files.len());
+        debug!("Got {} files after filtering", file_list.files.len());
         if use_simd {
-            println!("DEBUG: Starting parallel file processing with SIMD");
+            debug!("Starting parallel file processing with SIMD");
         } else {
-            println!("DEBUG: Starting parallel file processing with ripgrep");
+            debug!("Starting parallel file processing with ripgrep");
         }
     }
 
@@ -1791,10 +2270,15 @@ pub fn search_with_structured_patterns(
         let mut sorted_files = file_list.files.clone();
         sorted_files.sort();
 
+        let progress = Progress::new("Scanning files", sorted_files.len());
         // Collect results in parallel first, then sort for deterministic order
         let results_vec: Vec<_> = sorted_files
             .par_iter()
             .filter_map(|file_path| {
+                if interrupt::is_interrupted() {
+                    return None;
+                }
+                progress.inc();
                 let simd_matcher = Arc::clone(&simd_matcher);
                 let pattern_to_terms = Arc::clone(&pattern_to_terms);
 
@@ -1803,8 +2287,8 @@ pub fn search_with_structured_patterns(
                     Ok(term_map) => {
                         if !term_map.is_empty() {
                             if debug_mode {
-                                println!(
-                                    "DEBUG: File {:?} matched patterns with {} term indices",
+                                debug!(
+                                    "File {:?} matched patterns with {} term indices",
                                     file_path,
                                     term_map.len()
                                 );
@@ -1816,7 +2300,7 @@ pub fn search_with_structured_patterns(
                     }
                     Err(e) => {
                         if debug_mode {
-                            println!("DEBUG: Error searching file {file_path:?}: {e:?}");
+                            debug!("Error searching file {file_path:?}: {e:?}");
                         }
                         None
                     }
@@ -1843,8 +2327,8 @@ pub fn search_with_structured_patterns(
     let total_duration = search_start.elapsed();
 
     if debug_mode {
-        println!(
-            "DEBUG: Parallel ripgrep search completed in {} - Found matches in {} files",
+        debug!(
+            "Parallel ripgrep search completed in {} - Found matches in {} files",
             format_duration(total_duration),
             result.len()
         );
@@ -1861,7 +2345,7 @@ fn search_file_with_simd(
     pattern_to_terms: &[HashSet<usize>],
 ) -> Result<HashMap<usize, HashSet<usize>>> {
     let mut term_map = HashMap::new();
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Define a reasonable maximum file size (e.g., 1MB)
     const MAX_FILE_SIZE: u64 = 1024 * 1024;
@@ -1871,7 +2355,7 @@ fn search_file_with_simd(
         Ok(path) => path,
         Err(e) => {
             if debug_mode {
-                println!("DEBUG: Error resolving path for {file_path:?}: {e:?}");
+                debug!("Error resolving path for {file_path:?}: {e:?}");
             }
             return Err(anyhow::anyhow!("Failed to resolve file path: {}", e));
         }
@@ -1882,7 +2366,7 @@ fn search_file_with_simd(
         Ok(meta) => meta,
         Err(e) => {
             if debug_mode {
-                println!("DEBUG: Error getting metadata for {resolved_path:?}: {e:?}");
+                debug!("Error getting metadata for {resolved_path:?}: {e:?}");
             }
             return Err(anyhow::anyhow!("Failed to get file metadata: {}", e));
         }
@@ -1891,8 +2375,8 @@ fn search_file_with_simd(
     // Check if the file is too large
     if metadata.len() > MAX_FILE_SIZE {
         if debug_mode {
-            println!(
-                "DEBUG: Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
+            debug!(
+                "Skipping file {:?} - file too large ({} bytes > {} bytes limit)",
                 resolved_path,
                 metadata.len(),
                 MAX_FILE_SIZE
@@ -1910,8 +2394,8 @@ fn search_file_with_simd(
         Ok(content) => content,
         Err(e) => {
             if debug_mode {
-                println!(
-                    "DEBUG: Error reading file {:?}: {:?} (size: {} bytes)",
+                debug!(
+                    "Error reading file {:?}: {:?} (size: {} bytes)",
                     resolved_path,
                     e,
                     metadata.len()
@@ -1926,8 +2410,8 @@ fn search_file_with_simd(
         // Skip lines that are too long
         if line.len() > 2000 {
             if debug_mode {
-                println!(
-                    "DEBUG: Skipping line {} in file {:?} - line too long ({} characters)",
+                debug!(
+                    "Skipping line {} in file {:?} - line too long ({} characters)",
                     line_number + 1,
                     file_path,
                     line.len()
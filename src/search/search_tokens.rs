@@ -1,4 +1,7 @@
 use dashmap::DashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tiktoken_rs::{p50k_base, CoreBPE};
@@ -21,16 +24,15 @@ struct BlockTokenCacheConfig {
     max_entries: usize,
     /// Minimum block size to cache (bytes) - lower than token cache since blocks are larger
     min_block_size: usize,
-    /// Maximum age for block cache entries (seconds) - longer since blocks change less frequently
-    max_age_seconds: u64,
 }
 
 impl Default for BlockTokenCacheConfig {
     fn default() -> Self {
         Self {
-            max_entries: 2000, // Larger cache for blocks since they're more expensive to compute
+            // An entry is a hash and two numbers, so a long-running `probe serve` or
+            // `probe mcp` can keep the counts of every block it has returned
+            max_entries: 100_000,
             min_block_size: 20, // Cache smaller blocks since they represent meaningful code units
-            max_age_seconds: 7200, // 2 hour cache lifetime for blocks (longer than content cache)
         }
     }
 }
@@ -61,7 +63,7 @@ struct BlockTokenCacheEntry {
     /// Optional: pre-computed tokenized content (for advanced use cases)
     /// Currently not stored to save memory, but could be added for further optimization
     _tokenized_content: Option<Vec<String>>,
-    /// Timestamp when this entry was last accessed (for LRU eviction)
+    /// Access sequence number when this entry was last used (for LRU eviction)
     last_accessed: u64,
     /// Content hash for validation (to detect if content changed)
     content_hash: String,
@@ -81,11 +83,14 @@ struct TokenCountCache {
 /// - Content-based cache keys using SHA-256 hashing for reliable content identification
 /// - Block-level granularity reduces cache misses compared to line-by-line caching
 /// - Optimized for repeated tokenization of the same code blocks across queries
-/// - LRU eviction with configurable size and TTL limits
+/// - Entries live for the whole process, with LRU eviction beyond a size limit
+/// - Optionally persisted on disk between runs (`--token-cache`)
 /// - Thread-safe concurrent access using DashMap
 struct BlockTokenCache {
     cache: DashMap<String, BlockTokenCacheEntry>,
     config: BlockTokenCacheConfig,
+    /// Source of the access sequence numbers
+    clock: AtomicU64,
 }
 
 impl TokenCountCache {
@@ -189,6 +194,7 @@ impl BlockTokenCache {
         Self {
             cache: DashMap::new(),
             config: BlockTokenCacheConfig::default(),
+            clock: AtomicU64::new(0),
         }
     }
 
@@ -209,44 +215,46 @@ impl BlockTokenCache {
         format!("{:016x}", hasher.finish())
     }
 
-    /// Get current timestamp in seconds since epoch
-    fn current_timestamp() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs()
+    /// Next access sequence number
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed)
     }
 
-    /// Clean expired and excess entries from block cache
-    /// This is more aggressive than the regular token cache cleanup since blocks
-    /// take more memory and we want to keep the cache lean
+    /// Evict the least recently used entries once the cache is over its size limit.
+    /// Counts don't go stale (they only depend on the content), so nothing expires.
     fn cleanup(&self) {
-        let current_time = Self::current_timestamp();
-        let max_age = self.config.max_age_seconds;
-
-        // Remove expired entries first
-        self.cache
-            .retain(|_, entry| current_time.saturating_sub(entry.last_accessed) < max_age);
-
-        // If still over limit, remove oldest entries (LRU eviction)
-        if self.cache.len() > self.config.max_entries {
-            let mut entries: Vec<_> = self
-                .cache
-                .iter()
-                .map(|item| (item.key().clone(), item.value().last_accessed))
-                .collect();
+        if self.cache.len() <= self.config.max_entries {
+            return;
+        }
+        let mut entries: Vec<_> = self
+            .cache
+            .iter()
+            .map(|item| (item.key().clone(), item.value().last_accessed))
+            .collect();
 
-            // Sort by access time (oldest first)
-            entries.sort_by_key(|(_, timestamp)| *timestamp);
+        // Sort by access time (oldest first)
+        entries.sort_by_key(|(_, last_accessed)| *last_accessed);
 
-            // Remove oldest entries to get under limit
-            let to_remove = self.cache.len() - self.config.max_entries;
-            for (key, _) in entries.into_iter().take(to_remove) {
-                self.cache.remove(&key);
-            }
+        // Remove a tenth more than needed so eviction doesn't run on every insertion
+        let to_remove = self.cache.len() - self.config.max_entries * 9 / 10;
+        for (key, _) in entries.into_iter().take(to_remove) {
+            self.cache.remove(&key);
         }
     }
 
+    /// Add a count read from disk, unless the block is already cached
+    fn insert_loaded(&self, content_hash: String, token_count: usize) {
+        let last_accessed = self.tick();
+        self.cache
+            .entry(content_hash.clone())
+            .or_insert(BlockTokenCacheEntry {
+                token_count,
+                _tokenized_content: None,
+                last_accessed,
+                content_hash,
+            });
+    }
+
     /// Get or compute token count for a block with block-level caching
     ///
     /// This method implements the core block-level pre-tokenization caching optimization.
@@ -270,7 +278,7 @@ impl BlockTokenCache {
         }
 
         let content_hash = Self::hash_block_content(block_content);
-        let current_time = Self::current_timestamp();
+        let current_time = self.tick();
 
         // Try to get from cache first
         if let Some(mut entry) = self.cache.get_mut(&content_hash) {
@@ -319,6 +327,9 @@ static TOKEN_CACHE: OnceLock<TokenCountCache> = OnceLock::new();
 /// Global block-level token cache instance for block pre-tokenization caching
 static BLOCK_TOKEN_CACHE: OnceLock<BlockTokenCache> = OnceLock::new();
 
+/// File the block token counts are kept in between runs, set with `--token-cache`
+static TOKEN_CACHE_FILE: OnceLock<PathBuf> = OnceLock::new();
+
 /// Get reference to the global token cache
 fn get_token_cache() -> &'static TokenCountCache {
     TOKEN_CACHE.get_or_init(TokenCountCache::new)
@@ -329,7 +340,58 @@ fn get_block_token_cache() -> &'static BlockTokenCache {
     BLOCK_TOKEN_CACHE.get_or_init(BlockTokenCache::new)
 }
 
+/// Where `--token-cache` keeps token counts: `~/.cache/probe/token-counts`
+pub fn default_token_cache_file() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("token-counts")
+}
+
+/// Keep block token counts in `file` between runs: the counts saved there by earlier
+/// runs are loaded now, and [`save_token_cache`] writes the cache back. A missing or
+/// unreadable file starts an empty cache.
+///
+/// Returns `false` if a file was already set.
+pub fn enable_token_cache_file(file: PathBuf) -> bool {
+    if let Ok(content) = std::fs::read_to_string(&file) {
+        let cache = get_block_token_cache();
+        for line in content.lines() {
+            if let Some((hash, count)) = line.split_once(' ') {
+                if let Ok(count) = count.parse() {
+                    cache.insert_loaded(hash.to_string(), count);
+                }
+            }
+        }
+    }
+    TOKEN_CACHE_FILE.set(file).is_ok()
+}
+
+/// Write the block token counts to the file set with [`enable_token_cache_file`], if any
+pub fn save_token_cache() -> std::io::Result<()> {
+    let Some(file) = TOKEN_CACHE_FILE.get() else {
+        return Ok(());
+    };
+    let cache = get_block_token_cache();
+    let mut content = Vec::with_capacity(cache.cache.len() * 24);
+    for entry in cache.cache.iter() {
+        writeln!(content, "{} {}", entry.key(), entry.value().token_count)?;
+    }
+    write_atomically(file, &content)
+}
+
+/// Replace `file` with `content` so concurrent runs never read half a file
+fn write_atomically(file: &Path, content: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp = file.with_extension(format!("tmp{}", std::process::id()));
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, file)
+}
+
 /// Returns a reference to the tiktoken tokenizer
+///
+/// Building it takes longer than a whole small search, so it is only built when a count
+/// is missing from the caches; empty text counts as no tokens without it.
 pub fn get_tokenizer() -> &'static CoreBPE {
     static TOKENIZER: OnceLock<CoreBPE> = OnceLock::new();
     TOKENIZER.get_or_init(|| p50k_base().expect("Failed to initialize tiktoken tokenizer"))
@@ -343,8 +405,9 @@ pub fn get_tokenizer() -> &'static CoreBPE {
 /// **Block-level caching (for larger content >= 100 bytes):**
 /// - Designed for code blocks that are tokenized multiple times across operations
 /// - Uses content hashing with collision detection for reliable cache keys
-/// - Longer TTL (2 hours) since code blocks change less frequently
-/// - Larger cache size (2000 entries) for better hit rates
+/// - Kept for the whole process (and between runs with `--token-cache`), since a count
+///   only depends on the content
+/// - Larger cache size (100,000 entries) for better hit rates
 /// - Optimized for reuse across search limiting, output formatting, etc.
 ///
 /// **Content-level caching (for smaller content 50-99 bytes):**
@@ -358,14 +421,18 @@ pub fn get_tokenizer() -> &'static CoreBPE {
 /// Performance optimizations:
 /// - Dual-tier caching strategy based on content characteristics
 /// - Thread-safe caching using DashMap for concurrent access
-/// - LRU eviction with configurable size and TTL limits
+/// - LRU eviction with configurable size limits
 /// - Content-based cache keys with hash collision detection
 /// - Size-based caching thresholds to optimize performance vs. memory usage
 ///
 /// Cache configuration:
-/// - Block cache: 2000 entries, 20+ bytes, 2-hour TTL, cleanup every 50 insertions
+/// - Block cache: 100,000 entries, 20+ bytes, no expiry, cleanup every 50 insertions
 /// - Content cache: 1000 entries, 50+ bytes, 1-hour TTL, cleanup every 100 insertions
 pub fn count_tokens(text: &str) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+
     // Use block-level caching for larger content (typical code blocks)
     // This provides better cache hit rates for code blocks that are tokenized
     // multiple times across different operations (limiting, output formatting, etc.)
@@ -403,6 +470,10 @@ pub fn count_tokens(text: &str) -> usize {
 /// - Formatting output where the same blocks may be tokenized multiple times
 /// - Any scenario where code blocks might be tokenized more than once
 pub fn count_block_tokens(block_content: &str) -> usize {
+    if block_content.is_empty() {
+        return 0;
+    }
+
     let block_cache = get_block_token_cache();
     block_cache.get_or_compute_block_tokens(block_content, |content| {
         let tokenizer = get_tokenizer();
@@ -466,17 +537,10 @@ pub fn batch_count_tokens_with_deduplication(content_blocks: &[&str]) -> Vec<usi
     // Process only unique content blocks to minimize expensive tiktoken calls
     let mut unique_token_counts: Vec<usize> = Vec::with_capacity(unique_contents.len());
 
-    // Get tokenizer and block cache references once for efficiency
-    let tokenizer = get_tokenizer();
-    let block_cache = get_block_token_cache();
-
     for &unique_content in &unique_contents {
         // Use existing block-level caching for each unique content piece
         // This ensures compatibility with existing cache infrastructure
-        let token_count = block_cache.get_or_compute_block_tokens(unique_content, |content| {
-            tokenizer.encode_with_special_tokens(content).len()
-        });
-        unique_token_counts.push(token_count);
+        unique_token_counts.push(count_block_tokens(unique_content));
     }
 
     // RESULT DISTRIBUTION PHASE:
@@ -671,6 +735,13 @@ mod tests {
         assert_eq!(cache_size, 0);
     }
 
+    #[test]
+    fn test_empty_text_has_no_tokens() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_block_tokens(""), 0);
+        assert_eq!(sum_tokens_with_deduplication(&["", ""]), 0);
+    }
+
     #[test]
     fn test_count_tokens_consistency() {
         // Test that our cached count_tokens function returns same results as direct tiktoken
@@ -745,4 +816,27 @@ mod tests {
 
         assert!(first_result > 0);
     }
+
+    #[test]
+    fn test_token_cache_file_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("token-counts");
+        let content = "fn cached_on_disk() { return 1234; }";
+        let hash = BlockTokenCache::hash_block_content(content);
+        // A saved count is used as is, without tokenizing the block
+        std::fs::write(&file, format!("{hash} 999\nnot an entry\n")).unwrap();
+
+        assert!(enable_token_cache_file(file.clone()));
+        assert_eq!(count_block_tokens(content), 999);
+
+        let other = "fn counted_in_this_run() { return 5678; }";
+        let other_count = count_block_tokens(other);
+        save_token_cache().unwrap();
+        let saved = std::fs::read_to_string(&file).unwrap();
+        assert!(saved.lines().any(|line| line == format!("{hash} 999")));
+        let other_hash = BlockTokenCache::hash_block_content(other);
+        assert!(saved
+            .lines()
+            .any(|line| line == format!("{other_hash} {other_count}")));
+    }
 }
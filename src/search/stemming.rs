@@ -0,0 +1,484 @@
+//! Stemmer selection for tokenization.
+//!
+//! Indexing (document tokenization) and query processing share one process-wide
+//! stemmer, chosen once at startup with `--stemmer`, the `PROBE_STEMMER` environment
+//! variable or a `stemmer = "..."` setting in a config file (see `project_config`).
+//! The default is the Snowball English stemmer.
+
+use rust_stemmers::{Algorithm, Stemmer};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The stemming algorithm applied to tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StemmerKind {
+    /// The original Porter (1980) algorithm
+    Porter,
+    /// A Snowball stemmer (English is the default; other languages for non-English code)
+    Snowball(Algorithm),
+    /// No stemming
+    None,
+}
+
+impl Default for StemmerKind {
+    fn default() -> Self {
+        StemmerKind::Snowball(Algorithm::English)
+    }
+}
+
+const SNOWBALL_LANGUAGES: &[(&str, &str, Algorithm)] = &[
+    ("ar", "arabic", Algorithm::Arabic),
+    ("da", "danish", Algorithm::Danish),
+    ("nl", "dutch", Algorithm::Dutch),
+    ("en", "english", Algorithm::English),
+    ("fi", "finnish", Algorithm::Finnish),
+    ("fr", "french", Algorithm::French),
+    ("de", "german", Algorithm::German),
+    ("el", "greek", Algorithm::Greek),
+    ("hu", "hungarian", Algorithm::Hungarian),
+    ("it", "italian", Algorithm::Italian),
+    ("no", "norwegian", Algorithm::Norwegian),
+    ("pt", "portuguese", Algorithm::Portuguese),
+    ("ro", "romanian", Algorithm::Romanian),
+    ("ru", "russian", Algorithm::Russian),
+    ("es", "spanish", Algorithm::Spanish),
+    ("sv", "swedish", Algorithm::Swedish),
+    ("ta", "tamil", Algorithm::Tamil),
+    ("tr", "turkish", Algorithm::Turkish),
+];
+
+impl std::fmt::Display for StemmerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StemmerKind::Porter => write!(f, "porter"),
+            StemmerKind::None => write!(f, "none"),
+            StemmerKind::Snowball(algorithm) => {
+                let code = SNOWBALL_LANGUAGES
+                    .iter()
+                    .find(|(_, _, a)| a == algorithm)
+                    .map_or("en", |(code, _, _)| code);
+                write!(f, "snowball-{code}")
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for StemmerKind {
+    type Err = String;
+
+    /// Parse `porter`, `none`, `snowball-<code>` (e.g. `snowball-en`, `snowball-de`)
+    /// or a language name (`english`, `german`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        match name.as_str() {
+            "porter" => return Ok(StemmerKind::Porter),
+            "none" | "off" => return Ok(StemmerKind::None),
+            _ => {}
+        }
+
+        let language = name.strip_prefix("snowball-").unwrap_or(&name);
+        SNOWBALL_LANGUAGES
+            .iter()
+            .find(|(code, lang, _)| language == *code || language == *lang)
+            .map(|(_, _, algorithm)| StemmerKind::Snowball(*algorithm))
+            .ok_or_else(|| {
+                let languages: Vec<&str> = SNOWBALL_LANGUAGES.iter().map(|(c, _, _)| *c).collect();
+                format!(
+                    "Unknown stemmer '{s}'. Use porter, none, or snowball-<lang> with one of: {}",
+                    languages.join(", ")
+                )
+            })
+    }
+}
+
+/// A configured stemmer
+pub struct ProbeStemmer {
+    kind: StemmerKind,
+    snowball: Option<Stemmer>,
+}
+
+impl ProbeStemmer {
+    pub fn new(kind: StemmerKind) -> Self {
+        let snowball = match kind {
+            StemmerKind::Snowball(algorithm) => Some(Stemmer::create(algorithm)),
+            _ => None,
+        };
+        ProbeStemmer { kind, snowball }
+    }
+
+    pub fn kind(&self) -> StemmerKind {
+        self.kind
+    }
+
+    /// Stem a lowercase token
+    pub fn stem<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        match (&self.kind, &self.snowball) {
+            (StemmerKind::Snowball(_), Some(stemmer)) => stemmer.stem(word),
+            (StemmerKind::Porter, _) => porter_stem(word),
+            _ => Cow::Borrowed(word),
+        }
+    }
+}
+
+static SELECTED_STEMMER: OnceLock<StemmerKind> = OnceLock::new();
+
+/// Select the stemmer for this process.
+///
+/// Must be called before the first token is stemmed; returns `false` if a stemmer
+/// was already in use.
+pub fn set_stemmer(kind: StemmerKind) -> bool {
+    SELECTED_STEMMER.set(kind).is_ok()
+}
+
+/// The stemmer kind in effect: the one set with [`set_stemmer`], else `PROBE_STEMMER`,
+/// else the default
+pub fn selected_stemmer() -> StemmerKind {
+    *SELECTED_STEMMER.get_or_init(|| {
+        std::env::var("PROBE_STEMMER")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    })
+}
+
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read the project config from `path` instead of looking for one above the search
+/// root, for `--config`. Returns `false` if a config file was already set.
+pub fn set_config_file(path: PathBuf) -> bool {
+    CONFIG_FILE.set(path).is_ok()
+}
+
+/// The file set with [`set_config_file`]
+pub fn config_file() -> Option<&'static PathBuf> {
+    CONFIG_FILE.get()
+}
+
+/// The file set with [`set_config_file`], else the nearest `probe.toml`, `.probe.toml`
+/// or `.probe/config.toml` at or above `start`
+pub fn project_config_path(start: &Path) -> Option<PathBuf> {
+    if let Some(path) = CONFIG_FILE.get() {
+        return Some(path.clone());
+    }
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    start.ancestors().find_map(|dir| {
+        [
+            dir.join("probe.toml"),
+            dir.join(".probe.toml"),
+            dir.join(".probe").join("config.toml"),
+        ]
+        .into_iter()
+        .find(|config| config.is_file())
+    })
+}
+
+/// Stem a word with the original Porter algorithm.
+///
+/// Words that are not lowercase ASCII, or are two characters or shorter, are returned
+/// unchanged.
+pub fn porter_stem(word: &str) -> Cow<'_, str> {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Cow::Borrowed(word);
+    }
+
+    let mut porter = Porter {
+        b: word.as_bytes().to_vec(),
+        k: word.len() as isize - 1,
+        j: 0,
+    };
+    porter.step1ab();
+    if porter.k > 0 {
+        porter.step1c();
+        porter.step2();
+        porter.step3();
+        porter.step4();
+        porter.step5();
+    }
+
+    let stemmed = &porter.b[..(porter.k + 1) as usize];
+    if stemmed == word.as_bytes() {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(String::from_utf8_lossy(stemmed).into_owned())
+    }
+}
+
+/// Porter stemmer state: `b[0..=k]` is the current word, `j` a general offset into it
+struct Porter {
+    b: Vec<u8>,
+    k: isize,
+    j: isize,
+}
+
+impl Porter {
+    fn at(&self, i: isize) -> u8 {
+        self.b[i as usize]
+    }
+
+    /// Whether `b[i]` is a consonant
+    fn cons(&self, i: isize) -> bool {
+        match self.at(i) {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.cons(i - 1),
+            _ => true,
+        }
+    }
+
+    /// The number of consonant sequences in `b[0..=j]` (the measure `m`)
+    fn m(&self) -> usize {
+        let mut n = 0;
+        let mut i = 0;
+        loop {
+            if i > self.j {
+                return n;
+            }
+            if !self.cons(i) {
+                break;
+            }
+            i += 1;
+        }
+        i += 1;
+        loop {
+            loop {
+                if i > self.j {
+                    return n;
+                }
+                if self.cons(i) {
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            n += 1;
+            loop {
+                if i > self.j {
+                    return n;
+                }
+                if !self.cons(i) {
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+        }
+    }
+
+    fn vowel_in_stem(&self) -> bool {
+        (0..=self.j).any(|i| !self.cons(i))
+    }
+
+    fn double_consonant(&self, i: isize) -> bool {
+        i >= 1 && self.at(i) == self.at(i - 1) && self.cons(i)
+    }
+
+    /// Whether `b[i-2..=i]` is consonant-vowel-consonant with the last not w, x or y
+    fn cvc(&self, i: isize) -> bool {
+        if i < 2 || !self.cons(i) || self.cons(i - 1) || !self.cons(i - 2) {
+            return false;
+        }
+        !matches!(self.at(i), b'w' | b'x' | b'y')
+    }
+
+    /// Whether the word ends with `s`; sets `j` to the offset before the suffix
+    fn ends(&mut self, s: &str) -> bool {
+        let len = s.len() as isize;
+        if len > self.k + 1 {
+            return false;
+        }
+        let start = (self.k + 1 - len) as usize;
+        if &self.b[start..=self.k as usize] != s.as_bytes() {
+            return false;
+        }
+        self.j = self.k - len;
+        true
+    }
+
+    /// Replace `b[j+1..=k]` with `s`
+    fn set_to(&mut self, s: &str) {
+        let start = (self.j + 1) as usize;
+        self.b.truncate(start);
+        self.b.extend_from_slice(s.as_bytes());
+        self.k = self.j + s.len() as isize;
+    }
+
+    fn replace_if_measured(&mut self, s: &str) {
+        if self.m() > 0 {
+            self.set_to(s);
+        }
+    }
+
+    /// Apply the first rule of `rules` whose suffix matches, if `m() > 0`
+    fn replace_suffix(&mut self, rules: &[(&str, &str)]) {
+        for (suffix, replacement) in rules {
+            if self.ends(suffix) {
+                self.replace_if_measured(replacement);
+                return;
+            }
+        }
+    }
+
+    /// Plurals and -ed / -ing
+    fn step1ab(&mut self) {
+        if self.at(self.k) == b's' {
+            if self.ends("sses") {
+                self.k -= 2;
+            } else if self.ends("ies") {
+                self.set_to("i");
+            } else if self.at(self.k - 1) != b's' {
+                self.k -= 1;
+            }
+        }
+        if self.ends("eed") {
+            if self.m() > 0 {
+                self.k -= 1;
+            }
+        } else if (self.ends("ed") || self.ends("ing")) && self.vowel_in_stem() {
+            self.k = self.j;
+            if self.ends("at") {
+                self.set_to("ate");
+            } else if self.ends("bl") {
+                self.set_to("ble");
+            } else if self.ends("iz") {
+                self.set_to("ize");
+            } else if self.double_consonant(self.k) {
+                if !matches!(self.at(self.k), b'l' | b's' | b'z') {
+                    self.k -= 1;
+                }
+            } else {
+                self.j = self.k;
+                if self.m() == 1 && self.cvc(self.k) {
+                    self.set_to("e");
+                }
+            }
+        }
+    }
+
+    /// Terminal y to i when there is another vowel in the stem
+    fn step1c(&mut self) {
+        if self.ends("y") && self.vowel_in_stem() {
+            let k = self.k as usize;
+            self.b[k] = b'i';
+        }
+    }
+
+    /// Double suffixes to single ones
+    fn step2(&mut self) {
+        self.replace_suffix(&[
+            ("ational", "ate"),
+            ("tional", "tion"),
+            ("enci", "ence"),
+            ("anci", "ance"),
+            ("izer", "ize"),
+            ("bli", "ble"),
+            ("alli", "al"),
+            ("entli", "ent"),
+            ("eli", "e"),
+            ("ousli", "ous"),
+            ("ization", "ize"),
+            ("ation", "ate"),
+            ("ator", "ate"),
+            ("alism", "al"),
+            ("iveness", "ive"),
+            ("fulness", "ful"),
+            ("ousness", "ous"),
+            ("aliti", "al"),
+            ("iviti", "ive"),
+            ("biliti", "ble"),
+            ("logi", "log"),
+        ]);
+    }
+
+    /// -ic-, -full, -ness etc.
+    fn step3(&mut self) {
+        self.replace_suffix(&[
+            ("icate", "ic"),
+            ("ative", ""),
+            ("alize", "al"),
+            ("iciti", "ic"),
+            ("ical", "ic"),
+            ("ful", ""),
+            ("ness", ""),
+        ]);
+    }
+
+    /// -ant, -ence etc. in context <c>vcvc<v>
+    fn step4(&mut self) {
+        const SUFFIXES: &[&str] = &[
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+            "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+        ];
+        for suffix in SUFFIXES {
+            if !self.ends(suffix) {
+                continue;
+            }
+            if *suffix == "ion" && !(self.j >= 0 && matches!(self.at(self.j), b's' | b't')) {
+                continue;
+            }
+            if self.m() > 1 {
+                self.k = self.j;
+            }
+            return;
+        }
+    }
+
+    /// Final -e and -ll
+    fn step5(&mut self) {
+        self.j = self.k;
+        if self.at(self.k) == b'e' {
+            let m = self.m();
+            if m > 1 || (m == 1 && !self.cvc(self.k - 1)) {
+                self.k -= 1;
+            }
+        }
+        if self.at(self.k) == b'l' && self.double_consonant(self.k) && self.m() > 1 {
+            self.k -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_porter_stem() {
+        for (word, expected) in [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("running", "run"),
+            ("hopping", "hop"),
+            ("filing", "file"),
+            ("relational", "relat"),
+            ("generalization", "gener"),
+            ("happiness", "happi"),
+            ("controlling", "control"),
+            ("parser", "parser"),
+            ("is", "is"),
+        ] {
+            assert_eq!(porter_stem(word), expected, "stemming {word}");
+        }
+    }
+
+    #[test]
+    fn test_parse_stemmer_kind() {
+        assert_eq!("porter".parse(), Ok(StemmerKind::Porter));
+        assert_eq!("none".parse(), Ok(StemmerKind::None));
+        assert_eq!(
+            "snowball-en".parse(),
+            Ok(StemmerKind::Snowball(Algorithm::English))
+        );
+        assert_eq!(
+            "German".parse(),
+            Ok(StemmerKind::Snowball(Algorithm::German))
+        );
+        assert!("klingon".parse::<StemmerKind>().is_err());
+
+        let stemmer = ProbeStemmer::new(StemmerKind::Snowball(Algorithm::German));
+        assert_eq!(stemmer.stem("häuser"), "haus");
+        assert_eq!(
+            ProbeStemmer::new(StemmerKind::None).stem("running"),
+            "running"
+        );
+    }
+}
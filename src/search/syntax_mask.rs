@@ -0,0 +1,242 @@
+//! Telling comments and string literals from the rest of the code, for `--no-comments`,
+//! `--comments-only` and `--strings-only`.
+//!
+//! The comment or string literal nodes of a file's tree-sitter tree give the byte ranges
+//! the mask covers. Matches are narrowed to lines inside or outside the mask, and blocks
+//! are tokenized for filtering and ranking with the masked text blanked out, or with
+//! everything but the masked text blanked out.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use tree_sitter::Tree;
+
+/// Where the comments or the string literals of a file are
+#[derive(Debug, Default)]
+pub struct SyntaxMask {
+    /// Byte ranges covered by the mask, in order
+    ranges: Vec<Range<usize>>,
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+}
+
+impl SyntaxMask {
+    /// The comments of `content` according to its tree; none without a tree
+    pub fn comments(content: &str, tree: Option<&Tree>) -> Self {
+        let ranges = tree.map(comment_ranges).unwrap_or_default();
+        Self::new(content, ranges)
+    }
+
+    /// The string literals of `content` according to its tree, without the code
+    /// interpolated into them; none without a tree
+    pub fn strings(content: &str, tree: Option<&Tree>) -> Self {
+        let mut ranges = Vec::new();
+        if let Some(tree) = tree {
+            collect_strings(tree.root_node(), &mut ranges);
+        }
+        Self::new(content, ranges)
+    }
+
+    fn new(content: &str, ranges: Vec<Range<usize>>) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        SyntaxMask {
+            ranges,
+            line_starts,
+        }
+    }
+
+    fn masked(&self, byte: usize) -> bool {
+        let index = self.ranges.partition_point(|range| range.end <= byte);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.start <= byte)
+    }
+
+    /// Byte range of a 1-based line, without its newline
+    fn line_bytes(&self, content: &str, line: usize) -> Range<usize> {
+        let start = self
+            .line_starts
+            .get(line - 1)
+            .copied()
+            .unwrap_or(content.len());
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(content.len(), |next| next - 1);
+        start..end.max(start)
+    }
+
+    /// Whether a 1-based line has masked text (`inside`), or anything else but whitespace
+    fn line_has(&self, content: &str, line: usize, inside: bool) -> bool {
+        let bytes = self.line_bytes(content, line);
+        content.as_bytes()[bytes.clone()]
+            .iter()
+            .zip(bytes)
+            .any(|(byte, index)| !byte.is_ascii_whitespace() && self.masked(index) == inside)
+    }
+
+    /// The matches on lines with masked text (`inside`) or with other code, dropping terms
+    /// left without any
+    pub fn narrow_matches(
+        &self,
+        content: &str,
+        term_matches: &HashMap<usize, HashSet<usize>>,
+        inside: bool,
+    ) -> (HashSet<usize>, HashMap<usize, HashSet<usize>>) {
+        let mut kept_lines = HashMap::new();
+        let term_matches: HashMap<usize, HashSet<usize>> = term_matches
+            .iter()
+            .map(|(&term, lines)| {
+                let lines: HashSet<usize> = lines
+                    .iter()
+                    .copied()
+                    .filter(|&line| {
+                        *kept_lines
+                            .entry(line)
+                            .or_insert_with(|| self.line_has(content, line, inside))
+                    })
+                    .collect();
+                (term, lines)
+            })
+            .filter(|(_, lines)| !lines.is_empty())
+            .collect();
+        let line_numbers = term_matches.values().flatten().copied().collect();
+        (line_numbers, term_matches)
+    }
+
+    /// The text of 1-based lines `start..=end` with either their masked text or everything
+    /// else blanked out, keeping only the masked text when `inside` is set
+    pub fn block_text(&self, content: &str, start: usize, end: usize, inside: bool) -> String {
+        let bytes = self.line_bytes(content, start).start..self.line_bytes(content, end).end;
+        let text: Vec<u8> = content.as_bytes()[bytes.clone()]
+            .iter()
+            .zip(bytes)
+            .map(|(&byte, index)| {
+                if byte == b'\n' || self.masked(index) == inside {
+                    byte
+                } else {
+                    b' '
+                }
+            })
+            .collect();
+        // Nodes start and end on character boundaries, so blanking byte by byte leaves
+        // valid UTF-8
+        String::from_utf8_lossy(&text).into_owned()
+    }
+}
+
+/// Byte ranges of the comment nodes of a tree, in order
+pub fn comment_ranges(tree: &Tree) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    collect_comments(tree.root_node(), &mut ranges);
+    ranges
+}
+
+fn collect_comments(node: tree_sitter::Node, ranges: &mut Vec<Range<usize>>) {
+    // line_comment, block_comment, doc_comment and the plain comment of most grammars
+    if node.kind().ends_with("comment") {
+        ranges.push(node.byte_range());
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_comments(child, ranges);
+    }
+}
+
+/// Whether a node is a string literal: string_literal, raw_string_literal,
+/// interpreted_string_literal, template_string, the plain string of most grammars...
+pub fn is_string(kind: &str) -> bool {
+    kind.ends_with("string")
+        || kind.ends_with("string_literal")
+        || kind == "text_block"
+        || kind == "interpolated_string_expression"
+}
+
+/// Code interpolated into a string: `${...}`, `#{...}`, `{...}` of f-strings
+fn is_interpolation(kind: &str) -> bool {
+    kind.contains("interpolation") || kind.contains("substitution")
+}
+
+fn collect_strings(node: tree_sitter::Node, ranges: &mut Vec<Range<usize>>) {
+    if !is_string(node.kind()) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect_strings(child, ranges);
+        }
+        return;
+    }
+    // The string without its interpolations, whose nested strings are strings again
+    let mut start = node.start_byte();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if is_interpolation(child.kind()) {
+            if start < child.start_byte() {
+                ranges.push(start..child.start_byte());
+            }
+            collect_strings(child, ranges);
+            start = child.end_byte();
+        }
+    }
+    if start < node.end_byte() {
+        ranges.push(start..node.end_byte());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "/// Loads the config\nfn load_config() -> Config {\n    // read config.toml\n    parse(\"config.toml\") // config\n}\n";
+
+    fn mask() -> SyntaxMask {
+        let tree = crate::language::get_or_parse_tree_pooled("comments_test.rs", SOURCE, "rs").ok();
+        SyntaxMask::comments(SOURCE, tree.as_ref())
+    }
+
+    #[test]
+    fn test_narrow_matches() {
+        let mask = mask();
+        let term_matches = HashMap::from([(0, HashSet::from([1, 2, 3, 4]))]);
+
+        let (lines, _) = mask.narrow_matches(SOURCE, &term_matches, false);
+        assert_eq!(lines, HashSet::from([2, 4]));
+
+        let (lines, terms) = mask.narrow_matches(SOURCE, &term_matches, true);
+        assert_eq!(lines, HashSet::from([1, 3, 4]));
+        assert_eq!(terms[&0], lines);
+    }
+
+    #[test]
+    fn test_block_text() {
+        let mask = mask();
+        let code = mask.block_text(SOURCE, 1, 4, false);
+        assert!(!code.contains("Loads") && !code.contains("read"));
+        assert!(code.contains("fn load_config() -> Config {"));
+        assert_eq!(code.lines().count(), 4);
+
+        let comments = mask.block_text(SOURCE, 1, 4, true);
+        assert!(comments.contains("/// Loads the config"));
+        assert!(comments.contains("// config"));
+        assert!(!comments.contains("parse") && !comments.contains("fn"));
+    }
+
+    #[test]
+    fn test_strings_without_interpolations() {
+        let source = "const label = `Hello ${user.name}, ${greet(\"welcome back\")}`;\nlog(\"user \" + user);\n";
+        let tree = crate::language::get_or_parse_tree_pooled("strings_test.js", source, "js").ok();
+        let mask = SyntaxMask::strings(source, tree.as_ref());
+
+        let strings = mask.block_text(source, 1, 2, true);
+        let words: Vec<&str> = strings.split_whitespace().collect();
+        assert_eq!(
+            words,
+            vec!["`Hello", ",", "\"welcome", "back\"", "`", "\"user", "\""]
+        );
+
+        let term_matches = HashMap::from([(0, HashSet::from([1, 2]))]);
+        let (lines, _) = mask.narrow_matches(source, &term_matches, true);
+        assert_eq!(lines, HashSet::from([1, 2]));
+    }
+}
@@ -10,9 +10,13 @@ mod token_limiter_failure_tests {
             file: "test.rs".to_string(),
             lines: (1, 1),
             node_type: "test".to_string(),
-            code: code.to_string(),
+            code: code.into(),
             symbol_signature: None,
             matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
             rank,
             score: None,
             tfidf_score: Some(1.0),
@@ -53,7 +57,7 @@ mod token_limiter_failure_tests {
             .collect();
 
         let token_limit = 100;
-        let limited = apply_limits(results, None, None, Some(token_limit));
+        let limited = apply_limits(results, None, None, Some(token_limit), None);
 
         // Calculate actual token count to verify overrun
         let actual_total_tokens: usize = limited
@@ -148,7 +152,7 @@ mod token_limiter_failure_tests {
             .collect();
 
         let token_limit = 150;
-        let limited = apply_limits(results.clone(), None, None, Some(token_limit));
+        let limited = apply_limits(results.clone(), None, None, Some(token_limit), None);
 
         let reported_tokens = limited
             .limits_applied
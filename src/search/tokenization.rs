@@ -5,6 +5,7 @@ use probe_code::search::simd_tokenization::SimdConfig;
 use probe_code::search::term_exceptions::{is_exception_term, EXCEPTION_TERMS};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Mutex, RwLock};
+use tracing::debug;
 
 /// VOCABULARY CACHE OPTIMIZATION FOR FILTERING:
 /// Enhanced vocabulary cache system specifically optimized for filtering operations.
@@ -873,8 +874,8 @@ pub fn add_special_term(term: &str) {
     special_terms.insert(term.to_lowercase());
 
     // Debug output
-    if std::env::var("DEBUG").unwrap_or_default() == "1" {
-        println!("DEBUG: Added special term: {term}");
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        debug!("Added special term: {term}");
     }
 }
 
@@ -1196,12 +1197,12 @@ pub fn is_special_case(word: &str) -> bool {
     // Convert to lowercase for case-insensitive comparison
     let lowercase = word.to_lowercase();
 
-    let debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     // Check if the word is in the static special case list
     if SPECIAL_CASE_WORDS.contains(&lowercase) {
         if debug_mode {
-            println!("DEBUG: Found static special case: {lowercase}");
+            debug!("Found static special case: {lowercase}");
         }
         return true;
     }
@@ -1223,14 +1224,14 @@ pub fn is_special_case(word: &str) -> bool {
     if special_terms.contains(&lowercase) {
         // Debug output
         if debug_mode {
-            println!("DEBUG: Found dynamic special term: {lowercase}");
+            debug!("Found dynamic special term: {lowercase}");
         }
         return true;
     }
 
     if debug_mode && (lowercase == "github" || lowercase == "issue") {
-        println!(
-            "DEBUG: is_special_case(\"{word}\") = false, special_terms has {} items",
+        debug!(
+            "is_special_case(\"{word}\") = false, special_terms has {} items",
             special_terms.len()
         );
     }
@@ -1939,7 +1940,7 @@ pub fn split_camel_case_with_config(input: &str, config: SimdConfig) -> Vec<Stri
         return crate::search::simd_tokenization::simd_split_camel_case_with_config(input, config);
     }
 
-    let _debug_mode = std::env::var("DEBUG").unwrap_or_default() == "1";
+    let _debug_mode = tracing::enabled!(tracing::Level::DEBUG);
 
     if input.is_empty() {
         return vec![];
@@ -2718,7 +2719,8 @@ pub fn tokenize_and_stem(keyword: &str) -> Vec<String> {
 ///
 /// The tokenization flow follows these steps:
 /// 1. Split input text on whitespace
-/// 2. For each token, further split on non-alphanumeric characters (except for leading "-")
+/// 2. For each token, further split on non-word characters (except for leading "-");
+///    CJK runs are turned into character bigrams
 /// 3. For each resulting token, check if it has mixed case
 /// 4. If it has mixed case, split using camel case rules
 /// 5. For each part, attempt to split compound words
@@ -2739,6 +2741,8 @@ pub fn tokenize(text: &str) -> Vec<String> {
     // OPTIMIZATION: Pre-allocate Vec capacity based on text length heuristics
     let estimated_tokens = (text.len() / 8).clamp(4, 32); // Estimate ~8 chars per token on average
     let mut tokens = Vec::with_capacity(estimated_tokens);
+    // Character bigrams of CJK runs, which bypass camelCase splitting and stemming
+    let mut cjk_grams: Vec<(String, bool)> = Vec::new();
     for word in text.split_whitespace() {
         // Check if this is a negated term
         let is_negated = word.starts_with('-');
@@ -2754,15 +2758,15 @@ pub fn tokenize(text: &str) -> Vec<String> {
         }
 
         for c in chars {
-            if c.is_alphanumeric() {
+            if is_token_char(c) {
                 current_token.push(c);
             } else if !current_token.is_empty() {
-                // We found a non-alphanumeric character, add the current token if not empty
+                // We found a separator (punctuation, emoji, symbol), add the current token
                 if is_negated {
                     // Track this as a negated term
                     negated_terms.insert(current_token.to_lowercase());
                 }
-                tokens.push(current_token);
+                push_token(&mut tokens, &mut cjk_grams, current_token, is_negated);
                 current_token = String::new();
             }
         }
@@ -2773,7 +2777,7 @@ pub fn tokenize(text: &str) -> Vec<String> {
                 // Track this as a negated term
                 negated_terms.insert(current_token.to_lowercase());
             }
-            tokens.push(current_token);
+            push_token(&mut tokens, &mut cjk_grams, current_token, is_negated);
         }
     }
 
@@ -2846,9 +2850,88 @@ pub fn tokenize(text: &str) -> Vec<String> {
         }
     }
 
+    let negated_grams: HashSet<String> = cjk_grams
+        .iter()
+        .filter(|(_, negated)| *negated)
+        .map(|(gram, _)| gram.clone())
+        .collect();
+    for (gram, _) in cjk_grams {
+        if !negated_grams.contains(&gram) && processed_tokens.insert(gram.clone()) {
+            result.push(gram);
+        }
+    }
+
     result
 }
 
+/// Whether `c` is part of a token: letters and digits of any script plus the
+/// combining marks that Unicode identifiers may contain (e.g. `é` written as `e` +
+/// U+0301, Devanagari vowel signs). Underscores, punctuation, symbols and emoji
+/// separate tokens.
+pub fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || (!c.is_ascii() && unicode_ident::is_xid_continue(c))
+}
+
+/// Whether `c` is written without spaces between words (Han, kana, Hangul), so runs
+/// of it are indexed as overlapping character bigrams
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x20000..=0x2FA1F // CJK Extensions B-F, compatibility supplement
+    )
+}
+
+/// Overlapping character bigrams of a CJK run (the run itself if it is one character)
+pub fn cjk_bigrams(run: &str) -> Vec<String> {
+    let chars: Vec<char> = run.chars().collect();
+    if chars.len() < 2 {
+        return vec![run.to_string()];
+    }
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+/// Add a raw token, splitting CJK runs out of it into `cjk_grams`
+fn push_token(
+    tokens: &mut Vec<String>,
+    cjk_grams: &mut Vec<(String, bool)>,
+    token: String,
+    is_negated: bool,
+) {
+    // Combining marks keep a word together but are dropped so camelCase splitting
+    // doesn't treat them as boundaries
+    let token = if token.is_ascii() {
+        token
+    } else {
+        token.chars().filter(|c| c.is_alphanumeric()).collect()
+    };
+    if !token.chars().any(is_cjk) {
+        tokens.push(token);
+        return;
+    }
+
+    let mut run = String::new();
+    let mut run_is_cjk = false;
+    for c in token.chars().chain(std::iter::once('\0')) {
+        let c_is_cjk = is_cjk(c);
+        if c == '\0' || (c_is_cjk != run_is_cjk && !run.is_empty()) {
+            if run_is_cjk {
+                cjk_grams.extend(cjk_bigrams(&run).into_iter().map(|g| (g, is_negated)));
+            } else if !run.is_empty() {
+                tokens.push(std::mem::take(&mut run));
+            }
+            run.clear();
+        }
+        run_is_cjk = c_is_cjk;
+        if c != '\0' {
+            run.push(c);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3248,4 +3331,24 @@ mod tests {
         assert!(queries_with_issues > 0,
             "Expected some queries to have issues with current heuristics (this demonstrates the problem)");
     }
+
+    #[test]
+    fn test_tokenize_unicode_and_cjk() {
+        // CJK runs become overlapping bigrams next to the surrounding identifiers
+        let tokens = tokenize("checkLogin // 用户登录失败");
+        for expected in ["check", "login", "用户", "户登", "登录", "录失", "失败"] {
+            assert!(tokens.contains(&expected.to_string()), "missing {expected}");
+        }
+        assert!(!tokens.contains(&"用户登录失败".to_string()));
+        assert_eq!(tokenize("錯"), vec!["錯"]);
+
+        // Non-ASCII identifiers stay whole, emoji and symbols are separators
+        assert!(tokenize("größe").iter().any(|t| t.starts_with("größ")));
+        // A combining mark does not split the word it belongs to
+        assert_eq!(tokenize("cafe\u{301}teria"), tokenize("cafeteria"));
+        assert_eq!(tokenize("🚀 → ✓"), Vec::<String>::new());
+
+        // Negated CJK words exclude their bigrams
+        assert!(!tokenize("登录 -登录").contains(&"登录".to_string()));
+    }
 }
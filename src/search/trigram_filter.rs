@@ -0,0 +1,166 @@
+//! Per-file trigram bloom filters for picking candidate files without reading them.
+//!
+//! `probe index` stores a small bloom filter of each file's (ASCII case-folded) byte
+//! trigrams next to the index. Before scanning, a search extracts the literals every
+//! match of each of its patterns must contain, and skips files whose filter shows that
+//! none of those literals can occur. Filters only ever rule files out: a literal shorter
+//! than three bytes, or a pattern without a finite set of literals, keeps every file.
+
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use std::collections::HashSet;
+
+/// Bits of filter per distinct trigram; with three hashes about 2% of absent trigrams
+/// test as present
+const BITS_PER_TRIGRAM: usize = 10;
+
+const HASHES: u64 = 3;
+
+/// Filters are at most 128 KiB, so huge files just get a less selective filter
+const MAX_BITS: usize = 1 << 20;
+
+/// A bloom filter of the trigrams of one file's content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrigramBloom {
+    words: Vec<u64>,
+}
+
+impl TrigramBloom {
+    pub fn from_content(content: &[u8]) -> Self {
+        let trigrams: HashSet<u32> = content.windows(3).map(trigram).collect();
+        let bits = (trigrams.len() * BITS_PER_TRIGRAM)
+            .next_power_of_two()
+            .clamp(64, MAX_BITS);
+        let mut bloom = TrigramBloom {
+            words: vec![0; bits / 64],
+        };
+        for trigram in trigrams {
+            for bit in bloom.bit_positions(trigram) {
+                bloom.words[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        bloom
+    }
+
+    /// A filter read back from its words; `None` unless there is a power of two of them
+    pub fn from_words(words: Vec<u64>) -> Option<Self> {
+        words
+            .len()
+            .is_power_of_two()
+            .then_some(TrigramBloom { words })
+    }
+
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Whether `literal` may occur in the file, ignoring ASCII case. Only trigrams made
+    /// of ASCII bytes are checked, since other bytes may match a different case.
+    pub fn may_contain(&self, literal: &[u8]) -> bool {
+        literal
+            .windows(3)
+            .filter(|window| window.is_ascii())
+            .all(|window| {
+                self.bit_positions(trigram(window))
+                    .all(|bit| self.words[bit / 64] & (1 << (bit % 64)) != 0)
+            })
+    }
+
+    fn bit_positions(&self, trigram: u32) -> impl Iterator<Item = usize> {
+        let mask = (self.words.len() * 64 - 1) as u64;
+        let hash = splitmix64(trigram as u64);
+        let (first, step) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        (0..HASHES).map(move |i| (first.wrapping_add(i.wrapping_mul(step)) & mask) as usize)
+    }
+}
+
+fn trigram(window: &[u8]) -> u32 {
+    u32::from_be_bytes([
+        0,
+        window[0].to_ascii_lowercase(),
+        window[1].to_ascii_lowercase(),
+        window[2].to_ascii_lowercase(),
+    ])
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// The literals each search pattern needs one of to match, for testing filters
+#[derive(Debug, Clone)]
+pub struct LiteralFilter {
+    patterns: Vec<Vec<Vec<u8>>>,
+}
+
+impl LiteralFilter {
+    /// `None` when some pattern has no finite set of literals, so no file can be ruled out
+    pub fn new(patterns: &[String]) -> Option<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| pattern_literals(pattern))
+            .collect::<Option<Vec<_>>>()?;
+        Some(LiteralFilter { patterns })
+    }
+
+    /// Whether any pattern may match in the file with this filter
+    pub fn may_match(&self, bloom: &TrigramBloom) -> bool {
+        self.patterns
+            .iter()
+            .flatten()
+            .any(|literal| bloom.may_contain(literal))
+    }
+}
+
+/// Lowercased literals one of which starts every match of `pattern`
+fn pattern_literals(pattern: &str) -> Option<Vec<Vec<u8>>> {
+    // Case is folded when building and testing filters, so drop the case-insensitive
+    // flags the query patterns carry rather than extracting every case variant
+    let pattern = pattern
+        .replace("(?i)", "")
+        .replace("(?i:", "(?:")
+        .replace("(?-i:", "(?:");
+    let hir = regex_syntax::Parser::new().parse(&pattern).ok()?;
+    let seq = Extractor::new().kind(ExtractKind::Prefix).extract(&hir);
+    let literals = seq.literals()?;
+    Some(
+        literals
+            .iter()
+            .map(|literal| literal.as_bytes().to_ascii_lowercase())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_rules_out_missing_literals() {
+        let bloom = TrigramBloom::from_content(b"fn parse_Config(path: &Path) {}");
+        assert!(bloom.may_contain(b"parse_config"));
+        assert!(bloom.may_contain(b"PATH"));
+        assert!(bloom.may_contain(b"fn"));
+        assert!(!bloom.may_contain(b"tokenizer"));
+        assert_eq!(
+            TrigramBloom::from_words(bloom.words().to_vec()),
+            Some(bloom)
+        );
+    }
+
+    #[test]
+    fn test_literal_filter() {
+        let bloom = TrigramBloom::from_content(b"struct ConfigLoader;");
+        let filter =
+            LiteralFilter::new(&[r"(?i)\bconfig".to_string(), "(?-i:Loader)".to_string()]).unwrap();
+        assert!(filter.may_match(&bloom));
+
+        let filter = LiteralFilter::new(&["(?i)(parser|lexer)".to_string()]).unwrap();
+        assert!(!filter.may_match(&bloom));
+
+        // A pattern that can start with anything rules nothing out
+        assert!(LiteralFilter::new(&[r"\w+config".to_string()]).is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Lexical similarity of hashed token and trigram vectors, and its fusion with BM25 for
+//! `--reranker hybrid-trigram`.
+//!
+//! There is no model: each stemmed, identifier-split token and each character trigram
+//! of it is hashed into a fixed-size vector (the "hashing trick"), which is then
+//! L2-normalized. Similarity is therefore purely lexical, about shared spellings rather
+//! than meaning. Trigrams let related word forms that BM25 treats as unrelated terms
+//! (`auth`, `authenticate`, `authorization`) land close together, and the token
+//! features keep whole-term matches dominant.
+//!
+//! The BM25 order and the cosine-similarity order are combined with reciprocal rank
+//! fusion, which only looks at ranks and so needs no score normalization.
+
+use probe_code::models::SearchResult;
+use probe_code::search::tokenization;
+
+/// Dimensions of a vector
+pub const VECTOR_DIM: usize = 512;
+
+/// Damping constant of reciprocal rank fusion; 60 is the value from the original paper
+const RRF_K: f64 = 60.0;
+
+/// Weight of a trigram feature relative to a whole-token feature
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+/// FNV-1a, so vectors are the same across runs and platforms
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Add a hashed feature; one hash bit picks the sign so collisions tend to cancel out
+fn add_feature(vector: &mut [f32], feature: &[u8], weight: f32) {
+    let hash = fnv1a(feature);
+    let index = (hash % VECTOR_DIM as u64) as usize;
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+/// Hash `text` into an L2-normalized vector of `VECTOR_DIM` dimensions. Text without
+/// any tokens is the zero vector.
+pub fn vectorize(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; VECTOR_DIM];
+    for token in tokenization::tokenize(text) {
+        add_feature(&mut vector, token.as_bytes(), 1.0);
+        let padded: Vec<u8> = [b"#", token.as_bytes(), b"#"].concat();
+        for trigram in padded.windows(3) {
+            add_feature(&mut vector, trigram, TRIGRAM_WEIGHT);
+        }
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// Cosine similarity of two vectors returned by [`vectorize`]
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Re-rank BM25-ranked `results` by fusing their rank with their rank by trigram-vector
+/// similarity to `query`. `vector_weight` (0.0 to 1.0) is the share of the vector rank
+/// in the fused score: 0 keeps the BM25 order, 1 orders by similarity alone.
+///
+/// Results without a rank (dropped by the BM25 ranker) keep their place at the end.
+pub fn fuse_vector_similarity(results: &mut [SearchResult], query: &str, vector_weight: f64) {
+    let vector_weight = vector_weight.clamp(0.0, 1.0);
+    let ranked = results
+        .iter()
+        .take_while(|r| r.rank.is_some_and(|rank| rank != usize::MAX))
+        .count();
+    let ranked_results = &mut results[..ranked];
+    if ranked_results.is_empty() {
+        return;
+    }
+
+    // BM25 order, as left by the ranker
+    ranked_results.sort_by_key(|r| r.rank);
+    let query_vector = vectorize(query);
+    let similarities: Vec<f32> = ranked_results
+        .iter()
+        .map(|r| {
+            cosine_similarity(
+                &query_vector,
+                &vectorize(&format!("{}\n{}", r.file, r.code)),
+            )
+        })
+        .collect();
+
+    // Rank by similarity, ties keeping the BM25 order
+    let mut by_similarity: Vec<usize> = (0..ranked_results.len()).collect();
+    by_similarity.sort_by(|&a, &b| similarities[b].total_cmp(&similarities[a]).then(a.cmp(&b)));
+    let mut vector_rank = vec![0; ranked_results.len()];
+    for (rank, &index) in by_similarity.iter().enumerate() {
+        vector_rank[index] = rank + 1;
+    }
+
+    for (index, result) in ranked_results.iter_mut().enumerate() {
+        let bm25_rank = (index + 1) as f64;
+        let fused = (1.0 - vector_weight) / (RRF_K + bm25_rank)
+            + vector_weight / (RRF_K + vector_rank[index] as f64);
+        result.score = Some(fused);
+    }
+    ranked_results.sort_by(|a, b| {
+        b.score
+            .unwrap_or(0.0)
+            .total_cmp(&a.score.unwrap_or(0.0))
+            .then(a.rank.cmp(&b.rank))
+    });
+    for (rank, result) in ranked_results.iter_mut().enumerate() {
+        result.rank = Some(rank + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(file: &str, code: &str, rank: usize) -> SearchResult {
+        SearchResult {
+            file: file.to_string(),
+            lines: (1, 1),
+            node_type: "function_item".to_string(),
+            code: code.into(),
+            symbol_signature: None,
+            matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
+            rank: Some(rank),
+            score: Some(1.0 / rank as f64),
+            tfidf_score: None,
+            bm25_score: None,
+            tfidf_rank: None,
+            bm25_rank: None,
+            new_score: None,
+            file_unique_terms: None,
+            file_total_matches: None,
+            file_match_rank: None,
+            block_unique_terms: None,
+            block_total_matches: None,
+            hybrid2_rank: None,
+            combined_score_rank: None,
+            parent_file_id: None,
+            block_id: None,
+            matched_keywords: None,
+            matched_lines: None,
+            tokenized_content: None,
+            parent_context: None,
+        }
+    }
+
+    #[test]
+    fn test_related_word_forms_are_close() {
+        let query = vectorize("authentication");
+        let related = vectorize("fn authenticate_user(token: &str) -> bool");
+        let unrelated = vectorize("fn render_chart(points: &[f64])");
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+        assert!((cosine_similarity(&query, &query) - 1.0).abs() < 1e-5);
+        assert!(vectorize("").iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_fuse_vector_similarity() {
+        let ranked = || {
+            vec![
+                result("chart.rs", "fn render_chart() {}", 1),
+                result("auth.rs", "fn authenticate_user() {}", 2),
+            ]
+        };
+
+        // Weight 0 keeps the BM25 order, weight 1 orders by similarity alone
+        let mut results = ranked();
+        fuse_vector_similarity(&mut results, "user authentication", 0.0);
+        assert_eq!(results[0].file, "chart.rs");
+
+        let mut results = ranked();
+        fuse_vector_similarity(&mut results, "user authentication", 1.0);
+        assert_eq!(results[0].file, "auth.rs");
+        assert_eq!(results[0].rank, Some(1));
+        assert!(results[0].score > results[1].score);
+    }
+}
@@ -0,0 +1,357 @@
+//! On-disk vector store of symbol-level chunks, for `probe similar`.
+//!
+//! Every symbol of `extract::symbols` is a chunk, hashed into a token and trigram
+//! vector with `trigram_vector::vectorize` from its path, qualified name, doc comment
+//! and code, so no model or network is needed and similarity is lexical. The store
+//! lives under `~/.cache/probe/similar/`, keyed like the file index
+//! by root and walk options: the chunks as JSON, with each file's size and modification
+//! time, and the vectors in a separate binary file. Updating it re-vectorizes only the
+//! files that changed since.
+//!
+//! Queries compare the question's vector with every chunk (exact nearest-neighbour
+//! search). At `VECTOR_DIM` floats per chunk that takes milliseconds for the tens of
+//! thousands of symbols of a large repository, so no approximate index is needed.
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use probe_code::extract::symbols::file_symbols;
+use probe_code::progress::Progress;
+use probe_code::search::file_index::IndexUpdate;
+use probe_code::search::file_list_cache::{generate_cache_key, get_file_list};
+use probe_code::search::trigram_vector::{cosine_similarity, vectorize, VECTOR_DIM};
+
+/// Start of a vectors file, with the version of its layout
+const VECTORS_MAGIC: &[u8; 8] = b"PRBVEC1\n";
+
+/// A symbol of a stored file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chunk {
+    /// Qualified with the symbols around it, as `Type.method`
+    pub symbol: String,
+    pub kind: String,
+    pub lines: (usize, usize),
+}
+
+/// The chunks of a file, with the size and modification time (in nanoseconds since
+/// the Unix epoch) of the content they were made from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFile {
+    pub size: u64,
+    pub mtime_nanos: u64,
+    pub chunks: Vec<Chunk>,
+    /// One per chunk; stored in the vectors file
+    #[serde(skip)]
+    vectors: Vec<Vec<f32>>,
+}
+
+/// The chunks of one root, for one set of walk options
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStore {
+    /// Canonical root directory
+    pub root: PathBuf,
+    pub allow_tests: bool,
+    pub custom_ignores: Vec<String>,
+    pub no_gitignore: bool,
+    /// Files keyed by their path relative to `root`
+    pub files: BTreeMap<PathBuf, StoredFile>,
+}
+
+/// A chunk close to a question
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarMatch<'a> {
+    /// Relative to the store's root
+    pub file: &'a Path,
+    pub chunk: &'a Chunk,
+    /// Cosine similarity of the chunk and the question, up to 1
+    pub similarity: f32,
+}
+
+/// The directory holding all vector stores
+fn store_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".cache").join("probe").join("similar")
+}
+
+/// Size and modification time of a file, to tell whether it changed
+fn file_version(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let mtime_nanos = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as u64);
+    Some((metadata.len(), mtime_nanos))
+}
+
+/// Chunk and vectorize a file. Its metadata is read first, so a write during the read
+/// leaves it with an outdated modification time and it is chunked again next time.
+fn store_file(path: &Path, relative: &Path, allow_tests: bool) -> Option<StoredFile> {
+    let (size, mtime_nanos) = file_version(path)?;
+    let content = fs::read_to_string(path).ok()?;
+    let symbols = file_symbols(&path.to_string_lossy(), &content, allow_tests);
+    let mut chunks = Vec::with_capacity(symbols.len());
+    let mut vectors = Vec::with_capacity(symbols.len());
+    for symbol in symbols {
+        let text = format!(
+            "{}\n{}\n{}\n{}",
+            relative.display(),
+            symbol.name,
+            symbol.doc_comment.as_deref().unwrap_or(""),
+            symbol.body
+        );
+        vectors.push(vectorize(&text));
+        chunks.push(Chunk {
+            symbol: symbol.name,
+            kind: symbol.kind,
+            lines: symbol.lines,
+        });
+    }
+    Some(StoredFile {
+        size,
+        mtime_nanos,
+        chunks,
+        vectors,
+    })
+}
+
+impl VectorStore {
+    /// Bring the store of `root` up to date with the files there, creating it if there
+    /// is none yet. Only new and changed files are chunked and vectorized.
+    pub fn update(
+        root: &Path,
+        allow_tests: bool,
+        custom_ignores: &[String],
+        no_gitignore: bool,
+    ) -> Result<(Self, IndexUpdate)> {
+        let root = fs::canonicalize(root)
+            .with_context(|| format!("Failed to resolve {}", root.display()))?;
+        anyhow::ensure!(root.is_dir(), "{} is not a directory", root.display());
+        let mut store = Self::load(&root, allow_tests, custom_ignores, no_gitignore)
+            .unwrap_or_else(|| VectorStore {
+                root: root.clone(),
+                allow_tests,
+                custom_ignores: custom_ignores.to_vec(),
+                no_gitignore,
+                files: BTreeMap::new(),
+            });
+
+        let file_list = get_file_list(&root, allow_tests, custom_ignores, no_gitignore)?;
+        let mut update = IndexUpdate::default();
+        let mut previous = std::mem::take(&mut store.files);
+        let mut changed = Vec::new();
+        for path in &file_list.files {
+            let relative = path.strip_prefix(&root).unwrap_or(path).to_path_buf();
+            match previous.remove(&relative) {
+                Some(stored) if file_version(path) == Some((stored.size, stored.mtime_nanos)) => {
+                    store.files.insert(relative, stored);
+                }
+                Some(_) => {
+                    update.modified += 1;
+                    changed.push((path, relative));
+                }
+                None => {
+                    update.added += 1;
+                    changed.push((path, relative));
+                }
+            }
+        }
+        update.removed = previous.len();
+
+        let progress = Progress::new("Indexing symbols", changed.len());
+        let stored: Vec<(PathBuf, StoredFile)> = changed
+            .into_par_iter()
+            .filter_map(|(path, relative)| {
+                progress.inc();
+                let file = store_file(path, &relative, allow_tests)?;
+                Some((relative, file))
+            })
+            .collect();
+        store.files.extend(stored);
+        Ok((store, update))
+    }
+
+    /// The stored chunks of `root`; `None` when there are none or they can't be read
+    fn load(
+        root: &Path,
+        allow_tests: bool,
+        custom_ignores: &[String],
+        no_gitignore: bool,
+    ) -> Option<Self> {
+        let path = Self::storage_path(root, allow_tests, custom_ignores, no_gitignore);
+        let mut store: VectorStore = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+        let mut vectors = decode_vectors(&fs::read(Self::vectors_path(&path)).ok()?).ok()?;
+        if vectors.len() != store.chunk_count() {
+            return None;
+        }
+        for file in store.files.values_mut() {
+            let rest = vectors.split_off(file.chunks.len());
+            file.vectors = std::mem::replace(&mut vectors, rest);
+        }
+        Some(store)
+    }
+
+    /// Where the store for these walk options is kept
+    fn storage_path(
+        root: &Path,
+        allow_tests: bool,
+        custom_ignores: &[String],
+        no_gitignore: bool,
+    ) -> PathBuf {
+        let key = generate_cache_key(root, allow_tests, custom_ignores, no_gitignore);
+        store_dir().join(format!("{:x}.json", md5::compute(key.as_bytes())))
+    }
+
+    fn vectors_path(storage: &Path) -> PathBuf {
+        storage.with_extension("vectors")
+    }
+
+    /// Write the store to disk, replacing any previous version atomically
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = Self::storage_path(
+            &self.root,
+            self.allow_tests,
+            &self.custom_ignores,
+            self.no_gitignore,
+        );
+        fs::create_dir_all(store_dir()).context("Failed to create vector store directory")?;
+        let vectors = Self::vectors_path(&path);
+        let temp = vectors.with_extension("vectors.tmp");
+        let all_vectors = self.files.values().flat_map(|file| &file.vectors);
+        fs::write(&temp, encode_vectors(all_vectors))
+            .with_context(|| format!("Failed to write vector store {}", temp.display()))?;
+        fs::rename(&temp, &vectors)
+            .with_context(|| format!("Failed to write vector store {}", vectors.display()))?;
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, serde_json::to_vec(self)?)
+            .with_context(|| format!("Failed to write vector store {}", temp.display()))?;
+        fs::rename(&temp, &path)
+            .with_context(|| format!("Failed to write vector store {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|file| file.chunks.len()).sum()
+    }
+
+    /// The `limit` chunks most similar to `question`, most similar first. Chunks that
+    /// share nothing with it are left out.
+    pub fn search(&self, question: &str, limit: usize) -> Vec<SimilarMatch<'_>> {
+        let query = vectorize(question);
+        let mut matches: Vec<SimilarMatch> = self
+            .files
+            .par_iter()
+            .flat_map_iter(|(file, stored)| {
+                let query = &query;
+                stored
+                    .chunks
+                    .iter()
+                    .zip(&stored.vectors)
+                    .map(move |(chunk, vector)| SimilarMatch {
+                        file,
+                        chunk,
+                        similarity: cosine_similarity(query, vector),
+                    })
+            })
+            .filter(|m| m.similarity > 0.0)
+            .collect();
+        matches.sort_by(|a, b| {
+            b.similarity
+                .total_cmp(&a.similarity)
+                .then_with(|| (a.file, a.chunk.lines).cmp(&(b.file, b.chunk.lines)))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Lay out vectors as: magic, dimensions, number of vectors, then the vectors' floats,
+/// all little-endian
+fn encode_vectors<'a>(vectors: impl Iterator<Item = &'a Vec<f32>>) -> Vec<u8> {
+    let vectors: Vec<&Vec<f32>> = vectors.collect();
+    let mut out = VECTORS_MAGIC.to_vec();
+    out.extend_from_slice(&(VECTOR_DIM as u32).to_le_bytes());
+    out.extend_from_slice(&(vectors.len() as u32).to_le_bytes());
+    for vector in vectors {
+        for value in vector {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    out
+}
+
+fn decode_vectors(data: &[u8]) -> io::Result<Vec<Vec<f32>>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid vectors file");
+    let mut reader = data
+        .strip_prefix(VECTORS_MAGIC.as_slice())
+        .ok_or_else(invalid)?;
+    let dim = read_u32(&mut reader)? as usize;
+    let count = read_u32(&mut reader)? as usize;
+    // Vectors of another size are from another version and need rebuilding
+    if dim != VECTOR_DIM || reader.len() != dim * count * 4 {
+        return Err(invalid());
+    }
+    Ok(reader
+        .chunks_exact(dim * 4)
+        .map(|vector| {
+            vector
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect()
+        })
+        .collect())
+}
+
+fn read_u32(reader: &mut &[u8]) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_search_ranks_related_symbols_first() {
+        let temp = TempDir::new().unwrap();
+        fs::write(
+            temp.path().join("auth.rs"),
+            "/// Check the user's password\nfn authenticate(user: &str) -> bool {\n    true\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            temp.path().join("chart.rs"),
+            "fn render_chart(points: &[f64]) {}\n",
+        )
+        .unwrap();
+
+        let (store, update) = VectorStore::update(temp.path(), false, &[], true).unwrap();
+        assert_eq!(update.added, 2);
+        assert_eq!(store.chunk_count(), 2);
+
+        let matches = store.search("how are users authenticated", 5);
+        assert_eq!(matches[0].file, Path::new("auth.rs"));
+        assert_eq!(matches[0].chunk.symbol, "authenticate");
+        assert_eq!(matches[0].chunk.lines, (2, 4));
+        assert!(matches
+            .iter()
+            .all(|m| m.similarity <= matches[0].similarity));
+        assert!(store.search("authenticated", 1).len() == 1);
+    }
+
+    #[test]
+    fn test_vectors_round_trip() {
+        let vectors = [vectorize("parse tokens"), vectorize("render chart")];
+        let decoded = decode_vectors(&encode_vectors(vectors.iter())).unwrap();
+        assert_eq!(decoded, vectors);
+        assert!(decode_vectors(b"PRBVEC1\n").is_err());
+    }
+}
@@ -0,0 +1,797 @@
+//! `probe semver`: classify the changes between two revisions by their impact on the
+//! public API, for release tooling deciding on the next version.
+//!
+//! Every file changed between the revisions is parsed at both, and its named items
+//! (functions, types, fields, variants, trait and interface members, ...) are paired
+//! up by name and kind. Items are compared by their tokens, so formatting and comments
+//! don't count as changes. What is public follows `--public-only`.
+//!
+//! - breaking: a public item was removed or made private, its signature changed, or a
+//!   public trait or interface gained a member without a default body
+//! - additive: a public item was added or made public
+//! - internal: anything else, such as a private item or the body of a public function
+//!
+//! The comparison is conservative: a type's signature includes everything but its
+//! items, so changing the base classes of a public class or the value of a public type
+//! alias is breaking, even when callers wouldn't notice.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use probe_code::extract::symbols::name_node;
+use probe_code::git::git;
+use probe_code::language::factory::get_language_impl;
+use probe_code::language::language_trait::LanguageImpl;
+use probe_code::language::{get_or_parse_tree_pooled, is_test_file};
+use probe_code::warnings;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Node kinds compared as items, across the supported languages
+const ITEM_KINDS: &[&str] = &[
+    // Rust
+    "function_item",
+    "function_signature_item",
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "enum_variant",
+    "field_declaration",
+    "trait_item",
+    "impl_item",
+    "mod_item",
+    "type_item",
+    "associated_type",
+    "const_item",
+    "static_item",
+    "macro_definition",
+    // Go
+    "function_declaration",
+    "method_declaration",
+    "type_spec",
+    "const_spec",
+    "var_spec",
+    "method_elem",
+    "method_spec",
+    // Python
+    "function_definition",
+    "class_definition",
+    // JavaScript and TypeScript
+    "generator_function_declaration",
+    "class_declaration",
+    "abstract_class_declaration",
+    "method_definition",
+    "public_field_definition",
+    "field_definition",
+    "interface_declaration",
+    "type_alias_declaration",
+    "enum_declaration",
+    "internal_module",
+    "method_signature",
+    "abstract_method_signature",
+    "property_signature",
+    "variable_declarator",
+    // Java and C#
+    "record_declaration",
+    "annotation_type_declaration",
+    "constructor_declaration",
+    "enum_constant",
+    "struct_declaration",
+    "property_declaration",
+    "namespace_declaration",
+    // C and C++
+    "declaration",
+    "struct_specifier",
+    "enum_specifier",
+    "union_specifier",
+    "type_definition",
+    "class_specifier",
+    "namespace_definition",
+    "enumerator",
+];
+
+pub struct SemverParams<'a> {
+    /// `<base>..<head>`; `<base>` alone or `<base>..` compare with HEAD
+    pub range: &'a str,
+    pub path: &'a Path,
+    pub allow_tests: bool,
+    pub format: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Impact {
+    Internal,
+    Additive,
+    Breaking,
+}
+
+impl Impact {
+    fn bump(impact: Option<Impact>) -> &'static str {
+        match impact {
+            Some(Impact::Breaking) => "major",
+            Some(Impact::Additive) => "minor",
+            Some(Impact::Internal) => "patch",
+            None => "none",
+        }
+    }
+}
+
+/// A named item of a file at one revision
+#[derive(Debug, Clone)]
+struct Item {
+    /// Qualified with the items around it, as `Type.method`
+    name: String,
+    kind: String,
+    line: usize,
+    public: bool,
+    /// Tokens of everything callers depend on: the item without its body, value and
+    /// nested items
+    signature: String,
+    /// Tokens of the whole item without its nested items
+    code: String,
+    /// The signature as written, for display
+    display: String,
+    /// Whether implementers must provide it: a member without a body of a trait or
+    /// interface
+    required: bool,
+    /// Qualified name of the item it belongs to
+    owner: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Change {
+    impact: Impact,
+    /// What happened to the item, as `removed` or `signature changed`
+    change: &'static str,
+    file: String,
+    symbol: String,
+    kind: String,
+    /// Line of the item at the head revision, or at the base when it was removed
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SemverReport<'a> {
+    base: &'a str,
+    head: &'a str,
+    /// The highest impact of any change, `null` without changes
+    impact: Option<Impact>,
+    /// The version part to bump: `major`, `minor`, `patch` or `none`
+    bump: &'static str,
+    breaking: usize,
+    additive: usize,
+    internal: usize,
+    changes: Vec<Change>,
+}
+
+pub fn handle_semver(params: SemverParams) -> Result<()> {
+    warnings::set_json(params.format == "json");
+    let (base, head) = params.range.split_once("..").unwrap_or((params.range, ""));
+    if head.starts_with('.') {
+        bail!("Use a two-dot range <base>..<head>");
+    }
+    let head = if head.is_empty() { "HEAD" } else { head };
+    if base.is_empty() {
+        bail!("The range needs a base revision, as in v1.2.0..HEAD");
+    }
+
+    let (dir, pathspec) = if params.path.is_file() {
+        let name = params
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        (params.path.parent().unwrap_or(Path::new("")), name)
+    } else {
+        (params.path, ".".to_string())
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    for revision in [base, head] {
+        git(
+            dir,
+            &["rev-parse", "--verify", &format!("{revision}^{{commit}}")],
+        )
+        .with_context(|| format!("Unknown revision '{revision}'"))?;
+    }
+
+    // Paths relative to `dir`, which `git show <revision>:./<path>` resolves the same way
+    let changed = git(
+        dir,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--relative",
+            base,
+            head,
+            "--",
+            &pathspec,
+        ],
+    )
+    .with_context(|| format!("Can't compare {base} with {head}"))?;
+    let files: Vec<&str> = changed
+        .lines()
+        .filter(|file| !file.is_empty())
+        .filter(|file| params.allow_tests || !is_test_file(Path::new(file)))
+        .filter(|file| get_language_impl(extension(file)).is_some())
+        .collect();
+
+    let mut changes: Vec<Change> = files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let items_at = |revision: &str| {
+                // A file added or deleted in the range has no items on the other side
+                let content = git(dir, &["show", &format!("{revision}:./{file}")]).ok()?;
+                Some(items(
+                    &format!("{revision}:{file}"),
+                    &content,
+                    extension(file),
+                    params.allow_tests,
+                ))
+            };
+            compare(
+                &dir.join(file).to_string_lossy(),
+                items_at(base).unwrap_or_default(),
+                items_at(head).unwrap_or_default(),
+            )
+        })
+        .collect();
+    changes.sort_by(|a, b| {
+        b.impact
+            .cmp(&a.impact)
+            .then_with(|| a.file.cmp(&b.file))
+            .then(a.line.cmp(&b.line))
+    });
+
+    let count = |impact: Impact| {
+        changes
+            .iter()
+            .filter(|change| change.impact == impact)
+            .count()
+    };
+    let impact = changes.iter().map(|change| change.impact).max();
+    let report = SemverReport {
+        base,
+        head,
+        impact,
+        bump: Impact::bump(impact),
+        breaking: count(Impact::Breaking),
+        additive: count(Impact::Additive),
+        internal: count(Impact::Internal),
+        changes,
+    };
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_plain(&report);
+    }
+    Ok(())
+}
+
+fn extension(file: &str) -> &str {
+    Path::new(file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+}
+
+/// The items of a file, outermost first
+fn items(key: &str, content: &str, extension: &str, allow_tests: bool) -> Vec<Item> {
+    let (Some(language_impl), Ok(tree)) = (
+        get_language_impl(extension),
+        get_or_parse_tree_pooled(key, content, extension),
+    ) else {
+        return Vec::new();
+    };
+    let mut items = Vec::new();
+    collect_items(
+        tree.root_node(),
+        content.as_bytes(),
+        language_impl.as_ref(),
+        allow_tests,
+        None,
+        &mut items,
+    );
+    items
+}
+
+/// The item around the items being collected: its name, whether it is public and
+/// whether it is a trait or interface
+type Owner<'a> = Option<(&'a str, bool, bool)>;
+
+fn collect_items(
+    node: Node,
+    source: &[u8],
+    language_impl: &dyn LanguageImpl,
+    allow_tests: bool,
+    owner: Owner<'_>,
+    items: &mut Vec<Item>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if is_comment(&child) || (!allow_tests && language_impl.is_test_node(&child, source)) {
+            continue;
+        }
+        let name = ITEM_KINDS
+            .contains(&child.kind())
+            .then(|| item_name(&child, source))
+            .flatten();
+        let Some(name) = name else {
+            // Look through wrappers like `export` statements and declaration lists
+            if !is_function_like(child.kind()) {
+                collect_items(child, source, language_impl, allow_tests, owner, items);
+            }
+            continue;
+        };
+
+        let (name, owner_name, owner_public, in_contract) = match owner {
+            Some((owner, public, contract)) => {
+                (format!("{owner}.{name}"), Some(owner), public, contract)
+            }
+            None => (name, None, true, false),
+        };
+        // `export const` exports the declaration around the declarator
+        let public = owner_public
+            && (language_impl.is_public(&child, source)
+                || (child.kind() == "variable_declarator"
+                    && child
+                        .parent()
+                        .is_some_and(|parent| language_impl.is_public(&parent, source))));
+        let mut signature = Vec::new();
+        tokens(child, source, true, &mut signature);
+        let mut code = Vec::new();
+        tokens(child, source, false, &mut code);
+        items.push(Item {
+            name: name.clone(),
+            kind: child.kind().to_string(),
+            line: child.start_position().row + 1,
+            public,
+            signature: signature.join(" "),
+            code: code.join(" "),
+            display: display_signature(&child, source),
+            required: in_contract
+                && is_function_like(child.kind())
+                && child.child_by_field_name("body").is_none(),
+            owner: owner_name.map(str::to_string),
+        });
+        // Members are in the body, or for Go in the struct or interface type
+        let members = child
+            .child_by_field_name("body")
+            .or_else(|| child.child_by_field_name("type"))
+            .filter(|_| !is_function_like(child.kind()));
+        if let Some(members) = members {
+            collect_items(
+                members,
+                source,
+                language_impl,
+                allow_tests,
+                Some((&name, public, is_contract(&child))),
+                items,
+            );
+        }
+    }
+}
+
+/// The name of an item, `Trait for Type` for trait impls
+fn item_name(node: &Node, source: &[u8]) -> Option<String> {
+    let text = |node: Node| {
+        node.utf8_text(source)
+            .ok()
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+    };
+    if let (Some(trait_node), Some(type_node)) = (
+        node.child_by_field_name("trait"),
+        node.child_by_field_name("type"),
+    ) {
+        return Some(format!("{} for {}", text(trait_node)?, text(type_node)?));
+    }
+    let mut name = name_node(*node)?;
+    // Qualified names are compared by their last part
+    while let Some(inner) = name
+        .child_by_field_name("declarator")
+        .or_else(|| name.child_by_field_name("name"))
+    {
+        name = inner;
+    }
+    text(name).filter(|name| !name.is_empty())
+}
+
+fn is_function_like(kind: &str) -> bool {
+    ["function", "method", "constructor", "arrow", "lambda"]
+        .iter()
+        .any(|part| kind.contains(part))
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind().contains("comment")
+}
+
+/// Whether implementers of an item must provide its members: traits, interfaces and
+/// Go interface types
+fn is_contract(node: &Node) -> bool {
+    node.kind().contains("trait")
+        || node.kind().contains("interface")
+        || node
+            .child_by_field_name("type")
+            .is_some_and(|type_node| type_node.kind() == "interface_type")
+}
+
+/// The tokens of a node without comments and nested items. For a signature, function
+/// bodies and values (of constants, fields and default parameters) are left out too,
+/// but the parameters of a function given as a value are kept.
+fn tokens<'a>(node: Node, source: &'a [u8], signature: bool, out: &mut Vec<&'a str>) {
+    let mut cursor = node.walk();
+    if !cursor.goto_first_child() {
+        if let Ok(text) = node.utf8_text(source) {
+            out.push(text);
+        }
+        return;
+    }
+    // The separator after a nested item goes with it, so adding a field changes nothing
+    let mut after_item = false;
+    loop {
+        let child = cursor.node();
+        let field = cursor.field_name();
+        let is_item = ITEM_KINDS.contains(&child.kind()) && item_name(&child, source).is_some();
+        let skip = is_item
+            || is_comment(&child)
+            || (after_item && matches!(child.kind(), "," | ";"))
+            || (signature
+                && match field {
+                    Some("body") => is_function_like(node.kind()),
+                    Some("value") => {
+                        !node.kind().contains("type") && !is_function_like(child.kind())
+                    }
+                    _ => false,
+                });
+        if !skip {
+            tokens(child, source, signature, out);
+        }
+        if !is_comment(&child) {
+            after_item = is_item;
+        }
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+/// An item as written, up to its body
+fn display_signature(node: &Node, source: &[u8]) -> String {
+    let end = node
+        .child_by_field_name("body")
+        .map_or(node.end_byte(), |body| body.start_byte());
+    let text = String::from_utf8_lossy(&source[node.start_byte()..end]);
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")");
+    let text = text.trim_end_matches('{').trim_end();
+    if text.chars().count() > 120 {
+        let short: String = text.chars().take(117).collect();
+        format!("{short}...")
+    } else {
+        text.to_string()
+    }
+}
+
+/// The changes between the items of a file at the base and at the head
+fn compare(file: &str, base: Vec<Item>, head: Vec<Item>) -> Vec<Change> {
+    let base_names: HashSet<String> = base
+        .iter()
+        .filter(|item| item.public)
+        .map(|item| item.name.clone())
+        .collect();
+    // Overloads share a name and kind; they are told apart by their signatures
+    let mut groups: BTreeMap<(String, String), (Vec<Item>, Vec<Item>)> = BTreeMap::new();
+    for item in base {
+        let key = (item.name.clone(), item.kind.clone());
+        groups.entry(key).or_default().0.push(item);
+    }
+    for item in head {
+        let key = (item.name.clone(), item.kind.clone());
+        groups.entry(key).or_default().1.push(item);
+    }
+
+    let mut changes: Vec<(Impact, &'static str, Option<Item>, Option<Item>)> = Vec::new();
+    for (_, (mut before, mut after)) in groups {
+        // Pair up the items whose signature didn't change first
+        let mut pairs = Vec::new();
+        before.retain(
+            |old| match after.iter().position(|new| new.signature == old.signature) {
+                Some(index) => {
+                    pairs.push((old.clone(), after.remove(index)));
+                    false
+                }
+                None => true,
+            },
+        );
+        let unpaired = before.len().min(after.len());
+        let rest: Vec<(Item, Item)> = before
+            .drain(..unpaired)
+            .zip(after.drain(..unpaired))
+            .collect();
+
+        for (old, new) in &pairs {
+            let found = match (old.public, new.public) {
+                (true, false) => Some((Impact::Breaking, "made private")),
+                (false, true) => Some((Impact::Additive, "made public")),
+                _ if old.code != new.code => Some((Impact::Internal, "body changed")),
+                _ => None,
+            };
+            if let Some((impact, what)) = found {
+                changes.push((impact, what, Some(old.clone()), Some(new.clone())));
+            }
+        }
+        for (old, new) in &rest {
+            let (impact, what) = match (old.public, new.public) {
+                (true, true) => (Impact::Breaking, "signature changed"),
+                (true, false) => (Impact::Breaking, "made private"),
+                (false, true) => (Impact::Additive, "made public"),
+                (false, false) => (Impact::Internal, "signature changed"),
+            };
+            changes.push((impact, what, Some(old.clone()), Some(new.clone())));
+        }
+        for old in &before {
+            let impact = if old.public {
+                Impact::Breaking
+            } else {
+                Impact::Internal
+            };
+            changes.push((impact, "removed", Some(old.clone()), None));
+        }
+        for new in &after {
+            // Implementers of a trait that existed before have to add the member
+            let breaks_implementers = new.public
+                && new.required
+                && new
+                    .owner
+                    .as_ref()
+                    .is_some_and(|owner| base_names.contains(owner));
+            let (impact, what) = if breaks_implementers {
+                (Impact::Breaking, "required member added")
+            } else if new.public {
+                (Impact::Additive, "added")
+            } else {
+                (Impact::Internal, "added")
+            };
+            changes.push((impact, what, None, Some(new.clone())));
+        }
+    }
+
+    // The members of an item added or removed as a whole come and go with it
+    let mut added = HashSet::new();
+    let mut removed = HashSet::new();
+    for (_, _, before, after) in &changes {
+        match (before, after) {
+            (None, Some(new)) => added.insert(new.name.clone()),
+            (Some(old), None) => removed.insert(old.name.clone()),
+            _ => false,
+        };
+    }
+    changes
+        .into_iter()
+        .filter(|(_, _, before, after)| match (before, after) {
+            (None, Some(new)) => !new
+                .owner
+                .as_ref()
+                .is_some_and(|owner| added.contains(owner)),
+            (Some(old), None) => !old
+                .owner
+                .as_ref()
+                .is_some_and(|owner| removed.contains(owner)),
+            _ => true,
+        })
+        .map(|(impact, change, before, after)| {
+            let item = after
+                .as_ref()
+                .or(before.as_ref())
+                .expect("a change has an item");
+            Change {
+                impact,
+                change,
+                file: file.to_string(),
+                symbol: item.name.clone(),
+                kind: item.kind.clone(),
+                line: item.line,
+                before: before.as_ref().map(|item| item.display.clone()),
+                after: after.as_ref().map(|item| item.display.clone()),
+            }
+        })
+        .collect()
+}
+
+fn print_plain(report: &SemverReport) {
+    let title = format!("{}..{}", report.base, report.head);
+    let Some(impact) = report.impact else {
+        println!("No changes to supported source files in {title}");
+        return;
+    };
+    let (name, color) = match impact {
+        Impact::Breaking => ("breaking", Color::Red),
+        Impact::Additive => ("additive", Color::Green),
+        Impact::Internal => ("internal", Color::Yellow),
+    };
+    println!(
+        "{} {} ({} bump)",
+        format!("Impact of {title}:").bold(),
+        name.color(color).bold(),
+        report.bump
+    );
+
+    for (impact, heading, count) in [
+        (Impact::Breaking, "Breaking", report.breaking),
+        (Impact::Additive, "Additive", report.additive),
+        (Impact::Internal, "Internal", report.internal),
+    ] {
+        if count == 0 {
+            continue;
+        }
+        println!();
+        println!("{} ({count})", heading.bold());
+        for change in report
+            .changes
+            .iter()
+            .filter(|change| change.impact == impact)
+        {
+            println!(
+                "  {}:{} {} {}",
+                change.file.cyan(),
+                change.line,
+                change.symbol.bold(),
+                format!("{} ({})", change.change, change.kind).dimmed()
+            );
+            match (&change.before, &change.after) {
+                (Some(before), Some(after)) if before != after => {
+                    println!("    {} {before}", "-".red());
+                    println!("    {} {after}", "+".green());
+                }
+                (_, Some(signature)) | (Some(signature), None) => {
+                    println!("      {signature}");
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(extension: &str, before: &str, after: &str) -> Vec<(Impact, &'static str, String)> {
+        let file = format!("semver_test.{extension}");
+        let mut found: Vec<_> = compare(
+            &file,
+            items(&format!("base:{file}"), before, extension, false),
+            items(&format!("head:{file}"), after, extension, false),
+        )
+        .into_iter()
+        .map(|change| (change.impact, change.change, change.symbol))
+        .collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn test_rust_changes() {
+        let before = r#"
+pub struct Config {
+    pub path: String,
+    cache: bool,
+}
+
+impl Config {
+    pub fn load(path: &str) -> Config {
+        Config { path: path.to_string(), cache: false }
+    }
+
+    pub fn reload(&mut self) {}
+
+    fn helper() {}
+}
+
+pub trait Store {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+pub enum Mode {
+    Fast,
+    Slow,
+}
+"#;
+        let after = r#"
+/// Where the settings come from
+pub struct Config {
+    pub path:   String,
+    cache: bool,
+    retries: u32,
+}
+
+impl Config {
+    pub fn load(path: &str) -> Config {
+        // Formatting and comments don't count
+        Config { path: path.to_string(), cache: true, retries: 3 }
+    }
+
+    pub fn reload(&mut self, force: bool) {}
+
+    pub fn save(&self) {}
+}
+
+pub trait Store {
+    fn get(&self, key: &str) -> Option<String>;
+    fn put(&mut self, key: &str, value: String);
+    fn len(&self) -> usize { 0 }
+}
+
+pub enum Mode {
+    Fast,
+}
+"#;
+        assert_eq!(
+            changes("rs", before, after),
+            vec![
+                (Impact::Internal, "added", "Config.retries".to_string()),
+                (Impact::Internal, "body changed", "Config.load".to_string()),
+                (Impact::Internal, "removed", "Config.helper".to_string()),
+                (Impact::Additive, "added", "Config.save".to_string()),
+                (Impact::Additive, "added", "Store.len".to_string()),
+                (Impact::Breaking, "removed", "Mode.Slow".to_string()),
+                (
+                    Impact::Breaking,
+                    "required member added",
+                    "Store.put".to_string()
+                ),
+                (
+                    Impact::Breaking,
+                    "signature changed",
+                    "Config.reload".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visibility_changes() {
+        let before = "pub fn exposed() {}\nfn hidden() {}\n";
+        let after = "fn exposed() {}\npub fn hidden() {}\n";
+        assert_eq!(
+            changes("rs", before, after),
+            vec![
+                (Impact::Additive, "made public", "hidden".to_string()),
+                (Impact::Breaking, "made private", "exposed".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_go_changes() {
+        let before =
+            "package store\n\nfunc Open(path string) error { return nil }\n\nfunc helper() {}\n";
+        let after = "package store\n\nfunc Open(path string, readOnly bool) error { return nil }\n\nfunc helper() { Open(\"\", true) }\n\nfunc Close() {}\n";
+        assert_eq!(
+            changes("go", before, after),
+            vec![
+                (Impact::Internal, "body changed", "helper".to_string()),
+                (Impact::Additive, "added", "Close".to_string()),
+                (Impact::Breaking, "signature changed", "Open".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,499 @@
+//! `probe serve`: an HTTP server exposing search, extract, outline and query.
+//!
+//! Every endpoint answers with the same JSON document the matching command prints with
+//! `--format json`. Requests run in the server process, so parser pools, parsed trees,
+//! file lists and search sessions stay warm from one request to the next.
+//!
+//! Parameters come from the query string (`GET /search?query=auth&max_results=5`) or a
+//! JSON object body (`POST /search`). Each connection is handled on its own thread and
+//! closed after one response.
+//!
+//! The config files of the working directory add ignore patterns and the `allow_tests`,
+//! `reranker` and `max_tokens` defaults. SIGHUP or `POST /reload` re-reads them and drops
+//! the cached file lists, so edited config and ignore files apply without a restart.
+
+use crate::reload::{self, Reloads};
+use anyhow::{Context, Result};
+use probe_code::extract::{
+    extract_all_symbols_from_file, format_extraction_results, format_outline_json,
+    group_symbols_by_type, keep_public_results, parse_file_with_line, process_file_for_extraction,
+};
+use probe_code::query::{format_query_results_json, perform_query, QueryOptions};
+use probe_code::search::file_list_cache::clear_file_list_cache;
+use probe_code::search::project_config::load_resolved_config;
+use probe_code::search::search_output::format_json_search_results;
+use probe_code::search::{perform_probe, SearchOptions};
+use serde_json::{json, Map, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Requests with a larger body are rejected
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// How long a connection may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct ServeParams {
+    pub host: String,
+    pub port: u16,
+    pub allow_tests: bool,
+    pub ignore: Vec<String>,
+    pub no_gitignore: bool,
+}
+
+/// The command line's settings with the config files' defaults
+#[derive(Debug)]
+struct Settings {
+    ignore: Vec<String>,
+    allow_tests: bool,
+    no_gitignore: bool,
+    reranker: Option<String>,
+    max_tokens: Option<usize>,
+}
+
+struct Server {
+    params: ServeParams,
+    settings: RwLock<Arc<Settings>>,
+    reloads: Reloads,
+}
+
+impl Server {
+    /// Read the config files again and forget the walked file lists
+    fn reload(&self) -> Result<Arc<Settings>> {
+        let settings = Arc::new(load_settings(&self.params)?);
+        clear_file_list_cache();
+        *self.settings.write().unwrap() = Arc::clone(&settings);
+        Ok(settings)
+    }
+
+    /// The current settings, reloaded first if a reload was requested
+    fn settings(&self) -> Arc<Settings> {
+        if self.reloads.pending() {
+            match self.reload() {
+                Ok(_) => eprintln!("Reloaded the config and file lists"),
+                Err(e) => eprintln!("Reload failed, keeping the previous config: {e:#}"),
+            }
+        }
+        Arc::clone(&self.settings.read().unwrap())
+    }
+}
+
+fn load_settings(params: &ServeParams) -> Result<Settings> {
+    let config = load_resolved_config(Path::new("."))?.config;
+    Ok(Settings {
+        ignore: params.ignore.iter().cloned().chain(config.ignore).collect(),
+        allow_tests: params.allow_tests || config.allow_tests.unwrap_or(false),
+        no_gitignore: params.no_gitignore,
+        reranker: config.reranker,
+        max_tokens: config.max_tokens,
+    })
+}
+
+/// Request parameters, from the query string or a JSON body
+type Params = Map<String, Value>;
+
+/// An error answered with the given HTTP status and a `{"error": ...}` body
+struct HttpError(u16, String);
+
+impl From<anyhow::Error> for HttpError {
+    fn from(e: anyhow::Error) -> Self {
+        HttpError(500, format!("{e:#}"))
+    }
+}
+
+pub fn handle_serve(params: ServeParams) -> Result<()> {
+    let listener = TcpListener::bind((params.host.as_str(), params.port))
+        .with_context(|| format!("Failed to listen on {}:{}", params.host, params.port))?;
+    let settings = load_settings(&params)?;
+    eprintln!(
+        "Serving /search, /extract, /outline and /query on http://{}",
+        listener.local_addr()?
+    );
+    eprintln!("SIGHUP or POST /reload re-reads the config files and the file tree");
+    reload::install_sighup_handler();
+
+    let server = Arc::new(Server {
+        params,
+        settings: RwLock::new(Arc::new(settings)),
+        reloads: Reloads::default(),
+    });
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let server = server.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &server) {
+                eprintln!("Connection error: {e:#}");
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, server: &Server) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let (status, body) = match read_request(&mut stream) {
+        Ok((method, path, params)) => match route(&method, &path, &params, server) {
+            Ok(body) => (200, body),
+            Err(HttpError(status, message)) => (status, error_body(&message)),
+        },
+        Err(HttpError(status, message)) => (status, error_body(&message)),
+    };
+    write_response(&mut stream, status, &body)
+}
+
+fn error_body(message: &str) -> String {
+    json!({ "error": message }).to_string()
+}
+
+/// Read the request line, headers and body, and collect the parameters
+fn read_request(stream: &mut TcpStream) -> Result<(String, String, Params), HttpError> {
+    let bad_request = |message: &str| HttpError(400, message.to_string());
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .map_err(|_| bad_request("Failed to read request"))?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|_| bad_request("Failed to read headers"))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("Invalid Content-Length"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(HttpError(413, "Request body too large".to_string()));
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut params = parse_query_string(query);
+    if content_length > 0 {
+        let mut body = vec![0; content_length];
+        reader
+            .read_exact(&mut body)
+            .map_err(|_| bad_request("Failed to read request body"))?;
+        match serde_json::from_slice(&body) {
+            Ok(Value::Object(body)) => params.extend(body),
+            _ => return Err(bad_request("Request body must be a JSON object")),
+        }
+    }
+    Ok((method.to_string(), path.to_string(), params))
+}
+
+/// Query string parameters as strings; a key without a value is a `true` flag
+fn parse_query_string(query: &str) -> Params {
+    let mut params = Params::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, "true"));
+        let value = Value::String(percent_decode(value));
+        let key = percent_decode(key);
+        // Repeated keys (`?targets=a&targets=b`) collect into an array
+        match params.get_mut(&key) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            None => {
+                params.insert(key, value);
+            }
+        }
+    }
+    params
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                match u8::from_str_radix(text.get(i + 1..i + 3).unwrap_or(""), 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+fn route(method: &str, path: &str, params: &Params, server: &Server) -> Result<String, HttpError> {
+    if method != "GET" && method != "POST" {
+        return Err(HttpError(405, format!("Unsupported method: {method}")));
+    }
+    let path = path.trim_end_matches('/');
+    if path == "/reload" {
+        if method != "POST" {
+            return Err(HttpError(405, "Use POST /reload".to_string()));
+        }
+        let settings = server.reload()?;
+        return Ok(json!({
+            "reloaded": true,
+            "ignore": settings.ignore,
+            "allow_tests": settings.allow_tests,
+            "reranker": settings.reranker,
+            "max_tokens": settings.max_tokens,
+        })
+        .to_string());
+    }
+    let settings = server.settings();
+    match path {
+        "/search" => search(params, &settings),
+        "/extract" => extract(params, &settings),
+        "/outline" => outline(params, &settings),
+        "/query" => query(params, &settings),
+        _ => Err(HttpError(404, format!("Unknown endpoint: {path}"))),
+    }
+}
+
+fn string<'a>(params: &'a Params, key: &str) -> Option<&'a str> {
+    params.get(key).and_then(Value::as_str)
+}
+
+fn required<'a>(params: &'a Params, key: &str) -> Result<&'a str, HttpError> {
+    string(params, key).ok_or_else(|| HttpError(400, format!("Missing parameter: {key}")))
+}
+
+/// Numbers and flags are JSON values in a body and strings in a query string
+fn number(params: &Params, key: &str) -> Option<usize> {
+    match params.get(key)? {
+        Value::String(value) => value.parse().ok(),
+        value => value.as_u64().map(|n| n as usize),
+    }
+}
+
+fn flag(params: &Params, key: &str) -> bool {
+    match params.get(key) {
+        Some(Value::String(value)) => value == "true" || value == "1",
+        Some(value) => value.as_bool().unwrap_or(false),
+        None => false,
+    }
+}
+
+/// A parameter given either once or as an array of strings
+fn strings(params: &Params, key: &str) -> Vec<String> {
+    match params.get(key) {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn search(params: &Params, settings: &Settings) -> Result<String, HttpError> {
+    let queries = vec![required(params, "query")?.to_string()];
+    let paths = vec![PathBuf::from(string(params, "path").unwrap_or("."))];
+    let reranker = string(params, "reranker")
+        .or(settings.reranker.as_deref())
+        .unwrap_or("bm25")
+        .to_string();
+    let sort = string(params, "sort").unwrap_or("score").to_string();
+
+    let options = SearchOptions {
+        paths: &paths,
+        queries: &queries,
+        files_only: flag(params, "files_only"),
+        custom_ignores: &settings.ignore,
+        exclude_filenames: flag(params, "exclude_filenames"),
+        reranker: &reranker,
+        frequency_search: true,
+        exact: flag(params, "exact"),
+        language: string(params, "language"),
+        max_results: number(params, "max_results"),
+        max_bytes: number(params, "max_bytes"),
+        max_tokens: number(params, "max_tokens").or(settings.max_tokens),
+        max_files: number(params, "max_files"),
+        allow_tests: settings.allow_tests || flag(params, "allow_tests"),
+        no_merge: flag(params, "no_merge"),
+        merge_threshold: number(params, "merge_threshold"),
+        dry_run: false,
+        session: string(params, "session"),
+        session_across_queries: false,
+        session_ttl: None,
+        session_max_entries: None,
+        timeout: number(params, "timeout").unwrap_or(30) as u64,
+        question: None,
+        no_gitignore: settings.no_gitignore,
+        no_attributes: false,
+        include_deps: false,
+        filename_weight: 1.0,
+        dedupe_cross_file: false,
+        diversify: None,
+        regex_escape: true,
+        include_generated: flag(params, "include_generated"),
+        sort: &sort,
+        reverse: flag(params, "reverse"),
+        smart_case: flag(params, "smart_case"),
+        files_from: None,
+        vector_weight: string(params, "vector_weight")
+            .and_then(|weight| weight.parse().ok())
+            .or_else(|| params.get("vector_weight").and_then(Value::as_f64))
+            .unwrap_or(0.5),
+        fuzzy: flag(params, "fuzzy"),
+        count_only: false,
+        case_sensitive: flag(params, "case_sensitive"),
+        max_block_lines: number(params, "max_block_lines"),
+        regex: flag(params, "regex"),
+        no_whole_files: flag(params, "no_whole_files"),
+        within_session: string(params, "within_session"),
+        include: &[],
+        offset: number(params, "offset").unwrap_or(0),
+        stable_order: flag(params, "stable_order"),
+        file_types: &[],
+        type_definitions: &[],
+        symbols_only: false,
+        no_comments: false,
+        comments_only: false,
+        strings_only: false,
+        boost_recent: string(params, "boost_recent"),
+        recency_weight: string(params, "recency_weight")
+            .and_then(|weight| weight.parse().ok())
+            .or_else(|| params.get("recency_weight").and_then(Value::as_f64))
+            .unwrap_or(0.3),
+        changed: flag(params, "changed"),
+        since: string(params, "since"),
+        blame: flag(params, "blame"),
+        owners: flag(params, "owners"),
+    };
+    let results = perform_probe(&options)?;
+    let valid: Vec<_> = results
+        .results
+        .iter()
+        .filter(|r| !r.file.is_empty())
+        .collect();
+    Ok(format_json_search_results(
+        &valid,
+        Some(&results.skipped_files),
+        results.limits_applied.as_ref(),
+        results.session_tokens,
+        results.total_ranked,
+        &results.large_files_skipped,
+    )?)
+}
+
+fn extract(params: &Params, settings: &Settings) -> Result<String, HttpError> {
+    let targets = strings(params, "targets");
+    if targets.is_empty() {
+        return Err(HttpError(400, "Missing parameter: targets".to_string()));
+    }
+    let allow_tests = settings.allow_tests || flag(params, "allow_tests");
+    let context_lines = number(params, "context_lines").unwrap_or(0);
+
+    let mut results = Vec::new();
+    for target in &targets {
+        for (path, start, end, symbol, lines) in parse_file_with_line(target, allow_tests) {
+            let result = process_file_for_extraction(
+                &path,
+                start,
+                end,
+                symbol.as_deref(),
+                allow_tests,
+                context_lines,
+                lines.as_ref(),
+                false,
+            )
+            .map_err(|e| HttpError(400, format!("{}: {e:#}", path.display())))?;
+            results.push(result);
+        }
+    }
+    if flag(params, "public_only") {
+        results = keep_public_results(results, allow_tests, false);
+    }
+    if flag(params, "no_whole_files") {
+        results = probe_code::search::file_chunking::chunk_whole_files(results, false);
+    }
+    Ok(format_extraction_results(
+        &results, "json", None, None, None, false,
+    )?)
+}
+
+fn outline(params: &Params, settings: &Settings) -> Result<String, HttpError> {
+    let file = Path::new(required(params, "file")?);
+    let allow_tests = settings.allow_tests || flag(params, "allow_tests");
+    let symbols = extract_all_symbols_from_file(file, allow_tests, flag(params, "public_only"))
+        .map_err(|e| HttpError(400, format!("{e:#}")))?;
+    Ok(format_outline_json(file, &group_symbols_by_type(symbols))?)
+}
+
+fn query(params: &Params, settings: &Settings) -> Result<String, HttpError> {
+    let path = PathBuf::from(string(params, "path").unwrap_or("."));
+    let options = QueryOptions {
+        path: &path,
+        pattern: required(params, "pattern")?,
+        language: string(params, "language"),
+        ignore: &settings.ignore,
+        allow_tests: settings.allow_tests || flag(params, "allow_tests"),
+        max_results: number(params, "max_results"),
+        format: "json",
+        no_gitignore: settings.no_gitignore,
+        full_block: flag(params, "full_block"),
+    };
+    let matches = perform_query(&options)?;
+    Ok(format_query_results_json(&matches)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_string() {
+        let params =
+            parse_query_string("query=auth+token%21&max_results=5&exact&targets=a.rs&targets=b.rs");
+        assert_eq!(string(&params, "query"), Some("auth token!"));
+        assert_eq!(number(&params, "max_results"), Some(5));
+        assert!(flag(&params, "exact"));
+        assert_eq!(strings(&params, "targets"), ["a.rs", "b.rs"]);
+    }
+
+    #[test]
+    fn test_percent_decode_keeps_invalid_escapes() {
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("a%2Fb%zz"), "a/b%zz");
+    }
+}
@@ -0,0 +1,45 @@
+//! `probe show`: render a result set saved with `--save-results` without searching again.
+
+use anyhow::{bail, Result};
+use probe_code::extract::format_extraction_results;
+use probe_code::search::{format_and_print_search_results, query::create_query_plan};
+use probe_code::snapshot::{ResultSnapshot, SnapshotKind};
+use std::path::Path;
+
+/// Output formats of `probe extract`; the outline formats of search need the query
+const EXTRACT_FORMATS: &[&str] = &["markdown", "plain", "json", "xml", "color", "outline-xml"];
+
+pub fn handle_show(file: &Path, format: Option<&str>) -> Result<()> {
+    let snapshot = ResultSnapshot::load(file)?;
+    match snapshot.kind {
+        SnapshotKind::Search => {
+            let format = format.unwrap_or("outline");
+            let query_plan = create_query_plan(&snapshot.query, false).ok();
+            format_and_print_search_results(
+                &snapshot.results,
+                false,
+                format,
+                query_plan.as_ref(),
+                None,
+                None,
+                None,
+                None,
+                &[],
+                None,
+            );
+        }
+        SnapshotKind::Extract => {
+            let format = format.unwrap_or("color");
+            if !EXTRACT_FORMATS.contains(&format) {
+                bail!(
+                    "Extracted results can't be shown as {format}; use one of: {}",
+                    EXTRACT_FORMATS.join(", ")
+                );
+            }
+            let output =
+                format_extraction_results(&snapshot.results, format, None, None, None, false)?;
+            println!("{output}");
+        }
+    }
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::ranking::QueryTokenMap;
 use ahash::AHashMap as HashMap;
+use rayon::prelude::*;
 use simsimd::SpatialSimilarity;
 
 /// Sparse vector representation optimized for SIMD operations
@@ -205,7 +206,7 @@ impl SparseDocumentMatrix {
     ) -> Self {
         // Convert document term frequencies to sparse vectors
         let documents: Vec<SparseVector> = term_frequencies
-            .iter()
+            .par_iter()
             .map(SparseVector::from_tf_map)
             .collect();
 
@@ -307,6 +308,7 @@ impl SparseDocumentMatrix {
     /// Compute BM25 scores for all documents in parallel using SIMD
     pub fn compute_all_scores(&self, document_lengths: &[usize]) -> Vec<f32> {
         (0..self.documents.len())
+            .into_par_iter()
             .map(|i| self.compute_bm25_score(i, document_lengths[i]))
             .collect()
     }
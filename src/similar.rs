@@ -0,0 +1,129 @@
+//! `probe similar`: keep a local vector store of a codebase's symbols and answer
+//! questions with the lexically closest code blocks, without a model or network.
+
+use anyhow::Result;
+use colored::*;
+use probe_code::models::SearchResult;
+use probe_code::search::format_and_print_search_results;
+use probe_code::search::vector_store::{SimilarMatch, VectorStore};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub struct SimilarIndexParams {
+    pub path: PathBuf,
+    pub allow_tests: bool,
+    pub ignore: Vec<String>,
+    pub no_gitignore: bool,
+}
+
+pub struct SimilarSearchParams<'a> {
+    pub question: &'a str,
+    pub path: &'a Path,
+    pub max_results: usize,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+pub fn handle_similar_index(params: SimilarIndexParams) -> Result<()> {
+    let start = Instant::now();
+    let (store, update) = VectorStore::update(
+        &params.path,
+        params.allow_tests,
+        &params.ignore,
+        params.no_gitignore,
+    )?;
+    let storage = store.save()?;
+    println!(
+        "Indexed {} symbols of {} files in {}: {} added, {} modified, {} removed ({:.2?})",
+        store.chunk_count(),
+        store.files.len(),
+        store.root.display(),
+        update.added,
+        update.modified,
+        update.removed,
+        start.elapsed()
+    );
+    println!("{} {}", "Vector store:".bold().green(), storage.display());
+    Ok(())
+}
+
+/// Answer a question from the store of `path`, updating the store first so answers
+/// reflect the files as they are now
+pub fn handle_similar_search(params: SimilarSearchParams) -> Result<()> {
+    let (store, update) = VectorStore::update(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.no_gitignore,
+    )?;
+    if !update.is_empty() {
+        store.save()?;
+    }
+    let results: Vec<SearchResult> = store
+        .search(params.question, params.max_results)
+        .iter()
+        .enumerate()
+        .filter_map(|(i, m)| match_result(&store.root, m, i + 1))
+        .collect();
+    format_and_print_search_results(
+        &results,
+        false,
+        params.format,
+        None,
+        None,
+        None,
+        None,
+        None,
+        &[],
+        None,
+    );
+    Ok(())
+}
+
+/// The code of a match, read from its file; `None` if the file is gone
+fn match_result(root: &Path, m: &SimilarMatch, rank: usize) -> Option<SearchResult> {
+    let path = root.join(m.file);
+    let content = fs::read_to_string(&path).ok()?;
+    let (start, end) = m.chunk.lines;
+    let code = content
+        .lines()
+        .skip(start - 1)
+        .take(end + 1 - start)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(SearchResult {
+        file: path.to_string_lossy().to_string(),
+        lines: m.chunk.lines,
+        node_type: m.chunk.kind.clone(),
+        code: code.into(),
+        symbol_signature: Some(m.chunk.symbol.clone()),
+        matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
+        rank: Some(rank),
+        score: Some(m.similarity as f64),
+        tfidf_score: None,
+        bm25_score: None,
+        tfidf_rank: None,
+        bm25_rank: None,
+        new_score: None,
+        hybrid2_rank: None,
+        combined_score_rank: None,
+        file_unique_terms: None,
+        file_total_matches: None,
+        file_match_rank: None,
+        block_unique_terms: None,
+        block_total_matches: None,
+        parent_file_id: None,
+        block_id: None,
+        matched_keywords: None,
+        matched_lines: None,
+        tokenized_content: None,
+        parent_context: None,
+    })
+}
@@ -0,0 +1,142 @@
+//! Saved result sets, for `--save-results` and `probe show`.
+//!
+//! A snapshot stores the blocks of a search or extraction with their full code and
+//! ranking data, so the same result set can be rendered again later in any output
+//! format without running the search, and without the files having to still match.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use probe_code::models::SearchResult;
+
+/// Version of the snapshot file layout, bumped on incompatible changes
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// The command a snapshot was saved by, which decides how it is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotKind {
+    Search,
+    Extract,
+}
+
+/// A result set saved with `--save-results`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResultSnapshot {
+    pub version: u32,
+    pub kind: SnapshotKind,
+    /// The search query, or the extraction targets
+    pub query: String,
+    /// When the snapshot was saved, in seconds since the Unix epoch
+    pub saved_at: u64,
+    pub results: Vec<SearchResult>,
+}
+
+impl ResultSnapshot {
+    pub fn new(kind: SnapshotKind, query: &str, results: &[SearchResult]) -> Self {
+        ResultSnapshot {
+            version: SNAPSHOT_VERSION,
+            kind,
+            query: query.to_string(),
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or(0),
+            results: results.to_vec(),
+        }
+    }
+
+    /// Write the snapshot to `path` as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to save results to {}", path.display()))
+    }
+
+    /// Read a snapshot written by [`ResultSnapshot::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read saved results from {}", path.display()))?;
+        let snapshot: ResultSnapshot = serde_json::from_str(&content).with_context(|| {
+            format!(
+                "{} is not a result set saved with --save-results",
+                path.display()
+            )
+        })?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            anyhow::bail!(
+                "{} was saved by a newer probe (format version {}); upgrade probe to show it",
+                path.display(),
+                snapshot.version
+            );
+        }
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let result = SearchResult {
+            file: "src/lib.rs".to_string(),
+            lines: (3, 5),
+            node_type: "function_item".to_string(),
+            code: "fn run() {\n    go();\n}".into(),
+            symbol_signature: None,
+            matched_by_filename: None,
+            duplicate_files: None,
+            blame: None,
+            owners: None,
+            provenance: None,
+            rank: Some(1),
+            score: Some(0.5),
+            tfidf_score: None,
+            bm25_score: None,
+            tfidf_rank: None,
+            bm25_rank: None,
+            new_score: None,
+            hybrid2_rank: None,
+            combined_score_rank: None,
+            file_unique_terms: None,
+            file_total_matches: None,
+            file_match_rank: None,
+            block_unique_terms: None,
+            block_total_matches: None,
+            parent_file_id: None,
+            block_id: None,
+            matched_keywords: Some(vec!["run".to_string()]),
+            matched_lines: Some(vec![0]),
+            tokenized_content: Some(vec!["run".to_string()]),
+            parent_context: None,
+        };
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("results.json");
+        ResultSnapshot::new(SnapshotKind::Search, "run", &[result])
+            .save(&path)
+            .unwrap();
+
+        let snapshot = ResultSnapshot::load(&path).unwrap();
+        assert_eq!(snapshot.kind, SnapshotKind::Search);
+        assert_eq!(snapshot.query, "run");
+        let loaded = &snapshot.results[0];
+        assert_eq!(loaded.lines, (3, 5));
+        assert_eq!(loaded.code, "fn run() {\n    go();\n}");
+        assert_eq!(loaded.matched_lines, Some(vec![0]));
+        // Tokens are recomputed when needed rather than saved
+        assert_eq!(loaded.tokenized_content, None);
+    }
+
+    #[test]
+    fn test_load_rejects_other_json() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("other.json");
+        std::fs::write(&path, "{\"results\": []}").unwrap();
+        assert!(ResultSnapshot::load(&path).is_err());
+    }
+}
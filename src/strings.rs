@@ -0,0 +1,411 @@
+//! `probe strings`: list the string literals a program could show to its users, with
+//! the symbol each one is in, for localization audits and for reviewing the prompts
+//! of LLM applications.
+//!
+//! Literals are found with tree-sitter. Those that are never user-facing are left
+//! out: imports and includes, attribute and annotation arguments, docstrings, object
+//! keys, and strings without a letter. Arguments of translation functions such as
+//! `t()` or `gettext()` are marked as i18n keys, and with `--no-logs` arguments of
+//! logging calls are left out too.
+
+use anyhow::Result;
+use colored::*;
+use probe_code::extract::symbols::symbol_name;
+use probe_code::language::factory::get_language_impl;
+use probe_code::language::get_or_parse_tree_pooled;
+use probe_code::language::language_trait::LanguageImpl;
+use probe_code::search::file_list_cache::get_file_list;
+use probe_code::search::syntax_mask::is_string;
+use probe_code::warnings;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tree_sitter::Node;
+
+/// Receivers and namespaces of logging calls: `console.log`, `logger.info`, `log::warn!`
+const LOG_NAMES: &[&str] = &[
+    "console", "log", "logger", "logging", "logrus", "slog", "syslog", "tracing", "zap",
+];
+
+/// Logging calls by level, when called on something or as a macro: `self.log.debug`,
+/// `info!`
+const LOG_LEVELS: &[&str] = &[
+    "critical", "debug", "error", "fatal", "info", "trace", "warn", "warning",
+];
+
+/// Translation functions, whose string arguments are i18n keys or source messages
+const I18N_FUNCTIONS: &[&str] = &[
+    "_",
+    "__",
+    "$t",
+    "formatmessage",
+    "gettext",
+    "i18n",
+    "ngettext",
+    "pgettext",
+    "t",
+    "tr",
+    "translate",
+];
+
+pub struct StringsParams<'a> {
+    pub path: &'a Path,
+    /// Shortest string to report, in characters
+    pub min_length: usize,
+    /// Leave out the arguments of logging calls
+    pub no_logs: bool,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+/// A string literal and where it is
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct StringLiteral {
+    file: String,
+    line: usize,
+    /// The innermost named function, method or type around the literal, as
+    /// `Type.method`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<String>,
+    /// The literal's text between its quotes, escapes and interpolations as written
+    text: String,
+    /// Passed to a translation function
+    i18n: bool,
+}
+
+pub fn handle_strings(params: StringsParams) -> Result<()> {
+    warnings::set_json(params.format == "json");
+    let file_list = get_file_list(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.no_gitignore,
+    )?;
+    let mut strings: Vec<StringLiteral> = file_list
+        .files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let Ok(content) = fs::read_to_string(file) else {
+                return Vec::new();
+            };
+            scan_strings(&file.to_string_lossy(), &content, &params)
+        })
+        .collect();
+    strings.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&strings)?);
+        return Ok(());
+    }
+    if strings.is_empty() {
+        println!("No strings found");
+        return Ok(());
+    }
+    let mut current_file = None;
+    for string in &strings {
+        if current_file != Some(&string.file) {
+            if current_file.is_some() {
+                println!();
+            }
+            println!("{}", string.file.bold().green());
+            current_file = Some(&string.file);
+        }
+        let mut line = format!("  {:>5}  ", string.line);
+        if let Some(symbol) = &string.symbol {
+            line.push_str(&format!("{}  ", symbol.cyan()));
+        }
+        line.push_str(&format!("{:?}", string.text));
+        if string.i18n {
+            line.push_str(&format!("  {}", "[i18n]".dimmed()));
+        }
+        println!("{line}");
+    }
+    let files = strings
+        .iter()
+        .map(|string| &string.file)
+        .collect::<HashSet<_>>()
+        .len();
+    println!();
+    println!("Found {} strings in {} files", strings.len(), files);
+    Ok(())
+}
+
+/// The user-facing string literals of a file; none without a parser for it
+fn scan_strings(file: &str, content: &str, params: &StringsParams) -> Vec<StringLiteral> {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+    let Some(language_impl) = get_language_impl(extension) else {
+        return Vec::new();
+    };
+    let Ok(tree) = get_or_parse_tree_pooled(file, content, extension) else {
+        return Vec::new();
+    };
+    let mut strings = Vec::new();
+    collect_strings(
+        tree.root_node(),
+        content.as_bytes(),
+        language_impl.as_ref(),
+        params,
+        &mut |node, text| {
+            strings.push(StringLiteral {
+                file: file.to_string(),
+                line: node.start_position().row + 1,
+                symbol: enclosing_symbol(node, content.as_bytes(), language_impl.as_ref()),
+                text,
+                i18n: nearest_call(node, content.as_bytes())
+                    .is_some_and(|(callee, _)| is_i18n_call(&callee)),
+            });
+        },
+    );
+    strings
+}
+
+fn collect_strings(
+    node: Node,
+    source: &[u8],
+    language_impl: &dyn LanguageImpl,
+    params: &StringsParams,
+    found: &mut dyn FnMut(Node, String),
+) {
+    if !is_string(node.kind()) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect_strings(child, source, language_impl, params, found);
+        }
+        return;
+    }
+    let Ok(literal) = node.utf8_text(source) else {
+        return;
+    };
+    let text = unquote(literal);
+    if text.chars().count() < params.min_length
+        || !text.chars().any(char::is_alphabetic)
+        || !is_user_facing(node)
+    {
+        return;
+    }
+    if !params.allow_tests && in_test(node, source, language_impl) {
+        return;
+    }
+    if params.no_logs
+        && nearest_call(node, source)
+            .is_some_and(|(callee, macro_call)| is_log_call(&callee, macro_call))
+    {
+        return;
+    }
+    found(node, text.to_string());
+}
+
+/// The text of a literal without its prefix (`r`, `b`, `f`, `@`, ...) and quotes
+fn unquote(literal: &str) -> &str {
+    let Some(start) = literal.find(['"', '\'', '`']) else {
+        return literal;
+    };
+    let hashes = literal[..start].matches('#').count();
+    let body = &literal[start..];
+    let quote = if body.starts_with("\"\"\"") || body.starts_with("'''") {
+        &body[..3]
+    } else {
+        &body[..1]
+    };
+    let Some(inner) = body.strip_prefix(quote) else {
+        return literal;
+    };
+    let inner = inner.strip_suffix(&"#".repeat(hashes)).unwrap_or(inner);
+    inner.strip_suffix(quote).unwrap_or(inner)
+}
+
+/// Whether a literal could be shown to a user, judging by where it is
+fn is_user_facing(node: Node) -> bool {
+    let Some(parent) = node.parent() else {
+        return true;
+    };
+    // Docstrings and directives like "use strict"
+    if parent.kind() == "expression_statement" {
+        return false;
+    }
+    // Keys of object literals and dictionaries
+    if parent.child_by_field_name("key") == Some(node) {
+        return false;
+    }
+    let mut ancestor = Some(parent);
+    while let Some(node) = ancestor {
+        let kind = node.kind();
+        if ["import", "include", "attribute", "annotation", "decorator"]
+            .iter()
+            .any(|part| kind.contains(part))
+        {
+            return false;
+        }
+        ancestor = node.parent();
+    }
+    true
+}
+
+fn in_test(node: Node, source: &[u8], language_impl: &dyn LanguageImpl) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(node) = ancestor {
+        if language_impl.is_test_node(&node, source) {
+            return true;
+        }
+        ancestor = node.parent();
+    }
+    false
+}
+
+/// The callee of the innermost call around a node, like `logger.info` or `log::warn`,
+/// and whether it is a macro
+fn nearest_call(node: Node, source: &[u8]) -> Option<(String, bool)> {
+    let mut ancestor = node.parent();
+    while let Some(node) = ancestor {
+        let kind = node.kind();
+        let macro_call = kind == "macro_invocation";
+        if macro_call || kind.contains("call") || kind.contains("invocation") {
+            let text = node.utf8_text(source).ok()?;
+            let callee = text.split(['(', '!']).next().unwrap_or("").trim();
+            return Some((callee.to_string(), macro_call));
+        }
+        ancestor = node.parent();
+    }
+    None
+}
+
+fn callee_names(callee: &str) -> Vec<String> {
+    callee
+        .split(['.', ':'])
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn is_log_call(callee: &str, macro_call: bool) -> bool {
+    let names = callee_names(callee);
+    let Some(last) = names.last() else {
+        return false;
+    };
+    names.iter().any(|name| LOG_NAMES.contains(&name.as_str()))
+        || ((macro_call || names.len() > 1) && LOG_LEVELS.contains(&last.as_str()))
+}
+
+fn is_i18n_call(callee: &str) -> bool {
+    callee_names(callee)
+        .last()
+        .is_some_and(|last| I18N_FUNCTIONS.contains(&last.as_str()))
+}
+
+/// The named functions and types around a node, outermost first, as `Type.method`
+fn enclosing_symbol(node: Node, source: &[u8], language_impl: &dyn LanguageImpl) -> Option<String> {
+    let mut names = Vec::new();
+    let mut ancestor = node.parent();
+    while let Some(node) = ancestor {
+        if language_impl.is_acceptable_parent(&node) {
+            if let Some(name) = symbol_name(node, source) {
+                names.push(name);
+            }
+        }
+        ancestor = node.parent();
+    }
+    if names.is_empty() {
+        return None;
+    }
+    names.reverse();
+    Some(names.join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r##"use std::fmt;
+
+#[derive(Debug)]
+#[serde(rename = "settings")]
+struct Config;
+
+impl Config {
+    fn greet(&self, name: &str) -> String {
+        log::debug!("greeting {}", name);
+        let title = t("home.title");
+        format!("Welcome back, {name}!")
+    }
+}
+
+fn raw() -> &'static str {
+    r#"Say "hi""#
+}
+
+#[cfg(test)]
+mod tests {
+    fn test_greet() {
+        assert_eq!("Welcome", "Welcome");
+    }
+}
+"##;
+
+    fn scan(no_logs: bool, allow_tests: bool) -> Vec<(usize, Option<String>, String, bool)> {
+        let params = StringsParams {
+            path: Path::new("."),
+            min_length: 2,
+            no_logs,
+            allow_tests,
+            ignore: &[],
+            no_gitignore: false,
+            format: "plain",
+        };
+        scan_strings("strings_test.rs", SOURCE, &params)
+            .into_iter()
+            .map(|string| (string.line, string.symbol, string.text, string.i18n))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_strings() {
+        let greet = Some("Config.greet".to_string());
+        assert_eq!(
+            scan(false, false),
+            vec![
+                (9, greet.clone(), "greeting {}".to_string(), false),
+                (10, greet.clone(), "home.title".to_string(), true),
+                (
+                    11,
+                    greet.clone(),
+                    "Welcome back, {name}!".to_string(),
+                    false
+                ),
+                (16, Some("raw".to_string()), "Say \"hi\"".to_string(), false),
+            ]
+        );
+        let without_logs = scan(true, false);
+        assert_eq!(without_logs.len(), 3);
+        assert_eq!(without_logs[0].2, "home.title");
+        assert_eq!(scan(false, true).len(), 6);
+    }
+
+    #[test]
+    fn test_log_and_i18n_calls() {
+        assert!(is_log_call("console.log", false));
+        assert!(is_log_call("self.logger.warning", false));
+        assert!(is_log_call("tracing::info", true));
+        assert!(is_log_call("debug", true));
+        assert!(!is_log_call("debug", false));
+        assert!(!is_log_call("println", true));
+        assert!(is_i18n_call("i18n.t"));
+        assert!(is_i18n_call("_"));
+        assert!(!is_i18n_call("format"));
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("\"Hello\""), "Hello");
+        assert_eq!(unquote("f'Hi {name}'"), "Hi {name}");
+        assert_eq!(unquote("\"\"\"Block\"\"\""), "Block");
+        assert_eq!(unquote("r#\"a \"quote\"\"#"), "a \"quote\"");
+        assert_eq!(unquote("@\"verbatim\""), "verbatim");
+    }
+}
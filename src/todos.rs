@@ -0,0 +1,359 @@
+//! `probe todos`: report the TODO, FIXME and HACK notes (or other tags) left in
+//! comments.
+//!
+//! Only comments count: files are parsed with tree-sitter and tags are looked for in
+//! their comment nodes, so a `"TODO"` string or a `todo!()` call is not a note. Notes
+//! are grouped by tag, by file, or with `--blame` by the author of the line's last
+//! commit.
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+use probe_code::language::get_or_parse_tree_pooled;
+use probe_code::models::BlameInfo;
+use probe_code::search::blame::line_blame;
+use probe_code::search::file_list_cache::get_file_list;
+use probe_code::search::syntax_mask::comment_ranges;
+use probe_code::warnings::{self, warn_once, WarningKind};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub struct TodosParams<'a> {
+    pub path: &'a Path,
+    /// Tags to look for, matched case-sensitively as whole words
+    pub tags: &'a [String],
+    /// `tag`, `file` or `owner`
+    pub group_by: &'a str,
+    pub blame: bool,
+    pub allow_tests: bool,
+    pub ignore: &'a [String],
+    pub no_gitignore: bool,
+    pub format: &'a str,
+}
+
+/// A tagged note in a comment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Todo {
+    file: String,
+    line: usize,
+    tag: String,
+    /// Who the note is for, from `TODO(name): ...`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    text: String,
+    /// Last commit to change the line (--blame)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blame: Option<BlameInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct TodoGroup {
+    name: String,
+    count: usize,
+    todos: Vec<Todo>,
+}
+
+#[derive(Debug, Serialize)]
+struct TodoReport<'a> {
+    group_by: &'a str,
+    total: usize,
+    groups: Vec<TodoGroup>,
+}
+
+pub fn handle_todos(params: TodosParams) -> Result<()> {
+    let tags: Vec<&str> = params
+        .tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    if tags.is_empty() {
+        bail!("No tags to look for");
+    }
+    if params.group_by == "owner" && !params.blame {
+        bail!("--group-by owner needs --blame, which finds who last changed each note");
+    }
+    warnings::set_json(params.format == "json");
+    let matcher = tag_regex(&tags)?;
+
+    let file_list = get_file_list(
+        params.path,
+        params.allow_tests,
+        params.ignore,
+        params.no_gitignore,
+    )?;
+    let mut todos: Vec<Todo> = file_list
+        .files
+        .par_iter()
+        .flat_map_iter(|file| {
+            let Ok(content) = fs::read_to_string(file) else {
+                return Vec::new();
+            };
+            // Most files have no tags at all; don't parse those
+            if !tags.iter().any(|tag| content.contains(tag)) {
+                return Vec::new();
+            }
+            let mut todos = scan_comments(&file.to_string_lossy(), &content, &matcher);
+            if params.blame && !todos.is_empty() {
+                annotate_blame(file, &mut todos);
+            }
+            todos
+        })
+        .collect();
+    todos.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+
+    let report = TodoReport {
+        group_by: params.group_by,
+        total: todos.len(),
+        groups: group(todos, params.group_by),
+    };
+    match params.format {
+        "json" => println!("{}", serde_json::to_string_pretty(&report)?),
+        "markdown" => print!("{}", markdown(&report)),
+        _ => print_plain(&report),
+    }
+    Ok(())
+}
+
+/// Matches a tag as a whole word, then an optional `(assignee)` and the note's text
+fn tag_regex(tags: &[&str]) -> Result<Regex> {
+    let alternatives: Vec<String> = tags.iter().map(|tag| regex::escape(tag)).collect();
+    let pattern = format!(
+        r"(?:^|[^\w])({})(?:\(([^)]*)\))?(?:[^\w(]|$)(.*)",
+        alternatives.join("|")
+    );
+    Regex::new(&pattern).context("Invalid tags")
+}
+
+/// The tagged notes in the comments of a file, at most one per line
+fn scan_comments(file: &str, content: &str, matcher: &Regex) -> Vec<Todo> {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("");
+    // Files without a parser have no comments we can tell apart from code
+    let Ok(tree) = get_or_parse_tree_pooled(file, content, extension) else {
+        return Vec::new();
+    };
+
+    let mut todos = Vec::new();
+    for range in comment_ranges(&tree) {
+        let first_line = content[..range.start].matches('\n').count() + 1;
+        for (offset, text) in content[range].lines().enumerate() {
+            let Some(captures) = matcher.captures(text) else {
+                continue;
+            };
+            todos.push(Todo {
+                file: file.to_string(),
+                line: first_line + offset,
+                tag: captures[1].to_string(),
+                assignee: captures
+                    .get(2)
+                    .map(|assignee| assignee.as_str().trim().to_string())
+                    .filter(|assignee| !assignee.is_empty()),
+                text: note_text(&captures[3]),
+                blame: None,
+            });
+        }
+    }
+    todos
+}
+
+/// The note after its tag, without separators or the comment's closing delimiter
+fn note_text(text: &str) -> String {
+    let text = text.trim();
+    let text = ["*/", "-->", "#}", "%}"]
+        .iter()
+        .fold(text, |text, end| text.strip_suffix(end).unwrap_or(text));
+    text.trim()
+        .trim_start_matches([':', '-', ' '])
+        .trim()
+        .to_string()
+}
+
+fn annotate_blame(file: &Path, todos: &mut [Todo]) {
+    let lines: Vec<usize> = todos.iter().map(|todo| todo.line).collect();
+    let Some(blamed) = line_blame(file, &lines) else {
+        let name = file.to_string_lossy();
+        warn_once(
+            WarningKind::Other,
+            &name,
+            format!("Can't blame {name}: not tracked by git"),
+        );
+        return;
+    };
+    for todo in todos {
+        todo.blame = blamed.get(&todo.line).cloned();
+    }
+}
+
+/// Group notes sorted by file and line, largest groups first
+fn group(todos: Vec<Todo>, group_by: &str) -> Vec<TodoGroup> {
+    let mut groups: BTreeMap<String, Vec<Todo>> = BTreeMap::new();
+    for todo in todos {
+        let name = match group_by {
+            "file" => todo.file.clone(),
+            "owner" => todo
+                .blame
+                .as_ref()
+                .map_or_else(|| "Not committed".to_string(), |blame| blame.author.clone()),
+            _ => todo.tag.clone(),
+        };
+        groups.entry(name).or_default().push(todo);
+    }
+    let mut groups: Vec<TodoGroup> = groups
+        .into_iter()
+        .map(|(name, todos)| TodoGroup {
+            name,
+            count: todos.len(),
+            todos,
+        })
+        .collect();
+    // Stable, so groups of the same size stay in name order
+    groups.sort_by_key(|group| std::cmp::Reverse(group.count));
+    groups
+}
+
+/// What a note shows besides its group's name
+fn describe(todo: &Todo, group_by: &str) -> String {
+    let mut parts = Vec::new();
+    if group_by != "file" {
+        parts.push(format!("{}:{}", todo.file, todo.line));
+    } else {
+        parts.push(format!("{}", todo.line));
+    }
+    if group_by != "tag" {
+        parts.push(todo.tag.clone());
+    }
+    if let Some(assignee) = &todo.assignee {
+        parts.push(format!("({assignee})"));
+    }
+    parts.join(" ")
+}
+
+fn blame_suffix(todo: &Todo, group_by: &str) -> Option<String> {
+    let blame = todo.blame.as_ref()?;
+    Some(if group_by == "owner" {
+        blame.date.clone()
+    } else {
+        format!("{}, {}", blame.author, blame.date)
+    })
+}
+
+fn print_plain(report: &TodoReport) {
+    if report.total == 0 {
+        println!("No notes found");
+        return;
+    }
+    for group in &report.groups {
+        println!("{} ({})", group.name.bold().green(), group.count);
+        for todo in &group.todos {
+            let mut line = format!("  {} {}", describe(todo, report.group_by).cyan(), todo.text);
+            if let Some(suffix) = blame_suffix(todo, report.group_by) {
+                line.push_str(&format!(" {}", format!("[{suffix}]").dimmed()));
+            }
+            println!("{line}");
+        }
+        println!();
+    }
+    let files = report
+        .groups
+        .iter()
+        .flat_map(|group| group.todos.iter().map(|todo| &todo.file))
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    println!("Found {} notes in {} files", report.total, files);
+}
+
+fn markdown(report: &TodoReport) -> String {
+    let mut output = String::from("# Notes\n\n");
+    if report.total == 0 {
+        output.push_str("No notes found.\n");
+        return output;
+    }
+    output.push_str(&format!(
+        "{} notes, grouped by {}.\n",
+        report.total, report.group_by
+    ));
+    for group in &report.groups {
+        output.push_str(&format!("\n## {} ({})\n\n", group.name, group.count));
+        for todo in &group.todos {
+            output.push_str(&format!("- `{}`", describe(todo, report.group_by)));
+            if !todo.text.is_empty() {
+                output.push_str(&format!(" {}", todo.text));
+            }
+            if let Some(suffix) = blame_suffix(todo, report.group_by) {
+                output.push_str(&format!(" _({suffix})_"));
+            }
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"// TODO: split this module
+fn main() {
+    let todo = "TODO: not a comment";
+    /* FIXME(ada): handle errors
+       and HACK around the parser */
+    todo!(); // TODOS are not a tag
+}
+"#;
+
+    #[test]
+    fn test_scan_comments() {
+        let matcher = tag_regex(&["TODO", "FIXME", "HACK"]).unwrap();
+        let todos = scan_comments("todos_test.rs", SOURCE, &matcher);
+        let found: Vec<(usize, &str, Option<&str>, &str)> = todos
+            .iter()
+            .map(|todo| {
+                (
+                    todo.line,
+                    todo.tag.as_str(),
+                    todo.assignee.as_deref(),
+                    todo.text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, "TODO", None, "split this module"),
+                (4, "FIXME", Some("ada"), "handle errors"),
+                (5, "HACK", None, "around the parser"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_file() {
+        let todo = |file: &str, tag: &str| Todo {
+            file: file.to_string(),
+            line: 1,
+            tag: tag.to_string(),
+            assignee: None,
+            text: String::new(),
+            blame: None,
+        };
+        let groups = group(
+            vec![
+                todo("a.rs", "TODO"),
+                todo("b.rs", "TODO"),
+                todo("b.rs", "FIXME"),
+            ],
+            "file",
+        );
+        let names: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|group| (group.name.as_str(), group.count))
+            .collect();
+        assert_eq!(names, vec![("b.rs", 2), ("a.rs", 1)]);
+    }
+}
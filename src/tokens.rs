@@ -0,0 +1,146 @@
+//! `probe tokens`: count tokens in files, directories or stdin.
+//!
+//! The default tokenizer is the one behind `--max-tokens`, so the numbers can be used
+//! to budget prompts consistently with probe's own limits.
+
+use anyhow::{Context, Result};
+use ignore::WalkBuilder;
+use probe_code::search::file_list_cache::PROBEIGNORE_FILENAME;
+use probe_code::search::search_tokens::get_tokenizer;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tiktoken_rs::CoreBPE;
+
+pub struct TokensParams {
+    pub paths: Vec<PathBuf>,
+    pub tokenizer: String,
+    pub format: String,
+    pub no_gitignore: bool,
+}
+
+#[derive(Serialize)]
+struct FileTokens {
+    path: String,
+    tokens: usize,
+}
+
+#[derive(Serialize)]
+struct TokenReport<'a> {
+    tokenizer: &'a str,
+    files: Vec<FileTokens>,
+    total: usize,
+    /// Files that could not be read as text (binary or not UTF-8)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+}
+
+pub fn handle_tokens(params: TokensParams) -> Result<()> {
+    let owned;
+    let bpe: &CoreBPE = match params.tokenizer.as_str() {
+        "p50k" => get_tokenizer(),
+        name => {
+            owned = load_tokenizer(name)?;
+            &owned
+        }
+    };
+
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+
+    for path in &params.paths {
+        if path.as_os_str() == "-" {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read stdin")?;
+            files.push(FileTokens {
+                path: "-".to_string(),
+                tokens: bpe.encode_with_special_tokens(&input).len(),
+            });
+            continue;
+        }
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Path does not exist: {}", path.display()));
+        }
+
+        let counted: Vec<(PathBuf, Option<usize>)> = collect_files(path, params.no_gitignore)
+            .into_par_iter()
+            .map(|file| {
+                let tokens = fs::read_to_string(&file)
+                    .ok()
+                    .map(|content| bpe.encode_with_special_tokens(&content).len());
+                (file, tokens)
+            })
+            .collect();
+        for (file, tokens) in counted {
+            match tokens {
+                Some(tokens) => files.push(FileTokens {
+                    path: file.to_string_lossy().to_string(),
+                    tokens,
+                }),
+                None => skipped.push(file.to_string_lossy().to_string()),
+            }
+        }
+    }
+
+    let report = TokenReport {
+        tokenizer: &params.tokenizer,
+        total: files.iter().map(|f| f.tokens).sum(),
+        files,
+        skipped,
+    };
+
+    if params.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let width = report.total.to_string().len();
+    for file in &report.files {
+        println!("{:>width$}  {}", file.tokens, file.path);
+    }
+    if report.files.len() != 1 {
+        println!("{:>width$}  total", report.total);
+    }
+    if !report.skipped.is_empty() {
+        eprintln!("Skipped {} non-text files", report.skipped.len());
+    }
+
+    Ok(())
+}
+
+/// Tokenizers other than the default `p50k`
+fn load_tokenizer(name: &str) -> Result<CoreBPE> {
+    match name {
+        "cl100k" => tiktoken_rs::cl100k_base(),
+        "o200k" => tiktoken_rs::o200k_base(),
+        "r50k" => tiktoken_rs::r50k_base(),
+        _ => Err(anyhow::anyhow!("Unknown tokenizer '{name}'")),
+    }
+}
+
+/// All files under `path` (or `path` itself), respecting ignore files, sorted by path
+fn collect_files(path: &Path, no_gitignore: bool) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut walker_builder = WalkBuilder::new(path);
+    walker_builder
+        .git_ignore(!no_gitignore)
+        .git_global(!no_gitignore)
+        .git_exclude(!no_gitignore)
+        .add_custom_ignore_filename(PROBEIGNORE_FILENAME);
+
+    let mut files: Vec<PathBuf> = walker_builder
+        .build()
+        .flatten()
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files
+}
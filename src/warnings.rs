@@ -0,0 +1,127 @@
+//! Warnings about how a search or extraction was carried out: line ranges clamped to
+//! the file, files without a parser, skipped binary files, files that failed to parse.
+//!
+//! Warnings always go to stderr, so they never mix with the results on stdout. With
+//! `--format json` each one is a JSON object on its own line, for consumers that need
+//! to tell them apart from other diagnostics. Otherwise the ones that were always shown
+//! are printed as `Warning: ...`, and the rest only appear in debug logs.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// A requested line range was outside the file and was clamped to it
+    ClampedLines,
+    /// No parser exists for a file's extension, so its matches have no AST context
+    UnsupportedExtension,
+    /// Binary files were left out of the search
+    BinarySkipped,
+    /// A file could not be parsed and its lines were used as they are
+    ParseFailed,
+    /// The query could not be parsed or used
+    InvalidQuery,
+    Other,
+}
+
+impl WarningKind {
+    /// Whether plain output shows this warning; the others are only logged at debug level
+    fn shown_in_text(self) -> bool {
+        matches!(self, WarningKind::InvalidQuery | WarningKind::Other)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Warning {
+    /// Always `"warning"`, to tell these lines apart from other JSON on stderr
+    #[serde(rename = "type")]
+    pub record_type: &'static str,
+    pub kind: WarningKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, file: Option<&Path>, message: impl Into<String>) -> Self {
+        Warning {
+            record_type: "warning",
+            kind,
+            file: file.map(|file| file.to_string_lossy().to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+static JSON: AtomicBool = AtomicBool::new(false);
+static SEEN: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Print warnings as JSON lines, for `--format json`
+pub fn set_json(enabled: bool) {
+    JSON.store(enabled, Ordering::Relaxed);
+}
+
+pub fn json_enabled() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Report a warning on stderr
+pub fn warn(kind: WarningKind, file: Option<&Path>, message: impl Into<String>) {
+    emit(&Warning::new(kind, file, message));
+}
+
+/// Report a warning only the first time `key` is seen in this process, for warnings
+/// that would otherwise repeat for every file
+pub fn warn_once(kind: WarningKind, key: &str, message: impl Into<String>) {
+    let seen = SEEN.get_or_init(|| Mutex::new(HashSet::new()));
+    let first = seen
+        .lock()
+        .map(|mut seen| seen.insert(format!("{kind:?}:{key}")))
+        .unwrap_or(true);
+    if first {
+        warn(kind, None, message);
+    }
+}
+
+fn emit(warning: &Warning) {
+    if json_enabled() {
+        eprintln!("{}", to_json_line(warning));
+    } else if warning.kind.shown_in_text() {
+        eprintln!("Warning: {}", warning.message);
+    } else {
+        debug!("{}", warning.message);
+    }
+}
+
+fn to_json_line(warning: &Warning) -> String {
+    serde_json::to_string(warning).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_json_line() {
+        let warning = Warning::new(
+            WarningKind::ClampedLines,
+            Some(Path::new("src/main.rs")),
+            "Requested lines 90-120 are outside the file; using 90-95",
+        );
+        let line = to_json_line(&warning);
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["type"], "warning");
+        assert_eq!(value["kind"], "clamped_lines");
+        assert_eq!(value["file"], "src/main.rs");
+
+        let line = to_json_line(&Warning::new(WarningKind::BinarySkipped, None, "x"));
+        assert!(!line.contains("\"file\""));
+        assert!(line.contains("\"kind\":\"binary_skipped\""));
+    }
+}
@@ -447,6 +447,7 @@ fn test_required_terms_query() {
         term_indices: indices.clone(),
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -456,6 +457,10 @@ fn test_required_terms_query() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Use the term indices directly
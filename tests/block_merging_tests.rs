@@ -15,9 +15,13 @@ fn test_merge_ranked_blocks() {
         node_type: "function".to_string(),
         code:
             "fn test_function() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", x + y);\n}"
-                .to_string(),
+                .into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(1),
         score: Some(0.9),
         tfidf_score: Some(0.8),
@@ -43,9 +47,13 @@ fn test_merge_ranked_blocks() {
     file: "test_file.rs".to_string(),
     lines: (6, 10),
     node_type: "function".to_string(),
-    code: "fn another_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".to_string(),
+    code: "fn another_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".into(),
         symbol_signature: None,
     matched_by_filename: None,
+    duplicate_files: None,
+    blame: None,
+    owners: None,
+    provenance: None,
     rank: Some(2),
     score: Some(0.8),
     tfidf_score: Some(0.7),
@@ -73,9 +81,13 @@ fn test_merge_ranked_blocks() {
         file: "other_file.rs".to_string(),
         lines: (1, 5),
         node_type: "function".to_string(),
-        code: "fn other_function() {\n    let a = 10;\n    let b = 20;\n    println!(\"{}\", a + b);\n}".to_string(),
+        code: "fn other_function() {\n    let a = 10;\n    let b = 20;\n    println!(\"{}\", a + b);\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(3),
         score: Some(0.7),
         tfidf_score: Some(0.6),
@@ -183,26 +195,14 @@ fn test_integration_with_search_flow() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "combined",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(20), // Increase threshold to ensure non-adjacent blocks are merged
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run a search that should produce multiple overlapping blocks
@@ -298,26 +298,14 @@ fn test_no_merge_flag() {
 
     // First test with merging enabled (no_merge = false)
     let options_with_merge = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "combined",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(20), // Increase threshold to ensure non-adjacent blocks are merged
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run a search that should produce merged blocks
@@ -325,26 +313,15 @@ fn test_no_merge_flag() {
 
     // Now test with merging disabled (no_merge = true)
     let options_without_merge = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "combined",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
         merge_threshold: Some(20), // Increase threshold to ensure non-adjacent blocks are merged
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run a search that should not merge blocks
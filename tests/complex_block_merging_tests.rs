@@ -15,9 +15,13 @@ fn test_merge_different_node_types() {
         node_type: "function".to_string(),
         code:
             "fn test_function() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", x + y);\n}"
-                .to_string(),
+                .into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(1),
         score: Some(0.9),
         tfidf_score: Some(0.8),
@@ -43,9 +47,13 @@ fn test_merge_different_node_types() {
     file: "mixed_types.rs".to_string(),
     lines: (6, 10),
     node_type: "comment".to_string(),
-    code: "// This is a comment block\n// It explains the function above\n// And provides context\n// For the next function\n// Below".to_string(),
+    code: "// This is a comment block\n// It explains the function above\n// And provides context\n// For the next function\n// Below".into(),
         symbol_signature: None,
     matched_by_filename: None,
+    duplicate_files: None,
+    blame: None,
+    owners: None,
+    provenance: None,
     rank: Some(2),
     score: Some(0.8),
     tfidf_score: Some(0.7),
@@ -72,9 +80,13 @@ fn test_merge_different_node_types() {
         file: "mixed_types.rs".to_string(),
         lines: (11, 15),
         node_type: "function".to_string(),
-        code: "fn another_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".to_string(),
+        code: "fn another_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(3),
         score: Some(0.7),
         tfidf_score: Some(0.6),
@@ -142,9 +154,13 @@ fn test_merge_with_gaps() {
         node_type: "function".to_string(),
         code:
             "fn first_function() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", x + y);\n}"
-                .to_string(),
+                .into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(1),
         score: Some(0.9),
         tfidf_score: Some(0.8),
@@ -172,9 +188,13 @@ fn test_merge_with_gaps() {
         file: "gaps.rs".to_string(),
         lines: (9, 13),
         node_type: "function".to_string(),
-        code: "fn second_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".to_string(),
+        code: "fn second_function() {\n    let z = 3;\n    let result = z * 2;\n    println!(\"{}\", result);\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(2),
         score: Some(0.8),
         tfidf_score: Some(0.7),
@@ -204,9 +224,13 @@ fn test_merge_with_gaps() {
         node_type: "function".to_string(),
         code:
             "fn third_function() {\n    let a = 4;\n    let b = 5;\n    println!(\"{}\", a + b);\n}"
-                .to_string(),
+                .into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(3),
         score: Some(0.7),
         tfidf_score: Some(0.6),
@@ -302,9 +326,13 @@ fn test_merge_overlapping_blocks() {
         file: "overlap.rs".to_string(),
         lines: (1, 7),
         node_type: "function".to_string(),
-        code: "fn first_function() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", x + y);\n    // Shared lines\n    let shared = true;\n}".to_string(),
+        code: "fn first_function() {\n    let x = 1;\n    let y = 2;\n    println!(\"{}\", x + y);\n    // Shared lines\n    let shared = true;\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(1),
         score: Some(0.9),
         tfidf_score: Some(0.8),
@@ -332,9 +360,13 @@ fn test_merge_overlapping_blocks() {
         file: "overlap.rs".to_string(),
         lines: (5, 10),
         node_type: "function".to_string(),
-        code: "    // Shared lines\n    let shared = true;\n}\n\nfn second_function() {\n    let z = 3;\n}".to_string(),
+        code: "    // Shared lines\n    let shared = true;\n}\n\nfn second_function() {\n    let z = 3;\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(2),
         score: Some(0.8),
         tfidf_score: Some(0.7),
@@ -431,26 +463,15 @@ fn third_function() {
     for &threshold in &[2, 5, 10, 20] {
         // Create SearchOptions with the current threshold
         let options = SearchOptions {
-            path: temp_path,
+            paths: &[temp_path.to_path_buf()],
             queries: &queries,
-            files_only: false,
             custom_ignores: &custom_ignores,
             exclude_filenames: true,
-            language: None,
             reranker: "combined",
             frequency_search: false,
-            max_results: None,
-            max_bytes: None,
-            max_tokens: None,
             allow_tests: true,
-            no_merge: false,
             merge_threshold: Some(threshold),
-            dry_run: false,
-            session: None,
-            timeout: 30,
-            question: None,
-            exact: false,
-            no_gitignore: false,
+            ..Default::default()
         };
 
         // Run the search
@@ -508,9 +529,13 @@ fn test_merge_parent_child_blocks() {
         file: "parent_child.rs".to_string(),
         lines: (1, 10),
         node_type: "class".to_string(),
-        code: "struct TestStruct {\n    x: i32,\n    y: i32,\n}\n\nimpl TestStruct {\n    fn new(x: i32, y: i32) -> Self {\n        Self { x, y }\n    }\n}".to_string(),
+        code: "struct TestStruct {\n    x: i32,\n    y: i32,\n}\n\nimpl TestStruct {\n    fn new(x: i32, y: i32) -> Self {\n        Self { x, y }\n    }\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(2),
         score: Some(0.8),
         tfidf_score: Some(0.7),
@@ -538,9 +563,13 @@ fn test_merge_parent_child_blocks() {
         file: "parent_child.rs".to_string(),
         lines: (7, 9),
         node_type: "function".to_string(),
-        code: "    fn new(x: i32, y: i32) -> Self {\n        Self { x, y }\n    }".to_string(),
+        code: "    fn new(x: i32, y: i32) -> Self {\n        Self { x, y }\n    }".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: Some(1),
         score: Some(0.9),
         tfidf_score: Some(0.8),
@@ -292,26 +292,13 @@ func (i *IPWhiteListMiddleware) Process() {
 
     // Create SearchOptions with stemming enabled (exact=false)
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -373,26 +360,14 @@ func Process() {
 
     // Create SearchOptions with filename matching enabled
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: false, // Include filenames in search
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -6,7 +6,7 @@ fn test_control_flow_closing_braces_in_outline_format() {
     let output = Command::new("./target/release/probe")
         .args([
             "search",
-            "println",
+            "trace",
             "./src/language/rust.rs",
             "--format",
             "outline",
@@ -28,8 +28,8 @@ fn test_control_flow_closing_braces_in_outline_format() {
         // This particular search might not have control flow, which is ok
         // Just verify we have the expected structure
         assert!(
-            stdout.contains("println"),
-            "Should contain println in search results"
+            stdout.contains("trace"),
+            "Should contain trace in search results"
         );
         return;
     }
@@ -103,26 +103,14 @@ fn test_required_term_query() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the temp_path for debugging
@@ -213,33 +201,21 @@ fn test_excluded_term_query() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the query for debugging
     println!("Executing search with query: {queries:?}");
     println!(
-        "Path: {:?}, frequency_search: {}",
-        options.path, options.frequency_search
+        "Paths: {:?}, frequency_search: {}",
+        options.paths, options.frequency_search
     );
 
     // Run the search
@@ -307,26 +283,15 @@ fn test_or_query() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
         files_only: true, // Use files_only to ensure we find all matching files
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: true, // Enable frequency search to improve matching
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the test files for debugging
@@ -347,8 +312,8 @@ fn test_or_query() {
     // Print the query for debugging
     println!("Executing search with query: {queries:?}");
     println!(
-        "Path: {:?}, frequency_search: {}",
-        options.path, options.frequency_search
+        "Paths: {:?}, frequency_search: {}",
+        options.paths, options.frequency_search
     );
 
     // Run the search
@@ -409,26 +374,14 @@ fn test_complex_query_or() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: true, // Enable frequency search to improve matching
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the test files for debugging
@@ -449,8 +402,8 @@ fn test_complex_query_or() {
     // Print the query for debugging
     println!("Executing search with query: {queries:?}");
     println!(
-        "Path: {:?}, frequency_search: {}",
-        options.path, options.frequency_search
+        "Paths: {:?}, frequency_search: {}",
+        options.paths, options.frequency_search
     );
 
     // Run the search
@@ -509,33 +462,21 @@ fn test_complex_query_exclusion() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the query for debugging
     println!("Executing search with query: {queries:?}");
     println!(
-        "Path: {:?}, frequency_search: {}",
-        options.path, options.frequency_search
+        "Paths: {:?}, frequency_search: {}",
+        options.paths, options.frequency_search
     );
 
     // Run the search
@@ -609,26 +550,14 @@ fn test_function() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -717,6 +646,7 @@ fn test_filter_code_block_with_ast() {
             set
         },
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -726,6 +656,10 @@ fn test_filter_code_block_with_ast() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Create term matches for a block
@@ -809,6 +743,7 @@ fn test_filter_tokenized_block() {
             set
         },
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -818,6 +753,10 @@ fn test_filter_tokenized_block() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Import the function from probe crate
@@ -897,6 +836,7 @@ fn test_filter_tokenized_block() {
         term_indices: term_indices_or.clone(),
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -906,6 +846,10 @@ fn test_filter_tokenized_block() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Test with only keywordGamma (lowercased since tokenization lowercases)
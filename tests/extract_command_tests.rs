@@ -4,7 +4,8 @@ use std::process::Command;
 
 // Import the necessary functions from the extract module
 use probe_code::extract::{
-    extract_file_paths_from_git_diff, format_and_print_extraction_results, is_git_diff_format,
+    exclude_leading_attributes, extract_file_paths_from_git_diff,
+    format_and_print_extraction_results, include_preceding_doc_comments, is_git_diff_format,
     process_file_for_extraction,
 };
 
@@ -149,6 +150,111 @@ fn test_process_file_for_extraction_fallback() {
     assert!(end_line - start_line <= 11); // At most 11 lines (5 before, 5 after, plus the line itself)
 }
 
+#[test]
+fn test_process_file_for_extraction_provenance() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let rust_file = temp_dir.path().join("lib.rs");
+    fs::write(&rust_file, "fn a() {\n    let x = 1;\n}\n\nfn b() {}\n").unwrap();
+    let text_file = temp_dir.path().join("notes.xyz");
+    fs::write(&text_file, "one\ntwo\nthree\n").unwrap();
+
+    let provenance = |path: &PathBuf, start, end, symbol| {
+        process_file_for_extraction(path, start, end, symbol, false, 1, None, false)
+            .unwrap()
+            .provenance
+            .unwrap()
+    };
+
+    let p = provenance(&rust_file, Some(2), None, None);
+    assert_eq!(p.requested, format!("{}:2", rust_file.display()));
+    assert_eq!(p.strategy, "ast-range");
+    assert_eq!(p.clamped_from, None);
+    assert_eq!(p.confidence, 0.9);
+
+    let p = provenance(&rust_file, Some(1), Some(99), None);
+    assert_eq!(p.strategy, "ast-range");
+    assert_eq!(p.clamped_from, Some((1, 99)));
+    assert_eq!(p.confidence, 0.6);
+
+    let p = provenance(&rust_file, None, None, Some("b"));
+    assert_eq!(p.requested, format!("{}#b", rust_file.display()));
+    assert_eq!(p.strategy, "symbol");
+    assert_eq!(p.confidence, 1.0);
+
+    assert_eq!(
+        provenance(&text_file, Some(2), None, None).strategy,
+        "context"
+    );
+    assert_eq!(
+        provenance(&text_file, Some(1), Some(2), None).strategy,
+        "literal-fallback"
+    );
+    let p = provenance(&text_file, None, None, None);
+    assert_eq!((p.strategy.as_str(), p.confidence), ("file", 1.0));
+    // A symbol of a file without a parser falls back to the whole file
+    let p = provenance(&text_file, None, None, Some("two"));
+    assert_eq!((p.strategy.as_str(), p.confidence), ("file", 0.3));
+
+    // Fallbacks are trusted less than AST blocks
+    assert_eq!(provenance(&text_file, Some(2), None, None).confidence, 0.5);
+    assert_eq!(
+        provenance(&text_file, Some(1), Some(2), None).confidence,
+        0.4
+    );
+}
+
+#[test]
+fn test_process_file_for_extraction_symbol_not_found() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let rust_file = temp_dir.path().join("lib.rs");
+    fs::write(
+        &rust_file,
+        "pub fn parse_config() {}\npub fn parse_args() {}\nstruct Config;\nfn unrelated() {}\n",
+    )
+    .unwrap();
+
+    let result = process_file_for_extraction(
+        &rust_file,
+        None,
+        None,
+        Some("parse_confg"),
+        false,
+        0,
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(result.node_type, "symbol_not_found");
+    assert!(result.code.is_empty());
+    let provenance = result.provenance.unwrap();
+    assert_eq!(provenance.strategy, "not-found");
+    assert_eq!(provenance.suggestions, vec!["parse_config", "parse_args"]);
+
+    // The structured result is reported in the JSON output rather than only on stderr
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "extract",
+            &format!("{}#Confg", rust_file.display()),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout[stdout.find('{').unwrap()..])
+        .expect("Output should be valid JSON");
+    let entry = &json["results"][0];
+    assert_eq!(entry["node_type"], "symbol_not_found");
+    assert_eq!(entry["provenance"]["suggestions"][0], "Config");
+    assert!(entry["error"]
+        .as_str()
+        .unwrap()
+        .contains("Did you mean: Config?"));
+}
+
 #[test]
 fn test_format_and_print_extraction_results() {
     // Create a simple search result
@@ -156,9 +262,13 @@ fn test_format_and_print_extraction_results() {
         file: "test_file.rs".to_string(),
         lines: (1, 5),
         node_type: "function".to_string(),
-        code: "fn test() {\n    println!(\"Hello\");\n}".to_string(),
+        code: "fn test() {\n    println!(\"Hello\");\n}".into(),
         symbol_signature: None,
         matched_by_filename: None,
+        duplicate_files: None,
+        blame: None,
+        owners: None,
+        provenance: None,
         rank: None,
         score: None,
         tfidf_score: None,
@@ -661,6 +771,105 @@ fn test_process_file_for_extraction_with_range() {
     assert_eq!(result.node_type, "range");
 }
 
+#[test]
+fn test_include_preceding_doc_comments() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    // Rust: rustdoc and attributes sit outside the function node
+    let rust_path = temp_dir.path().join("lib.rs");
+    let rust_content = r#"use std::fmt;
+
+// Ordinary comment, not documentation
+
+/// Parses the input.
+///
+/// Returns the trimmed string.
+#[inline]
+pub fn parse(input: &str) -> &str {
+    input.trim()
+}
+"#;
+    fs::write(&rust_path, rust_content).unwrap();
+
+    let mut result =
+        process_file_for_extraction(&rust_path, None, None, Some("parse"), false, 0, None, false)
+            .unwrap();
+    assert!(!result.code.contains("/// Parses the input."));
+
+    include_preceding_doc_comments(&rust_path, &mut result).unwrap();
+    assert_eq!(result.lines, (5, 11));
+    assert!(result.code.starts_with("/// Parses the input."));
+    assert!(result.code.contains("#[inline]"));
+    assert!(!result.code.contains("Ordinary comment"));
+
+    // JavaScript: only JSDoc blocks count as documentation
+    let js_path = temp_dir.path().join("util.js");
+    let js_content = r#"// helper section
+
+/**
+ * Adds two numbers.
+ */
+function add(a, b) {
+  return a + b;
+}
+
+// plain comment
+function sub(a, b) {
+  return a - b;
+}
+"#;
+    fs::write(&js_path, js_content).unwrap();
+
+    let mut result =
+        process_file_for_extraction(&js_path, None, None, Some("add"), false, 0, None, false)
+            .unwrap();
+    include_preceding_doc_comments(&js_path, &mut result).unwrap();
+    assert_eq!(result.lines.0, 3);
+    assert!(result.code.starts_with("/**"));
+
+    let mut result =
+        process_file_for_extraction(&js_path, None, None, Some("sub"), false, 0, None, false)
+            .unwrap();
+    let original_lines = result.lines;
+    include_preceding_doc_comments(&js_path, &mut result).unwrap();
+    assert_eq!(result.lines, original_lines);
+    assert!(!result.code.contains("plain comment"));
+}
+
+#[test]
+fn test_extraction_includes_attached_attributes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("point.rs");
+    let content = r#"use std::fmt;
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+"#;
+    fs::write(&file_path, content).unwrap();
+
+    // Symbol extraction starts at the attributes
+    let mut result =
+        process_file_for_extraction(&file_path, None, None, Some("Point"), false, 0, None, false)
+            .unwrap();
+    assert_eq!(result.lines, (3, 8));
+    assert!(result.code.starts_with("#[derive(Debug, Clone)]"));
+
+    // --no-attributes drops them again
+    exclude_leading_attributes(&file_path, &mut result).unwrap();
+    assert_eq!(result.lines, (5, 8));
+    assert!(result.code.starts_with("struct Point"));
+
+    // Line extraction inside the item resolves to the same attributed block
+    let result =
+        process_file_for_extraction(&file_path, Some(6), None, None, false, 0, None, false)
+            .unwrap();
+    assert_eq!(result.lines, (3, 8));
+}
+
 #[test]
 fn test_integration_extract_command() {
     // Create a temporary file for testing
@@ -52,6 +52,16 @@ fn standalone_function() {
         prompt: None,
         instructions: None,
         no_gitignore: false,
+        with_doc_comments: false,
+        no_attributes: false,
+        deps: Vec::new(),
+        redact_secrets: false,
+        no_whole_files: false,
+        save_results: None,
+        include: Vec::new(),
+        blame: false,
+        owners: false,
+        public_only: false,
     };
 
     // Call handle_extract
@@ -123,9 +133,9 @@ fn standalone_function() {
     println!("Command stdout: {stdout}");
     println!("Command stderr: {stderr}");
 
-    // Check for deduplication logs in stdout (not stderr)
+    // Debug logs go to stderr, keeping stdout for results
     assert!(
-        stdout.contains("Before deduplication:") && stdout.contains("After deduplication:"),
+        stderr.contains("Before deduplication:") && stderr.contains("After deduplication:"),
         "Deduplication logs not found in output"
     );
 
@@ -134,26 +134,14 @@ fn test_search_single_term() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search for a single term
@@ -200,26 +188,14 @@ fn test_search_multiple_terms() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search for multiple terms
@@ -248,26 +224,15 @@ fn test_search_files_only() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
         files_only: true,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search for files only
@@ -325,26 +290,13 @@ fn test_search_include_filenames() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search with filename matching enabled
@@ -383,26 +335,15 @@ fn test_search_with_limits() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
         max_results: Some(2), // limit to 2 results
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search with limits
@@ -435,26 +376,13 @@ fn test_frequency_search() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search using frequency-based search
@@ -494,27 +422,15 @@ func main() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: false, // filename matching is enabled by default
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         // using "all terms" mode
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search for both terms in "all terms" mode
@@ -538,26 +454,14 @@ fn test_search_with_custom_ignores() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Search with custom ignore patterns
@@ -657,26 +561,15 @@ fn calculate_product(a: i32, b: i32) -> i32 {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "combined",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Perform search
@@ -767,26 +660,15 @@ fn test_skipped_files_with_match_counts() {
 
     // Create SearchOptions with a very low limit to force skipping
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
         max_results: Some(2), // Very low limit to force skipping
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Perform search
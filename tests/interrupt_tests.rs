@@ -0,0 +1,34 @@
+use probe_code::api::SearchRequest;
+use probe_code::interrupt;
+use probe_code::search::search_output::format_json_search_results;
+use std::fs;
+use tempfile::TempDir;
+
+// Kept in its own test binary: the interrupt flag is process-wide
+#[test]
+fn test_interrupted_search_returns_partial_results() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("ledger.rs"),
+        "fn ledger_balance() -> i64 {\n    42\n}\n",
+    )
+    .unwrap();
+    let search = SearchRequest::new("ledger balance").path(temp.path());
+
+    // Interrupted before any file was searched, the search still completes
+    interrupt::interrupt();
+    assert!(search.run().unwrap().is_empty());
+    let json: serde_json::Value = serde_json::from_str(
+        &format_json_search_results(&[], None, None, None, None, &[]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(json["summary"]["interrupted"], true);
+
+    interrupt::reset();
+    assert_eq!(search.run().unwrap().len(), 1);
+    let json: serde_json::Value = serde_json::from_str(
+        &format_json_search_results(&[], None, None, None, None, &[]).unwrap(),
+    )
+    .unwrap();
+    assert!(json["summary"].get("interrupted").is_none());
+}
@@ -12,26 +12,15 @@ fn test_ip_whitelist_stemming() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: file_path.parent().unwrap().parent().unwrap(), // Use the tests directory
+        paths: &[file_path.parent().unwrap().parent().unwrap().to_path_buf()], // Use the tests directory
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
         frequency_search: true, // Use frequency search to get detailed term stats
-        exact: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Enable debug mode to see the actual terms
@@ -101,26 +90,14 @@ fn test_negative_terms_exclude_files() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: file_path.parent().unwrap().parent().unwrap(), // Use the tests directory
+        paths: &[file_path.parent().unwrap().parent().unwrap().to_path_buf()], // Use the tests directory
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        exact: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Enable debug mode to see the actual terms
@@ -1,33 +1,24 @@
 #[cfg(test)]
 mod tests {
-    use probe_code::search::{perform_probe, SearchOptions};
-    use std::path::Path;
+    use probe_code::search::{perform_probe, perform_probe_streaming, SearchOptions};
+    use std::path::{Path, PathBuf};
+
+    fn search_options<'a>(paths: &'a [PathBuf], queries: &'a [String]) -> SearchOptions<'a> {
+        SearchOptions {
+            paths,
+            queries,
+            max_results: Some(5),
+            allow_tests: true,
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_search_functionality() {
         // Create search options
-        let options = SearchOptions {
-            path: Path::new("."),
-            queries: &["function".to_string()],
-            files_only: false,
-            custom_ignores: &[],
-            exclude_filenames: false,
-            reranker: "bm25",
-            frequency_search: true,
-            exact: false,
-            language: None,
-            max_results: Some(5),
-            max_bytes: None,
-            max_tokens: None,
-            allow_tests: true,
-            no_merge: false,
-            merge_threshold: None,
-            dry_run: false,
-            session: None,
-            timeout: 30,
-            question: None,
-            no_gitignore: false,
-        };
+        let paths = [Path::new(".").to_path_buf()];
+        let queries = ["function".to_string()];
+        let options = search_options(&paths, &queries);
 
         let results = perform_probe(&options).unwrap();
 
@@ -36,6 +27,30 @@ mod tests {
         println!("Found {} results", results.results.len());
     }
 
+    #[test]
+    fn test_streaming_search_sends_the_same_results() {
+        let paths = [Path::new("src/search").to_path_buf()];
+        let queries = ["merge blocks".to_string()];
+        let options = search_options(&paths, &queries);
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let streamed = perform_probe_streaming(&options, sender).unwrap();
+        assert!(streamed.results.is_empty());
+        let sent: Vec<_> = receiver
+            .into_iter()
+            .map(|result| (result.file, result.lines))
+            .collect();
+
+        let collected: Vec<_> = perform_probe(&options)
+            .unwrap()
+            .results
+            .into_iter()
+            .map(|result| (result.file, result.lines))
+            .collect();
+        assert!(!sent.is_empty());
+        assert_eq!(sent, collected);
+    }
+
     #[test]
     fn test_query_functionality() {
         use probe_code::query::{perform_query, QueryOptions};
@@ -49,6 +64,7 @@ mod tests {
             max_results: Some(5),
             format: "text",
             no_gitignore: false,
+            full_block: false,
         };
 
         let matches = perform_query(&options).unwrap();
@@ -36,26 +36,15 @@ def hello_world():
     let queries = vec!["installation".to_string()];
     let custom_ignores: Vec<String> = vec![];
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
         language: Some("md"),
         reranker: "hybrid",
-        frequency_search: true,
-        exact: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        no_gitignore: false,
+        ..Default::default()
     };
     let results = perform_probe(&options).unwrap();
     assert!(!results.results.is_empty());
@@ -88,26 +77,15 @@ console.log("Hello from JS");
     let queries = vec!["hello_world".to_string()];
     let custom_ignores: Vec<String> = vec![];
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
         exclude_filenames: true,
         language: Some("md"),
         reranker: "hybrid",
-        frequency_search: true,
-        exact: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        no_gitignore: false,
+        ..Default::default()
     };
     let results = perform_probe(&options).unwrap();
     assert!(!results.results.is_empty());
@@ -158,26 +136,15 @@ fn main() {
         let queries = vec!["Feature".to_string()];
         let custom_ignores: Vec<String> = vec![];
         let options = SearchOptions {
-            path: temp_dir.path(),
+            paths: &[temp_dir.path().to_path_buf()],
             queries: &queries,
-            files_only: false,
             custom_ignores: &custom_ignores,
             exclude_filenames: true,
             language: Some(ext),
             reranker: "hybrid",
-            frequency_search: true,
-            exact: false,
-            max_results: None,
-            max_bytes: None,
-            max_tokens: None,
             allow_tests: true,
             no_merge: true,
-            merge_threshold: None,
-            dry_run: false,
-            session: None,
-            timeout: 30,
-            question: None,
-            no_gitignore: false,
+            ..Default::default()
         };
         let results = perform_probe(&options).unwrap();
         assert!(
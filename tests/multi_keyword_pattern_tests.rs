@@ -35,6 +35,7 @@ fn test_multi_keyword_pattern_generation() {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -44,6 +45,10 @@ fn test_multi_keyword_pattern_generation() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -112,6 +117,7 @@ fn test_excluded_term_pattern_generation() {
         term_indices,
         excluded_terms,
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -121,6 +127,10 @@ fn test_excluded_term_pattern_generation() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -174,6 +184,7 @@ fn test_and_expression_pattern_generation() {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -183,6 +194,10 @@ fn test_and_expression_pattern_generation() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -251,6 +266,7 @@ fn test_or_expression_pattern_generation() {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -260,6 +276,10 @@ fn test_or_expression_pattern_generation() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -352,6 +372,7 @@ fn test_complex_expression_pattern_generation() {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -361,6 +382,10 @@ fn test_complex_expression_pattern_generation() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -448,6 +473,7 @@ fn test_pattern_deduplication() {
         term_indices,
         excluded_terms: HashSet::new(),
         exact: false,
+        regex_escape: true,
         is_simple_query: false,
         required_terms: HashSet::new(),
         has_required_anywhere,
@@ -457,6 +483,10 @@ fn test_pattern_deduplication() {
         is_universal_query: false,
         special_case_indices: HashSet::new(),
         special_case_terms_lower: HashMap::new(),
+        smart_case: false,
+        fuzzy_terms: HashSet::new(),
+        case_sensitive: false,
+        written_terms: HashMap::new(),
     };
 
     // Generate patterns
@@ -474,3 +504,41 @@ fn test_pattern_deduplication() {
         "Should deduplicate patterns for the same term"
     );
 }
+
+/// Test delimiter-aware exact patterns for queries containing punctuation
+#[test]
+fn test_exact_pattern_delimiters() {
+    use probe_code::search::query::exact_pattern;
+    use regex::Regex;
+
+    let matches = |query: &str, text: &str| {
+        Regex::new(&format!("(?i){}", exact_pattern(query, true)))
+            .unwrap()
+            .is_match(text)
+    };
+
+    assert!(matches("HashMap::new", "let m = HashMap::new();"));
+    assert!(!matches("Map::new", "let m = HashMap::new();"));
+    assert!(matches("-> Result", "fn run()->Result<()>"));
+    assert!(matches("Vec<T>", "inner: Vec< T >,"));
+    assert!(matches("#[derive", "#[derive(Debug)]"));
+    assert!(!matches("a ::b", "a : : b"));
+
+    // Plain identifiers keep substring semantics
+    assert!(matches("Map", "HashMap"));
+
+    // With escaping off the query is a raw regex
+    assert_eq!(exact_pattern(r"fn \w+\(", false), r"fn \w+\(");
+}
+
+/// Test which patterns count as containing uppercase for --smart-case
+#[test]
+fn test_has_uppercase_literal() {
+    use probe_code::search::query::has_uppercase_literal;
+
+    assert!(has_uppercase_literal("HashMap"));
+    assert!(has_uppercase_literal(r"fn \w+Error"));
+    assert!(!has_uppercase_literal("hashmap"));
+    // Escapes such as \W and \S are not literal uppercase letters
+    assert!(!has_uppercase_literal(r"\W\S+\Bfoo"));
+}
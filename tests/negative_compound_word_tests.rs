@@ -87,26 +87,13 @@ fn test_basic_negative_compound_word(temp_path: &Path) {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -154,27 +141,14 @@ fn test_complex_negative_compound_word(temp_path: &Path) {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         // Use all terms mode
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -219,28 +193,23 @@ fn test_excluded_terms_extraction() {
     let query = "-networkfirewall";
     let plan = create_query_plan(query, false).unwrap();
 
-    // Check that the original term is in the excluded_terms set
+    // Negative terms are split like positive ones, so the parts are excluded
     assert!(
-        plan.excluded_terms.contains("networkfirewall"),
-        "Original term 'networkfirewall' should be in excluded_terms"
+        plan.excluded_terms.contains("network") && plan.excluded_terms.contains("firewall"),
+        "Compound parts of 'networkfirewall' should be in excluded_terms"
     );
 
-    // For negative terms, we don't apply compound word splitting anymore
-    // So we don't expect the compound parts to be in the excluded terms set
-
     // Test with a more complex query
     let complex_query = "settings AND -networkfirewall";
     let complex_plan = create_query_plan(complex_query, false).unwrap();
 
-    // Check that the original term is in the excluded_terms set
+    // Negative terms are split like positive ones, so the parts are excluded
     assert!(
-        complex_plan.excluded_terms.contains("networkfirewall"),
-        "Original term 'networkfirewall' should be in excluded_terms"
+        complex_plan.excluded_terms.contains("network")
+            && complex_plan.excluded_terms.contains("firewall"),
+        "Compound parts of 'networkfirewall' should be in excluded_terms"
     );
 
-    // For negative terms, we don't apply compound word splitting anymore
-    // So we don't expect the compound parts to be in the excluded terms set
-
     // Reset debug mode
     std::env::remove_var("DEBUG");
 }
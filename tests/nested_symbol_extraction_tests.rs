@@ -61,7 +61,7 @@ fn extract_nested_symbol(path: &PathBuf, symbol: &str) -> Result<String> {
         0,    // context_lines
     )?;
 
-    Ok(result.code)
+    Ok(result.code.to_string())
 }
 
 #[test]
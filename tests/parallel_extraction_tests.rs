@@ -37,6 +37,16 @@ fn test_parallel_file_extraction() {
         keep_input: false,
         prompt: None,
         no_gitignore: false,
+        with_doc_comments: false,
+        no_attributes: false,
+        deps: Vec::new(),
+        redact_secrets: false,
+        no_whole_files: false,
+        save_results: None,
+        include: Vec::new(),
+        blame: false,
+        owners: false,
+        public_only: false,
     };
 
     // Run the extraction
@@ -100,6 +110,16 @@ fn function_four() {
         keep_input: false,
         prompt: None,
         no_gitignore: false,
+        with_doc_comments: false,
+        no_attributes: false,
+        deps: Vec::new(),
+        redact_secrets: false,
+        no_whole_files: false,
+        save_results: None,
+        include: Vec::new(),
+        blame: false,
+        owners: false,
+        public_only: false,
     };
 
     // Run the extraction
@@ -167,6 +187,16 @@ fn test_parallel_extraction_performance() {
         keep_input: false,
         prompt: None,
         no_gitignore: false,
+        with_doc_comments: false,
+        no_attributes: false,
+        deps: Vec::new(),
+        redact_secrets: false,
+        no_whole_files: false,
+        save_results: None,
+        include: Vec::new(),
+        blame: false,
+        owners: false,
+        public_only: false,
     };
 
     // Run the extraction
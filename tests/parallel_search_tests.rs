@@ -68,26 +68,17 @@ fn test_parallel_file_search() {
     let custom_ignores = Vec::new();
 
     let options = SearchOptions {
-        path: base_path,
+        paths: &[base_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
         max_results: Some(100),
         max_bytes: Some(1_000_000),
         max_tokens: Some(100_000),
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Measure search time
@@ -142,6 +133,7 @@ fn test_structured_patterns_search() {
         allow_tests: true,
         language: None,
         no_gitignore: false,
+        files: None,
     };
     let result = search_with_structured_patterns(
         base_path,
@@ -204,26 +196,17 @@ fn function_{}() {{
     let custom_ignores = Vec::new();
 
     let options = SearchOptions {
-        path: base_path,
+        paths: &[base_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
         max_results: Some(100),
         max_bytes: Some(1_000_000),
         max_tokens: Some(100_000),
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Measure search time
@@ -309,26 +292,17 @@ fn function_with_blocks_{}() {{
     let custom_ignores = Vec::new();
 
     let options = SearchOptions {
-        path: base_path,
+        paths: &[base_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
         max_results: Some(100),
         max_bytes: Some(1_000_000),
         max_tokens: Some(100_000),
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Measure search time
@@ -32,6 +32,7 @@ fn add(a: i32, b: i32) -> i32 {
         max_results: None,
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -84,6 +85,7 @@ const multiply = (a, b) => a * b;
         max_results: None,
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -104,6 +106,7 @@ const multiply = (a, b) => a * b;
         max_results: None,
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -144,6 +147,7 @@ fn func5() {}
         max_results: Some(3),
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -181,6 +185,7 @@ fn test_query_ignore_patterns() -> Result<()> {
         max_results: None,
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -219,6 +224,7 @@ fn auto_detected_function() {
         max_results: None,
         format: "plain",
         no_gitignore: false,
+        full_block: false,
     };
 
     // Perform the query
@@ -230,3 +236,46 @@ fn auto_detected_function() {
 
     Ok(())
 }
+
+#[test]
+fn test_query_full_block() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let temp_path = temp_dir.path();
+    fs::write(
+        temp_path.join("calls.rs"),
+        r#"fn run() {
+    let a = compute(1);
+    let b = compute(2);
+    a + b
+}
+
+fn other() {
+    compute(3);
+}
+"#,
+    )?;
+
+    let options = QueryOptions {
+        path: temp_path,
+        pattern: "compute($X)",
+        language: Some("rust"),
+        ignore: &[],
+        allow_tests: true,
+        max_results: None,
+        format: "plain",
+        no_gitignore: false,
+        full_block: true,
+    };
+    let mut matches = perform_query(&options)?;
+    matches.sort_by_key(|m| m.line_start);
+
+    // The two calls in run() are merged into one result for the function
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[0].line_start, matches[0].line_end), (1, 5));
+    assert!(matches[0].matched_text.starts_with("fn run()"));
+    assert!(matches[0].matched_text.contains("a + b"));
+    assert_eq!((matches[1].line_start, matches[1].line_end), (7, 9));
+    assert_ne!(matches[1].node_type, "match");
+
+    Ok(())
+}
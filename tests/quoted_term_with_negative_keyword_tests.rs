@@ -69,33 +69,21 @@ fn test_quoted_term_with_negative_keyword() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the query for debugging
     println!("Executing search with query: {queries:?}");
     println!(
-        "Path: {:?}, frequency_search: {}",
-        options.path, options.frequency_search
+        "Paths: {:?}, frequency_search: {}",
+        options.paths, options.frequency_search
     );
 
     // Run the search
@@ -152,26 +140,14 @@ fn test_negative_quoted_term() {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
         frequency_search: false,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
-        no_merge: false,
         merge_threshold: Some(5),
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Print the query for debugging
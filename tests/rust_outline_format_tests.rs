@@ -841,3 +841,64 @@ mod benches {
 
     Ok(())
 }
+
+#[test]
+fn test_rust_outline_attribute_wrapped_fn_in_macro() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let test_file = temp_dir.path().join("platform.rs");
+
+    let content = r#"cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        #[cfg_attr(test, inline)]
+        pub fn unix_only() -> u32 {
+            let first = 1;
+            let second = 2;
+            let third = 3;
+            let fourth = 4;
+            let fifth = 5;
+            let zebracorn = first + second + third + fourth + fifth;
+            zebracorn
+        }
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+pub fn wrapped() -> u32 {
+    let first = 1;
+    let second = 2;
+    let third = 3;
+    let fourth = 4;
+    let fifth = 5;
+    let unicornfish = first + second + third + fourth + fifth;
+    unicornfish
+}
+"#;
+
+    fs::write(&test_file, content)?;
+
+    let ctx = TestContext::new();
+    let output = ctx.run_probe(&[
+        "search",
+        "zebracorn OR unicornfish",
+        test_file.to_str().unwrap(),
+        "--format",
+        "outline",
+    ])?;
+
+    // The attributes and signatures of both functions frame their matches, whether the
+    // function is an item or declared inside a macro body
+    assert!(
+        output.contains("#[cfg_attr(test, inline)]")
+            && output.contains("pub fn unix_only() -> u32 {"),
+        "Missing signature of the function inside the macro - output: {}",
+        output
+    );
+    assert!(
+        output.contains("#[cfg_attr(test, allow(dead_code))]")
+            && output.contains("pub fn wrapped() -> u32 {"),
+        "Missing signature of the attribute-wrapped function - output: {}",
+        output
+    );
+
+    Ok(())
+}
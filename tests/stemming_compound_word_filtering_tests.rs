@@ -82,26 +82,13 @@ fn test_stemming_with_and_query(temp_path: &Path) {
 
     // Create SearchOptions with stemming enabled (exact=false)
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -163,27 +150,14 @@ fn test_stemming_with_or_query(temp_path: &Path) {
 
     // Create SearchOptions with stemming enabled (exact=false)
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         // Use any term mode
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -339,10 +313,10 @@ fn test_stemming_with_complex_query(_temp_path: &Path) {
         whitelist_lines.insert(4);
         term_matches.insert(*term_indices.get("whitelist").unwrap(), whitelist_lines);
 
-        // Add "blocking" matches (the exact term used in the query)
+        // Add "block" matches (excluded terms are stemmed like the others)
         let mut block_lines = HashSet::new();
         block_lines.insert(5);
-        term_matches.insert(*term_indices.get("blocking").unwrap(), block_lines);
+        term_matches.insert(*term_indices.get("block").unwrap(), block_lines);
 
         // Block lines
         let block_lines = (1, 10);
@@ -368,26 +342,13 @@ fn test_compound_word_splitting(temp_path: &Path) {
 
     // Create SearchOptions with stemming and compound word splitting enabled
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -414,27 +375,14 @@ fn test_compound_word_splitting(temp_path: &Path) {
     let complex_queries = vec![complex_query.to_string()];
 
     let complex_options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &complex_queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         // Use all terms mode
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
@@ -474,26 +422,13 @@ fn test_negative_compound_word_in_existing_tests(temp_path: &Path) {
 
     // Create SearchOptions
     let options = SearchOptions {
-        path: temp_path,
+        paths: &[temp_path.to_path_buf()],
         queries: &queries,
-        files_only: false,
         custom_ignores: &custom_ignores,
-        exclude_filenames: false,
-        language: None,
         reranker: "hybrid",
-        frequency_search: true,
-        max_results: None,
-        max_bytes: None,
-        max_tokens: None,
         allow_tests: true,
         no_merge: true,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
-        exact: false,
-        no_gitignore: false,
+        ..Default::default()
     };
 
     // Run the search
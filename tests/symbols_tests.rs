@@ -37,26 +37,12 @@ pub const MAX_USERS: usize = 1000;
 
     // Test search with symbols flag enabled
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &["pub fn".to_string()],
-        files_only: false,
-        custom_ignores: &[],
-        exclude_filenames: false,
-        reranker: "bm25",
-        frequency_search: true,
-        exact: false,
-        language: None,
         max_results: Some(10),
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
-        no_merge: false,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
         no_gitignore: true,
+        symbols_only: true,
+        ..Default::default()
     };
 
     let results = perform_probe(&options).expect("Search should succeed");
@@ -72,7 +58,10 @@ pub const MAX_USERS: usize = 1000;
     );
 
     // Test with symbols flag disabled
-    let options_no_symbols = SearchOptions { ..options };
+    let options_no_symbols = SearchOptions {
+        symbols_only: false,
+        ..options
+    };
 
     let results_no_symbols = perform_probe(&options_no_symbols).expect("Search should succeed");
 
@@ -116,26 +105,12 @@ MAX_USERS = 1000
 
     // Test search with symbols flag enabled
     let options = SearchOptions {
-        path: temp_dir.path(),
+        paths: &[temp_dir.path().to_path_buf()],
         queries: &["def ".to_string()],
-        files_only: false,
-        custom_ignores: &[],
-        exclude_filenames: false,
-        reranker: "bm25",
-        frequency_search: true,
-        exact: false,
-        language: None,
         max_results: Some(10),
-        max_bytes: None,
-        max_tokens: None,
-        allow_tests: false,
-        no_merge: false,
-        merge_threshold: None,
-        dry_run: false,
-        session: None,
-        timeout: 30,
-        question: None,
         no_gitignore: true,
+        symbols_only: true,
+        ..Default::default()
     };
 
     let results = perform_probe(&options).expect("Search should succeed");